@@ -0,0 +1,89 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::fmt::Write;
+use etherparse::*;
+
+/// Slices & decodes an ethernet packet and returns the ports of the transport layer.
+pub fn ports(packet: &[u8]) -> Option<(u16, u16)> {
+    match SlicedPacket::from_ethernet(packet).ok()?.transport? {
+        TransportSlice::Udp(udp) => Some((udp.source_port(), udp.destination_port())),
+        TransportSlice::Tcp(tcp) => Some((tcp.source_port(), tcp.destination_port())),
+        _ => None,
+    }
+}
+
+/// Decodes the headers of an ethernet packet (keeping the headers before an error).
+pub fn headers(packet: &[u8]) -> LaxPacketHeaders<'_> {
+    LaxPacketHeaders::from_ethernet(packet)
+}
+
+/// Formats the error & its context at which slicing stopped.
+pub fn describe_error(packet: &[u8]) -> Option<String> {
+    let (err, context) = LaxSlicedPacket::from_ethernet(packet).stop_err?;
+    let mut result = String::new();
+    write!(result, "{} ({})", err, context).ok()?;
+    Some(result)
+}
+
+/// Writes an ethernet II header into the given buffer.
+pub fn write_ethernet(buffer: &mut [u8]) -> Result<(), WriteError> {
+    Ethernet2Header {
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [7, 8, 9, 10, 11, 12],
+        ether_type: ether_type::IPV4,
+    }
+    .write_to_slice(buffer)
+    .map(|_| ())
+}
+
+/// Builds an ethernet, ipv4 & udp packet in the given buffer and returns its length.
+pub fn build_udp(buffer: &mut [u8], payload: &[u8]) -> Result<usize, WriteError> {
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(21, 1234)
+        .write_to_slice(buffer, payload)
+}
+
+/// Prepends an ethernet, ipv4 & GRE header in front of the payload located at
+/// the given range and returns the range of the resulting frame.
+pub fn encapsulate_gre(
+    buffer: &mut [u8],
+    payload: core::ops::Range<usize>,
+) -> Result<core::ops::Range<usize>, WriteError> {
+    Encapsulation {
+        ethernet2: Some(Ethernet2Header {
+            source: [1, 2, 3, 4, 5, 6],
+            destination: [7, 8, 9, 10, 11, 12],
+            ether_type: 0,
+        }),
+        vlan: None,
+        ip: Some(IpHeader::Version4(
+            Ipv4Header::new(0, 20, 0, [192, 168, 1, 1], [192, 168, 1, 2]),
+            Default::default(),
+        )),
+        tunnel: Some(TunnelHeader::Gre(GreHeader::new(ether_type::IPV4))),
+    }
+    .prepend(buffer, payload)
+}
+
+/// Removes the outermost vlan tag of the frame located at the given range and
+/// returns the range of the resulting frame.
+pub fn pop_vlan(
+    buffer: &mut [u8],
+    range: core::ops::Range<usize>,
+) -> Result<core::ops::Range<usize>, FrameEditError> {
+    let mut frame = HeadroomFrame::new(buffer, range);
+    frame.pop_vlan()?;
+    Ok(frame.range())
+}
+
+/// Checks the given packet against a pcap filter expression.
+pub fn matches(expression: &str, packet: &[u8]) -> bool {
+    match (pcap_filter::parse(expression), SlicedPacket::from_ethernet(packet)) {
+        (Ok(filter), Ok(sliced)) => filter.applies_to_slice(&sliced),
+        _ => false,
+    }
+}
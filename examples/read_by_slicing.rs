@@ -63,7 +63,11 @@ fn main() {
             match value.transport {
                 Some(Icmpv4(value)) => println!(" Icmpv4 {:?}", value),
                 Some(Icmpv6(value)) => println!(" Icmpv6 {:?}", value),
+                Some(Igmp(value)) => println!(" Igmp {:?}", value),
+                Some(Sctp(value)) => println!("  SCTP {:?} -> {:?}", value.source_port(), value.destination_port()),
                 Some(Udp(value)) => println!("  UDP {:?} -> {:?}", value.source_port(), value.destination_port()),
+                Some(UdpLite(value)) => println!("  UDP-Lite {:?} -> {:?}", value.source_port(), value.destination_port()),
+                Some(Dccp(value)) => println!("  DCCP {:?} -> {:?}", value.source_port(), value.destination_port()),
                 Some(Tcp(value)) => {
                     println!("  TCP {:?} -> {:?}", value.source_port(), value.destination_port());
                     let options: Vec<Result<TcpOptionElement, TcpOptionReadError>> = value.options_iterator().collect();
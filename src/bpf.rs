@@ -0,0 +1,1167 @@
+//! Compilation of packet filters to classic BPF (cBPF) & a cBPF interpreter.
+//!
+//! [`compile`] translates a [`FilterExpression`] (or via [`compile_filter`] a
+//! [`Filter`]) into a cBPF program for packets starting with an Ethernet II
+//! header (e.g. for attaching it to a socket via `SO_ATTACH_FILTER`). The
+//! program accepts a packet (returns [`BPF_ACCEPT`]) exactly when
+//! [`FilterExpression::applies_to_slice`] would return true for the packet
+//! sliced via [`SlicedPacket::from_ethernet`]. This includes the handling of
+//! vlan tags, the ipv4 header length, ip extension headers & fragmentation.
+//!
+//! Limits of the generated programs:
+//!
+//! * At most [`BPF_MAX_IPV6_EXTENSIONS`] IPv6 extension headers are followed.
+//!   Further extension headers are treated like an unknown transport protocol.
+//! * Packets that can not be sliced by [`SlicedPacket::from_ethernet`] (e.g.
+//!   because they are truncated) can lead to different results.
+//!
+//! [`run`] executes a cBPF program in userspace (with the semantics of the
+//! linux kernel), which allows testing the generated programs.
+//!
+//! ```
+//! use etherparse::{bpf, pcap_filter, PacketBuilder};
+//!
+//! let program = bpf::compile(&pcap_filter::parse("vlan and tcp dst port 443").unwrap()).unwrap();
+//!
+//! let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .single_vlan(12)
+//!     .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!     .tcp(1234, 443, 1, 4000);
+//! let mut packet = Vec::with_capacity(builder.size(0));
+//! builder.write(&mut packet, &[]).unwrap();
+//!
+//! assert_eq!(Ok(bpf::BPF_ACCEPT), bpf::run(&program, &packet));
+//! assert_eq!(Ok(0), bpf::run(&program, &packet[..14]));
+//! ```
+
+use super::*;
+use crate::packet_filter::*;
+
+use core::convert::TryFrom;
+
+/// Instruction class: load into the accumulator.
+pub const BPF_LD: u16 = 0x00;
+/// Instruction class: load into the index register.
+pub const BPF_LDX: u16 = 0x01;
+/// Instruction class: store the accumulator in the scratch memory.
+pub const BPF_ST: u16 = 0x02;
+/// Instruction class: store the index register in the scratch memory.
+pub const BPF_STX: u16 = 0x03;
+/// Instruction class: arithmetic & logic operations.
+pub const BPF_ALU: u16 = 0x04;
+/// Instruction class: jumps.
+pub const BPF_JMP: u16 = 0x05;
+/// Instruction class: return.
+pub const BPF_RET: u16 = 0x06;
+/// Instruction class: register transfers.
+pub const BPF_MISC: u16 = 0x07;
+
+/// Load size: 32 bit word.
+pub const BPF_W: u16 = 0x00;
+/// Load size: 16 bit half word.
+pub const BPF_H: u16 = 0x08;
+/// Load size: byte.
+pub const BPF_B: u16 = 0x10;
+
+/// Load mode: immediate value.
+pub const BPF_IMM: u16 = 0x00;
+/// Load mode: packet data at an absolute offset.
+pub const BPF_ABS: u16 = 0x20;
+/// Load mode: packet data at an offset relative to the index register.
+pub const BPF_IND: u16 = 0x40;
+/// Load mode: scratch memory.
+pub const BPF_MEM: u16 = 0x60;
+/// Load mode: length of the packet.
+pub const BPF_LEN: u16 = 0x80;
+/// Load mode: `4 * (packet[k] & 0xf)` (ipv4 header length).
+pub const BPF_MSH: u16 = 0xa0;
+
+pub const BPF_ADD: u16 = 0x00;
+pub const BPF_SUB: u16 = 0x10;
+pub const BPF_MUL: u16 = 0x20;
+pub const BPF_DIV: u16 = 0x30;
+pub const BPF_OR: u16 = 0x40;
+pub const BPF_AND: u16 = 0x50;
+pub const BPF_LSH: u16 = 0x60;
+pub const BPF_RSH: u16 = 0x70;
+pub const BPF_NEG: u16 = 0x80;
+pub const BPF_MOD: u16 = 0x90;
+pub const BPF_XOR: u16 = 0xa0;
+
+pub const BPF_JA: u16 = 0x00;
+pub const BPF_JEQ: u16 = 0x10;
+pub const BPF_JGT: u16 = 0x20;
+pub const BPF_JGE: u16 = 0x30;
+pub const BPF_JSET: u16 = 0x40;
+
+/// Operand source: the constant `k`.
+pub const BPF_K: u16 = 0x00;
+/// Operand source: the index register.
+pub const BPF_X: u16 = 0x08;
+/// Return source: the accumulator.
+pub const BPF_A: u16 = 0x10;
+
+/// Misc operation: copy the accumulator to the index register.
+pub const BPF_TAX: u16 = 0x00;
+/// Misc operation: copy the index register to the accumulator.
+pub const BPF_TXA: u16 = 0x80;
+
+/// Number of 32 bit words in the scratch memory.
+pub const BPF_MEMWORDS: usize = 16;
+
+/// Maximum number of instructions in a program accepted by the linux kernel.
+pub const BPF_MAXINSNS: usize = 4096;
+
+/// Value returned by compiled programs for accepted packets (the whole packet is kept).
+pub const BPF_ACCEPT: u32 = 0xffff_ffff;
+
+/// Maximum number of IPv6 extension headers followed by compiled programs.
+pub const BPF_MAX_IPV6_EXTENSIONS: usize = 8;
+
+/// A classic BPF instruction (same memory layout as `struct sock_filter` in linux).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BpfInstruction {
+    /// Operation code (class, size/operation, mode/source).
+    pub code: u16,
+    /// Relative jump offset if a condition is true.
+    pub jt: u8,
+    /// Relative jump offset if a condition is false.
+    pub jf: u8,
+    /// Generic constant (offset, immediate value, memory index, ...).
+    pub k: u32,
+}
+
+impl BpfInstruction {
+    /// Creates a non jump instruction.
+    pub const fn statement(code: u16, k: u32) -> BpfInstruction {
+        BpfInstruction{ code, jt: 0, jf: 0, k }
+    }
+
+    /// Creates a jump instruction.
+    pub const fn jump(code: u16, k: u32, jt: u8, jf: u8) -> BpfInstruction {
+        BpfInstruction{ code, jt, jf, k }
+    }
+}
+
+/// Errors that can occur when compiling or running cBPF programs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BpfError {
+    /// The compiled program has more instructions then allowed ([`BPF_MAXINSNS`]).
+    ProgramTooLong(usize),
+    /// The instruction at the given index has an unknown operation code.
+    InvalidInstruction{ index: usize, code: u16 },
+    /// The instruction at the given index accesses a scratch memory word that does not exist.
+    InvalidMemoryIndex{ index: usize, k: u32 },
+    /// The program counter left the program (e.g. by a jump or missing return instruction).
+    ProgramCounterOutOfRange(usize),
+}
+
+#[cfg(feature = "std")]
+impl Error for BpfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for BpfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use BpfError::*;
+        match self {
+            ProgramTooLong(len) => {
+                write!(f, "BpfError: The program has {} instructions, which exceeds the maximum of {} instructions.", len, BPF_MAXINSNS)
+            },
+            InvalidInstruction{ index, code } => {
+                write!(f, "BpfError: Invalid operation code 0x{:04x} in the instruction at index {}.", code, index)
+            },
+            InvalidMemoryIndex{ index, k } => {
+                write!(f, "BpfError: The instruction at index {} accesses the non existing scratch memory word {}.", index, k)
+            },
+            ProgramCounterOutOfRange(pc) => {
+                write!(f, "BpfError: The program counter {} is outside of the program (missing return instruction or jump out of range).", pc)
+            },
+        }
+    }
+}
+
+/// Compiles a filter expression to a cBPF program for Ethernet II packets.
+pub fn compile(expression: &FilterExpression) -> Result<Vec<BpfInstruction>, BpfError> {
+    let mut codegen = Codegen::default();
+    codegen.prologue();
+    let accept = codegen.label();
+    let reject = codegen.label();
+    codegen.expression(expression, accept, reject);
+    codegen.place(accept);
+    codegen.statement(BPF_RET | BPF_K, BPF_ACCEPT);
+    codegen.place(reject);
+    codegen.statement(BPF_RET | BPF_K, 0);
+
+    let program = codegen.assemble();
+    if program.len() > BPF_MAXINSNS {
+        Err(BpfError::ProgramTooLong(program.len()))
+    } else {
+        Ok(program)
+    }
+}
+
+/// Compiles a layer filter to a cBPF program for Ethernet II packets.
+pub fn compile_filter(filter: &Filter) -> Result<Vec<BpfInstruction>, BpfError> {
+    compile(&FilterExpression::Layers(filter.clone()))
+}
+
+/// Runs a cBPF program for the given packet and returns the result of the
+/// program (the number of bytes to keep, 0 if the packet is dropped).
+///
+/// Like in the linux kernel packet loads outside of the packet & divisions
+/// by zero end the program with the result 0.
+pub fn run(program: &[BpfInstruction], packet: &[u8]) -> Result<u32, BpfError> {
+    let mut a: u32 = 0;
+    let mut x: u32 = 0;
+    let mut memory = [0u32;BPF_MEMWORDS];
+    let mut pc = 0;
+
+    let load = |offset: u64, size: usize| -> Option<u32> {
+        let start = usize::try_from(offset).ok()?;
+        let bytes = packet.get(start..start.checked_add(size)?)?;
+        Some(bytes.iter().fold(0u32, |value, byte| (value << 8) | u32::from(*byte)))
+    };
+
+    loop {
+        let index = pc;
+        let instruction = program.get(index).ok_or(BpfError::ProgramCounterOutOfRange(index))?;
+        pc += 1;
+        let k = instruction.k;
+        let invalid = BpfError::InvalidInstruction{ index, code: instruction.code };
+        let memory_index = || -> Result<usize, BpfError> {
+            if (k as usize) < BPF_MEMWORDS {
+                Ok(k as usize)
+            } else {
+                Err(BpfError::InvalidMemoryIndex{ index, k })
+            }
+        };
+        let size = || -> Result<usize, BpfError> {
+            match instruction.code & 0x18 {
+                BPF_W => Ok(4),
+                BPF_H => Ok(2),
+                BPF_B => Ok(1),
+                _ => Err(invalid.clone()),
+            }
+        };
+
+        match instruction.code & 0x07 {
+            BPF_LD => {
+                let value = match instruction.code & 0xe0 {
+                    BPF_IMM => Some(k),
+                    BPF_ABS => load(u64::from(k), size()?),
+                    BPF_IND => load(u64::from(x) + u64::from(k), size()?),
+                    BPF_MEM => Some(memory[memory_index()?]),
+                    BPF_LEN => Some(packet.len() as u32),
+                    _ => return Err(invalid),
+                };
+                match value {
+                    Some(value) => a = value,
+                    None => return Ok(0),
+                }
+            },
+            BPF_LDX => {
+                let value = match instruction.code & 0xe0 {
+                    BPF_IMM => Some(k),
+                    BPF_MEM => Some(memory[memory_index()?]),
+                    BPF_LEN => Some(packet.len() as u32),
+                    BPF_MSH => load(u64::from(k), 1).map(|value| 4*(value & 0xf)),
+                    _ => return Err(invalid),
+                };
+                match value {
+                    Some(value) => x = value,
+                    None => return Ok(0),
+                }
+            },
+            BPF_ST => memory[memory_index()?] = a,
+            BPF_STX => memory[memory_index()?] = x,
+            BPF_ALU => {
+                let operand = if 0 == instruction.code & BPF_X { k } else { x };
+                a = match instruction.code & 0xf0 {
+                    BPF_ADD => a.wrapping_add(operand),
+                    BPF_SUB => a.wrapping_sub(operand),
+                    BPF_MUL => a.wrapping_mul(operand),
+                    BPF_DIV => match a.checked_div(operand) {
+                        Some(value) => value,
+                        None => return Ok(0),
+                    },
+                    BPF_MOD => match a.checked_rem(operand) {
+                        Some(value) => value,
+                        None => return Ok(0),
+                    },
+                    BPF_OR => a | operand,
+                    BPF_AND => a & operand,
+                    BPF_XOR => a ^ operand,
+                    BPF_LSH => a.checked_shl(operand).unwrap_or(0),
+                    BPF_RSH => a.checked_shr(operand).unwrap_or(0),
+                    BPF_NEG => a.wrapping_neg(),
+                    _ => return Err(invalid),
+                };
+            },
+            BPF_JMP => {
+                let operand = if 0 == instruction.code & BPF_X { k } else { x };
+                let condition = match instruction.code & 0xf0 {
+                    BPF_JA => {
+                        pc = pc.checked_add(k as usize).ok_or(BpfError::ProgramCounterOutOfRange(usize::MAX))?;
+                        continue;
+                    },
+                    BPF_JEQ => a == operand,
+                    BPF_JGT => a > operand,
+                    BPF_JGE => a >= operand,
+                    BPF_JSET => 0 != a & operand,
+                    _ => return Err(invalid),
+                };
+                pc += usize::from(if condition { instruction.jt } else { instruction.jf });
+            },
+            BPF_RET => {
+                return match instruction.code & 0x18 {
+                    BPF_K => Ok(k),
+                    BPF_A => Ok(a),
+                    _ => Err(invalid),
+                };
+            },
+            _ => {
+                // BPF_MISC
+                match instruction.code & 0xf8 {
+                    BPF_TAX => x = a,
+                    BPF_TXA => a = x,
+                    _ => return Err(invalid),
+                }
+            },
+        }
+    }
+}
+
+/// Scratch memory word containing the offset of the network layer (after the vlan headers).
+const MEM_NET: u32 = 0;
+/// Scratch memory word containing the ether type of the network layer.
+const MEM_ETHER_TYPE: u32 = 1;
+/// Scratch memory word containing the number of vlan headers.
+const MEM_VLAN_COUNT: u32 = 2;
+/// Scratch memory word containing the ip number of the ip payload (after the extension headers).
+const MEM_IP_NUMBER: u32 = 3;
+/// Scratch memory word containing the offset of the transport layer (after the ip extension headers).
+const MEM_TRANSPORT: u32 = 4;
+/// Scratch memory word set to 1 if the ip payload is fragmented.
+const MEM_FRAGMENTED: u32 = 5;
+/// Scratch memory word containing the offset of the payload.
+const MEM_PAYLOAD: u32 = 6;
+/// Scratch memory word used to accumulate values read byte by byte.
+const MEM_BYTES_VALUE: u32 = 7;
+/// Scratch memory word used to keep the layer start while reading byte by byte.
+const MEM_BYTES_BASE: u32 = 8;
+
+/// Jump target during code generation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Label(usize);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Statement(BpfInstruction),
+    Jump{ code: u16, k: u32, jt: Label, jf: Label },
+    Goto(Label),
+    Place(Label),
+}
+
+#[derive(Default)]
+struct Codegen {
+    ops: Vec<Op>,
+    label_count: usize,
+}
+
+impl Codegen {
+    fn label(&mut self) -> Label {
+        self.label_count += 1;
+        Label(self.label_count - 1)
+    }
+
+    fn place(&mut self, label: Label) {
+        self.ops.push(Op::Place(label));
+    }
+
+    fn statement(&mut self, code: u16, k: u32) {
+        self.ops.push(Op::Statement(BpfInstruction::statement(code, k)));
+    }
+
+    fn jump(&mut self, code: u16, k: u32, jt: Label, jf: Label) {
+        self.ops.push(Op::Jump{ code: BPF_JMP | code | BPF_K, k, jt, jf });
+    }
+
+    fn goto(&mut self, label: Label) {
+        self.ops.push(Op::Goto(label));
+    }
+
+    /// Continues if the condition is true & jumps to `fail` otherwise.
+    fn expect(&mut self, code: u16, k: u32, fail: Label) {
+        let next = self.label();
+        self.jump(code, k, next, fail);
+        self.place(next);
+    }
+
+    /// Continues if the condition is false & jumps to `fail` otherwise.
+    fn expect_not(&mut self, code: u16, k: u32, fail: Label) {
+        let next = self.label();
+        self.jump(code, k, fail, next);
+        self.place(next);
+    }
+
+    fn load_memory(&mut self, index: u32) {
+        self.statement(BPF_LD | BPF_MEM, index);
+    }
+
+    fn load_x_memory(&mut self, index: u32) {
+        self.statement(BPF_LDX | BPF_MEM, index);
+    }
+
+    fn store(&mut self, index: u32) {
+        self.statement(BPF_ST, index);
+    }
+
+    /// Jumps to `vlan` if the accumulator contains a vlan ether type & to `other` otherwise.
+    fn jump_vlan_ether_type(&mut self, vlan: Label, other: Label) {
+        for (i, ether_type) in [ether_type::VLAN_TAGGED_FRAME, ether_type::PROVIDER_BRIDGING, ether_type::VLAN_DOUBLE_TAGGED_FRAME].iter().enumerate() {
+            if i < 2 {
+                let next = self.label();
+                self.jump(BPF_JEQ, u32::from(*ether_type), vlan, next);
+                self.place(next);
+            } else {
+                self.jump(BPF_JEQ, u32::from(*ether_type), vlan, other);
+            }
+        }
+    }
+
+    /// Generates the code that determines the offsets & protocol numbers of the
+    /// layers (stored in the scratch memory) the same way as `SlicedPacket::from_ethernet`.
+    fn prologue(&mut self) {
+        // link layer & vlan headers
+        self.statement(BPF_LD | BPF_IMM, Ethernet2Header::SERIALIZED_SIZE as u32);
+        self.store(MEM_NET);
+        self.statement(BPF_LD | BPF_IMM, 0);
+        self.store(MEM_VLAN_COUNT);
+        self.statement(BPF_LD | BPF_H | BPF_ABS, 12);
+        self.store(MEM_ETHER_TYPE);
+        let vlan = self.label();
+        let ip = self.label();
+        self.jump_vlan_ether_type(vlan, ip);
+        self.place(vlan);
+        self.statement(BPF_LD | BPF_H | BPF_ABS, 16);
+        let single = self.label();
+        let double = self.label();
+        self.jump_vlan_ether_type(double, single);
+        self.place(single);
+        self.store(MEM_ETHER_TYPE);
+        self.statement(BPF_LD | BPF_IMM, 1);
+        self.store(MEM_VLAN_COUNT);
+        self.statement(BPF_LD | BPF_IMM, 18);
+        self.store(MEM_NET);
+        self.goto(ip);
+        self.place(double);
+        self.statement(BPF_LD | BPF_H | BPF_ABS, 20);
+        self.store(MEM_ETHER_TYPE);
+        self.statement(BPF_LD | BPF_IMM, 2);
+        self.store(MEM_VLAN_COUNT);
+        self.statement(BPF_LD | BPF_IMM, 22);
+        self.store(MEM_NET);
+
+        // defaults for packets without an ip header
+        self.place(ip);
+        self.load_memory(MEM_NET);
+        self.store(MEM_TRANSPORT);
+        self.store(MEM_PAYLOAD);
+        self.statement(BPF_LD | BPF_IMM, 0);
+        self.store(MEM_FRAGMENTED);
+        self.store(MEM_IP_NUMBER);
+        let ipv4 = self.label();
+        let ipv6 = self.label();
+        let transport = self.label();
+        let done = self.label();
+        self.load_memory(MEM_ETHER_TYPE);
+        let not_ipv4 = self.label();
+        self.jump(BPF_JEQ, u32::from(ether_type::IPV4), ipv4, not_ipv4);
+        self.place(not_ipv4);
+        self.jump(BPF_JEQ, u32::from(ether_type::IPV6), ipv6, done);
+
+        // ipv4 header & authentication header
+        self.place(ipv4);
+        self.load_x_memory(MEM_NET);
+        self.statement(BPF_LD | BPF_H | BPF_IND, 6);
+        self.statement(BPF_ALU | BPF_AND | BPF_K, 0x3fff);
+        let not_fragmented = self.label();
+        let fragmented = self.label();
+        self.jump(BPF_JEQ, 0, not_fragmented, fragmented);
+        self.place(fragmented);
+        self.statement(BPF_LD | BPF_IMM, 1);
+        self.store(MEM_FRAGMENTED);
+        self.place(not_fragmented);
+        self.statement(BPF_LD | BPF_B | BPF_IND, 9);
+        self.store(MEM_IP_NUMBER);
+        self.statement(BPF_LD | BPF_B | BPF_IND, 0);
+        self.statement(BPF_ALU | BPF_AND | BPF_K, 0xf);
+        self.statement(BPF_ALU | BPF_LSH | BPF_K, 2);
+        self.statement(BPF_ALU | BPF_ADD | BPF_X, 0);
+        self.store(MEM_TRANSPORT);
+        self.load_memory(MEM_IP_NUMBER);
+        let ipv4_auth = self.label();
+        self.jump(BPF_JEQ, u32::from(ip_number::AUTH), ipv4_auth, transport);
+        self.place(ipv4_auth);
+        self.load_x_memory(MEM_TRANSPORT);
+        self.extension_header(ip_number::AUTH);
+        self.goto(transport);
+
+        // ipv6 header & extension headers
+        self.place(ipv6);
+        self.load_x_memory(MEM_NET);
+        self.statement(BPF_LD | BPF_B | BPF_IND, 6);
+        self.store(MEM_IP_NUMBER);
+        self.statement(BPF_MISC | BPF_TXA, 0);
+        self.statement(BPF_ALU | BPF_ADD | BPF_K, Ipv6Header::SERIALIZED_SIZE as u32);
+        self.store(MEM_TRANSPORT);
+        for _ in 0..BPF_MAX_IPV6_EXTENSIONS {
+            use ip_number::*;
+            let next = self.label();
+            let headers = [IPV6_HOP_BY_HOP, IPV6_ROUTE, IPV6_DEST_OPTIONS, IPV6_FRAG, AUTH];
+            let targets: Vec<Label> = headers.iter().map(|_| self.label()).collect();
+            self.load_memory(MEM_IP_NUMBER);
+            for (i, (header, target)) in headers.iter().zip(targets.iter()).enumerate() {
+                if i + 1 < headers.len() {
+                    let check_next = self.label();
+                    self.jump(BPF_JEQ, u32::from(*header), *target, check_next);
+                    self.place(check_next);
+                } else {
+                    self.jump(BPF_JEQ, u32::from(*header), *target, transport);
+                }
+            }
+            for (header, target) in headers.iter().zip(targets.iter()) {
+                self.place(*target);
+                self.load_x_memory(MEM_TRANSPORT);
+                self.extension_header(*header);
+                self.goto(next);
+            }
+            self.place(next);
+        }
+
+        // transport layer
+        self.place(transport);
+        self.load_memory(MEM_TRANSPORT);
+        self.store(MEM_PAYLOAD);
+        self.load_memory(MEM_FRAGMENTED);
+        let decode = self.label();
+        self.jump(BPF_JEQ, 0, decode, done);
+        self.place(decode);
+        self.load_x_memory(MEM_TRANSPORT);
+        self.load_memory(MEM_IP_NUMBER);
+        let fixed_8 = self.label();
+        let fixed_12 = self.label();
+        let tcp = self.label();
+        let dccp = self.label();
+        let whole = self.label();
+        {
+            use ip_number::*;
+            let cases = [
+                (UDP, fixed_8),
+                (UDP_LITE, fixed_8),
+                (SCTP, fixed_12),
+                (TCP, tcp),
+                (DCCP, dccp),
+                (ICMP, whole),
+                (IPV6_ICMP, whole),
+                (IGMP, whole),
+            ];
+            for (value, target) in cases.iter() {
+                let next = self.label();
+                self.jump(BPF_JEQ, u32::from(*value), *target, next);
+                self.place(next);
+            }
+            self.goto(done);
+        }
+        for (label, len) in [(fixed_8, 8), (fixed_12, 12)].iter() {
+            self.place(*label);
+            self.statement(BPF_MISC | BPF_TXA, 0);
+            self.statement(BPF_ALU | BPF_ADD | BPF_K, *len);
+            self.store(MEM_PAYLOAD);
+            self.goto(done);
+        }
+        self.place(tcp);
+        self.statement(BPF_LD | BPF_B | BPF_IND, 12);
+        self.statement(BPF_ALU | BPF_RSH | BPF_K, 4);
+        self.statement(BPF_ALU | BPF_LSH | BPF_K, 2);
+        self.statement(BPF_ALU | BPF_ADD | BPF_X, 0);
+        self.store(MEM_PAYLOAD);
+        self.goto(done);
+        self.place(dccp);
+        self.statement(BPF_LD | BPF_B | BPF_IND, 4);
+        self.statement(BPF_ALU | BPF_LSH | BPF_K, 2);
+        self.statement(BPF_ALU | BPF_ADD | BPF_X, 0);
+        self.store(MEM_PAYLOAD);
+        self.goto(done);
+        // icmp & igmp messages contain the rest of the packet
+        self.place(whole);
+        self.statement(BPF_LD | BPF_W | BPF_LEN, 0);
+        self.store(MEM_PAYLOAD);
+
+        self.place(done);
+    }
+
+    /// Skips the ip extension header at the offset in the index register
+    /// (updates the ip number, transport offset & fragmentation).
+    fn extension_header(&mut self, header: u8) {
+        self.statement(BPF_LD | BPF_B | BPF_IND, 0);
+        self.store(MEM_IP_NUMBER);
+        match header {
+            ip_number::IPV6_FRAG => {
+                // same check as Ipv6FragmentHeaderSlice::is_fragmenting_payload
+                self.statement(BPF_LD | BPF_H | BPF_IND, 2);
+                self.statement(BPF_ALU | BPF_AND | BPF_K, 0xff9f);
+                let skip = self.label();
+                let fragmented = self.label();
+                self.jump(BPF_JEQ, 0, skip, fragmented);
+                self.place(fragmented);
+                self.statement(BPF_LD | BPF_IMM, 1);
+                self.store(MEM_FRAGMENTED);
+                self.place(skip);
+                self.statement(BPF_MISC | BPF_TXA, 0);
+                self.statement(BPF_ALU | BPF_ADD | BPF_K, 8);
+            },
+            ip_number::AUTH => {
+                // length in 4 octets minus 2
+                self.statement(BPF_LD | BPF_B | BPF_IND, 1);
+                self.statement(BPF_ALU | BPF_ADD | BPF_K, 2);
+                self.statement(BPF_ALU | BPF_LSH | BPF_K, 2);
+                self.statement(BPF_ALU | BPF_ADD | BPF_X, 0);
+            },
+            _ => {
+                // length in 8 octets minus 1
+                self.statement(BPF_LD | BPF_B | BPF_IND, 1);
+                self.statement(BPF_ALU | BPF_ADD | BPF_K, 1);
+                self.statement(BPF_ALU | BPF_LSH | BPF_K, 3);
+                self.statement(BPF_ALU | BPF_ADD | BPF_X, 0);
+            },
+        }
+        self.store(MEM_TRANSPORT);
+    }
+
+    /// Jumps to `accept` if the expression matches & to `reject` otherwise.
+    fn expression(&mut self, expression: &FilterExpression, accept: Label, reject: Label) {
+        use FilterExpression::*;
+        match expression {
+            And(expressions) => {
+                for expression in expressions {
+                    let next = self.label();
+                    self.expression(expression, next, reject);
+                    self.place(next);
+                }
+                self.goto(accept);
+            },
+            Or(expressions) => {
+                for expression in expressions {
+                    let next = self.label();
+                    self.expression(expression, accept, next);
+                    self.place(next);
+                }
+                self.goto(reject);
+            },
+            Not(expression) => self.expression(expression, reject, accept),
+            leaf => {
+                self.condition(leaf, reject);
+                self.goto(accept);
+            },
+        }
+    }
+
+    /// Generates the code for a leaf expression that continues if the
+    /// condition is fulfilled & jumps to `fail` otherwise.
+    fn condition(&mut self, expression: &FilterExpression, fail: Label) {
+        use FilterExpression::*;
+        match expression {
+            Layers(filter) => self.layers(filter, fail),
+            Ipv4Net{ direction, net } => {
+                self.load_memory(MEM_ETHER_TYPE);
+                self.expect(BPF_JEQ, u32::from(ether_type::IPV4), fail);
+                self.load_x_memory(MEM_NET);
+                let mask = match net.prefix_len {
+                    0 => 0,
+                    len if len >= 32 => u32::MAX,
+                    len => u32::MAX << (32 - u32::from(len)),
+                };
+                let address = u32::from_be_bytes(net.address) & mask;
+                self.direction(*direction, fail, |codegen, is_source, fail| {
+                    codegen.masked_word(if is_source { 12 } else { 16 }, mask, address, fail);
+                });
+            },
+            Ipv6Net{ direction, net } => {
+                self.load_memory(MEM_ETHER_TYPE);
+                self.expect(BPF_JEQ, u32::from(ether_type::IPV6), fail);
+                self.load_x_memory(MEM_NET);
+                let mask = match net.prefix_len {
+                    0 => 0,
+                    len if len >= 128 => u128::MAX,
+                    len => u128::MAX << (128 - u32::from(len)),
+                };
+                let address = u128::from_be_bytes(net.address) & mask;
+                self.direction(*direction, fail, |codegen, is_source, fail| {
+                    let offset = if is_source { 8 } else { 24 };
+                    for i in 0..4u32 {
+                        let shift = 96 - 32*i;
+                        codegen.masked_word(offset + 4*i, (mask >> shift) as u32, (address >> shift) as u32, fail);
+                    }
+                });
+            },
+            Port{ direction, ports } => {
+                use ip_number::*;
+                self.require_transport(fail);
+                self.load_memory(MEM_IP_NUMBER);
+                let ok = self.label();
+                for value in [UDP, TCP, UDP_LITE, SCTP].iter() {
+                    let next = self.label();
+                    self.jump(BPF_JEQ, u32::from(*value), ok, next);
+                    self.place(next);
+                }
+                self.jump(BPF_JEQ, u32::from(DCCP), ok, fail);
+                self.place(ok);
+                self.load_x_memory(MEM_TRANSPORT);
+                self.direction(*direction, fail, |codegen, is_source, fail| {
+                    codegen.statement(BPF_LD | BPF_H | BPF_IND, if is_source { 0 } else { 2 });
+                    match ports {
+                        PortMatch::Range{ min, max } => {
+                            let (min, max) = (min.min(max), min.max(max));
+                            if *min > 0 {
+                                codegen.expect(BPF_JGE, u32::from(*min), fail);
+                            }
+                            if *max < u16::MAX {
+                                codegen.expect_not(BPF_JGT, u32::from(*max), fail);
+                            }
+                        },
+                        PortMatch::List(list) => {
+                            let ok = codegen.label();
+                            for port in list {
+                                let next = codegen.label();
+                                codegen.jump(BPF_JEQ, u32::from(*port), ok, next);
+                                codegen.place(next);
+                            }
+                            codegen.goto(fail);
+                            codegen.place(ok);
+                        },
+                    }
+                });
+            },
+            IpNumber(value) => {
+                self.require_ip(fail);
+                self.load_memory(MEM_IP_NUMBER);
+                self.expect(BPF_JEQ, u32::from(*value), fail);
+            },
+            TcpFlags{ mask, value } => {
+                self.require_transport_number(ip_number::TCP, fail);
+                self.statement(BPF_LD | BPF_H | BPF_IND, 12);
+                self.statement(BPF_ALU | BPF_AND | BPF_K, u32::from(*mask & 0x1ff));
+                self.expect(BPF_JEQ, u32::from(*value), fail);
+            },
+            Icmpv4{ icmp_type, code } => {
+                self.require_transport_number(ip_number::ICMP, fail);
+                self.icmp(*icmp_type, *code, fail);
+            },
+            Icmpv6{ icmp_type, code } => {
+                self.require_transport_number(ip_number::IPV6_ICMP, fail);
+                self.icmp(*icmp_type, *code, fail);
+            },
+            Dscp(value) => {
+                let ipv4 = self.label();
+                let ipv6 = self.label();
+                let compare = self.label();
+                let not_ipv4 = self.label();
+                self.load_memory(MEM_ETHER_TYPE);
+                self.load_x_memory(MEM_NET);
+                self.jump(BPF_JEQ, u32::from(ether_type::IPV4), ipv4, not_ipv4);
+                self.place(not_ipv4);
+                self.jump(BPF_JEQ, u32::from(ether_type::IPV6), ipv6, fail);
+                self.place(ipv4);
+                self.statement(BPF_LD | BPF_B | BPF_IND, 1);
+                self.statement(BPF_ALU | BPF_RSH | BPF_K, 2);
+                self.goto(compare);
+                self.place(ipv6);
+                self.statement(BPF_LD | BPF_H | BPF_IND, 0);
+                self.statement(BPF_ALU | BPF_RSH | BPF_K, 6);
+                self.statement(BPF_ALU | BPF_AND | BPF_K, 0x3f);
+                self.place(compare);
+                self.expect(BPF_JEQ, u32::from(*value), fail);
+            },
+            VlanPcp(value) => {
+                self.load_memory(MEM_VLAN_COUNT);
+                self.expect_not(BPF_JEQ, 0, fail);
+                self.statement(BPF_LD | BPF_B | BPF_ABS, 14);
+                self.statement(BPF_ALU | BPF_RSH | BPF_K, 5);
+                self.expect(BPF_JEQ, u32::from(*value), fail);
+            },
+            PayloadLength{ min, max } => {
+                self.load_x_memory(MEM_PAYLOAD);
+                self.statement(BPF_LD | BPF_W | BPF_LEN, 0);
+                self.statement(BPF_ALU | BPF_SUB | BPF_X, 0);
+                match u32::try_from(*min) {
+                    Ok(0) => {},
+                    Ok(min) => self.expect(BPF_JGE, min, fail),
+                    Err(_) => self.goto(fail),
+                }
+                if let Ok(max) = u32::try_from(*max) {
+                    self.expect_not(BPF_JGT, max, fail);
+                }
+            },
+            Bytes{ layer, offset, size, mask, op, value } => {
+                self.bytes(*layer, *offset, *size, *mask, *op, *value, fail);
+            },
+            And(_) | Or(_) | Not(_) => {
+                let accept = self.label();
+                self.expression(expression, accept, fail);
+                self.place(accept);
+            },
+        }
+    }
+
+    /// Checks the source and/or destination as defined by the direction.
+    fn direction(&mut self, direction: Direction, fail: Label, check: impl Fn(&mut Codegen, bool, Label)) {
+        match direction {
+            Direction::Source => check(self, true, fail),
+            Direction::Destination => check(self, false, fail),
+            Direction::SourceAndDestination => {
+                check(self, true, fail);
+                check(self, false, fail);
+            },
+            Direction::SourceOrDestination => {
+                let destination = self.label();
+                let ok = self.label();
+                check(self, true, destination);
+                self.goto(ok);
+                self.place(destination);
+                check(self, false, fail);
+                self.place(ok);
+            },
+        }
+    }
+
+    /// Compares the word at the offset (relative to the index register) with the masked value.
+    fn masked_word(&mut self, offset: u32, mask: u32, value: u32, fail: Label) {
+        if 0 == mask {
+            return;
+        }
+        self.statement(BPF_LD | BPF_W | BPF_IND, offset);
+        if u32::MAX != mask {
+            self.statement(BPF_ALU | BPF_AND | BPF_K, mask);
+        }
+        self.expect(BPF_JEQ, value, fail);
+    }
+
+    fn require_ip(&mut self, fail: Label) {
+        let ok = self.label();
+        let not_ipv4 = self.label();
+        self.load_memory(MEM_ETHER_TYPE);
+        self.jump(BPF_JEQ, u32::from(ether_type::IPV4), ok, not_ipv4);
+        self.place(not_ipv4);
+        self.jump(BPF_JEQ, u32::from(ether_type::IPV6), ok, fail);
+        self.place(ok);
+    }
+
+    /// Continues if the transport layer was decoded (ip header present & not fragmented).
+    fn require_transport(&mut self, fail: Label) {
+        self.require_ip(fail);
+        self.load_memory(MEM_FRAGMENTED);
+        self.expect(BPF_JEQ, 0, fail);
+    }
+
+    /// Continues if the transport layer was decoded & has the given ip number (the
+    /// index register is set to the transport offset).
+    fn require_transport_number(&mut self, value: u8, fail: Label) {
+        self.require_transport(fail);
+        self.load_memory(MEM_IP_NUMBER);
+        self.expect(BPF_JEQ, u32::from(value), fail);
+        self.load_x_memory(MEM_TRANSPORT);
+    }
+
+    fn icmp(&mut self, icmp_type: Option<u8>, code: Option<u8>, fail: Label) {
+        if let Some(value) = icmp_type {
+            self.statement(BPF_LD | BPF_B | BPF_IND, 0);
+            self.expect(BPF_JEQ, u32::from(value), fail);
+        }
+        if let Some(value) = code {
+            self.statement(BPF_LD | BPF_B | BPF_IND, 1);
+            self.expect(BPF_JEQ, u32::from(value), fail);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn bytes(&mut self, layer: FilterLayer, offset: usize, size: u8, mask: u32, op: CompareOp, value: u32, fail: Label) {
+        // check that the layer is present & set the index register to its start
+        match layer {
+            FilterLayer::Ethernet2 => self.statement(BPF_LDX | BPF_IMM, 0),
+            FilterLayer::Ipv4 | FilterLayer::Ipv6 => {
+                self.load_memory(MEM_ETHER_TYPE);
+                let ether_type = if layer == FilterLayer::Ipv4 { ether_type::IPV4 } else { ether_type::IPV6 };
+                self.expect(BPF_JEQ, u32::from(ether_type), fail);
+                self.load_x_memory(MEM_NET);
+            },
+            FilterLayer::Tcp => self.require_transport_number(ip_number::TCP, fail),
+            FilterLayer::Udp => self.require_transport_number(ip_number::UDP, fail),
+            FilterLayer::Icmpv4 => self.require_transport_number(ip_number::ICMP, fail),
+            FilterLayer::Icmpv6 => self.require_transport_number(ip_number::IPV6_ICMP, fail),
+        }
+
+        // data outside of the packet leads to a non match (instead of
+        // aborting the program)
+        if size > 0 {
+            let end = match offset.checked_add(usize::from(size)).and_then(|end| u32::try_from(end).ok()) {
+                Some(end) if end <= u32::MAX / 2 => end,
+                _ => {
+                    self.goto(fail);
+                    return;
+                },
+            };
+            self.statement(BPF_LD | BPF_W | BPF_LEN, 0);
+            self.statement(BPF_ALU | BPF_SUB | BPF_X, 0);
+            self.expect(BPF_JGE, end, fail);
+        }
+
+        // load the value (relative to the start of the layer in the index register)
+        let offset = offset as u32;
+        let load_size = match size {
+            1 => BPF_B,
+            2 => BPF_H,
+            4 => BPF_W,
+            _ => {
+                // other sizes are read byte by byte (only the last 4 bytes are kept)
+                self.statement(BPF_STX, MEM_BYTES_BASE);
+                self.statement(BPF_LD | BPF_IMM, 0);
+                self.store(MEM_BYTES_VALUE);
+                for i in 0..u32::from(size) {
+                    self.load_x_memory(MEM_BYTES_BASE);
+                    self.statement(BPF_LD | BPF_B | BPF_IND, offset + i);
+                    self.statement(BPF_MISC | BPF_TAX, 0);
+                    self.load_memory(MEM_BYTES_VALUE);
+                    self.statement(BPF_ALU | BPF_LSH | BPF_K, 8);
+                    self.statement(BPF_ALU | BPF_OR | BPF_X, 0);
+                    self.store(MEM_BYTES_VALUE);
+                }
+                self.compare(mask, op, value, fail);
+                return;
+            },
+        };
+        self.statement(BPF_LD | load_size | BPF_IND, offset);
+        self.compare(mask, op, value, fail);
+    }
+
+    /// Compares the masked accumulator with the value.
+    fn compare(&mut self, mask: u32, op: CompareOp, value: u32, fail: Label) {
+        if u32::MAX != mask {
+            self.statement(BPF_ALU | BPF_AND | BPF_K, mask);
+        }
+        match op {
+            CompareOp::Equal => self.expect(BPF_JEQ, value, fail),
+            CompareOp::NotEqual => self.expect_not(BPF_JEQ, value, fail),
+            CompareOp::Less => self.expect_not(BPF_JGE, value, fail),
+            CompareOp::LessOrEqual => self.expect_not(BPF_JGT, value, fail),
+            CompareOp::Greater => self.expect(BPF_JGT, value, fail),
+            CompareOp::GreaterOrEqual => self.expect(BPF_JGE, value, fail),
+        }
+    }
+
+    fn layers(&mut self, filter: &Filter, fail: Label) {
+        // link layer (always present for ethernet packets)
+        match &filter.link {
+            ElementFilter::Any => {},
+            ElementFilter::No => self.goto(fail),
+            ElementFilter::Some(LinkFilter::Ethernet2{ source, destination }) => {
+                for (offset, address) in [(0, destination), (6, source)].iter() {
+                    if let Some(address) = address {
+                        self.statement(BPF_LD | BPF_W | BPF_ABS, *offset);
+                        self.expect(BPF_JEQ, u32::from_be_bytes([address[0], address[1], address[2], address[3]]), fail);
+                        self.statement(BPF_LD | BPF_H | BPF_ABS, *offset + 4);
+                        self.expect(BPF_JEQ, u32::from(u16::from_be_bytes([address[4], address[5]])), fail);
+                    }
+                }
+            },
+        }
+
+        // vlan headers
+        let vlan_id = |codegen: &mut Codegen, offset: u32, id: &Option<u16>| {
+            if let Some(id) = id {
+                codegen.statement(BPF_LD | BPF_H | BPF_ABS, offset);
+                codegen.statement(BPF_ALU | BPF_AND | BPF_K, 0xfff);
+                codegen.expect(BPF_JEQ, u32::from(*id), fail);
+            }
+        };
+        match &filter.vlan {
+            ElementFilter::Any => {},
+            ElementFilter::No => {
+                self.load_memory(MEM_VLAN_COUNT);
+                self.expect(BPF_JEQ, 0, fail);
+            },
+            ElementFilter::Some(VlanFilter::Single(id)) => {
+                self.load_memory(MEM_VLAN_COUNT);
+                self.expect(BPF_JEQ, 1, fail);
+                vlan_id(self, 14, id);
+            },
+            ElementFilter::Some(VlanFilter::Double{ outer_identifier, inner_identifier }) => {
+                self.load_memory(MEM_VLAN_COUNT);
+                self.expect(BPF_JEQ, 2, fail);
+                vlan_id(self, 14, outer_identifier);
+                vlan_id(self, 18, inner_identifier);
+            },
+        }
+
+        // ip header
+        match &filter.ip {
+            ElementFilter::Any => {},
+            ElementFilter::No => {
+                self.load_memory(MEM_ETHER_TYPE);
+                self.expect_not(BPF_JEQ, u32::from(ether_type::IPV4), fail);
+                self.expect_not(BPF_JEQ, u32::from(ether_type::IPV6), fail);
+            },
+            ElementFilter::Some(IpFilter::Ipv4{ source, destination }) => {
+                self.load_memory(MEM_ETHER_TYPE);
+                self.expect(BPF_JEQ, u32::from(ether_type::IPV4), fail);
+                self.load_x_memory(MEM_NET);
+                for (offset, address) in [(12, source), (16, destination)].iter() {
+                    if let Some(address) = address {
+                        self.masked_word(*offset, u32::MAX, u32::from_be_bytes(*address), fail);
+                    }
+                }
+            },
+            ElementFilter::Some(IpFilter::Ipv6{ source, destination }) => {
+                self.load_memory(MEM_ETHER_TYPE);
+                self.expect(BPF_JEQ, u32::from(ether_type::IPV6), fail);
+                self.load_x_memory(MEM_NET);
+                for (offset, address) in [(8, source), (24, destination)].iter() {
+                    if let Some(address) = address {
+                        for (i, word) in address.chunks(4).enumerate() {
+                            let word = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+                            self.masked_word(*offset + 4*i as u32, u32::MAX, word, fail);
+                        }
+                    }
+                }
+            },
+        }
+
+        // transport layer
+        match &filter.transport {
+            ElementFilter::Any => {},
+            ElementFilter::No => {
+                // no transport layer if the ip header is missing or the payload is fragmented
+                let ok = self.label();
+                let ip = self.label();
+                let not_ipv4 = self.label();
+                self.load_memory(MEM_ETHER_TYPE);
+                self.jump(BPF_JEQ, u32::from(ether_type::IPV4), ip, not_ipv4);
+                self.place(not_ipv4);
+                self.jump(BPF_JEQ, u32::from(ether_type::IPV6), ip, ok);
+                self.place(ip);
+                self.load_memory(MEM_FRAGMENTED);
+                self.jump(BPF_JEQ, 0, fail, ok);
+                self.place(ok);
+            },
+            ElementFilter::Some(transport) => {
+                let (number, source_port, destination_port) = match transport {
+                    TransportFilter::Udp{ source_port, destination_port } => (ip_number::UDP, source_port, destination_port),
+                    TransportFilter::Tcp{ source_port, destination_port } => (ip_number::TCP, source_port, destination_port),
+                };
+                self.require_transport_number(number, fail);
+                for (offset, port) in [(0, source_port), (2, destination_port)].iter() {
+                    if let Some(port) = port {
+                        self.statement(BPF_LD | BPF_H | BPF_IND, *offset);
+                        self.expect(BPF_JEQ, u32::from(*port), fail);
+                    }
+                }
+            },
+        }
+    }
+
+    /// Resolves the labels & converts the operations to instructions. Conditional
+    /// jumps with targets further away then 255 instructions are redirected via
+    /// an additional unconditional jump.
+    fn assemble(self) -> Vec<BpfInstruction> {
+        // remove unconditional jumps to the directly following instruction
+        let mut ops: Vec<Op> = Vec::with_capacity(self.ops.len());
+        for (index, op) in self.ops.iter().enumerate() {
+            if let Op::Goto(target) = op {
+                let is_next = self.ops[index + 1..]
+                    .iter()
+                    .take_while(|other| matches!(other, Op::Place(_)))
+                    .any(|other| *other == Op::Place(*target));
+                if is_next {
+                    continue;
+                }
+            }
+            ops.push(*op);
+        }
+
+        // determine which jumps need an additional long jump (repeated until
+        // nothing changes, as every long jump moves the following labels)
+        let mut long_jumps = vec![(false, false); ops.len()];
+        let mut positions = vec![0usize; self.label_count];
+        loop {
+            // layout of the instructions with the current long jumps
+            let mut position = 0;
+            for (op, long) in ops.iter().zip(long_jumps.iter()) {
+                match op {
+                    Op::Place(label) => positions[label.0] = position,
+                    Op::Jump{ .. } => position += 1 + usize::from(long.0) + usize::from(long.1),
+                    _ => position += 1,
+                }
+            }
+
+            // determine the long jumps based on the fixed layout
+            let is_short = |target: Label, next: usize| {
+                positions[target.0]
+                    .checked_sub(next)
+                    .is_some_and(|distance| distance <= usize::from(u8::MAX))
+            };
+            let mut next_long_jumps = long_jumps.clone();
+            let mut position = 0;
+            for (op, long) in ops.iter().zip(next_long_jumps.iter_mut()) {
+                match op {
+                    Op::Place(_) => {},
+                    Op::Jump{ jt, jf, .. } => {
+                        let next = position + 1;
+                        position += 1 + usize::from(long.0) + usize::from(long.1);
+                        long.0 = long.0 || !is_short(*jt, next);
+                        long.1 = long.1 || !is_short(*jf, next);
+                    },
+                    _ => position += 1,
+                }
+            }
+            if next_long_jumps == long_jumps {
+                break;
+            }
+            long_jumps = next_long_jumps;
+        }
+
+        // generate the instructions
+        let offset = |target: Label, next: usize| {
+            positions[target.0]
+                .checked_sub(next)
+                .expect("bpf jumps can only target following instructions")
+        };
+        let mut result = Vec::with_capacity(ops.len());
+        for (op, long) in ops.iter().zip(long_jumps.iter()) {
+            match op {
+                Op::Place(_) => {},
+                Op::Statement(instruction) => result.push(*instruction),
+                Op::Goto(target) => {
+                    let k = offset(*target, result.len() + 1);
+                    result.push(BpfInstruction::statement(BPF_JMP | BPF_JA, k as u32));
+                },
+                Op::Jump{ code, k, jt, jf } => {
+                    let next = result.len() + 1;
+                    let jt_offset = if long.0 { 0 } else { offset(*jt, next) };
+                    let jf_offset = if long.1 { usize::from(long.0) } else { offset(*jf, next) };
+                    result.push(BpfInstruction::jump(*code, *k, jt_offset as u8, jf_offset as u8));
+                    for (is_long, target) in [(long.0, jt), (long.1, jf)].iter() {
+                        if *is_long {
+                            let k = offset(**target, result.len() + 1);
+                            result.push(BpfInstruction::statement(BPF_JMP | BPF_JA, k as u32));
+                        }
+                    }
+                },
+            }
+        }
+        result
+    }
+}
@@ -289,6 +289,125 @@ mod sum16_bit_words_tests {
     }
 }
 
+/// Helper for calculating CRC32c (Castagnoli) checksums as used in
+/// SCTP packets (see [RFC 9260 Appendix A](https://datatracker.ietf.org/doc/html/rfc9260#appendix-A)).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Crc32c {
+    /// Current (not yet inverted) crc value.
+    state: u32,
+}
+
+impl Crc32c {
+    /// Reversed CRC32c polynomial.
+    const POLYNOMIAL: u32 = 0x82f6_3b78;
+
+    /// Lookup table for a byte wise crc calculation.
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut value = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                value = if 0 != value & 1 {
+                    (value >> 1) ^ Crc32c::POLYNOMIAL
+                } else {
+                    value >> 1
+                };
+                bit += 1;
+            }
+            table[i] = value;
+            i += 1;
+        }
+        table
+    };
+
+    pub fn new() -> Crc32c {
+        Crc32c {
+            state: 0xffff_ffff
+        }
+    }
+
+    /// Add the given slice to the checksum.
+    #[inline]
+    pub fn add_slice(self, slice: &[u8]) -> Crc32c {
+        let mut state = self.state;
+        for byte in slice {
+            state = (state >> 8) ^ Crc32c::TABLE[usize::from((state as u8) ^ byte)];
+        }
+        Crc32c {
+            state
+        }
+    }
+
+    /// Add the given number of zero bytes to the checksum.
+    #[inline]
+    pub fn add_zeros(self, count: usize) -> Crc32c {
+        let mut state = self.state;
+        for _ in 0..count {
+            state = (state >> 8) ^ Crc32c::TABLE[usize::from(state as u8)];
+        }
+        Crc32c {
+            state
+        }
+    }
+
+    /// Returns the resulting CRC32c value.
+    #[inline]
+    pub fn crc(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32c {
+    fn default() -> Crc32c {
+        Crc32c::new()
+    }
+}
+
+#[cfg(test)]
+mod crc32c_tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(0, Crc32c::new().crc());
+        assert_eq!(Crc32c::new(), Crc32c::default());
+    }
+
+    #[test]
+    fn add_slice() {
+        // check value of the CRC32c (see "Catalogue of parametrised CRC algorithms")
+        assert_eq!(0xe306_9283, Crc32c::new().add_slice(b"123456789").crc());
+
+        // test vectors from RFC 3720 Appendix B.4
+        assert_eq!(0x8a91_36aa, Crc32c::new().add_slice(&[0u8; 32]).crc());
+        assert_eq!(0x62a8_ab43, Crc32c::new().add_slice(&[0xffu8; 32]).crc());
+        {
+            let mut data = [0u8; 32];
+            for (i, value) in data.iter_mut().enumerate() {
+                *value = i as u8;
+            }
+            assert_eq!(0x46dd_794e, Crc32c::new().add_slice(&data).crc());
+        }
+
+        // split calculation
+        assert_eq!(
+            Crc32c::new().add_slice(b"123456789").crc(),
+            Crc32c::new().add_slice(b"1234").add_slice(b"56789").crc()
+        );
+    }
+
+    #[test]
+    fn add_zeros() {
+        assert_eq!(0x8a91_36aa, Crc32c::new().add_zeros(32).crc());
+        assert_eq!(
+            Crc32c::new().add_slice(&[1, 2, 0, 0, 0, 0, 3]).crc(),
+            Crc32c::new().add_slice(&[1, 2]).add_zeros(4).add_slice(&[3]).crc()
+        );
+    }
+}
+
 /// Helper functions for calculating a 16 bit checksum using
 /// a u32 to sum up all values.
 pub mod u32_16bit_word {
@@ -0,0 +1,236 @@
+use super::*;
+
+use core::ops::Range;
+
+/// Tunnel header placed between the ip header and the payload
+/// by an [`Encapsulation`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TunnelHeader {
+    /// UDP header (e.g. for VXLAN or GRE-in-UDP tunnels, the tunnel
+    /// specific headers are expected to be part of the payload).
+    Udp(UdpHeader),
+    /// Generic routing encapsulation header.
+    Gre(GreHeader),
+}
+
+impl TunnelHeader {
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        use TunnelHeader::*;
+        match self {
+            Udp(header) => header.header_len(),
+            Gre(header) => header.header_len(),
+        }
+    }
+
+    /// Ip number identifying the tunnel header in the preceding ip header.
+    pub fn ip_number(&self) -> u8 {
+        use TunnelHeader::*;
+        match self {
+            Udp(_) => ip_number::UDP,
+            Gre(_) => ip_number::GRE,
+        }
+    }
+
+    /// Write the tunnel header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use TunnelHeader::*;
+        match self {
+            Udp(header) => header.write_to_slice(slice),
+            Gre(header) => header.write_to_slice(slice),
+        }
+    }
+}
+
+/// Headers that get prepended in front of a payload that is already
+/// located in a buffer (e.g. when tunneling or re-tagging packets).
+///
+/// In contrast to the [`PacketBuilder`] the payload is not copied. Instead
+/// the headers are written into the free space (headroom) directly in front
+/// of the payload. Before the headers are written the following fields are
+/// set based on the present headers & payload:
+///
+/// * The ether type of the Ethernet II & VLAN headers (if an ip header or a
+///   vlan header follows).
+/// * The payload length of the ip header.
+/// * The ip number of the ip header (or the last extension header) if a
+///   tunnel header is present. Without a tunnel header the next header
+///   fields are written as they are set (e.g. [`ip_number::IPV4`] for IP in IP).
+/// * The length & checksum of an UDP tunnel header (the checksum is only
+///   calculated if an ip header is present).
+/// * The checksum of a GRE tunnel header (if the checksum field is present).
+/// * The header checksum of an IPv4 header.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // buffer with 64 bytes of headroom followed by the payload
+/// let mut buffer = [0u8;64 + 4];
+/// buffer[64..].copy_from_slice(&[1,2,3,4]);
+///
+/// let encapsulation = Encapsulation {
+///     ethernet2: Some(Ethernet2Header {
+///         source: [1,2,3,4,5,6],
+///         destination: [7,8,9,10,11,12],
+///         ether_type: 0, // set by prepend
+///     }),
+///     vlan: None,
+///     ip: Some(IpHeader::Version4(
+///         Ipv4Header::new(0, 20, 0, [192,168,1,1], [192,168,1,2]),
+///         Default::default()
+///     )),
+///     tunnel: Some(TunnelHeader::Gre(GreHeader::new(ether_type::IPV4))),
+/// };
+/// let frame = encapsulation.prepend(&mut buffer, 64..68).unwrap();
+/// assert_eq!(frame, 64 - encapsulation.header_len()..68);
+///
+/// let sliced = SlicedPacket::from_ethernet(&buffer[frame]).unwrap();
+/// match sliced.ip.unwrap() {
+///     InternetSlice::Ipv4(ipv4, _) => assert_eq!(ip_number::GRE, ipv4.protocol()),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Encapsulation {
+    /// Outermost Ethernet II header.
+    pub ethernet2: Option<Ethernet2Header>,
+    /// Single or double vlan header following the Ethernet II header.
+    pub vlan: Option<VlanHeader>,
+    /// Ip header (including extension headers).
+    pub ip: Option<IpHeader>,
+    /// Tunnel header directly in front of the payload.
+    pub tunnel: Option<TunnelHeader>,
+}
+
+impl Encapsulation {
+    /// Length of all present headers in bytes (the headroom required in
+    /// front of the payload).
+    pub fn header_len(&self) -> usize {
+        self.ethernet2.as_ref().map(|h| h.header_len()).unwrap_or(0) +
+        self.vlan.as_ref().map(|h| h.header_len()).unwrap_or(0) +
+        self.ip.as_ref().map(|h| h.header_len()).unwrap_or(0) +
+        self.tunnel.as_ref().map(|h| h.header_len()).unwrap_or(0)
+    }
+
+    /// Writes the headers directly in front of the payload located at the given
+    /// range in the buffer and returns the range of the resulting frame.
+    ///
+    /// The payload itself is not modified or moved. If there is not enough headroom
+    /// in front of the payload a [`WriteError::SliceTooSmall`] containing the
+    /// required headroom ([`Encapsulation::header_len`]) is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the payload range is not inside of the buffer.
+    pub fn prepend(&self, buffer: &mut [u8], payload: Range<usize>) -> Result<Range<usize>, WriteError> {
+        // check that there is enough headroom
+        let header_len = self.header_len();
+        if payload.start < header_len {
+            return Err(WriteError::SliceTooSmall(header_len));
+        }
+        let (headroom, rest) = buffer.split_at_mut(payload.start);
+        let payload_slice = &rest[..payload.end - payload.start];
+
+        let mut headers = self.clone();
+        headers.set_payload_dependent_fields(payload_slice)?;
+
+        // write the headers directly in front of the payload
+        let start = payload.start - header_len;
+        let mut slice = &mut headroom[start..];
+        if let Some(header) = &headers.ethernet2 {
+            slice = header.write_to_slice(slice)?;
+        }
+        if let Some(header) = &headers.vlan {
+            slice = header.write_to_slice(slice)?;
+        }
+        if let Some(header) = &headers.ip {
+            slice = header.write_to_slice(slice)?;
+        }
+        if let Some(header) = &headers.tunnel {
+            header.write_to_slice(slice)?;
+        }
+        Ok(start..payload.end)
+    }
+
+    /// Sets the ether types, lengths, ip numbers & checksums of the headers
+    /// based on the given payload.
+    pub(crate) fn set_payload_dependent_fields(&mut self, payload: &[u8]) -> Result<(), ValueError> {
+        let Encapsulation { ethernet2, vlan, ip, tunnel } = self;
+
+        // ip payload length & next headers
+        let tunnel_len = tunnel.as_ref().map(|h| h.header_len()).unwrap_or(0);
+        match ip.as_mut() {
+            Some(IpHeader::Version4(header, ext)) => {
+                if let Some(tunnel) = tunnel.as_ref() {
+                    header.protocol = ext.set_next_headers(tunnel.ip_number());
+                }
+                header.set_payload_len(ext.header_len() + tunnel_len + payload.len())?;
+            },
+            Some(IpHeader::Version6(header, ext)) => {
+                if let Some(tunnel) = tunnel.as_ref() {
+                    header.next_header = ext.set_next_headers(tunnel.ip_number());
+                }
+                header.set_payload_length(ext.header_len() + tunnel_len + payload.len())?;
+            },
+            None => {},
+        }
+
+        // tunnel length & checksum
+        match tunnel.as_mut() {
+            Some(TunnelHeader::Udp(udp)) => {
+                if (u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE < payload.len() {
+                    return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
+                }
+                udp.length = (UdpHeader::SERIALIZED_SIZE + payload.len()) as u16;
+                match ip.as_ref() {
+                    Some(IpHeader::Version4(header, _)) => {
+                        udp.checksum = udp.calc_checksum_ipv4(header, payload)?;
+                    },
+                    Some(IpHeader::Version6(header, _)) => {
+                        udp.checksum = udp.calc_checksum_ipv6(header, payload)?;
+                    },
+                    None => {},
+                }
+            },
+            Some(TunnelHeader::Gre(gre)) if gre.checksum.is_some() => {
+                gre.checksum = Some(gre.calc_checksum(payload));
+            },
+            Some(TunnelHeader::Gre(_)) | None => {},
+        }
+
+        // ether types
+        let ip_ether_type = match ip {
+            Some(IpHeader::Version4(_, _)) => Some(ether_type::IPV4),
+            Some(IpHeader::Version6(_, _)) => Some(ether_type::IPV6),
+            None => None,
+        };
+        match vlan.as_mut() {
+            Some(VlanHeader::Single(header)) => {
+                if let Some(value) = ip_ether_type {
+                    header.ether_type = value;
+                }
+            },
+            Some(VlanHeader::Double(header)) => {
+                header.outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
+                if let Some(value) = ip_ether_type {
+                    header.inner.ether_type = value;
+                }
+            },
+            None => {},
+        }
+        if let Some(header) = ethernet2.as_mut() {
+            match vlan {
+                Some(VlanHeader::Single(_)) => header.ether_type = ether_type::VLAN_TAGGED_FRAME,
+                Some(VlanHeader::Double(_)) => header.ether_type = ether_type::PROVIDER_BRIDGING,
+                None => if let Some(value) = ip_ether_type {
+                    header.ether_type = value;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
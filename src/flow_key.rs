@@ -0,0 +1,298 @@
+use super::*;
+
+use core::net::IpAddr;
+
+/// Source & destination ip addresses of a flow.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum FlowAddresses {
+    Ipv4{
+        source: [u8;4],
+        destination: [u8;4],
+    },
+    Ipv6{
+        source: [u8;16],
+        destination: [u8;16],
+    },
+}
+
+impl FlowAddresses {
+    /// Returns the source address as an `std::net::IpAddr`.
+    pub fn source_addr(&self) -> IpAddr {
+        match self {
+            FlowAddresses::Ipv4{ source, .. } => IpAddr::from(*source),
+            FlowAddresses::Ipv6{ source, .. } => IpAddr::from(*source),
+        }
+    }
+
+    /// Returns the destination address as an `std::net::IpAddr`.
+    pub fn destination_addr(&self) -> IpAddr {
+        match self {
+            FlowAddresses::Ipv4{ destination, .. } => IpAddr::from(*destination),
+            FlowAddresses::Ipv6{ destination, .. } => IpAddr::from(*destination),
+        }
+    }
+}
+
+/// Transport layer part of a flow key.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum FlowTransportId {
+    /// No transport layer identifier is available (e.g. unknown protocol
+    /// or fragmented ip packets).
+    None,
+    /// Ports of protocols with ports (TCP, UDP, UDP-Lite, SCTP & DCCP).
+    Ports{
+        source: u16,
+        destination: u16,
+    },
+    /// Identifier of ICMP & ICMPv6 echo requests & replies.
+    IcmpId(u16),
+}
+
+/// Key identifying a flow via the ip addresses, the ip protocol number and
+/// the ports (or icmp echo identifier).
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::{FlowKey, PacketBuilder, SlicedPacket};
+/// # let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20).udp(21, 1234);
+/// # let mut packet = Vec::new();
+/// # builder.write(&mut packet, &[1,2,3]).unwrap();
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let key = FlowKey::from_sliced(&sliced).unwrap();
+///
+/// // the canonical form is the same for both directions
+/// assert_eq!(key.canonical(), key.reversed().canonical());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct FlowKey {
+    pub addresses: FlowAddresses,
+    /// IP protocol number of the transport layer (last next header after the
+    /// ip extension headers).
+    pub protocol: u8,
+    pub transport: FlowTransportId,
+}
+
+impl FlowKey {
+
+    /// Creates a flow key from a sliced packet. Returns `None` if the packet
+    /// contains no ip header.
+    pub fn from_sliced(packet: &SlicedPacket) -> Option<FlowKey> {
+        use crate::TransportSlice::*;
+
+        let ip = packet.ip.as_ref()?;
+        let addresses = match ip {
+            InternetSlice::Ipv4(header, _) => FlowAddresses::Ipv4{
+                source: header.source(),
+                destination: header.destination(),
+            },
+            InternetSlice::Ipv6(header, _) => FlowAddresses::Ipv6{
+                source: header.source(),
+                destination: header.destination(),
+            },
+        };
+        let ports = |source, destination| FlowTransportId::Ports{ source, destination };
+        let (protocol, transport) = match &packet.transport {
+            Some(Udp(udp)) => (ip_number::UDP, ports(udp.source_port(), udp.destination_port())),
+            Some(Tcp(tcp)) => (ip_number::TCP, ports(tcp.source_port(), tcp.destination_port())),
+            Some(UdpLite(udp)) => (ip_number::UDP_LITE, ports(udp.source_port(), udp.destination_port())),
+            Some(Sctp(sctp)) => (ip_number::SCTP, ports(sctp.source_port(), sctp.destination_port())),
+            Some(Dccp(dccp)) => (ip_number::DCCP, ports(dccp.source_port(), dccp.destination_port())),
+            Some(Icmpv4(icmp)) => (ip_number::ICMP, FlowKey::icmpv4_id(&icmp.icmp_type())),
+            Some(Icmpv6(icmp)) => (ip_number::IPV6_ICMP, FlowKey::icmpv6_id(&icmp.icmp_type())),
+            Some(Igmp(_)) => (ip_number::IGMP, FlowTransportId::None),
+            Some(Unknown(protocol)) => (*protocol, FlowTransportId::None),
+            None => {
+                // transport layer was not decoded (e.g. fragmented packet)
+                let protocol = match ip {
+                    InternetSlice::Ipv4(header, extensions) => {
+                        extensions.to_header().next_header(header.protocol()).ok()?
+                    },
+                    InternetSlice::Ipv6(header, extensions) => {
+                        Ipv6Extensions::from_slice(header.next_header(), extensions.slice()).ok()?.1
+                    },
+                };
+                (protocol, FlowTransportId::None)
+            },
+        };
+        Some(FlowKey{
+            addresses,
+            protocol,
+            transport,
+        })
+    }
+
+    /// Creates a flow key from decoded packet headers. Returns `None` if the packet
+    /// contains no ip header.
+    pub fn from_headers(packet: &PacketHeaders) -> Option<FlowKey> {
+        use crate::TransportHeader::*;
+
+        let ip = packet.ip.as_ref()?;
+        let addresses = match ip {
+            IpHeader::Version4(header, _) => FlowAddresses::Ipv4{
+                source: header.source,
+                destination: header.destination,
+            },
+            IpHeader::Version6(header, _) => FlowAddresses::Ipv6{
+                source: header.source,
+                destination: header.destination,
+            },
+        };
+        let ports = |source, destination| FlowTransportId::Ports{ source, destination };
+        let (protocol, transport) = match &packet.transport {
+            Some(Udp(udp)) => (ip_number::UDP, ports(udp.source_port, udp.destination_port)),
+            Some(Tcp(tcp)) => (ip_number::TCP, ports(tcp.source_port, tcp.destination_port)),
+            Some(UdpLite(udp)) => (ip_number::UDP_LITE, ports(udp.source_port, udp.destination_port)),
+            Some(Sctp(sctp)) => (ip_number::SCTP, ports(sctp.source_port, sctp.destination_port)),
+            Some(Dccp(dccp)) => (ip_number::DCCP, ports(dccp.source_port, dccp.destination_port)),
+            Some(Icmpv4(icmp)) => (ip_number::ICMP, FlowKey::icmpv4_id(&icmp.icmp_type)),
+            Some(Icmpv6(icmp)) => (ip_number::IPV6_ICMP, FlowKey::icmpv6_id(&icmp.icmp_type)),
+            Some(Igmp(_)) => (ip_number::IGMP, FlowTransportId::None),
+            None => (ip.next_header().ok()?, FlowTransportId::None),
+        };
+        Some(FlowKey{
+            addresses,
+            protocol,
+            transport,
+        })
+    }
+
+    fn icmpv4_id(icmp_type: &Icmpv4Type) -> FlowTransportId {
+        match icmp_type {
+            Icmpv4Type::EchoRequest(echo) | Icmpv4Type::EchoReply(echo) => FlowTransportId::IcmpId(echo.id),
+            _ => FlowTransportId::None,
+        }
+    }
+
+    fn icmpv6_id(icmp_type: &Icmpv6Type) -> FlowTransportId {
+        match icmp_type {
+            Icmpv6Type::EchoRequest(echo) | Icmpv6Type::EchoReply(echo) => FlowTransportId::IcmpId(echo.id),
+            _ => FlowTransportId::None,
+        }
+    }
+
+    /// Returns the key of the opposite direction (source & destination swapped).
+    pub fn reversed(&self) -> FlowKey {
+        FlowKey{
+            addresses: match self.addresses {
+                FlowAddresses::Ipv4{ source, destination } => FlowAddresses::Ipv4{
+                    source: destination,
+                    destination: source,
+                },
+                FlowAddresses::Ipv6{ source, destination } => FlowAddresses::Ipv6{
+                    source: destination,
+                    destination: source,
+                },
+            },
+            protocol: self.protocol,
+            transport: match self.transport {
+                FlowTransportId::Ports{ source, destination } => FlowTransportId::Ports{
+                    source: destination,
+                    destination: source,
+                },
+                other => other,
+            },
+        }
+    }
+
+    /// Returns true if the key is in its canonical form (the source address
+    /// & port are smaller or equal to the destination address & port).
+    pub fn is_canonical(&self) -> bool {
+        let (source_port, destination_port) = match self.transport {
+            FlowTransportId::Ports{ source, destination } => (source, destination),
+            _ => (0, 0),
+        };
+        match self.addresses {
+            FlowAddresses::Ipv4{ source, destination } => (source, source_port) <= (destination, destination_port),
+            FlowAddresses::Ipv6{ source, destination } => (source, source_port) <= (destination, destination_port),
+        }
+    }
+
+    /// Returns the direction independent form of the key (packets of both
+    /// directions of a flow have the same canonical key).
+    pub fn canonical(&self) -> FlowKey {
+        if self.is_canonical() {
+            *self
+        } else {
+            self.reversed()
+        }
+    }
+
+    /// Calculates the Toeplitz hash used for "receive side scaling" (RSS) by
+    /// network cards with the given key.
+    ///
+    /// The hash input are the source & destination addresses followed by the
+    /// source & destination ports (if present), as defined in the Microsoft RSS
+    /// specification. Use [`rss::SYMMETRIC_KEY`] to get the same hash value
+    /// for both directions of a flow.
+    pub fn toeplitz_hash(&self, key: &[u8]) -> u32 {
+        let mut input = [0u8;36];
+        let mut len = match self.addresses {
+            FlowAddresses::Ipv4{ source, destination } => {
+                input[..4].copy_from_slice(&source);
+                input[4..8].copy_from_slice(&destination);
+                8
+            },
+            FlowAddresses::Ipv6{ source, destination } => {
+                input[..16].copy_from_slice(&source);
+                input[16..32].copy_from_slice(&destination);
+                32
+            },
+        };
+        if let FlowTransportId::Ports{ source, destination } = self.transport {
+            input[len..len + 2].copy_from_slice(&source.to_be_bytes());
+            input[len + 2..len + 4].copy_from_slice(&destination.to_be_bytes());
+            len += 4;
+        }
+        rss::toeplitz_hash(key, &input[..len])
+    }
+}
+
+/// Constants & functions for calculating "receive side scaling" (RSS) hashes.
+pub mod rss {
+    /// Default RSS key from the Microsoft RSS specification (also used
+    /// as default by many network card drivers).
+    pub const MICROSOFT_KEY: [u8;40] = [
+        0x6d, 0x5a, 0x56, 0xda, 0x25, 0x5b, 0x0e, 0xc2,
+        0x41, 0x67, 0x25, 0x3d, 0x43, 0xa3, 0x8f, 0xb0,
+        0xd0, 0xca, 0x2b, 0xcb, 0xae, 0x7b, 0x30, 0xb4,
+        0x77, 0xcb, 0x2d, 0xa3, 0x80, 0x30, 0xf2, 0x0c,
+        0x6a, 0x42, 0xb7, 0x3b, 0xbe, 0xac, 0x01, 0xfa,
+    ];
+
+    /// RSS key that results in the same hash for both directions of a flow
+    /// (see "Scalable TCP Session Monitoring with Symmetric Receive-side Scaling").
+    pub const SYMMETRIC_KEY: [u8;40] = [
+        0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+        0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+        0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+        0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+        0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a, 0x6d, 0x5a,
+    ];
+
+    /// Calculates the Toeplitz hash of the input with the given key.
+    ///
+    /// The key should be at least 4 bytes longer then the input, missing
+    /// key bits are treated as zero.
+    pub fn toeplitz_hash(key: &[u8], input: &[u8]) -> u32 {
+        let key_bit = |index: usize| -> u32 {
+            match key.get(index / 8) {
+                Some(byte) => u32::from((byte >> (7 - (index % 8))) & 1),
+                None => 0,
+            }
+        };
+
+        // the window contains the 32 key bits starting at the current input bit
+        let mut window: u32 = (0..32).fold(0, |acc, i| (acc << 1) | key_bit(i));
+        let mut result = 0;
+        for (byte_index, byte) in input.iter().enumerate() {
+            for bit in 0..8 {
+                if 0 != byte & (0x80 >> bit) {
+                    result ^= window;
+                }
+                window = (window << 1) | key_bit(byte_index*8 + bit + 32);
+            }
+        }
+        result
+    }
+}
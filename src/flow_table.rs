@@ -0,0 +1,519 @@
+//! NetFlow like accounting of packets & bytes per flow.
+//!
+//! The [`FlowTable`] aggregates the packets of every unidirectional flow
+//! (identified via a [`FlowKey`]) and hands out [`FlowRecord`]s via a callback
+//! once a flow expires. The expired records can be encoded as IPFIX or
+//! NetFlow v9 messages with a [`FlowExporter`].
+//!
+//! All timestamps are passed in by the caller (e.g. the capture timestamps
+//! of a pcap file) as a [`Duration`] since the UNIX epoch.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use etherparse::SlicedPacket;
+//! use etherparse::flow_table::*;
+//!
+//! let mut table = FlowTable::default();
+//! let mut exporter = FlowExporter::new(FlowExportFormat::Ipfix, 1);
+//! let mut expired = Vec::new();
+//!
+//! # let packets: Vec<(Duration, Vec<u8>)> = Vec::new();
+//! for (timestamp, packet) in packets.iter() {
+//!     if let Ok(sliced) = SlicedPacket::from_ethernet(packet) {
+//!         table.process_sliced(*timestamp, &sliced, &mut |record| expired.push(record));
+//!     }
+//! }
+//! table.flush(&mut |record| expired.push(record));
+//!
+//! for message in exporter.encode(Duration::from_secs(1_600_000_000), &expired) {
+//!     // send the message to a collector (e.g. via udp)
+//! }
+//! ```
+use super::*;
+
+use std::collections::HashMap;
+use core::time::Duration;
+
+/// Reason why a flow record was exported (values match the IPFIX "flowEndReason").
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum FlowEndReason {
+    /// No packet was received for longer then the idle timeout.
+    IdleTimeout = 1,
+    /// The flow was active for longer then the active timeout (the flow
+    /// continues in a new record).
+    ActiveTimeout = 2,
+    /// A TCP FIN or RST was received.
+    EndOfFlowDetected = 3,
+    /// The flow table was flushed.
+    ForcedEnd = 4,
+    /// The flow was removed to make room for a new flow.
+    LackOfResources = 5,
+}
+
+/// Accumulated statistics of a unidirectional flow.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowRecord {
+    pub key: FlowKey,
+    /// Timestamp of the first packet.
+    pub first_seen: Duration,
+    /// Timestamp of the last packet.
+    pub last_seen: Duration,
+    /// Number of packets.
+    pub packets: u64,
+    /// Number of bytes (ip header, extensions & payload).
+    pub bytes: u64,
+    /// Union of the TCP flags of all packets (bit layout of the TCP header flags byte).
+    pub tcp_flags: u8,
+    /// Reason the record was exported (`None` while the flow is still active).
+    pub end_reason: Option<FlowEndReason>,
+}
+
+/// Timeouts & limits of a [`FlowTable`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowTableConfig {
+    /// Flows without packets for longer then this timeout get exported.
+    pub idle_timeout: Duration,
+    /// Flows active for longer then this timeout get exported (& continue in a new record).
+    pub active_timeout: Duration,
+    /// Maximum number of flows. When the limit is reached the least recently
+    /// seen flow is exported to make room for a new flow.
+    pub max_flows: usize,
+}
+
+impl Default for FlowTableConfig {
+    fn default() -> FlowTableConfig {
+        FlowTableConfig {
+            idle_timeout: Duration::from_secs(15),
+            active_timeout: Duration::from_secs(30 * 60),
+            max_flows: 64 * 1024,
+        }
+    }
+}
+
+/// Aggregates packets per flow (see the [module documentation](self)).
+#[derive(Clone, Debug, Default)]
+pub struct FlowTable {
+    config: FlowTableConfig,
+    flows: HashMap<FlowKey, FlowRecord>,
+}
+
+/// Returns the TCP flags byte (CWR, ECE, URG, ACK, PSH, RST, SYN & FIN).
+fn tcp_flags_u8(tcp: &TcpHeaderSlice) -> u8 {
+    [tcp.fin(), tcp.syn(), tcp.rst(), tcp.psh(), tcp.ack(), tcp.urg(), tcp.ece(), tcp.cwr()]
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, set)| if *set { acc | (1 << i) } else { acc })
+}
+
+impl FlowTable {
+
+    /// Creates a flow table with the given timeouts & limits.
+    pub fn new(config: FlowTableConfig) -> FlowTable {
+        FlowTable {
+            config,
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Returns the configuration of the flow table.
+    pub fn config(&self) -> &FlowTableConfig {
+        &self.config
+    }
+
+    /// Returns the number of active flows.
+    pub fn len(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Returns true if there are no active flows.
+    pub fn is_empty(&self) -> bool {
+        self.flows.is_empty()
+    }
+
+    /// Returns the record of an active flow.
+    pub fn get(&self, key: &FlowKey) -> Option<&FlowRecord> {
+        self.flows.get(key)
+    }
+
+    /// Returns an iterator over the records of all active flows.
+    pub fn iter(&self) -> impl Iterator<Item = &FlowRecord> {
+        self.flows.values()
+    }
+
+    /// Adds a sliced packet to its flow. Returns false if the packet
+    /// contains no ip header and was therefore ignored.
+    pub fn process_sliced<F>(&mut self, timestamp: Duration, packet: &SlicedPacket, expired: &mut F) -> bool
+    where F: FnMut(FlowRecord)
+    {
+        let key = match FlowKey::from_sliced(packet) {
+            Some(key) => key,
+            None => return false,
+        };
+        let bytes = match &packet.ip {
+            Some(InternetSlice::Ipv4(header, _)) => u64::from(header.total_len()),
+            Some(InternetSlice::Ipv6(header, _)) => {
+                Ipv6Header::SERIALIZED_SIZE as u64 + u64::from(header.payload_length())
+            },
+            None => 0,
+        };
+        let tcp_flags = match &packet.transport {
+            Some(TransportSlice::Tcp(tcp)) => tcp_flags_u8(tcp),
+            _ => 0,
+        };
+        self.add_packet(timestamp, key, bytes, tcp_flags, expired);
+        true
+    }
+
+    /// Adds a packet with the given size (ip header & payload) & TCP flags
+    /// to the flow with the given key.
+    pub fn add_packet<F>(&mut self, timestamp: Duration, key: FlowKey, bytes: u64, tcp_flags: u8, expired: &mut F)
+    where F: FnMut(FlowRecord)
+    {
+        // check if the existing flow expired
+        if let Some(record) = self.flows.get(&key) {
+            if let Some(reason) = self.expire_reason(record, timestamp) {
+                let mut record = self.flows.remove(&key).unwrap();
+                record.end_reason = Some(reason);
+                expired(record);
+            }
+        }
+
+        // make room for a new flow
+        if !self.flows.contains_key(&key) && self.flows.len() >= self.config.max_flows {
+            let oldest = self.flows
+                .values()
+                .min_by_key(|record| record.last_seen)
+                .map(|record| record.key);
+            match oldest {
+                Some(oldest) => {
+                    let mut record = self.flows.remove(&oldest).unwrap();
+                    record.end_reason = Some(FlowEndReason::LackOfResources);
+                    expired(record);
+                },
+                // no flows can be stored
+                None => return,
+            }
+        }
+
+        let record = self.flows.entry(key).or_insert(FlowRecord{
+            key,
+            first_seen: timestamp,
+            last_seen: timestamp,
+            packets: 0,
+            bytes: 0,
+            tcp_flags: 0,
+            end_reason: None,
+        });
+        record.last_seen = record.last_seen.max(timestamp);
+        record.packets += 1;
+        record.bytes += bytes;
+        record.tcp_flags |= tcp_flags;
+
+        // fin or rst end the flow
+        const FIN_RST: u8 = 0b0000_0101;
+        if key.protocol == ip_number::TCP && 0 != tcp_flags & FIN_RST {
+            let mut record = self.flows.remove(&key).unwrap();
+            record.end_reason = Some(FlowEndReason::EndOfFlowDetected);
+            expired(record);
+        }
+    }
+
+    /// Returns the reason if the flow is expired at the given time.
+    fn expire_reason(&self, record: &FlowRecord, now: Duration) -> Option<FlowEndReason> {
+        if now.checked_sub(record.last_seen).unwrap_or_default() > self.config.idle_timeout {
+            Some(FlowEndReason::IdleTimeout)
+        } else if now.checked_sub(record.first_seen).unwrap_or_default() > self.config.active_timeout {
+            Some(FlowEndReason::ActiveTimeout)
+        } else {
+            None
+        }
+    }
+
+    /// Exports all flows that exceeded the idle or active timeout at the given time.
+    pub fn expire<F>(&mut self, now: Duration, expired: &mut F)
+    where F: FnMut(FlowRecord)
+    {
+        let keys: Vec<FlowKey> = self.flows
+            .values()
+            .filter(|record| self.expire_reason(record, now).is_some())
+            .map(|record| record.key)
+            .collect();
+        for key in keys {
+            let mut record = self.flows.remove(&key).unwrap();
+            record.end_reason = self.expire_reason(&record, now);
+            expired(record);
+        }
+    }
+
+    /// Exports all flows.
+    pub fn flush<F>(&mut self, expired: &mut F)
+    where F: FnMut(FlowRecord)
+    {
+        for (_, mut record) in self.flows.drain() {
+            record.end_reason = Some(FlowEndReason::ForcedEnd);
+            expired(record);
+        }
+    }
+}
+
+/// Format of the messages generated by a [`FlowExporter`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum FlowExportFormat {
+    /// IPFIX (RFC 7011).
+    Ipfix,
+    /// Cisco NetFlow version 9 (RFC 3954).
+    NetflowV9,
+}
+
+/// Constants used in the flow export messages.
+pub mod flow_export {
+    /// Template id used for the records of IPv4 flows.
+    pub const TEMPLATE_ID_IPV4: u16 = 256;
+    /// Template id used for the records of IPv6 flows.
+    pub const TEMPLATE_ID_IPV6: u16 = 257;
+
+    /// Information element "octetDeltaCount".
+    pub const IE_OCTET_DELTA_COUNT: u16 = 1;
+    /// Information element "packetDeltaCount".
+    pub const IE_PACKET_DELTA_COUNT: u16 = 2;
+    /// Information element "protocolIdentifier".
+    pub const IE_PROTOCOL_IDENTIFIER: u16 = 4;
+    /// Information element "tcpControlBits".
+    pub const IE_TCP_CONTROL_BITS: u16 = 6;
+    /// Information element "sourceTransportPort".
+    pub const IE_SOURCE_TRANSPORT_PORT: u16 = 7;
+    /// Information element "sourceIPv4Address".
+    pub const IE_SOURCE_IPV4_ADDRESS: u16 = 8;
+    /// Information element "destinationTransportPort".
+    pub const IE_DESTINATION_TRANSPORT_PORT: u16 = 11;
+    /// Information element "destinationIPv4Address".
+    pub const IE_DESTINATION_IPV4_ADDRESS: u16 = 12;
+    /// Information element "flowEndSysUpTime" (NetFlow v9 "LAST_SWITCHED").
+    pub const IE_FLOW_END_SYS_UP_TIME: u16 = 21;
+    /// Information element "flowStartSysUpTime" (NetFlow v9 "FIRST_SWITCHED").
+    pub const IE_FLOW_START_SYS_UP_TIME: u16 = 22;
+    /// Information element "sourceIPv6Address".
+    pub const IE_SOURCE_IPV6_ADDRESS: u16 = 27;
+    /// Information element "destinationIPv6Address".
+    pub const IE_DESTINATION_IPV6_ADDRESS: u16 = 28;
+    /// Information element "flowEndReason".
+    pub const IE_FLOW_END_REASON: u16 = 136;
+    /// Information element "flowStartMilliseconds".
+    pub const IE_FLOW_START_MILLISECONDS: u16 = 152;
+    /// Information element "flowEndMilliseconds".
+    pub const IE_FLOW_END_MILLISECONDS: u16 = 153;
+}
+
+/// Encodes flow records as IPFIX or NetFlow v9 messages.
+///
+/// Every message contains the templates of the records it contains, so
+/// each message can be decoded on its own (e.g. when sent via udp).
+///
+/// Ports are only filled for flows with ports (ICMP echo identifiers are
+/// not exported). IPFIX records contain absolute timestamps in milliseconds
+/// and the end reason. NetFlow v9 records contain the start & end time
+/// relative to the `system_init_time` (system uptime).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowExporter {
+    format: FlowExportFormat,
+    /// Observation domain id (IPFIX) or source id (NetFlow v9).
+    pub observation_domain_id: u32,
+    /// Maximum number of flow records per message.
+    pub max_records_per_message: usize,
+    /// Time the NetFlow v9 system uptime is relative to.
+    pub system_init_time: Duration,
+    /// Number of records (IPFIX) or messages (NetFlow v9) sent.
+    sequence_number: u32,
+}
+
+impl FlowExporter {
+
+    /// Creates an exporter for the given format.
+    pub fn new(format: FlowExportFormat, observation_domain_id: u32) -> FlowExporter {
+        FlowExporter {
+            format,
+            observation_domain_id,
+            max_records_per_message: 16,
+            system_init_time: Duration::from_secs(0),
+            sequence_number: 0,
+        }
+    }
+
+    /// Returns the format of the generated messages.
+    pub fn format(&self) -> FlowExportFormat {
+        self.format
+    }
+
+    /// Returns the current sequence number (number of exported records
+    /// for IPFIX, number of exported messages for NetFlow v9).
+    pub fn sequence_number(&self) -> u32 {
+        self.sequence_number
+    }
+
+    /// Returns the fields (information element id & length) of the template
+    /// for ipv4 or ipv6 records.
+    fn template_fields(&self, ipv6: bool) -> Vec<(u16, u16)> {
+        use flow_export::*;
+        let (source, destination, address_len) = if ipv6 {
+            (IE_SOURCE_IPV6_ADDRESS, IE_DESTINATION_IPV6_ADDRESS, 16)
+        } else {
+            (IE_SOURCE_IPV4_ADDRESS, IE_DESTINATION_IPV4_ADDRESS, 4)
+        };
+        let mut result = vec![
+            (source, address_len),
+            (destination, address_len),
+            (IE_PROTOCOL_IDENTIFIER, 1),
+            (IE_SOURCE_TRANSPORT_PORT, 2),
+            (IE_DESTINATION_TRANSPORT_PORT, 2),
+            (IE_PACKET_DELTA_COUNT, 8),
+            (IE_OCTET_DELTA_COUNT, 8),
+            (IE_TCP_CONTROL_BITS, 1),
+        ];
+        match self.format {
+            FlowExportFormat::Ipfix => result.extend_from_slice(&[
+                (IE_FLOW_START_MILLISECONDS, 8),
+                (IE_FLOW_END_MILLISECONDS, 8),
+                (IE_FLOW_END_REASON, 1),
+            ]),
+            // NetFlow v9 has no end reason
+            FlowExportFormat::NetflowV9 => result.extend_from_slice(&[
+                (IE_FLOW_START_SYS_UP_TIME, 4),
+                (IE_FLOW_END_SYS_UP_TIME, 4),
+            ]),
+        }
+        result
+    }
+
+    /// Encodes a single record (without set header).
+    fn write_record(&self, record: &FlowRecord, target: &mut Vec<u8>) {
+        match record.key.addresses {
+            FlowAddresses::Ipv4{ source, destination } => {
+                target.extend_from_slice(&source);
+                target.extend_from_slice(&destination);
+            },
+            FlowAddresses::Ipv6{ source, destination } => {
+                target.extend_from_slice(&source);
+                target.extend_from_slice(&destination);
+            },
+        }
+        target.push(record.key.protocol);
+        let (source_port, destination_port) = match record.key.transport {
+            FlowTransportId::Ports{ source, destination } => (source, destination),
+            _ => (0, 0),
+        };
+        target.extend_from_slice(&source_port.to_be_bytes());
+        target.extend_from_slice(&destination_port.to_be_bytes());
+        target.extend_from_slice(&record.packets.to_be_bytes());
+        target.extend_from_slice(&record.bytes.to_be_bytes());
+        target.push(record.tcp_flags);
+        match self.format {
+            FlowExportFormat::Ipfix => {
+                target.extend_from_slice(&(record.first_seen.as_millis() as u64).to_be_bytes());
+                target.extend_from_slice(&(record.last_seen.as_millis() as u64).to_be_bytes());
+                target.push(record.end_reason.map(|r| r as u8).unwrap_or(0));
+            },
+            FlowExportFormat::NetflowV9 => {
+                let uptime = |t: Duration| t.checked_sub(self.system_init_time).unwrap_or_default().as_millis() as u32;
+                target.extend_from_slice(&uptime(record.first_seen).to_be_bytes());
+                target.extend_from_slice(&uptime(record.last_seen).to_be_bytes());
+            },
+        }
+    }
+
+    /// Encodes the records into one or more messages (each containing at
+    /// most `max_records_per_message` records). `export_time` is the current
+    /// time since the UNIX epoch.
+    pub fn encode(&mut self, export_time: Duration, records: &[FlowRecord]) -> Vec<Vec<u8>> {
+        records
+            .chunks(self.max_records_per_message.max(1))
+            .map(|chunk| self.encode_message(export_time, chunk))
+            .collect()
+    }
+
+    /// Encodes a single message containing all given records.
+    fn encode_message(&mut self, export_time: Duration, records: &[FlowRecord]) -> Vec<u8> {
+        let is_v6 = |r: &&FlowRecord| matches!(r.key.addresses, FlowAddresses::Ipv6{ .. });
+        let v4: Vec<&FlowRecord> = records.iter().filter(|r| !is_v6(r)).collect();
+        let v6: Vec<&FlowRecord> = records.iter().filter(is_v6).collect();
+        let (template_set_id, pad) = match self.format {
+            FlowExportFormat::Ipfix => (2u16, false),
+            FlowExportFormat::NetflowV9 => (0u16, true),
+        };
+
+        // sets (templates & data)
+        let mut body = Vec::new();
+        let mut set_count = 0u16;
+        let write_set = |body: &mut Vec<u8>, set_id: u16, content: &[u8]| {
+            let padding = if pad { (4 - content.len() % 4) % 4 } else { 0 };
+            body.extend_from_slice(&set_id.to_be_bytes());
+            body.extend_from_slice(&((4 + content.len() + padding) as u16).to_be_bytes());
+            body.extend_from_slice(content);
+            body.resize(body.len() + padding, 0);
+        };
+
+        // templates
+        {
+            let mut content = Vec::new();
+            for (records, ipv6, template_id) in [
+                (&v4, false, flow_export::TEMPLATE_ID_IPV4),
+                (&v6, true, flow_export::TEMPLATE_ID_IPV6),
+            ] {
+                if !records.is_empty() {
+                    let fields = self.template_fields(ipv6);
+                    content.extend_from_slice(&template_id.to_be_bytes());
+                    content.extend_from_slice(&(fields.len() as u16).to_be_bytes());
+                    for (id, len) in fields.iter() {
+                        content.extend_from_slice(&id.to_be_bytes());
+                        content.extend_from_slice(&len.to_be_bytes());
+                    }
+                    set_count += 1;
+                }
+            }
+            if !content.is_empty() {
+                write_set(&mut body, template_set_id, &content);
+            }
+        }
+
+        // data
+        for (records, template_id) in [
+            (&v4, flow_export::TEMPLATE_ID_IPV4),
+            (&v6, flow_export::TEMPLATE_ID_IPV6),
+        ] {
+            if !records.is_empty() {
+                let mut content = Vec::new();
+                for record in records.iter() {
+                    self.write_record(record, &mut content);
+                }
+                write_set(&mut body, template_id, &content);
+                set_count += records.len() as u16;
+            }
+        }
+
+        // header
+        let mut message = Vec::with_capacity(20 + body.len());
+        match self.format {
+            FlowExportFormat::Ipfix => {
+                message.extend_from_slice(&10u16.to_be_bytes());
+                message.extend_from_slice(&((16 + body.len()) as u16).to_be_bytes());
+                message.extend_from_slice(&(export_time.as_secs() as u32).to_be_bytes());
+                message.extend_from_slice(&self.sequence_number.to_be_bytes());
+                message.extend_from_slice(&self.observation_domain_id.to_be_bytes());
+                self.sequence_number = self.sequence_number.wrapping_add(records.len() as u32);
+            },
+            FlowExportFormat::NetflowV9 => {
+                let uptime = export_time.checked_sub(self.system_init_time).unwrap_or_default();
+                message.extend_from_slice(&9u16.to_be_bytes());
+                message.extend_from_slice(&set_count.to_be_bytes());
+                message.extend_from_slice(&(uptime.as_millis() as u32).to_be_bytes());
+                message.extend_from_slice(&(export_time.as_secs() as u32).to_be_bytes());
+                message.extend_from_slice(&self.sequence_number.to_be_bytes());
+                message.extend_from_slice(&self.observation_domain_id.to_be_bytes());
+                self.sequence_number = self.sequence_number.wrapping_add(1);
+            },
+        }
+        message.extend_from_slice(&body);
+        message
+    }
+}
@@ -0,0 +1,427 @@
+use super::*;
+
+use crate::icmp_extension::MplsLabelStackEntry;
+use core::ops::Range;
+
+/// Errors that can occur when pushing or popping headers of an Ethernet II frame
+/// via an [`EditableFrame`].
+#[derive(Debug)]
+pub enum FrameEditError {
+    /// Error when reading the headers in front of the position at which a
+    /// header gets pushed or popped (e.g. because the frame is too short).
+    Read(ReadError),
+    /// Error when writing a header (e.g. not enough headroom or a value
+    /// of the pushed header is out of range).
+    Write(WriteError),
+    /// The ether type at the position of the operation does not match the
+    /// header type that should be popped (e.g. popping a vlan tag from an
+    /// untagged frame) or is not supported by the pushed header.
+    UnexpectedEtherType(u16),
+    /// The ip number following the ip header is not supported when popping
+    /// a tunnel (only IPv4, IPv6 & GRE are supported).
+    UnsupportedIpNumber(u8),
+}
+
+#[cfg(feature = "std")]
+impl Error for FrameEditError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FrameEditError::Read(ref err) => Some(err),
+            FrameEditError::Write(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for FrameEditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use FrameEditError::*;
+        match self {
+            Read(err) => write!(f, "FrameEditError: Failed to read the frame headers: {}", err),
+            Write(err) => write!(f, "FrameEditError: Failed to write the pushed header: {}", err),
+            UnexpectedEtherType(ether_type) => {
+                write!(f, "FrameEditError: Unexpected ether type 0x{:04x} at the position of the operation.", ether_type)
+            },
+            UnsupportedIpNumber(ip_number) => {
+                write!(f, "FrameEditError: Unsupported ip number {} after the tunnel ip header (only IPv4, IPv6 & GRE are supported).", ip_number)
+            },
+        }
+    }
+}
+
+impl From<ReadError> for FrameEditError {
+    fn from(err: ReadError) -> FrameEditError {
+        FrameEditError::Read(err)
+    }
+}
+
+impl From<WriteError> for FrameEditError {
+    fn from(err: WriteError) -> FrameEditError {
+        FrameEditError::Write(err)
+    }
+}
+
+impl From<ValueError> for FrameEditError {
+    fn from(err: ValueError) -> FrameEditError {
+        FrameEditError::Write(WriteError::ValueError(err))
+    }
+}
+
+/// Ethernet II frame located in a buffer with free space (headroom) in front of it.
+///
+/// Pushing a header moves the headers in front of the insert position into
+/// the headroom, popping a header moves them back. The rest of the frame
+/// (e.g. the payload) is never moved.
+#[derive(Debug, Eq, PartialEq)]
+pub struct HeadroomFrame<'a> {
+    buffer: &'a mut [u8],
+    range: Range<usize>,
+}
+
+impl<'a> HeadroomFrame<'a> {
+    /// Creates a frame located at the given range in the buffer (everything
+    /// in front of the range is used as headroom).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not inside of the buffer.
+    pub fn new(buffer: &'a mut [u8], range: Range<usize>) -> HeadroomFrame<'a> {
+        assert!(range.start <= range.end && range.end <= buffer.len());
+        HeadroomFrame { buffer, range }
+    }
+
+    /// Range of the frame in the buffer.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Number of free bytes in front of the frame.
+    pub fn headroom(&self) -> usize {
+        self.range.start
+    }
+}
+
+/// Push & pop operations for vlan tags, MPLS labels & tunnel headers on a
+/// buffer containing an Ethernet II frame.
+///
+/// The operations are implemented for a `Vec<u8>` (containing only the frame)
+/// and for a [`HeadroomFrame`]. Other buffer types can be supported by
+/// implementing [`EditableFrame::frame`], [`EditableFrame::frame_mut`],
+/// [`EditableFrame::insert_bytes`] & [`EditableFrame::remove_bytes`].
+///
+/// All operations patch the ether type in front of the pushed or popped header.
+/// If an error is returned the frame is not modified.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// let mut frame = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut frame, &[1,2,3,4])
+///     .unwrap();
+///
+/// // add a vlan tag
+/// frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &SingleVlanHeader {
+///     priority_code_point: 0,
+///     drop_eligible_indicator: false,
+///     vlan_identifier: 123,
+///     ether_type: 0, // set by push_vlan
+/// }).unwrap();
+///
+/// let headers = PacketHeaders::from_ethernet_slice(&frame).unwrap();
+/// assert_eq!(ether_type::VLAN_TAGGED_FRAME, headers.link.unwrap().ether_type);
+/// match headers.vlan.unwrap() {
+///     VlanHeader::Single(vlan) => assert_eq!(ether_type::IPV4, vlan.ether_type),
+///     _ => unreachable!(),
+/// }
+///
+/// // and remove it again
+/// let (tpid, vlan) = frame.pop_vlan().unwrap();
+/// assert_eq!(ether_type::VLAN_TAGGED_FRAME, tpid);
+/// assert_eq!(123, vlan.vlan_identifier);
+/// assert_eq!(ether_type::IPV4, Ethernet2Header::from_slice(&frame).unwrap().0.ether_type);
+/// ```
+pub trait EditableFrame {
+    /// Returns the bytes of the frame.
+    fn frame(&self) -> &[u8];
+
+    /// Returns the bytes of the frame as mutable slice.
+    fn frame_mut(&mut self) -> &mut [u8];
+
+    /// Inserts the given bytes at the given offset of the frame.
+    ///
+    /// If the bytes can not be inserted (e.g. because there is not enough
+    /// headroom) an error is returned & the frame is not modified.
+    fn insert_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<(), WriteError>;
+
+    /// Removes the bytes in the given range of the frame.
+    fn remove_bytes(&mut self, range: Range<usize>);
+
+    /// Pushes a vlan tag as outermost tag directly after the Ethernet II header.
+    ///
+    /// The given tag protocol identifier (e.g. [`ether_type::VLAN_TAGGED_FRAME`]
+    /// for IEEE 802.1Q or [`ether_type::PROVIDER_BRIDGING`] for IEEE 802.1ad)
+    /// is set as ether type of the Ethernet II header. The `ether_type` of the
+    /// given vlan header is replaced by the previous ether type of the
+    /// Ethernet II header.
+    fn push_vlan(&mut self, tpid: u16, vlan: &SingleVlanHeader) -> Result<(), FrameEditError> {
+        if !VlanHeader::VLAN_ETHER_TYPES.contains(&tpid) {
+            return Err(FrameEditError::UnexpectedEtherType(tpid));
+        }
+        let (ethernet, _) = Ethernet2Header::from_slice(self.frame())?;
+        let bytes = SingleVlanHeader {
+            ether_type: ethernet.ether_type,
+            ..vlan.clone()
+        }.to_bytes()?;
+
+        self.insert_bytes(Ethernet2Header::SERIALIZED_SIZE, &bytes)?;
+        set_ether_type(self.frame_mut(), ETHER_TYPE_OFFSET, tpid);
+        Ok(())
+    }
+
+    /// Removes the outermost vlan tag & returns its tag protocol identifier and
+    /// header.
+    ///
+    /// The ether type of the removed tag is set as ether type of the
+    /// Ethernet II header.
+    fn pop_vlan(&mut self) -> Result<(u16, SingleVlanHeader), FrameEditError> {
+        let (ethernet, rest) = Ethernet2Header::from_slice(self.frame())?;
+        if !VlanHeader::VLAN_ETHER_TYPES.contains(&ethernet.ether_type) {
+            return Err(FrameEditError::UnexpectedEtherType(ethernet.ether_type));
+        }
+        if rest.len() < SingleVlanHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(
+                Ethernet2Header::SERIALIZED_SIZE + SingleVlanHeader::SERIALIZED_SIZE
+            ).into());
+        }
+        let vlan = SingleVlanHeader::from_bytes([rest[0], rest[1], rest[2], rest[3]]);
+
+        set_ether_type(self.frame_mut(), ETHER_TYPE_OFFSET, vlan.ether_type);
+        self.remove_bytes(
+            Ethernet2Header::SERIALIZED_SIZE..Ethernet2Header::SERIALIZED_SIZE + SingleVlanHeader::SERIALIZED_SIZE
+        );
+        Ok((ethernet.ether_type, vlan))
+    }
+
+    /// Pushes a MPLS label stack entry after the Ethernet II header & vlan tags
+    /// (on top of the label stack if one is already present).
+    ///
+    /// The `bottom_of_stack` flag of the entry is set based on whether a label
+    /// stack is already present. If no label stack is present the preceding
+    /// ether type is set to [`ether_type::MPLS_UNICAST`]. Bits of the `label`
+    /// & `traffic_class` fields outside of their range are ignored.
+    fn push_mpls(&mut self, entry: MplsLabelStackEntry) -> Result<(), FrameEditError> {
+        let (ether_type_offset, ether_type) = network_ether_type(self.frame())?;
+        let is_mpls = is_mpls_ether_type(ether_type);
+        let bytes = MplsLabelStackEntry {
+            bottom_of_stack: !is_mpls,
+            ..entry
+        }.to_bytes();
+
+        self.insert_bytes(ether_type_offset + 2, &bytes)?;
+        if !is_mpls {
+            set_ether_type(self.frame_mut(), ether_type_offset, ether_type::MPLS_UNICAST);
+        }
+        Ok(())
+    }
+
+    /// Removes the top entry of the MPLS label stack located after the Ethernet II
+    /// header & vlan tags and returns it.
+    ///
+    /// As the MPLS label stack does not identify the type of the payload, the
+    /// preceding ether type is set to the given `payload_ether_type` if the
+    /// removed entry was the bottom of the stack.
+    fn pop_mpls(&mut self, payload_ether_type: u16) -> Result<MplsLabelStackEntry, FrameEditError> {
+        let (ether_type_offset, ether_type) = network_ether_type(self.frame())?;
+        if !is_mpls_ether_type(ether_type) {
+            return Err(FrameEditError::UnexpectedEtherType(ether_type));
+        }
+        let start = ether_type_offset + 2;
+        let end = start + MplsLabelStackEntry::SERIALIZED_SIZE;
+        let frame = self.frame();
+        if frame.len() < end {
+            return Err(ReadError::UnexpectedEndOfSlice(end).into());
+        }
+        let entry = MplsLabelStackEntry::from_bytes([
+            frame[start], frame[start + 1], frame[start + 2], frame[start + 3]
+        ]);
+
+        if entry.bottom_of_stack {
+            set_ether_type(self.frame_mut(), ether_type_offset, payload_ether_type);
+        }
+        self.remove_bytes(start..end);
+        Ok(entry)
+    }
+
+    /// Pushes an ip header (optionally followed by a tunnel header) after the
+    /// Ethernet II header & vlan tags, encapsulating the rest of the frame.
+    ///
+    /// The same fields as in [`Encapsulation::prepend`] are set. Additionally
+    /// the `protocol_type` of a GRE tunnel header is set to the ether type of
+    /// the encapsulated data and without a tunnel header (IP in IP) the last
+    /// next header field is set to [`ip_number::IPV4`] or [`ip_number::IPV6`]
+    /// (other encapsulated ether types are rejected).
+    fn push_tunnel(&mut self, ip: IpHeader, tunnel: Option<TunnelHeader>) -> Result<(), FrameEditError> {
+        let (ether_type_offset, ether_type) = network_ether_type(self.frame())?;
+        let payload = &self.frame()[ether_type_offset + 2..];
+
+        let mut headers = Encapsulation {
+            ethernet2: None,
+            vlan: None,
+            ip: Some(ip),
+            tunnel,
+        };
+        match headers.tunnel.as_mut() {
+            Some(TunnelHeader::Gre(gre)) => gre.protocol_type = ether_type,
+            Some(TunnelHeader::Udp(_)) => {},
+            None => {
+                let next_header = match ether_type {
+                    ether_type::IPV4 => ip_number::IPV4,
+                    ether_type::IPV6 => ip_number::IPV6,
+                    value => return Err(FrameEditError::UnexpectedEtherType(value)),
+                };
+                if let Some(ip) = headers.ip.as_mut() {
+                    ip.set_next_headers(next_header);
+                }
+            },
+        }
+        headers.set_payload_dependent_fields(payload)?;
+
+        // serialize the headers before modifying the frame
+        let mut bytes = vec![0u8;headers.header_len()];
+        {
+            let mut slice = &mut bytes[..];
+            if let Some(header) = &headers.ip {
+                slice = header.write_to_slice(slice)?;
+            }
+            if let Some(header) = &headers.tunnel {
+                header.write_to_slice(slice)?;
+            }
+        }
+        let outer_ether_type = match headers.ip {
+            Some(IpHeader::Version6(_, _)) => ether_type::IPV6,
+            _ => ether_type::IPV4,
+        };
+
+        self.insert_bytes(ether_type_offset + 2, &bytes)?;
+        set_ether_type(self.frame_mut(), ether_type_offset, outer_ether_type);
+        Ok(())
+    }
+
+    /// Removes the ip header (including extension headers) after the Ethernet II
+    /// header & vlan tags and, if present, the following GRE header
+    /// (decapsulation of IP in IP & GRE tunnels).
+    ///
+    /// The preceding ether type is set to the ether type of the decapsulated data
+    /// (based on the ip number of the removed ip header or the `protocol_type`
+    /// of the removed GRE header).
+    fn pop_tunnel(&mut self) -> Result<(IpHeader, Option<TunnelHeader>), FrameEditError> {
+        let (ether_type_offset, ether_type) = network_ether_type(self.frame())?;
+        if ether_type != ether_type::IPV4 && ether_type != ether_type::IPV6 {
+            return Err(FrameEditError::UnexpectedEtherType(ether_type));
+        }
+        let start = ether_type_offset + 2;
+        let (ip, next_header, rest) = IpHeader::from_slice(&self.frame()[start..])
+            .map_err(|err| err.add_slice_offset(start))?;
+        let (tunnel, payload_ether_type) = match next_header {
+            ip_number::IPV4 => (None, ether_type::IPV4),
+            ip_number::IPV6 => (None, ether_type::IPV6),
+            ip_number::GRE => {
+                let (gre, _) = GreHeader::from_slice(rest)
+                    .map_err(|err| err.add_slice_offset(start + ip.header_len()))?;
+                let protocol_type = gre.protocol_type;
+                (Some(TunnelHeader::Gre(gre)), protocol_type)
+            },
+            value => return Err(FrameEditError::UnsupportedIpNumber(value)),
+        };
+
+        let end = start + ip.header_len() + tunnel.as_ref().map(|h| h.header_len()).unwrap_or(0);
+        set_ether_type(self.frame_mut(), ether_type_offset, payload_ether_type);
+        self.remove_bytes(start..end);
+        Ok((ip, tunnel))
+    }
+}
+
+impl EditableFrame for Vec<u8> {
+    fn frame(&self) -> &[u8] {
+        self
+    }
+
+    fn frame_mut(&mut self) -> &mut [u8] {
+        self
+    }
+
+    fn insert_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<(), WriteError> {
+        self.splice(offset..offset, bytes.iter().cloned());
+        Ok(())
+    }
+
+    fn remove_bytes(&mut self, range: Range<usize>) {
+        self.drain(range);
+    }
+}
+
+impl<'a> EditableFrame for HeadroomFrame<'a> {
+    fn frame(&self) -> &[u8] {
+        &self.buffer[self.range.clone()]
+    }
+
+    fn frame_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.range.clone()]
+    }
+
+    fn insert_bytes(&mut self, offset: usize, bytes: &[u8]) -> Result<(), WriteError> {
+        if self.range.start < bytes.len() {
+            return Err(WriteError::SliceTooSmall(bytes.len()));
+        }
+        // move the data in front of the offset into the headroom
+        let start = self.range.start;
+        let new_start = start - bytes.len();
+        self.buffer.copy_within(start..start + offset, new_start);
+        self.buffer[new_start + offset..start + offset].copy_from_slice(bytes);
+        self.range.start = new_start;
+        Ok(())
+    }
+
+    fn remove_bytes(&mut self, range: Range<usize>) {
+        // move the data in front of the range over the removed bytes
+        let start = self.range.start;
+        let new_start = start + range.len();
+        self.buffer.copy_within(start..start + range.start, new_start);
+        self.range.start = new_start;
+    }
+}
+
+/// Offset of the ether type field in the Ethernet II header.
+const ETHER_TYPE_OFFSET: usize = 12;
+
+/// Returns the offset & value of the ether type field that identifies the
+/// data following the Ethernet II header & all vlan tags.
+fn network_ether_type(frame: &[u8]) -> Result<(usize, u16), ReadError> {
+    if frame.len() < Ethernet2Header::SERIALIZED_SIZE {
+        return Err(ReadError::UnexpectedEndOfSlice(Ethernet2Header::SERIALIZED_SIZE));
+    }
+    let mut offset = ETHER_TYPE_OFFSET;
+    let mut ether_type = u16::from_be_bytes([frame[offset], frame[offset + 1]]);
+    while VlanHeader::VLAN_ETHER_TYPES.contains(&ether_type) {
+        // the ether type of a vlan tag is located at the end of the tag
+        offset += SingleVlanHeader::SERIALIZED_SIZE;
+        if frame.len() < offset + 2 {
+            return Err(ReadError::UnexpectedEndOfSlice(offset + 2));
+        }
+        ether_type = u16::from_be_bytes([frame[offset], frame[offset + 1]]);
+    }
+    Ok((offset, ether_type))
+}
+
+fn is_mpls_ether_type(ether_type: u16) -> bool {
+    ether_type == ether_type::MPLS_UNICAST || ether_type == ether_type::MPLS_MULTICAST
+}
+
+fn set_ether_type(frame: &mut [u8], offset: usize, ether_type: u16) {
+    frame[offset..offset + 2].copy_from_slice(&ether_type.to_be_bytes());
+}
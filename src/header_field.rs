@@ -0,0 +1,210 @@
+use super::*;
+
+use core::ops::Range;
+
+/// Name, position & size of a field in a serialized header.
+///
+/// Header slices expose a table of their fields via an associated `FIELDS`
+/// constant (e.g. [`crate::Ipv4HeaderSlice::FIELDS`]). Bits are counted in network
+/// order, starting with the most significant bit of the first byte of the
+/// header as bit 0. Variable length parts of a header (e.g. options) are not
+/// part of the tables.
+///
+/// ```
+/// use etherparse::Ipv4HeaderSlice;
+///
+/// let ttl = Ipv4HeaderSlice::FIELDS.iter().find(|field| field.name == "ttl").unwrap();
+/// assert_eq!(64, ttl.bit_offset);
+/// assert_eq!(8, ttl.bit_width);
+/// assert_eq!(8..9, ttl.byte_range());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct HeaderField {
+    /// Name of the field (e.g. "ttl").
+    pub name: &'static str,
+    /// Offset of the first bit of the field from the start of the header.
+    pub bit_offset: usize,
+    /// Number of bits of the field (at most 128).
+    pub bit_width: usize,
+}
+
+impl HeaderField {
+    /// Range of the bytes containing (at least partially) the field.
+    #[inline]
+    pub fn byte_range(&self) -> Range<usize> {
+        (self.bit_offset / 8)..(self.bit_offset + self.bit_width).div_ceil(8)
+    }
+
+    /// Reads the value of the field from the given header bytes. Returns `None`
+    /// if the slice is too short to contain the field.
+    pub fn read(&self, header: &[u8]) -> Option<u128> {
+        if header.len() < self.byte_range().end {
+            return None;
+        }
+        let mut value = 0u128;
+        for bit in self.bit_offset..self.bit_offset + self.bit_width {
+            let byte = header[bit / 8];
+            value = (value << 1) | u128::from((byte >> (7 - bit % 8)) & 1);
+        }
+        Some(value)
+    }
+
+    /// Writes the value of the field to the given header bytes (the bits of
+    /// the other fields are not modified).
+    pub fn write(&self, header: &mut [u8], value: u128) -> Result<(), ValueError> {
+        use crate::ValueError::*;
+        if self.bit_width < 128 && value >> self.bit_width != 0 {
+            return Err(HeaderFieldValueTooLarge{
+                name: self.name,
+                value,
+                bit_width: self.bit_width,
+            });
+        }
+        let required_len = self.byte_range().end;
+        if header.len() < required_len {
+            return Err(HeaderFieldOutOfSlice{
+                name: self.name,
+                required_len,
+                actual_len: header.len(),
+            });
+        }
+        for (index, bit) in (self.bit_offset..self.bit_offset + self.bit_width).enumerate() {
+            let mask = 1u8 << (7 - bit % 8);
+            if 0 != (value >> (self.bit_width - 1 - index)) & 1 {
+                header[bit / 8] |= mask;
+            } else {
+                header[bit / 8] &= !mask;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Access to the fields of a header slice by the names in its field table.
+///
+/// As header slices are read only, fields are written via
+/// [`HeaderFieldAccess::set_field`] directly into the serialized bytes.
+///
+/// ```
+/// use etherparse::{HeaderFieldAccess, Ipv4HeaderSlice, PacketBuilder, SlicedPacket, InternetSlice};
+///
+/// let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234);
+/// let mut packet = Vec::with_capacity(builder.size(0));
+/// builder.write(&mut packet, &[]).unwrap();
+///
+/// // modify the ttl in the serialized packet
+/// Ipv4HeaderSlice::set_field(&mut packet, "ttl", 5).unwrap();
+///
+/// let header = Ipv4HeaderSlice::from_slice(&packet).unwrap();
+/// assert_eq!(5, header.ttl());
+/// assert_eq!(Some(5), header.get_field("ttl"));
+/// assert_eq!(Some(0xc0a80102), header.get_field("destination"));
+/// ```
+pub trait HeaderFieldAccess {
+    /// Table of the fields of the header.
+    fn fields() -> &'static [HeaderField];
+
+    /// Serialized bytes of the header.
+    fn header_bytes(&self) -> &[u8];
+
+    /// Returns the field with the given name (if it exists).
+    fn field(name: &str) -> Option<&'static HeaderField> where Self: Sized {
+        Self::fields().iter().find(|field| field.name == name)
+    }
+
+    /// Reads the value of the field with the given name. Returns `None` if
+    /// no field with the given name exists.
+    fn get_field(&self, name: &str) -> Option<u128> where Self: Sized {
+        Self::field(name)?.read(self.header_bytes())
+    }
+
+    /// Writes the value of the field with the given name to the serialized
+    /// header stored in `header`.
+    fn set_field(header: &mut [u8], name: &str, value: u128) -> Result<(), ValueError> where Self: Sized {
+        match Self::field(name) {
+            Some(field) => field.write(header, value),
+            None => Err(ValueError::HeaderFieldUnknown(name.to_string())),
+        }
+    }
+}
+
+impl<'a> HeaderFieldAccess for Ethernet2HeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for SingleVlanHeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for Ipv4HeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for Ipv6HeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for TcpHeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for UdpHeaderSlice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for Icmpv4Slice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
+
+impl<'a> HeaderFieldAccess for Icmpv6Slice<'a> {
+    fn fields() -> &'static [HeaderField] {
+        Self::FIELDS
+    }
+
+    fn header_bytes(&self) -> &[u8] {
+        self.slice()
+    }
+}
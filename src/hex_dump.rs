@@ -0,0 +1,307 @@
+//! Hex dumps of packets annotated with the layer & field names of the bytes.
+//!
+//! An [`AnnotatedHexDump`] labels the byte ranges of every decoded header with
+//! the layer & field name (e.g. `ipv4.ttl` or `tcp.flags`) based on the field
+//! tables exposed by the header slices (see [`crate::HeaderField`]). When a
+//! packet can not be completely decoded the dump shows the headers that could
+//! be decoded, marks the rest as unparsed & contains the error.
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::PacketBuilder;
+//! use etherparse::hex_dump::AnnotatedHexDump;
+//!
+//! let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!     .udp(21, 1234);
+//! let mut packet = Vec::with_capacity(builder.size(4));
+//! builder.write(&mut packet, &[1,2,3,4]).unwrap();
+//!
+//! // cut the packet in the middle of the udp header
+//! let dump = AnnotatedHexDump::from_ethernet(&packet[..38]);
+//! assert_eq!(Some(34), dump.error_offset());
+//! println!("{}", dump);
+//! ```
+//!
+//! The output starts with the following lines (one line per field or group of
+//! fields sharing bytes):
+//!
+//! ```text
+//! 0000  07 08 09 0a 0b 0c                                eth.destination
+//! 0006  01 02 03 04 05 06                                eth.source
+//! 000c  08 00                                            eth.ether_type
+//! 000e  45                                               ipv4.version, ipv4.ihl
+//! 000f  00                                               ipv4.dscp, ipv4.ecn
+//! ```
+use super::*;
+
+use core::ops::Range;
+
+/// Number of bytes shown per line in an [`AnnotatedHexDump`].
+pub const HEX_DUMP_BYTES_PER_LINE: usize = 16;
+
+/// Label of a byte range in an [`AnnotatedHexDump`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HexDumpAnnotation {
+    /// Range of the labeled bytes in the dumped data.
+    pub range: Range<usize>,
+    /// Layer & field name (e.g. "ipv4.ttl").
+    pub label: String,
+}
+
+/// Hex dump of a packet with the bytes labeled by layer & field name
+/// (see the module documentation).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnnotatedHexDump<'a> {
+    data: &'a [u8],
+    annotations: Vec<HexDumpAnnotation>,
+    error: Option<(usize, String)>,
+}
+
+impl<'a> AnnotatedHexDump<'a> {
+    /// Creates an annotated hex dump of a packet starting with an ethernet II header.
+    ///
+    /// If the packet can not be sliced the headers in front of the error
+    /// are annotated & the error is added to the dump.
+    pub fn from_ethernet(data: &'a [u8]) -> AnnotatedHexDump<'a> {
+        let sliced = LaxSlicedPacket::from_ethernet(data);
+        match sliced.stop_err {
+            None => AnnotatedHexDump::from_sliced(data, &sliced.packet),
+            Some((err, _)) => AnnotatedHexDump::from_partial(data, &sliced.packet, &err),
+        }
+    }
+
+    /// Creates an annotated hex dump of the given data based on the headers
+    /// of the sliced packet.
+    ///
+    /// `data` has to be the slice that was used to create the sliced packet
+    /// (headers pointing to memory outside of `data` are ignored).
+    pub fn from_sliced(data: &'a [u8], sliced: &SlicedPacket) -> AnnotatedHexDump<'a> {
+        let mut result = AnnotatedHexDump{
+            data,
+            annotations: Vec::new(),
+            error: None,
+        };
+        result.add_sliced(sliced);
+        if let Some(offset) = offset_in(data, sliced.payload) {
+            result.add(offset..offset + sliced.payload.len(), "payload".to_string());
+        }
+        result
+    }
+
+    /// Creates an annotated hex dump of a partially parsed packet.
+    ///
+    /// The headers present in `partial` are annotated and the rest of the data
+    /// starting at the payload of `partial` is marked as unparsed because of
+    /// the given error.
+    pub fn from_partial(data: &'a [u8], partial: &SlicedPacket, error: &ReadError) -> AnnotatedHexDump<'a> {
+        let mut result = AnnotatedHexDump{
+            data,
+            annotations: Vec::new(),
+            error: None,
+        };
+        result.add_sliced(partial);
+        let offset = offset_in(data, partial.payload).unwrap_or(0);
+        if offset < data.len() {
+            result.add(offset..data.len(), "unparsed".to_string());
+        }
+        result.error = Some((offset, error.to_string()));
+        result
+    }
+
+    /// The dumped data.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Labels of the byte ranges in the order they were added.
+    pub fn annotations(&self) -> &[HexDumpAnnotation] {
+        &self.annotations
+    }
+
+    /// Offset at which the parsing stopped because of an error (if an error occurred).
+    pub fn error_offset(&self) -> Option<usize> {
+        self.error.as_ref().map(|(offset, _)| *offset)
+    }
+
+    /// Message of the error that stopped the parsing (if an error occurred).
+    pub fn error_message(&self) -> Option<&str> {
+        self.error.as_ref().map(|(_, message)| message.as_str())
+    }
+
+    fn add(&mut self, range: Range<usize>, label: String) {
+        if range.start < range.end {
+            self.annotations.push(HexDumpAnnotation{ range, label });
+        }
+    }
+
+    /// Annotates the fields of a header based on its field table. Bytes of the
+    /// header after the fixed fields are labeled with `rest_name`.
+    fn add_fields(&mut self, layer: &str, header: &[u8], fields: &[HeaderField], rest_name: &str) {
+        let offset = match offset_in(self.data, header) {
+            Some(value) => value,
+            None => return,
+        };
+        let mut fixed_end = 0;
+        for field in fields {
+            let range = field.byte_range();
+            fixed_end = fixed_end.max(range.end);
+            self.add(
+                (offset + range.start)..(offset + range.end),
+                format!("{}.{}", layer, field.name)
+            );
+        }
+        if header.len() > fixed_end {
+            self.add(
+                (offset + fixed_end)..(offset + header.len()),
+                format!("{}.{}", layer, rest_name)
+            );
+        }
+    }
+
+    /// Annotates a header without a field table as a whole.
+    fn add_header(&mut self, label: &str, header: &[u8]) {
+        if let Some(offset) = offset_in(self.data, header) {
+            self.add(offset..offset + header.len(), label.to_string());
+        }
+    }
+
+    fn add_sliced(&mut self, sliced: &SlicedPacket) {
+        if let Some(LinkSlice::Ethernet2(eth)) = &sliced.link {
+            self.add_fields("eth", eth.slice(), Ethernet2HeaderSlice::FIELDS, "");
+        }
+
+        match &sliced.vlan {
+            Some(VlanSlice::SingleVlan(single)) => {
+                self.add_fields("vlan", single.slice(), SingleVlanHeaderSlice::FIELDS, "");
+            },
+            Some(VlanSlice::DoubleVlan(double)) => {
+                self.add_fields("vlan", double.outer().slice(), SingleVlanHeaderSlice::FIELDS, "");
+                self.add_fields("vlan", double.inner().slice(), SingleVlanHeaderSlice::FIELDS, "");
+            },
+            None => {},
+        }
+
+        match &sliced.ip {
+            Some(InternetSlice::Ipv4(header, extensions)) => {
+                self.add_fields("ipv4", header.slice(), Ipv4HeaderSlice::FIELDS, "options");
+                if let Some(auth) = &extensions.auth {
+                    self.add_header("ipv4.auth", auth.slice());
+                }
+            },
+            Some(InternetSlice::Ipv6(header, extensions)) => {
+                self.add_fields("ipv6", header.slice(), Ipv6HeaderSlice::FIELDS, "");
+                for extension in extensions.clone() {
+                    use Ipv6ExtensionSlice::*;
+                    match extension {
+                        HopByHop(value) => self.add_header("ipv6.hop_by_hop", value.slice()),
+                        Routing(value) => self.add_header("ipv6.routing", value.slice()),
+                        Fragment(value) => self.add_header("ipv6.fragment", value.slice()),
+                        DestinationOptions(value) => self.add_header("ipv6.destination_options", value.slice()),
+                        Authentication(value) => self.add_header("ipv6.auth", value.slice()),
+                    }
+                }
+            },
+            None => {},
+        }
+
+        use TransportSlice::*;
+        match &sliced.transport {
+            Some(Icmpv4(icmp)) => {
+                let slice = icmp.slice();
+                self.add_fields("icmp", &slice[..icmp.header_len()], Icmpv4Slice::FIELDS, "rest_of_header");
+                self.add_header("icmp.payload", &slice[icmp.header_len()..]);
+            },
+            Some(Icmpv6(icmp)) => {
+                let slice = icmp.slice();
+                self.add_fields("icmpv6", &slice[..icmp.header_len()], Icmpv6Slice::FIELDS, "rest_of_header");
+                self.add_header("icmpv6.payload", &slice[icmp.header_len()..]);
+            },
+            Some(Igmp(igmp)) => self.add_header("igmp", igmp.slice()),
+            Some(Sctp(sctp)) => self.add_header("sctp", sctp.slice()),
+            Some(Udp(udp)) => self.add_fields("udp", udp.slice(), UdpHeaderSlice::FIELDS, ""),
+            Some(Tcp(tcp)) => self.add_fields("tcp", tcp.slice(), TcpHeaderSlice::FIELDS, "options"),
+            Some(UdpLite(udp_lite)) => self.add_header("udp_lite", udp_lite.slice()),
+            Some(Dccp(dccp)) => self.add_header("dccp", dccp.slice()),
+            Some(Unknown(_)) | None => {},
+        }
+    }
+}
+
+impl<'a> fmt::Display for AnnotatedHexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // group annotations with overlapping byte ranges
+        let mut sorted: Vec<&HexDumpAnnotation> = self.annotations.iter().collect();
+        sorted.sort_by_key(|annotation| annotation.range.start);
+
+        let mut groups: Vec<(Range<usize>, Vec<&str>)> = Vec::with_capacity(sorted.len());
+        for annotation in sorted {
+            match groups.last_mut() {
+                Some((range, labels)) if annotation.range.start < range.end => {
+                    range.end = range.end.max(annotation.range.end);
+                    if false == labels.contains(&annotation.label.as_str()) {
+                        labels.push(&annotation.label);
+                    }
+                },
+                _ => groups.push((annotation.range.clone(), vec![&annotation.label])),
+            }
+        }
+
+        // write the groups & the bytes between them
+        let mut offset = 0;
+        for (range, labels) in groups.iter() {
+            let end = range.end.min(self.data.len());
+            if offset < range.start && range.start <= self.data.len() {
+                self.fmt_lines(f, offset..range.start, "")?;
+            }
+            if range.start < end {
+                self.fmt_lines(f, range.start..end, &labels.join(", "))?;
+            }
+            offset = offset.max(end);
+        }
+        if offset < self.data.len() {
+            self.fmt_lines(f, offset..self.data.len(), "")?;
+        }
+
+        if let Some((offset, message)) = &self.error {
+            writeln!(f, "error at offset {}: {}", offset, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> AnnotatedHexDump<'a> {
+    /// Writes the bytes in the range (split over multiple lines if needed)
+    /// with the label behind the first line.
+    fn fmt_lines(&self, f: &mut fmt::Formatter<'_>, range: Range<usize>, label: &str) -> fmt::Result {
+        let mut start = range.start;
+        let mut first = true;
+        while start < range.end {
+            let end = range.end.min(start + HEX_DUMP_BYTES_PER_LINE);
+            write!(f, "{:04x} ", start)?;
+            for byte in &self.data[start..end] {
+                write!(f, " {:02x}", byte)?;
+            }
+            if first && false == label.is_empty() {
+                let padding = (HEX_DUMP_BYTES_PER_LINE - (end - start))*3;
+                write!(f, "{:padding$}  {}", "", label, padding = padding)?;
+            }
+            writeln!(f)?;
+            first = false;
+            start = end;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the offset of `part` in `data` if `part` points into `data`.
+fn offset_in(data: &[u8], part: &[u8]) -> Option<usize> {
+    let data_start = data.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+    if data_start <= part_start && part_start + part.len() <= data_start + data.len() {
+        Some(part_start - data_start)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,175 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+
+/// Generic Routing Encapsulation (GRE) header according to
+/// [RFC 2784](https://datatracker.ietf.org/doc/html/rfc2784) including the
+/// key & sequence number extensions of [RFC 2890](https://datatracker.ietf.org/doc/html/rfc2890).
+///
+/// The optional fields are only present in the serialized header if they
+/// are set to `Some`. The routing fields of the deprecated
+/// [RFC 1701](https://datatracker.ietf.org/doc/html/rfc1701) are not supported.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct GreHeader {
+    /// Ether type of the encapsulated payload (e.g. [`ether_type::IPV4`]).
+    pub protocol_type: u16,
+    /// Checksum over the GRE header & payload (see [`GreHeader::calc_checksum`]).
+    pub checksum: Option<u16>,
+    /// Key identifying an individual traffic flow within a tunnel.
+    pub key: Option<u32>,
+    /// Sequence number of the packet within the tunnel.
+    pub sequence_number: Option<u32>,
+}
+
+impl GreHeader {
+    /// Minimum length of a GRE header in bytes (no optional fields present).
+    pub const MIN_LEN: usize = 4;
+
+    /// Maximum length of a GRE header in bytes (all optional fields present).
+    pub const MAX_LEN: usize = 16;
+
+    /// Flag in the first byte signaling that the checksum field is present.
+    const CHECKSUM_PRESENT: u8 = 0b1000_0000;
+
+    /// Flag in the first byte signaling that the key field is present.
+    const KEY_PRESENT: u8 = 0b0010_0000;
+
+    /// Flag in the first byte signaling that the sequence number field is present.
+    const SEQUENCE_NUMBER_PRESENT: u8 = 0b0001_0000;
+
+    /// Returns a GRE header with the given protocol type and no optional fields.
+    pub fn new(protocol_type: u16) -> GreHeader {
+        GreHeader {
+            protocol_type,
+            checksum: None,
+            key: None,
+            sequence_number: None,
+        }
+    }
+
+    /// Reads a GRE header from a slice and returns a tuple containing the
+    /// resulting header & the unused part of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(GreHeader, &[u8]), ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < GreHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GreHeader::MIN_LEN));
+        }
+
+        let version = slice[1] & 0b111;
+        if 0 != version {
+            return Err(GreUnsupportedVersion(version));
+        }
+
+        let flags = slice[0];
+        let header_len = GreHeader::MIN_LEN
+            + if 0 != flags & GreHeader::CHECKSUM_PRESENT { 4 } else { 0 }
+            + if 0 != flags & GreHeader::KEY_PRESENT { 4 } else { 0 }
+            + if 0 != flags & GreHeader::SEQUENCE_NUMBER_PRESENT { 4 } else { 0 };
+        if slice.len() < header_len {
+            return Err(UnexpectedEndOfSlice(header_len));
+        }
+
+        // read the optional fields in the order they are present
+        let mut offset = GreHeader::MIN_LEN;
+        let mut next_u32 = |present: bool| -> Option<[u8;4]> {
+            if present {
+                let value = [slice[offset], slice[offset + 1], slice[offset + 2], slice[offset + 3]];
+                offset += 4;
+                Some(value)
+            } else {
+                None
+            }
+        };
+        let checksum = next_u32(0 != flags & GreHeader::CHECKSUM_PRESENT)
+            .map(|value| u16::from_be_bytes([value[0], value[1]]));
+        let key = next_u32(0 != flags & GreHeader::KEY_PRESENT)
+            .map(u32::from_be_bytes);
+        let sequence_number = next_u32(0 != flags & GreHeader::SEQUENCE_NUMBER_PRESENT)
+            .map(u32::from_be_bytes);
+
+        Ok((
+            GreHeader {
+                protocol_type: u16::from_be_bytes([slice[2], slice[3]]),
+                checksum,
+                key,
+                sequence_number,
+            },
+            &slice[header_len..]
+        ))
+    }
+
+    /// Calculates the checksum over the GRE header (with a zero checksum field)
+    /// and the given payload.
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        checksum::Sum16BitWords::new()
+        .add_slice(&self.to_bytes_with_checksum(0))
+        .add_slice(payload)
+        .ones_complement()
+        .to_be()
+    }
+
+    /// Write the GRE header to the current position without recalculating the checksum.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write the GRE header to the start of the given slice without recalculating
+    /// the checksum. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        let bytes = self.to_bytes();
+        //length check
+        if slice.len() < bytes.len() {
+            Err(WriteError::SliceTooSmall(bytes.len()))
+        } else {
+            slice[..bytes.len()].copy_from_slice(&bytes);
+            Ok(&mut slice[bytes.len()..])
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        GreHeader::MIN_LEN
+            + if self.checksum.is_some() { 4 } else { 0 }
+            + if self.key.is_some() { 4 } else { 0 }
+            + if self.sequence_number.is_some() { 4 } else { 0 }
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { GreHeader::MAX_LEN }> {
+        self.to_bytes_with_checksum(self.checksum.unwrap_or(0))
+    }
+
+    /// Serializes the header with the given value in the checksum field
+    /// (only written if a checksum is present).
+    fn to_bytes_with_checksum(&self, checksum: u16) -> ArrayVec<u8, { GreHeader::MAX_LEN }> {
+        let mut result = ArrayVec::new();
+
+        let mut flags = 0;
+        if self.checksum.is_some() {
+            flags |= GreHeader::CHECKSUM_PRESENT;
+        }
+        if self.key.is_some() {
+            flags |= GreHeader::KEY_PRESENT;
+        }
+        if self.sequence_number.is_some() {
+            flags |= GreHeader::SEQUENCE_NUMBER_PRESENT;
+        }
+        let protocol_type_be = self.protocol_type.to_be_bytes();
+        result.extend([flags, 0, protocol_type_be[0], protocol_type_be[1]]);
+
+        if self.checksum.is_some() {
+            let checksum_be = checksum.to_be_bytes();
+            result.extend([checksum_be[0], checksum_be[1], 0, 0]);
+        }
+        if let Some(key) = self.key {
+            result.extend(key.to_be_bytes());
+        }
+        if let Some(sequence_number) = self.sequence_number {
+            result.extend(sequence_number.to_be_bytes());
+        }
+        result
+    }
+}
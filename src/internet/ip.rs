@@ -47,6 +47,7 @@ impl IpHeader {
     }
 
     ///Reads an IP (v4 or v6) header from the current position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<(IpHeader, u8), ReadError> {
         let value = {
             let mut buf = [0;1];
@@ -71,6 +72,7 @@ impl IpHeader {
     }
 
     ///Writes an IP (v4 or v6) header to the current position
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         use crate::IpHeader::*;
         match *self {
@@ -85,6 +87,29 @@ impl IpHeader {
         }
     }
 
+    /// Writes an IP (v4 or v6) header including its extensions to the start
+    /// of the given slice. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use crate::IpHeader::*;
+
+        //length check (done upfront so the error reports the full length)
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            return Err(WriteError::SliceTooSmall(header_len));
+        }
+
+        match *self {
+            Version4(ref header, ref extensions) => {
+                let rest = header.write_to_slice(slice)?;
+                extensions.write_to_slice(rest, header.protocol)
+            }
+            Version6(ref header, ref extensions) => {
+                let rest = header.write_to_slice(slice)?;
+                extensions.write_to_slice(rest, header.next_header)
+            }
+        }
+    }
+
     /// Returns the size when the ip header & extensions are serialized
     pub fn header_len(&self) -> usize {
         use crate::IpHeader::*;
@@ -520,12 +545,16 @@ pub mod ip_number {
     pub const TCP: u8 = Tcp as u8; //6
     ///User Datagram \[[RFC768](https://datatracker.ietf.org/doc/html/rfc768)\] \[Jon_Postel\]
     pub const UDP: u8 = Udp as u8; //17
+    ///Datagram Congestion Control Protocol \[[RFC4340](https://datatracker.ietf.org/doc/html/rfc4340)\]
+    pub const DCCP: u8 = Dccp as u8; //33
     ///IPv6 encapsulation \[[RFC2473](https://datatracker.ietf.org/doc/html/rfc2473)\]
     pub const IPV6: u8 = Ipv6 as u8; //41
     ///Routing Header for IPv6 \[Steve_Deering\]
     pub const IPV6_ROUTE: u8 = IPv6RouteHeader as u8; //43
     ///Fragment Header for IPv6 \[Steve_Deering\]
     pub const IPV6_FRAG: u8 = IPv6FragmentationHeader as u8; //44
+    ///Generic Routing Encapsulation \[[RFC2784](https://datatracker.ietf.org/doc/html/rfc2784)\]\[Tony_Li\]
+    pub const GRE: u8 = Gre as u8; //47
     ///Encapsulating Security Payload \[[RFC4303](https://datatracker.ietf.org/doc/html/rfc4303)\]
     pub const ENCAP_SEC: u8 = EncapsulatingSecurityPayload as u8; //50
     ///Authentication Header \[[RFC4302](https://datatracker.ietf.org/doc/html/rfc4302)\]
@@ -534,8 +563,12 @@ pub mod ip_number {
     pub const IPV6_ICMP: u8 = IPv6Icmp as u8; // 58
     ///Destination Options for IPv6 \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
     pub const IPV6_DEST_OPTIONS: u8 = IPv6DestinationOptions as u8; //60
+    ///Stream Control Transmission Protocol \[[RFC9260](https://datatracker.ietf.org/doc/html/rfc9260)\]
+    pub const SCTP: u8 = Sctp as u8; //132
     ///MobilityHeader \[[RFC6275](https://datatracker.ietf.org/doc/html/rfc6275)\]
     pub const MOBILITY: u8 = MobilityHeader as u8; //135
+    ///UDPLite \[[RFC3828](https://datatracker.ietf.org/doc/html/rfc3828)\]
+    pub const UDP_LITE: u8 = UdpLite as u8; //136
     ///Host Identity Protocol \[[RFC7401](https://datatracker.ietf.org/doc/html/rfc7401)\]
     pub const HIP: u8 = Hip as u8; //139
     ///Shim6 Protocol \[[RFC5533](https://datatracker.ietf.org/doc/html/rfc5533)\]
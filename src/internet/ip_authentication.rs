@@ -1,7 +1,7 @@
 use super::super::*;
 
-use std::fmt::{Debug, Formatter};
-use std::slice::from_raw_parts;
+use core::fmt::{Debug, Formatter};
+use core::slice::from_raw_parts;
 
 /// Deprecated use [IpAuthenticationHeader] instead.
 #[deprecated(
@@ -31,7 +31,7 @@ pub struct IpAuthenticationHeader {
 }
 
 impl Debug for IpAuthenticationHeader {
-    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), core::fmt::Error> {
         write!(formatter, "IpAuthenticationHeader {{ next_header: {}, spi: {}, sequence_number: {}, raw_icv: {:?} }}", 
             self.next_header,
             self.spi,
@@ -97,6 +97,7 @@ impl<'a> IpAuthenticationHeader {
     }
 
     /// Read an authentication header from the current reader position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<IpAuthenticationHeader, ReadError> {
         
         let start = {
@@ -165,30 +166,47 @@ impl<'a> IpAuthenticationHeader {
     }
 
     /// Writes the given authentication header to the current position.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes_without_icv())?;
+        writer.write_all(self.raw_icv())?;
+        Ok(())
+    }
+
+    /// Writes the given authentication header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'b>(&self, slice: &'b mut [u8]) -> Result<&'b mut [u8], WriteError> {
+        //length check
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            Err(WriteError::SliceTooSmall(header_len))
+        } else {
+            slice[..12].copy_from_slice(&self.to_bytes_without_icv());
+            slice[12..header_len].copy_from_slice(self.raw_icv());
+            Ok(&mut slice[header_len..])
+        }
+    }
 
+    /// Returns the first 12 bytes of the serialized header (everything except the icv).
+    fn to_bytes_without_icv(&self) -> [u8;12] {
         let spi_be = self.spi.to_be_bytes();
         let sequence_number_be = self.sequence_number.to_be_bytes();
         debug_assert!(self.raw_icv_len != 0xff);
 
-        writer.write_all(
-            &[
-                self.next_header,
-                self.raw_icv_len + 1,
-                0,
-                0,
-                spi_be[0],
-                spi_be[1],
-                spi_be[2],
-                spi_be[3],
-                sequence_number_be[0],
-                sequence_number_be[1],
-                sequence_number_be[2],
-                sequence_number_be[3],
-            ]
-        )?;
-        writer.write_all(self.raw_icv())?;
-        Ok(())
+        [
+            self.next_header,
+            self.raw_icv_len + 1,
+            0,
+            0,
+            spi_be[0],
+            spi_be[1],
+            spi_be[2],
+            spi_be[3],
+            sequence_number_be[0],
+            sequence_number_be[1],
+            sequence_number_be[2],
+            sequence_number_be[3],
+        ]
     }
 
     ///Length of the header in bytes.
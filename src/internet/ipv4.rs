@@ -1,8 +1,8 @@
 use super::super::*;
 
-use std::net::Ipv4Addr;
-use std::fmt::{Debug, Formatter};
-use std::slice::from_raw_parts;
+use core::net::Ipv4Addr;
+use core::fmt::{Debug, Formatter};
+use core::slice::from_raw_parts;
 
 /// IPv4 header without options.
 #[derive(Clone)]
@@ -95,7 +95,7 @@ impl Ipv4Header {
 
     ///Returns the maximum payload size based on the current options size.
     pub fn max_payload_len(&self) -> u16 {
-        std::u16::MAX - u16::from(self.options_len) - (Ipv4Header::SERIALIZED_SIZE as u16)
+        core::u16::MAX - u16::from(self.options_len) - (Ipv4Header::SERIALIZED_SIZE as u16)
     }
 
     ///Sets the options & header_length based on the provided length.
@@ -141,6 +141,7 @@ impl Ipv4Header {
     }
 
     /// Reads an IPv4 header from the current position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<Ipv4Header, ReadError> {
         let mut first_byte : [u8;1] = [0;1];
         reader.read_exact(&mut first_byte)?;
@@ -153,6 +154,7 @@ impl Ipv4Header {
     }
 
     /// Reads an IPv4 header assuming the version & ihl field have already been read.
+    #[cfg(feature = "std")]
     pub fn read_without_version<T: io::Read + io::Seek + Sized>(reader: &mut T, first_byte: u8) -> Result<Ipv4Header, ReadError> {
         
         let mut header_raw : [u8;20] = [0;20];
@@ -229,6 +231,7 @@ impl Ipv4Header {
     }
 
     /// Writes a given IPv4 header to the current position (this method automatically calculates the header length and checksum).
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         //check ranges
         self.check_ranges()?;
@@ -238,6 +241,7 @@ impl Ipv4Header {
     }
 
     /// Writes a given IPv4 header to the current position (this method just writes the specified checksum and does note compute it).
+    #[cfg(feature = "std")]
     pub fn write_raw<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         //check ranges
         self.check_ranges()?;
@@ -246,8 +250,40 @@ impl Ipv4Header {
         self.write_ipv4_header_internal(writer, self.header_checksum)
     }
 
+    /// Writes the IPv4 header (including options) to the start of the given slice and
+    /// returns the unused part of the slice (this method automatically calculates
+    /// the header length and checksum).
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        //check ranges
+        self.check_ranges()?;
+
+        //length check
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            return Err(WriteError::SliceTooSmall(header_len));
+        }
+
+        slice[..Ipv4Header::SERIALIZED_SIZE].copy_from_slice(
+            &self.to_bytes_without_options(self.calc_header_checksum_unchecked())
+        );
+        slice[Ipv4Header::SERIALIZED_SIZE..header_len].copy_from_slice(self.options());
+        Ok(&mut slice[header_len..])
+    }
+
     /// Write the given header with the  checksum and header length specified in the seperate arguments
+    #[cfg(feature = "std")]
     fn write_ipv4_header_internal<T: io::Write>(&self, write: &mut T, header_checksum: u16) -> Result<(), WriteError> {
+        write.write_all(&self.to_bytes_without_options(header_checksum))?;
+
+        //options
+        write.write_all(self.options())?;
+
+        //done
+        Ok(())
+    }
+
+    /// Returns the serialized fixed part of the header (without options) with the given checksum.
+    fn to_bytes_without_options(&self, header_checksum: u16) -> [u8;Ipv4Header::SERIALIZED_SIZE] {
         let total_len_be = self.total_len().to_be_bytes();
         let id_be = self.identification.to_be_bytes();
         let frag_and_flags = {
@@ -268,8 +304,7 @@ impl Ipv4Header {
             ]
         };
         let header_checksum_be = header_checksum.to_be_bytes();
-
-        let header_raw = [
+        [
             (4 << 4) | self.ihl(),
             (self.differentiated_services_code_point << 2) | self.explicit_congestion_notification,
             total_len_be[0],
@@ -294,14 +329,7 @@ impl Ipv4Header {
             self.destination[1],
             self.destination[2],
             self.destination[3],
-        ];
-        write.write_all(&header_raw)?;
-
-        //options
-        write.write_all(self.options())?;
-
-        //done
-        Ok(())
+        ]
     }
 
     /// Calculate header checksum of the current ipv4 header.
@@ -392,7 +420,7 @@ impl Default for Ipv4Header {
 }
 
 impl Debug for Ipv4Header {
-    fn fmt(&self, fotmatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, fotmatter: &mut Formatter) -> Result<(), core::fmt::Error> {
         write!(fotmatter, "Ipv4Header {{ ihl: {}, differentiated_services_code_point: {}, explicit_congestion_notification: {}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {:?}, destination: {:?}, options: {:?} }}", 
             self.ihl(),
             self.differentiated_services_code_point,
@@ -411,7 +439,7 @@ impl Debug for Ipv4Header {
     }
 }
 
-impl std::cmp::PartialEq for Ipv4Header {
+impl core::cmp::PartialEq for Ipv4Header {
     fn eq(&self, other: &Ipv4Header) -> bool {
         self.differentiated_services_code_point == other.differentiated_services_code_point &&
         self.explicit_congestion_notification == other.explicit_congestion_notification &&
@@ -430,7 +458,7 @@ impl std::cmp::PartialEq for Ipv4Header {
     }
 }
 
-impl std::cmp::Eq for Ipv4Header {}
+impl core::cmp::Eq for Ipv4Header {}
 
 /// A slice containing an ipv4 header of a network package.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -440,6 +468,23 @@ pub struct Ipv4HeaderSlice<'a> {
 
 impl<'a> Ipv4HeaderSlice<'a> {
 
+    /// Position & size of the fields in the serialized header (excluding the options).
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "version", bit_offset: 0, bit_width: 4 },
+        HeaderField{ name: "ihl", bit_offset: 4, bit_width: 4 },
+        HeaderField{ name: "dscp", bit_offset: 8, bit_width: 6 },
+        HeaderField{ name: "ecn", bit_offset: 14, bit_width: 2 },
+        HeaderField{ name: "total_length", bit_offset: 16, bit_width: 16 },
+        HeaderField{ name: "identification", bit_offset: 32, bit_width: 16 },
+        HeaderField{ name: "flags", bit_offset: 48, bit_width: 3 },
+        HeaderField{ name: "fragment_offset", bit_offset: 51, bit_width: 13 },
+        HeaderField{ name: "ttl", bit_offset: 64, bit_width: 8 },
+        HeaderField{ name: "protocol", bit_offset: 72, bit_width: 8 },
+        HeaderField{ name: "header_checksum", bit_offset: 80, bit_width: 16 },
+        HeaderField{ name: "source", bit_offset: 96, bit_width: 32 },
+        HeaderField{ name: "destination", bit_offset: 128, bit_width: 32 },
+    ];
+
     /// Creates a slice containing an ipv4 header (including header options).
     pub fn from_slice(slice: &'a[u8]) -> Result<Ipv4HeaderSlice<'a>, ReadError> {
 
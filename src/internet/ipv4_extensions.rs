@@ -36,6 +36,7 @@ impl Ipv4Extensions {
     /// Reads the known ipv4 extension headers from the reader and returns the
     /// headers together with the internet protocol number identifying the protocol
     /// that will be next.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T, start_ip_number: u8) -> Result<(Ipv4Extensions, u8), ReadError> {
         use ip_number::*;
         if AUTH == start_ip_number {
@@ -53,6 +54,7 @@ impl Ipv4Extensions {
     }
 
     /// Write the extensions to the writer.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T, start_ip_number: u8) -> Result<(), WriteError> {
         use ip_number::*;
         use IpNumber::*;
@@ -67,6 +69,21 @@ impl Ipv4Extensions {
         }
     }
 
+    /// Write the extensions to the start of the given slice. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8], start_ip_number: u8) -> Result<&'a mut [u8], WriteError> {
+        use ip_number::*;
+        use IpNumber::*;
+        use ValueError::*;
+        match self.auth {
+            Some(ref header) => if AUTH == start_ip_number {
+                header.write_to_slice(slice)
+            } else {
+                Err(Ipv4ExtensionNotReferenced(AuthenticationHeader).into())
+            },
+            None => Ok(slice)
+        }
+    }
+
     ///Length of the all present headers in bytes.
     pub fn header_len(&self) -> usize {
         if let Some(ref header) = self.auth {
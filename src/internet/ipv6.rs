@@ -1,7 +1,7 @@
 use super::super::*;
 
-use std::net::Ipv6Addr;
-use std::slice::from_raw_parts;
+use core::net::Ipv6Addr;
+use core::slice::from_raw_parts;
 
 ///IPv6 header according to rfc8200.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -50,6 +50,7 @@ impl Ipv6Header {
     }
 
     ///Reads an IPv6 header from the current position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<Ipv6Header, ReadError> {
         let mut value : [u8;1] = [0;1];
         reader.read_exact(&mut value)?;
@@ -64,6 +65,7 @@ impl Ipv6Header {
     }
 
     ///Reads an IPv6 header assuming the version & flow_label field have already been read.
+    #[cfg(feature = "std")]
     pub fn read_without_version<T: io::Read + io::Seek + Sized>(reader: &mut T, version_rest: u8) -> Result<Ipv6Header, io::Error> {
 
         let mut buffer : [u8;8+32-1] = [0;8+32-1];
@@ -154,6 +156,7 @@ impl Ipv6Header {
     }
 
     ///Skips the ipv6 header extension and returns the next ip protocol number
+    #[cfg(feature = "std")]
     pub fn skip_header_extension<T: io::Read + io::Seek + Sized>(reader: &mut T, next_header: u8) -> Result<u8, io::Error> {
         use crate::ip_number::*;
 
@@ -194,6 +197,7 @@ impl Ipv6Header {
     }
 
     ///Skips all ipv6 header extensions and returns the next ip protocol number
+    #[cfg(feature = "std")]
     pub fn skip_all_header_extensions<T: io::Read + io::Seek + Sized>(reader: &mut T, next_header: u8) -> Result<u8, ReadError> {
 
         let mut next_header = next_header;
@@ -216,20 +220,38 @@ impl Ipv6Header {
     }
 
     ///Writes a given IPv6 header to the current position.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Serialize the header to the start of the given slice. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < Ipv6Header::SERIALIZED_SIZE {
+            Err(SliceTooSmall(Ipv6Header::SERIALIZED_SIZE))
+        } else {
+            slice[..Ipv6Header::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes()?);
+            Ok(&mut slice[Ipv6Header::SERIALIZED_SIZE..])
+        }
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the header values are outside of range.
+    pub fn to_bytes(&self) -> Result<[u8;Ipv6Header::SERIALIZED_SIZE], ValueError> {
         use crate::ErrorField::*;
-        fn max_check_u32(value: u32, max: u32, field: ErrorField) -> Result<(), WriteError> {
+        fn max_check_u32(value: u32, max: u32, field: ErrorField) -> Result<(), ValueError> {
             if value <= max {
                 Ok(())
             } else {
                 Err(
-                    WriteError::ValueError(
-                        ValueError::U32TooLarge{
-                            value, 
-                            max, 
-                            field
-                        }
-                    )
+                    ValueError::U32TooLarge{
+                        value, 
+                        max, 
+                        field
+                    }
                 )
             }
         }
@@ -241,7 +263,7 @@ impl Ipv6Header {
         let flow_label_be = self.flow_label.to_be_bytes();
         let payload_len_be = self.payload_length.to_be_bytes();
 
-        let header_raw = [
+        Ok([
             (6 << 4) | (self.traffic_class >> 4), 
             (self.traffic_class << 4) | flow_label_be[1],
             flow_label_be[2],
@@ -261,10 +283,7 @@ impl Ipv6Header {
             self.destination[4], self.destination[5], self.destination[6], self.destination[7],
             self.destination[8], self.destination[9], self.destination[10], self.destination[11],
             self.destination[12], self.destination[13], self.destination[14], self.destination[15],
-        ];
-        writer.write_all(&header_raw)?;
-
-        Ok(())
+        ])
     }
 
     /// Length of the serialized header in bytes.
@@ -279,7 +298,7 @@ impl Ipv6Header {
     ///Sets the field total_length based on the size of the payload and the options. Returns an error if the payload is too big to fit.
     pub fn set_payload_length(&mut self, size: usize) -> Result<(), ValueError> {
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = std::u16::MAX as usize;
+        const MAX_PAYLOAD_LENGTH: usize = core::u16::MAX as usize;
         if MAX_PAYLOAD_LENGTH < size {
             return Err(ValueError::Ipv6PayloadLengthTooLarge(size));
         }
@@ -297,6 +316,18 @@ pub struct Ipv6HeaderSlice<'a> {
 
 impl<'a> Ipv6HeaderSlice<'a, > {
 
+    /// Position & size of the fields in the serialized header.
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "version", bit_offset: 0, bit_width: 4 },
+        HeaderField{ name: "traffic_class", bit_offset: 4, bit_width: 8 },
+        HeaderField{ name: "flow_label", bit_offset: 12, bit_width: 20 },
+        HeaderField{ name: "payload_length", bit_offset: 32, bit_width: 16 },
+        HeaderField{ name: "next_header", bit_offset: 48, bit_width: 8 },
+        HeaderField{ name: "hop_limit", bit_offset: 56, bit_width: 8 },
+        HeaderField{ name: "source", bit_offset: 64, bit_width: 128 },
+        HeaderField{ name: "destination", bit_offset: 192, bit_width: 128 },
+    ];
+
     /// Creates a slice containing an ipv6 header (without header extensions).
     pub fn from_slice(slice: &'a[u8]) -> Result<Ipv6HeaderSlice<'a>, ReadError> {
 
@@ -1,5 +1,5 @@
 use super::super::*;
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 /// IPv6 extension headers present after the ip header.
 ///
@@ -27,6 +27,14 @@ pub struct Ipv6Extensions {
     pub auth: Option<IpAuthenticationHeader>,
 }
 
+/// Reference to one of the headers in [`Ipv6Extensions`] (used to share
+/// the header ordering logic between the different write methods).
+enum ExtensionHeaderRef<'a> {
+    Raw(&'a Ipv6RawExtensionHeader),
+    Fragment(&'a Ipv6FragmentHeader),
+    Auth(&'a IpAuthenticationHeader),
+}
+
 impl Ipv6Extensions {
 
     /// Reads as many extension headers as possible from the slice.
@@ -57,9 +65,12 @@ impl Ipv6Extensions {
     /// the start. In this case an `ReadError::Ipv6HopByHopHeaderNotAtStart` error is generated as
     /// the hop by hop header is required to be located directly after the IPv6 header according 
     /// to RFC 8200.
-    pub fn from_slice(start_ip_number: u8, slice: &[u8]) -> Result<(Ipv6Extensions, u8, &[u8]), ReadError> {
+    ///
+    /// Length errors (e.g. `ReadError::UnexpectedEndOfSlice`) are relative to the start
+    /// of the given slice (not to the start of the extension header that caused them).
+    pub fn from_slice(start_ip_number: u8, start_slice: &[u8]) -> Result<(Ipv6Extensions, u8, &[u8]), ReadError> {
         let mut result: Ipv6Extensions = Default::default();
-        let mut rest = slice;
+        let mut rest = start_slice;
         let mut next_header = start_ip_number;
 
         use ip_number::*;
@@ -67,7 +78,8 @@ impl Ipv6Extensions {
 
         // the hop by hop header is required to occur directly after the ipv6 header
         if IPV6_HOP_BY_HOP == next_header {
-            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
             rest = &rest[slice.slice().len()..];
             next_header = slice.next_header();
             result.hop_by_hop_options = Some(slice.to_header());   
@@ -86,7 +98,8 @@ impl Ipv6Extensions {
                             // more then one header of this type found -> abort parsing
                             return Ok((result, next_header, rest))
                         } else {
-                            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                                .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                             rest = &rest[slice.slice().len()..];
                             next_header = slice.next_header();
                             routing.final_destination_options = Some(slice.to_header());
@@ -95,7 +108,8 @@ impl Ipv6Extensions {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_header, rest));
                     } else {
-                        let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                        let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                            .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                         rest = &rest[slice.slice().len()..];
                         next_header = slice.next_header();
                         result.destination_options = Some(slice.to_header());
@@ -106,7 +120,8 @@ impl Ipv6Extensions {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_header, rest))
                     } else {
-                        let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                        let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                            .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                         rest = &rest[slice.slice().len()..];
                         next_header = slice.next_header();
                         result.routing = Some(
@@ -122,7 +137,8 @@ impl Ipv6Extensions {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_header, rest))
                     } else {
-                        let slice = Ipv6FragmentHeaderSlice::from_slice(rest)?;
+                        let slice = Ipv6FragmentHeaderSlice::from_slice(rest)
+                            .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                         rest = &rest[slice.slice().len()..];
                         next_header = slice.next_header();
                         result.fragment = Some(slice.to_header());
@@ -133,7 +149,8 @@ impl Ipv6Extensions {
                         // more then one header of this type found -> abort parsing
                         return Ok((result, next_header, rest))
                     } else {
-                        let slice = IpAuthenticationHeaderSlice::from_slice(rest)?;
+                        let slice = IpAuthenticationHeaderSlice::from_slice(rest)
+                            .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                         rest = &rest[slice.slice().len()..];
                         next_header = slice.next_header();
                         result.auth = Some(slice.to_header());
@@ -177,6 +194,7 @@ impl Ipv6Extensions {
     /// the start. In this case an `ReadError::Ipv6HopByHopHeaderNotAtStart` error is generated as
     /// the hop by hop header is required to be located directly after the IPv6 header according 
     /// to RFC 8200.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T, start_ip_number: u8) -> Result<(Ipv6Extensions, u8), ReadError> {
         let mut result: Ipv6Extensions = Default::default();
         let mut next_protocol = start_ip_number;
@@ -267,7 +285,58 @@ impl Ipv6Extensions {
     ///
     /// It is required that all next header are correctly set in the headers and no other ipv6 header 
     /// extensions follow this header. If this is not the case a `ValueError::Ipv6ExtensionNotReferenced`
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T, first_header: u8) -> Result<(), WriteError> {
+        self.write_internal(first_header, |header| {
+            use ExtensionHeaderRef::*;
+            match header {
+                Raw(header) => header.write(writer),
+                Fragment(header) => header.write(writer),
+                Auth(header) => header.write(writer),
+            }
+        })
+    }
+
+    /// Writes the given headers to the start of a slice based on the order defined in the
+    /// next_header fields of the headers and the first header_id passed to this function.
+    /// Returns the unused part of the slice.
+    ///
+    /// The same requirements as for [`Ipv6Extensions::write`] apply.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8], first_header: u8) -> Result<&'a mut [u8], WriteError> {
+        //length check
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            return Err(WriteError::SliceTooSmall(header_len));
+        }
+
+        let mut offset = 0;
+        self.write_internal(first_header, |header| {
+            use ExtensionHeaderRef::*;
+            let len = match header {
+                Raw(header) => {
+                    header.write_to_slice(&mut slice[offset..])?;
+                    header.header_len()
+                },
+                Fragment(header) => {
+                    header.write_to_slice(&mut slice[offset..])?;
+                    header.header_len()
+                },
+                Auth(header) => {
+                    header.write_to_slice(&mut slice[offset..])?;
+                    header.header_len()
+                },
+            };
+            offset += len;
+            Ok(())
+        })?;
+        Ok(&mut slice[header_len..])
+    }
+
+    /// Determines the order in which the headers have to be written and passes
+    /// them to the `write_header` function in that order.
+    fn write_internal<F>(&self, first_header: u8, mut write_header: F) -> Result<(), WriteError>
+    where F: FnMut(ExtensionHeaderRef<'_>) -> Result<(), WriteError>
+    {
         use ip_number::*;
         use IpNumber::*;
         use ValueError::*;
@@ -301,7 +370,7 @@ impl Ipv6Extensions {
         // check if hop by hop header should be written first
         if IPV6_HOP_BY_HOP == next_header {
             let header = &self.hop_by_hop_options.as_ref().unwrap();
-            header.write(writer)?;
+            write_header(ExtensionHeaderRef::Raw(header))?;
             next_header = header.next_header;
             needs_write.hop_by_hop_options = false;
         }
@@ -329,7 +398,7 @@ impl Ipv6Extensions {
                     if route_written {
                         if needs_write.final_destination_options {
                             let header = &self.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap();
-                            header.write(writer)?;
+                            write_header(ExtensionHeaderRef::Raw(header))?;
                             next_header = header.next_header;
                             needs_write.final_destination_options = false;
                         } else {
@@ -337,7 +406,7 @@ impl Ipv6Extensions {
                         }
                     } else if needs_write.destination_options {
                         let header = &self.destination_options.as_ref().unwrap();
-                        header.write(writer)?;
+                        write_header(ExtensionHeaderRef::Raw(header))?;
                         next_header = header.next_header;
                         needs_write.destination_options = false;
                     } else {
@@ -347,7 +416,7 @@ impl Ipv6Extensions {
                 IPV6_ROUTE => {
                     if needs_write.routing {
                         let header = &self.routing.as_ref().unwrap().routing;
-                        header.write(writer)?;
+                        write_header(ExtensionHeaderRef::Raw(header))?;
                         next_header = header.next_header;
                         needs_write.routing = false;
                         // for destination options
@@ -359,7 +428,7 @@ impl Ipv6Extensions {
                 IPV6_FRAG => {
                     if needs_write.fragment {
                         let header = &self.fragment.as_ref().unwrap();
-                        header.write(writer)?;
+                        write_header(ExtensionHeaderRef::Fragment(header))?;
                         next_header = header.next_header;
                         needs_write.fragment = false;
                     } else {
@@ -369,7 +438,7 @@ impl Ipv6Extensions {
                 AUTH => {
                     if needs_write.auth {
                         let header = &self.auth.as_ref().unwrap();
-                        header.write(writer)?;
+                        write_header(ExtensionHeaderRef::Auth(header))?;
                         next_header = header.next_header;
                         needs_write.auth = false;
                     } else {
@@ -690,6 +759,9 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
 
     /// Collects all ipv6 extension headers in a slice & checks if
     /// a fragmentation header that fragments the packet is present.
+    ///
+    /// Length errors (e.g. `ReadError::UnexpectedEndOfSlice`) are relative to the start
+    /// of the given slice (not to the start of the extension header that caused them).
     pub fn from_slice(start_ip_number: u8, start_slice: &'a [u8]) -> Result<(Ipv6ExtensionsSlice, u8, &'a[u8]), ReadError> {
         let mut rest = start_slice;
         let mut next_header = start_ip_number;
@@ -700,7 +772,8 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
 
         // the hop by hop header is required to occur directly after the ipv6 header
         if IPV6_HOP_BY_HOP == next_header {
-            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+            let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
             rest = &rest[slice.slice().len()..];
             next_header = slice.next_header();
         }
@@ -711,7 +784,8 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
                     return Err(Ipv6HopByHopHeaderNotAtStart);
                 },
                 IPV6_DEST_OPTIONS | IPV6_ROUTE => {
-                    let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                    let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)
+                        .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                     // SAFETY:
                     // Ipv6RawExtensionHeaderSlice::from_slice always generates
                     // a subslice from the given slice rest. Therefor it is guranteed
@@ -726,7 +800,8 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
                     next_header = slice.next_header();
                 },
                 IPV6_FRAG => {
-                    let slice = Ipv6FragmentHeaderSlice::from_slice(rest)?;
+                    let slice = Ipv6FragmentHeaderSlice::from_slice(rest)
+                        .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                     // SAFETY:
                     // Ipv6FragmentHeaderSlice::from_slice always generates
                     // a subslice from the given slice rest. Therefor it is guranteed
@@ -744,7 +819,8 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
                     fragmented = fragmented || slice.is_fragmenting_payload();
                 },
                 AUTH => {
-                    let slice = IpAuthenticationHeaderSlice::from_slice(rest)?;
+                    let slice = IpAuthenticationHeaderSlice::from_slice(rest)
+                        .map_err(|err| err.add_slice_offset(start_slice.len() - rest.len()))?;
                     // SAFETY:
                     // IpAuthenticationHeaderSlice::from_slice always generates
                     // a subslice from the given slice rest. Therefor it is guranteed
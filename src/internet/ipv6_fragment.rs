@@ -1,6 +1,6 @@
 use super::super::*;
 
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 /// IPv6 fragment header.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -45,6 +45,7 @@ impl Ipv6FragmentHeader {
     }
 
     /// Read an fragment header from the current reader position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<Ipv6FragmentHeader, ReadError> {
         let buffer = {
             let mut buffer : [u8;8] = [0;8];
@@ -74,10 +75,24 @@ impl Ipv6FragmentHeader {
     }
 
     /// Writes a given IPv6 fragment header to the current position.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         Ok(writer.write_all(&self.to_bytes()?)?)
     }
 
+    /// Writes the fragment header to the start of the given slice. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            Err(SliceTooSmall(header_len))
+        } else {
+            slice[..header_len].copy_from_slice(&self.to_bytes()?);
+            Ok(&mut slice[header_len..])
+        }
+    }
+
     /// Length of the header in bytes.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -1,11 +1,54 @@
 use super::super::*;
 
-use std::fmt::{Debug, Formatter};
-use std::slice::from_raw_parts;
+use core::fmt::{Debug, Formatter};
+use core::slice::from_raw_parts;
 
 ///Maximum number of header extensions allowed (according to the ipv6 rfc8200, & iana protocol numbers).
 pub const IPV6_MAX_NUM_HEADER_EXTENSIONS: usize = 12;
 
+/// Module containing the constants for options in IPv6 hop-by-hop & destination options headers.
+pub mod ipv6_option {
+    /// Option type of the "Pad1" option (single byte padding without length field).
+    pub const TYPE_PAD1: u8 = 0;
+    /// Option type of the "PadN" option (padding of two or more bytes).
+    pub const TYPE_PADN: u8 = 1;
+    /// Option type of the "Router Alert" option (from [RFC 2711](https://tools.ietf.org/html/rfc2711)).
+    pub const TYPE_ROUTER_ALERT: u8 = 5;
+    /// Length in bytes of the data of the "Router Alert" option (excluding type & length fields).
+    pub const LEN_ROUTER_ALERT: u8 = 2;
+    /// "Router Alert" value indicating that the datagram contains a Multicast Listener Discovery message.
+    pub const ROUTER_ALERT_MLD: u16 = 0;
+    /// "Router Alert" value indicating that the datagram contains a RSVP message.
+    pub const ROUTER_ALERT_RSVP: u16 = 1;
+    /// "Router Alert" value indicating that the datagram contains an Active Networks message.
+    pub const ROUTER_ALERT_ACTIVE_NETWORKS: u16 = 2;
+}
+
+/// Searches the given options (payload of a hop-by-hop or destination
+/// options header) for a "Router Alert" option and returns its value.
+fn find_router_alert(options: &[u8]) -> Option<u16> {
+    use ipv6_option::*;
+    let mut rest = options;
+    while let Some(option_type) = rest.first() {
+        if TYPE_PAD1 == *option_type {
+            rest = &rest[1..];
+            continue;
+        }
+        if rest.len() < 2 {
+            return None;
+        }
+        let len = 2 + usize::from(rest[1]);
+        if rest.len() < len {
+            return None;
+        }
+        if TYPE_ROUTER_ALERT == *option_type && LEN_ROUTER_ALERT == rest[1] {
+            return Some(u16::from_be_bytes([rest[2], rest[3]]));
+        }
+        rest = &rest[len..];
+    }
+    None
+}
+
 /// Raw IPv6 extension header (undecoded payload).
 ///
 /// IPv6 extension header with only minimal data interpretation. NOTE only ipv6 header
@@ -33,7 +76,7 @@ pub struct Ipv6RawExtensionHeader {
 }
 
 impl Debug for Ipv6RawExtensionHeader {
-    fn fmt(&self, fotmatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, fotmatter: &mut Formatter) -> Result<(), core::fmt::Error> {
         write!(fotmatter, "Ipv6RawExtensionHeader {{ next_header: {}, payload: {:?} }}", 
             self.next_header,
             self.payload())
@@ -94,6 +137,77 @@ impl Ipv6RawExtensionHeader {
         }
     }
 
+    /// Creates an extension header containing only a "Router Alert" option
+    /// (padded to 8 bytes). This is intended for hop-by-hop options headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv6RawExtensionHeader, ip_number, ipv6_option};
+    ///
+    /// // hop-by-hop header as required by MLD (RFC 2710 & RFC 3810)
+    /// let hop_by_hop = Ipv6RawExtensionHeader::new_router_alert(
+    ///     ip_number::IPV6_ICMP,
+    ///     ipv6_option::ROUTER_ALERT_MLD
+    /// );
+    /// assert_eq!(Some(ipv6_option::ROUTER_ALERT_MLD), hop_by_hop.router_alert());
+    /// assert_eq!(8, hop_by_hop.header_len());
+    /// ```
+    pub fn new_router_alert(next_header: u8, value: u16) -> Ipv6RawExtensionHeader {
+        use ipv6_option::*;
+        let value_be = value.to_be_bytes();
+        // the "PadN" option at the end aligns the header to 8 bytes
+        Ipv6RawExtensionHeader::new_raw(
+            next_header,
+            &[TYPE_ROUTER_ALERT, LEN_ROUTER_ALERT, value_be[0], value_be[1], TYPE_PADN, 0]
+        ).unwrap()
+    }
+
+    /// Appends a "Router Alert" option with the given value to the options
+    /// of the header (followed by a "PadN" option to keep the header aligned
+    /// to 8 bytes). This is intended for hop-by-hop options headers.
+    ///
+    /// If the payload can not be extended by 8 bytes without exceeding
+    /// `Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN` a `ValueError` is returned
+    /// and the header is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv6RawExtensionHeader, ip_number, ipv6_option};
+    ///
+    /// let mut hop_by_hop = Ipv6RawExtensionHeader::new_raw(
+    ///     ip_number::IPV6_ICMP,
+    ///     &[ipv6_option::TYPE_PADN, 4, 0, 0, 0, 0]
+    /// ).unwrap();
+    /// hop_by_hop.add_router_alert(ipv6_option::ROUTER_ALERT_MLD).unwrap();
+    /// assert_eq!(Some(ipv6_option::ROUTER_ALERT_MLD), hop_by_hop.router_alert());
+    /// assert_eq!(16, hop_by_hop.header_len());
+    /// ```
+    pub fn add_router_alert(&mut self, value: u16) -> Result<(), ValueError> {
+        use ipv6_option::*;
+        let start = self.payload().len();
+        let end = start + 8;
+        if end > Self::MAX_PAYLOAD_LEN {
+            return Err(ValueError::Ipv6ExtensionPayloadTooLarge(end));
+        }
+        let value_be = value.to_be_bytes();
+        // the "PadN" option at the end keeps the header aligned to 8 bytes
+        self.payload_buffer[start..end].copy_from_slice(
+            &[TYPE_ROUTER_ALERT, LEN_ROUTER_ALERT, value_be[0], value_be[1], TYPE_PADN, 2, 0, 0]
+        );
+        self.header_length += 1;
+        Ok(())
+    }
+
+    /// Returns the value of the "Router Alert" option if the header contains one.
+    ///
+    /// Note that this method only makes sense for hop-by-hop options
+    /// headers, as the payload is interpreted as a list of options.
+    pub fn router_alert(&self) -> Option<u16> {
+        find_router_alert(self.payload())
+    }
+
     /// Read an Ipv6ExtensionHeader from a slice and return the header & unused parts of the slice.
     pub fn from_slice(slice: &[u8]) -> Result<(Ipv6RawExtensionHeader, &[u8]), ReadError> {
         let s = Ipv6RawExtensionHeaderSlice::from_slice(slice)?;
@@ -135,6 +249,7 @@ impl Ipv6RawExtensionHeader {
     }
 
     /// Read an fragment header from the current reader position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<Ipv6RawExtensionHeader, ReadError> {
         let (next_header, header_length) = {
             let mut d : [u8;2] = [0;2];
@@ -154,12 +269,27 @@ impl Ipv6RawExtensionHeader {
     }
 
     /// Writes a given IPv6 extension header to the current position.
+    #[cfg(feature = "std")]
     pub fn write<W: io::Write + Sized>(&self, writer: &mut W) -> Result<(), WriteError> {
         writer.write_all(&[self.next_header, self.header_length])?;
         writer.write_all(self.payload())?;
         Ok(())
     }
 
+    /// Writes the extension header to the start of the given slice. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        //length check
+        let header_len = self.header_len();
+        if slice.len() < header_len {
+            Err(WriteError::SliceTooSmall(header_len))
+        } else {
+            slice[0] = self.next_header;
+            slice[1] = self.header_length;
+            slice[2..header_len].copy_from_slice(self.payload());
+            Ok(&mut slice[header_len..])
+        }
+    }
+
     ///Length of the header in bytes.
     pub fn header_len(&self) -> usize {
         2 + (6 + usize::from(self.header_length)*8)
@@ -276,6 +406,14 @@ impl<'a> Ipv6RawExtensionHeaderSlice<'a> {
         }
     }
 
+    /// Returns the value of the "Router Alert" option if the header contains one.
+    ///
+    /// Note that this method only makes sense for hop-by-hop options
+    /// headers, as the payload is interpreted as a list of options.
+    pub fn router_alert(&self) -> Option<u16> {
+        find_router_alert(self.payload())
+    }
+
     /// Convert the slice to an [Ipv6RawExtensionHeader].
     ///
     /// Decode some of the fields and copy the results to a 
@@ -7,3 +7,4 @@ pub mod ipv6;
 pub mod ipv6_extensions;
 pub mod ipv6_raw_extension;
 pub mod ipv6_fragment;
+pub mod gre;
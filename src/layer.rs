@@ -0,0 +1,73 @@
+use super::*;
+
+/// Layers (headers) of a packet that are decoded by etherparse.
+///
+/// Used to report in which layer a read error occurred (see
+/// [`ReadErrorContext`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Layer {
+    /// Link layer header of a capture that is not an Ethernet II header
+    /// (e.g. the BSD loopback or the Linux cooked capture header).
+    LinkHeader,
+    /// Ethernet II header.
+    Ethernet2Header,
+    /// Single or double vlan header.
+    VlanHeader,
+    /// IP header with a not yet known version.
+    IpHeader,
+    /// IPv4 header (without extensions).
+    Ipv4Header,
+    /// IPv4 extension headers (authentication header).
+    Ipv4Extensions,
+    /// IPv6 header (without extensions).
+    Ipv6Header,
+    /// IPv6 extension headers.
+    Ipv6Extensions,
+    /// ICMPv4 header.
+    Icmpv4,
+    /// ICMPv6 header.
+    Icmpv6,
+    /// IGMP message.
+    Igmp,
+    /// SCTP common header.
+    SctpHeader,
+    /// UDP header.
+    UdpHeader,
+    /// TCP header.
+    TcpHeader,
+    /// UDP-Lite header.
+    UdpLiteHeader,
+    /// DCCP header.
+    DccpHeader,
+}
+
+impl Layer {
+    /// Returns a short human readable name of the layer.
+    pub fn name(&self) -> &'static str {
+        use Layer::*;
+        match self {
+            LinkHeader => "link header",
+            Ethernet2Header => "Ethernet 2 header",
+            VlanHeader => "VLAN header",
+            IpHeader => "IP header",
+            Ipv4Header => "IPv4 header",
+            Ipv4Extensions => "IPv4 extension headers",
+            Ipv6Header => "IPv6 header",
+            Ipv6Extensions => "IPv6 extension headers",
+            Icmpv4 => "ICMPv4 packet",
+            Icmpv6 => "ICMPv6 packet",
+            Igmp => "IGMP message",
+            SctpHeader => "SCTP header",
+            UdpHeader => "UDP header",
+            TcpHeader => "TCP header",
+            UdpLiteHeader => "UDP-Lite header",
+            DccpHeader => "DCCP header",
+        }
+    }
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
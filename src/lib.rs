@@ -6,8 +6,12 @@
 //! * IPv4
 //! * IPv6 (supporting the most common extension headers, but not all)
 //! * UDP
+//! * UDP-Lite
 //! * TCP
-//! * ICMP & ICMPv6 (not all message types are supported)
+//! * DCCP
+//! * ICMP & ICMPv6 (not all message types are supported, including RFC 4884 extension objects)
+//! * IGMP (IGMPv1, IGMPv2 & IGMPv3)
+//! * SCTP (common header & the most common chunk types)
 //! 
 //! # Usage
 //! 
@@ -18,6 +22,21 @@
 //! etherparse = "0.13"
 //! ```
 //!
+//! ## `no_std`
+//!
+//! Etherparse can also be used without the standard library (only `core` & `alloc`
+//! are required) by disabling the default `std` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! etherparse = { version = "0.13", default-features = false }
+//! ```
+//!
+//! Without the `std` feature the slice based parsing & writing functions, the error
+//! types and the filter modules are available. The `std::io` based `read` & `write`
+//! methods, the `IoError` error variants and the modules [`pcap`], [`pcapng`], [`flow_table`],
+//! [`tcp_tracking`] & [`tcp_reassembly`] require the `std` feature.
+//!
 //! # What is etherparse?
 //! Etherparse is intended to provide the basic network parsing functions that allow for easy analysis, transformation or generation of recorded network data.
 //! 
@@ -103,6 +122,9 @@
 //! * [`PacketHeaders::from_ether_type`] for parsing a slice starting after an Ethernet II header
 //! * [`PacketHeaders::from_ip_slice`] for parsing from an IPv4 or IPv6 downwards
 //!
+//! ## Keeping partial results on errors
+//! By default slicing & decoding returns an error as soon as one header is malformed or truncated. If you are also interested in the headers before the malformed one (e.g. for truncated captures or the packet embedded in an ICMP error message) use the lax versions [`LaxSlicedPacket`] & [`LaxPacketHeaders`]. These return all successfully parsed headers together with the error and a [`ReadErrorContext`] describing where parsing stopped (layer, offset, required & available length and the invalid field). The strict functions return the same context together with the error as a [`PacketReadError`].
+//!
 //! ## Manually slicing & parsing packets
 //! It is also possible to manually slice & parse a packet. For each header type there is are metods that create a slice or struct from a memory slice. 
 //! 
@@ -119,9 +141,13 @@
 //! * [`IpAuthenticationHeaderSlice::from_slice`]
 //! * [`Ipv6FragmentHeaderSlice::from_slice`]
 //! * [`UdpHeaderSlice::from_slice`]
+//! * [`UdpLiteHeaderSlice::from_slice`]
+//! * [`DccpHeaderSlice::from_slice`]
 //! * [`TcpHeaderSlice::from_slice`]
 //! * [`Icmpv4Slice::from_slice`]
 //! * [`Icmpv6Slice::from_slice`]
+//! * [`IgmpSlice::from_slice`]
+//! * [`SctpHeaderSlice::from_slice`]
 //!
 //! And for deserialization into the corresponding header structs have a look at:
 //!
@@ -137,9 +163,13 @@
 //! * [`IpAuthenticationHeader::read`] & [`IpAuthenticationHeader::from_slice`]
 //! * [`Ipv6FragmentHeader::read`] & [`Ipv6FragmentHeader::from_slice`]
 //! * [`UdpHeader::read`] & [`UdpHeader::from_slice`]
+//! * [`UdpLiteHeader::read`] & [`UdpLiteHeader::from_slice`]
+//! * [`DccpHeader::read`] & [`DccpHeader::from_slice`]
 //! * [`TcpHeader::read`] & [`TcpHeader::from_slice`]
 //! * [`Icmpv4Header::read`] & [`Icmpv4Header::from_slice`]
 //! * [`Icmpv6Header::read`] & [`Icmpv6Header::from_slice`]
+//! * [`IgmpHeader::read`] & [`IgmpHeader::from_slice`]
+//! * [`SctpHeader::read`] & [`SctpHeader::from_slice`]
 //!
 //! # How to generate fake packet data?
 //! ## Packet Builder
@@ -174,6 +204,35 @@
 //! 
 //! Check out the [PacketBuilder documentation](struct.PacketBuilder.html) for more informations.
 //! 
+//! ### Writing into a buffer without allocations
+//! Instead of writing to an `std::io::Write` the packet can also be serialized directly
+//! into a caller provided slice (also available in `no_std` builds). The number of written
+//! bytes is returned and an error is returned if the slice is too small (no panic):
+//!
+//! ```
+//! use etherparse::{PacketBuilder, WriteError};
+//!
+//! let builder = PacketBuilder::
+//!     ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!    .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!    .udp(21, 1234);
+//!
+//! let payload = [1,2,3,4,5,6,7,8];
+//! let mut buffer = [0u8;1500];
+//! let len = builder.write_to_slice(&mut buffer, &payload).unwrap();
+//! assert_eq!(len, 14 + 20 + 8 + payload.len());
+//!
+//! // a too small buffer results in an error
+//! let builder = PacketBuilder::
+//!     ipv4([192,168,1,1], [192,168,1,2], 20)
+//!    .udp(21, 1234);
+//! let mut buffer = [0u8;10];
+//! assert!(matches!(
+//!     builder.write_to_slice(&mut buffer, &payload),
+//!     Err(WriteError::SliceTooSmall(36))
+//! ));
+//! ```
+//!
 //! ## Manually serialising each header
 //! Alternativly it is possible to manually build a packet ([example](https://github.com/JulianSchmid/etherparse/blob/0.10.1/examples/write_ipv4_udp.rs)). Generally each struct representing a header has a "write" method that allows it to be serialized. These write methods sometimes automatically calculate checksums and fill them in. In case this is unwanted behavior (e.g. if you want to generate a packet with an invalid checksum), it is also possible to call a "write_raw" method that will simply serialize the data without doing checksum calculations.
 //! 
@@ -191,9 +250,54 @@
 //! * [`IpAuthenticationHeader::write`]
 //! * [`Ipv6FragmentHeader::write`]
 //! * [`UdpHeader::write`]
+//! * [`UdpLiteHeader::write`]
+//! * [`DccpHeader::write`]
 //! * [`TcpHeader::write`]
 //! * [`Icmpv4Header::write`]
 //! * [`Icmpv6Header::write`]
+//! * [`IgmpHeader::write`]
+//! * [`SctpHeader::write`]
+//!
+//! Each of these headers also has a `write_to_slice` method (e.g. [`Ipv4Header::write_to_slice`])
+//! that serializes the header to the start of a slice and returns the unused rest of the slice.
+//!
+//! # How to modify existing frames?
+//! An [`Encapsulation`] writes Ethernet II, vlan, ip & tunnel (UDP or [`GreHeader`]) headers
+//! into the free space in front of a payload that is already located in a buffer, without
+//! copying the payload. Vlan tags, MPLS labels & ip tunnel headers of an Ethernet II frame
+//! can be pushed & popped in place via the [`EditableFrame`] methods (e.g.
+//! [`EditableFrame::pop_vlan`]), which are implemented for `Vec<u8>` and [`HeadroomFrame`].
+//!
+//! # How to reassemble tcp streams?
+//! The [`tcp_reassembly::TcpReassembler`] tracks the tcp connections in sliced packets
+//! and delivers the payload of both directions as ordered byte streams (see the
+//! [`tcp_reassembly`] module for an example). The states of tcp connections can be
+//! followed with the [`tcp_tracking::TcpConnectionTracker`].
+//!
+//! # How to account traffic per flow?
+//! Every sliced packet can be converted to a [`FlowKey`]. The [`flow_table::FlowTable`]
+//! uses these keys to aggregate NetFlow like statistics, which can be exported as
+//! IPFIX or NetFlow v9 messages via a [`flow_table::FlowExporter`].
+//!
+//! # How to filter packets?
+//! A [`packet_filter::FilterExpression`] combines conditions on networks, ports, protocols,
+//! flags & header bytes with `And`, `Or` & `Not` and can be evaluated against sliced packets.
+//! tcpdump like filter expressions (e.g. `tcp port 443 and not net 10.0.0.0/8`) can be
+//! compiled to such an expression via [`pcap_filter::parse`]. Expressions can also be
+//! compiled to classic BPF programs (e.g. for `SO_ATTACH_FILTER`) via [`bpf::compile`].
+//! Layer filters ([`packet_filter::Filter`]) can also be evaluated against [`PacketHeaders`]
+//! or directly against the raw packet data, decoding only the headers required for the result.
+//!
+//! # How to read & write capture files?
+//! The [`pcap`] & [`pcapng`] modules contain readers & writers for pcap and pcapng files.
+//! The link type of the packets can be used to slice them via [`SlicedPacket::from_link_type`].
+//!
+//! # How to print packets?
+//! [`SlicedPacket::summary`] & [`PacketHeaders::summary`] return tcpdump like one line
+//! summaries and [`SlicedPacket::dissection`] & [`PacketHeaders::dissection`] a Wireshark
+//! like tree of all header fields (see the [`packet_format`] module). The bytes of a
+//! packet can be dumped with the layer & field name of each byte range via
+//! [`hex_dump::AnnotatedHexDump`] (also for packets that can only be partially decoded).
 //!
 //! # Roadmap
 //! * Documentation
@@ -227,7 +331,20 @@
 //! * ICMP Router Discovery Messages [RFC 1256](https://datatracker.ietf.org/doc/html/rfc1256)
 //! * [Internet Control Message Protocol version 6 (ICMPv6) Parameters](https://www.iana.org/assignments/icmpv6-parameters/icmpv6-parameters.xhtml)
 //! * Multicast Listener Discovery (MLD) for IPv6 [RFC 2710](https://datatracker.ietf.org/doc/html/rfc2710)
+//! * Multicast Listener Discovery Version 2 (MLDv2) for IPv6 [RFC 3810](https://datatracker.ietf.org/doc/html/rfc3810)
+//! * IPv6 Router Alert Option [RFC 2711](https://datatracker.ietf.org/doc/html/rfc2711)
 //! * Neighbor Discovery for IP version 6 (IPv6) [RFC 4861](https://datatracker.ietf.org/doc/html/rfc4861)
+//! * Host Extensions for IP Multicasting [RFC 1112](https://datatracker.ietf.org/doc/html/rfc1112)
+//! * Internet Group Management Protocol, Version 2 [RFC 2236](https://datatracker.ietf.org/doc/html/rfc2236)
+//! * Internet Group Management Protocol, Version 3 [RFC 3376](https://datatracker.ietf.org/doc/html/rfc3376)
+//! * IP Router Alert Option [RFC 2113](https://datatracker.ietf.org/doc/html/rfc2113)
+//! * Extended ICMP to Support Multi-Part Messages [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884)
+//! * ICMP Extensions for Multiprotocol Label Switching [RFC 4950](https://datatracker.ietf.org/doc/html/rfc4950)
+//! * Extending ICMP for Interface and Next-Hop Identification [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837)
+//! * Stream Control Transmission Protocol [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260)
+//! * Internet Protocol Small Computer System Interface (iSCSI) (CRC32c) [RFC 3720](https://datatracker.ietf.org/doc/html/rfc3720#appendix-B.4)
+//! * The Lightweight User Datagram Protocol (UDP-Lite) [RFC 3828](https://datatracker.ietf.org/doc/html/rfc3828)
+//! * Datagram Congestion Control Protocol (DCCP) [RFC 4340](https://datatracker.ietf.org/doc/html/rfc4340)
 
 // # Reason for 'bool_comparison' disable:
  //
@@ -247,12 +364,24 @@
    clippy::bool_comparison,
  )]
 
+// Without the "std" feature only `core` & `alloc` are used.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::{String, ToString}, vec::Vec};
+
+#[cfg(feature = "std")]
 use std::io;
-use std::fmt;
+use core::fmt;
+#[cfg(feature = "std")]
 use std::error::Error;
 
 mod link;
 pub use crate::link::LinkSlice;
+pub use crate::link::link_type;
 pub use crate::link::ethernet::*;
 pub use crate::link::vlan_tagging::*;
 
@@ -265,13 +394,20 @@ pub use crate::internet::ipv6::*;
 pub use crate::internet::ipv6_extensions::*;
 pub use crate::internet::ipv6_raw_extension::*;
 pub use crate::internet::ipv6_fragment::*;
+pub use crate::internet::gre::*;
 
 mod transport;
+pub use crate::transport::dccp_impl::*;
 pub use crate::transport::icmp::*;
+pub use crate::transport::icmp_extension_impl::*;
 pub use crate::transport::icmpv4_impl::*;
 pub use crate::transport::icmpv6_impl::*;
+pub use crate::transport::igmp_impl::*;
+pub use crate::transport::mptcp_impl::*;
+pub use crate::transport::sctp_impl::*;
 pub use crate::transport::tcp::*;
 pub use crate::transport::udp::*;
+pub use crate::transport::udp_lite::*;
 pub use crate::transport::TransportHeader;
 
 /// Helpers for calculating checksums.
@@ -280,14 +416,51 @@ pub mod checksum;
 mod packet_builder;
 pub use crate::packet_builder::*;
 
+mod encapsulation;
+pub use crate::encapsulation::*;
+
+mod frame_editing;
+pub use crate::frame_editing::*;
+
+mod layer;
+pub use crate::layer::*;
+
 mod packet_decoder;
 pub use crate::packet_decoder::*;
 
 mod packet_slicing;
 pub use crate::packet_slicing::*;
 
+mod flow_key;
+pub use crate::flow_key::*;
+
+mod header_field;
+pub use crate::header_field::*;
+
 pub mod packet_filter;
 
+pub mod pcap_filter;
+pub mod bpf;
+
+#[cfg(feature = "std")]
+pub mod tcp_reassembly;
+
+#[cfg(feature = "std")]
+pub mod tcp_tracking;
+
+#[cfg(feature = "std")]
+pub mod flow_table;
+
+#[cfg(feature = "std")]
+pub mod pcap;
+
+#[cfg(feature = "std")]
+pub mod pcapng;
+
+pub mod packet_format;
+
+pub mod hex_dump;
+
 ///Contains the size when serialized.
 pub trait SerializedSize {
     const SERIALIZED_SIZE: usize;
@@ -297,6 +470,7 @@ pub trait SerializedSize {
 #[derive(Debug)]
 pub enum ReadError {
     ///Whenever an std::io::Error gets triggerd during a write it gets forwarded via this enum value.
+    #[cfg(feature = "std")]
     IoError(std::io::Error),
     ///Error when an unexpected end of a slice was reached even though more data was expected to be present (expected minimum size as argument).
     UnexpectedEndOfSlice(usize),
@@ -328,10 +502,57 @@ pub enum ReadError {
     /// This error can be triggered by
     /// * `Icmpv6Slice::from_slice`
     Icmpv6PacketTooBig(usize),
+    /// Error when the version field of an ICMP extension structure (RFC 4884) is not 2.
+    /// The value is the version that was received.
+    IcmpExtensionUnexpectedVersion(u8),
+    /// Error when the length field of an ICMP extension object (RFC 4884) is smaller
+    /// then the object header itself (4).
+    IcmpExtensionObjectLengthTooSmall(u16),
+    /// Error when the length field of a SCTP chunk is smaller then the
+    /// chunk header itself (4).
+    SctpChunkLengthTooSmall(u16),
+    /// Error when the length field of a SCTP chunk parameter or error cause
+    /// is smaller then the parameter header itself (4).
+    SctpParameterLengthTooSmall(u16),
+    /// Error when the data offset field of a DCCP header is smaller then the
+    /// fixed part of the header (generic header & packet type specific fields).
+    DccpDataOffsetTooSmall(u8),
+    /// Error when the length field of a DCCP option is smaller then 2 (the
+    /// type & length bytes themself).
+    DccpOptionLengthTooSmall(u8),
+    /// Error when the version field of a GRE header is not 0 (other
+    /// versions such as the enhanced GRE header used by PPTP are not supported).
+    GreUnsupportedVersion(u8),
+    /// Error when the magic number at the start of a pcap file is not one of the
+    /// known values (microsecond or nanosecond timestamps in either byte order).
+    PcapUnknownMagicNumber(u32),
+    /// Error when the captured length of a packet in a pcap file is bigger then
+    /// the allowed maximum (the snapshot length or [`pcap::MAX_SNAPLEN`]
+    /// whichever is bigger).
+    PcapCapturedLengthTooBig{ captured_len: u32, max_len: u32 },
+    /// Error when the byte order magic of a pcapng section header block is not
+    /// 0x1a2b3c4d in either byte order.
+    PcapngByteOrderMagicUnknown(u32),
+    /// Error when the length of a pcapng block is smaller then the block content,
+    /// not a multiple of 4, bigger then [`pcapng::MAX_BLOCK_LEN`] or does not
+    /// match the length at the end of the block.
+    PcapngBlockLengthBad(u32),
+    /// Error when the length of a pcapng option (or name resolution record)
+    /// exceeds the block it is contained in.
+    PcapngOptionLengthTooBig(u16),
+    /// Error when a pcapng file does not start with a section header block.
+    /// The value is the block type of the first block.
+    PcapngFirstBlockNotSectionHeader(u32),
+    /// Error when a pcapng packet references an interface that was not described
+    /// in the current section.
+    PcapngUnknownInterfaceId(u32),
 }
 
 impl ReadError {
-    ///Adds an offset value to the UnexpectedEndOfSlice error.
+    /// Adds an offset value to the slice lengths contained in the error.
+    ///
+    /// Only `UnexpectedEndOfSlice` & `UnexpectedLenOfSlice` contain lengths
+    /// of the read slice, all other errors are returned unchanged.
     pub fn add_slice_offset(self, offset: usize) -> ReadError {
         use crate::ReadError::*;
         match self {
@@ -343,6 +564,7 @@ impl ReadError {
 
     /// Returns the `std::io::Error` value if the `ReadError` is an `IoError`.
     /// Otherwise `None is returned.
+    #[cfg(feature = "std")]
     pub fn io_error(self) -> Option<std::io::Error> {
         match self {
             ReadError::IoError(value) => Some(value),
@@ -356,6 +578,30 @@ impl ReadError {
             _ => None
         }
     }
+
+    /// Returns the header field that contained the invalid value (if the
+    /// error was caused by the value of a header field).
+    pub fn field(&self) -> Option<ErrorField> {
+        use crate::ReadError::*;
+        match self {
+            DoubleVlanOuterNonVlanEtherType(_) => Some(ErrorField::VlanTagEtherType),
+            IpUnsupportedVersion(_) |
+            Ipv4UnexpectedVersion(_) |
+            Ipv6UnexpectedVersion(_) => Some(ErrorField::IpVersion),
+            Ipv4HeaderLengthBad(_) => Some(ErrorField::Ipv4Ihl),
+            Ipv4TotalLengthTooSmall(_) => Some(ErrorField::Ipv4TotalLength),
+            IpAuthenticationHeaderTooSmallPayloadLength(_) => Some(ErrorField::IpAuthPayloadLength),
+            TcpDataOffsetTooSmall(_) => Some(ErrorField::TcpDataOffset),
+            IcmpExtensionUnexpectedVersion(_) => Some(ErrorField::IcmpExtensionVersion),
+            IcmpExtensionObjectLengthTooSmall(_) => Some(ErrorField::IcmpExtensionObjectLength),
+            SctpChunkLengthTooSmall(_) => Some(ErrorField::SctpChunkLength),
+            SctpParameterLengthTooSmall(_) => Some(ErrorField::SctpParameterLength),
+            DccpDataOffsetTooSmall(_) => Some(ErrorField::DccpDataOffset),
+            DccpOptionLengthTooSmall(_) => Some(ErrorField::DccpOptionLength),
+            GreUnsupportedVersion(_) => Some(ErrorField::GreVersion),
+            _ => None
+        }
+    }
 }
 
 impl fmt::Display for ReadError {
@@ -363,6 +609,7 @@ impl fmt::Display for ReadError {
         use ReadError::*;
 
         match self {
+            #[cfg(feature = "std")]
             IoError(err) => err.fmt(f),
             UnexpectedEndOfSlice(expected_minimum_size) => { // usize
                 write!(f, "ReadError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", expected_minimum_size)
@@ -402,11 +649,54 @@ impl fmt::Display for ReadError {
             },
             Icmpv6PacketTooBig(size) => {
                 write!(f, "ReadError: ICMPv6 packet length {} is bigger then can be represented in an u32.", size)
+            },
+            IcmpExtensionUnexpectedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unexpected ICMP extension structure version. Expected version 2 but the extension header contained the version number {}.", version_number)
+            },
+            IcmpExtensionObjectLengthTooSmall(length) => { //u16
+                write!(f, "ReadError: ICMP extension object length too small. The length value {} in the extension object is smaller then the object header itself.", length)
+            },
+            SctpChunkLengthTooSmall(length) => { //u16
+                write!(f, "ReadError: SCTP chunk length too small. The length value {} in the chunk is smaller then the chunk header itself.", length)
+            },
+            SctpParameterLengthTooSmall(length) => { //u16
+                write!(f, "ReadError: SCTP parameter length too small. The length value {} in the parameter is smaller then the parameter header itself.", length)
+            },
+            DccpDataOffsetTooSmall(data_offset) => { //u8
+                write!(f, "ReadError: DCCP data offset too small. The data offset value {} in the DCCP header is smaller then the fixed part of the header.", data_offset)
+            },
+            DccpOptionLengthTooSmall(length) => { //u8
+                write!(f, "ReadError: DCCP option length too small. The length value {} in the option is smaller then the minimum of 2.", length)
+            },
+            GreUnsupportedVersion(version) => { //u8
+                write!(f, "ReadError: Unsupported GRE version {} (only version 0 is supported).", version)
+            },
+            PcapUnknownMagicNumber(magic_number) => { //u32
+                write!(f, "ReadError: Unknown pcap magic number 0x{:08x}.", magic_number)
+            },
+            PcapCapturedLengthTooBig{ captured_len, max_len } => {
+                write!(f, "ReadError: Captured length {} of the pcap packet is bigger then the allowed maximum of {} bytes.", captured_len, max_len)
+            },
+            PcapngByteOrderMagicUnknown(magic) => { //u32
+                write!(f, "ReadError: Unknown pcapng byte order magic 0x{:08x} in the section header block.", magic)
+            },
+            PcapngBlockLengthBad(length) => { //u32
+                write!(f, "ReadError: Bad pcapng block length. The block length {} is not a multiple of 4, too big or does not match the block content.", length)
+            },
+            PcapngOptionLengthTooBig(length) => { //u16
+                write!(f, "ReadError: The length {} of a pcapng option exceeds the block containing it.", length)
+            },
+            PcapngFirstBlockNotSectionHeader(block_type) => { //u32
+                write!(f, "ReadError: Expected a pcapng section header block at the start of the file but found a block with type 0x{:08x}.", block_type)
+            },
+            PcapngUnknownInterfaceId(interface_id) => { //u32
+                write!(f, "ReadError: A pcapng packet references the undescribed interface with id {}.", interface_id)
             }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for ReadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
@@ -416,15 +706,106 @@ impl Error for ReadError {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for ReadError {
     fn from(err: std::io::Error) -> ReadError {
         ReadError::IoError(err)
     }
 }
 
+/// Location & lengths of a [`ReadError`] in a packet.
+///
+/// Returned together with the error by the slicing & decoding functions
+/// (as part of a [`PacketReadError`] by the strict functions like
+/// [`SlicedPacket::from_ethernet`] & [`PacketHeaders::from_ethernet_slice`]
+/// and in the `stop_err` field of [`LaxSlicedPacket`] & [`LaxPacketHeaders`]).
+///
+/// The lengths in the context are relative to `offset` while the lengths in the
+/// returned error are relative to the start of the packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ReadErrorContext {
+    /// Layer (header) that could not be read.
+    pub layer: Layer,
+    /// Offset of the start of the layer in the packet (in bytes).
+    pub offset: usize,
+    /// Number of bytes (starting at `offset`) that were required to read the
+    /// layer (only set for length errors).
+    pub required_len: Option<usize>,
+    /// Number of bytes (starting at `offset`) that were available.
+    pub available_len: usize,
+    /// Header field that contained an invalid value (only set for value errors).
+    pub field: Option<ErrorField>,
+}
+
+impl ReadErrorContext {
+    /// Creates the context of an error that occurred while reading the layer
+    /// starting at `offset` (the lengths in `err` are relative to the start of the layer).
+    pub(crate) fn new(err: &ReadError, layer: Layer, offset: usize, available_len: usize) -> ReadErrorContext {
+        use crate::ReadError::*;
+        ReadErrorContext {
+            layer,
+            offset,
+            required_len: match err {
+                UnexpectedEndOfSlice(value) => Some(*value),
+                UnexpectedLenOfSlice{ expected, actual: _ } => Some(*expected),
+                _ => None
+            },
+            available_len,
+            field: err.field(),
+        }
+    }
+}
+
+impl fmt::Display for ReadErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {} ({} bytes available", self.layer, self.offset, self.available_len)?;
+        if let Some(required_len) = self.required_len {
+            write!(f, ", {} bytes required", required_len)?;
+        }
+        if let Some(field) = self.field {
+            write!(f, ", invalid field {}", field)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// [`ReadError`] together with the location at which the slicing or decoding
+/// of a packet stopped.
+///
+/// Returned by the strict slicing & decoding functions (e.g. [`SlicedPacket::from_ethernet`]
+/// & [`PacketHeaders::from_ethernet_slice`]). Can be converted into a plain
+/// [`ReadError`] (e.g. via the `?` operator).
+#[derive(Debug)]
+pub struct PacketReadError {
+    /// Error that stopped the slicing or decoding (lengths are relative to the start of the packet).
+    pub error: ReadError,
+    /// Layer, offset, lengths & field of the error.
+    pub context: ReadErrorContext,
+}
+
+impl fmt::Display for PacketReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in {}", self.error, self.context)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PacketReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<PacketReadError> for ReadError {
+    fn from(err: PacketReadError) -> ReadError {
+        err.error
+    }
+}
+
 ///Errors that can occur when writing.
 #[derive(Debug)]
 pub enum WriteError {
+    #[cfg(feature = "std")]
     IoError(std::io::Error),
     ///Error in the data that was given to write
     ValueError(ValueError),
@@ -435,6 +816,7 @@ pub enum WriteError {
 impl WriteError {
     /// Returns the `std::io::Error` value if the `WriteError` is an `IoError`.
     /// Otherwise `None is returned.
+    #[cfg(feature = "std")]
     pub fn io_error(self) -> Option<std::io::Error> {
         match self {
             WriteError::IoError(value) => Some(value),
@@ -465,6 +847,7 @@ impl From<ValueError> for WriteError {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for WriteError {
     fn from(err: std::io::Error) -> WriteError {
         WriteError::IoError(err)
@@ -475,6 +858,7 @@ impl fmt::Display for WriteError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use WriteError::*;
         match self {
+            #[cfg(feature = "std")]
             IoError(err) => err.fmt(f),
             ValueError(err) => {
                 write!(f, "ValueError: {}", err)
@@ -486,6 +870,7 @@ impl fmt::Display for WriteError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for WriteError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         use WriteError::*;
@@ -534,10 +919,37 @@ pub enum ValueError {
     U16TooLarge{value: u16, max: u16, field: ErrorField},
     /// Error when a u32 field in a header has a larger value then supported.
     U32TooLarge{value: u32, max: u32, field: ErrorField},
+    /// Error when a u64 field in a header has a larger value then supported.
+    U64TooLarge{value: u64, max: u64, field: ErrorField},
     /// Error when an Icmpv6 payload is found in an IPv4 packet.
     Icmpv6InIpv4,
+    /// Error when the UDP-Lite checksum coverage is smaller then the UDP-Lite
+    /// header (but not zero) or bigger then the UDP-Lite packet.
+    UdpLiteChecksumCoverageBad(u16),
+    /// Error when the DCCP header & payload are bigger then what can be
+    /// represented in the length field of the checksum pseudo header.
+    DccpLengthTooLarge(usize),
+    /// Error when the DCCP checksum coverage covers more application data then present.
+    DccpChecksumCoverageBad(u8),
+    /// Error when the DCCP options length is not a multiple of 4 or too big to be
+    /// represented in the data offset field.
+    DccpOptionsLengthBad(usize),
+    /// Error when a packet should be written for a pcapng interface id that was not added.
+    PcapngUnknownInterfaceId(u32),
+    /// Error when the value of a pcapng option is too big to be represented in the
+    /// option length field.
+    PcapngOptionTooLarge(usize),
+    /// Error when a header field is accessed by a name that does not exist in
+    /// the field table of the header.
+    HeaderFieldUnknown(String),
+    /// Error when a value is too big to be stored in the bits of a header field.
+    HeaderFieldValueTooLarge{ name: &'static str, value: u128, bit_width: usize },
+    /// Error when the slice is too short to contain the header field that
+    /// should be written.
+    HeaderFieldOutOfSlice{ name: &'static str, required_len: usize, actual_len: usize },
 }
 
+#[cfg(feature = "std")]
 impl Error for ValueError {
 
 }
@@ -594,15 +1006,45 @@ impl fmt::Display for ValueError {
             U32TooLarge{value, max, field} => {
                 write!(f, "The value {} of the field '{}' is larger then the allowed maximum of {}.", value, field, max)
             },
+            U64TooLarge{value, max, field} => {
+                write!(f, "The value {} of the field '{}' is larger then the allowed maximum of {}.", value, field, max)
+            },
             Icmpv6InIpv4 => {
                 write!(f, "ICMPv6 packet can not be combined with IPv4 headers.")
             },
+            UdpLiteChecksumCoverageBad(coverage) => { //u16
+                write!(f, "UDP-Lite checksum coverage bad. The checksum coverage ({} bytes) is either smaller then the UDP-Lite header or bigger then the UDP-Lite packet.", coverage)
+            },
+            DccpLengthTooLarge(length) => { //usize
+                write!(f, "DCCP length too large. The DCCP packet length ({} bytes) is larger then what is supported.", length)
+            },
+            DccpChecksumCoverageBad(coverage) => { //u8
+                write!(f, "DCCP checksum coverage bad. The checksum coverage value {} covers more application data then present.", coverage)
+            },
+            DccpOptionsLengthBad(options_len) => { //usize
+                write!(f, "Bad DCCP options length. The DCCP options length ({} bytes) is either not a multiple of 4 bytes or too big to be represented in the data offset field.", options_len)
+            },
+            PcapngUnknownInterfaceId(interface_id) => { //u32
+                write!(f, "Unknown pcapng interface id {}. No interface with this id was added to the writer.", interface_id)
+            },
+            PcapngOptionTooLarge(len) => { //usize
+                write!(f, "pcapng option too large. The option value ({} bytes) is too big to be represented in the option length field.", len)
+            },
+            HeaderFieldUnknown(name) => { //String
+                write!(f, "Unknown header field '{}'. No field with this name exists in the field table of the header.", name)
+            },
+            HeaderFieldValueTooLarge{ name, value, bit_width } => {
+                write!(f, "The value {} is too large for the header field '{}' ({} bits).", value, name, bit_width)
+            },
+            HeaderFieldOutOfSlice{ name, required_len, actual_len } => {
+                write!(f, "The header field '{}' requires a slice with at least {} bytes but the given slice only has {} bytes.", name, required_len, actual_len)
+            },
         }
     }
 }
 
-///Fields that can produce errors when serialized.
-#[derive(Debug, PartialEq, Eq, Clone)]
+///Fields that can produce errors when serialized or read.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum ErrorField {
     Ipv4PayloadLength,
     Ipv4Dscp,
@@ -615,6 +1057,40 @@ pub enum ErrorField {
     VlanTagPriorityCodePoint,
     ///VlanTaggingHeader.vlan_identifier
     VlanTagVlanId,
+    ///DccpHeader.ccval
+    DccpCcVal,
+    ///DccpHeader.checksum_coverage
+    DccpChecksumCoverage,
+    ///DccpHeader.sequence_number
+    DccpSequenceNumber,
+    ///Acknowledgement number in DccpHeader.packet_type
+    DccpAcknowledgementNumber,
+    ///Version field of an IPv4 or IPv6 header
+    IpVersion,
+    ///SingleVlanHeader.ether_type
+    VlanTagEtherType,
+    ///Ipv4Header.ihl (internet header length)
+    Ipv4Ihl,
+    ///Ipv4Header.total_len
+    Ipv4TotalLength,
+    ///IpAuthenticationHeader payload length
+    IpAuthPayloadLength,
+    ///TcpHeader.data_offset
+    TcpDataOffset,
+    ///IcmpExtensionsSlice.version
+    IcmpExtensionVersion,
+    ///IcmpExtensionObjectSlice.length
+    IcmpExtensionObjectLength,
+    ///Length of a SCTP chunk
+    SctpChunkLength,
+    ///Length of a SCTP chunk parameter or error cause
+    SctpParameterLength,
+    ///DccpHeader.data_offset
+    DccpDataOffset,
+    ///Length of a DCCP option
+    DccpOptionLength,
+    ///GreHeader version
+    GreVersion,
 }
 
 impl fmt::Display for ErrorField {
@@ -628,7 +1104,24 @@ impl fmt::Display for ErrorField {
             Ipv6FlowLabel => write!(f, "Ipv6Header.flow_label"),
             Ipv6FragmentOffset => write!(f, "Ipv6FragmentHeader.fragment_offset"),
             VlanTagPriorityCodePoint => write!(f, "SingleVlanHeader.priority_code_point"),
-            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier")
+            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier"),
+            DccpCcVal => write!(f, "DccpHeader.ccval"),
+            DccpChecksumCoverage => write!(f, "DccpHeader.checksum_coverage"),
+            DccpSequenceNumber => write!(f, "DccpHeader.sequence_number"),
+            DccpAcknowledgementNumber => write!(f, "DccpHeader.packet_type.acknowledgement_number"),
+            IpVersion => write!(f, "IpHeader.version"),
+            VlanTagEtherType => write!(f, "SingleVlanHeader.ether_type"),
+            Ipv4Ihl => write!(f, "Ipv4Header.ihl"),
+            Ipv4TotalLength => write!(f, "Ipv4Header.total_len"),
+            IpAuthPayloadLength => write!(f, "IpAuthenticationHeader.payload_len"),
+            TcpDataOffset => write!(f, "TcpHeader.data_offset"),
+            IcmpExtensionVersion => write!(f, "IcmpExtensionsSlice.version"),
+            IcmpExtensionObjectLength => write!(f, "IcmpExtensionObjectSlice.length"),
+            SctpChunkLength => write!(f, "SctpChunk.length"),
+            SctpParameterLength => write!(f, "SctpParameter.length"),
+            DccpDataOffset => write!(f, "DccpHeader.data_offset"),
+            DccpOptionLength => write!(f, "DccpOption.length"),
+            GreVersion => write!(f, "GreHeader.version"),
         }
     }
 }
@@ -659,6 +1152,19 @@ fn max_check_u16(value: u16, max: u16, field: ErrorField) -> Result<(), ValueErr
     }
 }
 
+fn max_check_u64(value: u64, max: u64, field: ErrorField) -> Result<(), ValueError> {
+    use crate::ValueError::U64TooLarge;
+    if value <= max {
+        Ok(())
+    } else {
+        Err(U64TooLarge{ 
+            value, 
+            max, 
+            field
+        })
+    }
+}
+
 /// Helper function for reading big endian u16 values from a ptr unchecked.
 ///
 /// # Safety
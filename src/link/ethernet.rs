@@ -1,6 +1,7 @@
 use super::super::*;
 
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
+#[cfg(feature = "std")]
 use std::io;
 
 /// Ether type enum present in ethernet II header.
@@ -12,7 +13,9 @@ pub enum EtherType {
     WakeOnLan = 0x0842,
     VlanTaggedFrame = 0x8100,
     ProviderBridging = 0x88A8,
-    VlanDoubleTaggedFrame = 0x9100
+    VlanDoubleTaggedFrame = 0x9100,
+    MplsUnicast = 0x8847,
+    MplsMulticast = 0x8848
 }
 
 impl EtherType {
@@ -27,6 +30,8 @@ impl EtherType {
             0x88A8 => Some(ProviderBridging),
             0x8100 => Some(VlanTaggedFrame),
             0x9100 => Some(VlanDoubleTaggedFrame),
+            0x8847 => Some(MplsUnicast),
+            0x8848 => Some(MplsMulticast),
             _ => None
         }
     }
@@ -54,6 +59,8 @@ pub mod ether_type {
     pub const VLAN_TAGGED_FRAME: u16 = VlanTaggedFrame as u16;
     pub const PROVIDER_BRIDGING: u16 = ProviderBridging as u16;
     pub const VLAN_DOUBLE_TAGGED_FRAME: u16 = VlanDoubleTaggedFrame as u16;
+    pub const MPLS_UNICAST: u16 = MplsUnicast as u16;
+    pub const MPLS_MULTICAST: u16 = MplsMulticast as u16;
 }
 
 ///Ethernet II header.
@@ -120,6 +127,7 @@ impl Ethernet2Header {
     }
 
     /// Reads an Ethernet-II header from the current position of the read argument.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<Ethernet2Header, io::Error> {
 
         let buffer = {
@@ -147,6 +155,7 @@ impl Ethernet2Header {
 
     /// Writes a given Ethernet-II header to the current position of the write argument.
     #[inline]
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
         writer.write_all(&self.to_bytes())
     }
@@ -189,6 +198,13 @@ pub struct Ethernet2HeaderSlice<'a> {
 
 impl<'a> Ethernet2HeaderSlice<'a> {
 
+    /// Position & size of the fields in the serialized header.
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "destination", bit_offset: 0, bit_width: 48 },
+        HeaderField{ name: "source", bit_offset: 48, bit_width: 48 },
+        HeaderField{ name: "ether_type", bit_offset: 96, bit_width: 16 },
+    ];
+
     /// Creates a ethernet slice from an other slice.
     pub fn from_slice(slice: &'a[u8]) -> Result<Ethernet2HeaderSlice<'a>, ReadError>{
         //check length
@@ -19,3 +19,26 @@ impl<'a> LinkSlice<'a> {
         }
     }
 }
+
+/// `u16` constants for the most used link layer header type values (see
+/// <https://www.tcpdump.org/linktypes.html>).
+///
+/// Link types are used in capture files (e.g. pcap & pcapng) to identify
+/// the first header of a packet. [`crate::SlicedPacket::from_link_type`]
+/// can be used to slice packets based on their link type.
+pub mod link_type {
+    /// BSD loopback encapsulation (4 byte protocol family in host byte order).
+    pub const NULL: u16 = 0;
+    /// Ethernet II header.
+    pub const ETHERNET: u16 = 1;
+    /// Raw IPv4 or IPv6 packets (no link layer header).
+    pub const RAW: u16 = 101;
+    /// OpenBSD loopback encapsulation (4 byte protocol family in network byte order).
+    pub const LOOP: u16 = 108;
+    /// Linux "cooked" capture encapsulation (16 byte header).
+    pub const LINUX_SLL: u16 = 113;
+    /// Raw IPv4 packets (no link layer header).
+    pub const IPV4: u16 = 228;
+    /// Raw IPv6 packets (no link layer header).
+    pub const IPV6: u16 = 229;
+}
@@ -1,7 +1,8 @@
 use super::super::*;
 
+#[cfg(feature = "std")]
 use std::io;
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 /// IEEE 802.1Q VLAN Tagging Header (can be single or double tagged).
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -22,6 +23,7 @@ impl VlanHeader {
 
     /// Write the IEEE 802.1Q VLAN single or double tagging header
     #[inline]
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         use VlanHeader::*;
         match &self {
@@ -30,6 +32,17 @@ impl VlanHeader {
         }
     }
 
+    /// Write the IEEE 802.1Q VLAN single or double tagging header to the
+    /// start of the given slice. Returns the unused part of the slice.
+    #[inline]
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use VlanHeader::*;
+        match &self {
+            Single(header) => header.write_to_slice(slice),
+            Double(header) => header.write_to_slice(slice),
+        }
+    }
+
     /// Length of the serialized header(s) in bytes.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -122,6 +135,7 @@ impl SingleVlanHeader {
     }
 
     /// Read a IEEE 802.1Q VLAN tagging header
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized >(reader: &mut T) -> Result<SingleVlanHeader, io::Error> {
         let buffer = {
             let mut buffer : [u8; SingleVlanHeader::SERIALIZED_SIZE] = [0;SingleVlanHeader::SERIALIZED_SIZE];
@@ -136,11 +150,25 @@ impl SingleVlanHeader {
 
     /// Write the IEEE 802.1Q VLAN tagging header
     #[inline]
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         writer.write_all(&self.to_bytes()?)?;
         Ok(())
     }
 
+    /// Serialize the header to the start of the given slice. Returns the
+    /// unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < SingleVlanHeader::SERIALIZED_SIZE {
+            Err(SliceTooSmall(SingleVlanHeader::SERIALIZED_SIZE))
+        } else {
+            slice[..SingleVlanHeader::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes()?);
+            Ok(&mut slice[SingleVlanHeader::SERIALIZED_SIZE..])
+        }
+    }
+
     /// Length of the serialized header in bytes.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -212,6 +240,7 @@ impl DoubleVlanHeader {
     }
 
     /// Read a double tagging header from the given source
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized >(reader: &mut T) -> Result<DoubleVlanHeader, ReadError> {
         let outer = SingleVlanHeader::read(reader)?;
 
@@ -232,11 +261,25 @@ impl DoubleVlanHeader {
     }
 
     /// Write the double IEEE 802.1Q VLAN tagging header
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         self.outer.write(writer)?;
         self.inner.write(writer)
     }
 
+    /// Serialize the outer and inner header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < DoubleVlanHeader::SERIALIZED_SIZE {
+            Err(SliceTooSmall(DoubleVlanHeader::SERIALIZED_SIZE))
+        } else {
+            slice[..DoubleVlanHeader::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes()?);
+            Ok(&mut slice[DoubleVlanHeader::SERIALIZED_SIZE..])
+        }
+    }
+
     /// Length of the serialized headers in bytes.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -285,6 +328,14 @@ pub struct SingleVlanHeaderSlice<'a> {
 }
 
 impl<'a> SingleVlanHeaderSlice<'a> {
+
+    /// Position & size of the fields in the serialized header.
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "priority_code_point", bit_offset: 0, bit_width: 3 },
+        HeaderField{ name: "drop_eligible_indicator", bit_offset: 3, bit_width: 1 },
+        HeaderField{ name: "vlan_identifier", bit_offset: 4, bit_width: 12 },
+        HeaderField{ name: "ether_type", bit_offset: 16, bit_width: 16 },
+    ];
     ///Creates a vlan header slice from a slice.
     #[inline]
     pub fn from_slice(slice: &'a[u8]) -> Result<SingleVlanHeaderSlice<'a>, ReadError>{
@@ -1,6 +1,8 @@
 use super::*;
 
-use std::{io, marker};
+use core::marker;
+#[cfg(feature = "std")]
+use std::io;
 
 /// Helper for building packets.
 ///
@@ -58,6 +60,7 @@ use std::{io, marker};
 ///     * [`PacketBuilderStep<VlanHeader>::ipv6`]
 /// * Options after an IP header was added:
 ///     * [`PacketBuilderStep<IpHeader>::write`]
+///     * [`PacketBuilderStep<IpHeader>::write_to_slice`]
 ///     * [`PacketBuilderStep<IpHeader>::tcp`]
 ///     * [`PacketBuilderStep<IpHeader>::udp`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv4`]
@@ -68,8 +71,16 @@ use std::{io, marker};
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_raw`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_echo_request`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_echo_reply`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_mld_query`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_mld_report`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_mld_done`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_mldv2_query`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_mldv2_report`]
+///     * [`PacketBuilderStep<IpHeader>::igmp`]
+///     * [`PacketBuilderStep<IpHeader>::sctp`]
 /// * Options after an TCP header was added:
 ///     * [`PacketBuilderStep<TcpHeader>::write`]
+///     * [`PacketBuilderStep<TcpHeader>::write_to_slice`]
 ///     * [`PacketBuilderStep<TcpHeader>::size`]
 ///     * [`PacketBuilderStep<TcpHeader>::ns`]
 ///     * [`PacketBuilderStep<TcpHeader>::fin`]
@@ -84,13 +95,35 @@ use std::{io, marker};
 ///     * [`PacketBuilderStep<TcpHeader>::options_raw`]
 /// * Options after an UDP header was added:
 ///     * [`PacketBuilderStep<UdpHeader>::write`]
+///     * [`PacketBuilderStep<UdpHeader>::write_to_slice`]
 ///     * [`PacketBuilderStep<UdpHeader>::size`]
 /// * Options after an ICMPv4 header was added:
 ///     * [`PacketBuilderStep<Icmpv4Header>::write`]
+///     * [`PacketBuilderStep<Icmpv4Header>::write_to_slice`]
 ///     * [`PacketBuilderStep<Icmpv4Header>::size`]
 /// * Options after an ICMPv6 header was added:
 ///     * [`PacketBuilderStep<Icmpv6Header>::write`]
+///     * [`PacketBuilderStep<Icmpv6Header>::write_to_slice`]
 ///     * [`PacketBuilderStep<Icmpv6Header>::size`]
+/// * Options after an IGMP header was added:
+///     * [`PacketBuilderStep<IgmpHeader>::write`]
+///     * [`PacketBuilderStep<IgmpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<IgmpHeader>::size`]
+/// * Options after a SCTP header was added:
+///     * [`PacketBuilderStep<SctpHeader>::write`]
+///     * [`PacketBuilderStep<SctpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<SctpHeader>::size`]
+/// * Options after an UDP-Lite header was added:
+///     * [`PacketBuilderStep<UdpLiteHeader>::write`]
+///     * [`PacketBuilderStep<UdpLiteHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<UdpLiteHeader>::size`]
+/// * Options after a DCCP header was added:
+///     * [`PacketBuilderStep<DccpHeader>::ccval`]
+///     * [`PacketBuilderStep<DccpHeader>::checksum_coverage`]
+///     * [`PacketBuilderStep<DccpHeader>::options_raw`]
+///     * [`PacketBuilderStep<DccpHeader>::write`]
+///     * [`PacketBuilderStep<DccpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<DccpHeader>::size`]
 ///
 pub struct PacketBuilder {}
 
@@ -133,7 +166,8 @@ impl PacketBuilder {
                 }),
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                value_error: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }
@@ -171,7 +205,8 @@ impl PacketBuilder {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                value_error: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv4(source, destination, time_to_live)
@@ -213,7 +248,8 @@ impl PacketBuilder {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                value_error: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv6(source, destination, hop_limit)
@@ -289,7 +325,8 @@ impl PacketBuilder {
                 ethernet2_header: None,
                 vlan_header: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                value_error: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ip(ip_header)
@@ -300,7 +337,9 @@ struct PacketImpl {
     ethernet2_header: Option<Ethernet2Header>,
     ip_header: Option<IpHeader>,
     vlan_header: Option<VlanHeader>,
-    transport_header: Option<TransportHeader>
+    transport_header: Option<TransportHeader>,
+    /// Error that occurred while adding a header (returned when writing the packet).
+    value_error: Option<ValueError>
 }
 
 ///An unfinished packet that is build with the packet builder
@@ -1119,6 +1158,407 @@ impl PacketBuilderStep<IpHeader> {
         }
     }
 
+    /// Adds an MLDv1 "Multicast Listener Query" (RFC 2710).
+    ///
+    /// The "Router Alert" option (as required by MLD) gets added to the
+    /// hop-by-hop header of the IPv6 header (a hop-by-hop header is added
+    /// if none is present). Note that MLD messages should be sent with a link-local
+    /// source address and a hop limit of 1.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source (link-local)
+    ///         [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //destination (all nodes)
+    ///         [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //hop_limit
+    ///         1)
+    ///    .icmpv6_mld_query(
+    ///         10000, // maximum response delay in milliseconds
+    ///         [0;16], // multicast address (zero for a general query)
+    ///     );
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn icmpv6_mld_query(self, maximum_response_delay: u16, multicast_address: [u8;16]) -> PacketBuilderStep<Icmpv6Header> {
+        self.icmpv6_mld(Icmpv6Type::MulticastListenerQuery(
+            icmpv6::MulticastListenerHeader{
+                maximum_response_delay,
+                multicast_address,
+            }
+        ))
+    }
+
+    /// Adds an MLDv1 "Multicast Listener Report" (RFC 2710).
+    ///
+    /// The "Router Alert" option (as required by MLD) gets added to the
+    /// hop-by-hop header of the IPv6 header (a hop-by-hop header is added
+    /// if none is present).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// #
+    /// let group = [0xff,0x12,0,0,0,0,0,0,0,0,0,0,0,0,0,0x42];
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source (link-local)
+    ///         [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //destination (the reported multicast address)
+    ///         group,
+    ///         //hop_limit
+    ///         1)
+    ///    .icmpv6_mld_report(group);
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn icmpv6_mld_report(self, multicast_address: [u8;16]) -> PacketBuilderStep<Icmpv6Header> {
+        self.icmpv6_mld(Icmpv6Type::MulticastListenerReport(
+            icmpv6::MulticastListenerHeader{
+                maximum_response_delay: 0,
+                multicast_address,
+            }
+        ))
+    }
+
+    /// Adds an MLDv1 "Multicast Listener Done" message (RFC 2710).
+    ///
+    /// The "Router Alert" option (as required by MLD) gets added to the
+    /// hop-by-hop header of the IPv6 header (a hop-by-hop header is added
+    /// if none is present).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source (link-local)
+    ///         [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //destination (all routers)
+    ///         [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,2],
+    ///         //hop_limit
+    ///         1)
+    ///    .icmpv6_mld_done([0xff,0x12,0,0,0,0,0,0,0,0,0,0,0,0,0,0x42]);
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn icmpv6_mld_done(self, multicast_address: [u8;16]) -> PacketBuilderStep<Icmpv6Header> {
+        self.icmpv6_mld(Icmpv6Type::MulticastListenerDone(
+            icmpv6::MulticastListenerHeader{
+                maximum_response_delay: 0,
+                multicast_address,
+            }
+        ))
+    }
+
+    /// Adds an MLDv2 "Multicast Listener Query" (RFC 3810).
+    ///
+    /// The source addresses (`header.number_of_sources` times 16 bytes)
+    /// have to be passed as payload to the `write` call.
+    ///
+    /// The "Router Alert" option (as required by MLD) gets added to the
+    /// hop-by-hop header of the IPv6 header (a hop-by-hop header is added
+    /// if none is present).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, icmpv6::MulticastListenerQueryV2Header};
+    /// #
+    /// let group = [0xff,0x12,0,0,0,0,0,0,0,0,0,0,0,0,0,0x42];
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source (link-local)
+    ///         [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //destination
+    ///         group,
+    ///         //hop_limit
+    ///         1)
+    ///    .icmpv6_mldv2_query(MulticastListenerQueryV2Header{
+    ///         maximum_response_code: 1000,
+    ///         multicast_address: group,
+    ///         suppress_router_side_processing: false,
+    ///         querier_robustness_variable: 2,
+    ///         querier_query_interval_code: 125,
+    ///         number_of_sources: 1,
+    ///     });
+    ///
+    /// // source addresses of the query
+    /// let payload = [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn icmpv6_mldv2_query(self, header: icmpv6::MulticastListenerQueryV2Header) -> PacketBuilderStep<Icmpv6Header> {
+        self.icmpv6_mld(Icmpv6Type::MulticastListenerQueryV2(header))
+    }
+
+    /// Adds an MLDv2 "Multicast Listener Report" (RFC 3810).
+    ///
+    /// The multicast address records have to be passed as payload to
+    /// the `write` call (see [`icmpv6::MulticastAddressRecordHeader`]).
+    ///
+    /// The "Router Alert" option (as required by MLD) gets added to the
+    /// hop-by-hop header of the IPv6 header (a hop-by-hop header is added
+    /// if none is present).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, icmpv6};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source (link-local)
+    ///         [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+    ///         //destination (all MLDv2-capable routers)
+    ///         [0xff,2,0,0,0,0,0,0,0,0,0,0,0,0,0,0x16],
+    ///         //hop_limit
+    ///         1)
+    ///    .icmpv6_mldv2_report(1);
+    ///
+    /// // multicast address records
+    /// let payload = icmpv6::MulticastAddressRecordHeader{
+    ///     record_type: icmpv6::MLDV2_RECORD_CHANGE_TO_EXCLUDE_MODE,
+    ///     aux_data_len: 0,
+    ///     number_of_sources: 0,
+    ///     multicast_address: [0xff,0x12,0,0,0,0,0,0,0,0,0,0,0,0,0,0x42],
+    /// }.to_bytes();
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn icmpv6_mldv2_report(self, number_of_records: u16) -> PacketBuilderStep<Icmpv6Header> {
+        self.icmpv6_mld(Icmpv6Type::MulticastListenerReportV2{ number_of_records })
+    }
+
+    /// Adds the given MLD message and a "Router Alert" option to the
+    /// hop-by-hop header (the hop-by-hop header is added if not present).
+    fn icmpv6_mld(mut self, icmp_type: Icmpv6Type) -> PacketBuilderStep<Icmpv6Header> {
+        if let Some(IpHeader::Version6(_, ref mut ext)) = self.state.ip_header {
+            match ext.hop_by_hop_options {
+                Some(ref mut hop_by_hop) => {
+                    if hop_by_hop.router_alert().is_none() {
+                        // if the option does not fit, the error is returned when writing
+                        if let Err(err) = hop_by_hop.add_router_alert(ipv6_option::ROUTER_ALERT_MLD) {
+                            self.state.value_error = Some(err);
+                        }
+                    }
+                },
+                None => {
+                    ext.hop_by_hop_options = Some(
+                        Ipv6RawExtensionHeader::new_router_alert(
+                            ip_number::IPV6_ICMP, // gets overwritten by set_next_headers
+                            ipv6_option::ROUTER_ALERT_MLD
+                        )
+                    );
+                },
+            }
+        }
+        self.icmpv6(icmp_type)
+    }
+
+    /// Adds an IGMP message.
+    ///
+    /// If the IPv4 header contains no options, the "Router Alert" option
+    /// (as required by IGMPv2 & IGMPv3) gets added to it. Note that IGMP
+    /// messages should be sent with a time to live of 1.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, IgmpType};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv4([192,168,1,1],  //source ip
+    ///          [224,0,0,2],    //destination ip (all routers)
+    ///          1)              //time to life
+    ///    .igmp(IgmpType::LeaveGroup{
+    ///         group_address: [239,1,2,3]
+    ///     });
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn igmp(mut self, igmp_type: IgmpType) -> PacketBuilderStep<IgmpHeader> {
+        if let Some(IpHeader::Version4(ref mut ip, _)) = self.state.ip_header {
+            if ip.options().is_empty() {
+                // "Router Alert" option (RFC 2113) with the value 0
+                // ("Router shall examine packet")
+                ip.set_options(&[0x94, 0x04, 0, 0]).unwrap();
+            }
+        }
+        self.state.transport_header = Some(TransportHeader::Igmp(IgmpHeader{
+            igmp_type,
+            checksum: 0, // calculated later
+        }));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<IgmpHeader>{}
+        }
+    }
+
+    /// Adds a SCTP common header.
+    ///
+    /// The chunks have to be passed as payload to the `write` call. The
+    /// CRC32c checksum gets calculated over the header & chunks during
+    /// the `write` call.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, sctp};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv4([192,168,1,1], //source ip
+    ///          [192,168,1,2], //desitionation ip
+    ///          20)            //time to life
+    ///    .sctp(2905,        //source port 
+    ///          2905,        //desitnation port
+    ///          0x12345678); //verification tag
+    ///
+    /// // SHUTDOWN chunk
+    /// let payload = [sctp::CHUNK_TYPE_SHUTDOWN, 0, 0, 8, 0, 0, 0, 1];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn sctp(mut self, source_port: u16, destination_port: u16, verification_tag: u32) -> PacketBuilderStep<SctpHeader> {
+        self.state.transport_header = Some(TransportHeader::Sctp(
+            SctpHeader::new(source_port, destination_port, verification_tag)
+        ));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<SctpHeader>{}
+        }
+    }
+
+    /// Adds an UDP-Lite header.
+    ///
+    /// The checksum coverage defines how many bytes (starting with the
+    /// UDP-Lite header) are covered by the checksum. The value 0 means
+    /// that the whole packet is covered.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv4([192,168,1,1], //source ip
+    ///          [192,168,1,2], //desitionation ip
+    ///          20)            //time to life
+    ///    .udp_lite(21,    //source port 
+    ///              1234,  //desitnation port
+    ///              12);   //checksum coverage (header + 4 bytes)
+    ///
+    /// //payload of the udp-lite packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn udp_lite(mut self, source_port: u16, destination_port: u16, checksum_coverage: u16) -> PacketBuilderStep<UdpLiteHeader> {
+        self.state.transport_header = Some(TransportHeader::UdpLite(
+            UdpLiteHeader::new(source_port, destination_port, checksum_coverage)
+        ));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<UdpLiteHeader>{}
+        }
+    }
+
+    /// Adds a DCCP header.
+    ///
+    /// The checksum gets calculated during the `write` call.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, DccpSequenceNumber, DccpPacketType};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6([11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26], //source ip
+    ///          [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46], //desitionation ip
+    ///          47)                                                //time to life
+    ///    .dccp(5001, //source port 
+    ///          5002, //desitnation port
+    ///          DccpSequenceNumber::Extended(1234),
+    ///          DccpPacketType::Request{ service_code: 42 });
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(0));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &[]).unwrap();
+    /// ```
+    pub fn dccp(mut self, source_port: u16, destination_port: u16, sequence_number: DccpSequenceNumber, packet_type: DccpPacketType) -> PacketBuilderStep<DccpHeader> {
+        self.state.transport_header = Some(TransportHeader::Dccp(
+            DccpHeader::new(source_port, destination_port, sequence_number, packet_type)
+        ));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<DccpHeader>{}
+        }
+    }
+
     /// Adds an UDP header.
     ///
     /// # Example
@@ -1207,11 +1647,23 @@ impl PacketBuilderStep<IpHeader> {
     /// `last_next_header_ip_number` will be set in the last extension header
     /// or if no extension header exists the ip header as the "next header" or
     /// "protocol number".
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(mut self, writer: &mut T, last_next_header_ip_number: u8, payload: &[u8]) -> Result<(),WriteError> {
         self.state.ip_header.as_mut().unwrap().set_next_headers(last_next_header_ip_number);
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload with the given ip number to the
+    /// start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`]. `last_next_header_ip_number`
+    /// is used the same way as in [`Self::write`].
+    pub fn write_to_slice(mut self, slice: &mut [u8], last_next_header_ip_number: u8, payload: &[u8]) -> Result<usize,WriteError> {
+        self.state.ip_header.as_mut().unwrap().set_next_headers(last_next_header_ip_number);
+        final_write_to_slice(self, slice, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1220,10 +1672,19 @@ impl PacketBuilderStep<IpHeader> {
 
 impl PacketBuilderStep<Icmpv4Header> {
     /// Write all the headers and the payload.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
     /// Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1233,10 +1694,61 @@ impl PacketBuilderStep<Icmpv4Header> {
 
 impl PacketBuilderStep<Icmpv6Header> {
     ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<IgmpHeader> {
+    ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<SctpHeader> {
+    ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1245,10 +1757,79 @@ impl PacketBuilderStep<Icmpv6Header> {
 
 impl PacketBuilderStep<UdpHeader> {
     ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<UdpLiteHeader> {
+    ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<DccpHeader> {
+    ///Sets the "ccval" field of the DCCP header.
+    pub fn ccval(mut self, ccval: u8) -> PacketBuilderStep<DccpHeader> {
+        self.state.transport_header.as_mut().unwrap().mut_dccp().unwrap().ccval = ccval;
+        self
+    }
+
+    ///Sets the "checksum coverage" field of the DCCP header.
+    pub fn checksum_coverage(mut self, checksum_coverage: u8) -> PacketBuilderStep<DccpHeader> {
+        self.state.transport_header.as_mut().unwrap().mut_dccp().unwrap().checksum_coverage = checksum_coverage;
+        self
+    }
+
+    ///Set the DCCP options of the header (setting the bytes directly).
+    pub fn options_raw(mut self, options: &[u8]) -> Result<PacketBuilderStep<DccpHeader>, ValueError> {
+        self.state.transport_header.as_mut().unwrap().mut_dccp().unwrap().set_options_raw(options)?;
+        Ok(self)
+    }
+
+    ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1330,10 +1911,19 @@ impl PacketBuilderStep<TcpHeader> {
     }
 
     ///Write all the headers and the payload.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload to the start of the given slice.
+    ///
+    /// Returns the number of bytes written or a [`WriteError::SliceTooSmall`]
+    /// if the slice is smaller than [`Self::size`].
+    pub fn write_to_slice(self, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1341,8 +1931,66 @@ impl PacketBuilderStep<TcpHeader> {
 }
 
 ///Write all the headers and the payload.
+#[cfg(feature = "std")]
 fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
-    
+    let headers = final_headers(builder, payload)?;
+    if let Some(eth) = headers.ethernet2_header {
+        eth.write(writer)?;
+    }
+    if let Some(vlan) = headers.vlan_header {
+        vlan.write(writer)?;
+    }
+    headers.ip_header.write(writer)?;
+    if let Some(transport) = headers.transport_header {
+        transport.write(writer)?;
+    }
+    writer.write_all(payload)?;
+    Ok(())
+}
+
+///Write all the headers and the payload to the start of the given slice
+///and return the number of bytes written.
+fn final_write_to_slice<B>(builder: PacketBuilderStep<B>, slice: &mut [u8], payload: &[u8]) -> Result<usize,WriteError> {
+    //length check
+    let size = final_size(&builder, payload.len());
+    if slice.len() < size {
+        return Err(WriteError::SliceTooSmall(size));
+    }
+
+    let headers = final_headers(builder, payload)?;
+    let mut rest = &mut slice[..];
+    if let Some(eth) = headers.ethernet2_header {
+        rest = eth.write_to_slice(rest)?;
+    }
+    if let Some(vlan) = headers.vlan_header {
+        rest = vlan.write_to_slice(rest)?;
+    }
+    rest = headers.ip_header.write_to_slice(rest)?;
+    if let Some(transport) = headers.transport_header {
+        rest = transport.write_to_slice(rest)?;
+    }
+    rest[..payload.len()].copy_from_slice(payload);
+    Ok(size)
+}
+
+///Headers of a packet with all lengths, next header fields & checksums
+///set so they can directly be serialized.
+struct FinalHeaders {
+    ethernet2_header: Option<Ethernet2Header>,
+    vlan_header: Option<VlanHeader>,
+    ip_header: IpHeader,
+    transport_header: Option<TransportHeader>,
+}
+
+///Sets the lengths, ether types, next header fields & checksums of all headers
+///based on the given payload.
+fn final_headers<B>(builder: PacketBuilderStep<B>, payload: &[u8]) -> Result<FinalHeaders,WriteError> {
+
+    //errors that occurred while adding the headers
+    if let Some(err) = builder.state.value_error {
+        return Err(err.into());
+    }
+
     let ip_ether_type = {
         use crate::IpHeader::*;
         match builder.state.ip_header {
@@ -1353,7 +2001,7 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
     };
 
     //ethernetII header
-    if let Some(mut eth) = builder.state.ethernet2_header {
+    let ethernet2_header = if let Some(mut eth) = builder.state.ethernet2_header {
         eth.ether_type = {
             
             use crate::VlanHeader::*;
@@ -1365,27 +2013,27 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                 None => ip_ether_type
             }
         };
-        eth.write(writer)?;
-    }
+        Some(eth)
+    } else {
+        None
+    };
 
-    //write the vlan header if it exists
+    //set the ether types in the vlan header if it exists
     use crate::VlanHeader::*;
-    match builder.state.vlan_header {
+    let vlan_header = match builder.state.vlan_header {
         Some(Single(mut value)) => {
             //set ether types
             value.ether_type = ip_ether_type;
-            //serialize
-            value.write(writer)?;
+            Some(Single(value))
         },
         Some(Double(mut value)) => {
             //set ether types
             value.outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
             value.inner.ether_type = ip_ether_type;
-            //serialize
-            value.write(writer)?;
+            Some(Double(value))
         },
-        None => {}
-    }
+        None => None
+    };
 
 
     //ip header
@@ -1394,7 +2042,7 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
 
     //transport header
     let transport = builder.state.transport_header;
-    match transport {
+    let (ip_header, transport_header) = match transport {
         None => {
             // in case no transport header is present the protocol
             // number and next_header fields are set in the write call
@@ -1402,18 +2050,16 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
             match ip_header {
                 Version4(mut ip, ext) => {
                     ip.set_payload_len(ext.header_len() + payload.len())?;
-                    ip.write(writer)?;
-                    ext.write(writer, ip.protocol)?;
+                    (Version4(ip, ext), None)
                 },
                 Version6(mut ip, ext) => {
                     ip.set_payload_length(ext.header_len() + payload.len())?;
-                    ip.write(writer)?;
-                    ext.write(writer, ip.next_header)?;
+                    (Version6(ip, ext), None)
                 }
             }
         },
         Some(mut transport) => {
-            match ip_header {
+            let ip_header = match ip_header {
                 Version4(mut ip, mut ext) => {
                     //set total length & udp payload length (ip checks that the payload length is ok)
                     let transport_size = transport.header_len() + payload.len();
@@ -1422,8 +2068,12 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                     match transport {
                         Icmpv4(_) => {},
                         Icmpv6(_) => {},
+                        Igmp(_) => {},
+                        Sctp(_) => {},
                         Udp(ref mut udp) => { udp.length = transport_size as u16; }
                         Tcp(_) => {},
+                        UdpLite(_) => {},
+                        Dccp(_) => {},
                     }
         
                     //ip protocol number & next header values of the extension header
@@ -1431,17 +2081,19 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                         match transport {
                             Icmpv4(_) => ip_number::ICMP,
                             Icmpv6(_) => ip_number::IPV6_ICMP,
+                            Igmp(_) => ip_number::IGMP,
+                            Sctp(_) => ip_number::SCTP,
                             Udp(_) => ip_number::UDP,
-                            Tcp(_) => ip_number::TCP
+                            Tcp(_) => ip_number::TCP,
+                            UdpLite(_) => ip_number::UDP_LITE,
+                            Dccp(_) => ip_number::DCCP,
                         }
                     );
         
                     //calculate the udp checksum
                     transport.update_checksum_ipv4(&ip, payload)?;
         
-                    //write (will automatically calculate the checksum)
-                    ip.write(writer)?;
-                    ext.write(writer, ip.protocol)?;
+                    Version4(ip, ext)
                 },
                 Version6(mut ip, mut ext) => {
                     //set total length
@@ -1451,8 +2103,12 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                     match transport {
                         Icmpv4(_) => {},
                         Icmpv6(_) => {},
+                        Igmp(_) => {},
+                        Sctp(_) => {},
                         Udp(ref mut udp) => { udp.length = transport_size as u16; }
-                        Tcp(_) => {}
+                        Tcp(_) => {},
+                        UdpLite(_) => {},
+                        Dccp(_) => {},
                     }
         
                     //set the protocol
@@ -1460,26 +2116,31 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                         match transport {
                             Icmpv4(_) => ip_number::ICMP,
                             Icmpv6(_) => ip_number::IPV6_ICMP,
+                            Igmp(_) => ip_number::IGMP,
+                            Sctp(_) => ip_number::SCTP,
                             Udp(_) => ip_number::UDP,
-                            Tcp(_) => ip_number::TCP
+                            Tcp(_) => ip_number::TCP,
+                            UdpLite(_) => ip_number::UDP_LITE,
+                            Dccp(_) => ip_number::DCCP,
                         }
                     );
         
                     //calculate the udp checksum
                     transport.update_checksum_ipv6(&ip, payload)?;
         
-                    //write (will automatically calculate the checksum)
-                    ip.write(writer)?;
-                    ext.write(writer, ip.next_header)?;
+                    Version6(ip, ext)
                 }
-            }
-        
-            //finaly write the udp header & payload
-            transport.write(writer)?;
+            };
+            (ip_header, Some(transport))
         },
-    }
-    writer.write_all(payload)?;
-    Ok(())
+    };
+
+    Ok(FinalHeaders {
+        ethernet2_header,
+        vlan_header,
+        ip_header,
+        transport_header,
+    })
 }
 
 ///Returns the size of the packet when it is serialized
@@ -1501,8 +2162,12 @@ fn final_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
     } + match builder.state.transport_header {
         Some(Icmpv4(ref value)) => value.header_len(),
         Some(Icmpv6(ref value)) => value.header_len(),
+        Some(Igmp(ref value)) => value.header_len(),
+        Some(Sctp(_)) => SctpHeader::SERIALIZED_SIZE,
         Some(Udp(_)) => UdpHeader::SERIALIZED_SIZE,
         Some(Tcp(ref value)) => value.header_len() as usize,
+        Some(UdpLite(_)) => UdpLiteHeader::SERIALIZED_SIZE,
+        Some(Dccp(ref value)) => value.header_len(),
         None => 0
     } + payload_size
 }
@@ -1520,7 +2185,8 @@ mod whitebox_tests {
                 ethernet2_header: None,
                 ip_header: None,
                 vlan_header: None,
-                transport_header: None
+                transport_header: None,
+                value_error: None,
             },
             _marker: marker::PhantomData::<UdpHeader>{}
         }.size(0));
@@ -1536,7 +2202,8 @@ mod whitebox_tests {
                     ethernet2_header: None,
                     ip_header: None,
                     vlan_header: None,
-                    transport_header: None
+                    transport_header: None,
+                    value_error: None,
                 },
                 _marker: marker::PhantomData::<UdpHeader>{}
             },
@@ -9,7 +9,8 @@ use super::*;
 /// * [`PacketHeaders::from_ip_slice`]
 ///
 /// depending on your starting header to parse the headers in a slice and get this
-/// struct as a result.
+/// struct as a result. If you want to keep the successfully decoded headers in
+/// case of an error use the lax versions in [`LaxPacketHeaders`] instead.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PacketHeaders<'a> {
     /// Ethernet II header if present.
@@ -29,6 +30,10 @@ impl<'a> PacketHeaders<'a> {
     ///
     /// The result is returned as a [`PacketHeaders`] struct.
     ///
+    /// Errors are returned together with the layer & offset at which the
+    /// slicing stopped (see [`PacketReadError`]). Use [`LaxPacketHeaders::from_ethernet`] if the
+    /// headers before the error are needed as well.
+    ///
     /// # Example
     ///
     /// Basic usage:
@@ -66,104 +71,8 @@ impl<'a> PacketHeaders<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ethernet_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
-        
-        let (ethernet, mut rest) = Ethernet2Header::from_slice(packet)?;
-        let mut ether_type = ethernet.ether_type;
-
-        let mut result = PacketHeaders{
-            link: Some(ethernet),
-            vlan: None,
-            ip: None,
-            transport: None,
-            payload: &[]
-        };
-
-        //parse vlan header(s)
-        use ether_type::*;
-
-        result.vlan = match ether_type {
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-                use crate::VlanHeader::*;
-                let (outer, outer_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                //set the rest & ether_type for the following operations
-                rest = outer_rest;
-                ether_type = outer.ether_type;
-
-                //parse second vlan header if present
-                match ether_type {
-                    //second vlan tagging header
-                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-
-                        let (inner, inner_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                        //set the rest & ether_type for the following operations
-                        rest = inner_rest;
-                        ether_type = inner.ether_type;
-
-                        Some(Double(DoubleVlanHeader{
-                            outer,
-                            inner
-                        }))
-                    },
-                    //no second vlan header detected -> single vlan header
-                    _ => Some(Single(outer))
-                }
-            },
-            //no vlan header
-            _ => None
-        };
-
-        //parse ip (if present)
-        match ether_type {
-            IPV4 => {
-                let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
-                let fragmented = ip.is_fragmenting_payload();
-                let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version4(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
-
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-            },
-            IPV6 => {
-                let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
-                let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
-                let fragmented = ip_ext.is_fragmenting_payload();
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version6(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
-
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-
-            },
-            _ => {}
-        }
-
-        //finally update the rest slice based on the cursor position
-        result.payload = rest;
-
-        Ok(result)
+    pub fn from_ethernet_slice(packet: &[u8]) -> Result<PacketHeaders<'_>, PacketReadError> {
+        LaxPacketHeaders::from_ethernet(packet).into_result()
     }
 
     /// Tries to decode a network packet into different headers using the
@@ -218,104 +127,10 @@ impl<'a> PacketHeaders<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ether_type(mut ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders, ReadError> {
-        let mut rest = data;
-        let mut result = PacketHeaders{
-            link: None,
-            vlan: None,
-            ip: None,
-            transport: None,
-            payload: &[]
-        };
-
-        //parse vlan header(s)
-        use ether_type::*;
-
-        result.vlan = match ether_type {
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-                use crate::VlanHeader::*;
-                let (outer, outer_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                //set the rest & ether_type for the following operations
-                rest = outer_rest;
-                ether_type = outer.ether_type;
-
-                //parse second vlan header if present
-                match ether_type {
-                    //second vlan tagging header
-                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-
-                        let (inner, inner_rest) = SingleVlanHeader::from_slice(rest)?;
-
-                        //set the rest & ether_type for the following operations
-                        rest = inner_rest;
-                        ether_type = inner.ether_type;
-
-                        Some(Double(DoubleVlanHeader{
-                            outer,
-                            inner
-                        }))
-                    },
-                    //no second vlan header detected -> single vlan header
-                    _ => Some(Single(outer))
-                }
-            },
-            //no vlan header
-            _ => None
-        };
-
-        //parse ip (if present)
-        match ether_type {
-            IPV4 => {
-                let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
-                let fragmented = ip.is_fragmenting_payload();
-                let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version4(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
-
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-            },
-            IPV6 => {
-                let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
-                let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
-                let fragmented = ip_ext.is_fragmenting_payload();
-
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version6(ip, ip_ext));
-
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
-
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-
-            },
-            _ => {}
-        }
-
-        //finally update the rest slice based on the cursor position
-        result.payload = rest;
-
-        Ok(result)
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders<'a>, PacketReadError> {
+        LaxPacketHeaders::from_ether_type(ether_type, data).into_result()
     }
 
-
     /// Tries to decode an ip packet and its transport headers.
     ///
     /// Assumes the given slice starts with the first byte of the IP header.
@@ -355,30 +170,8 @@ impl<'a> PacketHeaders<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ip_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
-        let mut result = PacketHeaders {
-            link: None,
-            vlan: None,
-            ip: None,
-            transport: None,
-            payload: &[],
-        };
-
-        let (transport_proto, rest) = {
-            let (ip, transport_proto, rest) = IpHeader::from_slice(packet)?;
-            // update output
-            result.ip = Some(ip);
-            (transport_proto, rest)
-        };
-
-        // try to parse the transport header
-        let (transport, rest) = read_transport(transport_proto, rest)?;
-
-        // update output
-        result.transport = transport;
-        result.payload = rest;
-
-        Ok(result)
+    pub fn from_ip_slice(packet: &[u8]) -> Result<PacketHeaders<'_>, PacketReadError> {
+        LaxPacketHeaders::from_ip(packet).into_result()
     }
 
     /// If the slice in the `payload` field contains an ethernet payload
@@ -415,25 +208,276 @@ impl<'a> PacketHeaders<'a> {
     }
 }
 
+/// Result of decoding a packet in lax mode: all headers that could be decoded
+/// plus the error & its location at which decoding stopped (if an error occurred).
+///
+/// This is the [`PacketHeaders`] counterpart of [`LaxSlicedPacket`]:
+///
+/// ```
+/// use etherparse::{Layer, LaxPacketHeaders, PacketBuilder, ReadError};
+///
+/// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv6([0;16], [1;16], 20)
+///     .udp(21, 1234);
+/// let mut packet = Vec::with_capacity(builder.size(0));
+/// builder.write(&mut packet, &[]).unwrap();
+///
+/// // truncated udp header
+/// let result = LaxPacketHeaders::from_ethernet(&packet[..packet.len() - 1]);
+/// assert!(result.headers.link.is_some());
+/// assert!(result.headers.ip.is_some());
+/// assert!(result.headers.transport.is_none());
+/// match result.stop_err {
+///     Some((ReadError::UnexpectedEndOfSlice(_), context)) => {
+///         assert_eq!(Layer::UdpHeader, context.layer);
+///         assert_eq!(14 + 40, context.offset);
+///     },
+///     _ => panic!(),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LaxPacketHeaders<'a> {
+    /// Headers that could be decoded. If decoding stopped because of an error,
+    /// the payload contains the rest of the packet starting at the header
+    /// that could not be decoded.
+    pub headers: PacketHeaders<'a>,
+    /// Error & its location at which decoding stopped (`None` if the complete packet was decoded).
+    ///
+    /// Lengths in the error (e.g. `ReadError::UnexpectedEndOfSlice`) are relative to the
+    /// start of the packet (the `offset` of the context is already added).
+    pub stop_err: Option<(ReadError, ReadErrorContext)>,
+}
+
+impl<'a> LaxPacketHeaders<'a> {
+    /// Decodes a packet starting with an Ethernet II header and keeps the
+    /// successfully decoded headers if an error is encountered.
+    ///
+    /// See [`PacketHeaders::from_ethernet_slice`] for the strict version.
+    pub fn from_ethernet(packet: &'a [u8]) -> LaxPacketHeaders<'a> {
+        let mut result = PacketHeaders{
+            link: None,
+            vlan: None,
+            ip: None,
+            transport: None,
+            payload: packet,
+        };
+        match Ethernet2Header::from_slice(packet) {
+            Ok((ethernet, rest)) => {
+                let ether_type = ethernet.ether_type;
+                result.link = Some(ethernet);
+                decode_ether_type(result, ether_type, rest)
+            },
+            Err(err) => stop(result, packet, err, Layer::Ethernet2Header),
+        }
+    }
+
+    /// Decodes a packet starting with the header identified by the given ether type
+    /// and keeps the successfully decoded headers if an error is encountered.
+    ///
+    /// See [`PacketHeaders::from_ether_type`] for the strict version.
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> LaxPacketHeaders<'a> {
+        let result = PacketHeaders{
+            link: None,
+            vlan: None,
+            ip: None,
+            transport: None,
+            payload: data,
+        };
+        decode_ether_type(result, ether_type, data)
+    }
+
+    /// Decodes a packet starting with an IPv4 or IPv6 header and keeps the
+    /// successfully decoded headers if an error is encountered.
+    ///
+    /// See [`PacketHeaders::from_ip_slice`] for the strict version.
+    pub fn from_ip(packet: &'a [u8]) -> LaxPacketHeaders<'a> {
+        use crate::ReadError::*;
+
+        let result = PacketHeaders{
+            link: None,
+            vlan: None,
+            ip: None,
+            transport: None,
+            payload: packet,
+        };
+        if packet.is_empty() {
+            stop(result, packet, UnexpectedEndOfSlice(1), Layer::IpHeader)
+        } else {
+            // the transport layer is decoded independent of
+            // fragmentation (same as PacketHeaders::from_ip_slice)
+            match packet[0] >> 4 {
+                4 => decode_ipv4(result, packet, false),
+                6 => decode_ipv6(result, packet, false),
+                version => stop(result, packet, IpUnsupportedVersion(version), Layer::IpHeader),
+            }
+        }
+    }
+
+    /// Converts the result to the result of the strict decoding functions.
+    fn into_result(self) -> Result<PacketHeaders<'a>, PacketReadError> {
+        match self.stop_err {
+            Some((error, context)) => Err(PacketReadError{ error, context }),
+            None => Ok(self.headers),
+        }
+    }
+}
+
+/// helper function to stop decoding (the undecoded rest is set as payload)
+fn stop<'a>(mut headers: PacketHeaders<'a>, rest: &'a [u8], err: ReadError, layer: Layer) -> LaxPacketHeaders<'a> {
+    // until decoding is done the payload contains the complete packet
+    let offset = headers.payload.len() - rest.len();
+    let context = ReadErrorContext::new(&err, layer, offset, rest.len());
+    headers.payload = rest;
+    LaxPacketHeaders{
+        headers,
+        stop_err: Some((err.add_slice_offset(offset), context)),
+    }
+}
+
+/// helper function to finish decoding without an error
+fn done<'a>(mut headers: PacketHeaders<'a>, rest: &'a [u8]) -> LaxPacketHeaders<'a> {
+    headers.payload = rest;
+    LaxPacketHeaders{
+        headers,
+        stop_err: None,
+    }
+}
+
+/// helper function to process the vlan, ip & transport headers
+fn decode_ether_type<'a>(mut result: PacketHeaders<'a>, mut ether_type: u16, mut rest: &'a [u8]) -> LaxPacketHeaders<'a> {
+    use ether_type::*;
+
+    //parse vlan header(s)
+    result.vlan = match ether_type {
+        VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+            use crate::VlanHeader::*;
+            let (outer, outer_rest) = match SingleVlanHeader::from_slice(rest) {
+                Ok(value) => value,
+                Err(err) => return stop(result, rest, err, Layer::VlanHeader),
+            };
+
+            //parse second vlan header if present
+            match outer.ether_type {
+                //second vlan tagging header
+                VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+
+                    let (inner, inner_rest) = match SingleVlanHeader::from_slice(outer_rest) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            // both vlan headers are treated as one layer
+                            let err = err.add_slice_offset(SingleVlanHeader::SERIALIZED_SIZE);
+                            return stop(result, rest, err, Layer::VlanHeader);
+                        },
+                    };
+
+                    //set the rest & ether_type for the following operations
+                    rest = inner_rest;
+                    ether_type = inner.ether_type;
+
+                    Some(Double(DoubleVlanHeader{
+                        outer,
+                        inner
+                    }))
+                },
+                //no second vlan header detected -> single vlan header
+                value => {
+                    //set the rest & ether_type for the following operations
+                    rest = outer_rest;
+                    ether_type = value;
+
+                    Some(Single(outer))
+                }
+            }
+        },
+        //no vlan header
+        _ => None
+    };
+
+    //parse ip (if present)
+    match ether_type {
+        IPV4 => decode_ipv4(result, rest, true),
+        IPV6 => decode_ipv6(result, rest, true),
+        _ => done(result, rest),
+    }
+}
+
+/// helper function to process an ipv4 header, its extensions & the transport header
+fn decode_ipv4<'a>(mut result: PacketHeaders<'a>, rest: &'a [u8], check_fragmentation: bool) -> LaxPacketHeaders<'a> {
+    let (ip, ip_rest) = match Ipv4Header::from_slice(rest) {
+        Ok(value) => value,
+        Err(err) => return stop(result, rest, err, Layer::Ipv4Header),
+    };
+    let fragmented = check_fragmentation && ip.is_fragmenting_payload();
+    let (ip_ext, ip_protocol, ip_ext_rest) = match Ipv4Extensions::from_slice(ip.protocol, ip_rest) {
+        Ok(value) => value,
+        Err(err) => {
+            result.ip = Some(IpHeader::Version4(ip, Default::default()));
+            return stop(result, ip_rest, err, Layer::Ipv4Extensions);
+        }
+    };
+
+    result.ip = Some(IpHeader::Version4(ip, ip_ext));
+
+    // only try to decode the transport layer if the payload
+    // is not fragmented
+    if fragmented {
+        done(result, ip_ext_rest)
+    } else {
+        decode_transport(result, ip_protocol, ip_ext_rest)
+    }
+}
+
+/// helper function to process an ipv6 header, its extensions & the transport header
+fn decode_ipv6<'a>(mut result: PacketHeaders<'a>, rest: &'a [u8], check_fragmentation: bool) -> LaxPacketHeaders<'a> {
+    let (ip, ip_rest) = match Ipv6Header::from_slice(rest) {
+        Ok(value) => value,
+        Err(err) => return stop(result, rest, err, Layer::Ipv6Header),
+    };
+    let (ip_ext, next_header, ip_ext_rest) = match Ipv6Extensions::from_slice(ip.next_header, ip_rest) {
+        Ok(value) => value,
+        Err(err) => {
+            result.ip = Some(IpHeader::Version6(ip, Default::default()));
+            return stop(result, ip_rest, err, Layer::Ipv6Extensions);
+        }
+    };
+    let fragmented = check_fragmentation && ip_ext.is_fragmenting_payload();
+
+    result.ip = Some(IpHeader::Version6(ip, ip_ext));
+
+    // only try to decode the transport layer if the payload
+    // is not fragmented
+    if fragmented {
+        done(result, ip_ext_rest)
+    } else {
+        decode_transport(result, next_header, ip_ext_rest)
+    }
+}
+
 /// helper function to process transport headers
-fn read_transport(
-    protocol: u8,
-    rest: &[u8],
-) -> Result<(Option<TransportHeader>, &[u8]), ReadError> {
+fn decode_transport<'a>(mut result: PacketHeaders<'a>, protocol: u8, rest: &'a [u8]) -> LaxPacketHeaders<'a> {
     use crate::ip_number::*;
+
+    macro_rules! decode_transport {
+        ($header_type:ty, $variant:ident, $layer:ident) => {
+            match <$header_type>::from_slice(rest) {
+                Ok((header, header_rest)) => {
+                    result.transport = Some(TransportHeader::$variant(header));
+                    done(result, header_rest)
+                },
+                Err(err) => stop(result, rest, err, Layer::$layer),
+            }
+        }
+    }
+
     match protocol {
-        ICMP => {
-            Ok(Icmpv4Header::from_slice(rest)?)
-            .map( |value| (Some(TransportHeader::Icmpv4(value.0)), value.1))
-        },
-        IPV6_ICMP => {
-            Ok(Icmpv6Header::from_slice(rest)?)
-            .map( |value| (Some(TransportHeader::Icmpv6(value.0)), value.1))
-        },
-        UDP => Ok(UdpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Udp(value.0)), value.1))?),
-        TCP => Ok(TcpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Tcp(value.0)), value.1))?),
-        _ => Ok((None, rest)),
+        ICMP => decode_transport!(Icmpv4Header, Icmpv4, Icmpv4),
+        IPV6_ICMP => decode_transport!(Icmpv6Header, Icmpv6, Icmpv6),
+        IGMP => decode_transport!(IgmpHeader, Igmp, Igmp),
+        SCTP => decode_transport!(SctpHeader, Sctp, SctpHeader),
+        UDP_LITE => decode_transport!(UdpLiteHeader, UdpLite, UdpLiteHeader),
+        DCCP => decode_transport!(DccpHeader, Dccp, DccpHeader),
+        UDP => decode_transport!(UdpHeader, Udp, UdpHeader),
+        TCP => decode_transport!(TcpHeader, Tcp, TcpHeader),
+        _ => done(result, rest),
     }
-}
\ No newline at end of file
+}
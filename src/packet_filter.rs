@@ -234,4 +234,888 @@ impl Filter {
             }
         })
     }
-}
\ No newline at end of file
+}
+
+impl<T> ElementFilter<T> {
+    /// Checks an optional layer against the element filter using the given
+    /// function for the `Some` case.
+    fn applies<V>(&self, value: Option<V>, f: impl FnOnce(&T, V) -> bool) -> bool {
+        match self {
+            ElementFilter::Any => true,
+            ElementFilter::No => value.is_none(),
+            ElementFilter::Some(filter) => match value {
+                Some(value) => f(filter, value),
+                None => false,
+            },
+        }
+    }
+
+    fn is_any(&self) -> bool {
+        matches!(self, ElementFilter::Any)
+    }
+}
+
+impl LinkFilter {
+    /// Returns true if the given decoded header fullfills the conditions of the filter.
+    pub fn applies_to_header(&self, header: &Ethernet2Header) -> bool {
+        match self {
+            LinkFilter::Ethernet2{ source, destination } => {
+                source.iter().all(|e| *e == header.source) &&
+                destination.iter().all(|e| *e == header.destination)
+            }
+        }
+    }
+}
+
+impl VlanFilter {
+    /// Returns true if the given decoded header fullfills the conditions of the filter.
+    pub fn applies_to_header(&self, header: &VlanHeader) -> bool {
+        match (self, header) {
+            (VlanFilter::Single(id), VlanHeader::Single(single)) => {
+                id.iter().all(|e| *e == single.vlan_identifier)
+            },
+            (VlanFilter::Double{ outer_identifier, inner_identifier }, VlanHeader::Double(double)) => {
+                outer_identifier.iter().all(|e| *e == double.outer.vlan_identifier) &&
+                inner_identifier.iter().all(|e| *e == double.inner.vlan_identifier)
+            },
+            _ => false,
+        }
+    }
+}
+
+impl IpFilter {
+    /// Returns true if the given decoded header fullfills the conditions of the filter.
+    pub fn applies_to_header(&self, header: &IpHeader) -> bool {
+        match (self, header) {
+            (IpFilter::Ipv4{ source, destination }, IpHeader::Version4(header, _)) => {
+                source.iter().all(|e| *e == header.source) &&
+                destination.iter().all(|e| *e == header.destination)
+            },
+            (IpFilter::Ipv6{ source, destination }, IpHeader::Version6(header, _)) => {
+                source.iter().all(|e| *e == header.source) &&
+                destination.iter().all(|e| *e == header.destination)
+            },
+            _ => false,
+        }
+    }
+}
+
+impl TransportFilter {
+    /// Returns true if the given decoded header fullfills the conditions of the filter.
+    pub fn applies_to_header(&self, header: &TransportHeader) -> bool {
+        match (self, header) {
+            (TransportFilter::Udp{ source_port, destination_port }, TransportHeader::Udp(header)) => {
+                source_port.iter().all(|e| *e == header.source_port) &&
+                destination_port.iter().all(|e| *e == header.destination_port)
+            },
+            (TransportFilter::Tcp{ source_port, destination_port }, TransportHeader::Tcp(header)) => {
+                source_port.iter().all(|e| *e == header.source_port) &&
+                destination_port.iter().all(|e| *e == header.destination_port)
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Filter {
+    /// Returns true if the headers decoded via [`PacketHeaders`] fullfill the conditions of the filter.
+    ///
+    /// Note that [`PacketHeaders`] only contain a transport header for supported
+    /// transport protocols (in contrast to [`TransportSlice::Unknown`]).
+    pub fn applies_to_headers(&self, headers: &PacketHeaders) -> bool {
+        self.link.applies(headers.link.as_ref(), LinkFilter::applies_to_header) &&
+        self.vlan.applies(headers.vlan.as_ref(), VlanFilter::applies_to_header) &&
+        self.ip.applies(headers.ip.as_ref(), IpFilter::applies_to_header) &&
+        self.transport.applies(headers.transport.as_ref(), TransportFilter::applies_to_header)
+    }
+
+    /// Returns true if the given packet (starting with an Ethernet II header)
+    /// fullfills the conditions of the filter.
+    ///
+    /// In contrast to slicing the packet via [`SlicedPacket::from_ethernet`] first,
+    /// the headers are decoded one after the other and the evaluation stops as soon
+    /// as the result is known (e.g. if the link filter does not match or all
+    /// remaining layers are not filtered). Headers that are required to evaluate
+    /// the filter but can not be decoded lead to a non match. Errors in headers
+    /// after the last filtered layer are not detected.
+    ///
+    /// ```
+    /// use etherparse::PacketBuilder;
+    /// use etherparse::packet_filter::*;
+    ///
+    /// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    ///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///     .udp(21, 1234);
+    /// let mut packet = Vec::with_capacity(builder.size(0));
+    /// builder.write(&mut packet, &[]).unwrap();
+    ///
+    /// let filter = Filter{
+    ///     ip: ElementFilter::Some(IpFilter::Ipv4{ source: Some([192,168,1,1]), destination: None }),
+    ///     ..Default::default()
+    /// };
+    /// assert!(filter.applies_to_ethernet_packet(&packet));
+    ///
+    /// // the truncated udp header is not decoded as the transport layer is not filtered
+    /// assert!(filter.applies_to_ethernet_packet(&packet[..packet.len() - 2]));
+    /// ```
+    pub fn applies_to_ethernet_packet(&self, packet: &[u8]) -> bool {
+        use ether_type::*;
+
+        // link layer
+        let ethernet = match Ethernet2HeaderSlice::from_slice(packet) {
+            Ok(value) => value,
+            Err(_) => return false,
+        };
+        if !self.link.applies(Some(LinkSlice::Ethernet2(ethernet.clone())), |f, s| f.applies_to_slice(&s)) {
+            return false;
+        }
+        if self.vlan.is_any() && self.ip.is_any() && self.transport.is_any() {
+            return true;
+        }
+
+        // vlan headers
+        let mut rest = &packet[ethernet.slice().len()..];
+        let mut ether_type = ethernet.ether_type();
+        let vlan = match ether_type {
+            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+                if let ElementFilter::No = self.vlan {
+                    return false;
+                }
+                let outer = match SingleVlanHeaderSlice::from_slice(rest) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+                match outer.ether_type() {
+                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+                        let double = match DoubleVlanHeaderSlice::from_slice(rest) {
+                            Ok(value) => value,
+                            Err(_) => return false,
+                        };
+                        ether_type = double.inner().ether_type();
+                        rest = &rest[double.slice().len()..];
+                        Some(VlanSlice::DoubleVlan(double))
+                    },
+                    value => {
+                        ether_type = value;
+                        rest = &rest[outer.slice().len()..];
+                        Some(VlanSlice::SingleVlan(outer))
+                    },
+                }
+            },
+            _ => None,
+        };
+        if !self.vlan.applies(vlan.as_ref(), VlanFilter::applies_to_slice) {
+            return false;
+        }
+        if self.ip.is_any() && self.transport.is_any() {
+            return true;
+        }
+
+        // ip header & extensions (the ip number is only set if the payload is not fragmented)
+        let (ip, payload_ip_number, rest) = match ether_type {
+            IPV4 | IPV6 if matches!(self.ip, ElementFilter::No) => return false,
+            IPV4 => {
+                let header = match Ipv4HeaderSlice::from_slice(rest) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+                let fragmented = header.is_fragmenting_payload();
+                let (extensions, ip_number, rest) = match Ipv4ExtensionsSlice::from_slice(header.protocol(), &rest[header.slice().len()..]) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+                (
+                    Some(InternetSlice::Ipv4(header, extensions)),
+                    if fragmented { None } else { Some(ip_number) },
+                    rest
+                )
+            },
+            IPV6 => {
+                let header = match Ipv6HeaderSlice::from_slice(rest) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+                let (extensions, ip_number, rest) = match Ipv6ExtensionsSlice::from_slice(header.next_header(), &rest[header.slice().len()..]) {
+                    Ok(value) => value,
+                    Err(_) => return false,
+                };
+                let fragmented = extensions.is_fragmenting_payload();
+                (
+                    Some(InternetSlice::Ipv6(header, extensions)),
+                    if fragmented { None } else { Some(ip_number) },
+                    rest
+                )
+            },
+            _ => (None, None, rest),
+        };
+        if !self.ip.applies(ip.as_ref(), IpFilter::applies_to_slice) {
+            return false;
+        }
+
+        // transport layer (only decoded if not fragmented)
+        match &self.transport {
+            ElementFilter::Any => true,
+            ElementFilter::No => payload_ip_number.is_none(),
+            ElementFilter::Some(filter) => {
+                let transport = match payload_ip_number {
+                    Some(ip_number::UDP) => UdpHeaderSlice::from_slice(rest).ok().map(TransportSlice::Udp),
+                    Some(ip_number::TCP) => TcpHeaderSlice::from_slice(rest).ok().map(TransportSlice::Tcp),
+                    // other protocols never match a transport filter
+                    _ => None,
+                };
+                match transport {
+                    Some(transport) => filter.applies_to_slice(&transport),
+                    None => false,
+                }
+            },
+        }
+    }
+}
+
+/// Which addresses or ports of a packet a condition is checked against.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Source,
+    Destination,
+    /// The condition has to match the source or the destination.
+    SourceOrDestination,
+    /// The condition has to match the source and the destination.
+    SourceAndDestination,
+}
+
+impl Direction {
+    fn applies<T: Copy>(self, source: T, destination: T, f: impl Fn(T) -> bool) -> bool {
+        use Direction::*;
+        match self {
+            Source => f(source),
+            Destination => f(destination),
+            SourceOrDestination => f(source) || f(destination),
+            SourceAndDestination => f(source) && f(destination),
+        }
+    }
+}
+
+/// IPv4 network in CIDR notation (e.g. 10.0.0.0/8).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Ipv4Cidr {
+    pub address: [u8;4],
+    /// Number of leading bits of the address that have to match
+    /// (values above 32 are treated as 32).
+    pub prefix_len: u8,
+}
+
+impl Ipv4Cidr {
+    /// Returns true if the given address is part of the network.
+    pub fn contains(&self, address: [u8;4]) -> bool {
+        let mask = match self.prefix_len {
+            0 => 0,
+            len if len >= 32 => u32::MAX,
+            len => u32::MAX << (32 - u32::from(len)),
+        };
+        (u32::from_be_bytes(self.address) & mask) == (u32::from_be_bytes(address) & mask)
+    }
+}
+
+/// IPv6 network in CIDR notation (e.g. fe80::/10).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Ipv6Cidr {
+    pub address: [u8;16],
+    /// Number of leading bits of the address that have to match
+    /// (values above 128 are treated as 128).
+    pub prefix_len: u8,
+}
+
+impl Ipv6Cidr {
+    /// Returns true if the given address is part of the network.
+    pub fn contains(&self, address: [u8;16]) -> bool {
+        let mask = match self.prefix_len {
+            0 => 0,
+            len if len >= 128 => u128::MAX,
+            len => u128::MAX << (128 - u32::from(len)),
+        };
+        (u128::from_be_bytes(self.address) & mask) == (u128::from_be_bytes(address) & mask)
+    }
+}
+
+/// Set of ports a port condition matches.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum PortMatch {
+    /// All ports from `min` to `max` (both inclusive). Reversed bounds
+    /// (`min > max`) are swapped (same as in libpcap).
+    Range{ min: u16, max: u16 },
+    /// All ports contained in the list.
+    List(Vec<u16>),
+}
+
+impl PortMatch {
+    /// Returns true if the given port is part of the set.
+    pub fn contains(&self, port: u16) -> bool {
+        match self {
+            PortMatch::Range{ min, max } => (*min).min(*max) <= port && port <= (*min).max(*max),
+            PortMatch::List(ports) => ports.contains(&port),
+        }
+    }
+}
+
+/// Bit of the "fin" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_FIN: u16 = 0x001;
+/// Bit of the "syn" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_SYN: u16 = 0x002;
+/// Bit of the "rst" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_RST: u16 = 0x004;
+/// Bit of the "psh" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_PSH: u16 = 0x008;
+/// Bit of the "ack" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_ACK: u16 = 0x010;
+/// Bit of the "urg" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_URG: u16 = 0x020;
+/// Bit of the "ece" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_ECE: u16 = 0x040;
+/// Bit of the "cwr" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_CWR: u16 = 0x080;
+/// Bit of the "ns" flag in the tcp flags (as used by [`FilterExpression::TcpFlags`]).
+pub const TCP_FLAG_NS: u16 = 0x100;
+
+/// Protocol layer the offsets of a [`FilterExpression::Bytes`] condition are relative to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FilterLayer {
+    Ethernet2,
+    Ipv4,
+    Ipv6,
+    Tcp,
+    Udp,
+    Icmpv4,
+    Icmpv6,
+}
+
+/// Comparison operator used by [`FilterExpression::Bytes`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CompareOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl CompareOp {
+    /// Returns the result of `left <op> right`.
+    pub fn compare(self, left: u32, right: u32) -> bool {
+        use CompareOp::*;
+        match self {
+            Equal => left == right,
+            NotEqual => left != right,
+            Less => left < right,
+            LessOrEqual => left <= right,
+            Greater => left > right,
+            GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// Filter condition tree that can be evaluated against a sliced packet.
+///
+/// Leaf conditions that refer to a layer that is not present in a packet
+/// (e.g. a port condition for an ICMP packet) do not match. Evaluating
+/// an expression does not allocate.
+///
+/// ```
+/// use etherparse::{PacketBuilder, SlicedPacket};
+/// use etherparse::packet_filter::*;
+///
+/// // "tcp and dst port 443 and not src net 10.0.0.0/8"
+/// let filter = FilterExpression::And(vec![
+///     FilterExpression::IpNumber(etherparse::ip_number::TCP),
+///     FilterExpression::Port{
+///         direction: Direction::Destination,
+///         ports: PortMatch::List(vec![443]),
+///     },
+///     FilterExpression::Not(Box::new(FilterExpression::Ipv4Net{
+///         direction: Direction::Source,
+///         net: Ipv4Cidr{ address: [10,0,0,0], prefix_len: 8 },
+///     })),
+/// ]);
+///
+/// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .tcp(1234, 443, 1, 4000);
+/// let mut packet = Vec::with_capacity(builder.size(0));
+/// builder.write(&mut packet, &[]).unwrap();
+///
+/// assert!(filter.applies_to_slice(&SlicedPacket::from_ethernet(&packet).unwrap()));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FilterExpression {
+    /// Conditions on the decoded layers (see [`Filter`]).
+    Layers(Filter),
+    /// Matches IPv4 packets with addresses in the given network.
+    Ipv4Net{ direction: Direction, net: Ipv4Cidr },
+    /// Matches IPv6 packets with addresses in the given network.
+    Ipv6Net{ direction: Direction, net: Ipv6Cidr },
+    /// Matches TCP, UDP, UDP-Lite, SCTP & DCCP packets with ports in the given set.
+    Port{ direction: Direction, ports: PortMatch },
+    /// Matches packets where the ip payload has the given protocol number (the
+    /// protocol number after all ip extension headers).
+    IpNumber(u8),
+    /// Matches tcp packets where `flags & mask == value` (see the `TCP_FLAG_*` constants).
+    TcpFlags{ mask: u16, value: u16 },
+    /// Matches ICMPv4 packets with the given type & code (`None` matches any value).
+    Icmpv4{ icmp_type: Option<u8>, code: Option<u8> },
+    /// Matches ICMPv6 packets with the given type & code (`None` matches any value).
+    Icmpv6{ icmp_type: Option<u8>, code: Option<u8> },
+    /// Matches IPv4 packets with the given DSCP & IPv6 packets with the given
+    /// DSCP in the upper 6 bits of the traffic class.
+    Dscp(u8),
+    /// Matches packets where the (outer) vlan header has the given priority code point.
+    VlanPcp(u8),
+    /// Matches packets with a payload length between `min` & `max` (both inclusive).
+    PayloadLength{ min: usize, max: usize },
+    /// Reads `size` bytes (1, 2 or 4) at `offset` bytes after the start of the
+    /// header of `layer` as big endian value and matches if `(value & mask) <op> compare_value`.
+    ///
+    /// The offset can also point to data after the header (e.g. the payload).
+    /// Packets not containing the layer or being too short do not match.
+    Bytes{ layer: FilterLayer, offset: usize, size: u8, mask: u32, op: CompareOp, value: u32 },
+    /// Matches if all sub expressions match (true if empty).
+    And(Vec<FilterExpression>),
+    /// Matches if at least one sub expression matches (false if empty).
+    Or(Vec<FilterExpression>),
+    /// Matches if the sub expression does not match.
+    Not(Box<FilterExpression>),
+}
+
+impl From<Filter> for FilterExpression {
+    fn from(filter: Filter) -> FilterExpression {
+        FilterExpression::Layers(filter)
+    }
+}
+
+impl FilterExpression {
+    ///Returns true if a given sliced network package fullfills the conditions of the expression.
+    pub fn applies_to_slice(&self, slice: &SlicedPacket) -> bool {
+        use FilterExpression::*;
+        match self {
+            Layers(filter) => filter.applies_to_slice(slice),
+            Ipv4Net{ direction, net } => match &slice.ip {
+                Some(InternetSlice::Ipv4(header, _)) => {
+                    direction.applies(header.source(), header.destination(), |address| net.contains(address))
+                },
+                _ => false,
+            },
+            Ipv6Net{ direction, net } => match &slice.ip {
+                Some(InternetSlice::Ipv6(header, _)) => {
+                    direction.applies(header.source(), header.destination(), |address| net.contains(address))
+                },
+                _ => false,
+            },
+            Port{ direction, ports } => match transport_ports(slice) {
+                Some((source, destination)) => {
+                    direction.applies(source, destination, |port| ports.contains(port))
+                },
+                None => false,
+            },
+            IpNumber(expected) => ip_payload_number(slice) == Some(*expected),
+            TcpFlags{ mask, value } => match &slice.transport {
+                Some(TransportSlice::Tcp(tcp)) => {
+                    let flags = (u16::from(tcp.slice()[12] & 1) << 8) | u16::from(tcp.slice()[13]);
+                    flags & mask == *value
+                },
+                _ => false,
+            },
+            Icmpv4{ icmp_type, code } => match &slice.transport {
+                Some(TransportSlice::Icmpv4(icmp)) => {
+                    icmp_type.iter().all(|e| *e == icmp.type_u8()) &&
+                    code.iter().all(|e| *e == icmp.code_u8())
+                },
+                _ => false,
+            },
+            Icmpv6{ icmp_type, code } => match &slice.transport {
+                Some(TransportSlice::Icmpv6(icmp)) => {
+                    icmp_type.iter().all(|e| *e == icmp.type_u8()) &&
+                    code.iter().all(|e| *e == icmp.code_u8())
+                },
+                _ => false,
+            },
+            Dscp(expected) => match &slice.ip {
+                Some(InternetSlice::Ipv4(header, _)) => header.dcp() == *expected,
+                Some(InternetSlice::Ipv6(header, _)) => header.traffic_class() >> 2 == *expected,
+                None => false,
+            },
+            VlanPcp(expected) => match &slice.vlan {
+                Some(VlanSlice::SingleVlan(header)) => header.priority_code_point() == *expected,
+                Some(VlanSlice::DoubleVlan(header)) => header.outer().priority_code_point() == *expected,
+                None => false,
+            },
+            PayloadLength{ min, max } => *min <= slice.payload.len() && slice.payload.len() <= *max,
+            Bytes{ layer, offset, size, mask, op, value } => {
+                match layer_bytes(slice, *layer, *offset, *size) {
+                    Some(read) => op.compare(read & mask, *value),
+                    None => false,
+                }
+            },
+            And(expressions) => expressions.iter().all(|e| e.applies_to_slice(slice)),
+            Or(expressions) => expressions.iter().any(|e| e.applies_to_slice(slice)),
+            Not(expression) => !expression.applies_to_slice(slice),
+        }
+    }
+
+    /// Returns true if the headers decoded via [`PacketHeaders`] fullfill the conditions of the expression.
+    ///
+    /// [`FilterExpression::Bytes`] conditions are evaluated against the serialized
+    /// headers followed by the payload (note that the IPv4 header checksum is
+    /// recalculated during the serialization). This is the only case in which
+    /// the evaluation allocates.
+    pub fn applies_to_headers(&self, headers: &PacketHeaders) -> bool {
+        use FilterExpression::*;
+        match self {
+            Layers(filter) => filter.applies_to_headers(headers),
+            Ipv4Net{ direction, net } => match &headers.ip {
+                Some(IpHeader::Version4(header, _)) => {
+                    direction.applies(header.source, header.destination, |address| net.contains(address))
+                },
+                _ => false,
+            },
+            Ipv6Net{ direction, net } => match &headers.ip {
+                Some(IpHeader::Version6(header, _)) => {
+                    direction.applies(header.source, header.destination, |address| net.contains(address))
+                },
+                _ => false,
+            },
+            Port{ direction, ports } => match header_transport_ports(headers) {
+                Some((source, destination)) => {
+                    direction.applies(source, destination, |port| ports.contains(port))
+                },
+                None => false,
+            },
+            IpNumber(expected) => match &headers.ip {
+                Some(ip) => ip.next_header().ok() == Some(*expected),
+                None => false,
+            },
+            TcpFlags{ mask, value } => match &headers.transport {
+                Some(TransportHeader::Tcp(tcp)) => {
+                    let flags = [
+                        (tcp.fin, TCP_FLAG_FIN),
+                        (tcp.syn, TCP_FLAG_SYN),
+                        (tcp.rst, TCP_FLAG_RST),
+                        (tcp.psh, TCP_FLAG_PSH),
+                        (tcp.ack, TCP_FLAG_ACK),
+                        (tcp.urg, TCP_FLAG_URG),
+                        (tcp.ece, TCP_FLAG_ECE),
+                        (tcp.cwr, TCP_FLAG_CWR),
+                        (tcp.ns, TCP_FLAG_NS),
+                    ].iter().filter(|(set, _)| *set).fold(0, |flags, (_, flag)| flags | flag);
+                    flags & mask == *value
+                },
+                _ => false,
+            },
+            Icmpv4{ icmp_type, code } => match &headers.transport {
+                Some(TransportHeader::Icmpv4(icmp)) => {
+                    let bytes = icmp.to_bytes();
+                    icmp_type.iter().all(|e| *e == bytes[0]) &&
+                    code.iter().all(|e| *e == bytes[1])
+                },
+                _ => false,
+            },
+            Icmpv6{ icmp_type, code } => match &headers.transport {
+                Some(TransportHeader::Icmpv6(icmp)) => {
+                    icmp_type.iter().all(|e| *e == icmp.icmp_type.type_u8()) &&
+                    code.iter().all(|e| *e == icmp.icmp_type.code_u8())
+                },
+                _ => false,
+            },
+            Dscp(expected) => match &headers.ip {
+                Some(IpHeader::Version4(header, _)) => header.differentiated_services_code_point == *expected,
+                Some(IpHeader::Version6(header, _)) => header.traffic_class >> 2 == *expected,
+                None => false,
+            },
+            VlanPcp(expected) => match &headers.vlan {
+                Some(VlanHeader::Single(header)) => header.priority_code_point == *expected,
+                Some(VlanHeader::Double(header)) => header.outer.priority_code_point == *expected,
+                None => false,
+            },
+            PayloadLength{ min, max } => *min <= headers.payload.len() && headers.payload.len() <= *max,
+            Bytes{ layer, offset, size, mask, op, value } => {
+                match header_layer_bytes(headers, *layer, *offset, *size) {
+                    Some(read) => op.compare(read & mask, *value),
+                    None => false,
+                }
+            },
+            And(expressions) => expressions.iter().all(|e| e.applies_to_headers(headers)),
+            Or(expressions) => expressions.iter().any(|e| e.applies_to_headers(headers)),
+            Not(expression) => !expression.applies_to_headers(headers),
+        }
+    }
+
+    /// Returns true if the given packet (starting with an Ethernet II header)
+    /// fullfills the conditions of the expression.
+    ///
+    /// The packet is sliced via [`LaxSlicedPacket::from_ethernet`]. If the slicing
+    /// stops because of an error in a layer the expression refers to (or in any
+    /// layer if the expression refers to the payload), the packet does not match
+    /// (independent of `Not` conditions). Errors in layers after the last layer
+    /// the expression refers to are ignored.
+    ///
+    /// ```
+    /// use etherparse::PacketBuilder;
+    /// use etherparse::packet_filter::*;
+    ///
+    /// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    ///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///     .udp(21, 1234);
+    /// let mut packet = Vec::with_capacity(builder.size(0));
+    /// builder.write(&mut packet, &[]).unwrap();
+    ///
+    /// let expression = FilterExpression::Ipv4Net{
+    ///     direction: Direction::Source,
+    ///     net: Ipv4Cidr{ address: [192,168,0,0], prefix_len: 16 },
+    /// };
+    /// assert!(expression.applies_to_ethernet_packet(&packet));
+    ///
+    /// // the truncated udp header is ignored as the expression only refers to the ip layer
+    /// assert!(expression.applies_to_ethernet_packet(&packet[..packet.len() - 2]));
+    /// ```
+    pub fn applies_to_ethernet_packet(&self, packet: &[u8]) -> bool {
+        let sliced = LaxSlicedPacket::from_ethernet(packet);
+        if let Some((_, context)) = &sliced.stop_err {
+            if FilterDepth::of_layer(context.layer) <= self.depth() {
+                return false;
+            }
+        }
+        self.applies_to_slice(&sliced.packet)
+    }
+
+    /// Deepest part of a packet the expression refers to.
+    fn depth(&self) -> FilterDepth {
+        use FilterExpression::*;
+        match self {
+            Layers(filter) => if !filter.transport.is_any() {
+                FilterDepth::Transport
+            } else if !filter.ip.is_any() {
+                FilterDepth::Ip
+            } else if !filter.vlan.is_any() {
+                FilterDepth::Vlan
+            } else {
+                FilterDepth::Link
+            },
+            Ipv4Net{ .. } | Ipv6Net{ .. } | IpNumber(_) | Dscp(_) => FilterDepth::Ip,
+            Port{ .. } | TcpFlags{ .. } | Icmpv4{ .. } | Icmpv6{ .. } => FilterDepth::Transport,
+            VlanPcp(_) => FilterDepth::Vlan,
+            PayloadLength{ .. } | Bytes{ .. } => FilterDepth::Payload,
+            And(expressions) | Or(expressions) => {
+                expressions.iter().map(|e| e.depth()).max().unwrap_or(FilterDepth::Link)
+            },
+            Not(expression) => expression.depth(),
+        }
+    }
+}
+
+/// Parts of a packet in the order they are decoded.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum FilterDepth {
+    Link,
+    Vlan,
+    Ip,
+    Transport,
+    Payload,
+}
+
+impl FilterDepth {
+    /// Part of the packet a header layer belongs to.
+    fn of_layer(layer: Layer) -> FilterDepth {
+        use crate::Layer::*;
+        match layer {
+            LinkHeader | Ethernet2Header => FilterDepth::Link,
+            VlanHeader => FilterDepth::Vlan,
+            IpHeader | Ipv4Header | Ipv4Extensions | Ipv6Header | Ipv6Extensions => FilterDepth::Ip,
+            Icmpv4 | Icmpv6 | Igmp | SctpHeader | UdpHeader | TcpHeader | UdpLiteHeader | DccpHeader => FilterDepth::Transport,
+        }
+    }
+}
+
+/// Source & destination port of the transport layer (if it has ports).
+fn transport_ports(slice: &SlicedPacket) -> Option<(u16, u16)> {
+    use crate::TransportSlice::*;
+    match slice.transport.as_ref()? {
+        Udp(udp) => Some((udp.source_port(), udp.destination_port())),
+        Tcp(tcp) => Some((tcp.source_port(), tcp.destination_port())),
+        UdpLite(udp) => Some((udp.source_port(), udp.destination_port())),
+        Sctp(sctp) => Some((sctp.source_port(), sctp.destination_port())),
+        Dccp(dccp) => Some((dccp.source_port(), dccp.destination_port())),
+        _ => None,
+    }
+}
+
+/// Source & destination port of the decoded transport header (if it has ports).
+fn header_transport_ports(headers: &PacketHeaders) -> Option<(u16, u16)> {
+    use crate::TransportHeader::*;
+    match headers.transport.as_ref()? {
+        Udp(udp) => Some((udp.source_port, udp.destination_port)),
+        Tcp(tcp) => Some((tcp.source_port, tcp.destination_port)),
+        UdpLite(udp) => Some((udp.source_port, udp.destination_port)),
+        Sctp(sctp) => Some((sctp.source_port, sctp.destination_port)),
+        Dccp(dccp) => Some((dccp.source_port, dccp.destination_port)),
+        _ => None,
+    }
+}
+
+/// Reads `size` bytes as big endian value at `offset` bytes after the start of the
+/// given layer in the serialized headers & payload. Returns `None` if the layer is
+/// not present, the headers can not be serialized or the data is too short.
+fn header_layer_bytes(headers: &PacketHeaders, layer: FilterLayer, offset: usize, size: u8) -> Option<u32> {
+    let (link, vlan, ip) = match layer {
+        FilterLayer::Ethernet2 => (Some(headers.link.as_ref()?), headers.vlan.as_ref(), headers.ip.as_ref()),
+        FilterLayer::Ipv4 => match &headers.ip {
+            Some(ip @ IpHeader::Version4(_, _)) => (None, None, Some(ip)),
+            _ => return None,
+        },
+        FilterLayer::Ipv6 => match &headers.ip {
+            Some(ip @ IpHeader::Version6(_, _)) => (None, None, Some(ip)),
+            _ => return None,
+        },
+        FilterLayer::Tcp => match &headers.transport {
+            Some(TransportHeader::Tcp(_)) => (None, None, None),
+            _ => return None,
+        },
+        FilterLayer::Udp => match &headers.transport {
+            Some(TransportHeader::Udp(_)) => (None, None, None),
+            _ => return None,
+        },
+        FilterLayer::Icmpv4 => match &headers.transport {
+            Some(TransportHeader::Icmpv4(_)) => (None, None, None),
+            _ => return None,
+        },
+        FilterLayer::Icmpv6 => match &headers.transport {
+            Some(TransportHeader::Icmpv6(_)) => (None, None, None),
+            _ => return None,
+        },
+    };
+
+    // serialize the headers starting at the layer
+    let len = link.map_or(0, |h| h.header_len()) +
+        vlan.map_or(0, |h| h.header_len()) +
+        ip.map_or(0, |h| h.header_len()) +
+        headers.transport.as_ref().map_or(0, |h| h.header_len());
+    let mut bytes = vec![0u8;len];
+    {
+        let mut slice = &mut bytes[..];
+        if let Some(header) = link {
+            slice = header.write_to_slice(slice).ok()?;
+        }
+        if let Some(header) = vlan {
+            slice = header.write_to_slice(slice).ok()?;
+        }
+        if let Some(header) = ip {
+            slice = header.write_to_slice(slice).ok()?;
+        }
+        if let Some(header) = &headers.transport {
+            header.write_to_slice(slice).ok()?;
+        }
+    }
+    read_be(&[&bytes, headers.payload], offset, size)
+}
+
+/// Reads `size` bytes as big endian value at `offset` bytes after the start of the
+/// given layer. Returns `None` if the layer is not present or the data is too short.
+fn layer_bytes(slice: &SlicedPacket, layer: FilterLayer, offset: usize, size: u8) -> Option<u32> {
+    let link = match &slice.link {
+        Some(LinkSlice::Ethernet2(header)) => header.slice(),
+        None => &[],
+    };
+    let vlan = match &slice.vlan {
+        Some(VlanSlice::SingleVlan(header)) => header.slice(),
+        Some(VlanSlice::DoubleVlan(header)) => header.slice(),
+        None => &[],
+    };
+    let (ip, ip_extensions) = match &slice.ip {
+        Some(InternetSlice::Ipv4(header, extensions)) => (
+            header.slice(),
+            extensions.auth.as_ref().map_or(&[][..], |auth| auth.slice())
+        ),
+        Some(InternetSlice::Ipv6(header, extensions)) => (header.slice(), extensions.slice()),
+        None => (&[][..], &[][..]),
+    };
+    let transport = {
+        use crate::TransportSlice::*;
+        match &slice.transport {
+            Some(Icmpv4(s)) => s.slice(),
+            Some(Icmpv6(s)) => s.slice(),
+            Some(Igmp(s)) => s.slice(),
+            Some(Sctp(s)) => s.slice(),
+            Some(Udp(s)) => s.slice(),
+            Some(Tcp(s)) => s.slice(),
+            Some(UdpLite(s)) => s.slice(),
+            Some(Dccp(s)) => s.slice(),
+            Some(Unknown(_)) | None => &[],
+        }
+    };
+
+    // the slices of the layers are consecutive in the original packet
+    let data: [&[u8]; 6] = match layer {
+        FilterLayer::Ethernet2 => {
+            slice.link.as_ref()?;
+            [link, vlan, ip, ip_extensions, transport, slice.payload]
+        },
+        FilterLayer::Ipv4 => match &slice.ip {
+            Some(InternetSlice::Ipv4(_, _)) => [ip, ip_extensions, transport, slice.payload, &[], &[]],
+            _ => return None,
+        },
+        FilterLayer::Ipv6 => match &slice.ip {
+            Some(InternetSlice::Ipv6(_, _)) => [ip, ip_extensions, transport, slice.payload, &[], &[]],
+            _ => return None,
+        },
+        FilterLayer::Tcp => match &slice.transport {
+            Some(TransportSlice::Tcp(_)) => [transport, slice.payload, &[], &[], &[], &[]],
+            _ => return None,
+        },
+        FilterLayer::Udp => match &slice.transport {
+            Some(TransportSlice::Udp(_)) => [transport, slice.payload, &[], &[], &[], &[]],
+            _ => return None,
+        },
+        FilterLayer::Icmpv4 => match &slice.transport {
+            Some(TransportSlice::Icmpv4(_)) => [transport, slice.payload, &[], &[], &[], &[]],
+            _ => return None,
+        },
+        FilterLayer::Icmpv6 => match &slice.transport {
+            Some(TransportSlice::Icmpv6(_)) => [transport, slice.payload, &[], &[], &[], &[]],
+            _ => return None,
+        },
+    };
+
+    read_be(&data, offset, size)
+}
+
+/// Reads `size` bytes as big endian value at `offset` bytes in the concatenation of
+/// the given parts. Returns `None` if the parts are too short.
+fn read_be(data: &[&[u8]], offset: usize, size: u8) -> Option<u32> {
+    let mut value = 0u32;
+    let mut read = 0;
+    for byte in data.iter().flat_map(|part| part.iter()).skip(offset).take(usize::from(size)) {
+        value = (value << 8) | u32::from(*byte);
+        read += 1;
+    }
+    if read == usize::from(size) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Protocol number of the ip payload after all extension headers.
+fn ip_payload_number(slice: &SlicedPacket) -> Option<u8> {
+    match slice.ip.as_ref()? {
+        InternetSlice::Ipv4(header, extensions) => Some(
+            extensions.auth.as_ref().map_or(header.protocol(), |auth| auth.next_header())
+        ),
+        InternetSlice::Ipv6(header, extensions) => Some(
+            extensions.clone().into_iter().last().map_or(header.next_header(), |ext| {
+                use crate::Ipv6ExtensionSlice::*;
+                match ext {
+                    HopByHop(s) | Routing(s) | DestinationOptions(s) => s.next_header(),
+                    Fragment(s) => s.next_header(),
+                    Authentication(s) => s.next_header(),
+                }
+            })
+        ),
+    }
+}
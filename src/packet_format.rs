@@ -0,0 +1,1093 @@
+//! Human readable formatting of decoded packets.
+//!
+//! [`PacketSummary`] formats a packet as a tcpdump like one line summary
+//! (e.g. `IP 10.0.0.1.1234 > 10.0.0.2.80: Flags [S], seq 1, win 64240, length 0`)
+//! & [`PacketDissection`] formats all decoded headers as an indented tree
+//! similar to the dissection view of Wireshark. Both can be created from a
+//! [`SlicedPacket`] or a [`PacketHeaders`] and implement [`std::fmt::Display`].
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::{PacketBuilder, SlicedPacket};
+//!
+//! let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .ipv4([10,0,0,1], [10,0,0,2], 64)
+//!     .tcp(1234, 80, 1, 64240)
+//!     .syn();
+//! let mut packet = Vec::with_capacity(builder.size(0));
+//! builder.write(&mut packet, &[]).unwrap();
+//!
+//! let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+//! assert_eq!(
+//!     "IP 10.0.0.1.1234 > 10.0.0.2.80: Flags [S], seq 1, win 64240, length 0",
+//!     sliced.summary().to_string()
+//! );
+//!
+//! // multi line tree with one entry per header field
+//! println!("{}", sliced.dissection());
+//! ```
+use super::*;
+
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Maximum number of payload bytes shown as hex in a [`PacketDissection`].
+pub const DISSECTION_MAX_DATA_BYTES: usize = 32;
+
+/// tcpdump like one line summary of a packet (see the module documentation).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketSummary<'a> {
+    headers: PacketHeaders<'a>,
+}
+
+/// Wireshark like multi line tree of all headers in a packet (see the module
+/// documentation).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketDissection<'a> {
+    headers: PacketHeaders<'a>,
+}
+
+impl<'a> PacketSummary<'a> {
+    /// Creates a summary of the headers in the given sliced packet.
+    pub fn from_sliced(sliced: &SlicedPacket<'a>) -> PacketSummary<'a> {
+        PacketSummary{ headers: headers_from_sliced(sliced) }
+    }
+
+    /// Creates a summary of the given decoded headers.
+    pub fn from_headers(headers: &PacketHeaders<'a>) -> PacketSummary<'a> {
+        PacketSummary{ headers: headers.clone() }
+    }
+}
+
+impl<'a> PacketDissection<'a> {
+    /// Creates a dissection of the headers in the given sliced packet.
+    pub fn from_sliced(sliced: &SlicedPacket<'a>) -> PacketDissection<'a> {
+        PacketDissection{ headers: headers_from_sliced(sliced) }
+    }
+
+    /// Creates a dissection of the given decoded headers.
+    pub fn from_headers(headers: &PacketHeaders<'a>) -> PacketDissection<'a> {
+        PacketDissection{ headers: headers.clone() }
+    }
+}
+
+impl<'a> SlicedPacket<'a> {
+    /// Returns a tcpdump like one line summary of the packet that can be
+    /// formatted via [`std::fmt::Display`].
+    pub fn summary(&self) -> PacketSummary<'a> {
+        PacketSummary::from_sliced(self)
+    }
+
+    /// Returns a Wireshark like multi line dissection of the packet that can
+    /// be formatted via [`std::fmt::Display`].
+    pub fn dissection(&self) -> PacketDissection<'a> {
+        PacketDissection::from_sliced(self)
+    }
+}
+
+impl<'a> PacketHeaders<'a> {
+    /// Returns a tcpdump like one line summary of the packet that can be
+    /// formatted via [`std::fmt::Display`].
+    pub fn summary(&self) -> PacketSummary<'a> {
+        PacketSummary::from_headers(self)
+    }
+
+    /// Returns a Wireshark like multi line dissection of the packet that can
+    /// be formatted via [`std::fmt::Display`].
+    pub fn dissection(&self) -> PacketDissection<'a> {
+        PacketDissection::from_headers(self)
+    }
+}
+
+/// Decodes the slices of a sliced packet into headers.
+///
+/// The payload of ICMP & IGMP slices is moved to the payload so that it
+/// matches the result of [`PacketHeaders`].
+fn headers_from_sliced<'a>(sliced: &SlicedPacket<'a>) -> PacketHeaders<'a> {
+    use TransportSlice::*;
+
+    let mut payload = sliced.payload;
+    let transport = match &sliced.transport {
+        Some(Icmpv4(icmp)) => {
+            payload = icmp.payload();
+            Some(TransportHeader::Icmpv4(icmp.header()))
+        },
+        Some(Icmpv6(icmp)) => {
+            payload = icmp.payload();
+            Some(TransportHeader::Icmpv6(icmp.header()))
+        },
+        Some(Igmp(igmp)) => {
+            payload = igmp.payload();
+            Some(TransportHeader::Igmp(igmp.header()))
+        },
+        Some(Sctp(sctp)) => Some(TransportHeader::Sctp(sctp.to_header())),
+        Some(Udp(udp)) => Some(TransportHeader::Udp(udp.to_header())),
+        Some(Tcp(tcp)) => Some(TransportHeader::Tcp(tcp.to_header())),
+        Some(UdpLite(udp_lite)) => Some(TransportHeader::UdpLite(udp_lite.to_header())),
+        Some(Dccp(dccp)) => Some(TransportHeader::Dccp(dccp.to_header())),
+        Some(Unknown(_)) | None => None,
+    };
+
+    PacketHeaders{
+        link: sliced.link.as_ref().map(|link| link.to_header()),
+        vlan: sliced.vlan.as_ref().map(|vlan| vlan.to_header()),
+        ip: sliced.ip.as_ref().map(|ip| match ip {
+            InternetSlice::Ipv4(header, extensions) => IpHeader::Version4(
+                header.to_header(),
+                extensions.to_header()
+            ),
+            InternetSlice::Ipv6(header, extensions) => IpHeader::Version6(
+                header.to_header(),
+                // the slice was already validated during slicing
+                extensions.first_header()
+                    .and_then(|first| Ipv6Extensions::from_slice(first, extensions.slice()).ok())
+                    .map(|(extensions, _, _)| extensions)
+                    .unwrap_or_default()
+            ),
+        }),
+        transport,
+        payload,
+    }
+}
+
+impl<'a> fmt::Display for PacketSummary<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers = &self.headers;
+        let (family, source, destination, protocol, fragment) = match &headers.ip {
+            Some(IpHeader::Version4(ip, extensions)) => (
+                "IP",
+                IpAddr::V4(Ipv4Addr::from(ip.source)),
+                IpAddr::V4(Ipv4Addr::from(ip.destination)),
+                extensions.next_header(ip.protocol).unwrap_or(ip.protocol),
+                if ip.is_fragmenting_payload() {
+                    Some((u32::from(ip.identification), ip.fragments_offset, ip.more_fragments))
+                } else {
+                    None
+                },
+            ),
+            Some(IpHeader::Version6(ip, extensions)) => (
+                "IP6",
+                IpAddr::V6(Ipv6Addr::from(ip.source)),
+                IpAddr::V6(Ipv6Addr::from(ip.destination)),
+                extensions.next_header(ip.next_header).unwrap_or(ip.next_header),
+                match &extensions.fragment {
+                    Some(frag) if frag.is_fragmenting_payload() => {
+                        Some((frag.identification, frag.fragment_offset, frag.more_fragments))
+                    },
+                    _ => None,
+                },
+            ),
+            None => return fmt_link_summary(f, headers),
+        };
+
+        match transport_ports(&headers.transport) {
+            Some((source_port, destination_port)) => write!(
+                f, "{} {}.{} > {}.{}: ",
+                family, source, source_port, destination, destination_port
+            )?,
+            None => write!(f, "{} {} > {}: ", family, source, destination)?,
+        }
+
+        let payload_len = headers.payload.len();
+        use TransportHeader::*;
+        match &headers.transport {
+            Some(Tcp(tcp)) => fmt_tcp_summary(f, tcp, payload_len),
+            Some(Udp(_)) => write!(f, "UDP, length {}", payload_len),
+            Some(UdpLite(udp_lite)) => write!(
+                f, "UDP-Lite, coverage {}, length {}",
+                udp_lite.checksum_coverage, payload_len
+            ),
+            Some(Sctp(sctp)) => write!(
+                f, "SCTP, vtag 0x{:08x}, length {}",
+                sctp.verification_tag, payload_len
+            ),
+            Some(Dccp(dccp)) => {
+                write!(
+                    f, "DCCP ({}), seq {}",
+                    dccp_packet_type_name(dccp.packet_type.type_value()),
+                    dccp.sequence_number.value()
+                )?;
+                if let Some(ack) = dccp.packet_type.acknowledgement_number() {
+                    write!(f, ", ack {}", ack)?;
+                }
+                write!(f, ", length {}", payload_len)
+            },
+            Some(Icmpv4(icmp)) => {
+                write!(f, "ICMP ")?;
+                fmt_icmpv4_description(f, icmp)?;
+                write!(f, ", length {}", icmp.header_len() + payload_len)
+            },
+            Some(Icmpv6(icmp)) => {
+                write!(f, "ICMP6, ")?;
+                fmt_icmpv6_description(f, icmp)?;
+                write!(f, ", length {}", icmp.header_len() + payload_len)
+            },
+            Some(Igmp(igmp)) => {
+                write!(f, "IGMP ")?;
+                fmt_igmp_description(f, igmp)?;
+                write!(f, ", length {}", igmp.header_len() + payload_len)
+            },
+            None => {
+                match ip_number_name(protocol) {
+                    Some(name) => write!(f, "{}, length {}", name, payload_len)?,
+                    None => write!(f, "ip-proto-{}, length {}", protocol, payload_len)?,
+                }
+                if let Some((id, offset, more_fragments)) = fragment {
+                    write!(
+                        f, " (frag {}:{}@{}{})",
+                        id, payload_len, u32::from(offset)*8,
+                        if more_fragments { "+" } else { "" }
+                    )?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Summary for packets without an ip header (similar to `tcpdump -e`).
+fn fmt_link_summary(f: &mut fmt::Formatter<'_>, headers: &PacketHeaders) -> fmt::Result {
+    let mut separator = "";
+    if let Some(eth) = &headers.link {
+        write!(f, "{} > {}, ethertype ", MacAddress(eth.source), MacAddress(eth.destination))?;
+        fmt_ether_type(f, eth.ether_type)?;
+        separator = ", ";
+    }
+    let mut fmt_vlan = |f: &mut fmt::Formatter<'_>, vlan: &SingleVlanHeader| -> fmt::Result {
+        write!(
+            f, "{}vlan {}, p {}{}, ethertype ",
+            separator,
+            vlan.vlan_identifier,
+            vlan.priority_code_point,
+            if vlan.drop_eligible_indicator { ", DEI" } else { "" }
+        )?;
+        separator = ", ";
+        fmt_ether_type(f, vlan.ether_type)
+    };
+    match &headers.vlan {
+        Some(VlanHeader::Single(single)) => fmt_vlan(f, single)?,
+        Some(VlanHeader::Double(double)) => {
+            fmt_vlan(f, &double.outer)?;
+            fmt_vlan(f, &double.inner)?;
+        },
+        None => {},
+    }
+    write!(f, "{}length {}", separator, headers.payload.len())
+}
+
+fn fmt_tcp_summary(f: &mut fmt::Formatter<'_>, tcp: &TcpHeader, payload_len: usize) -> fmt::Result {
+    write!(f, "Flags [")?;
+    let flags = [
+        (tcp.fin, "F"),
+        (tcp.syn, "S"),
+        (tcp.rst, "R"),
+        (tcp.psh, "P"),
+        (tcp.ack, "."),
+        (tcp.urg, "U"),
+        (tcp.ece, "E"),
+        (tcp.cwr, "W"),
+    ];
+    if flags.iter().any(|(set, _)| *set) {
+        for (_, name) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, "{}", name)?;
+        }
+    } else {
+        write!(f, "none")?;
+    }
+    write!(f, "]")?;
+
+    if payload_len > 0 {
+        write!(
+            f, ", seq {}:{}",
+            tcp.sequence_number,
+            tcp.sequence_number.wrapping_add(payload_len as u32)
+        )?;
+    } else if tcp.syn || tcp.fin || tcp.rst {
+        write!(f, ", seq {}", tcp.sequence_number)?;
+    }
+    if tcp.ack {
+        write!(f, ", ack {}", tcp.acknowledgment_number)?;
+    }
+    write!(f, ", win {}", tcp.window_size)?;
+    if tcp.urg {
+        write!(f, ", urg {}", tcp.urgent_pointer)?;
+    }
+    if false == tcp.options().is_empty() {
+        write!(f, ", options [")?;
+        for (index, option) in tcp.options_iterator().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            match option {
+                Ok(option) => fmt_tcp_option(f, &option)?,
+                Err(_) => write!(f, "bad opt")?,
+            }
+        }
+        write!(f, "]")?;
+    }
+    write!(f, ", length {}", payload_len)
+}
+
+/// Writes a tcp option in the tcpdump notation.
+fn fmt_tcp_option(f: &mut fmt::Formatter<'_>, option: &TcpOptionElement) -> fmt::Result {
+    use TcpOptionElement::*;
+    match option {
+        Noop => write!(f, "nop"),
+        End => write!(f, "eol"),
+        MaximumSegmentSize(mss) => write!(f, "mss {}", mss),
+        WindowScale(shift) => write!(f, "wscale {}", shift),
+        SelectiveAcknowledgementPermitted => write!(f, "sackOK"),
+        SelectiveAcknowledgement(first, rest) => {
+            let count = 1 + rest.iter().filter(|value| value.is_some()).count();
+            write!(f, "sack {} {{{}:{}}}", count, first.0, first.1)?;
+            for (left, right) in rest.iter().flatten() {
+                write!(f, "{{{}:{}}}", left, right)?;
+            }
+            Ok(())
+        },
+        Timestamp(value, echo_reply) => write!(f, "TS val {} ecr {}", value, echo_reply),
+        UserTimeout{ granularity_minutes, timeout } => write!(
+            f, "uto {}{}",
+            timeout,
+            if *granularity_minutes { "min" } else { "sec" }
+        ),
+        AuthenticationOption{ key_id, rnext_key_id, .. } => write!(
+            f, "tcp-ao keyid {} rnextkeyid {}", key_id, rnext_key_id
+        ),
+        Mptcp(mptcp) => write!(f, "mptcp {}", mptcp_option_name(mptcp)),
+        FastOpenCookie(cookie) => write!(f, "tfo cookie {}", HexBytes(cookie.as_slice())),
+        Unknown{ kind, data } => write!(f, "unknown-{} {}", kind, HexBytes(data.as_slice())),
+    }
+}
+
+fn mptcp_option_name(option: &MptcpOption) -> &'static str {
+    use MptcpOption::*;
+    match option {
+        MpCapable{ .. } => "capable",
+        MpJoinSyn{ .. } | MpJoinSynAck{ .. } | MpJoinAck{ .. } => "join",
+        Dss{ .. } => "dss",
+        AddAddr{ .. } => "add-addr",
+    }
+}
+
+/// Returns the source & destination port of transport headers containing ports.
+fn transport_ports(transport: &Option<TransportHeader>) -> Option<(u16, u16)> {
+    use TransportHeader::*;
+    match transport {
+        Some(Tcp(tcp)) => Some((tcp.source_port, tcp.destination_port)),
+        Some(Udp(udp)) => Some((udp.source_port, udp.destination_port)),
+        Some(UdpLite(udp_lite)) => Some((udp_lite.source_port, udp_lite.destination_port)),
+        Some(Sctp(sctp)) => Some((sctp.source_port, sctp.destination_port)),
+        Some(Dccp(dccp)) => Some((dccp.source_port, dccp.destination_port)),
+        Some(Icmpv4(_)) | Some(Icmpv6(_)) | Some(Igmp(_)) | None => None,
+    }
+}
+
+fn fmt_icmpv4_description(f: &mut fmt::Formatter<'_>, icmp: &Icmpv4Header) -> fmt::Result {
+    use Icmpv4Type::*;
+    match &icmp.icmp_type {
+        EchoRequest(echo) => write!(f, "echo request, id {}, seq {}", echo.id, echo.seq),
+        EchoReply(echo) => write!(f, "echo reply, id {}, seq {}", echo.id, echo.seq),
+        DestinationUnreachable(header) => {
+            write!(f, "destination unreachable, code {}", header.code_u8())?;
+            if let icmpv4::DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu } = header {
+                write!(f, ", mtu {}", next_hop_mtu)?;
+            }
+            Ok(())
+        },
+        Redirect(header) => write!(
+            f, "redirect, code {}, gateway {}",
+            header.code.code_u8(),
+            Ipv4Addr::from(header.gateway_internet_address)
+        ),
+        TimeExceeded(code) => write!(f, "time exceeded, code {}", code.code_u8()),
+        ParameterProblem(header) => {
+            use icmpv4::ParameterProblemHeader::*;
+            match header {
+                PointerIndicatesError(pointer) => write!(f, "parameter problem, pointer {}", pointer),
+                MissingRequiredOption => write!(f, "parameter problem, missing required option"),
+                BadLength => write!(f, "parameter problem, bad length"),
+            }
+        },
+        TimestampRequest(timestamp) => write!(
+            f, "timestamp request, id {}, seq {}", timestamp.id, timestamp.seq
+        ),
+        TimestampReply(timestamp) => write!(
+            f, "timestamp reply, id {}, seq {}", timestamp.id, timestamp.seq
+        ),
+        Unknown{ type_u8, code_u8, .. } => match icmpv4_type_name(*type_u8) {
+            Some(name) => write!(f, "{}, code {}", name, code_u8),
+            None => write!(f, "type {}, code {}", type_u8, code_u8),
+        },
+    }
+}
+
+fn fmt_icmpv6_description(f: &mut fmt::Formatter<'_>, icmp: &Icmpv6Header) -> fmt::Result {
+    use Icmpv6Type::*;
+    match &icmp.icmp_type {
+        EchoRequest(echo) => write!(f, "echo request, id {}, seq {}", echo.id, echo.seq),
+        EchoReply(echo) => write!(f, "echo reply, id {}, seq {}", echo.id, echo.seq),
+        PacketTooBig{ mtu } => write!(f, "packet too big, mtu {}", mtu),
+        ParameterProblem(header) => write!(
+            f, "parameter problem, code {}, pointer {}",
+            header.code.code_u8(), header.pointer
+        ),
+        MulticastListenerQuery(header) | MulticastListenerReport(header) | MulticastListenerDone(header) => write!(
+            f, "{}, group {}",
+            icmpv6_type_name(icmp.icmp_type.type_u8()).unwrap_or("multicast listener"),
+            Ipv6Addr::from(header.multicast_address)
+        ),
+        MulticastListenerQueryV2(header) => write!(
+            f, "multicast listener query v2, group {}",
+            Ipv6Addr::from(header.multicast_address)
+        ),
+        MulticastListenerReportV2{ number_of_records } => write!(
+            f, "multicast listener report v2, {} group record(s)", number_of_records
+        ),
+        other => match icmpv6_type_name(other.type_u8()) {
+            Some(name) => write!(f, "{}, code {}", name, other.code_u8()),
+            None => write!(f, "type {}, code {}", other.type_u8(), other.code_u8()),
+        },
+    }
+}
+
+fn fmt_igmp_description(f: &mut fmt::Formatter<'_>, igmp: &IgmpHeader) -> fmt::Result {
+    use IgmpType::*;
+    match &igmp.igmp_type {
+        MembershipQuery(query) => {
+            if query.max_response_time == 0 {
+                write!(f, "v1 query")?;
+            } else {
+                write!(f, "v2 query, max resp time {}", query.max_response_time)?;
+            }
+            if query.group_address != [0;4] {
+                write!(f, ", group {}", Ipv4Addr::from(query.group_address))?;
+            }
+            Ok(())
+        },
+        MembershipQueryV3(query) => write!(
+            f, "v3 query, max resp code {}, group {}",
+            query.max_response_code, Ipv4Addr::from(query.group_address)
+        ),
+        MembershipReportV1{ group_address } => write!(f, "v1 report {}", Ipv4Addr::from(*group_address)),
+        MembershipReportV2{ group_address } => write!(f, "v2 report {}", Ipv4Addr::from(*group_address)),
+        LeaveGroup{ group_address } => write!(f, "leave {}", Ipv4Addr::from(*group_address)),
+        MembershipReportV3{ number_of_records } => write!(f, "v3 report, {} group record(s)", number_of_records),
+        Unknown{ type_u8, .. } => write!(f, "type 0x{:02x}", type_u8),
+    }
+}
+
+impl<'a> fmt::Display for PacketDissection<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers = &self.headers;
+        let mut tree = Tree{ f };
+
+        if let Some(eth) = &headers.link {
+            tree.line(0, format_args!(
+                "Ethernet II, Src: {}, Dst: {}",
+                MacAddress(eth.source), MacAddress(eth.destination)
+            ))?;
+            tree.line(1, format_args!("Destination: {}", MacAddress(eth.destination)))?;
+            tree.line(1, format_args!("Source: {}", MacAddress(eth.source)))?;
+            tree.line(1, format_args!("Type: {}", EtherTypeValue(eth.ether_type)))?;
+        }
+
+        match &headers.vlan {
+            Some(VlanHeader::Single(single)) => tree.vlan(single)?,
+            Some(VlanHeader::Double(double)) => {
+                tree.vlan(&double.outer)?;
+                tree.vlan(&double.inner)?;
+            },
+            None => {},
+        }
+
+        match &headers.ip {
+            Some(IpHeader::Version4(ip, extensions)) => {
+                tree.ipv4(ip)?;
+                if let Some(auth) = &extensions.auth {
+                    tree.auth(auth)?;
+                }
+            },
+            Some(IpHeader::Version6(ip, extensions)) => {
+                tree.ipv6(ip)?;
+                tree.ipv6_extensions(extensions)?;
+            },
+            None => {},
+        }
+
+        let payload_len = headers.payload.len();
+        use TransportHeader::*;
+        match &headers.transport {
+            Some(Tcp(tcp)) => tree.tcp(tcp, payload_len)?,
+            Some(Udp(udp)) => tree.udp(udp)?,
+            Some(UdpLite(udp_lite)) => tree.udp_lite(udp_lite)?,
+            Some(Sctp(sctp)) => tree.sctp(sctp)?,
+            Some(Dccp(dccp)) => tree.dccp(dccp)?,
+            Some(Icmpv4(icmp)) => tree.icmpv4(icmp)?,
+            Some(Icmpv6(icmp)) => tree.icmpv6(icmp)?,
+            Some(Igmp(igmp)) => tree.igmp(igmp)?,
+            None => {},
+        }
+
+        if payload_len > 0 {
+            tree.line(0, format_args!("Data ({} bytes)", payload_len))?;
+            if payload_len > DISSECTION_MAX_DATA_BYTES {
+                tree.line(1, format_args!(
+                    "Data: {}...",
+                    HexBytes(&headers.payload[..DISSECTION_MAX_DATA_BYTES])
+                ))?;
+            } else {
+                tree.line(1, format_args!("Data: {}", HexBytes(headers.payload)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Helper writing the indented lines of a [`PacketDissection`].
+struct Tree<'f, 'b> {
+    f: &'f mut fmt::Formatter<'b>,
+}
+
+impl<'f, 'b> Tree<'f, 'b> {
+    fn line(&mut self, depth: usize, args: fmt::Arguments<'_>) -> fmt::Result {
+        writeln!(self.f, "{:indent$}{}", "", args, indent = depth*4)
+    }
+
+    fn vlan(&mut self, vlan: &SingleVlanHeader) -> fmt::Result {
+        self.line(0, format_args!(
+            "802.1Q Virtual LAN, PRI: {}, DEI: {}, ID: {}",
+            vlan.priority_code_point,
+            u8::from(vlan.drop_eligible_indicator),
+            vlan.vlan_identifier
+        ))?;
+        self.line(1, format_args!("Priority: {}", vlan.priority_code_point))?;
+        self.line(1, format_args!("DEI: {}", u8::from(vlan.drop_eligible_indicator)))?;
+        self.line(1, format_args!("ID: {}", vlan.vlan_identifier))?;
+        self.line(1, format_args!("Type: {}", EtherTypeValue(vlan.ether_type)))
+    }
+
+    fn ipv4(&mut self, ip: &Ipv4Header) -> fmt::Result {
+        self.line(0, format_args!(
+            "Internet Protocol Version 4, Src: {}, Dst: {}",
+            Ipv4Addr::from(ip.source), Ipv4Addr::from(ip.destination)
+        ))?;
+        self.line(1, format_args!("Header Length: {} bytes ({})", ip.header_len(), ip.ihl()))?;
+        self.line(1, format_args!("Differentiated Services Code Point: {}", ip.differentiated_services_code_point))?;
+        self.line(1, format_args!("Explicit Congestion Notification: {}", ip.explicit_congestion_notification))?;
+        self.line(1, format_args!("Total Length: {}", ip.total_len()))?;
+        self.line(1, format_args!("Identification: 0x{:04x} ({})", ip.identification, ip.identification))?;
+        self.line(1, format_args!("Don't Fragment: {}", set_str(ip.dont_fragment)))?;
+        self.line(1, format_args!("More Fragments: {}", set_str(ip.more_fragments)))?;
+        self.line(1, format_args!("Fragment Offset: {} ({} bytes)", ip.fragments_offset, u32::from(ip.fragments_offset)*8))?;
+        self.line(1, format_args!("Time to Live: {}", ip.time_to_live))?;
+        self.line(1, format_args!("Protocol: {}", IpNumberValue(ip.protocol)))?;
+        self.line(1, format_args!("Header Checksum: 0x{:04x}", ip.header_checksum))?;
+        self.line(1, format_args!("Source Address: {}", Ipv4Addr::from(ip.source)))?;
+        self.line(1, format_args!("Destination Address: {}", Ipv4Addr::from(ip.destination)))?;
+        if false == ip.options().is_empty() {
+            self.line(1, format_args!("Options: ({} bytes) {}", ip.options().len(), HexBytes(ip.options())))?;
+        }
+        Ok(())
+    }
+
+    fn ipv6(&mut self, ip: &Ipv6Header) -> fmt::Result {
+        self.line(0, format_args!(
+            "Internet Protocol Version 6, Src: {}, Dst: {}",
+            Ipv6Addr::from(ip.source), Ipv6Addr::from(ip.destination)
+        ))?;
+        self.line(1, format_args!("Traffic Class: 0x{:02x}", ip.traffic_class))?;
+        self.line(1, format_args!("Flow Label: 0x{:05x}", ip.flow_label))?;
+        self.line(1, format_args!("Payload Length: {}", ip.payload_length))?;
+        self.line(1, format_args!("Next Header: {}", IpNumberValue(ip.next_header)))?;
+        self.line(1, format_args!("Hop Limit: {}", ip.hop_limit))?;
+        self.line(1, format_args!("Source Address: {}", Ipv6Addr::from(ip.source)))?;
+        self.line(1, format_args!("Destination Address: {}", Ipv6Addr::from(ip.destination)))
+    }
+
+    fn ipv6_extensions(&mut self, extensions: &Ipv6Extensions) -> fmt::Result {
+        if let Some(hop_by_hop) = &extensions.hop_by_hop_options {
+            self.raw_extension("IPv6 Hop-by-Hop Option", hop_by_hop)?;
+        }
+        if let Some(destination_options) = &extensions.destination_options {
+            self.raw_extension("Destination Options for IPv6", destination_options)?;
+        }
+        if let Some(routing) = &extensions.routing {
+            self.raw_extension("Routing Header for IPv6", &routing.routing)?;
+            if let Some(final_destination_options) = &routing.final_destination_options {
+                self.raw_extension("Destination Options for IPv6", final_destination_options)?;
+            }
+        }
+        if let Some(fragment) = &extensions.fragment {
+            self.line(0, format_args!("Fragment Header for IPv6"))?;
+            self.line(1, format_args!("Next Header: {}", IpNumberValue(fragment.next_header)))?;
+            self.line(1, format_args!("Offset: {} ({} bytes)", fragment.fragment_offset, u32::from(fragment.fragment_offset)*8))?;
+            self.line(1, format_args!("More Fragments: {}", yes_no(fragment.more_fragments)))?;
+            self.line(1, format_args!("Identification: 0x{:08x}", fragment.identification))?;
+        }
+        if let Some(auth) = &extensions.auth {
+            self.auth(auth)?;
+        }
+        Ok(())
+    }
+
+    fn raw_extension(&mut self, name: &str, header: &Ipv6RawExtensionHeader) -> fmt::Result {
+        self.line(0, format_args!("{}", name))?;
+        self.line(1, format_args!("Next Header: {}", IpNumberValue(header.next_header)))?;
+        self.line(1, format_args!("Length: {} bytes", header.header_len()))?;
+        self.line(1, format_args!("Data: {}", HexBytes(header.payload())))
+    }
+
+    fn auth(&mut self, auth: &IpAuthenticationHeader) -> fmt::Result {
+        self.line(0, format_args!("Authentication Header"))?;
+        self.line(1, format_args!("Next Header: {}", IpNumberValue(auth.next_header)))?;
+        self.line(1, format_args!("Length: {} bytes", auth.header_len()))?;
+        self.line(1, format_args!("AH SPI: 0x{:08x}", auth.spi))?;
+        self.line(1, format_args!("AH Sequence: {}", auth.sequence_number))?;
+        self.line(1, format_args!("AH ICV: {}", HexBytes(auth.raw_icv())))
+    }
+
+    fn tcp(&mut self, tcp: &TcpHeader, payload_len: usize) -> fmt::Result {
+        self.line(0, format_args!(
+            "Transmission Control Protocol, Src Port: {}, Dst Port: {}, Seq: {}, Ack: {}, Len: {}",
+            tcp.source_port, tcp.destination_port, tcp.sequence_number,
+            tcp.acknowledgment_number, payload_len
+        ))?;
+        self.line(1, format_args!("Source Port: {}", tcp.source_port))?;
+        self.line(1, format_args!("Destination Port: {}", tcp.destination_port))?;
+        self.line(1, format_args!("Sequence Number: {}", tcp.sequence_number))?;
+        self.line(1, format_args!("Acknowledgment Number: {}", tcp.acknowledgment_number))?;
+        self.line(1, format_args!("Header Length: {} bytes ({})", tcp.header_len(), tcp.data_offset()))?;
+
+        let flags = [
+            (tcp.ns, 0x100, "NS"),
+            (tcp.cwr, 0x80, "CWR"),
+            (tcp.ece, 0x40, "ECE"),
+            (tcp.urg, 0x20, "URG"),
+            (tcp.ack, 0x10, "ACK"),
+            (tcp.psh, 0x08, "PSH"),
+            (tcp.rst, 0x04, "RST"),
+            (tcp.syn, 0x02, "SYN"),
+            (tcp.fin, 0x01, "FIN"),
+        ];
+        let value = flags.iter()
+            .filter(|(set, _, _)| *set)
+            .fold(0u16, |value, (_, bit, _)| value | bit);
+        write!(self.f, "    Flags: 0x{:03x} (", value)?;
+        for (index, (_, _, name)) in flags.iter().filter(|(set, _, _)| *set).enumerate() {
+            if index > 0 {
+                write!(self.f, ", ")?;
+            }
+            write!(self.f, "{}", name)?;
+        }
+        writeln!(self.f, ")")?;
+
+        self.line(1, format_args!("Window: {}", tcp.window_size))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", tcp.checksum))?;
+        self.line(1, format_args!("Urgent Pointer: {}", tcp.urgent_pointer))?;
+        if false == tcp.options().is_empty() {
+            self.line(1, format_args!("Options: ({} bytes)", tcp.options().len()))?;
+            for option in tcp.options_iterator() {
+                match option {
+                    Ok(option) => self.line(2, format_args!("{}", TcpOptionValue(&option)))?,
+                    Err(err) => self.line(2, format_args!("Malformed option: {}", err))?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn udp(&mut self, udp: &UdpHeader) -> fmt::Result {
+        self.line(0, format_args!(
+            "User Datagram Protocol, Src Port: {}, Dst Port: {}",
+            udp.source_port, udp.destination_port
+        ))?;
+        self.line(1, format_args!("Source Port: {}", udp.source_port))?;
+        self.line(1, format_args!("Destination Port: {}", udp.destination_port))?;
+        self.line(1, format_args!("Length: {}", udp.length))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", udp.checksum))
+    }
+
+    fn udp_lite(&mut self, udp_lite: &UdpLiteHeader) -> fmt::Result {
+        self.line(0, format_args!(
+            "Lightweight User Datagram Protocol, Src Port: {}, Dst Port: {}",
+            udp_lite.source_port, udp_lite.destination_port
+        ))?;
+        self.line(1, format_args!("Source Port: {}", udp_lite.source_port))?;
+        self.line(1, format_args!("Destination Port: {}", udp_lite.destination_port))?;
+        self.line(1, format_args!("Checksum Coverage: {}", udp_lite.checksum_coverage))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", udp_lite.checksum))
+    }
+
+    fn sctp(&mut self, sctp: &SctpHeader) -> fmt::Result {
+        self.line(0, format_args!(
+            "Stream Control Transmission Protocol, Src Port: {}, Dst Port: {}",
+            sctp.source_port, sctp.destination_port
+        ))?;
+        self.line(1, format_args!("Source Port: {}", sctp.source_port))?;
+        self.line(1, format_args!("Destination Port: {}", sctp.destination_port))?;
+        self.line(1, format_args!("Verification Tag: 0x{:08x}", sctp.verification_tag))?;
+        self.line(1, format_args!("Checksum: 0x{:08x}", sctp.checksum))
+    }
+
+    fn dccp(&mut self, dccp: &DccpHeader) -> fmt::Result {
+        self.line(0, format_args!(
+            "Datagram Congestion Control Protocol, Src Port: {}, Dst Port: {}",
+            dccp.source_port, dccp.destination_port
+        ))?;
+        self.line(1, format_args!("Source Port: {}", dccp.source_port))?;
+        self.line(1, format_args!("Destination Port: {}", dccp.destination_port))?;
+        self.line(1, format_args!(
+            "Type: {} ({})",
+            dccp_packet_type_name(dccp.packet_type.type_value()),
+            dccp.packet_type.type_value()
+        ))?;
+        self.line(1, format_args!("CCVal: {}", dccp.ccval))?;
+        self.line(1, format_args!("Checksum Coverage: {}", dccp.checksum_coverage))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", dccp.checksum))?;
+        self.line(1, format_args!(
+            "Sequence Number: {}{}",
+            dccp.sequence_number.value(),
+            if dccp.sequence_number.is_extended() { "" } else { " (short)" }
+        ))?;
+        if let Some(ack) = dccp.packet_type.acknowledgement_number() {
+            self.line(1, format_args!("Acknowledgement Number: {}", ack))?;
+        }
+        match &dccp.packet_type {
+            DccpPacketType::Request{ service_code } | DccpPacketType::Response{ service_code, .. } => {
+                self.line(1, format_args!("Service Code: {}", service_code))?;
+            },
+            DccpPacketType::Reset{ reset_code, data, .. } => {
+                self.line(1, format_args!("Reset Code: {}", reset_code))?;
+                self.line(1, format_args!("Data: {}", HexBytes(data)))?;
+            },
+            _ => {},
+        }
+        if false == dccp.options().is_empty() {
+            self.line(1, format_args!("Options: ({} bytes) {}", dccp.options().len(), HexBytes(dccp.options())))?;
+        }
+        Ok(())
+    }
+
+    fn icmpv4(&mut self, icmp: &Icmpv4Header) -> fmt::Result {
+        let bytes = icmp.to_bytes();
+        self.line(0, format_args!("Internet Control Message Protocol"))?;
+        match icmpv4_type_name(bytes[0]) {
+            Some(name) => self.line(1, format_args!("Type: {} ({})", bytes[0], name))?,
+            None => self.line(1, format_args!("Type: {}", bytes[0]))?,
+        }
+        self.line(1, format_args!("Code: {}", bytes[1]))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", icmp.checksum))?;
+
+        use Icmpv4Type::*;
+        match &icmp.icmp_type {
+            EchoRequest(echo) | EchoReply(echo) => {
+                self.line(1, format_args!("Identifier: {}", echo.id))?;
+                self.line(1, format_args!("Sequence Number: {}", echo.seq))?;
+            },
+            DestinationUnreachable(icmpv4::DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu }) => {
+                self.line(1, format_args!("MTU of next hop: {}", next_hop_mtu))?;
+            },
+            Redirect(header) => {
+                self.line(1, format_args!("Gateway Address: {}", Ipv4Addr::from(header.gateway_internet_address)))?;
+            },
+            ParameterProblem(icmpv4::ParameterProblemHeader::PointerIndicatesError(pointer)) => {
+                self.line(1, format_args!("Pointer: {}", pointer))?;
+            },
+            TimestampRequest(timestamp) | TimestampReply(timestamp) => {
+                self.line(1, format_args!("Identifier: {}", timestamp.id))?;
+                self.line(1, format_args!("Sequence Number: {}", timestamp.seq))?;
+                self.line(1, format_args!("Originate Timestamp: {}", timestamp.originate_timestamp))?;
+                self.line(1, format_args!("Receive Timestamp: {}", timestamp.receive_timestamp))?;
+                self.line(1, format_args!("Transmit Timestamp: {}", timestamp.transmit_timestamp))?;
+            },
+            Unknown{ bytes5to8, .. } => {
+                self.line(1, format_args!("Rest of Header: {}", HexBytes(bytes5to8)))?;
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn icmpv6(&mut self, icmp: &Icmpv6Header) -> fmt::Result {
+        let type_u8 = icmp.icmp_type.type_u8();
+        self.line(0, format_args!("Internet Control Message Protocol v6"))?;
+        match icmpv6_type_name(type_u8) {
+            Some(name) => self.line(1, format_args!("Type: {} ({})", type_u8, name))?,
+            None => self.line(1, format_args!("Type: {}", type_u8))?,
+        }
+        self.line(1, format_args!("Code: {}", icmp.icmp_type.code_u8()))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", icmp.checksum))?;
+
+        use Icmpv6Type::*;
+        match &icmp.icmp_type {
+            EchoRequest(echo) | EchoReply(echo) => {
+                self.line(1, format_args!("Identifier: {}", echo.id))?;
+                self.line(1, format_args!("Sequence Number: {}", echo.seq))?;
+            },
+            PacketTooBig{ mtu } => {
+                self.line(1, format_args!("MTU: {}", mtu))?;
+            },
+            ParameterProblem(header) => {
+                self.line(1, format_args!("Pointer: {}", header.pointer))?;
+            },
+            MulticastListenerQuery(header) | MulticastListenerReport(header) | MulticastListenerDone(header) => {
+                self.line(1, format_args!("Maximum Response Delay: {}", header.maximum_response_delay))?;
+                self.line(1, format_args!("Multicast Address: {}", Ipv6Addr::from(header.multicast_address)))?;
+            },
+            MulticastListenerQueryV2(header) => {
+                self.line(1, format_args!("Maximum Response Code: {}", header.maximum_response_code))?;
+                self.line(1, format_args!("Multicast Address: {}", Ipv6Addr::from(header.multicast_address)))?;
+            },
+            MulticastListenerReportV2{ number_of_records } => {
+                self.line(1, format_args!("Number of Multicast Address Records: {}", number_of_records))?;
+            },
+            Unknown{ bytes5to8, .. } => {
+                self.line(1, format_args!("Rest of Header: {}", HexBytes(bytes5to8)))?;
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn igmp(&mut self, igmp: &IgmpHeader) -> fmt::Result {
+        self.line(0, format_args!("Internet Group Management Protocol"))?;
+        self.line(1, format_args!("Type: 0x{:02x} ({})", igmp.igmp_type.type_u8(), IgmpDescription(igmp)))?;
+        self.line(1, format_args!("Checksum: 0x{:04x}", igmp.checksum))?;
+
+        use IgmpType::*;
+        match &igmp.igmp_type {
+            MembershipQuery(query) => {
+                self.line(1, format_args!("Max Resp Time: {}", query.max_response_time))?;
+                self.line(1, format_args!("Multicast Address: {}", Ipv4Addr::from(query.group_address)))?;
+            },
+            MembershipQueryV3(query) => {
+                self.line(1, format_args!("Max Resp Code: {}", query.max_response_code))?;
+                self.line(1, format_args!("Multicast Address: {}", Ipv4Addr::from(query.group_address)))?;
+                self.line(1, format_args!("S Flag: {}", u8::from(query.suppress_router_side_processing)))?;
+            },
+            MembershipReportV1{ group_address } | MembershipReportV2{ group_address } | LeaveGroup{ group_address } => {
+                self.line(1, format_args!("Multicast Address: {}", Ipv4Addr::from(*group_address)))?;
+            },
+            MembershipReportV3{ number_of_records } => {
+                self.line(1, format_args!("Num Group Records: {}", number_of_records))?;
+            },
+            Unknown{ max_response_code, bytes5to8, .. } => {
+                self.line(1, format_args!("Max Resp Code: {}", max_response_code))?;
+                self.line(1, format_args!("Rest of Header: {}", HexBytes(bytes5to8)))?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Displays a mac address as colon separated lower case hex bytes.
+struct MacAddress([u8;6]);
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let m = &self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", m[0], m[1], m[2], m[3], m[4], m[5])
+    }
+}
+
+/// Displays bytes as lower case hex without separators.
+struct HexBytes<'a>(&'a [u8]);
+
+impl<'a> fmt::Display for HexBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays an ether type as "name (0x....)".
+struct EtherTypeValue(u16);
+
+impl fmt::Display for EtherTypeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_ether_type(f, self.0)
+    }
+}
+
+fn fmt_ether_type(f: &mut fmt::Formatter<'_>, ether_type: u16) -> fmt::Result {
+    use crate::ether_type::*;
+    let name = match ether_type {
+        IPV4 => "IPv4",
+        IPV6 => "IPv6",
+        ARP => "ARP",
+        WAKE_ON_LAN => "Wake-on-LAN",
+        VLAN_TAGGED_FRAME => "802.1Q",
+        PROVIDER_BRIDGING => "802.1ad",
+        VLAN_DOUBLE_TAGGED_FRAME => "QinQ",
+        MPLS_UNICAST => "MPLS",
+        MPLS_MULTICAST => "MPLS multicast",
+        _ => "Unknown",
+    };
+    write!(f, "{} (0x{:04x})", name, ether_type)
+}
+
+/// Displays an ip number as "name (number)".
+struct IpNumberValue(u8);
+
+impl fmt::Display for IpNumberValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", ip_number_name(self.0).unwrap_or("Unknown"), self.0)
+    }
+}
+
+/// Displays a tcp option for the dissection tree.
+struct TcpOptionValue<'a>(&'a TcpOptionElement);
+
+impl<'a> fmt::Display for TcpOptionValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TcpOptionElement::*;
+        match self.0 {
+            Noop => write!(f, "No-Operation (NOP)"),
+            End => write!(f, "End of Option List (EOL)"),
+            MaximumSegmentSize(mss) => write!(f, "Maximum segment size: {} bytes", mss),
+            WindowScale(shift) => write!(f, "Window scale: {} (multiply by {})", shift, 1u32 << u32::from((*shift).min(14))),
+            SelectiveAcknowledgementPermitted => write!(f, "SACK permitted"),
+            SelectiveAcknowledgement(first, rest) => {
+                write!(f, "SACK: {}-{}", first.0, first.1)?;
+                for (left, right) in rest.iter().flatten() {
+                    write!(f, " {}-{}", left, right)?;
+                }
+                Ok(())
+            },
+            Timestamp(value, echo_reply) => write!(f, "Timestamps: TSval {}, TSecr {}", value, echo_reply),
+            UserTimeout{ granularity_minutes, timeout } => write!(
+                f, "User Timeout: {} {}",
+                timeout,
+                if *granularity_minutes { "minutes" } else { "seconds" }
+            ),
+            AuthenticationOption{ key_id, rnext_key_id, mac } => write!(
+                f, "TCP Authentication Option: KeyID {}, RNextKeyID {}, MAC {}",
+                key_id, rnext_key_id, HexBytes(mac.as_slice())
+            ),
+            Mptcp(mptcp) => write!(f, "Multipath TCP: {}", mptcp_option_name(mptcp)),
+            FastOpenCookie(cookie) => write!(f, "TCP Fast Open Cookie: {}", HexBytes(cookie.as_slice())),
+            Unknown{ kind, data } => write!(f, "Unknown (kind {}): {}", kind, HexBytes(data.as_slice())),
+        }
+    }
+}
+
+/// Displays the short igmp description used in summaries.
+struct IgmpDescription<'a>(&'a IgmpHeader);
+
+impl<'a> fmt::Display for IgmpDescription<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use IgmpType::*;
+        let name = match &self.0.igmp_type {
+            MembershipQuery(_) | MembershipQueryV3(_) => "Membership Query",
+            MembershipReportV1{ .. } => "Membership Report v1",
+            MembershipReportV2{ .. } => "Membership Report v2",
+            LeaveGroup{ .. } => "Leave Group",
+            MembershipReportV3{ .. } => "Membership Report v3",
+            Unknown{ .. } => "Unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn set_str(value: bool) -> &'static str {
+    if value { "Set" } else { "Not set" }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "Yes" } else { "No" }
+}
+
+/// Returns the tcpdump name of commonly used ip numbers.
+fn ip_number_name(value: u8) -> Option<&'static str> {
+    use crate::ip_number::*;
+    match value {
+        IPV6_HOP_BY_HOP => Some("IPv6 Hop-by-Hop"),
+        ICMP => Some("ICMP"),
+        IGMP => Some("IGMP"),
+        IPV4 => Some("IPIP"),
+        TCP => Some("TCP"),
+        UDP => Some("UDP"),
+        DCCP => Some("DCCP"),
+        IPV6 => Some("IPv6"),
+        IPV6_ROUTE => Some("IPv6 Routing"),
+        IPV6_FRAG => Some("IPv6 Fragment"),
+        47 => Some("GRE"),
+        ENCAP_SEC => Some("ESP"),
+        AUTH => Some("AH"),
+        IPV6_ICMP => Some("ICMPv6"),
+        59 => Some("IPv6 No Next Header"),
+        IPV6_DEST_OPTIONS => Some("IPv6 Destination Options"),
+        SCTP => Some("SCTP"),
+        UDP_LITE => Some("UDP-Lite"),
+        _ => None,
+    }
+}
+
+fn icmpv4_type_name(type_u8: u8) -> Option<&'static str> {
+    use icmpv4::*;
+    match type_u8 {
+        TYPE_ECHO_REPLY => Some("echo reply"),
+        TYPE_DEST_UNREACH => Some("destination unreachable"),
+        TYPE_SOURCE_QUENCH => Some("source quench"),
+        TYPE_REDIRECT => Some("redirect"),
+        TYPE_ALTERNATE_HOST_ADDRESS => Some("alternate host address"),
+        TYPE_ECHO_REQUEST => Some("echo request"),
+        TYPE_ROUTER_ADVERTISEMENT => Some("router advertisement"),
+        TYPE_ROUTER_SOLICITATION => Some("router solicitation"),
+        TYPE_TIME_EXCEEDED => Some("time exceeded"),
+        TYPE_PARAMETER_PROBLEM => Some("parameter problem"),
+        TYPE_TIMESTAMP => Some("timestamp request"),
+        TYPE_TIMESTAMP_REPLY => Some("timestamp reply"),
+        TYPE_INFO_REQUEST => Some("information request"),
+        TYPE_INFO_REPLY => Some("information reply"),
+        TYPE_ADDRESS => Some("address mask request"),
+        TYPE_ADDRESSREPLY => Some("address mask reply"),
+        _ => None,
+    }
+}
+
+fn icmpv6_type_name(type_u8: u8) -> Option<&'static str> {
+    use icmpv6::*;
+    match type_u8 {
+        TYPE_DST_UNREACH => Some("destination unreachable"),
+        TYPE_PACKET_TOO_BIG => Some("packet too big"),
+        TYPE_TIME_EXCEEDED => Some("time exceeded"),
+        TYPE_PARAMETER_PROBLEM => Some("parameter problem"),
+        TYPE_ECHO_REQUEST => Some("echo request"),
+        TYPE_ECHO_REPLY => Some("echo reply"),
+        TYPE_MULTICAST_LISTENER_QUERY => Some("multicast listener query"),
+        TYPE_MULTICAST_LISTENER_REPORT => Some("multicast listener report"),
+        TYPE_MULTICAST_LISTENER_REDUCTION => Some("multicast listener done"),
+        TYPE_ROUTER_SOLICITATION => Some("router solicitation"),
+        TYPE_ROUTER_ADVERTISEMENT => Some("router advertisement"),
+        TYPE_NEIGHBOR_SOLICITATION => Some("neighbor solicitation"),
+        TYPE_NEIGHBOR_ADVERTISEMENT => Some("neighbor advertisement"),
+        TYPE_REDIRECT_MESSAGE => Some("redirect"),
+        TYPE_ROUTER_RENUMBERING => Some("router renumbering"),
+        TYPE_INVERSE_NEIGHBOR_DISCOVERY_SOLICITATION => Some("inverse neighbor solicitation"),
+        TYPE_INVERSE_NEIGHBOR_DISCOVERY_ADVERTISEMENT => Some("inverse neighbor advertisement"),
+        TYPE_MULTICAST_LISTENER_REPORT_V2 => Some("multicast listener report v2"),
+        TYPE_EXT_ECHO_REQUEST => Some("extended echo request"),
+        TYPE_EXT_ECHO_REPLY => Some("extended echo reply"),
+        _ => None,
+    }
+}
+
+fn dccp_packet_type_name(type_value: u8) -> &'static str {
+    match type_value {
+        0 => "Request",
+        1 => "Response",
+        2 => "Data",
+        3 => "Ack",
+        4 => "DataAck",
+        5 => "CloseReq",
+        6 => "Close",
+        7 => "Reset",
+        8 => "Sync",
+        9 => "SyncAck",
+        _ => "Unknown",
+    }
+}
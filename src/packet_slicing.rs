@@ -24,10 +24,18 @@ pub enum TransportSlice<'a> {
     Icmpv4(Icmpv4Slice<'a>),
     /// A slice containing an Icmp6 header
     Icmpv6(Icmpv6Slice<'a>),
+    /// A slice containing an IGMP message
+    Igmp(IgmpSlice<'a>),
+    /// A slice containing a SCTP common header (the chunks are stored in the payload).
+    Sctp(SctpHeaderSlice<'a>),
     /// A slice containing an UDP header.
     Udp(UdpHeaderSlice<'a>),
     /// A slice containing a TCP header.
     Tcp(TcpHeaderSlice<'a>),
+    /// A slice containing an UDP-Lite header.
+    UdpLite(UdpLiteHeaderSlice<'a>),
+    /// A slice containing a DCCP header (including the options).
+    Dccp(DccpHeaderSlice<'a>),
     /// Unknonwn transport layer protocol. The value is the last parsed ip protocol number.
     Unknown(u8),
 }
@@ -42,7 +50,9 @@ pub enum TransportSlice<'a> {
 /// * [`SlicedPacket::from_ether_type`]
 /// * [`SlicedPacket::from_ip`]
 ///
-/// depending on your starting header to slice a packet.
+/// depending on your starting header to slice a packet. If you want to keep the
+/// successfully sliced headers in case of an error use the lax versions in
+/// [`LaxSlicedPacket`] instead.
 ///
 /// # Examples
 ///
@@ -101,6 +111,10 @@ impl<'a> SlicedPacket<'a> {
     /// The result is returned as a [`SlicedPacket`] struct. This function assumes the given data starts
     /// with an ethernet II header.
     ///
+    /// Errors are returned together with the layer & offset at which the
+    /// slicing stopped (see [`PacketReadError`]). Use [`LaxSlicedPacket::from_ethernet`] if the
+    /// headers before the error are needed as well.
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -131,8 +145,8 @@ impl<'a> SlicedPacket<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ethernet(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ethernet2()
+    pub fn from_ethernet(data: &'a [u8]) -> Result<SlicedPacket<'a>, PacketReadError> {
+        CursorSlice::new(data).slice_ethernet2().into_result()
     }
 
     /// Seperates a network packet slice into different slices containing the headers using
@@ -187,22 +201,92 @@ impl<'a> SlicedPacket<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        use ether_type::*;
-        match ether_type {
-            IPV4 => CursorSlice::new(data).slice_ipv4(),
-            IPV6 => CursorSlice::new(data).slice_ipv6(),
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME =>
-                CursorSlice::new(data).slice_vlan(),
-            _ => Ok(
-                SlicedPacket {
-                    link: None,
-                    vlan: None,
-                    ip: None,
-                    transport: None,
-                    payload: data
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<SlicedPacket<'a>, PacketReadError> {
+        CursorSlice::new(data).slice_ether_type(ether_type).into_result()
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers using
+    /// the given link type (e.g. from a pcap or pcapng file) to identify the first header.
+    ///
+    /// The result is returned as a [`SlicedPacket`] struct. Currently supported
+    /// link types are:
+    ///
+    /// * `link_type::ETHERNET`
+    /// * `link_type::RAW`, `link_type::IPV4` & `link_type::IPV6`
+    /// * `link_type::NULL` & `link_type::LOOP` (IPv4 & IPv6 protocol families)
+    /// * `link_type::LINUX_SLL`
+    ///
+    /// The loopback & linux "cooked" capture headers are skipped and not
+    /// present in the result. If an unsupported link type is given the given
+    /// slice will be set as payload and all other fields will be set to `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// # let builder = PacketBuilder::
+    /// #    ipv4([192,168,1,1], //source ip
+    /// #         [192,168,1,2], //desitionation ip
+    /// #         20)            //time to life
+    /// #    .udp(21,    //source port
+    /// #         1234); //desitnation port
+    /// # let payload = [1,2,3,4,5,6,7,8];
+    /// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    /// # builder.write(&mut packet, &payload).unwrap();
+    /// use etherparse::{link_type, SlicedPacket};
+    ///
+    /// match SlicedPacket::from_link_type(link_type::RAW, &packet) {
+    ///     Err(value) => println!("Err {:?}", value),
+    ///     Ok(value) => {
+    ///         println!("ip: {:?}", value.ip);
+    ///         println!("transport: {:?}", value.transport);
+    ///     }
+    /// }
+    /// ```
+    pub fn from_link_type(link_type: u16, data: &'a [u8]) -> Result<SlicedPacket<'a>, PacketReadError> {
+        use link_type::*;
+        use ReadError::*;
+        match link_type {
+            ETHERNET => CursorSlice::new(data).slice_ethernet2().into_result(),
+            RAW => CursorSlice::new(data).slice_ip().into_result(),
+            IPV4 => CursorSlice::new(data).slice_ipv4().into_result(),
+            IPV6 => CursorSlice::new(data).slice_ipv6().into_result(),
+            NULL | LOOP => {
+                if data.len() < 4 {
+                    return CursorSlice::new(data).stop(UnexpectedEndOfSlice(4), Layer::LinkHeader).into_result();
                 }
-            ),
+                let family = if LOOP == link_type {
+                    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+                } else {
+                    // host byte order of the capturing system, as protocol
+                    // families are small values the byte order can be
+                    // determined by checking which bytes are set
+                    let value = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+                    if 0 == value & 0xffff {
+                        value.swap_bytes()
+                    } else {
+                        value
+                    }
+                };
+                let mut cursor = CursorSlice::new(data);
+                cursor.move_by_slice(&data[..4]);
+                match family {
+                    // AF_INET
+                    2 => cursor.slice_ipv4(),
+                    // AF_INET6 (Linux, BSD, FreeBSD & Darwin)
+                    10 | 24 | 28 | 30 => cursor.slice_ipv6(),
+                    _ => cursor.slice_payload(),
+                }.into_result()
+            },
+            LINUX_SLL => {
+                if data.len() < 16 {
+                    return CursorSlice::new(data).stop(UnexpectedEndOfSlice(16), Layer::LinkHeader).into_result();
+                }
+                let mut cursor = CursorSlice::new(data);
+                cursor.move_by_slice(&data[..16]);
+                cursor.slice_ether_type(u16::from_be_bytes([data[14], data[15]])).into_result()
+            },
+            _ => CursorSlice::new(data).slice_payload().into_result(),
         }
     }
 
@@ -242,8 +326,8 @@ impl<'a> SlicedPacket<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ip(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ip()
+    pub fn from_ip(data: &'a [u8]) -> Result<SlicedPacket<'a>, PacketReadError> {
+        CursorSlice::new(data).slice_ip().into_result()
     }
 
     /// If the slice in the `payload` field contains an ethernet payload
@@ -285,6 +369,115 @@ impl<'a> SlicedPacket<'a> {
     }
 }
 
+/// Result of slicing a packet in lax mode: all headers that could be sliced
+/// plus the error & its location at which slicing stopped (if an error occurred).
+///
+/// Lax slicing is useful for packets that were truncated (e.g. captures with a
+/// snaplen or the "original datagram" embedded in ICMP error messages), as the
+/// successfully sliced headers are kept.
+///
+/// ```
+/// use etherparse::{Layer, LaxSlicedPacket, PacketBuilder, ReadErrorContext};
+///
+/// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .tcp(21, 1234, 1, 4000);
+/// let mut packet = Vec::with_capacity(builder.size(0));
+/// builder.write(&mut packet, &[]).unwrap();
+///
+/// // truncated tcp header
+/// let result = LaxSlicedPacket::from_ethernet(&packet[..40]);
+/// assert!(result.packet.link.is_some());
+/// assert!(result.packet.ip.is_some());
+/// assert!(result.packet.transport.is_none());
+/// // the payload contains the data that could not be sliced
+/// assert_eq!(&packet[34..40], result.packet.payload);
+/// let (err, context) = result.stop_err.unwrap();
+/// assert_eq!(Some(34 + 20), err.unexpected_end_of_slice_min_expected_size());
+/// assert_eq!(
+///     ReadErrorContext{
+///         layer: Layer::TcpHeader,
+///         offset: 34,
+///         required_len: Some(20),
+///         available_len: 6,
+///         field: None,
+///     },
+///     context
+/// );
+/// ```
+#[derive(Debug)]
+pub struct LaxSlicedPacket<'a> {
+    /// Headers that could be sliced. If slicing stopped because of an error,
+    /// the payload contains the rest of the packet starting at the header
+    /// that could not be sliced.
+    pub packet: SlicedPacket<'a>,
+    /// Error & its location at which slicing stopped (`None` if the complete packet was sliced).
+    ///
+    /// Lengths in the error (e.g. `ReadError::UnexpectedEndOfSlice`) are relative to the
+    /// start of the packet (the `offset` of the context is already added).
+    pub stop_err: Option<(ReadError, ReadErrorContext)>,
+}
+
+impl<'a> LaxSlicedPacket<'a> {
+    /// Slices a packet starting with an Ethernet II header and keeps the
+    /// successfully sliced headers if an error is encountered.
+    ///
+    /// See [`SlicedPacket::from_ethernet`] for the strict version.
+    pub fn from_ethernet(data: &'a [u8]) -> LaxSlicedPacket<'a> {
+        CursorSlice::new(data).slice_ethernet2()
+    }
+
+    /// Slices a packet starting with the header identified by the given ether type
+    /// and keeps the successfully sliced headers if an error is encountered.
+    ///
+    /// See [`SlicedPacket::from_ether_type`] for the strict version.
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> LaxSlicedPacket<'a> {
+        CursorSlice::new(data).slice_ether_type(ether_type)
+    }
+
+    /// Slices a packet starting with an IPv4 or IPv6 header and keeps the
+    /// successfully sliced headers if an error is encountered.
+    ///
+    /// See [`SlicedPacket::from_ip`] for the strict version. This can also
+    /// be used to slice the packet embedded in ICMP error messages:
+    ///
+    /// ```
+    /// use etherparse::{Layer, LaxSlicedPacket, PacketBuilder, SlicedPacket, TransportSlice};
+    ///
+    /// // packet that caused the error
+    /// let builder = PacketBuilder::ipv4([192,168,1,2], [10,0,0,1], 1)
+    ///     .tcp(1234, 80, 1, 4000);
+    /// let mut invoking = Vec::with_capacity(builder.size(0));
+    /// builder.write(&mut invoking, &[]).unwrap();
+    ///
+    /// // icmp time exceeded message containing the first 28 bytes of the packet
+    /// let builder = PacketBuilder::ipv4([10,0,0,254], [192,168,1,2], 20)
+    ///     .icmpv4_raw(11, 0, [0;4]);
+    /// let mut packet = Vec::with_capacity(builder.size(28));
+    /// builder.write(&mut packet, &invoking[..28]).unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    /// if let Some(TransportSlice::Icmpv4(icmp)) = sliced.transport {
+    ///     let embedded = LaxSlicedPacket::from_ip(icmp.original_datagram());
+    ///     assert!(embedded.packet.ip.is_some());
+    ///     assert_eq!(Some(Layer::TcpHeader), embedded.stop_err.map(|(_, context)| context.layer));
+    ///     // the ports are contained in the first 8 bytes of the tcp header
+    ///     assert_eq!(&[0x04, 0xd2, 0, 80], &embedded.packet.payload[..4]);
+    /// }
+    /// ```
+    pub fn from_ip(data: &'a [u8]) -> LaxSlicedPacket<'a> {
+        CursorSlice::new(data).slice_ip()
+    }
+
+    /// Converts the result to the result of the strict slicing functions.
+    fn into_result(self) -> Result<SlicedPacket<'a>, PacketReadError> {
+        match self.stop_err {
+            Some((error, context)) => Err(PacketReadError{ error, context }),
+            None => Ok(self.packet),
+        }
+    }
+}
+
 ///Helper class for slicing packets
 struct CursorSlice<'a> {
     pub slice: &'a [u8],
@@ -310,7 +503,7 @@ impl<'a> CursorSlice<'a> {
 
     fn move_by_slice(&mut self, other: &'a[u8]) {
         unsafe {
-            use std::slice::from_raw_parts;
+            use core::slice::from_raw_parts;
             self.slice = from_raw_parts(
                 self.slice.as_ptr().add(other.len()),
                 self.slice.len() - other.len()
@@ -324,14 +517,24 @@ impl<'a> CursorSlice<'a> {
         self.slice = other;
     }
 
-    pub fn slice_ethernet2(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    /// Stops slicing because of an error (the unparsed rest is set as payload).
+    fn stop(mut self, err: ReadError, layer: Layer) -> LaxSlicedPacket<'a> {
+        let context = ReadErrorContext::new(&err, layer, self.offset, self.slice.len());
+        self.result.payload = self.slice;
+        LaxSlicedPacket {
+            packet: self.result,
+            stop_err: Some((err.add_slice_offset(self.offset), context)),
+        }
+    }
+
+    pub fn slice_ethernet2(mut self) -> LaxSlicedPacket<'a> {
         use LinkSlice::*;
         use ether_type::*;
 
-        let result = Ethernet2HeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        let result = match Ethernet2HeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(err) => return self.stop(err, Layer::Ethernet2Header),
+        };
 
         //cache the ether_type for later
         let ether_type = result.ether_type();
@@ -349,14 +552,14 @@ impl<'a> CursorSlice<'a> {
         }
     }
 
-    pub fn slice_vlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_vlan(mut self) -> LaxSlicedPacket<'a> {
         use VlanSlice::*;
         use ether_type::*;
 
-        let single = SingleVlanHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        let single = match SingleVlanHeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(err) => return self.stop(err, Layer::VlanHeader),
+        };
 
         //check if it is a double vlan header
         match single.ether_type() {
@@ -376,14 +579,14 @@ impl<'a> CursorSlice<'a> {
         }
     }
 
-    pub fn slice_double_vlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_double_vlan(mut self) -> LaxSlicedPacket<'a> {
         use VlanSlice::*;
         use ether_type::*;
 
-        let result = DoubleVlanHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        let result = match DoubleVlanHeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(err) => return self.stop(err, Layer::VlanHeader),
+        };
 
         //cache ether_type for later
         let ether_type = result.inner().ether_type();
@@ -400,37 +603,41 @@ impl<'a> CursorSlice<'a> {
         }
     }
 
-    pub fn slice_ip(self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_ip(self) -> LaxSlicedPacket<'a> {
         use ReadError::*;
 
         if self.slice.is_empty() {
-            Err(UnexpectedEndOfSlice(self.offset + 1))
+            // offset is added when stopping
+            self.stop(UnexpectedEndOfSlice(1), Layer::IpHeader)
         } else {
             match self.slice[0] >> 4 {
                 4 => self.slice_ipv4(),
                 6 => self.slice_ipv6(),
-                version => Err(IpUnsupportedVersion(version))
+                version => self.stop(IpUnsupportedVersion(version), Layer::IpHeader)
             }
         }
     }
 
-    pub fn slice_ipv4(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_ipv4(mut self) -> LaxSlicedPacket<'a> {
         use InternetSlice::*;
 
-        let ip_header = Ipv4HeaderSlice::from_slice(self.slice)
-                        .map_err(|err| 
-                            err.add_slice_offset(self.offset)
-                        )?;
+        let ip_header = match Ipv4HeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(err) => return self.stop(err, Layer::Ipv4Header),
+        };
         let fragmented = ip_header.is_fragmenting_payload();
 
         // move the slice
         self.move_by_slice(ip_header.slice());
 
         // slice extensions
-        let (ip_ext, protocol, rest) = Ipv4ExtensionsSlice::from_slice(ip_header.protocol(), self.slice)
-                                       .map_err(|err| 
-                                            err.add_slice_offset(self.offset)
-                                       )?;
+        let (ip_ext, protocol, rest) = match Ipv4ExtensionsSlice::from_slice(ip_header.protocol(), self.slice) {
+            Ok(value) => value,
+            Err(err) => {
+                self.result.ip = Some(Ipv4(ip_header, Default::default()));
+                return self.stop(err, Layer::Ipv4Extensions);
+            },
+        };
 
         // set the new data
         self.move_to_slice(rest);
@@ -439,36 +646,29 @@ impl<'a> CursorSlice<'a> {
         if fragmented {
             self.slice_payload()
         } else {
-            match protocol {
-                ip_number::UDP => self.slice_udp(),
-                ip_number::TCP => self.slice_tcp(),
-                ip_number::ICMP => self.slice_icmp4(),
-                ip_number::IPV6_ICMP => self.slice_icmp6(),
-                value => {
-                    use TransportSlice::*;
-                    self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
-                }
-            }
+            self.slice_transport(protocol)
         }
     }
 
-    pub fn slice_ipv6(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_ipv6(mut self) -> LaxSlicedPacket<'a> {
         use crate::InternetSlice::*;
 
-        let ip = Ipv6HeaderSlice::from_slice(self.slice)
-                 .map_err(|err| 
-                    err.add_slice_offset(self.offset)
-                 )?;
+        let ip = match Ipv6HeaderSlice::from_slice(self.slice) {
+            Ok(value) => value,
+            Err(err) => return self.stop(err, Layer::Ipv6Header),
+        };
 
         //move the slice
         self.move_by_slice(ip.slice());
 
         //extension headers
-        let (ip_ext, next_header, rest) = Ipv6ExtensionsSlice::from_slice(ip.next_header(), self.slice)
-                                          .map_err(|err| 
-                                              err.add_slice_offset(self.offset)
-                                          )?;
+        let (ip_ext, next_header, rest) = match Ipv6ExtensionsSlice::from_slice(ip.next_header(), self.slice) {
+            Ok(value) => value,
+            Err(err) => {
+                self.result.ip = Some(Ipv6(ip, Default::default()));
+                return self.stop(err, Layer::Ipv6Extensions);
+            },
+        };
         let fragmented = ip_ext.is_fragmenting_payload();
 
         // set the new data 
@@ -480,88 +680,58 @@ impl<'a> CursorSlice<'a> {
         if fragmented {
             self.slice_payload()
         } else {
-            //parse the data bellow
-            match next_header {
-                ip_number::ICMP => self.slice_icmp4(),
-                ip_number::UDP => self.slice_udp(),
-                ip_number::TCP => self.slice_tcp(),
-                ip_number::IPV6_ICMP => self.slice_icmp6(),
-                value => {
-                    use TransportSlice::*;
-                    self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
-                }
-            }
+            self.slice_transport(next_header)
         }
     }
 
-    pub fn slice_icmp4(mut self) -> Result<SlicedPacket<'a>, ReadError> {
-        use crate::TransportSlice::*;
-
-        let result = Icmpv4Slice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
-
-        //set the new data
-        self.move_by_slice(result.slice());
-        self.result.transport = Some(Icmpv4(result));
-
-        //done
-        self.slice_payload()
-    }
-
-    pub fn slice_icmp6(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    fn slice_transport(mut self, ip_number: u8) -> LaxSlicedPacket<'a> {
         use crate::TransportSlice::*;
 
-        let result = Icmpv6Slice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
-
-        //set the new data
-        self.move_by_slice(result.slice());
-        self.result.transport = Some(Icmpv6(result));
-
-        //done
-        self.slice_payload()
-    }
-
-    pub fn slice_udp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
-        use crate::TransportSlice::*;
-
-        let result = UdpHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
-
-        //set the new data
-        self.move_by_slice(result.slice());
-        self.result.transport = Some(Udp(result));
+        macro_rules! slice_transport {
+            ($slice_type:ty, $variant:ident, $layer:ident) => {
+                match <$slice_type>::from_slice(self.slice) {
+                    Ok(value) => {
+                        self.move_by_slice(value.slice());
+                        self.result.transport = Some($variant(value));
+                        self.slice_payload()
+                    },
+                    Err(err) => self.stop(err, Layer::$layer),
+                }
+            }
+        }
 
-        //done
-        self.slice_payload()
+        match ip_number {
+            ip_number::ICMP => slice_transport!(Icmpv4Slice, Icmpv4, Icmpv4),
+            ip_number::UDP => slice_transport!(UdpHeaderSlice, Udp, UdpHeader),
+            ip_number::TCP => slice_transport!(TcpHeaderSlice, Tcp, TcpHeader),
+            ip_number::IPV6_ICMP => slice_transport!(Icmpv6Slice, Icmpv6, Icmpv6),
+            ip_number::IGMP => slice_transport!(IgmpSlice, Igmp, Igmp),
+            ip_number::SCTP => slice_transport!(SctpHeaderSlice, Sctp, SctpHeader),
+            ip_number::UDP_LITE => slice_transport!(UdpLiteHeaderSlice, UdpLite, UdpLiteHeader),
+            ip_number::DCCP => slice_transport!(DccpHeaderSlice, Dccp, DccpHeader),
+            value => {
+                self.result.transport = Some(Unknown(value));
+                self.slice_payload()
+            }
+        }
     }
 
-    pub fn slice_tcp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
-        use crate::TransportSlice::*;
-
-        let result = TcpHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
-
-        //set the new data
-        self.move_by_slice(result.slice());
-        self.result.transport = Some(Tcp(result));
-
-        //done
-        self.slice_payload()
+    pub fn slice_ether_type(self, ether_type: u16) -> LaxSlicedPacket<'a> {
+        use ether_type::*;
+        match ether_type {
+            IPV4 => self.slice_ipv4(),
+            IPV6 => self.slice_ipv6(),
+            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_vlan(),
+            _ => self.slice_payload()
+        }
     }
 
-    pub fn slice_payload(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_payload(mut self) -> LaxSlicedPacket<'a> {
         self.result.payload = self.slice;
-        Ok(self.result)
+        LaxSlicedPacket {
+            packet: self.result,
+            stop_err: None,
+        }
     }
 
 }
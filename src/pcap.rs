@@ -0,0 +1,463 @@
+//! Reading & writing of pcap capture files.
+//!
+//! Supported are files with microsecond & nanosecond timestamps in both
+//! byte orders. The [`PcapReader`] reads packets from any [`std::io::Read`]
+//! source, the [`PcapSliceReader`] iterates over the packets of a file already
+//! loaded into memory & the [`PcapWriter`] writes packets (e.g. generated
+//! via a [`PacketBuilder`]) to a capture file.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use etherparse::{link_type, PacketBuilder};
+//! use etherparse::pcap::*;
+//!
+//! // write a packet to a capture file (a Vec in this case)
+//! let mut writer = PcapWriter::new(Vec::new(), PcapHeader::new(link_type::ETHERNET)).unwrap();
+//! {
+//!     let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!         .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!         .udp(21, 1234);
+//!     let mut packet = Vec::with_capacity(builder.size(4));
+//!     builder.write(&mut packet, &[1,2,3,4]).unwrap();
+//!     writer.write_packet(Duration::from_millis(1500), &packet).unwrap();
+//! }
+//! let file = writer.into_inner();
+//!
+//! // read the packets again
+//! let reader = PcapSliceReader::new(&file).unwrap();
+//! for packet in reader {
+//!     let packet = packet.unwrap();
+//!     assert_eq!(Duration::from_millis(1500), packet.timestamp);
+//!     // slice the packet based on the link type of the file
+//!     let sliced = packet.slice().unwrap();
+//!     assert_eq!(&[1,2,3,4], sliced.payload);
+//! }
+//! ```
+use super::*;
+
+#[cfg(feature = "std")]
+use std::io;
+use core::time::Duration;
+
+/// Magic number of pcap files with microsecond timestamps.
+pub const MAGIC_NUMBER_MICROSECONDS: u32 = 0xa1b2_c3d4;
+
+/// Magic number of pcap files with nanosecond timestamps.
+pub const MAGIC_NUMBER_NANOSECONDS: u32 = 0xa1b2_3c4d;
+
+/// Default snapshot length & minimum allowed captured length of packets
+/// when reading (some capture tools write packets bigger then the
+/// snapshot length).
+pub const MAX_SNAPLEN: u32 = 262_144;
+
+/// Resolution of the packet timestamps in a pcap file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PcapTimestampPrecision {
+    Microseconds,
+    Nanoseconds,
+}
+
+/// Byte order of the fields in a pcap or pcapng file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PcapByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+/// Global header at the start of a pcap file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapHeader {
+    /// Byte order of the header & packet record fields.
+    pub byte_order: PcapByteOrder,
+    /// Resolution of the packet timestamps.
+    pub precision: PcapTimestampPrecision,
+    pub version_major: u16,
+    pub version_minor: u16,
+    /// Maximum number of bytes captured per packet.
+    pub snaplen: u32,
+    /// Link type of all packets in the file (see [`crate::link_type`]).
+    pub link_type: u16,
+    /// Length of the frame check sequence at the end of each packet in 16 bit words (if known).
+    pub fcs_len: Option<u8>,
+}
+
+impl PcapHeader {
+    /// Serialized size of the header in bytes.
+    pub const SERIALIZED_SIZE: usize = 24;
+
+    /// Creates a header for a little endian file with microsecond timestamps
+    /// (the most widely supported variant).
+    pub fn new(link_type: u16) -> PcapHeader {
+        PcapHeader {
+            byte_order: PcapByteOrder::LittleEndian,
+            precision: PcapTimestampPrecision::Microseconds,
+            version_major: 2,
+            version_minor: 4,
+            snaplen: MAX_SNAPLEN,
+            link_type,
+            fcs_len: None,
+        }
+    }
+
+    /// Decodes the header from the start of the slice & returns it together
+    /// with the rest of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(PcapHeader, &[u8]), ReadError> {
+        use ReadError::*;
+        if slice.len() < PcapHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(PcapHeader::SERIALIZED_SIZE));
+        }
+
+        let magic = [slice[0], slice[1], slice[2], slice[3]];
+        let (byte_order, precision) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (MAGIC_NUMBER_MICROSECONDS, _) => (PcapByteOrder::LittleEndian, PcapTimestampPrecision::Microseconds),
+            (MAGIC_NUMBER_NANOSECONDS, _) => (PcapByteOrder::LittleEndian, PcapTimestampPrecision::Nanoseconds),
+            (_, MAGIC_NUMBER_MICROSECONDS) => (PcapByteOrder::BigEndian, PcapTimestampPrecision::Microseconds),
+            (_, MAGIC_NUMBER_NANOSECONDS) => (PcapByteOrder::BigEndian, PcapTimestampPrecision::Nanoseconds),
+            (_, value) => return Err(PcapUnknownMagicNumber(value)),
+        };
+
+        let u16_at = |offset: usize| byte_order.u16_from([slice[offset], slice[offset + 1]]);
+        let u32_at = |offset: usize| byte_order.u32_from(
+            [slice[offset], slice[offset + 1], slice[offset + 2], slice[offset + 3]]
+        );
+        // bytes 8..16 contain the time zone & timestamp accuracy (both are always 0 in practice)
+        let link_type_and_fcs = u32_at(20);
+        Ok((
+            PcapHeader {
+                byte_order,
+                precision,
+                version_major: u16_at(4),
+                version_minor: u16_at(6),
+                snaplen: u32_at(16),
+                link_type: (link_type_and_fcs & 0xffff) as u16,
+                fcs_len: if 0 != link_type_and_fcs & 0x1000_0000 {
+                    Some((link_type_and_fcs >> 29) as u8)
+                } else {
+                    None
+                },
+            },
+            &slice[PcapHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Reads the header from the given reader.
+    #[cfg(feature = "std")]
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<PcapHeader, ReadError> {
+        let mut buffer = [0u8; PcapHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(PcapHeader::from_slice(&buffer)?.0)
+    }
+
+    /// Returns the serialized header.
+    pub fn to_bytes(&self) -> [u8; PcapHeader::SERIALIZED_SIZE] {
+        let magic = match self.precision {
+            PcapTimestampPrecision::Microseconds => MAGIC_NUMBER_MICROSECONDS,
+            PcapTimestampPrecision::Nanoseconds => MAGIC_NUMBER_NANOSECONDS,
+        };
+        let link_type_and_fcs = u32::from(self.link_type) | match self.fcs_len {
+            Some(value) => 0x1000_0000 | (u32::from(value & 0b111) << 29),
+            None => 0,
+        };
+        let mut result = [0u8; PcapHeader::SERIALIZED_SIZE];
+        result[0..4].copy_from_slice(&self.byte_order.u32_to(magic));
+        result[4..6].copy_from_slice(&self.byte_order.u16_to(self.version_major));
+        result[6..8].copy_from_slice(&self.byte_order.u16_to(self.version_minor));
+        result[16..20].copy_from_slice(&self.byte_order.u32_to(self.snaplen));
+        result[20..24].copy_from_slice(&self.byte_order.u32_to(link_type_and_fcs));
+        result
+    }
+
+    /// Writes the header to the given writer.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Decodes a packet record header (timestamp, captured length & original length).
+    fn decode_record_header(&self, bytes: &[u8]) -> Result<(Duration, u32, u32), ReadError> {
+        let u32_at = |offset: usize| self.byte_order.u32_from(
+            [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]
+        );
+        let seconds = Duration::from_secs(u64::from(u32_at(0)));
+        let timestamp = match self.precision {
+            PcapTimestampPrecision::Microseconds => seconds + Duration::from_micros(u64::from(u32_at(4))),
+            PcapTimestampPrecision::Nanoseconds => seconds + Duration::from_nanos(u64::from(u32_at(4))),
+        };
+        let captured_len = u32_at(8);
+        let max_len = self.snaplen.max(MAX_SNAPLEN);
+        if captured_len > max_len {
+            return Err(ReadError::PcapCapturedLengthTooBig{ captured_len, max_len });
+        }
+        Ok((timestamp, captured_len, u32_at(12)))
+    }
+
+    /// Encodes a packet record header.
+    fn encode_record_header(&self, timestamp: Duration, captured_len: u32, original_len: u32) -> [u8; PCAP_RECORD_HEADER_SIZE] {
+        let fraction = match self.precision {
+            PcapTimestampPrecision::Microseconds => timestamp.subsec_micros(),
+            PcapTimestampPrecision::Nanoseconds => timestamp.subsec_nanos(),
+        };
+        let mut result = [0u8; PCAP_RECORD_HEADER_SIZE];
+        result[0..4].copy_from_slice(&self.byte_order.u32_to(timestamp.as_secs() as u32));
+        result[4..8].copy_from_slice(&self.byte_order.u32_to(fraction));
+        result[8..12].copy_from_slice(&self.byte_order.u32_to(captured_len));
+        result[12..16].copy_from_slice(&self.byte_order.u32_to(original_len));
+        result
+    }
+}
+
+/// Size of the header in front of every packet.
+const PCAP_RECORD_HEADER_SIZE: usize = 16;
+
+impl PcapByteOrder {
+    pub(crate) fn u16_from(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            PcapByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            PcapByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn u32_from(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            PcapByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            PcapByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn u64_from(self, bytes: [u8; 8]) -> u64 {
+        match self {
+            PcapByteOrder::LittleEndian => u64::from_le_bytes(bytes),
+            PcapByteOrder::BigEndian => u64::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn u16_to(self, value: u16) -> [u8; 2] {
+        match self {
+            PcapByteOrder::LittleEndian => value.to_le_bytes(),
+            PcapByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    pub(crate) fn u32_to(self, value: u32) -> [u8; 4] {
+        match self {
+            PcapByteOrder::LittleEndian => value.to_le_bytes(),
+            PcapByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    pub(crate) fn u64_to(self, value: u64) -> [u8; 8] {
+        match self {
+            PcapByteOrder::LittleEndian => value.to_le_bytes(),
+            PcapByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+}
+
+/// A packet read from a pcap file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapPacket<'a> {
+    /// Capture time of the packet (since the UNIX epoch).
+    pub timestamp: Duration,
+    /// Length of the packet on the wire (can be bigger then `data` if
+    /// the packet was truncated to the snapshot length).
+    pub original_len: u32,
+    /// Link type of the packet (see [`crate::link_type`]).
+    pub link_type: u16,
+    /// Captured bytes of the packet.
+    pub data: &'a [u8],
+}
+
+impl<'a> PcapPacket<'a> {
+    /// Returns true if not the complete packet was captured.
+    pub fn is_truncated(&self) -> bool {
+        (self.data.len() as u64) < u64::from(self.original_len)
+    }
+
+    /// Slices the packet based on its link type (see [`SlicedPacket::from_link_type`]).
+    pub fn slice(&self) -> Result<SlicedPacket<'a>, PacketReadError> {
+        SlicedPacket::from_link_type(self.link_type, self.data)
+    }
+}
+
+/// Iterates over the packets of a pcap file stored in a slice.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapSliceReader<'a> {
+    header: PcapHeader,
+    rest: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> PcapSliceReader<'a> {
+    /// Decodes the pcap header & creates an iterator over the packets.
+    pub fn new(slice: &'a [u8]) -> Result<PcapSliceReader<'a>, ReadError> {
+        let (header, rest) = PcapHeader::from_slice(slice)?;
+        Ok(PcapSliceReader {
+            header,
+            rest,
+            offset: PcapHeader::SERIALIZED_SIZE,
+        })
+    }
+
+    /// Returns the header of the pcap file.
+    pub fn header(&self) -> &PcapHeader {
+        &self.header
+    }
+}
+
+impl<'a> Iterator for PcapSliceReader<'a> {
+    type Item = Result<PcapPacket<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use ReadError::*;
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        // stop after the first error
+        let rest = core::mem::take(&mut self.rest);
+        if rest.len() < PCAP_RECORD_HEADER_SIZE {
+            return Some(Err(UnexpectedEndOfSlice(self.offset + PCAP_RECORD_HEADER_SIZE)));
+        }
+        let (timestamp, captured_len, original_len) = match self.header.decode_record_header(rest) {
+            Ok(value) => value,
+            Err(err) => return Some(Err(err)),
+        };
+        let end = PCAP_RECORD_HEADER_SIZE + captured_len as usize;
+        if rest.len() < end {
+            return Some(Err(UnexpectedEndOfSlice(self.offset + end)));
+        }
+
+        self.rest = &rest[end..];
+        self.offset += end;
+        Some(Ok(PcapPacket {
+            timestamp,
+            original_len,
+            link_type: self.header.link_type,
+            data: &rest[PCAP_RECORD_HEADER_SIZE..end],
+        }))
+    }
+}
+
+/// Reads the packets of a pcap file from a [`std::io::Read`] source.
+#[derive(Debug)]
+pub struct PcapReader<T: io::Read> {
+    reader: T,
+    header: PcapHeader,
+    buffer: Vec<u8>,
+}
+
+impl<T: io::Read> PcapReader<T> {
+    /// Reads the pcap header & creates a reader for the packets.
+    pub fn new(mut reader: T) -> Result<PcapReader<T>, ReadError> {
+        let header = PcapHeader::read(&mut reader)?;
+        Ok(PcapReader {
+            reader,
+            header,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Returns the header of the pcap file.
+    pub fn header(&self) -> &PcapHeader {
+        &self.header
+    }
+
+    /// Reads the next packet. Returns `Ok(None)` if the end of the file was reached.
+    ///
+    /// An `IoError` with the kind `UnexpectedEof` is returned if the
+    /// file ends within a packet.
+    pub fn next_packet(&mut self) -> Result<Option<PcapPacket<'_>>, ReadError> {
+        let mut record_header = [0u8; PCAP_RECORD_HEADER_SIZE];
+
+        // an end of file directly before a record is the regular end
+        let mut filled = 0;
+        while filled < record_header.len() {
+            match self.reader.read(&mut record_header[filled..]) {
+                Ok(0) if 0 == filled => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(len) => filled += len,
+                Err(err) if io::ErrorKind::Interrupted == err.kind() => {},
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let (timestamp, captured_len, original_len) = self.header.decode_record_header(&record_header)?;
+        self.buffer.resize(captured_len as usize, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+        Ok(Some(PcapPacket {
+            timestamp,
+            original_len,
+            link_type: self.header.link_type,
+            data: &self.buffer,
+        }))
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> T {
+        self.reader
+    }
+}
+
+/// Writes packets to a pcap file.
+#[derive(Debug)]
+pub struct PcapWriter<T: io::Write> {
+    writer: T,
+    header: PcapHeader,
+}
+
+impl<T: io::Write> PcapWriter<T> {
+    /// Writes the given header & creates a writer for the packets.
+    pub fn new(mut writer: T, header: PcapHeader) -> Result<PcapWriter<T>, WriteError> {
+        header.write(&mut writer)?;
+        Ok(PcapWriter {
+            writer,
+            header,
+        })
+    }
+
+    /// Returns the header of the pcap file.
+    pub fn header(&self) -> &PcapHeader {
+        &self.header
+    }
+
+    /// Writes a packet. Packets bigger then the snapshot length get truncated.
+    pub fn write_packet(&mut self, timestamp: Duration, data: &[u8]) -> Result<(), WriteError> {
+        let original_len = if data.len() > u32::MAX as usize {
+            u32::MAX
+        } else {
+            data.len() as u32
+        };
+        self.write_packet_with_original_len(timestamp, original_len, data)
+    }
+
+    /// Writes a packet that was already truncated during the capture (e.g.
+    /// when copying packets from another capture file). Packets bigger then
+    /// the snapshot length get truncated.
+    pub fn write_packet_with_original_len(&mut self, timestamp: Duration, original_len: u32, data: &[u8]) -> Result<(), WriteError> {
+        let captured = if 0 != self.header.snaplen && data.len() > self.header.snaplen as usize {
+            &data[..self.header.snaplen as usize]
+        } else {
+            data
+        };
+        let captured_len = captured.len() as u32;
+        self.writer.write_all(
+            &self.header.encode_record_header(timestamp, captured_len, original_len.max(captured_len))
+        )?;
+        self.writer.write_all(captured)?;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), WriteError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> T {
+        self.writer
+    }
+}
@@ -0,0 +1,733 @@
+//! Parser for the pcap-filter language (the filter expressions used by tcpdump).
+//!
+//! [`parse`] compiles an expression like `tcp port 443 and not net 10.0.0.0/8`
+//! into a [`FilterExpression`] that can be evaluated against sliced packets.
+//! The following subset of the language is supported:
+//!
+//! * `host`, `net` (CIDR, partial addresses or `mask`), `port` & `portrange`
+//!   with the optional direction qualifiers `src`, `dst`, `src or dst` & `src and dst`
+//! * the protocol qualifiers `ether` (only with `host`), `ip`, `ip6`, `tcp`, `udp` & `sctp`
+//! * the protocols `ip`, `ip6`, `tcp`, `udp`, `sctp`, `icmp`, `icmp6` & `igmp`
+//!   as well as `proto`, `ip proto` & `ip6 proto`
+//! * `vlan` & `vlan <id>` (matching the outer vlan header)
+//! * byte comparisons like `tcp[13] & 2 != 0` or `ip[2:2] > 576` on the layers
+//!   `ether`, `ip`, `ip6`, `tcp`, `udp`, `icmp` & `icmp6` (including the named
+//!   offsets & values like `tcp[tcpflags] & (tcp-syn|tcp-ack) != 0`)
+//! * `and` (`&&`), `or` (`||`), `not` (`!`) & parentheses
+//!
+//! Like in libpcap `not` has the highest precedence while `and` & `or` have
+//! the same precedence and are evaluated from left to right.
+//!
+//! ```
+//! use etherparse::{PacketBuilder, SlicedPacket};
+//! use etherparse::pcap_filter;
+//!
+//! let filter = pcap_filter::parse("tcp port 443 and not net 10.0.0.0/8").unwrap();
+//!
+//! let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!     .tcp(1234, 443, 1, 4000);
+//! let mut packet = Vec::with_capacity(builder.size(0));
+//! builder.write(&mut packet, &[]).unwrap();
+//!
+//! assert!(filter.applies_to_slice(&SlicedPacket::from_ethernet(&packet).unwrap()));
+//!
+//! // unsupported parts of the language are reported with their position
+//! assert_eq!(
+//!     "PcapFilterError: The keyword 'arp' (at position 0) is not supported.",
+//!     pcap_filter::parse("arp or icmp").unwrap_err().to_string()
+//! );
+//! ```
+
+use super::*;
+use crate::packet_filter::*;
+
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+/// Errors that can occur when parsing a pcap-filter expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PcapFilterError {
+    /// The expression ended even though more tokens were expected.
+    UnexpectedEnd{ expected: &'static str },
+    /// A token was found that is not allowed at the position.
+    UnexpectedToken{ position: usize, token: String, expected: &'static str },
+    /// A character was found that is not part of the language.
+    UnexpectedCharacter{ position: usize, character: char },
+    /// A keyword of the pcap-filter language was found that is not supported.
+    Unsupported{ position: usize, keyword: String },
+    /// A value (number, address, network, port, ...) could not be parsed or is out of range.
+    InvalidValue{ position: usize, value: String, reason: &'static str },
+}
+
+#[cfg(feature = "std")]
+impl Error for PcapFilterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for PcapFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PcapFilterError::*;
+        match self {
+            UnexpectedEnd{ expected } => {
+                write!(f, "PcapFilterError: Unexpected end of the filter expression (expected {}).", expected)
+            },
+            UnexpectedToken{ position, token, expected } => {
+                write!(f, "PcapFilterError: Unexpected '{}' at position {} (expected {}).", token, position, expected)
+            },
+            UnexpectedCharacter{ position, character } => {
+                write!(f, "PcapFilterError: Unexpected character '{}' at position {}.", character, position)
+            },
+            Unsupported{ position, keyword } => {
+                write!(f, "PcapFilterError: The keyword '{}' (at position {}) is not supported.", keyword, position)
+            },
+            InvalidValue{ position, value, reason } => {
+                write!(f, "PcapFilterError: Invalid value '{}' at position {} ({}).", value, position, reason)
+            },
+        }
+    }
+}
+
+/// Parses a pcap-filter expression (see the [module documentation](self) for
+/// the supported subset). An empty expression matches all packets.
+pub fn parse(expression: &str) -> Result<FilterExpression, PcapFilterError> {
+    let mut parser = Parser{
+        tokens: tokenize(expression)?,
+        index: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Ok(FilterExpression::And(Vec::new()));
+    }
+    let result = parser.parse_expression()?;
+    match parser.next() {
+        None => Ok(result),
+        Some(token) => Err(token.unexpected("'and', 'or' or the end of the expression")),
+    }
+}
+
+/// Keywords of the pcap-filter language that are not supported by the parser.
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "ah", "arp", "atalk", "broadcast", "carp", "decnet", "dir", "esp", "fddi",
+    "gateway", "geneve", "greater", "inbound", "ipx", "iso", "len", "less",
+    "llc", "mpls", "multicast", "netbeui", "outbound", "pim", "ppp", "pppoed",
+    "pppoes", "radio", "rarp", "stp", "subtype", "tr", "type", "vrrp", "vxlan",
+    "wlan",
+];
+
+/// Named offsets & values that can be used in byte comparisons.
+const NAMED_VALUES: &[(&str, u32)] = &[
+    ("tcpflags", 13),
+    ("icmptype", 0),
+    ("icmpcode", 1),
+    ("icmp6type", 0),
+    ("icmp6code", 1),
+    ("tcp-fin", 0x01),
+    ("tcp-syn", 0x02),
+    ("tcp-rst", 0x04),
+    ("tcp-push", 0x08),
+    ("tcp-ack", 0x10),
+    ("tcp-urg", 0x20),
+    ("tcp-ece", 0x40),
+    ("tcp-cwr", 0x80),
+    ("icmp-echoreply", 0),
+    ("icmp-unreach", 3),
+    ("icmp-sourcequench", 4),
+    ("icmp-redirect", 5),
+    ("icmp-echo", 8),
+    ("icmp-routeradvert", 9),
+    ("icmp-routersolicit", 10),
+    ("icmp-timxceed", 11),
+    ("icmp-paramprob", 12),
+    ("icmp-tstamp", 13),
+    ("icmp-tstampreply", 14),
+    ("icmp-ireq", 15),
+    ("icmp-ireqreply", 16),
+    ("icmp-maskreq", 17),
+    ("icmp-maskreply", 18),
+    ("icmp6-destinationunreach", 1),
+    ("icmp6-packettoobig", 2),
+    ("icmp6-timeexceeded", 3),
+    ("icmp6-parameterproblem", 4),
+    ("icmp6-echo", 128),
+    ("icmp6-echoreply", 129),
+    ("icmp6-routersolicit", 133),
+    ("icmp6-routeradvert", 134),
+    ("icmp6-neighborsolicit", 135),
+    ("icmp6-neighboradvert", 136),
+    ("icmp6-redirect", 137),
+];
+
+/// Operators & brackets (longer symbols first so they are preferred).
+const SYMBOLS: &[&str] = &[
+    "&&", "||", "!=", "==", "<=", ">=", "(", ")", "[", "]", "&", "|", "!", "=", "<", ">",
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Token<'a> {
+    position: usize,
+    text: &'a str,
+    is_word: bool,
+}
+
+impl<'a> Token<'a> {
+    fn unexpected(&self, expected: &'static str) -> PcapFilterError {
+        PcapFilterError::UnexpectedToken{
+            position: self.position,
+            token: self.text.to_string(),
+            expected,
+        }
+    }
+
+    fn invalid(&self, reason: &'static str) -> PcapFilterError {
+        PcapFilterError::InvalidValue{
+            position: self.position,
+            value: self.text.to_string(),
+            reason,
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.is_word && self.text == keyword
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || ".:-/\\_".contains(c)
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token<'_>>, PcapFilterError> {
+    let mut result = Vec::new();
+    let mut rest = expression;
+    while let Some(c) = rest.chars().next() {
+        let position = expression.len() - rest.len();
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if is_word_char(c) {
+            let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+            result.push(Token{ position, text: &rest[..len], is_word: true });
+            rest = &rest[len..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            result.push(Token{ position, text: &rest[..symbol.len()], is_word: false });
+            rest = &rest[symbol.len()..];
+        } else {
+            return Err(PcapFilterError::UnexpectedCharacter{ position, character: c });
+        }
+    }
+    Ok(result)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.index).cloned()
+    }
+
+    fn peek_is(&self, text: &str) -> bool {
+        matches!(self.peek(), Some(token) if token.text == text)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let result = self.peek();
+        if result.is_some() {
+            self.index += 1;
+        }
+        result
+    }
+
+    fn expect_any(&mut self, expected: &'static str) -> Result<Token<'a>, PcapFilterError> {
+        self.next().ok_or(PcapFilterError::UnexpectedEnd{ expected })
+    }
+
+    fn expect_word(&mut self, expected: &'static str) -> Result<Token<'a>, PcapFilterError> {
+        let token = self.expect_any(expected)?;
+        if token.is_word {
+            Ok(token)
+        } else {
+            Err(token.unexpected(expected))
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str, expected: &'static str) -> Result<(), PcapFilterError> {
+        let token = self.expect_any(expected)?;
+        if !token.is_word && token.text == symbol {
+            Ok(())
+        } else {
+            Err(token.unexpected(expected))
+        }
+    }
+
+    /// Parses a sequence of `and` & `or` combined terms (both operators have
+    /// the same precedence and are evaluated left to right).
+    fn parse_expression(&mut self) -> Result<FilterExpression, PcapFilterError> {
+        use FilterExpression::*;
+
+        let mut result = self.parse_term()?;
+        loop {
+            let is_and = match self.peek() {
+                Some(token) if token.text == "and" || token.text == "&&" => true,
+                Some(token) if token.text == "or" || token.text == "||" => false,
+                _ => return Ok(result),
+            };
+            self.next();
+            let right = self.parse_term()?;
+            result = match (is_and, result) {
+                (true, And(mut expressions)) => {
+                    expressions.push(right);
+                    And(expressions)
+                },
+                (true, left) => And(vec![left, right]),
+                (false, Or(mut expressions)) => {
+                    expressions.push(right);
+                    Or(expressions)
+                },
+                (false, left) => Or(vec![left, right]),
+            };
+        }
+    }
+
+    /// Parses a negation, a parenthesized expression or a primitive.
+    fn parse_term(&mut self) -> Result<FilterExpression, PcapFilterError> {
+        let token = self.expect_any("a filter primitive")?;
+        match token.text {
+            "not" | "!" => Ok(FilterExpression::Not(Box::new(self.parse_term()?))),
+            "(" => {
+                let result = self.parse_expression()?;
+                self.expect_symbol(")", "')'")?;
+                Ok(result)
+            },
+            _ if token.is_word => self.parse_primitive(token),
+            _ => Err(token.unexpected("a filter primitive")),
+        }
+    }
+
+    fn parse_primitive(&mut self, token: Token<'a>) -> Result<FilterExpression, PcapFilterError> {
+        use FilterExpression::*;
+        match token.text {
+            "vlan" => self.parse_vlan(),
+            "proto" => Ok(IpNumber(self.parse_ip_number()?)),
+            "src" | "dst" | "host" | "net" | "port" | "portrange" => {
+                self.index -= 1;
+                self.parse_qualified(None)
+            },
+            "ether" | "ip" | "ip6" | "tcp" | "udp" | "sctp" | "icmp" | "icmp6" | "igmp" => {
+                if self.peek_is("[") {
+                    return self.parse_comparison(token);
+                }
+                if let Some(next) = self.peek() {
+                    let unsupported = UNSUPPORTED_KEYWORDS.contains(&next.text) ||
+                        (token.text == "ether" && next.text == "proto");
+                    if next.is_word && unsupported {
+                        return Err(PcapFilterError::Unsupported{
+                            position: next.position,
+                            keyword: next.text.to_string(),
+                        });
+                    }
+                    if next.is_keyword("proto") && (token.text == "ip" || token.text == "ip6") {
+                        self.next();
+                        let layer = protocol_expression(token.text).unwrap();
+                        return Ok(And(vec![layer, IpNumber(self.parse_ip_number()?)]));
+                    }
+                    if next.is_word && ["src", "dst", "host", "net", "port", "portrange"].contains(&next.text) {
+                        return self.parse_qualified(Some(token));
+                    }
+                }
+                protocol_expression(token.text).ok_or_else(|| match self.peek() {
+                    Some(next) => next.unexpected("'host', 'src' or 'dst'"),
+                    None => PcapFilterError::UnexpectedEnd{ expected: "'host', 'src' or 'dst'" },
+                })
+            },
+            keyword if UNSUPPORTED_KEYWORDS.contains(&keyword) => {
+                Err(PcapFilterError::Unsupported{
+                    position: token.position,
+                    keyword: keyword.to_string(),
+                })
+            },
+            _ => Err(token.unexpected("a filter primitive")),
+        }
+    }
+
+    /// Parses `vlan` with an optional vlan identifier.
+    fn parse_vlan(&mut self) -> Result<FilterExpression, PcapFilterError> {
+        use FilterExpression::*;
+        let id = match self.peek() {
+            Some(token) if token.is_word && token.text.starts_with(|c: char| c.is_ascii_digit()) => {
+                self.next();
+                let value = parse_number(&token)?;
+                if value > 0xfff {
+                    return Err(token.invalid("vlan identifiers have to be smaller then 4096"));
+                }
+                value as u16
+            },
+            _ => {
+                return Ok(Not(Box::new(Layers(Filter{
+                    vlan: ElementFilter::No,
+                    ..Default::default()
+                }))));
+            },
+        };
+        Ok(Or(vec![
+            Layers(Filter{
+                vlan: ElementFilter::Some(VlanFilter::Single(Some(id))),
+                ..Default::default()
+            }),
+            Layers(Filter{
+                vlan: ElementFilter::Some(VlanFilter::Double{
+                    outer_identifier: Some(id),
+                    inner_identifier: None,
+                }),
+                ..Default::default()
+            }),
+        ]))
+    }
+
+    /// Parses an ip protocol number or name (e.g. `6`, `tcp` or `\tcp`).
+    fn parse_ip_number(&mut self) -> Result<u8, PcapFilterError> {
+        let token = self.expect_word("a protocol number or name")?;
+        let name = token.text.trim_start_matches('\\');
+        match name {
+            "tcp" => Ok(ip_number::TCP),
+            "udp" => Ok(ip_number::UDP),
+            "icmp" => Ok(ip_number::ICMP),
+            "icmp6" => Ok(ip_number::IPV6_ICMP),
+            "igmp" => Ok(ip_number::IGMP),
+            "sctp" => Ok(ip_number::SCTP),
+            _ => {
+                let value = parse_number(&token)?;
+                if value > 0xff {
+                    Err(token.invalid("protocol numbers have to be smaller then 256"))
+                } else {
+                    Ok(value as u8)
+                }
+            },
+        }
+    }
+
+    /// Parses `[src|dst|src or dst|src and dst] [host|net|port|portrange] <value>`
+    /// with an optional protocol qualifier in front of it.
+    fn parse_qualified(&mut self, protocol: Option<Token<'a>>) -> Result<FilterExpression, PcapFilterError> {
+        use FilterExpression::*;
+
+        let direction = self.parse_direction();
+        let kind_token = match self.peek() {
+            Some(token) if token.is_word && ["host", "net", "port", "portrange"].contains(&token.text) => {
+                self.next();
+                Some(token)
+            },
+            _ => None,
+        };
+        let kind = kind_token.map_or("host", |token| token.text);
+        let protocol_name = protocol.map(|token| token.text);
+        let value = self.expect_word(match kind {
+            "host" => "an address",
+            "net" => "a network",
+            "port" => "a port",
+            _ => "a port range",
+        })?;
+
+        // ethernet addresses
+        if Some("ether") == protocol_name {
+            if let Some(token) = kind_token.filter(|token| token.text != "host") {
+                return Err(token.unexpected("'host' after 'ether'"));
+            }
+            let mac = parse_mac(&value)?;
+            let filter = |source, destination| Layers(Filter{
+                link: ElementFilter::Some(LinkFilter::Ethernet2{ source, destination }),
+                ..Default::default()
+            });
+            return Ok(match direction {
+                Direction::Source => filter(Some(mac), None),
+                Direction::Destination => filter(None, Some(mac)),
+                Direction::SourceOrDestination => Or(vec![filter(Some(mac), None), filter(None, Some(mac))]),
+                Direction::SourceAndDestination => filter(Some(mac), Some(mac)),
+            });
+        }
+
+        let expression = match kind {
+            "host" | "net" => {
+                let expression = if kind == "host" {
+                    parse_host(&value, direction)?
+                } else {
+                    self.parse_net(&value, direction)?
+                };
+                let mismatch = matches!(
+                    (&expression, protocol_name),
+                    (Ipv6Net{ .. }, Some("ip")) | (Ipv4Net{ .. }, Some("ip6"))
+                );
+                if mismatch {
+                    return Err(value.invalid("address family does not match the protocol"));
+                }
+                expression
+            },
+            _ => {
+                if let Some(token) = protocol {
+                    if !["tcp", "udp", "sctp"].contains(&token.text) {
+                        return Err(token.unexpected("'tcp', 'udp' or 'sctp' in front of a port"));
+                    }
+                }
+                let ports = if kind == "port" {
+                    let port = parse_port(&value, value.text)?;
+                    PortMatch::Range{ min: port, max: port }
+                } else {
+                    let mut parts = value.text.splitn(2, '-');
+                    let first = parse_port(&value, parts.next().unwrap())?;
+                    let second = match parts.next() {
+                        Some(text) => parse_port(&value, text)?,
+                        None => return Err(value.invalid("port ranges have the format <start>-<end>")),
+                    };
+                    PortMatch::Range{ min: first.min(second), max: first.max(second) }
+                };
+                Port{ direction, ports }
+            },
+        };
+
+        // add the protocol condition
+        Ok(match protocol_name {
+            Some(name) if name == "ip" || name == "ip6" => expression,
+            Some(name) => And(vec![protocol_expression(name).unwrap(), expression]),
+            None => expression,
+        })
+    }
+
+    fn parse_direction(&mut self) -> Direction {
+        let first = match self.peek() {
+            Some(token) if token.is_keyword("src") => Direction::Source,
+            Some(token) if token.is_keyword("dst") => Direction::Destination,
+            _ => return Direction::SourceOrDestination,
+        };
+        self.next();
+
+        // check for "src or dst" & "src and dst"
+        let other = if first == Direction::Source { "dst" } else { "src" };
+        let combined = match (self.peek(), self.tokens.get(self.index + 1)) {
+            (Some(op), Some(second)) if second.is_keyword(other) => {
+                if op.is_keyword("or") {
+                    Some(Direction::SourceOrDestination)
+                } else if op.is_keyword("and") {
+                    Some(Direction::SourceAndDestination)
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        };
+        match combined {
+            Some(direction) => {
+                self.index += 2;
+                direction
+            },
+            None => first,
+        }
+    }
+
+    /// Parses a network (`10.0.0.0/8`, `10.0`, `10.0.0.0 mask 255.0.0.0` or `fe80::/10`).
+    fn parse_net(&mut self, value: &Token<'a>, direction: Direction) -> Result<FilterExpression, PcapFilterError> {
+        let mut parts = value.text.splitn(2, '/');
+        let address = parts.next().unwrap();
+        let prefix_len = match parts.next() {
+            Some(text) => match text.parse::<u8>() {
+                Ok(len) => Some(len),
+                Err(_) => return Err(value.invalid("the prefix length is not a number")),
+            },
+            None => None,
+        };
+
+        if address.contains(':') {
+            let address = address.parse::<Ipv6Addr>()
+                .map_err(|_| value.invalid("not an ipv6 address"))?;
+            let prefix_len = prefix_len.unwrap_or(128);
+            if prefix_len > 128 {
+                return Err(value.invalid("ipv6 prefix lengths can be at most 128"));
+            }
+            return Ok(FilterExpression::Ipv6Net{
+                direction,
+                net: Ipv6Cidr{ address: address.octets(), prefix_len },
+            });
+        }
+
+        // ipv4 networks can be abbreviated (e.g. "192.168" for "192.168.0.0/16")
+        let mut octets = [0u8;4];
+        let mut count = 0;
+        for part in address.split('.') {
+            if count == 4 {
+                return Err(value.invalid("not an ipv4 address"));
+            }
+            octets[count] = part.parse().map_err(|_| value.invalid("not an ipv4 address"))?;
+            count += 1;
+        }
+
+        let prefix_len = if matches!(self.peek(), Some(token) if token.is_keyword("mask")) {
+            if prefix_len.is_some() {
+                return Err(self.peek().unwrap().unexpected("either a prefix length or a mask"));
+            }
+            self.next();
+            let mask_token = self.expect_word("a network mask")?;
+            let mask = mask_token.text.parse::<Ipv4Addr>()
+                .map_err(|_| mask_token.invalid("not an ipv4 address"))?;
+            let mask = u32::from(mask);
+            if mask.leading_ones() + mask.trailing_zeros() != 32 {
+                return Err(mask_token.invalid("non contiguous masks are not supported"));
+            }
+            mask.leading_ones() as u8
+        } else {
+            prefix_len.unwrap_or(8 * count as u8)
+        };
+        if prefix_len > 32 {
+            return Err(value.invalid("ipv4 prefix lengths can be at most 32"));
+        }
+        Ok(FilterExpression::Ipv4Net{
+            direction,
+            net: Ipv4Cidr{ address: octets, prefix_len },
+        })
+    }
+
+    /// Parses a byte comparison like `tcp[13:1] & 0x12 != 0`.
+    fn parse_comparison(&mut self, layer_token: Token<'a>) -> Result<FilterExpression, PcapFilterError> {
+        let layer = match layer_token.text {
+            "ether" => FilterLayer::Ethernet2,
+            "ip" => FilterLayer::Ipv4,
+            "ip6" => FilterLayer::Ipv6,
+            "tcp" => FilterLayer::Tcp,
+            "udp" => FilterLayer::Udp,
+            "icmp" => FilterLayer::Icmpv4,
+            "icmp6" => FilterLayer::Icmpv6,
+            _ => return Err(PcapFilterError::Unsupported{
+                position: layer_token.position,
+                keyword: format!("{}[]", layer_token.text),
+            }),
+        };
+        self.expect_symbol("[", "'['")?;
+        let index = self.expect_word("a byte offset")?;
+        let mut parts = index.text.splitn(2, ':');
+        let offset = parse_value(&index, parts.next().unwrap())? as usize;
+        let size = match parts.next() {
+            Some("1") | None => 1,
+            Some("2") => 2,
+            Some("4") => 4,
+            Some(_) => return Err(index.invalid("the size has to be 1, 2 or 4")),
+        };
+        self.expect_symbol("]", "']'")?;
+
+        let mask = if self.peek_is("&") {
+            self.next();
+            self.parse_mask()?
+        } else {
+            u32::MAX
+        };
+
+        let op_token = self.expect_any("a comparison operator")?;
+        let op = match op_token.text {
+            "=" | "==" => CompareOp::Equal,
+            "!=" => CompareOp::NotEqual,
+            "<" => CompareOp::Less,
+            "<=" => CompareOp::LessOrEqual,
+            ">" => CompareOp::Greater,
+            ">=" => CompareOp::GreaterOrEqual,
+            _ => return Err(op_token.unexpected("a comparison operator")),
+        };
+        let value_token = self.expect_word("a value")?;
+        let value = parse_value(&value_token, value_token.text)?;
+
+        Ok(FilterExpression::Bytes{ layer, offset, size, mask, op, value })
+    }
+
+    /// Parses a mask value (e.g. `0x12`, `tcp-syn` or `(tcp-syn|tcp-ack)`).
+    fn parse_mask(&mut self) -> Result<u32, PcapFilterError> {
+        if !self.peek_is("(") {
+            let token = self.expect_word("a mask value")?;
+            return parse_value(&token, token.text);
+        }
+        self.next();
+        let mut result = 0;
+        loop {
+            let token = self.expect_word("a mask value")?;
+            result |= parse_value(&token, token.text)?;
+            let separator = self.expect_any("'|' or ')'")?;
+            match separator.text {
+                "|" => {},
+                ")" => return Ok(result),
+                _ => return Err(separator.unexpected("'|' or ')'")),
+            }
+        }
+    }
+}
+
+/// Expression matching all packets of the given protocol.
+fn protocol_expression(name: &str) -> Option<FilterExpression> {
+    use FilterExpression::*;
+    let ip = |filter| Layers(Filter{
+        ip: ElementFilter::Some(filter),
+        ..Default::default()
+    });
+    match name {
+        "ip" => Some(ip(IpFilter::Ipv4{ source: None, destination: None })),
+        "ip6" => Some(ip(IpFilter::Ipv6{ source: None, destination: None })),
+        "tcp" => Some(IpNumber(ip_number::TCP)),
+        "udp" => Some(IpNumber(ip_number::UDP)),
+        "sctp" => Some(IpNumber(ip_number::SCTP)),
+        "icmp" => Some(IpNumber(ip_number::ICMP)),
+        "icmp6" => Some(IpNumber(ip_number::IPV6_ICMP)),
+        "igmp" => Some(IpNumber(ip_number::IGMP)),
+        _ => None,
+    }
+}
+
+fn parse_host(value: &Token, direction: Direction) -> Result<FilterExpression, PcapFilterError> {
+    if let Ok(address) = value.text.parse::<Ipv4Addr>() {
+        Ok(FilterExpression::Ipv4Net{
+            direction,
+            net: Ipv4Cidr{ address: address.octets(), prefix_len: 32 },
+        })
+    } else if let Ok(address) = value.text.parse::<Ipv6Addr>() {
+        Ok(FilterExpression::Ipv6Net{
+            direction,
+            net: Ipv6Cidr{ address: address.octets(), prefix_len: 128 },
+        })
+    } else {
+        Err(value.invalid("not an ip address (host names are not supported)"))
+    }
+}
+
+fn parse_mac(value: &Token) -> Result<[u8;6], PcapFilterError> {
+    let separator = if value.text.contains('-') { '-' } else { ':' };
+    let mut result = [0u8;6];
+    let mut count = 0;
+    for part in value.text.split(separator) {
+        if count == 6 || part.is_empty() || part.len() > 2 {
+            return Err(value.invalid("not an ethernet address"));
+        }
+        result[count] = u8::from_str_radix(part, 16).map_err(|_| value.invalid("not an ethernet address"))?;
+        count += 1;
+    }
+    if count == 6 {
+        Ok(result)
+    } else {
+        Err(value.invalid("not an ethernet address"))
+    }
+}
+
+fn parse_port(token: &Token, text: &str) -> Result<u16, PcapFilterError> {
+    text.parse::<u16>().map_err(|_| token.invalid("not a port number (service names are not supported)"))
+}
+
+/// Parses a decimal or hexadecimal (`0x` prefix) number.
+fn parse_number(token: &Token) -> Result<u32, PcapFilterError> {
+    parse_value(token, token.text)
+}
+
+/// Parses a decimal or hexadecimal (`0x` prefix) number or a named value (e.g. `tcp-syn`).
+fn parse_value(token: &Token, text: &str) -> Result<u32, PcapFilterError> {
+    if let Some((_, value)) = NAMED_VALUES.iter().find(|(name, _)| *name == text) {
+        return Ok(*value);
+    }
+    let result = if text.starts_with("0x") || text.starts_with("0X") {
+        u32::from_str_radix(&text[2..], 16)
+    } else {
+        text.parse::<u32>()
+    };
+    result.map_err(|_| token.invalid("not a number"))
+}
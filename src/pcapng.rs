@@ -0,0 +1,983 @@
+//! Reading & writing of pcapng capture files.
+//!
+//! A pcapng file consists of blocks. Every section starts with a section header
+//! block (defining the byte order), followed by interface description blocks
+//! (defining the link type & timestamp resolution of each capture interface)
+//! and the packet blocks referencing these interfaces.
+//!
+//! The [`PcapngSliceReader`] iterates over the blocks of a file stored in
+//! a slice & the [`PcapngReader`] reads the blocks from a [`std::io::Read`]
+//! source. Both keep track of the interfaces & can directly return the
+//! packets with their link type & timestamp via `next_packet`. The
+//! [`PcapngWriter`] writes enhanced packet blocks (optionally with comments).
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use etherparse::{link_type, PacketBuilder};
+//! use etherparse::pcap::PcapByteOrder;
+//! use etherparse::pcapng::*;
+//!
+//! // write a packet to a capture file (a Vec in this case)
+//! let mut writer = PcapngWriter::new(Vec::new(), PcapByteOrder::LittleEndian).unwrap();
+//! let interface_id = writer.add_interface(link_type::ETHERNET, 0).unwrap();
+//! {
+//!     let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!         .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!         .udp(21, 1234);
+//!     let mut packet = Vec::with_capacity(builder.size(4));
+//!     builder.write(&mut packet, &[1,2,3,4]).unwrap();
+//!     writer.write_packet_with_comments(
+//!         interface_id,
+//!         Duration::from_millis(1500),
+//!         &packet,
+//!         &["first packet"]
+//!     ).unwrap();
+//! }
+//! let file = writer.into_inner();
+//!
+//! // read the packets again
+//! let mut reader = PcapngSliceReader::new(&file).unwrap();
+//! while let Some(packet) = reader.next_packet() {
+//!     let packet = packet.unwrap();
+//!     assert_eq!(Some(Duration::from_millis(1500)), packet.timestamp);
+//!     assert_eq!(vec!["first packet"], packet.options.comments().collect::<Vec<_>>());
+//!     // slice the packet based on the link type of its interface
+//!     let sliced = packet.slice().unwrap();
+//!     assert_eq!(&[1,2,3,4], sliced.payload);
+//! }
+//! ```
+use super::*;
+use crate::pcap::PcapByteOrder;
+
+#[cfg(feature = "std")]
+use std::io;
+use core::net::IpAddr;
+use core::time::Duration;
+
+/// Maximum length of a block accepted by the readers.
+pub const MAX_BLOCK_LEN: u32 = 16 * 1024 * 1024;
+
+/// Value of the byte order magic field in the section header block.
+pub const BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+
+/// Block type values.
+pub mod block_type {
+    pub const SECTION_HEADER: u32 = 0x0a0d_0d0a;
+    pub const INTERFACE_DESCRIPTION: u32 = 1;
+    pub const SIMPLE_PACKET: u32 = 3;
+    pub const NAME_RESOLUTION: u32 = 4;
+    pub const INTERFACE_STATISTICS: u32 = 5;
+    pub const ENHANCED_PACKET: u32 = 6;
+    /// Custom block that can be copied to new files.
+    pub const CUSTOM: u32 = 0x0000_0bad;
+    /// Custom block that should not be copied to new files.
+    pub const CUSTOM_NO_COPY: u32 = 0x4000_0bad;
+}
+
+/// Option codes (option codes other then the end of options & comment
+/// depend on the block type).
+pub mod option_code {
+    pub const END_OF_OPT: u16 = 0;
+    pub const COMMENT: u16 = 1;
+    pub const SHB_HARDWARE: u16 = 2;
+    pub const SHB_OS: u16 = 3;
+    pub const SHB_USERAPPL: u16 = 4;
+    pub const IF_NAME: u16 = 2;
+    pub const IF_DESCRIPTION: u16 = 3;
+    pub const IF_TSRESOL: u16 = 9;
+    pub const IF_TSOFFSET: u16 = 14;
+    pub const EPB_FLAGS: u16 = 2;
+}
+
+/// Name resolution record types.
+pub mod name_record_type {
+    pub const END: u16 = 0;
+    pub const IPV4: u16 = 1;
+    pub const IPV6: u16 = 2;
+}
+
+/// Type-length-value entry as returned by `split_tlv` consisting
+/// of the code, the value and the rest of the slice.
+type TlvSplit<'a> = (u16, &'a [u8], &'a [u8]);
+
+/// Returns the next type-length-value entry (options & name resolution
+/// records) and the rest of the slice. Returns `None` if not enough data
+/// for another entry is left.
+fn split_tlv(byte_order: PcapByteOrder, slice: &[u8]) -> Result<Option<TlvSplit<'_>>, ReadError> {
+    if slice.len() < 4 {
+        return Ok(None);
+    }
+    let code = byte_order.u16_from([slice[0], slice[1]]);
+    let len = byte_order.u16_from([slice[2], slice[3]]);
+    let end = 4 + usize::from(len);
+    if end > slice.len() {
+        return Err(ReadError::PcapngOptionLengthTooBig(len));
+    }
+    let padded_end = (end + 3) & !3;
+    Ok(Some((code, &slice[4..end], &slice[padded_end.min(slice.len())..])))
+}
+
+/// Option of a pcapng block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PcapngOption<'a> {
+    pub code: u16,
+    pub value: &'a [u8],
+}
+
+/// Options of a pcapng block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PcapngOptions<'a> {
+    byte_order: PcapByteOrder,
+    slice: &'a [u8],
+}
+
+impl<'a> PcapngOptions<'a> {
+    /// Creates the options from the serialized options of a block.
+    pub fn from_slice(byte_order: PcapByteOrder, slice: &'a [u8]) -> PcapngOptions<'a> {
+        PcapngOptions { byte_order, slice }
+    }
+
+    /// Returns the byte order of the section (used for numeric option values).
+    pub fn byte_order(&self) -> PcapByteOrder {
+        self.byte_order
+    }
+
+    /// Returns the serialized options.
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns an iterator over all options.
+    pub fn iter(&self) -> PcapngOptionsIterator<'a> {
+        PcapngOptionsIterator {
+            byte_order: self.byte_order,
+            rest: self.slice,
+        }
+    }
+
+    /// Returns the value of the first option with the given code.
+    pub fn get(&self, code: u16) -> Option<&'a [u8]> {
+        self.iter()
+            .filter_map(|option| option.ok())
+            .find(|option| option.code == code)
+            .map(|option| option.value)
+    }
+
+    /// Returns the value of the first option with the given code if it
+    /// contains a 64 bit value.
+    pub fn get_u64(&self, code: u16) -> Option<u64> {
+        match self.get(code) {
+            Some(&[a, b, c, d, e, f, g, h]) => Some(self.byte_order.u64_from([a, b, c, d, e, f, g, h])),
+            _ => None,
+        }
+    }
+
+    /// Returns all comments (ignoring comments that are not valid UTF-8).
+    pub fn comments(&self) -> impl Iterator<Item = &'a str> {
+        self.iter()
+            .filter_map(|option| option.ok())
+            .filter(|option| option_code::COMMENT == option.code)
+            .filter_map(|option| core::str::from_utf8(option.value).ok())
+    }
+}
+
+/// Iterator over the options of a pcapng block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngOptionsIterator<'a> {
+    byte_order: PcapByteOrder,
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for PcapngOptionsIterator<'a> {
+    type Item = Result<PcapngOption<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match split_tlv(self.byte_order, self.rest) {
+            Ok(Some((code, value, rest))) if option_code::END_OF_OPT != code => {
+                self.rest = rest;
+                Some(Ok(PcapngOption { code, value }))
+            },
+            Ok(_) => {
+                self.rest = &[];
+                None
+            },
+            Err(err) => {
+                self.rest = &[];
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// Resolution of the timestamps of an interface.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PcapngTimestampResolution {
+    /// Timestamps are in units of 10^-n seconds.
+    Decimal(u8),
+    /// Timestamps are in units of 2^-n seconds.
+    Binary(u8),
+}
+
+impl Default for PcapngTimestampResolution {
+    fn default() -> PcapngTimestampResolution {
+        // microseconds
+        PcapngTimestampResolution::Decimal(6)
+    }
+}
+
+impl PcapngTimestampResolution {
+    /// Decodes the value of the "if_tsresol" option.
+    pub fn from_option_value(value: u8) -> PcapngTimestampResolution {
+        if 0 != value & 0x80 {
+            PcapngTimestampResolution::Binary(value & 0x7f)
+        } else {
+            PcapngTimestampResolution::Decimal(value)
+        }
+    }
+
+    /// Returns the value of the "if_tsresol" option.
+    pub fn to_option_value(&self) -> u8 {
+        match self {
+            PcapngTimestampResolution::Decimal(value) => value & 0x7f,
+            PcapngTimestampResolution::Binary(value) => 0x80 | (value & 0x7f),
+        }
+    }
+
+    /// Converts a timestamp value to a duration (rounded down to nanoseconds).
+    pub fn to_duration(&self, timestamp: u64) -> Duration {
+        let timestamp = u128::from(timestamp);
+        let nanos = match *self {
+            PcapngTimestampResolution::Decimal(exp) if exp <= 9 => timestamp * 10u128.pow(9 - u32::from(exp)),
+            PcapngTimestampResolution::Decimal(exp) if exp <= 38 => timestamp / 10u128.pow(u32::from(exp) - 9),
+            PcapngTimestampResolution::Decimal(_) => 0,
+            PcapngTimestampResolution::Binary(exp) => (timestamp * 1_000_000_000) >> (exp & 0x7f),
+        };
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+/// Section header block (start of a section).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngSectionHeader<'a> {
+    pub byte_order: PcapByteOrder,
+    pub version_major: u16,
+    pub version_minor: u16,
+    /// Length of the section in bytes (if known).
+    pub section_length: Option<u64>,
+    pub options: PcapngOptions<'a>,
+}
+
+/// Interface description block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngInterfaceDescription<'a> {
+    /// Link type of the packets captured on the interface (see [`crate::link_type`]).
+    pub link_type: u16,
+    /// Maximum number of bytes captured per packet (0 if unlimited).
+    pub snaplen: u32,
+    pub options: PcapngOptions<'a>,
+}
+
+impl<'a> PcapngInterfaceDescription<'a> {
+    /// Returns the interface properties required to decode packets.
+    pub fn interface(&self) -> PcapngInterface {
+        PcapngInterface {
+            link_type: self.link_type,
+            snaplen: self.snaplen,
+            timestamp_resolution: match self.options.get(option_code::IF_TSRESOL) {
+                Some(&[value]) => PcapngTimestampResolution::from_option_value(value),
+                _ => PcapngTimestampResolution::default(),
+            },
+            timestamp_offset: self.options.get_u64(option_code::IF_TSOFFSET).map(|v| v as i64).unwrap_or(0),
+        }
+    }
+}
+
+/// Enhanced packet block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngEnhancedPacket<'a> {
+    pub interface_id: u32,
+    /// Timestamp in the resolution of the interface.
+    pub timestamp: u64,
+    /// Length of the packet on the wire.
+    pub original_len: u32,
+    /// Captured bytes of the packet.
+    pub data: &'a [u8],
+    pub options: PcapngOptions<'a>,
+}
+
+/// Simple packet block (packet captured on the first interface without timestamp).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngSimplePacket<'a> {
+    /// Length of the packet on the wire.
+    pub original_len: u32,
+    /// Captured bytes of the packet (not yet truncated to the snapshot length of the interface).
+    pub data: &'a [u8],
+}
+
+/// Name resolution block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngNameResolution<'a> {
+    byte_order: PcapByteOrder,
+    /// Serialized records (including the end record).
+    records: &'a [u8],
+    pub options: PcapngOptions<'a>,
+}
+
+impl<'a> PcapngNameResolution<'a> {
+    /// Returns an iterator over the name records.
+    pub fn records(&self) -> PcapngNameRecordsIterator<'a> {
+        PcapngNameRecordsIterator {
+            options: PcapngOptionsIterator {
+                byte_order: self.byte_order,
+                rest: self.records,
+            },
+        }
+    }
+}
+
+/// Iterator over the records of a name resolution block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngNameRecordsIterator<'a> {
+    // records have the same format as options
+    options: PcapngOptionsIterator<'a>,
+}
+
+impl<'a> Iterator for PcapngNameRecordsIterator<'a> {
+    type Item = Result<PcapngNameRecord<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.options.next().map(|value| value.map(|option| PcapngNameRecord {
+            record_type: option.code,
+            value: option.value,
+        }))
+    }
+}
+
+/// Record of a name resolution block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PcapngNameRecord<'a> {
+    pub record_type: u16,
+    pub value: &'a [u8],
+}
+
+impl<'a> PcapngNameRecord<'a> {
+    /// Returns the address of IPv4 & IPv6 records.
+    pub fn address(&self) -> Option<IpAddr> {
+        match (self.record_type, self.value.len()) {
+            (name_record_type::IPV4, len) if len >= 4 => {
+                let mut address = [0u8; 4];
+                address.copy_from_slice(&self.value[..4]);
+                Some(IpAddr::from(address))
+            },
+            (name_record_type::IPV6, len) if len >= 16 => {
+                let mut address = [0u8; 16];
+                address.copy_from_slice(&self.value[..16]);
+                Some(IpAddr::from(address))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the names of IPv4 & IPv6 records (ignoring names that are not valid UTF-8).
+    pub fn names(&self) -> impl Iterator<Item = &'a str> {
+        let names = match self.record_type {
+            name_record_type::IPV4 if self.value.len() >= 4 => &self.value[4..],
+            name_record_type::IPV6 if self.value.len() >= 16 => &self.value[16..],
+            _ => &[],
+        };
+        names
+            .split(|c| 0 == *c)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| core::str::from_utf8(name).ok())
+    }
+}
+
+/// Custom block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngCustomBlock<'a> {
+    /// True if the block can be copied to new files.
+    pub copyable: bool,
+    pub private_enterprise_number: u32,
+    /// Custom data (including custom options).
+    pub data: &'a [u8],
+}
+
+/// A block of a pcapng file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PcapngBlock<'a> {
+    SectionHeader(PcapngSectionHeader<'a>),
+    InterfaceDescription(PcapngInterfaceDescription<'a>),
+    EnhancedPacket(PcapngEnhancedPacket<'a>),
+    SimplePacket(PcapngSimplePacket<'a>),
+    NameResolution(PcapngNameResolution<'a>),
+    Custom(PcapngCustomBlock<'a>),
+    /// Block with a type not decoded by etherparse (e.g. interface statistics).
+    Unknown{ block_type: u32, body: &'a [u8] },
+}
+
+impl<'a> PcapngBlock<'a> {
+    /// Decodes the block at the start of the slice & returns it together
+    /// with the rest of the slice.
+    ///
+    /// The given byte order is used for all blocks except section header
+    /// blocks (which define the byte order of their section).
+    pub fn from_slice(byte_order: PcapByteOrder, slice: &'a [u8]) -> Result<(PcapngBlock<'a>, &'a [u8]), ReadError> {
+        use ReadError::*;
+        if slice.len() < 12 {
+            return Err(UnexpectedEndOfSlice(12));
+        }
+        let u32_at = |byte_order: PcapByteOrder, offset: usize| byte_order.u32_from(
+            [slice[offset], slice[offset + 1], slice[offset + 2], slice[offset + 3]]
+        );
+
+        // the section header block type is the same in both byte orders
+        let block_type = u32_at(byte_order, 0);
+        let byte_order = if block_type_is_section_header(block_type) {
+            section_byte_order([slice[8], slice[9], slice[10], slice[11]])?
+        } else {
+            byte_order
+        };
+
+        let total_len = u32_at(byte_order, 4);
+        if total_len < 12 || 0 != total_len % 4 || total_len > MAX_BLOCK_LEN {
+            return Err(PcapngBlockLengthBad(total_len));
+        }
+        let end = total_len as usize;
+        if slice.len() < end {
+            return Err(UnexpectedEndOfSlice(end));
+        }
+        let trailing_len = u32_at(byte_order, end - 4);
+        if trailing_len != total_len {
+            return Err(PcapngBlockLengthBad(trailing_len));
+        }
+
+        let block = PcapngBlock::from_body(byte_order, block_type, &slice[8..end - 4])
+            .ok_or(PcapngBlockLengthBad(total_len))?;
+        Ok((block, &slice[end..]))
+    }
+
+    /// Decodes the body of a block (returns `None` if the body is too small).
+    fn from_body(byte_order: PcapByteOrder, block_type: u32, body: &'a [u8]) -> Option<PcapngBlock<'a>> {
+        use PcapngBlock::*;
+        let u16_at = |offset: usize| byte_order.u16_from([body[offset], body[offset + 1]]);
+        let u32_at = |offset: usize| byte_order.u32_from(
+            [body[offset], body[offset + 1], body[offset + 2], body[offset + 3]]
+        );
+        let options = |offset: usize| PcapngOptions::from_slice(byte_order, &body[offset..]);
+
+        match block_type {
+            block_type::SECTION_HEADER if body.len() >= 16 => {
+                let section_length = byte_order.u64_from([
+                    body[8], body[9], body[10], body[11],
+                    body[12], body[13], body[14], body[15],
+                ]);
+                Some(SectionHeader(PcapngSectionHeader {
+                    byte_order,
+                    version_major: u16_at(4),
+                    version_minor: u16_at(6),
+                    // -1 is used for unknown section lengths
+                    section_length: if u64::MAX == section_length {
+                        None
+                    } else {
+                        Some(section_length)
+                    },
+                    options: options(16),
+                }))
+            },
+            block_type::INTERFACE_DESCRIPTION if body.len() >= 8 => {
+                Some(InterfaceDescription(PcapngInterfaceDescription {
+                    link_type: u16_at(0),
+                    snaplen: u32_at(4),
+                    options: options(8),
+                }))
+            },
+            block_type::ENHANCED_PACKET if body.len() >= 20 => {
+                let captured_len = u32_at(12) as usize;
+                let data_end = 20 + ((captured_len + 3) & !3);
+                if data_end > body.len() {
+                    return None;
+                }
+                Some(EnhancedPacket(PcapngEnhancedPacket {
+                    interface_id: u32_at(0),
+                    timestamp: (u64::from(u32_at(4)) << 32) | u64::from(u32_at(8)),
+                    original_len: u32_at(16),
+                    data: &body[20..20 + captured_len],
+                    options: options(data_end),
+                }))
+            },
+            block_type::SIMPLE_PACKET if body.len() >= 4 => {
+                let original_len = u32_at(0);
+                let captured_len = (body.len() - 4).min(original_len as usize);
+                Some(SimplePacket(PcapngSimplePacket {
+                    original_len,
+                    data: &body[4..4 + captured_len],
+                }))
+            },
+            block_type::NAME_RESOLUTION => {
+                // search the end of the records
+                let mut rest = body;
+                loop {
+                    match split_tlv(byte_order, rest) {
+                        Ok(Some((name_record_type::END, _, value_rest))) => {
+                            rest = value_rest;
+                            break;
+                        },
+                        Ok(Some((_, _, value_rest))) => rest = value_rest,
+                        // records without end record (the iterator reports errors)
+                        Ok(None) | Err(_) => {
+                            rest = &[];
+                            break;
+                        },
+                    }
+                }
+                Some(NameResolution(PcapngNameResolution {
+                    byte_order,
+                    records: &body[..body.len() - rest.len()],
+                    options: PcapngOptions::from_slice(byte_order, rest),
+                }))
+            },
+            block_type::CUSTOM | block_type::CUSTOM_NO_COPY if body.len() >= 4 => {
+                Some(Custom(PcapngCustomBlock {
+                    copyable: block_type::CUSTOM == block_type,
+                    private_enterprise_number: u32_at(0),
+                    data: &body[4..],
+                }))
+            },
+            block_type::SECTION_HEADER |
+            block_type::INTERFACE_DESCRIPTION |
+            block_type::ENHANCED_PACKET |
+            block_type::SIMPLE_PACKET |
+            block_type::CUSTOM |
+            block_type::CUSTOM_NO_COPY => None,
+            _ => Some(Unknown{ block_type, body }),
+        }
+    }
+}
+
+fn block_type_is_section_header(block_type: u32) -> bool {
+    block_type::SECTION_HEADER == block_type
+}
+
+/// Determines the byte order of a section based on the byte order magic.
+fn section_byte_order(magic: [u8; 4]) -> Result<PcapByteOrder, ReadError> {
+    if BYTE_ORDER_MAGIC == u32::from_le_bytes(magic) {
+        Ok(PcapByteOrder::LittleEndian)
+    } else if BYTE_ORDER_MAGIC == u32::from_be_bytes(magic) {
+        Ok(PcapByteOrder::BigEndian)
+    } else {
+        Err(ReadError::PcapngByteOrderMagicUnknown(u32::from_be_bytes(magic)))
+    }
+}
+
+/// Properties of a capture interface required to decode its packets.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngInterface {
+    /// Link type of the packets (see [`crate::link_type`]).
+    pub link_type: u16,
+    /// Maximum number of bytes captured per packet (0 if unlimited).
+    pub snaplen: u32,
+    pub timestamp_resolution: PcapngTimestampResolution,
+    /// Offset in seconds added to all timestamps.
+    pub timestamp_offset: i64,
+}
+
+/// A packet read from a pcapng file (from an enhanced or simple packet block).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngPacket<'a> {
+    pub interface_id: u32,
+    /// Capture time of the packet since the UNIX epoch (`None` for
+    /// simple packet blocks as they contain no timestamp).
+    pub timestamp: Option<Duration>,
+    /// Length of the packet on the wire.
+    pub original_len: u32,
+    /// Link type of the interface the packet was captured on (see [`crate::link_type`]).
+    pub link_type: u16,
+    /// Captured bytes of the packet.
+    pub data: &'a [u8],
+    /// Options of the packet (e.g. comments).
+    pub options: PcapngOptions<'a>,
+}
+
+impl<'a> PcapngPacket<'a> {
+    /// Converts a packet block to a packet (returns `None` for all other blocks).
+    pub fn from_block(interfaces: &[PcapngInterface], block: &PcapngBlock<'a>) -> Option<Result<PcapngPacket<'a>, ReadError>> {
+        use PcapngBlock::*;
+        match block {
+            EnhancedPacket(packet) => Some(
+                match interfaces.get(packet.interface_id as usize) {
+                    Some(interface) => {
+                        let timestamp = interface.timestamp_resolution.to_duration(packet.timestamp);
+                        let offset = Duration::from_secs(interface.timestamp_offset.unsigned_abs());
+                        Ok(PcapngPacket {
+                            interface_id: packet.interface_id,
+                            // timestamps outside of the range of a duration are saturated
+                            timestamp: Some(if interface.timestamp_offset < 0 {
+                                timestamp.checked_sub(offset).unwrap_or_default()
+                            } else {
+                                timestamp.checked_add(offset).unwrap_or(Duration::MAX)
+                            }),
+                            original_len: packet.original_len,
+                            link_type: interface.link_type,
+                            data: packet.data,
+                            options: packet.options,
+                        })
+                    },
+                    None => Err(ReadError::PcapngUnknownInterfaceId(packet.interface_id)),
+                }
+            ),
+            SimplePacket(packet) => Some(
+                match interfaces.first() {
+                    Some(interface) => Ok(PcapngPacket {
+                        interface_id: 0,
+                        timestamp: None,
+                        original_len: packet.original_len,
+                        link_type: interface.link_type,
+                        data: if 0 != interface.snaplen && packet.data.len() > interface.snaplen as usize {
+                            &packet.data[..interface.snaplen as usize]
+                        } else {
+                            packet.data
+                        },
+                        options: PcapngOptions::from_slice(PcapByteOrder::LittleEndian, &[]),
+                    }),
+                    None => Err(ReadError::PcapngUnknownInterfaceId(0)),
+                }
+            ),
+            _ => None,
+        }
+    }
+
+    /// Returns true if not the complete packet was captured.
+    pub fn is_truncated(&self) -> bool {
+        (self.data.len() as u64) < u64::from(self.original_len)
+    }
+
+    /// Slices the packet based on its link type (see [`SlicedPacket::from_link_type`]).
+    pub fn slice(&self) -> Result<SlicedPacket<'a>, PacketReadError> {
+        SlicedPacket::from_link_type(self.link_type, self.data)
+    }
+}
+
+/// Updates the section state (byte order & interfaces) based on a block.
+fn update_section(byte_order: &mut PcapByteOrder, interfaces: &mut Vec<PcapngInterface>, block: &PcapngBlock) {
+    match block {
+        PcapngBlock::SectionHeader(header) => {
+            *byte_order = header.byte_order;
+            interfaces.clear();
+        },
+        PcapngBlock::InterfaceDescription(description) => {
+            interfaces.push(description.interface());
+        },
+        _ => {},
+    }
+}
+
+/// Iterates over the blocks of a pcapng file stored in a slice.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcapngSliceReader<'a> {
+    byte_order: PcapByteOrder,
+    interfaces: Vec<PcapngInterface>,
+    rest: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> PcapngSliceReader<'a> {
+    /// Creates an iterator over the blocks of the given file. Returns an
+    /// error if the file does not start with a section header block.
+    pub fn new(slice: &'a [u8]) -> Result<PcapngSliceReader<'a>, ReadError> {
+        if slice.len() < 12 {
+            return Err(ReadError::UnexpectedEndOfSlice(12));
+        }
+        let block_type = u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]);
+        if !block_type_is_section_header(block_type) {
+            return Err(ReadError::PcapngFirstBlockNotSectionHeader(block_type));
+        }
+        Ok(PcapngSliceReader {
+            byte_order: section_byte_order([slice[8], slice[9], slice[10], slice[11]])?,
+            interfaces: Vec::new(),
+            rest: slice,
+            offset: 0,
+        })
+    }
+
+    /// Returns the interfaces of the current section.
+    pub fn interfaces(&self) -> &[PcapngInterface] {
+        &self.interfaces
+    }
+
+    /// Returns the next packet (skipping all other blocks).
+    pub fn next_packet(&mut self) -> Option<Result<PcapngPacket<'a>, ReadError>> {
+        loop {
+            match self.next()? {
+                Ok(block) => {
+                    if let Some(packet) = PcapngPacket::from_block(&self.interfaces, &block) {
+                        return Some(packet);
+                    }
+                },
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for PcapngSliceReader<'a> {
+    type Item = Result<PcapngBlock<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // stop after the first error
+        let rest = core::mem::take(&mut self.rest);
+        match PcapngBlock::from_slice(self.byte_order, rest) {
+            Ok((block, next)) => {
+                update_section(&mut self.byte_order, &mut self.interfaces, &block);
+                self.offset += rest.len() - next.len();
+                self.rest = next;
+                Some(Ok(block))
+            },
+            Err(err) => Some(Err(err.add_slice_offset(self.offset))),
+        }
+    }
+}
+
+/// Reads the blocks of a pcapng file from a [`std::io::Read`] source.
+#[derive(Debug)]
+pub struct PcapngReader<T: io::Read> {
+    reader: T,
+    byte_order: PcapByteOrder,
+    interfaces: Vec<PcapngInterface>,
+    buffer: Vec<u8>,
+    /// True if the buffer contains a block not yet returned.
+    pending: bool,
+}
+
+impl<T: io::Read> PcapngReader<T> {
+    /// Reads the section header block at the start of the file & creates a
+    /// reader for the blocks (starting with the section header block).
+    pub fn new(reader: T) -> Result<PcapngReader<T>, ReadError> {
+        let mut result = PcapngReader {
+            reader,
+            byte_order: PcapByteOrder::LittleEndian,
+            interfaces: Vec::new(),
+            buffer: Vec::new(),
+            pending: false,
+        };
+        result.read_block(true)?;
+        result.pending = true;
+        Ok(result)
+    }
+
+    /// Returns the interfaces of the current section.
+    pub fn interfaces(&self) -> &[PcapngInterface] {
+        &self.interfaces
+    }
+
+    /// Reads the next block into the buffer. Returns false if the end of
+    /// the file was reached.
+    fn read_block(&mut self, first: bool) -> Result<bool, ReadError> {
+        use ReadError::*;
+        let mut header = [0u8; 12];
+
+        // an end of file directly before a block is the regular end
+        let mut filled = 0;
+        while filled < 8 {
+            match self.reader.read(&mut header[filled..8]) {
+                Ok(0) if 0 == filled && !first => return Ok(false),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(len) => filled += len,
+                Err(err) if io::ErrorKind::Interrupted == err.kind() => {},
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let block_type = self.byte_order.u32_from([header[0], header[1], header[2], header[3]]);
+        let byte_order = if block_type_is_section_header(block_type) {
+            self.reader.read_exact(&mut header[8..12])?;
+            filled = 12;
+            section_byte_order([header[8], header[9], header[10], header[11]])?
+        } else if first {
+            return Err(PcapngFirstBlockNotSectionHeader(u32::from_be_bytes([header[0], header[1], header[2], header[3]])));
+        } else {
+            self.byte_order
+        };
+
+        let total_len = byte_order.u32_from([header[4], header[5], header[6], header[7]]);
+        if total_len < 12 || 0 != total_len % 4 || total_len > MAX_BLOCK_LEN {
+            return Err(PcapngBlockLengthBad(total_len));
+        }
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&header[..filled]);
+        self.buffer.resize(total_len as usize, 0);
+        self.reader.read_exact(&mut self.buffer[filled..])?;
+
+        // decode to validate the block & update the section state
+        let (block, _) = PcapngBlock::from_slice(self.byte_order, &self.buffer)?;
+        update_section(&mut self.byte_order, &mut self.interfaces, &block);
+        Ok(true)
+    }
+
+    /// Reads the next block. Returns `Ok(None)` if the end of the file was reached.
+    ///
+    /// An `IoError` with the kind `UnexpectedEof` is returned if the
+    /// file ends within a block.
+    pub fn next_block(&mut self) -> Result<Option<PcapngBlock<'_>>, ReadError> {
+        if self.pending {
+            self.pending = false;
+        } else if !self.read_block(false)? {
+            return Ok(None);
+        }
+        Ok(Some(PcapngBlock::from_slice(self.byte_order, &self.buffer)?.0))
+    }
+
+    /// Reads the next packet (skipping all other blocks). Returns `Ok(None)`
+    /// if the end of the file was reached.
+    pub fn next_packet(&mut self) -> Result<Option<PcapngPacket<'_>>, ReadError> {
+        loop {
+            if self.pending {
+                self.pending = false;
+            } else if !self.read_block(false)? {
+                return Ok(None);
+            }
+            let block_type = self.byte_order.u32_from([self.buffer[0], self.buffer[1], self.buffer[2], self.buffer[3]]);
+            if block_type::ENHANCED_PACKET == block_type || block_type::SIMPLE_PACKET == block_type {
+                break;
+            }
+        }
+        let block = PcapngBlock::from_slice(self.byte_order, &self.buffer)?.0;
+        match PcapngPacket::from_block(&self.interfaces, &block) {
+            Some(result) => result.map(Some),
+            // not reachable as only packet blocks are decoded
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> T {
+        self.reader
+    }
+}
+
+/// Writes packets to a pcapng file.
+#[derive(Debug)]
+pub struct PcapngWriter<T: io::Write> {
+    writer: T,
+    byte_order: PcapByteOrder,
+    interfaces: Vec<PcapngInterface>,
+}
+
+impl<T: io::Write> PcapngWriter<T> {
+    /// Writes a section header block & creates a writer for the section.
+    pub fn new(mut writer: T, byte_order: PcapByteOrder) -> Result<PcapngWriter<T>, WriteError> {
+        let mut body = Vec::with_capacity(16);
+        body.extend_from_slice(&byte_order.u32_to(BYTE_ORDER_MAGIC));
+        body.extend_from_slice(&byte_order.u16_to(1));
+        body.extend_from_slice(&byte_order.u16_to(0));
+        // unknown section length
+        body.extend_from_slice(&byte_order.u64_to(u64::MAX));
+        write_block(&mut writer, byte_order, block_type::SECTION_HEADER, &body)?;
+        Ok(PcapngWriter {
+            writer,
+            byte_order,
+            interfaces: Vec::new(),
+        })
+    }
+
+    /// Returns the interfaces added to the writer.
+    pub fn interfaces(&self) -> &[PcapngInterface] {
+        &self.interfaces
+    }
+
+    /// Writes an interface description block & returns the id of the
+    /// interface. All timestamps of the interface are stored in nanoseconds.
+    pub fn add_interface(&mut self, link_type: u16, snaplen: u32) -> Result<u32, WriteError> {
+        let timestamp_resolution = PcapngTimestampResolution::Decimal(9);
+        let mut body = Vec::with_capacity(20);
+        body.extend_from_slice(&self.byte_order.u16_to(link_type));
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(&self.byte_order.u32_to(snaplen));
+        push_option(&mut body, self.byte_order, option_code::IF_TSRESOL, &[timestamp_resolution.to_option_value()]);
+        push_option(&mut body, self.byte_order, option_code::END_OF_OPT, &[]);
+        write_block(&mut self.writer, self.byte_order, block_type::INTERFACE_DESCRIPTION, &body)?;
+
+        self.interfaces.push(PcapngInterface {
+            link_type,
+            snaplen,
+            timestamp_resolution,
+            timestamp_offset: 0,
+        });
+        Ok((self.interfaces.len() - 1) as u32)
+    }
+
+    /// Writes an enhanced packet block. Packets bigger then the snapshot
+    /// length of the interface get truncated.
+    pub fn write_packet(&mut self, interface_id: u32, timestamp: Duration, data: &[u8]) -> Result<(), WriteError> {
+        self.write_packet_with_comments(interface_id, timestamp, data, &[])
+    }
+
+    /// Writes an enhanced packet block with the given comments. Packets
+    /// bigger then the snapshot length of the interface get truncated.
+    pub fn write_packet_with_comments(&mut self, interface_id: u32, timestamp: Duration, data: &[u8], comments: &[&str]) -> Result<(), WriteError> {
+        use ValueError::*;
+        let interface = self.interfaces
+            .get(interface_id as usize)
+            .ok_or(WriteError::ValueError(PcapngUnknownInterfaceId(interface_id)))?;
+        if let Some(comment) = comments.iter().find(|c| c.len() > u16::MAX as usize) {
+            return Err(WriteError::ValueError(PcapngOptionTooLarge(comment.len())));
+        }
+
+        let captured = if 0 != interface.snaplen && data.len() > interface.snaplen as usize {
+            &data[..interface.snaplen as usize]
+        } else {
+            data
+        };
+        let ticks = timestamp.as_nanos() as u64;
+
+        let mut body = Vec::with_capacity(24 + captured.len() + 3);
+        body.extend_from_slice(&self.byte_order.u32_to(interface_id));
+        body.extend_from_slice(&self.byte_order.u32_to((ticks >> 32) as u32));
+        body.extend_from_slice(&self.byte_order.u32_to(ticks as u32));
+        body.extend_from_slice(&self.byte_order.u32_to(captured.len() as u32));
+        body.extend_from_slice(&self.byte_order.u32_to(data.len() as u32));
+        body.extend_from_slice(captured);
+        body.resize((body.len() + 3) & !3, 0);
+        for comment in comments {
+            push_option(&mut body, self.byte_order, option_code::COMMENT, comment.as_bytes());
+        }
+        if !comments.is_empty() {
+            push_option(&mut body, self.byte_order, option_code::END_OF_OPT, &[]);
+        }
+        write_block(&mut self.writer, self.byte_order, block_type::ENHANCED_PACKET, &body)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), WriteError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> T {
+        self.writer
+    }
+}
+
+/// Appends an option (padded to 4 bytes) to the target.
+fn push_option(target: &mut Vec<u8>, byte_order: PcapByteOrder, code: u16, value: &[u8]) {
+    target.extend_from_slice(&byte_order.u16_to(code));
+    target.extend_from_slice(&byte_order.u16_to(value.len() as u16));
+    target.extend_from_slice(value);
+    target.resize((target.len() + 3) & !3, 0);
+}
+
+/// Writes a block with the given (already padded) body.
+#[cfg(feature = "std")]
+fn write_block<T: io::Write>(writer: &mut T, byte_order: PcapByteOrder, block_type: u32, body: &[u8]) -> Result<(), WriteError> {
+    let total_len = byte_order.u32_to((12 + body.len()) as u32);
+    writer.write_all(&byte_order.u32_to(block_type))?;
+    writer.write_all(&total_len)?;
+    writer.write_all(body)?;
+    writer.write_all(&total_len)?;
+    Ok(())
+}
@@ -0,0 +1,482 @@
+//! Reassembly of the byte streams of tcp connections.
+//!
+//! The [`TcpReassembler`] tracks the sequence numbers of both directions of
+//! every tcp connection it sees and delivers the payload in order via a
+//! callback. Out of order segments are buffered until the missing data
+//! arrives, retransmitted & overlapping data is only delivered once
+//! (for overlapping buffered segments the segment starting first wins) and
+//! sequence number wraparounds are handled transparently.
+//!
+//! Connections are no longer tracked after a RST, after both directions
+//! were closed via a FIN, after they were idle for longer then the idle
+//! timeout (see [`TcpReassembler::expire`]) or when they are removed via
+//! [`TcpReassembler::remove`]. All timestamps are passed in by the caller
+//! (e.g. the capture timestamps of a pcap file) as a [`Duration`] since an
+//! arbitrary start point.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use etherparse::SlicedPacket;
+//! use etherparse::tcp_reassembly::{TcpReassembler, TcpStreamEvent};
+//!
+//! let mut reassembler = TcpReassembler::default();
+//! # let packets: Vec<(Duration, Vec<u8>)> = Vec::new();
+//! for (timestamp, packet) in packets.iter() {
+//!     if let Ok(sliced) = SlicedPacket::from_ethernet(packet) {
+//!         reassembler.process_sliced(*timestamp, &sliced, &mut |key, direction, event| {
+//!             if let TcpStreamEvent::Data(data) = event {
+//!                 println!("{:?} {:?}: {} bytes", key, direction, data.len());
+//!             }
+//!         });
+//!     }
+//!     // stop tracking idle connections
+//!     reassembler.expire(*timestamp, &mut |key, direction, event| {
+//!         println!("{:?} {:?}: {:?}", key, direction, event);
+//!     });
+//! }
+//!
+//! // deliver the data that is still buffered at the end of the capture
+//! reassembler.flush(&mut |key, direction, event| {
+//!     println!("{:?} {:?}: {:?}", key, direction, event);
+//! });
+//! ```
+use super::*;
+
+use std::collections::{BTreeMap, HashMap};
+use core::net::IpAddr;
+use core::time::Duration;
+
+/// IP address & port of one side of a tcp connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct TcpEndpoint {
+    pub address: IpAddr,
+    pub port: u16,
+}
+
+/// Identifies a tcp connection tracked by the [`TcpReassembler`].
+///
+/// The client is the side that sent the initial SYN. If the start of the
+/// connection was not seen, the sender of the first seen segment is
+/// treated as the client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct TcpConnectionKey {
+    pub client: TcpEndpoint,
+    pub server: TcpEndpoint,
+}
+
+/// Direction of a byte stream in a tcp connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TcpStreamDirection {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// Events delivered by the [`TcpReassembler`] for one direction of a connection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TcpStreamEvent<'a> {
+    /// Next bytes of the stream (in order).
+    Data(&'a [u8]),
+    /// The given number of bytes were skipped, as they were never received
+    /// and the buffer limits were reached (or the reassembler was flushed).
+    Gap(u64),
+    /// All data up to the FIN was delivered. No more events follow for this direction.
+    Fin,
+    /// A RST was received from the sender in this direction. The connection
+    /// is no longer tracked & no more events follow for both directions.
+    Reset,
+    /// The connection was idle for longer then the idle timeout. The buffered
+    /// data was delivered before (missing data is reported as gaps) & no more
+    /// events follow for this direction.
+    TimedOut,
+}
+
+/// Limits & behaviour of a [`TcpReassembler`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcpReassemblyConfig {
+    /// Maximum number of out of order bytes buffered per direction of a
+    /// connection. When the limit is exceeded the missing data is skipped
+    /// (reported as [`TcpStreamEvent::Gap`]) until the buffer is within the limit again.
+    pub max_buffered_bytes: usize,
+    /// Maximum number of out of order segments buffered per direction of a connection.
+    pub max_buffered_segments: usize,
+    /// Maximum number of tracked connections. Segments of new connections
+    /// are ignored while the limit is reached.
+    pub max_connections: usize,
+    /// If true connections are also tracked when the start of the connection
+    /// (the SYN) was not seen. Otherwise segments of such connections are ignored.
+    pub allow_midstream: bool,
+    /// Time after the last segment of a connection after which the connection
+    /// gets removed by [`TcpReassembler::expire`].
+    pub idle_timeout: Duration,
+}
+
+impl Default for TcpReassemblyConfig {
+    fn default() -> TcpReassemblyConfig {
+        TcpReassemblyConfig {
+            max_buffered_bytes: 1024 * 1024,
+            max_buffered_segments: 1024,
+            max_connections: 64 * 1024,
+            allow_midstream: true,
+            idle_timeout: Duration::from_secs(2 * 60 * 60),
+        }
+    }
+}
+
+/// Reassembles the byte streams of tcp connections (see the [module documentation](self)).
+#[derive(Clone, Debug, Default)]
+pub struct TcpReassembler {
+    config: TcpReassemblyConfig,
+    connections: HashMap<TcpConnectionKey, TcpConnectionStreams>,
+}
+
+/// State of both directions of a connection.
+#[derive(Clone, Debug)]
+struct TcpConnectionStreams {
+    client_to_server: TcpHalfStream,
+    server_to_client: TcpHalfStream,
+    /// Timestamp of the last segment of the connection.
+    last_seen: Duration,
+}
+
+/// State of one direction of a connection.
+#[derive(Clone, Debug, Default)]
+struct TcpHalfStream {
+    /// Sequence number at the stream offset 0 (None if not yet synchronized).
+    base_seq: Option<u32>,
+    /// Offset of the next byte to be delivered.
+    next_offset: u64,
+    /// Out of order data (keyed by the stream offset of the first byte).
+    pending: BTreeMap<u64, Vec<u8>>,
+    /// Sum of the lengths of the pending segments.
+    buffered: usize,
+    /// Stream offset of the FIN (if it was received).
+    fin_offset: Option<u64>,
+    /// True if the FIN was delivered.
+    finished: bool,
+}
+
+impl TcpReassembler {
+
+    /// Creates a reassembler with the given limits.
+    pub fn new(config: TcpReassemblyConfig) -> TcpReassembler {
+        TcpReassembler {
+            config,
+            connections: HashMap::new(),
+        }
+    }
+
+    /// Returns the configuration of the reassembler.
+    pub fn config(&self) -> &TcpReassemblyConfig {
+        &self.config
+    }
+
+    /// Returns the number of currently tracked connections.
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Processes a sliced packet. Returns false if the packet does not contain
+    /// a tcp segment (or is an ip fragment) and was therefore ignored.
+    pub fn process_sliced<F>(&mut self, timestamp: Duration, packet: &SlicedPacket, callback: &mut F) -> bool
+    where F: FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>)
+    {
+        match (&packet.ip, &packet.transport) {
+            (Some(ip), Some(TransportSlice::Tcp(tcp))) if !ip.is_fragmenting_payload() => {
+                let (source, destination) = match ip {
+                    InternetSlice::Ipv4(header, _) => (
+                        IpAddr::V4(header.source_addr()),
+                        IpAddr::V4(header.destination_addr()),
+                    ),
+                    InternetSlice::Ipv6(header, _) => (
+                        IpAddr::V6(header.source_addr()),
+                        IpAddr::V6(header.destination_addr()),
+                    ),
+                };
+                self.process_segment(timestamp, source, destination, tcp, packet.payload, callback);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Processes a tcp segment sent from the `source` to the `destination` ip address.
+    pub fn process_segment<F>(
+        &mut self,
+        timestamp: Duration,
+        source: IpAddr,
+        destination: IpAddr,
+        tcp: &TcpHeaderSlice,
+        payload: &[u8],
+        callback: &mut F
+    )
+    where F: FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>)
+    {
+        let sender = TcpEndpoint{ address: source, port: tcp.source_port() };
+        let receiver = TcpEndpoint{ address: destination, port: tcp.destination_port() };
+
+        // determine the connection & direction
+        let forward = TcpConnectionKey{ client: sender, server: receiver };
+        let backward = TcpConnectionKey{ client: receiver, server: sender };
+        let (key, direction) = if self.connections.contains_key(&forward) {
+            (forward, TcpStreamDirection::ClientToServer)
+        } else if self.connections.contains_key(&backward) {
+            (backward, TcpStreamDirection::ServerToClient)
+        } else {
+            // new connection
+            if tcp.rst() ||
+               self.connections.len() >= self.config.max_connections ||
+               (!tcp.syn() && !self.config.allow_midstream)
+            {
+                return;
+            }
+            let result = if tcp.syn() && tcp.ack() {
+                (backward, TcpStreamDirection::ServerToClient)
+            } else {
+                (forward, TcpStreamDirection::ClientToServer)
+            };
+            self.connections.insert(result.0, TcpConnectionStreams{
+                client_to_server: Default::default(),
+                server_to_client: Default::default(),
+                last_seen: timestamp,
+            });
+            result
+        };
+
+        let streams = self.connections.get_mut(&key).unwrap();
+        streams.last_seen = timestamp;
+        if tcp.rst() {
+            callback(&key, direction, TcpStreamEvent::Reset);
+            self.connections.remove(&key);
+            return;
+        }
+
+        streams.stream_mut(direction).add_segment(
+            &self.config,
+            tcp.sequence_number(),
+            tcp.syn(),
+            tcp.fin(),
+            payload,
+            &mut |event| callback(&key, direction, event)
+        );
+
+        if streams.client_to_server.finished && streams.server_to_client.finished {
+            self.connections.remove(&key);
+        }
+    }
+
+    /// Delivers the buffered data of all connections (missing data is reported
+    /// as gaps) and stops tracking all connections.
+    pub fn flush<F>(&mut self, callback: &mut F)
+    where F: FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>)
+    {
+        for (key, mut streams) in self.connections.drain() {
+            streams.flush(&key, callback);
+        }
+    }
+
+    /// Removes all connections that were idle for longer then the idle timeout.
+    ///
+    /// The buffered data of the removed connections is delivered (missing data
+    /// is reported as gaps) followed by a [`TcpStreamEvent::TimedOut`] for
+    /// every direction that was not yet closed.
+    pub fn expire<F>(&mut self, now: Duration, callback: &mut F)
+    where F: FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>)
+    {
+        let idle_timeout = self.config.idle_timeout;
+        self.connections.retain(|key, streams| {
+            let idle = now.checked_sub(streams.last_seen).unwrap_or_default();
+            if idle > idle_timeout {
+                for direction in [TcpStreamDirection::ClientToServer, TcpStreamDirection::ServerToClient].iter() {
+                    let stream = streams.stream_mut(*direction);
+                    if !stream.finished {
+                        stream.flush(&mut |event| callback(key, *direction, event));
+                        callback(key, *direction, TcpStreamEvent::TimedOut);
+                    }
+                }
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Stops tracking the given connection without delivering its buffered data.
+    /// Returns false if the connection was not tracked.
+    pub fn remove(&mut self, key: &TcpConnectionKey) -> bool {
+        self.connections.remove(key).is_some()
+    }
+}
+
+impl TcpConnectionStreams {
+
+    /// Returns the stream of the given direction.
+    fn stream_mut(&mut self, direction: TcpStreamDirection) -> &mut TcpHalfStream {
+        match direction {
+            TcpStreamDirection::ClientToServer => &mut self.client_to_server,
+            TcpStreamDirection::ServerToClient => &mut self.server_to_client,
+        }
+    }
+
+    /// Delivers the buffered data of both directions.
+    fn flush<F>(&mut self, key: &TcpConnectionKey, callback: &mut F)
+    where F: FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>)
+    {
+        self.client_to_server.flush(&mut |event| {
+            callback(key, TcpStreamDirection::ClientToServer, event)
+        });
+        self.server_to_client.flush(&mut |event| {
+            callback(key, TcpStreamDirection::ServerToClient, event)
+        });
+    }
+}
+
+impl TcpHalfStream {
+
+    /// Adds a segment to the stream & delivers all data that is in order.
+    fn add_segment(
+        &mut self,
+        config: &TcpReassemblyConfig,
+        seq: u32,
+        syn: bool,
+        fin: bool,
+        payload: &[u8],
+        emit: &mut dyn FnMut(TcpStreamEvent<'_>)
+    ) {
+        if self.finished {
+            return;
+        }
+
+        // the SYN uses one sequence number (data starts after it)
+        let seq = if syn { seq.wrapping_add(1) } else { seq };
+        let base_seq = match self.base_seq {
+            Some(value) => value,
+            None => {
+                if !syn && !config.allow_midstream {
+                    return;
+                }
+                self.base_seq = Some(seq);
+                seq
+            }
+        };
+
+        // determine the stream offset of the segment relative to the next
+        // expected byte (the difference is interpreted as signed value to
+        // handle sequence number wraparounds)
+        let next_seq = base_seq.wrapping_add(self.next_offset as u32);
+        let start = (self.next_offset as i64) + i64::from(seq.wrapping_sub(next_seq) as i32);
+        let end = start + payload.len() as i64;
+
+        if fin && self.fin_offset.is_none() && end >= self.next_offset as i64 {
+            self.fin_offset = Some(end as u64);
+        }
+
+        // skip the data that was already delivered (retransmissions)
+        if end > self.next_offset as i64 {
+            let skip = ((self.next_offset as i64) - start).max(0) as usize;
+            let start = (start as u64) + skip as u64;
+            let data = &payload[skip..];
+            if start == self.next_offset && self.pending.is_empty() {
+                // fast path, data is in order
+                self.deliver(data, emit);
+            } else {
+                self.insert_pending(start, data);
+                self.drain_pending(emit);
+
+                // skip missing data until the buffer limits are respected again
+                while self.buffered > config.max_buffered_bytes ||
+                      self.pending.len() > config.max_buffered_segments
+                {
+                    self.skip_to_first_pending(emit);
+                }
+            }
+        }
+
+        self.check_fin(emit);
+    }
+
+    /// Delivers all pending data, reporting missing data as gaps.
+    fn flush(&mut self, emit: &mut dyn FnMut(TcpStreamEvent<'_>)) {
+        if self.finished {
+            return;
+        }
+        while !self.pending.is_empty() {
+            self.skip_to_first_pending(emit);
+        }
+        self.check_fin(emit);
+    }
+
+    /// Delivers in order data (data beyond a received FIN is discarded).
+    fn deliver(&mut self, data: &[u8], emit: &mut dyn FnMut(TcpStreamEvent<'_>)) {
+        let data = match self.fin_offset {
+            Some(fin_offset) => {
+                let max_len = fin_offset.saturating_sub(self.next_offset) as usize;
+                &data[..data.len().min(max_len)]
+            },
+            None => data,
+        };
+        if !data.is_empty() {
+            self.next_offset += data.len() as u64;
+            emit(TcpStreamEvent::Data(data));
+        }
+    }
+
+    /// Buffers an out of order segment (if a segment with the same start
+    /// is already buffered the longer one is kept).
+    fn insert_pending(&mut self, start: u64, data: &[u8]) {
+        match self.pending.get_mut(&start) {
+            Some(existing) => {
+                if existing.len() < data.len() {
+                    self.buffered += data.len() - existing.len();
+                    existing.clear();
+                    existing.extend_from_slice(data);
+                }
+            },
+            None => {
+                self.buffered += data.len();
+                self.pending.insert(start, data.to_vec());
+            },
+        }
+    }
+
+    /// Delivers all pending segments that are in order.
+    fn drain_pending(&mut self, emit: &mut dyn FnMut(TcpStreamEvent<'_>)) {
+        while let Some(start) = self.pending.keys().next().copied() {
+            if start > self.next_offset {
+                break;
+            }
+            let data = self.pending.remove(&start).unwrap();
+            self.buffered -= data.len();
+            let end = start + data.len() as u64;
+            if end > self.next_offset {
+                let skip = (self.next_offset - start) as usize;
+                self.deliver(&data[skip..], emit);
+            }
+        }
+    }
+
+    /// Skips the missing data up to the first pending segment and delivers
+    /// all data that is in order afterwards.
+    fn skip_to_first_pending(&mut self, emit: &mut dyn FnMut(TcpStreamEvent<'_>)) {
+        if let Some(start) = self.pending.keys().next().copied() {
+            if start > self.next_offset {
+                emit(TcpStreamEvent::Gap(start - self.next_offset));
+                self.next_offset = start;
+            }
+            self.drain_pending(emit);
+        }
+    }
+
+    /// Reports the FIN if all data before it was delivered.
+    fn check_fin(&mut self, emit: &mut dyn FnMut(TcpStreamEvent<'_>)) {
+        if let Some(fin_offset) = self.fin_offset {
+            if self.next_offset >= fin_offset {
+                self.finished = true;
+                self.pending.clear();
+                self.buffered = 0;
+                emit(TcpStreamEvent::Fin);
+            }
+        }
+    }
+}
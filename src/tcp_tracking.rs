@@ -0,0 +1,496 @@
+//! Tracking of the states of tcp connections.
+//!
+//! The [`TcpConnectionTracker`] follows the [RFC 793](https://datatracker.ietf.org/doc/html/rfc793)
+//! states of both endpoints of every tcp connection based on the observed
+//! segments (similar to a connection tracking firewall). Changes like a
+//! completed handshake, a half close, a reset or a timeout are reported via a
+//! callback together with detected anomalies (e.g. SYN+FIN or Xmas flag combinations).
+//!
+//! All timestamps are passed in by the caller (e.g. the capture timestamps
+//! of a pcap file) as a [`Duration`] since an arbitrary start point.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use etherparse::SlicedPacket;
+//! use etherparse::tcp_tracking::{TcpConnectionTracker, TcpTrackerEvent};
+//!
+//! let mut tracker = TcpConnectionTracker::default();
+//! # let packets: Vec<(Duration, Vec<u8>)> = Vec::new();
+//! for (timestamp, packet) in packets.iter() {
+//!     if let Ok(sliced) = SlicedPacket::from_ethernet(packet) {
+//!         tracker.process_sliced(*timestamp, &sliced, &mut |key, event| {
+//!             if let TcpTrackerEvent::HandshakeCompleted(Some(rtt)) = event {
+//!                 println!("{:?} handshake rtt {:?}", key, rtt.total());
+//!             }
+//!         });
+//!     }
+//!     // remove idle connections
+//!     tracker.expire(*timestamp, &mut |key, event| println!("{:?} {:?}", key, event));
+//! }
+//! ```
+use super::*;
+use crate::tcp_reassembly::{TcpConnectionKey, TcpEndpoint, TcpStreamDirection};
+
+use std::collections::HashMap;
+use core::net::IpAddr;
+use core::time::Duration;
+
+/// States of a tcp endpoint as defined in RFC 793.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TcpState {
+    Closed,
+    Listen,
+    SynSent,
+    SynReceived,
+    Established,
+    FinWait1,
+    FinWait2,
+    CloseWait,
+    Closing,
+    LastAck,
+    TimeWait,
+}
+
+/// Round trip times measured during the three way handshake.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TcpHandshakeRtt {
+    /// Time between the SYN and the SYN+ACK (round trip time between the
+    /// capture point & the server).
+    pub syn_to_syn_ack: Duration,
+    /// Time between the SYN+ACK and the ACK (round trip time between the
+    /// capture point & the client).
+    pub syn_ack_to_ack: Duration,
+}
+
+impl TcpHandshakeRtt {
+    /// Returns the round trip time between the client & the server.
+    pub fn total(&self) -> Duration {
+        self.syn_to_syn_ack + self.syn_ack_to_ack
+    }
+}
+
+/// Unusual segments detected by the [`TcpConnectionTracker`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TcpAnomaly {
+    /// SYN & FIN flags are both set.
+    SynFin,
+    /// SYN & RST flags are both set.
+    SynRst,
+    /// FIN, PSH & URG flags are set ("Xmas" scan).
+    Xmas,
+    /// No flag is set ("Null" scan).
+    NullFlags,
+    /// Segment with payload (and without a SYN flag) before the handshake was completed.
+    DataBeforeHandshake,
+}
+
+/// Changes of a connection reported by the [`TcpConnectionTracker`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TcpTrackerEvent {
+    /// A new connection is tracked.
+    New,
+    /// The three way handshake was completed (the round trip times are
+    /// only present if the SYN & SYN+ACK were seen).
+    HandshakeCompleted(Option<TcpHandshakeRtt>),
+    /// The sender in the given direction closed its side of the connection (sent a FIN).
+    HalfClosed(TcpStreamDirection),
+    /// Both sides closed the connection.
+    Closed,
+    /// The sender in the given direction reset the connection.
+    Reset(TcpStreamDirection),
+    /// The connection was idle for longer then the timeout & is no longer tracked.
+    TimedOut,
+    /// An unusual segment was sent in the given direction. Segments with invalid
+    /// flag combinations do not change the state of the connection.
+    Anomaly(TcpStreamDirection, TcpAnomaly),
+}
+
+/// Timeouts & limits of a [`TcpConnectionTracker`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcpTrackerConfig {
+    /// Idle timeout of established (or half closed) connections.
+    pub established_timeout: Duration,
+    /// Idle timeout of connections in the handshake or while closing.
+    pub transitory_timeout: Duration,
+    /// Time closed connections are kept (e.g. to recognize retransmissions).
+    pub time_wait_timeout: Duration,
+    /// Maximum number of tracked connections. Segments of new connections
+    /// are ignored while the limit is reached.
+    pub max_connections: usize,
+    /// If true connections are also tracked when the handshake was not seen
+    /// (both endpoints are then assumed to be established).
+    pub allow_midstream: bool,
+}
+
+impl Default for TcpTrackerConfig {
+    fn default() -> TcpTrackerConfig {
+        TcpTrackerConfig {
+            established_timeout: Duration::from_secs(5 * 24 * 60 * 60),
+            transitory_timeout: Duration::from_secs(2 * 60),
+            time_wait_timeout: Duration::from_secs(2 * 60),
+            max_connections: 64 * 1024,
+            allow_midstream: true,
+        }
+    }
+}
+
+/// State of a tracked tcp connection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcpConnection {
+    /// State of the client endpoint.
+    pub client_state: TcpState,
+    /// State of the server endpoint.
+    pub server_state: TcpState,
+    /// Timestamp of the first segment of the connection.
+    pub first_seen: Duration,
+    /// Timestamp of the last segment of the connection.
+    pub last_seen: Duration,
+    /// Round trip times of the handshake (if the complete handshake was seen).
+    pub handshake_rtt: Option<TcpHandshakeRtt>,
+    syn_time: Option<Duration>,
+    syn_ack_time: Option<Duration>,
+    /// Sequence number the client FIN is acknowledged with.
+    client_fin_ack: Option<u32>,
+    /// Sequence number the server FIN is acknowledged with.
+    server_fin_ack: Option<u32>,
+}
+
+impl TcpConnection {
+    fn new(timestamp: Duration, client_state: TcpState, server_state: TcpState) -> TcpConnection {
+        TcpConnection {
+            client_state,
+            server_state,
+            first_seen: timestamp,
+            last_seen: timestamp,
+            handshake_rtt: None,
+            syn_time: None,
+            syn_ack_time: None,
+            client_fin_ack: None,
+            server_fin_ack: None,
+        }
+    }
+
+    /// Returns true if both endpoints closed the connection.
+    pub fn is_closed(&self) -> bool {
+        use TcpState::*;
+        matches!(self.client_state, Closed | TimeWait) &&
+        matches!(self.server_state, Closed | TimeWait)
+    }
+
+    /// Returns true if the handshake was completed (or not seen).
+    pub fn is_established(&self) -> bool {
+        use TcpState::*;
+        !matches!(self.client_state, Listen | SynSent | SynReceived) &&
+        !matches!(self.server_state, Listen | SynSent | SynReceived)
+    }
+
+    /// Returns the idle timeout based on the current states.
+    fn timeout(&self, config: &TcpTrackerConfig) -> Duration {
+        use TcpState::*;
+        if self.is_closed() {
+            config.time_wait_timeout
+        } else if matches!(self.client_state, Established | FinWait2 | CloseWait) &&
+                  matches!(self.server_state, Established | FinWait2 | CloseWait)
+        {
+            config.established_timeout
+        } else {
+            config.transitory_timeout
+        }
+    }
+}
+
+/// Tracks the states of tcp connections (see the [module documentation](self)).
+#[derive(Clone, Debug, Default)]
+pub struct TcpConnectionTracker {
+    config: TcpTrackerConfig,
+    connections: HashMap<TcpConnectionKey, TcpConnection>,
+}
+
+/// Returns true if `a` is equal or after `b` in sequence number space.
+#[inline]
+fn seq_ge(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) >= 0
+}
+
+impl TcpConnectionTracker {
+
+    /// Creates a tracker with the given timeouts & limits.
+    pub fn new(config: TcpTrackerConfig) -> TcpConnectionTracker {
+        TcpConnectionTracker {
+            config,
+            connections: HashMap::new(),
+        }
+    }
+
+    /// Returns the configuration of the tracker.
+    pub fn config(&self) -> &TcpTrackerConfig {
+        &self.config
+    }
+
+    /// Returns the number of currently tracked connections.
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Returns the state of a tracked connection.
+    pub fn connection(&self, key: &TcpConnectionKey) -> Option<&TcpConnection> {
+        self.connections.get(key)
+    }
+
+    /// Returns an iterator over all tracked connections.
+    pub fn connections(&self) -> impl Iterator<Item = (&TcpConnectionKey, &TcpConnection)> {
+        self.connections.iter()
+    }
+
+    /// Processes a sliced packet. Returns false if the packet does not contain
+    /// a tcp segment (or is an ip fragment) and was therefore ignored.
+    pub fn process_sliced<F>(&mut self, timestamp: Duration, packet: &SlicedPacket, callback: &mut F) -> bool
+    where F: FnMut(&TcpConnectionKey, TcpTrackerEvent)
+    {
+        match (&packet.ip, &packet.transport) {
+            (Some(ip), Some(TransportSlice::Tcp(tcp))) if !ip.is_fragmenting_payload() => {
+                let (source, destination) = match ip {
+                    InternetSlice::Ipv4(header, _) => (
+                        IpAddr::V4(header.source_addr()),
+                        IpAddr::V4(header.destination_addr()),
+                    ),
+                    InternetSlice::Ipv6(header, _) => (
+                        IpAddr::V6(header.source_addr()),
+                        IpAddr::V6(header.destination_addr()),
+                    ),
+                };
+                self.process_segment(timestamp, source, destination, tcp, packet.payload.len(), callback);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Processes a tcp segment sent from the `source` to the `destination` ip address.
+    pub fn process_segment<F>(
+        &mut self,
+        timestamp: Duration,
+        source: IpAddr,
+        destination: IpAddr,
+        tcp: &TcpHeaderSlice,
+        payload_len: usize,
+        callback: &mut F
+    )
+    where F: FnMut(&TcpConnectionKey, TcpTrackerEvent)
+    {
+        use TcpState::*;
+        use TcpStreamDirection::*;
+
+        let sender = TcpEndpoint{ address: source, port: tcp.source_port() };
+        let receiver = TcpEndpoint{ address: destination, port: tcp.destination_port() };
+        let forward = TcpConnectionKey{ client: sender, server: receiver };
+        let backward = TcpConnectionKey{ client: receiver, server: sender };
+
+        // determine the connection & direction
+        let existing = if self.connections.contains_key(&forward) {
+            Some((forward, ClientToServer))
+        } else if self.connections.contains_key(&backward) {
+            Some((backward, ServerToClient))
+        } else {
+            None
+        };
+
+        // check the flags
+        let flag_anomaly = if tcp.syn() && tcp.fin() {
+            Some(TcpAnomaly::SynFin)
+        } else if tcp.syn() && tcp.rst() {
+            Some(TcpAnomaly::SynRst)
+        } else if tcp.fin() && tcp.psh() && tcp.urg() {
+            Some(TcpAnomaly::Xmas)
+        } else if !(tcp.ns() || tcp.cwr() || tcp.ece() || tcp.urg() || tcp.ack() ||
+                    tcp.psh() || tcp.rst() || tcp.syn() || tcp.fin())
+        {
+            Some(TcpAnomaly::NullFlags)
+        } else {
+            None
+        };
+        if let Some(anomaly) = flag_anomaly {
+            let (key, direction) = existing.unwrap_or((forward, ClientToServer));
+            callback(&key, TcpTrackerEvent::Anomaly(direction, anomaly));
+            return;
+        }
+
+        // a new syn replaces a closed connection
+        if let Some((key, _)) = existing {
+            if tcp.syn() && !tcp.ack() && self.connections[&key].is_closed() {
+                self.connections.remove(&key);
+                return self.process_segment(timestamp, source, destination, tcp, payload_len, callback);
+            }
+        }
+
+        let (key, direction) = match existing {
+            Some(value) => value,
+            None => {
+                if tcp.rst() || self.connections.len() >= self.config.max_connections {
+                    return;
+                }
+                let (key, direction, connection) = if tcp.syn() {
+                    if tcp.ack() {
+                        // syn of the client was not seen
+                        let mut c = TcpConnection::new(timestamp, SynSent, SynReceived);
+                        c.syn_ack_time = Some(timestamp);
+                        (backward, ServerToClient, c)
+                    } else {
+                        let mut c = TcpConnection::new(timestamp, SynSent, Listen);
+                        c.syn_time = Some(timestamp);
+                        (forward, ClientToServer, c)
+                    }
+                } else if self.config.allow_midstream {
+                    (forward, ClientToServer, TcpConnection::new(timestamp, Established, Established))
+                } else {
+                    return;
+                };
+                self.connections.insert(key, connection);
+                callback(&key, TcpTrackerEvent::New);
+                if !tcp.syn() {
+                    // the fin flag still has to be processed
+                    self.update(key, direction, timestamp, tcp, payload_len, callback);
+                }
+                return;
+            }
+        };
+        self.update(key, direction, timestamp, tcp, payload_len, callback);
+    }
+
+    /// Updates the state of an existing connection.
+    fn update<F>(
+        &mut self,
+        key: TcpConnectionKey,
+        direction: TcpStreamDirection,
+        timestamp: Duration,
+        tcp: &TcpHeaderSlice,
+        payload_len: usize,
+        callback: &mut F
+    )
+    where F: FnMut(&TcpConnectionKey, TcpTrackerEvent)
+    {
+        use TcpState::*;
+        use TcpStreamDirection::*;
+
+        let c = self.connections.get_mut(&key).unwrap();
+        c.last_seen = timestamp;
+        let was_closed = c.is_closed();
+
+        if tcp.rst() {
+            c.client_state = Closed;
+            c.server_state = Closed;
+            if !was_closed {
+                callback(&key, TcpTrackerEvent::Reset(direction));
+            }
+            return;
+        }
+
+        if payload_len > 0 && !tcp.syn() && !c.is_established() {
+            callback(&key, TcpTrackerEvent::Anomaly(direction, TcpAnomaly::DataBeforeHandshake));
+        }
+
+        // handshake
+        match direction {
+            ClientToServer => {
+                if tcp.syn() && c.syn_time.is_none() {
+                    c.syn_time = Some(timestamp);
+                }
+                if tcp.ack() && !tcp.syn() && c.client_state == SynSent && c.server_state == SynReceived {
+                    c.client_state = Established;
+                    c.server_state = Established;
+                    if let (Some(syn), Some(syn_ack)) = (c.syn_time, c.syn_ack_time) {
+                        c.handshake_rtt = Some(TcpHandshakeRtt{
+                            syn_to_syn_ack: syn_ack.checked_sub(syn).unwrap_or_default(),
+                            syn_ack_to_ack: timestamp.checked_sub(syn_ack).unwrap_or_default(),
+                        });
+                    }
+                    callback(&key, TcpTrackerEvent::HandshakeCompleted(c.handshake_rtt));
+                }
+            },
+            ServerToClient => {
+                if tcp.syn() && tcp.ack() && matches!(c.server_state, Listen | SynReceived) {
+                    c.server_state = SynReceived;
+                    if c.syn_ack_time.is_none() {
+                        c.syn_ack_time = Some(timestamp);
+                    }
+                }
+            },
+        }
+
+        // split in the states of the sender & receiver
+        let (sender_state, receiver_state, sender_fin_ack, receiver_fin_ack) = match direction {
+            ClientToServer => (&mut c.client_state, &mut c.server_state, &mut c.client_fin_ack, &mut c.server_fin_ack),
+            ServerToClient => (&mut c.server_state, &mut c.client_state, &mut c.server_fin_ack, &mut c.client_fin_ack),
+        };
+
+        // acknowledgment of the fin of the receiver
+        if tcp.ack() {
+            if let Some(fin_ack) = receiver_fin_ack {
+                if seq_ge(tcp.acknowledgment_number(), *fin_ack) {
+                    *receiver_state = match *receiver_state {
+                        FinWait1 => FinWait2,
+                        Closing => TimeWait,
+                        LastAck => Closed,
+                        other => other,
+                    };
+                }
+            }
+        }
+
+        // fin of the sender
+        if tcp.fin() && sender_fin_ack.is_none() {
+            let new_state = match *sender_state {
+                Established | SynReceived => Some(FinWait1),
+                CloseWait => Some(LastAck),
+                _ => None,
+            };
+            if let Some(new_state) = new_state {
+                *sender_fin_ack = Some(
+                    tcp.sequence_number()
+                       .wrapping_add(payload_len as u32)
+                       .wrapping_add(1)
+                );
+                if CloseWait == *sender_state {
+                    // the receiver gets the fin after its own fin
+                    *receiver_state = match *receiver_state {
+                        FinWait2 => TimeWait,
+                        _ => Closing,
+                    };
+                } else {
+                    *receiver_state = match *receiver_state {
+                        FinWait1 => Closing,
+                        FinWait2 => TimeWait,
+                        _ => CloseWait,
+                    };
+                }
+                *sender_state = new_state;
+                callback(&key, TcpTrackerEvent::HalfClosed(direction));
+            }
+        }
+
+        if !was_closed && c.is_closed() {
+            callback(&key, TcpTrackerEvent::Closed);
+        }
+    }
+
+    /// Removes all connections that were idle for longer then their timeout
+    /// (closed connections are removed silently after the time wait timeout).
+    pub fn expire<F>(&mut self, now: Duration, callback: &mut F)
+    where F: FnMut(&TcpConnectionKey, TcpTrackerEvent)
+    {
+        let config = &self.config;
+        self.connections.retain(|key, connection| {
+            let idle = now.checked_sub(connection.last_seen).unwrap_or_default();
+            if idle > connection.timeout(config) {
+                if !connection.is_closed() {
+                    callback(key, TcpTrackerEvent::TimedOut);
+                }
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
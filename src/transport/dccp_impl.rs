@@ -0,0 +1,838 @@
+use super::super::*;
+
+use core::fmt::{Debug, Formatter};
+use core::slice::from_raw_parts;
+
+/// Module containing DCCP related types and constants.
+pub mod dccp {
+    use crate::*;
+
+    /// DCCP packet type of a "DCCP-Request" packet.
+    pub const TYPE_REQUEST: u8 = 0;
+    /// DCCP packet type of a "DCCP-Response" packet.
+    pub const TYPE_RESPONSE: u8 = 1;
+    /// DCCP packet type of a "DCCP-Data" packet.
+    pub const TYPE_DATA: u8 = 2;
+    /// DCCP packet type of a "DCCP-Ack" packet.
+    pub const TYPE_ACK: u8 = 3;
+    /// DCCP packet type of a "DCCP-DataAck" packet.
+    pub const TYPE_DATA_ACK: u8 = 4;
+    /// DCCP packet type of a "DCCP-CloseReq" packet.
+    pub const TYPE_CLOSE_REQ: u8 = 5;
+    /// DCCP packet type of a "DCCP-Close" packet.
+    pub const TYPE_CLOSE: u8 = 6;
+    /// DCCP packet type of a "DCCP-Reset" packet.
+    pub const TYPE_RESET: u8 = 7;
+    /// DCCP packet type of a "DCCP-Sync" packet.
+    pub const TYPE_SYNC: u8 = 8;
+    /// DCCP packet type of a "DCCP-SyncAck" packet.
+    pub const TYPE_SYNC_ACK: u8 = 9;
+
+    /// Option type of the "Padding" option (single byte).
+    pub const OPTION_PADDING: u8 = 0;
+    /// Option type of the "Mandatory" option (single byte).
+    pub const OPTION_MANDATORY: u8 = 1;
+    /// Option type of the "Slow Receiver" option (single byte).
+    pub const OPTION_SLOW_RECEIVER: u8 = 2;
+    /// Option type of the "Change L" feature negotiation option.
+    pub const OPTION_CHANGE_L: u8 = 32;
+    /// Option type of the "Confirm L" feature negotiation option.
+    pub const OPTION_CONFIRM_L: u8 = 33;
+    /// Option type of the "Change R" feature negotiation option.
+    pub const OPTION_CHANGE_R: u8 = 34;
+    /// Option type of the "Confirm R" feature negotiation option.
+    pub const OPTION_CONFIRM_R: u8 = 35;
+    /// Option type of the "Init Cookie" option.
+    pub const OPTION_INIT_COOKIE: u8 = 36;
+    /// Option type of the "NDP Count" option.
+    pub const OPTION_NDP_COUNT: u8 = 37;
+    /// Option type of the "Ack Vector \[Nonce 0\]" option.
+    pub const OPTION_ACK_VECTOR_NONCE_0: u8 = 38;
+    /// Option type of the "Ack Vector \[Nonce 1\]" option.
+    pub const OPTION_ACK_VECTOR_NONCE_1: u8 = 39;
+    /// Option type of the "Data Dropped" option.
+    pub const OPTION_DATA_DROPPED: u8 = 40;
+    /// Option type of the "Timestamp" option.
+    pub const OPTION_TIMESTAMP: u8 = 41;
+    /// Option type of the "Timestamp Echo" option.
+    pub const OPTION_TIMESTAMP_ECHO: u8 = 42;
+    /// Option type of the "Elapsed Time" option.
+    pub const OPTION_ELAPSED_TIME: u8 = 43;
+    /// Option type of the "Data Checksum" option.
+    pub const OPTION_DATA_CHECKSUM: u8 = 44;
+
+    /// Reset code "Unspecified".
+    pub const RESET_CODE_UNSPECIFIED: u8 = 0;
+    /// Reset code "Closed" (normal connection close).
+    pub const RESET_CODE_CLOSED: u8 = 1;
+    /// Reset code "Aborted".
+    pub const RESET_CODE_ABORTED: u8 = 2;
+    /// Reset code "No Connection".
+    pub const RESET_CODE_NO_CONNECTION: u8 = 3;
+    /// Reset code "Packet Error".
+    pub const RESET_CODE_PACKET_ERROR: u8 = 4;
+    /// Reset code "Option Error".
+    pub const RESET_CODE_OPTION_ERROR: u8 = 5;
+    /// Reset code "Mandatory Error".
+    pub const RESET_CODE_MANDATORY_ERROR: u8 = 6;
+    /// Reset code "Connection Refused".
+    pub const RESET_CODE_CONNECTION_REFUSED: u8 = 7;
+    /// Reset code "Bad Service Code".
+    pub const RESET_CODE_BAD_SERVICE_CODE: u8 = 8;
+    /// Reset code "Too Busy".
+    pub const RESET_CODE_TOO_BUSY: u8 = 9;
+    /// Reset code "Bad Init Cookie".
+    pub const RESET_CODE_BAD_INIT_COOKIE: u8 = 10;
+    /// Reset code "Aggression Penalty".
+    pub const RESET_CODE_AGGRESSION_PENALTY: u8 = 11;
+
+    /// Maximum value of a short (24 bit) sequence or acknowledgement number.
+    pub const MAX_SHORT_SEQUENCE_NUMBER: u64 = 0xff_ffff;
+    /// Maximum value of an extended (48 bit) sequence or acknowledgement number.
+    pub const MAX_EXTENDED_SEQUENCE_NUMBER: u64 = 0xffff_ffff_ffff;
+
+    /// Option contained in a DCCP header.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct DccpOption<'a> {
+        /// Type of the option.
+        pub option_type: u8,
+        /// Data of the option (excluding the type & length bytes).
+        /// Always empty for the single byte options (type 0 to 31).
+        pub data: &'a [u8],
+    }
+
+    /// Iterator over the options of a DCCP header.
+    ///
+    /// After an error was returned the iteration ends.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct DccpOptionsIterator<'a> {
+        options: &'a [u8],
+    }
+
+    impl<'a> DccpOptionsIterator<'a> {
+        /// Creates an iterator over the options contained in the given slice.
+        pub fn from_slice(options: &'a [u8]) -> DccpOptionsIterator<'a> {
+            DccpOptionsIterator { options }
+        }
+
+        /// Returns the not yet iterated part of the options.
+        #[inline]
+        pub fn rest(&self) -> &'a [u8] {
+            self.options
+        }
+    }
+
+    impl<'a> Iterator for DccpOptionsIterator<'a> {
+        type Item = Result<DccpOption<'a>, ReadError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let option_type = *self.options.first()?;
+            if option_type < 32 {
+                // single byte option
+                self.options = &self.options[1..];
+                return Some(Ok(DccpOption { option_type, data: &[] }));
+            }
+            if self.options.len() < 2 {
+                self.options = &[];
+                return Some(Err(ReadError::UnexpectedEndOfSlice(2)));
+            }
+            let len = self.options[1];
+            if len < 2 {
+                self.options = &[];
+                return Some(Err(ReadError::DccpOptionLengthTooSmall(len)));
+            }
+            let len = usize::from(len);
+            if self.options.len() < len {
+                self.options = &[];
+                return Some(Err(ReadError::UnexpectedEndOfSlice(len)));
+            }
+            let result = DccpOption {
+                option_type,
+                data: &self.options[2..len],
+            };
+            self.options = &self.options[len..];
+            Some(Ok(result))
+        }
+    }
+}
+
+use dccp::*;
+
+/// Sequence number of a DCCP packet.
+///
+/// Depending on the "Extended Sequence Numbers" (X) bit in the header
+/// the sequence & acknowledgement numbers are transmitted with 24 or 48 bits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DccpSequenceNumber {
+    /// 24 bit sequence number ("X" bit not set). Only allowed in DCCP-Data,
+    /// DCCP-Ack & DCCP-DataAck packets.
+    Short(u32),
+    /// 48 bit sequence number ("X" bit set).
+    Extended(u64),
+}
+
+impl DccpSequenceNumber {
+    /// Returns the sequence number value.
+    #[inline]
+    pub fn value(&self) -> u64 {
+        match self {
+            DccpSequenceNumber::Short(value) => u64::from(*value),
+            DccpSequenceNumber::Extended(value) => *value,
+        }
+    }
+
+    /// Returns true if the sequence number is an extended (48 bit) sequence number.
+    #[inline]
+    pub fn is_extended(&self) -> bool {
+        matches!(self, DccpSequenceNumber::Extended(_))
+    }
+}
+
+/// Packet type specific part of a DCCP header.
+///
+/// Acknowledgement numbers use the same size (24 or 48 bits) as the
+/// sequence number of the header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DccpPacketType {
+    /// DCCP-Request packet.
+    Request{ service_code: u32 },
+    /// DCCP-Response packet.
+    Response{ acknowledgement_number: u64, service_code: u32 },
+    /// DCCP-Data packet.
+    Data,
+    /// DCCP-Ack packet.
+    Ack{ acknowledgement_number: u64 },
+    /// DCCP-DataAck packet.
+    DataAck{ acknowledgement_number: u64 },
+    /// DCCP-CloseReq packet.
+    CloseReq{ acknowledgement_number: u64 },
+    /// DCCP-Close packet.
+    Close{ acknowledgement_number: u64 },
+    /// DCCP-Reset packet.
+    Reset{ acknowledgement_number: u64, reset_code: u8, data: [u8;3] },
+    /// DCCP-Sync packet.
+    Sync{ acknowledgement_number: u64 },
+    /// DCCP-SyncAck packet.
+    SyncAck{ acknowledgement_number: u64 },
+    /// Packet with a reserved type value (10 to 15). Reserved packet types
+    /// have no type specific part.
+    Reserved(u8),
+}
+
+impl DccpPacketType {
+    /// Returns the value of the "Type" field.
+    pub fn type_value(&self) -> u8 {
+        use DccpPacketType::*;
+        match self {
+            Request{ .. } => TYPE_REQUEST,
+            Response{ .. } => TYPE_RESPONSE,
+            Data => TYPE_DATA,
+            Ack{ .. } => TYPE_ACK,
+            DataAck{ .. } => TYPE_DATA_ACK,
+            CloseReq{ .. } => TYPE_CLOSE_REQ,
+            Close{ .. } => TYPE_CLOSE,
+            Reset{ .. } => TYPE_RESET,
+            Sync{ .. } => TYPE_SYNC,
+            SyncAck{ .. } => TYPE_SYNC_ACK,
+            Reserved(value) => *value,
+        }
+    }
+
+    /// Returns the acknowledgement number if the packet type contains one.
+    pub fn acknowledgement_number(&self) -> Option<u64> {
+        use DccpPacketType::*;
+        match self {
+            Request{ .. } | Data | Reserved(_) => None,
+            Response{ acknowledgement_number, .. } |
+            Ack{ acknowledgement_number } |
+            DataAck{ acknowledgement_number } |
+            CloseReq{ acknowledgement_number } |
+            Close{ acknowledgement_number } |
+            Reset{ acknowledgement_number, .. } |
+            Sync{ acknowledgement_number } |
+            SyncAck{ acknowledgement_number } => Some(*acknowledgement_number),
+        }
+    }
+
+    /// Length of the packet type specific part for the given type
+    /// value (after the generic header and before the options).
+    fn type_specific_len(type_value: u8, extended: bool) -> usize {
+        let ack_len = if extended { 8 } else { 4 };
+        match type_value {
+            TYPE_REQUEST => 4,
+            TYPE_RESPONSE | TYPE_RESET => ack_len + 4,
+            TYPE_DATA => 0,
+            TYPE_ACK | TYPE_DATA_ACK | TYPE_CLOSE_REQ | TYPE_CLOSE | TYPE_SYNC | TYPE_SYNC_ACK => ack_len,
+            _ => 0,
+        }
+    }
+}
+
+/// DCCP header according to [RFC 4340](https://datatracker.ietf.org/doc/html/rfc4340)
+/// (including the packet type specific fields & the options).
+#[derive(Clone)]
+pub struct DccpHeader {
+    /// Source port of the packet.
+    pub source_port: u16,
+    /// Destination port of the packet.
+    pub destination_port: u16,
+    /// Value used by the congestion control mechanism (4 bits).
+    pub ccval: u8,
+    /// Checksum coverage (4 bits). 0 indicates that the checksum covers the
+    /// header & all application data. Other values indicate that the
+    /// checksum covers the header and `(checksum_coverage - 1)*4` bytes of
+    /// application data.
+    pub checksum_coverage: u8,
+    /// Checksum over a pseudo header, the DCCP header and the covered
+    /// application data.
+    pub checksum: u16,
+    /// Sequence number of the packet (also defines the "X" bit).
+    pub sequence_number: DccpSequenceNumber,
+    /// Packet type & the type specific fields.
+    pub packet_type: DccpPacketType,
+    /// Length of the options in bytes.
+    options_len: usize,
+    /// Buffer containing the options (only the first options_len bytes are used).
+    options_buffer: [u8;DccpHeader::MAX_OPTIONS_LEN],
+}
+
+impl Debug for DccpHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), core::fmt::Error> {
+        write!(formatter, "DccpHeader {{ source_port: {}, destination_port: {}, ccval: {}, checksum_coverage: {}, checksum: {}, sequence_number: {:?}, packet_type: {:?}, options: {:?} }}",
+            self.source_port,
+            self.destination_port,
+            self.ccval,
+            self.checksum_coverage,
+            self.checksum,
+            self.sequence_number,
+            self.packet_type,
+            self.options())
+    }
+}
+
+impl PartialEq for DccpHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.source_port == other.source_port &&
+        self.destination_port == other.destination_port &&
+        self.ccval == other.ccval &&
+        self.checksum_coverage == other.checksum_coverage &&
+        self.checksum == other.checksum &&
+        self.sequence_number == other.sequence_number &&
+        self.packet_type == other.packet_type &&
+        self.options() == other.options()
+    }
+}
+
+impl Eq for DccpHeader {}
+
+impl DccpHeader {
+
+    /// Minimum length of a DCCP header (generic header with a short sequence number).
+    pub const MIN_LEN: usize = 12;
+
+    /// Maximum length of a DCCP header (data offset of 255 words).
+    pub const MAX_LEN: usize = 0xff*4;
+
+    /// Maximum length of the options (maximum header length minus the minimum header length).
+    pub const MAX_OPTIONS_LEN: usize = DccpHeader::MAX_LEN - DccpHeader::MIN_LEN;
+
+    /// Creates a DCCP header without options and a zero checksum.
+    pub fn new(source_port: u16, destination_port: u16, sequence_number: DccpSequenceNumber, packet_type: DccpPacketType) -> DccpHeader {
+        DccpHeader {
+            source_port,
+            destination_port,
+            ccval: 0,
+            checksum_coverage: 0,
+            checksum: 0,
+            sequence_number,
+            packet_type,
+            options_len: 0,
+            options_buffer: [0;DccpHeader::MAX_OPTIONS_LEN],
+        }
+    }
+
+    /// Returns the length of the header excluding the options.
+    pub fn fixed_header_len(&self) -> usize {
+        let extended = self.sequence_number.is_extended();
+        (if extended { 16 } else { 12 }) +
+        DccpPacketType::type_specific_len(self.packet_type.type_value(), extended)
+    }
+
+    /// Returns the length of the header including the options.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        self.fixed_header_len() + self.options_len
+    }
+
+    /// Returns a slice containing the options of the header.
+    #[inline]
+    pub fn options(&self) -> &[u8] {
+        &self.options_buffer[..self.options_len]
+    }
+
+    /// Returns an iterator over the options of the header.
+    #[inline]
+    pub fn options_iterator(&self) -> DccpOptionsIterator<'_> {
+        DccpOptionsIterator::from_slice(self.options())
+    }
+
+    /// Sets the options (including padding) of the header.
+    ///
+    /// The length of the options has to be a multiple of 4 and the resulting
+    /// header length has to be representable in the "data offset" field
+    /// (maximum of 1020 bytes), otherwise an error is returned.
+    pub fn set_options_raw(&mut self, options: &[u8]) -> Result<(), ValueError> {
+        if !options.len().is_multiple_of(4) || self.fixed_header_len() + options.len() > DccpHeader::MAX_LEN {
+            return Err(ValueError::DccpOptionsLengthBad(options.len()));
+        }
+        self.options_buffer[..options.len()].copy_from_slice(options);
+        self.options_len = options.len();
+        Ok(())
+    }
+
+    /// Checks if the values in this header are valid values for a DCCP header.
+    ///
+    /// Specifically it will be checked, that:
+    /// * ccval & checksum_coverage are not greater then 0xf
+    /// * the sequence & acknowledgement numbers fit in 24 or 48 bits
+    /// * the header length can be represented in the data offset field
+    pub fn check_ranges(&self) -> Result<(), ValueError> {
+        max_check_u8(self.ccval, 0xf, ErrorField::DccpCcVal)?;
+        max_check_u8(self.checksum_coverage, 0xf, ErrorField::DccpChecksumCoverage)?;
+
+        let max_number = match self.sequence_number {
+            DccpSequenceNumber::Short(value) => {
+                if u64::from(value) > MAX_SHORT_SEQUENCE_NUMBER {
+                    return Err(ValueError::U32TooLarge{
+                        value,
+                        max: MAX_SHORT_SEQUENCE_NUMBER as u32,
+                        field: ErrorField::DccpSequenceNumber,
+                    });
+                }
+                MAX_SHORT_SEQUENCE_NUMBER
+            },
+            DccpSequenceNumber::Extended(value) => {
+                max_check_u64(value, MAX_EXTENDED_SEQUENCE_NUMBER, ErrorField::DccpSequenceNumber)?;
+                MAX_EXTENDED_SEQUENCE_NUMBER
+            }
+        };
+        if let Some(ack) = self.packet_type.acknowledgement_number() {
+            max_check_u64(ack, max_number, ErrorField::DccpAcknowledgementNumber)?;
+        }
+        if self.header_len() > DccpHeader::MAX_LEN {
+            return Err(ValueError::DccpOptionsLengthBad(self.options_len));
+        }
+        Ok(())
+    }
+
+    /// Calculates the checksum of the DCCP header based on an ipv4 header and the payload.
+    pub fn calc_checksum_ipv4(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the checksum of the DCCP header based on an ipv4 source & destination address and the payload.
+    pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
+        self.check_ranges()?;
+        let length = self.header_len() + payload.len();
+        if length > usize::from(u16::MAX) {
+            return Err(ValueError::DccpLengthTooLarge(length));
+        }
+        self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_4bytes(source)
+            .add_4bytes(destination)
+            .add_2bytes([0, ip_number::DCCP])
+            .add_2bytes((length as u16).to_be_bytes()),
+            payload
+        )
+    }
+
+    /// Calculates the checksum of the DCCP header based on an ipv6 header and the payload.
+    pub fn calc_checksum_ipv6(&self, ip_header: &Ipv6Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the checksum of the DCCP header based on an ipv6 source & destination address and the payload.
+    pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
+        self.check_ranges()?;
+        let length = self.header_len() + payload.len();
+        if length > u32::MAX as usize {
+            return Err(ValueError::DccpLengthTooLarge(length));
+        }
+        self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_16bytes(source)
+            .add_16bytes(destination)
+            .add_4bytes((length as u32).to_be_bytes())
+            .add_4bytes([0, 0, 0, ip_number::DCCP]),
+            payload
+        )
+    }
+
+    /// Adds the header & the covered application data to the pseudo header sum
+    /// and returns the resulting checksum.
+    fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, payload: &[u8]) -> Result<u16, ValueError> {
+        let covered = if 0 == self.checksum_coverage {
+            payload
+        } else {
+            let len = usize::from(self.checksum_coverage - 1)*4;
+            if len > payload.len() {
+                return Err(ValueError::DccpChecksumCoverageBad(self.checksum_coverage));
+            }
+            &payload[..len]
+        };
+        let (header, header_len) = self.to_bytes_with_checksum(0);
+        Ok(
+            ip_pseudo_header_sum
+            .add_slice(&header[..header_len])
+            .add_slice(covered)
+            .ones_complement()
+            .to_be()
+        )
+    }
+
+    /// Reads a DCCP header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(DccpHeader, &[u8]), ReadError> {
+        let header = DccpHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Tries to read a DCCP header from the current position.
+    #[cfg(feature = "std")]
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<DccpHeader, ReadError> {
+        let mut buffer = [0u8;DccpHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..DccpHeader::MIN_LEN])?;
+
+        // read the rest of the extended sequence number
+        let mut len = DccpHeader::MIN_LEN;
+        if 0 != buffer[8] & 1 {
+            reader.read_exact(&mut buffer[len..16])?;
+            len = 16;
+        }
+
+        // read the rest of the header (based on the data offset)
+        let data_offset_len = usize::from(buffer[4])*4;
+        if data_offset_len > len {
+            reader.read_exact(&mut buffer[len..data_offset_len])?;
+            len = data_offset_len;
+        }
+        Ok(DccpHeaderSlice::from_slice(&buffer[..len])?.to_header())
+    }
+
+    /// Write the DCCP header without recalculating the checksum.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        self.check_ranges()?;
+        let (bytes, len) = self.to_bytes_with_checksum(self.checksum);
+        writer.write_all(&bytes[..len])?;
+        Ok(())
+    }
+
+    /// Write the DCCP header to the start of the given slice without recalculating
+    /// the checksum. Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        self.check_ranges()?;
+        let (bytes, len) = self.to_bytes_with_checksum(self.checksum);
+        //length check
+        if slice.len() < len {
+            Err(WriteError::SliceTooSmall(len))
+        } else {
+            slice[..len].copy_from_slice(&bytes[..len]);
+            Ok(&mut slice[len..])
+        }
+    }
+
+    /// Serializes the header with the given checksum into a buffer and
+    /// returns the buffer together with the used length.
+    ///
+    /// Expects that the ranges have already been checked.
+    fn to_bytes_with_checksum(&self, checksum: u16) -> ([u8;DccpHeader::MAX_LEN], usize) {
+        use DccpPacketType::*;
+
+        let mut result = [0u8;DccpHeader::MAX_LEN];
+        let extended = self.sequence_number.is_extended();
+        let header_len = self.header_len();
+
+        result[..2].copy_from_slice(&self.source_port.to_be_bytes());
+        result[2..4].copy_from_slice(&self.destination_port.to_be_bytes());
+        result[4] = (header_len / 4) as u8;
+        result[5] = ((self.ccval & 0xf) << 4) | (self.checksum_coverage & 0xf);
+        result[6..8].copy_from_slice(&checksum.to_be_bytes());
+        result[8] = ((self.packet_type.type_value() & 0xf) << 1) | (if extended { 1 } else { 0 });
+
+        // sequence number
+        let seq = self.sequence_number.value().to_be_bytes();
+        let mut offset = if extended {
+            result[10..16].copy_from_slice(&seq[2..]);
+            16
+        } else {
+            result[9..12].copy_from_slice(&seq[5..]);
+            12
+        };
+
+        // acknowledgement number subheader
+        if let Some(ack) = self.packet_type.acknowledgement_number() {
+            let ack = ack.to_be_bytes();
+            if extended {
+                result[offset + 2..offset + 8].copy_from_slice(&ack[2..]);
+                offset += 8;
+            } else {
+                result[offset + 1..offset + 4].copy_from_slice(&ack[5..]);
+                offset += 4;
+            }
+        }
+
+        // type specific fields
+        match self.packet_type {
+            Request{ service_code } | Response{ service_code, .. } => {
+                result[offset..offset + 4].copy_from_slice(&service_code.to_be_bytes());
+                offset += 4;
+            },
+            Reset{ reset_code, data, .. } => {
+                result[offset] = reset_code;
+                result[offset + 1..offset + 4].copy_from_slice(&data);
+                offset += 4;
+            },
+            _ => {},
+        }
+
+        // options
+        result[offset..offset + self.options_len].copy_from_slice(self.options());
+        (result, header_len)
+    }
+}
+
+///A slice containing a DCCP header of a network package (including the options).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DccpHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> DccpHeaderSlice<'a> {
+
+    /// Creates a slice containing a DCCP header.
+    pub fn from_slice(slice: &'a[u8]) -> Result<DccpHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        //check length of the generic header
+        if slice.len() < DccpHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(DccpHeader::MIN_LEN));
+        }
+        let extended = 0 != slice[8] & 1;
+        let generic_len = if extended { 16 } else { 12 };
+        if slice.len() < generic_len {
+            return Err(UnexpectedEndOfSlice(generic_len));
+        }
+
+        //check the data offset
+        let data_offset = slice[4];
+        let header_len = usize::from(data_offset)*4;
+        let fixed_len = generic_len + DccpPacketType::type_specific_len((slice[8] >> 1) & 0xf, extended);
+        if header_len < fixed_len {
+            return Err(DccpDataOffsetTooSmall(data_offset));
+        }
+        if slice.len() < header_len {
+            return Err(UnexpectedEndOfSlice(header_len));
+        }
+
+        //done
+        Ok(DccpHeaderSlice{
+            // SAFETY:
+            // Safe as slice length is checked to be at least
+            // header_len before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    header_len
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the DCCP header (including the options).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads the "source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Reads the "destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Reads the "data offset" (header length in 32 bit words) from the slice.
+    #[inline]
+    pub fn data_offset(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            *self.slice.get_unchecked(4)
+        }
+    }
+
+    /// Reads the "CCVal" field from the slice.
+    #[inline]
+    pub fn ccval(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            *self.slice.get_unchecked(5) >> 4
+        }
+    }
+
+    /// Reads the "checksum coverage" (CsCov) field from the slice.
+    #[inline]
+    pub fn checksum_coverage(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            *self.slice.get_unchecked(5) & 0xf
+        }
+    }
+
+    /// Reads the "checksum" from the slice.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(6))
+        }
+    }
+
+    /// Reads the "type" field from the slice.
+    #[inline]
+    pub fn packet_type_value(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            (*self.slice.get_unchecked(8) >> 1) & 0xf
+        }
+    }
+
+    /// Returns true if the "Extended Sequence Numbers" (X) bit is set.
+    #[inline]
+    pub fn extended_sequence_numbers(&self) -> bool {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of DccpHeader::MIN_LEN (12).
+        unsafe {
+            0 != *self.slice.get_unchecked(8) & 1
+        }
+    }
+
+    /// Decodes the sequence number.
+    pub fn sequence_number(&self) -> DccpSequenceNumber {
+        let s = self.slice;
+        if self.extended_sequence_numbers() {
+            DccpSequenceNumber::Extended(
+                u64::from_be_bytes([0, 0, s[10], s[11], s[12], s[13], s[14], s[15]])
+            )
+        } else {
+            DccpSequenceNumber::Short(
+                u32::from_be_bytes([0, s[9], s[10], s[11]])
+            )
+        }
+    }
+
+    /// Decodes the packet type & the type specific fields.
+    pub fn packet_type(&self) -> DccpPacketType {
+        use DccpPacketType::*;
+
+        let s = self.slice;
+        let extended = self.extended_sequence_numbers();
+        let start = if extended { 16 } else { 12 };
+
+        // acknowledgement number (if present in the packet type)
+        let ack = || -> u64 {
+            if extended {
+                u64::from_be_bytes([0, 0, s[start + 2], s[start + 3], s[start + 4], s[start + 5], s[start + 6], s[start + 7]])
+            } else {
+                u64::from_be_bytes([0, 0, 0, 0, 0, s[start + 1], s[start + 2], s[start + 3]])
+            }
+        };
+        let after_ack = start + if extended { 8 } else { 4 };
+
+        // the constructor checked that the data offset is big enough
+        // for the type specific fields
+        match self.packet_type_value() {
+            TYPE_REQUEST => Request{
+                service_code: u32::from_be_bytes([s[start], s[start + 1], s[start + 2], s[start + 3]]),
+            },
+            TYPE_RESPONSE => Response{
+                acknowledgement_number: ack(),
+                service_code: u32::from_be_bytes([s[after_ack], s[after_ack + 1], s[after_ack + 2], s[after_ack + 3]]),
+            },
+            TYPE_DATA => Data,
+            TYPE_ACK => Ack{ acknowledgement_number: ack() },
+            TYPE_DATA_ACK => DataAck{ acknowledgement_number: ack() },
+            TYPE_CLOSE_REQ => CloseReq{ acknowledgement_number: ack() },
+            TYPE_CLOSE => Close{ acknowledgement_number: ack() },
+            TYPE_RESET => Reset{
+                acknowledgement_number: ack(),
+                reset_code: s[after_ack],
+                data: [s[after_ack + 1], s[after_ack + 2], s[after_ack + 3]],
+            },
+            TYPE_SYNC => Sync{ acknowledgement_number: ack() },
+            TYPE_SYNC_ACK => SyncAck{ acknowledgement_number: ack() },
+            value => Reserved(value),
+        }
+    }
+
+    /// Returns a slice containing the options of the header.
+    pub fn options(&self) -> &'a [u8] {
+        let extended = self.extended_sequence_numbers();
+        let fixed_len = (if extended { 16 } else { 12 }) +
+            DccpPacketType::type_specific_len(self.packet_type_value(), extended);
+        &self.slice[fixed_len..]
+    }
+
+    /// Returns an iterator over the options of the header.
+    #[inline]
+    pub fn options_iterator(&self) -> DccpOptionsIterator<'a> {
+        DccpOptionsIterator::from_slice(self.options())
+    }
+
+    /// Decode all the fields and copy the results to a DccpHeader struct.
+    pub fn to_header(&self) -> DccpHeader {
+        let options = self.options();
+        let mut result = DccpHeader::new(
+            self.source_port(),
+            self.destination_port(),
+            self.sequence_number(),
+            self.packet_type()
+        );
+        result.ccval = self.ccval();
+        result.checksum_coverage = self.checksum_coverage();
+        result.checksum = self.checksum();
+        result.options_buffer[..options.len()].copy_from_slice(options);
+        result.options_len = options.len();
+        result
+    }
+}
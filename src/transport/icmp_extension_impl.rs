@@ -0,0 +1,590 @@
+use super::super::*;
+
+use core::slice::from_raw_parts;
+
+/// Module containing types and constants related to ICMP extension
+/// structures (multi-part messages as defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884)).
+pub mod icmp_extension {
+    use crate::*;
+    use core::slice::from_raw_parts;
+
+    /// Version of the ICMP extension structure defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884).
+    pub const VERSION: u8 = 2;
+
+    /// Minimum length in bytes/octets of the "original datagram" field if an
+    /// extension structure is present (defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884#section-5.1)).
+    pub const MIN_ORIGINAL_DATAGRAM_LEN: usize = 128;
+
+    /// Extension object class number of a "MPLS Label Stack Class" object
+    /// (defined in [RFC 4950](https://datatracker.ietf.org/doc/html/rfc4950)).
+    pub const CLASS_NUM_MPLS_LABEL_STACK: u8 = 1;
+
+    /// Extension object class number of an "Interface Information Object"
+    /// (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837)).
+    pub const CLASS_NUM_INTERFACE_INFORMATION: u8 = 2;
+
+    /// Extension object c-type of an "Incoming MPLS Label Stack" object
+    /// (defined in [RFC 4950](https://datatracker.ietf.org/doc/html/rfc4950)).
+    pub const C_TYPE_MPLS_INCOMING_LABEL_STACK: u8 = 1;
+
+    /// Address family identifier for IPv4 addresses in an "Interface IP Address Sub-Object"
+    /// (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837#section-4.2)).
+    pub const AFI_IPV4: u16 = 1;
+
+    /// Address family identifier for IPv6 addresses in an "Interface IP Address Sub-Object"
+    /// (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837#section-4.2)).
+    pub const AFI_IPV6: u16 = 2;
+
+    /// Maximum length in bytes/octets of an "Interface Name Sub-Object"
+    /// (including the length octet, defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837#section-4.3)).
+    pub const MAX_INTERFACE_NAME_SUB_OBJECT_LEN: usize = 64;
+
+    /// Entry of a MPLS label stack contained in an "MPLS Label Stack Class"
+    /// extension object (defined in [RFC 4950](https://datatracker.ietf.org/doc/html/rfc4950)).
+    ///
+    /// ```text
+    ///  0                   1                   2                   3
+    ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |                Label                  |EXP  |S|      TTL      |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MplsLabelStackEntry {
+        /// 20 bit MPLS label.
+        pub label: u32,
+        /// 3 bit "experimental use" field (renamed to "traffic class" in RFC 5462).
+        pub traffic_class: u8,
+        /// Set if the entry is the last entry of the label stack.
+        pub bottom_of_stack: bool,
+        /// Time to live of the label stack entry.
+        pub ttl: u8,
+    }
+
+    impl MplsLabelStackEntry {
+        /// Number of bytes/octets an entry takes up when serialized.
+        pub const SERIALIZED_SIZE: usize = 4;
+
+        /// Maximum value of the `label` field.
+        pub const MAX_LABEL: u32 = 0xf_ffff;
+
+        /// Maximum value of the `traffic_class` field.
+        pub const MAX_TRAFFIC_CLASS: u8 = 0b111;
+
+        /// Decodes an entry from its on the wire format.
+        #[inline]
+        pub fn from_bytes(bytes: [u8; 4]) -> MplsLabelStackEntry {
+            MplsLabelStackEntry {
+                label: (u32::from(bytes[0]) << 12)
+                    | (u32::from(bytes[1]) << 4)
+                    | u32::from(bytes[2] >> 4),
+                traffic_class: (bytes[2] >> 1) & 0b111,
+                bottom_of_stack: 0 != bytes[2] & 1,
+                ttl: bytes[3],
+            }
+        }
+
+        /// Returns the on the wire format of the entry (bits of `label` and
+        /// `traffic_class` outside of their range are ignored).
+        #[inline]
+        pub fn to_bytes(&self) -> [u8; 4] {
+            [
+                (self.label >> 12) as u8,
+                (self.label >> 4) as u8,
+                ((self.label << 4) as u8)
+                    | ((self.traffic_class & 0b111) << 1)
+                    | u8::from(self.bottom_of_stack),
+                self.ttl,
+            ]
+        }
+    }
+
+    /// Iterator over the entries of an "MPLS Label Stack Class" extension object.
+    ///
+    /// Trailing bytes that are not enough to form a complete entry are ignored.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct MplsLabelStackIterator<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> MplsLabelStackIterator<'a> {
+        /// Creates an iterator over the label stack entries contained in the given slice.
+        pub fn from_slice(slice: &'a [u8]) -> MplsLabelStackIterator<'a> {
+            MplsLabelStackIterator { slice }
+        }
+    }
+
+    impl<'a> Iterator for MplsLabelStackIterator<'a> {
+        type Item = MplsLabelStackEntry;
+
+        fn next(&mut self) -> Option<MplsLabelStackEntry> {
+            if self.slice.len() < MplsLabelStackEntry::SERIALIZED_SIZE {
+                None
+            } else {
+                // SAFETY:
+                // Safe as the slice length is checked to be at least
+                // MplsLabelStackEntry::SERIALIZED_SIZE (4).
+                let result = unsafe {
+                    MplsLabelStackEntry::from_bytes(get_unchecked_4_byte_array(self.slice.as_ptr()))
+                };
+                // SAFETY:
+                // Safe as the slice length is checked to be at least
+                // MplsLabelStackEntry::SERIALIZED_SIZE (4).
+                self.slice = unsafe {
+                    from_raw_parts(
+                        self.slice.as_ptr().add(MplsLabelStackEntry::SERIALIZED_SIZE),
+                        self.slice.len() - MplsLabelStackEntry::SERIALIZED_SIZE,
+                    )
+                };
+                Some(result)
+            }
+        }
+    }
+
+    /// Role of the interface described by an "Interface Information Object"
+    /// (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837#section-4.1)).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum InterfaceRole {
+        /// The interface on which the IP datagram was received.
+        IncomingIpInterface = 0,
+        /// Sub-IP component of the interface on which the IP datagram was received.
+        SubIpComponentOfIncomingIpInterface = 1,
+        /// The outgoing interface the IP datagram would have been forwarded to.
+        OutgoingIpInterface = 2,
+        /// The next hop the IP datagram would have been forwarded to.
+        IpNextHop = 3,
+    }
+
+    impl InterfaceRole {
+        /// Decodes the role from the two most significant bits of the c-type.
+        #[inline]
+        pub fn from_c_type(c_type: u8) -> InterfaceRole {
+            use InterfaceRole::*;
+            match c_type >> 6 {
+                0 => IncomingIpInterface,
+                1 => SubIpComponentOfIncomingIpInterface,
+                2 => OutgoingIpInterface,
+                _ => IpNextHop,
+            }
+        }
+
+        /// Returns the 2 bit value of the role.
+        #[inline]
+        pub fn value(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    /// IP address contained in an "Interface IP Address Sub-Object".
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum InterfaceIpAddress {
+        Ipv4([u8; 4]),
+        Ipv6([u8; 16]),
+    }
+
+    /// Decoded "Interface Information Object" (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837)).
+    ///
+    /// Which of the optional fields are present is determined by the
+    /// bits in the c-type of the extension object:
+    ///
+    /// ```text
+    /// Bit     0       1       2       3       4       5       6       7
+    ///     +-------+-------+-------+-------+-------+-------+-------+-------+
+    ///     | Interface Role| Rsvd1 | Rsvd2 |ifIndex| IPAddr|  name |  MTU  |
+    ///     +-------+-------+-------+-------+-------+-------+-------+-------+
+    /// ```
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct InterfaceInformation<'a> {
+        /// Role of the interface.
+        pub role: InterfaceRole,
+        /// The 32 bit "ifIndex" of the interface.
+        pub if_index: Option<u32>,
+        /// IP address of the interface.
+        pub ip_address: Option<InterfaceIpAddress>,
+        /// Name of the interface (UTF-8 encoded) with the trailing zero
+        /// padding removed.
+        pub name: Option<&'a [u8]>,
+        /// MTU of the interface.
+        pub mtu: Option<u32>,
+    }
+
+    impl<'a> InterfaceInformation<'a> {
+        /// Bit in the c-type indicating that the "ifIndex" is present.
+        pub const C_TYPE_IF_INDEX_BIT: u8 = 0b1000;
+        /// Bit in the c-type indicating that the "IP Address Sub-Object" is present.
+        pub const C_TYPE_IP_ADDRESS_BIT: u8 = 0b100;
+        /// Bit in the c-type indicating that the "Interface Name Sub-Object" is present.
+        pub const C_TYPE_NAME_BIT: u8 = 0b10;
+        /// Bit in the c-type indicating that the MTU is present.
+        pub const C_TYPE_MTU_BIT: u8 = 0b1;
+
+        /// Decodes the payload of an "Interface Information Object".
+        ///
+        /// `None` is returned if the payload does not match the fields
+        /// indicated by the c-type (e.g. a length mismatch or an unknown
+        /// address family).
+        pub fn from_slice(c_type: u8, payload: &'a [u8]) -> Option<InterfaceInformation<'a>> {
+            let mut rest = payload;
+
+            let if_index = if 0 != c_type & Self::C_TYPE_IF_INDEX_BIT {
+                if rest.len() < 4 {
+                    return None;
+                }
+                let (value, r) = rest.split_at(4);
+                rest = r;
+                Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+            } else {
+                None
+            };
+
+            let ip_address = if 0 != c_type & Self::C_TYPE_IP_ADDRESS_BIT {
+                if rest.len() < 4 {
+                    return None;
+                }
+                match u16::from_be_bytes([rest[0], rest[1]]) {
+                    AFI_IPV4 if rest.len() >= 8 => {
+                        let (value, r) = rest.split_at(8);
+                        rest = r;
+                        Some(InterfaceIpAddress::Ipv4([value[4], value[5], value[6], value[7]]))
+                    }
+                    AFI_IPV6 if rest.len() >= 20 => {
+                        let (value, r) = rest.split_at(20);
+                        rest = r;
+                        let mut address = [0u8; 16];
+                        address.copy_from_slice(&value[4..]);
+                        Some(InterfaceIpAddress::Ipv6(address))
+                    }
+                    _ => return None,
+                }
+            } else {
+                None
+            };
+
+            let name = if 0 != c_type & Self::C_TYPE_NAME_BIT {
+                let len = usize::from(*rest.first()?);
+                if 0 == len || 0 != len % 4 || len > MAX_INTERFACE_NAME_SUB_OBJECT_LEN || len > rest.len() {
+                    return None;
+                }
+                let (value, r) = rest.split_at(len);
+                rest = r;
+                let mut name = &value[1..];
+                while let Some((0, start)) = name.split_last() {
+                    name = start;
+                }
+                Some(name)
+            } else {
+                None
+            };
+
+            let mtu = if 0 != c_type & Self::C_TYPE_MTU_BIT {
+                if rest.len() < 4 {
+                    return None;
+                }
+                let (value, r) = rest.split_at(4);
+                rest = r;
+                Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+            } else {
+                None
+            };
+
+            if rest.is_empty() {
+                Some(InterfaceInformation {
+                    role: InterfaceRole::from_c_type(c_type),
+                    if_index,
+                    ip_address,
+                    name,
+                    mtu,
+                })
+            } else {
+                None
+            }
+        }
+
+        /// Returns the c-type value matching the role and the present fields.
+        pub fn c_type(&self) -> u8 {
+            (self.role.value() << 6)
+                | if self.if_index.is_some() { Self::C_TYPE_IF_INDEX_BIT } else { 0 }
+                | if self.ip_address.is_some() { Self::C_TYPE_IP_ADDRESS_BIT } else { 0 }
+                | if self.name.is_some() { Self::C_TYPE_NAME_BIT } else { 0 }
+                | if self.mtu.is_some() { Self::C_TYPE_MTU_BIT } else { 0 }
+        }
+    }
+}
+
+use icmp_extension::*;
+
+/// Slice containing an ICMP extension structure (defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884)).
+///
+/// The extension structure is appended to the "original datagram" field of
+/// ICMP "Destination Unreachable", "Time Exceeded" & "Parameter Problem"
+/// (ICMPv4 only) messages and can be accessed via [`Icmpv4Slice::extensions`]
+/// & [`Icmpv6Slice::extensions`].
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |Version|      (Reserved)       |           Checksum            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// ...                  extension objects                        ...
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IcmpExtensionsSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IcmpExtensionsSlice<'a> {
+    /// Length in bytes/octets of the extension header.
+    pub const HEADER_LEN: usize = 4;
+
+    /// Creates a slice containing an ICMP extension structure (the given
+    /// slice is expected to contain only the extension structure).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IcmpExtensionsSlice<'a>, ReadError> {
+        if slice.len() < IcmpExtensionsSlice::HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(IcmpExtensionsSlice::HEADER_LEN));
+        }
+        // SAFETY:
+        // Safe as the slice length is checked to be at least
+        // IcmpExtensionsSlice::HEADER_LEN (4).
+        let version = unsafe { *slice.get_unchecked(0) } >> 4;
+        if VERSION != version {
+            return Err(ReadError::IcmpExtensionUnexpectedVersion(version));
+        }
+        Ok(IcmpExtensionsSlice { slice })
+    }
+
+    /// Returns the version field of the extension header (always 2).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionsSlice::HEADER_LEN (4).
+        unsafe { *self.slice.get_unchecked(0) >> 4 }
+    }
+
+    /// Returns the checksum field of the extension header.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionsSlice::HEADER_LEN (4).
+        unsafe { get_unchecked_be_u16(self.slice.as_ptr().add(2)) }
+    }
+
+    /// Returns if the checksum over the extension structure is correct.
+    pub fn is_checksum_valid(&self) -> bool {
+        checksum::Sum16BitWords::new()
+            .add_slice(self.slice)
+            .ones_complement()
+            == 0
+    }
+
+    /// Returns an iterator over the extension objects.
+    #[inline]
+    pub fn objects(&self) -> IcmpExtensionObjectIterator<'a> {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionsSlice::HEADER_LEN (4).
+        IcmpExtensionObjectIterator {
+            slice: unsafe {
+                from_raw_parts(
+                    self.slice.as_ptr().add(IcmpExtensionsSlice::HEADER_LEN),
+                    self.slice.len() - IcmpExtensionsSlice::HEADER_LEN,
+                )
+            },
+        }
+    }
+
+    /// Returns the slice containing the extension structure.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+}
+
+/// Iterator over the objects of an ICMP extension structure.
+///
+/// After an error was returned the iteration ends.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IcmpExtensionObjectIterator<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IcmpExtensionObjectIterator<'a> {
+    /// Returns the not yet iterated part of the extension structure.
+    #[inline]
+    pub fn rest(&self) -> &'a [u8] {
+        self.slice
+    }
+}
+
+impl<'a> Iterator for IcmpExtensionObjectIterator<'a> {
+    type Item = Result<IcmpExtensionObjectSlice<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.slice.is_empty() {
+            return None;
+        }
+        match IcmpExtensionObjectSlice::from_slice(self.slice) {
+            Ok(object) => {
+                self.slice = &self.slice[object.slice().len()..];
+                Some(Ok(object))
+            }
+            Err(err) => {
+                self.slice = &[];
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Slice containing an ICMP extension object (defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884#section-7)).
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             Length            |   Class-Num   |   C-Type      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                                                               |
+/// |                   // (Object payload) //                      |
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IcmpExtensionObjectSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IcmpExtensionObjectSlice<'a> {
+    /// Length in bytes/octets of the extension object header.
+    pub const HEADER_LEN: usize = 4;
+
+    /// Creates a slice containing the extension object at the start of the
+    /// given slice (the slice is cut to the length of the object).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IcmpExtensionObjectSlice<'a>, ReadError> {
+        if slice.len() < IcmpExtensionObjectSlice::HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(IcmpExtensionObjectSlice::HEADER_LEN));
+        }
+        // SAFETY:
+        // Safe as the slice length is checked to be at least
+        // IcmpExtensionObjectSlice::HEADER_LEN (4).
+        let length = unsafe { get_unchecked_be_u16(slice.as_ptr()) };
+        if usize::from(length) < IcmpExtensionObjectSlice::HEADER_LEN {
+            return Err(ReadError::IcmpExtensionObjectLengthTooSmall(length));
+        }
+        if slice.len() < usize::from(length) {
+            return Err(ReadError::UnexpectedEndOfSlice(usize::from(length)));
+        }
+        Ok(IcmpExtensionObjectSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least length.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), usize::from(length)) },
+        })
+    }
+
+    /// Returns the length field of the object (including the object header).
+    #[inline]
+    pub fn length(&self) -> u16 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionObjectSlice::HEADER_LEN (4).
+        unsafe { get_unchecked_be_u16(self.slice.as_ptr()) }
+    }
+
+    /// Returns the "Class-Num" field of the object.
+    #[inline]
+    pub fn class_num(&self) -> u8 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionObjectSlice::HEADER_LEN (4).
+        unsafe { *self.slice.get_unchecked(2) }
+    }
+
+    /// Returns the "C-Type" field of the object.
+    #[inline]
+    pub fn c_type(&self) -> u8 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionObjectSlice::HEADER_LEN (4).
+        unsafe { *self.slice.get_unchecked(3) }
+    }
+
+    /// Returns the payload of the object (the bytes after the object header).
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of IcmpExtensionObjectSlice::HEADER_LEN (4).
+        unsafe {
+            from_raw_parts(
+                self.slice.as_ptr().add(IcmpExtensionObjectSlice::HEADER_LEN),
+                self.slice.len() - IcmpExtensionObjectSlice::HEADER_LEN,
+            )
+        }
+    }
+
+    /// Decodes the object based on the class-num & c-type.
+    ///
+    /// Objects with an unknown class-num/c-type combination or with a
+    /// payload that can not be decoded are returned as
+    /// [`IcmpExtensionObject::Unknown`].
+    pub fn object(&self) -> IcmpExtensionObject<'a> {
+        match (self.class_num(), self.c_type()) {
+            (CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK)
+                if self.payload().len().is_multiple_of(MplsLabelStackEntry::SERIALIZED_SIZE) =>
+            {
+                return IcmpExtensionObject::MplsLabelStack(
+                    MplsLabelStackIterator::from_slice(self.payload())
+                );
+            }
+            (CLASS_NUM_INTERFACE_INFORMATION, c_type) => {
+                if let Some(value) = InterfaceInformation::from_slice(c_type, self.payload()) {
+                    return IcmpExtensionObject::InterfaceInformation(value);
+                }
+            }
+            _ => {}
+        }
+        IcmpExtensionObject::Unknown {
+            class_num: self.class_num(),
+            c_type: self.c_type(),
+            payload: self.payload(),
+        }
+    }
+
+    /// Returns the slice containing the extension object.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+}
+
+/// Decoded ICMP extension object.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IcmpExtensionObject<'a> {
+    /// "Incoming MPLS Label Stack" object (defined in [RFC 4950](https://datatracker.ietf.org/doc/html/rfc4950)).
+    MplsLabelStack(MplsLabelStackIterator<'a>),
+    /// "Interface Information Object" (defined in [RFC 5837](https://datatracker.ietf.org/doc/html/rfc5837)).
+    InterfaceInformation(InterfaceInformation<'a>),
+    /// Object with an unknown class-num/c-type combination or a payload
+    /// that could not be decoded.
+    Unknown {
+        class_num: u8,
+        c_type: u8,
+        payload: &'a [u8],
+    },
+}
+
+/// Splits an ICMP error message payload into the "original datagram" and the
+/// extension structure based on the RFC 4884 length attribute (in bytes).
+pub(crate) fn split_icmp_extensions(payload: &[u8], original_datagram_len: usize) -> (&[u8], Option<&[u8]>) {
+    if 0 == original_datagram_len || payload.len() <= original_datagram_len {
+        (payload, None)
+    } else {
+        let (original, extensions) = payload.split_at(original_datagram_len);
+        (original, Some(extensions))
+    }
+}
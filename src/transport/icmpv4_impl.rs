@@ -1,7 +1,7 @@
 use super::super::*;
 
 use arrayvec::ArrayVec;
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 /// Module containing ICMPv4 related types and constants
 pub mod icmpv4 {
@@ -775,6 +775,7 @@ impl Icmpv4Header {
     }
 
     /// Reads an ICMPv4 header from the given reader.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<Icmpv4Header, ReadError> {
         let mut bytes = [0u8;Icmpv4Header::MAX_SERIALIZED_SIZE];
 
@@ -804,10 +805,24 @@ impl Icmpv4Header {
     }
 
     /// Write the ICMPv4 header to the given writer.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         writer.write_all(&self.to_bytes()).map_err(WriteError::from)
     }
 
+    /// Write the ICMPv4 header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        let bytes = self.to_bytes();
+        //length check
+        if slice.len() < bytes.len() {
+            Err(WriteError::SliceTooSmall(bytes.len()))
+        } else {
+            slice[..bytes.len()].copy_from_slice(&bytes);
+            Ok(&mut slice[bytes.len()..])
+        }
+    }
+
     /// Length in bytes/octets of this header type.
     #[inline]
     pub fn header_len(&self) -> usize {
@@ -989,6 +1004,15 @@ pub struct Icmpv4Slice<'a> {
 }
 
 impl<'a> Icmpv4Slice<'a> {
+
+    /// Position & size of the fields in the first 8 bytes of the ICMPv4 message
+    /// ("rest_of_header" contains the type specific values).
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "type", bit_offset: 0, bit_width: 8 },
+        HeaderField{ name: "code", bit_offset: 8, bit_width: 8 },
+        HeaderField{ name: "checksum", bit_offset: 16, bit_width: 16 },
+        HeaderField{ name: "rest_of_header", bit_offset: 32, bit_width: 32 },
+    ];
     /// Creates a slice containing an ICMPv4 packet.
     ///
     /// # Errors
@@ -1279,6 +1303,53 @@ impl<'a> Icmpv4Slice<'a> {
         unsafe { from_raw_parts(self.slice.as_ptr().add(header_len), self.slice.len() - header_len) }
     }
 
+    /// Returns the "length" attribute defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884)
+    /// (length of the "original datagram" in 32-bit words) for message types
+    /// that support ICMP extension structures.
+    ///
+    /// The attribute is located in the 6th byte/octet of the ICMP packet and
+    /// `Some` is only returned for "Destination Unreachable", "Time Exceeded"
+    /// & "Parameter Problem" messages. A value of zero indicates that no
+    /// extension structure is present.
+    #[inline]
+    pub fn length_attribute(&self) -> Option<u8> {
+        match self.type_u8() {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of Icmpv4Header::MIN_SERIALIZED_SIZE (8).
+            TYPE_DEST_UNREACH | TYPE_TIME_EXCEEDED | TYPE_PARAMETER_PROBLEM => {
+                Some(unsafe { *self.slice.get_unchecked(5) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the "original datagram" part of the payload.
+    ///
+    /// If a non zero RFC 4884 length attribute is present ([`Icmpv4Slice::length_attribute`])
+    /// the returned slice is limited to the indicated length, otherwise the
+    /// complete payload is returned.
+    #[inline]
+    pub fn original_datagram(&self) -> &'a [u8] {
+        let len = usize::from(self.length_attribute().unwrap_or(0))*4;
+        split_icmp_extensions(self.payload(), len).0
+    }
+
+    /// Returns the ICMP extension structure ([RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884))
+    /// located after the "original datagram" if one is present.
+    ///
+    /// `Ok(None)` is returned if the message type does not support extensions,
+    /// the length attribute is zero or no data is present after the
+    /// "original datagram". An error is returned if data is present but does
+    /// not start with a valid extension header.
+    pub fn extensions(&self) -> Result<Option<IcmpExtensionsSlice<'a>>, ReadError> {
+        let len = usize::from(self.length_attribute().unwrap_or(0))*4;
+        match split_icmp_extensions(self.payload(), len).1 {
+            Some(slice) => Ok(Some(IcmpExtensionsSlice::from_slice(slice)?)),
+            None => Ok(None),
+        }
+    }
+
     /// Returns the slice containing the ICMPv4 packet.
     #[inline]
     pub fn slice(&self) -> &'a [u8] {
@@ -1,7 +1,7 @@
 use super::super::*;
 
 use arrayvec::ArrayVec;
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 /// Module containing ICMPv6 related types and constants
 pub mod icmpv6 {
@@ -72,6 +72,9 @@ pub mod icmpv6 {
     /// ICMPv6 type value indicating a "Inverse Neighbor Discovery Advertisement" message.
     pub const TYPE_INVERSE_NEIGHBOR_DISCOVERY_ADVERTISEMENT: u8 = 142;
 
+    /// ICMPv6 type value indicating a "Version 2 Multicast Listener Report" message (from [RFC 3810](https://tools.ietf.org/html/rfc3810)).
+    pub const TYPE_MULTICAST_LISTENER_REPORT_V2: u8 = 143;
+
     /// ICMPv6 type value indicating a "Extended Echo Request" message.
     pub const TYPE_EXT_ECHO_REQUEST: u8 = 160;
 
@@ -321,6 +324,431 @@ pub mod icmpv6 {
         pub pointer: u32,
     }
 
+    /// Values of the "Multicast Listener Query", "Multicast Listener Report" &
+    /// "Multicast Listener Done" messages of MLDv1 (from [RFC 2710](https://tools.ietf.org/html/rfc2710)).
+    ///
+    /// The `reserved` field is not stored and is written as zero.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MulticastListenerHeader {
+        /// Maximum allowed delay (in milliseconds) before sending a responding
+        /// report. Only meaningful in queries, set to zero in reports & done messages.
+        pub maximum_response_delay: u16,
+        /// Multicast address the message refers to. Set to zero in general queries.
+        pub multicast_address: [u8; 16],
+    }
+
+    impl MulticastListenerHeader {
+        /// Number of bytes/octets of an MLDv1 message (including the ICMPv6 type,
+        /// code & checksum fields).
+        pub const SERIALIZED_SIZE: usize = 24;
+    }
+
+    /// Values of a "Version 2 Multicast Listener Query" message
+    /// (from [RFC 3810](https://tools.ietf.org/html/rfc3810)).
+    ///
+    /// The source addresses following the statically sized part are
+    /// not part of this header but of the payload (use
+    /// [`crate::Icmpv6Slice::mldv2_query_sources`] to iterate over them).
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      130      |       0       |  checksum (in Icmpv6Header)   |  |
+    /// +---------------------------------------------------------------+  |
+    /// |    maximum_response_code      |           <reserved>          |  |
+    /// +---------------------------------------------------------------+  |
+    /// |                                                               |  | part of header & type
+    /// |                       multicast_address                       |  |
+    /// |                                                               |  |
+    /// +---------------------------------------------------------------+  |
+    /// | Resv  |S| QRV |      QQIC     |      number_of_sources        |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// |                                                               |  |
+    /// ...                     <source addresses>                    ...  | part of payload
+    /// |                                                               |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MulticastListenerQueryV2Header {
+        /// Encoded maximum allowed delay before sending a responding report
+        /// (see [`MulticastListenerQueryV2Header::maximum_response_delay`] for the
+        /// decoded value in milliseconds).
+        pub maximum_response_code: u16,
+        /// Multicast address being queried. Set to zero in general queries.
+        pub multicast_address: [u8; 16],
+        /// "Suppress Router-Side Processing" flag.
+        pub suppress_router_side_processing: bool,
+        /// "Querier's Robustness Variable" (3 bit value, only the lower 3 bits are serialized).
+        pub querier_robustness_variable: u8,
+        /// "Querier's Query Interval Code" (see [`MulticastListenerQueryV2Header::querier_query_interval`]
+        /// for the decoded value in seconds).
+        pub querier_query_interval_code: u8,
+        /// Number of source addresses present in the payload after the header.
+        pub number_of_sources: u16,
+    }
+
+    impl MulticastListenerQueryV2Header {
+        /// Number of bytes/octets of the statically sized part of an MLDv2 query
+        /// (including the ICMPv6 type, code & checksum fields).
+        pub const SERIALIZED_SIZE: usize = 28;
+
+        /// Maximum value of the 3 bit "Querier's Robustness Variable" field.
+        pub const MAX_QUERIER_ROBUSTNESS_VARIABLE: u8 = 0b111;
+
+        /// Returns the byte containing the "Suppress Router-Side Processing"
+        /// flag & "Querier's Robustness Variable" (reserved bits set to zero).
+        #[inline]
+        pub fn flags_u8(&self) -> u8 {
+            let s = if self.suppress_router_side_processing {
+                0b1000
+            } else {
+                0
+            };
+            s | (self.querier_robustness_variable & Self::MAX_QUERIER_ROBUSTNESS_VARIABLE)
+        }
+
+        /// Decodes the "Maximum Response Code" into the maximum response
+        /// delay in milliseconds (RFC 3810 section 5.1.3).
+        pub fn maximum_response_delay(&self) -> u32 {
+            let code = self.maximum_response_code;
+            if code < 0x8000 {
+                u32::from(code)
+            } else {
+                let mant = u32::from(code & 0x0fff);
+                let exp = u32::from((code >> 12) & 0b111);
+                (mant | 0x1000) << (exp + 3)
+            }
+        }
+
+        /// Decodes the "Querier's Query Interval Code" into the query
+        /// interval in seconds (RFC 3810 section 5.1.9).
+        pub fn querier_query_interval(&self) -> u32 {
+            let code = self.querier_query_interval_code;
+            if code < 0x80 {
+                u32::from(code)
+            } else {
+                let mant = u32::from(code & 0x0f);
+                let exp = u32::from((code >> 4) & 0b111);
+                (mant | 0x10) << (exp + 3)
+            }
+        }
+    }
+
+    /// MLDv2 multicast address record type "MODE_IS_INCLUDE" (current state record).
+    pub const MLDV2_RECORD_MODE_IS_INCLUDE: u8 = 1;
+
+    /// MLDv2 multicast address record type "MODE_IS_EXCLUDE" (current state record).
+    pub const MLDV2_RECORD_MODE_IS_EXCLUDE: u8 = 2;
+
+    /// MLDv2 multicast address record type "CHANGE_TO_INCLUDE_MODE" (filter mode change record).
+    pub const MLDV2_RECORD_CHANGE_TO_INCLUDE_MODE: u8 = 3;
+
+    /// MLDv2 multicast address record type "CHANGE_TO_EXCLUDE_MODE" (filter mode change record).
+    pub const MLDV2_RECORD_CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+
+    /// MLDv2 multicast address record type "ALLOW_NEW_SOURCES" (source list change record).
+    pub const MLDV2_RECORD_ALLOW_NEW_SOURCES: u8 = 5;
+
+    /// MLDv2 multicast address record type "BLOCK_OLD_SOURCES" (source list change record).
+    pub const MLDV2_RECORD_BLOCK_OLD_SOURCES: u8 = 6;
+
+    /// Type of a multicast address record in an MLDv2 report
+    /// (from [RFC 3810](https://tools.ietf.org/html/rfc3810) section 5.2.12).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum MulticastAddressRecordType {
+        /// "MODE_IS_INCLUDE" current state record.
+        ModeIsInclude = 1,
+        /// "MODE_IS_EXCLUDE" current state record.
+        ModeIsExclude = 2,
+        /// "CHANGE_TO_INCLUDE_MODE" filter mode change record.
+        ChangeToIncludeMode = 3,
+        /// "CHANGE_TO_EXCLUDE_MODE" filter mode change record.
+        ChangeToExcludeMode = 4,
+        /// "ALLOW_NEW_SOURCES" source list change record.
+        AllowNewSources = 5,
+        /// "BLOCK_OLD_SOURCES" source list change record.
+        BlockOldSources = 6,
+    }
+
+    impl MulticastAddressRecordType {
+        /// Tries to convert a record type [`u8`] value to a [`MulticastAddressRecordType`] value.
+        ///
+        /// Returns [`None`] in case the value is not a known record type.
+        pub fn from_u8(value: u8) -> Option<MulticastAddressRecordType> {
+            use MulticastAddressRecordType::*;
+            match value {
+                MLDV2_RECORD_MODE_IS_INCLUDE => Some(ModeIsInclude),
+                MLDV2_RECORD_MODE_IS_EXCLUDE => Some(ModeIsExclude),
+                MLDV2_RECORD_CHANGE_TO_INCLUDE_MODE => Some(ChangeToIncludeMode),
+                MLDV2_RECORD_CHANGE_TO_EXCLUDE_MODE => Some(ChangeToExcludeMode),
+                MLDV2_RECORD_ALLOW_NEW_SOURCES => Some(AllowNewSources),
+                MLDV2_RECORD_BLOCK_OLD_SOURCES => Some(BlockOldSources),
+                _ => None,
+            }
+        }
+
+        /// Returns the [`u8`] value of the record type.
+        #[inline]
+        pub fn type_u8(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    /// Statically sized start of a multicast address record in an MLDv2 report.
+    ///
+    /// The source addresses & auxiliary data following the header are not part
+    /// of this struct and have to be written after it.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+
+    /// |  record_type  | aux_data_len  |       number_of_sources       |
+    /// +---------------------------------------------------------------+
+    /// |                                                               |
+    /// |                       multicast_address                       |
+    /// |                                                               |
+    /// +---------------------------------------------------------------+
+    /// |                                                               |
+    /// ...       <number_of_sources * 16 bytes source addresses>     ...
+    /// |                                                               |
+    /// +---------------------------------------------------------------+
+    /// |                                                               |
+    /// ...           <aux_data_len * 4 bytes auxiliary data>         ...
+    /// |                                                               |
+    /// +---------------------------------------------------------------+
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MulticastAddressRecordHeader {
+        /// Record type (see [`MulticastAddressRecordType`] for the known values).
+        pub record_type: u8,
+        /// Length of the auxiliary data in units of 32 bit words.
+        pub aux_data_len: u8,
+        /// Number of source addresses present in the record.
+        pub number_of_sources: u16,
+        /// Multicast address the record refers to.
+        pub multicast_address: [u8; 16],
+    }
+
+    impl MulticastAddressRecordHeader {
+        /// Number of bytes/octets of the statically sized part of a record.
+        pub const SERIALIZED_SIZE: usize = 20;
+
+        /// Length of the complete record (including source addresses &
+        /// auxiliary data) in bytes/octets.
+        #[inline]
+        pub fn record_len(&self) -> usize {
+            MulticastAddressRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources) * 16
+                + usize::from(self.aux_data_len) * 4
+        }
+
+        /// Returns the serialized form of the header.
+        pub fn to_bytes(&self) -> [u8; 20] {
+            let n = self.number_of_sources.to_be_bytes();
+            let a = self.multicast_address;
+            [
+                self.record_type, self.aux_data_len, n[0], n[1],
+                a[0], a[1], a[2], a[3],
+                a[4], a[5], a[6], a[7],
+                a[8], a[9], a[10], a[11],
+                a[12], a[13], a[14], a[15],
+            ]
+        }
+
+        /// Writes the header to the given writer.
+        #[cfg(feature = "std")]
+        pub fn write<T: std::io::Write + Sized>(&self, writer: &mut T) -> Result<(), crate::WriteError> {
+            writer.write_all(&self.to_bytes())?;
+            Ok(())
+        }
+    }
+
+    /// Slice containing a complete multicast address record of an MLDv2 report
+    /// (including source addresses & auxiliary data).
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct MulticastAddressRecordSlice<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> MulticastAddressRecordSlice<'a> {
+        /// Creates a record slice from the start of the given slice.
+        ///
+        /// The resulting slice only contains the record itself (the length
+        /// is determined via the "Number of Sources" & "Aux Data Len" fields).
+        pub fn from_slice(slice: &'a [u8]) -> Result<MulticastAddressRecordSlice<'a>, crate::ReadError> {
+            use crate::ReadError::*;
+            if slice.len() < MulticastAddressRecordHeader::SERIALIZED_SIZE {
+                return Err(UnexpectedEndOfSlice(MulticastAddressRecordHeader::SERIALIZED_SIZE));
+            }
+            let record_len = MulticastAddressRecordHeader::SERIALIZED_SIZE
+                + usize::from(u16::from_be_bytes([slice[2], slice[3]])) * 16
+                + usize::from(slice[1]) * 4;
+            if slice.len() < record_len {
+                return Err(UnexpectedEndOfSlice(record_len));
+            }
+            Ok(MulticastAddressRecordSlice {
+                slice: &slice[..record_len],
+            })
+        }
+
+        /// Returns the slice containing the record.
+        #[inline]
+        pub fn slice(&self) -> &'a [u8] {
+            self.slice
+        }
+
+        /// Raw "Record Type" value.
+        #[inline]
+        pub fn record_type_u8(&self) -> u8 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of MulticastAddressRecordHeader::SERIALIZED_SIZE (20).
+            unsafe { *self.slice.get_unchecked(0) }
+        }
+
+        /// Decoded "Record Type" ([`None`] if the record type is unknown).
+        #[inline]
+        pub fn record_type(&self) -> Option<MulticastAddressRecordType> {
+            MulticastAddressRecordType::from_u8(self.record_type_u8())
+        }
+
+        /// Length of the auxiliary data in units of 32 bit words.
+        #[inline]
+        pub fn aux_data_len(&self) -> u8 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of MulticastAddressRecordHeader::SERIALIZED_SIZE (20).
+            unsafe { *self.slice.get_unchecked(1) }
+        }
+
+        /// Number of source addresses present in the record.
+        #[inline]
+        pub fn number_of_sources(&self) -> u16 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of MulticastAddressRecordHeader::SERIALIZED_SIZE (20).
+            unsafe { crate::get_unchecked_be_u16(self.slice.as_ptr().add(2)) }
+        }
+
+        /// Multicast address the record refers to.
+        #[inline]
+        pub fn multicast_address(&self) -> [u8; 16] {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of MulticastAddressRecordHeader::SERIALIZED_SIZE (20).
+            unsafe { crate::get_unchecked_16_byte_array(self.slice.as_ptr().add(4)) }
+        }
+
+        /// Iterator over the source addresses of the record.
+        #[inline]
+        pub fn sources(&self) -> SourceAddressIterator<'a> {
+            let end = MulticastAddressRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources()) * 16;
+            SourceAddressIterator::from_slice(
+                &self.slice[MulticastAddressRecordHeader::SERIALIZED_SIZE..end]
+            )
+        }
+
+        /// Auxiliary data of the record.
+        #[inline]
+        pub fn aux_data(&self) -> &'a [u8] {
+            let start = MulticastAddressRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources()) * 16;
+            &self.slice[start..]
+        }
+
+        /// Decodes the statically sized part of the record into a [`MulticastAddressRecordHeader`].
+        pub fn to_header(&self) -> MulticastAddressRecordHeader {
+            MulticastAddressRecordHeader {
+                record_type: self.record_type_u8(),
+                aux_data_len: self.aux_data_len(),
+                number_of_sources: self.number_of_sources(),
+                multicast_address: self.multicast_address(),
+            }
+        }
+    }
+
+    /// Iterator over the multicast address records of an MLDv2 report.
+    ///
+    /// In case a record can not be read (e.g. the slice ends too early)
+    /// an error is returned and the iteration ends afterwards.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct MulticastAddressRecordIterator<'a> {
+        number_of_records: u16,
+        rest: &'a [u8],
+    }
+
+    impl<'a> MulticastAddressRecordIterator<'a> {
+        /// Creates an iterator over `number_of_records` records at the
+        /// start of the given slice (usually the payload of an MLDv2 report).
+        pub fn new(number_of_records: u16, slice: &'a [u8]) -> MulticastAddressRecordIterator<'a> {
+            MulticastAddressRecordIterator {
+                number_of_records,
+                rest: slice,
+            }
+        }
+
+        /// Returns the part of the slice not yet processed.
+        #[inline]
+        pub fn rest(&self) -> &'a [u8] {
+            self.rest
+        }
+    }
+
+    impl<'a> Iterator for MulticastAddressRecordIterator<'a> {
+        type Item = Result<MulticastAddressRecordSlice<'a>, crate::ReadError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if 0 == self.number_of_records {
+                return None;
+            }
+            match MulticastAddressRecordSlice::from_slice(self.rest) {
+                Ok(record) => {
+                    self.number_of_records -= 1;
+                    self.rest = &self.rest[record.slice().len()..];
+                    Some(Ok(record))
+                },
+                Err(err) => {
+                    self.number_of_records = 0;
+                    Some(Err(err))
+                }
+            }
+        }
+    }
+
+    /// Iterator over a list of IPv6 source addresses (e.g. in an MLDv2 query
+    /// or multicast address record).
+    ///
+    /// Trailing bytes not making up a full address are ignored.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct SourceAddressIterator<'a> {
+        rest: &'a [u8],
+    }
+
+    impl<'a> SourceAddressIterator<'a> {
+        /// Creates an iterator over the 16 byte addresses in the given slice.
+        #[inline]
+        pub fn from_slice(slice: &'a [u8]) -> SourceAddressIterator<'a> {
+            SourceAddressIterator { rest: slice }
+        }
+    }
+
+    impl<'a> Iterator for SourceAddressIterator<'a> {
+        type Item = [u8; 16];
+
+        fn next(&mut self) -> Option<[u8; 16]> {
+            if self.rest.len() < 16 {
+                None
+            } else {
+                // SAFETY:
+                // Safe as the length is checked to be at least 16 above.
+                let result = unsafe { crate::get_unchecked_16_byte_array(self.rest.as_ptr()) };
+                self.rest = &self.rest[16..];
+                Some(result)
+            }
+        }
+    }
+
 } // mod icmpv6
 
 use icmpv6::*;
@@ -362,6 +790,11 @@ use icmpv6::*;
 ///             ParameterProblem(header) => println!("{:?}", header),
 ///             EchoRequest(header) => println!("{:?}", header),
 ///             EchoReply(header) => println!("{:?}", header),
+///             MulticastListenerQuery(header) => println!("{:?}", header),
+///             MulticastListenerReport(header) => println!("{:?}", header),
+///             MulticastListenerDone(header) => println!("{:?}", header),
+///             MulticastListenerQueryV2(header) => println!("{:?}", header),
+///             MulticastListenerReportV2{ number_of_records } => println!("MulticastListenerReportV2{{ number_of_records: {} }}", number_of_records),
 ///         }
 ///     },
 ///     _ => {},
@@ -651,6 +1084,84 @@ pub enum Icmpv6Type {
     /// The data received in the ICMPv6 Echo Request message MUST be returned
     /// entirely and unmodified in the ICMPv6 Echo Reply message.
     EchoReply(IcmpEchoHeader),
+
+    /// MLDv1 query sent by routers to learn which multicast addresses have
+    /// listeners on a link (from [RFC 2710](https://tools.ietf.org/html/rfc2710)).
+    ///
+    /// # What is part of the header for `Icmpv6Type::MulticastListenerQuery`?
+    ///
+    /// The complete 24 bytes/octets of the message are part of the header.
+    /// The `reserved` part is not stored and droped.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      130      |       0       |  checksum (in Icmpv6Header)   |  |
+    /// +---------------------------------------------------------------+  |
+    /// |  [value].maximum_response_delay |          <reserved>         |  | part of header & type
+    /// +---------------------------------------------------------------+  |
+    /// |                                                               |  |
+    /// |                   [value].multicast_address                   |  |
+    /// |                                                               |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    ///
+    /// Note that a query with 28 or more bytes is decoded as an
+    /// [`Icmpv6Type::MulticastListenerQueryV2`] (see RFC 3810 section 8.1).
+    MulticastListenerQuery(icmpv6::MulticastListenerHeader),
+
+    /// MLDv1 report sent by a node to announce that it is listening
+    /// to a multicast address (from [RFC 2710](https://tools.ietf.org/html/rfc2710)).
+    ///
+    /// # What is part of the header for `Icmpv6Type::MulticastListenerReport`?
+    ///
+    /// The complete 24 bytes/octets of the message are part of the header (same
+    /// layout as [`Icmpv6Type::MulticastListenerQuery`] with type 131).
+    MulticastListenerReport(icmpv6::MulticastListenerHeader),
+
+    /// MLDv1 done message sent by a node when it stops listening to a
+    /// multicast address (from [RFC 2710](https://tools.ietf.org/html/rfc2710)).
+    ///
+    /// # What is part of the header for `Icmpv6Type::MulticastListenerDone`?
+    ///
+    /// The complete 24 bytes/octets of the message are part of the header (same
+    /// layout as [`Icmpv6Type::MulticastListenerQuery`] with type 132).
+    MulticastListenerDone(icmpv6::MulticastListenerHeader),
+
+    /// MLDv2 query (from [RFC 3810](https://tools.ietf.org/html/rfc3810)).
+    ///
+    /// # What is part of the header for `Icmpv6Type::MulticastListenerQueryV2`?
+    ///
+    /// The first 28 bytes/octets are part of the header. The source addresses
+    /// are part of the payload ([`Icmpv6Slice::payload`] & [`PacketHeaders::payload`]),
+    /// see [`icmpv6::MulticastListenerQueryV2Header`] for the layout.
+    MulticastListenerQueryV2(icmpv6::MulticastListenerQueryV2Header),
+
+    /// MLDv2 report (from [RFC 3810](https://tools.ietf.org/html/rfc3810)).
+    ///
+    /// # What is part of the header for `Icmpv6Type::MulticastListenerReportV2`?
+    ///
+    /// The first 8 bytes/octets are part of the header. The multicast
+    /// address records are part of the payload ([`Icmpv6Slice::payload`] &
+    /// [`PacketHeaders::payload`]) and can be iterated with
+    /// [`Icmpv6Slice::mldv2_records`] or [`icmpv6::MulticastAddressRecordIterator`].
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      143      |       0       |  checksum (in Icmpv6Header)   |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |           <reserved>          |  [value].number_of_records    |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// |                                                               |  |
+    /// ...                 <multicast address records>               ...  | part of payload
+    /// |                                                               |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MulticastListenerReportV2 {
+        /// Number of multicast address records in the payload.
+        number_of_records: u16,
+    },
 }
 
 impl Icmpv6Type {
@@ -671,6 +1182,11 @@ impl Icmpv6Type {
             ParameterProblem(_) => TYPE_PARAMETER_PROBLEM,
             EchoRequest(_) => TYPE_ECHO_REQUEST,
             EchoReply(_) => TYPE_ECHO_REPLY,
+            MulticastListenerQuery(_) => TYPE_MULTICAST_LISTENER_QUERY,
+            MulticastListenerReport(_) => TYPE_MULTICAST_LISTENER_REPORT,
+            MulticastListenerDone(_) => TYPE_MULTICAST_LISTENER_REDUCTION,
+            MulticastListenerQueryV2(_) => TYPE_MULTICAST_LISTENER_QUERY,
+            MulticastListenerReportV2 { number_of_records: _ } => TYPE_MULTICAST_LISTENER_REPORT_V2,
         }
     }
 
@@ -690,6 +1206,11 @@ impl Icmpv6Type {
             ParameterProblem(header) => header.code.code_u8(),
             EchoRequest(_) => 0,
             EchoReply(_) => 0,
+            MulticastListenerQuery(_)
+            | MulticastListenerReport(_)
+            | MulticastListenerDone(_)
+            | MulticastListenerQueryV2(_)
+            | MulticastListenerReportV2 { number_of_records: _ } => 0,
         }
     }
 
@@ -717,7 +1238,7 @@ impl Icmpv6Type {
         // their own length information (like ICMPv6), is "the Payload Length
         // from the IPv6 header, minus the length of any extension headers present
         // between the IPv6 header and the upper-layer header."
-        let max_payload_len: usize = (std::u32::MAX as usize) - self.header_len();
+        let max_payload_len: usize = (core::u32::MAX as usize) - self.header_len();
         if max_payload_len < payload.len() {
             return Err(ValueError::Ipv6PayloadLengthTooLarge(payload.len()));
         }
@@ -766,6 +1287,35 @@ impl Icmpv6Type {
                     pseudo_sum.add_2bytes([TYPE_ECHO_REPLY, 0])
                     .add_4bytes(echo.to_bytes())
                 }
+                MulticastListenerQuery(header) => {
+                    pseudo_sum.add_2bytes([TYPE_MULTICAST_LISTENER_QUERY, 0])
+                    .add_2bytes(header.maximum_response_delay.to_be_bytes())
+                    .add_16bytes(header.multicast_address)
+                }
+                MulticastListenerReport(header) => {
+                    pseudo_sum.add_2bytes([TYPE_MULTICAST_LISTENER_REPORT, 0])
+                    .add_2bytes(header.maximum_response_delay.to_be_bytes())
+                    .add_16bytes(header.multicast_address)
+                }
+                MulticastListenerDone(header) => {
+                    pseudo_sum.add_2bytes([TYPE_MULTICAST_LISTENER_REDUCTION, 0])
+                    .add_2bytes(header.maximum_response_delay.to_be_bytes())
+                    .add_16bytes(header.multicast_address)
+                }
+                MulticastListenerQueryV2(header) => {
+                    pseudo_sum.add_2bytes([TYPE_MULTICAST_LISTENER_QUERY, 0])
+                    .add_2bytes(header.maximum_response_code.to_be_bytes())
+                    .add_16bytes(header.multicast_address)
+                    .add_2bytes([
+                        header.flags_u8(),
+                        header.querier_query_interval_code
+                    ])
+                    .add_2bytes(header.number_of_sources.to_be_bytes())
+                }
+                MulticastListenerReportV2{ number_of_records } => {
+                    pseudo_sum.add_2bytes([TYPE_MULTICAST_LISTENER_REPORT_V2, 0])
+                    .add_2bytes(number_of_records.to_be_bytes())
+                }
             }
             .add_slice(payload)
             .ones_complement()
@@ -803,7 +1353,12 @@ impl Icmpv6Type {
             | TimeExceeded(_)
             | ParameterProblem(_)
             | EchoRequest(_)
-            | EchoReply(_) => 8,
+            | EchoReply(_)
+            | MulticastListenerReportV2 { number_of_records: _ } => 8,
+            MulticastListenerQuery(_)
+            | MulticastListenerReport(_)
+            | MulticastListenerDone(_) => icmpv6::MulticastListenerHeader::SERIALIZED_SIZE,
+            MulticastListenerQueryV2(_) => icmpv6::MulticastListenerQueryV2Header::SERIALIZED_SIZE,
         }
     }
 
@@ -823,7 +1378,12 @@ impl Icmpv6Type {
             | TimeExceeded(_)
             | ParameterProblem(_)
             | EchoRequest(_)
-            | EchoReply(_) => None,
+            | EchoReply(_)
+            | MulticastListenerQueryV2(_)
+            | MulticastListenerReportV2 { number_of_records: _ } => None,
+            MulticastListenerQuery(_)
+            | MulticastListenerReport(_)
+            | MulticastListenerDone(_) => Some(0),
         }
     }
 }
@@ -886,20 +1446,65 @@ impl Icmpv6Header {
     }
 
     /// Read a ICMPv6 header from the given reader
+    ///
+    /// Note that ICMPv6 packets contain no length field. Because of this
+    /// MLD queries are always read as MLDv1 queries
+    /// ([`Icmpv6Type::MulticastListenerQuery`]) by this method, use
+    /// [`Icmpv6Header::from_slice`] if MLDv2 queries should be decoded.
+    ///
+    /// MLD messages that end before the multicast address are read as
+    /// [`Icmpv6Type::Unknown`] (same as in [`Icmpv6Header::from_slice`]).
+    /// The bytes after the first 8 bytes of such messages are consumed
+    /// from the reader.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<Icmpv6Header, ReadError> {
         // read the initial 8 bytes
-        let mut start = [0u8;8];
-        reader.read_exact(&mut start)?;
-        Ok(Icmpv6Slice{
-            slice: &start
-        }.header())
+        let mut bytes = [0u8;Icmpv6Header::MAX_SERIALIZED_SIZE];
+        reader.read_exact(&mut bytes[..8])?;
+
+        match bytes[0] {
+            TYPE_MULTICAST_LISTENER_QUERY | TYPE_MULTICAST_LISTENER_REPORT | TYPE_MULTICAST_LISTENER_REDUCTION if 0 == bytes[1] => {
+                // MLDv1 messages contain an additional multicast address (read
+                // until the end of the reader to detect shorter messages)
+                let mut len = 8;
+                while len < MulticastListenerHeader::SERIALIZED_SIZE {
+                    match reader.read(&mut bytes[len..MulticastListenerHeader::SERIALIZED_SIZE]) {
+                        Ok(0) => break,
+                        Ok(read) => len += read,
+                        Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {},
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                // shorter messages fallback to unknown
+                Ok(Icmpv6Slice {
+                    slice: &bytes[..len]
+                }.header())
+            },
+            _ => Ok(Icmpv6Slice{
+                slice: &bytes[..8]
+            }.header())
+        }
     }
 
     /// Write the ICMPv6 header to the given writer.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         writer.write_all(&self.to_bytes()).map_err(WriteError::from)
     }
 
+    /// Write the ICMPv6 header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        let bytes = self.to_bytes();
+        //length check
+        if slice.len() < bytes.len() {
+            Err(WriteError::SliceTooSmall(bytes.len()))
+        } else {
+            slice[..bytes.len()].copy_from_slice(&bytes);
+            Ok(&mut slice[bytes.len()..])
+        }
+    }
+
     /// Serialized length of the header in bytes/octets.
     ///
     /// Note that this size is not the size of the entire
@@ -981,6 +1586,31 @@ impl Icmpv6Header {
             re
         };
 
+        let return_mld = |type_u8: u8, header: MulticastListenerHeader| -> ArrayVec<u8, { Icmpv6Header::MAX_SERIALIZED_SIZE }> {
+            let delay_be = header.maximum_response_delay.to_be_bytes();
+            let a = header.multicast_address;
+            #[rustfmt::skip]
+            let mut re = ArrayVec::from([
+                type_u8, 0, checksum_be[0], checksum_be[1],
+                delay_be[0], delay_be[1], 0, 0,
+
+                a[0], a[1], a[2], a[3],
+                a[4], a[5], a[6], a[7],
+                a[8], a[9], a[10], a[11],
+                a[12], a[13], a[14], a[15],
+
+                0, 0, 0, 0,
+                0, 0, 0, 0,
+                0, 0, 0, 0,
+                0, 0, 0, 0,
+            ]);
+            // SAFETY: Safe as u8 has no destruction behavior and as 24 is smaller then 40.
+            unsafe {
+                re.set_len(MulticastListenerHeader::SERIALIZED_SIZE);
+            }
+            re
+        };
+
         use Icmpv6Type::*;
         match self.icmp_type {
             Unknown {
@@ -1008,6 +1638,44 @@ impl Icmpv6Header {
             EchoReply(echo) => {
                 return_4u8(TYPE_ECHO_REPLY, 0, echo.to_bytes())
             },
+            MulticastListenerQuery(header) => {
+                return_mld(TYPE_MULTICAST_LISTENER_QUERY, header)
+            },
+            MulticastListenerReport(header) => {
+                return_mld(TYPE_MULTICAST_LISTENER_REPORT, header)
+            },
+            MulticastListenerDone(header) => {
+                return_mld(TYPE_MULTICAST_LISTENER_REDUCTION, header)
+            },
+            MulticastListenerQueryV2(header) => {
+                let code_be = header.maximum_response_code.to_be_bytes();
+                let a = header.multicast_address;
+                let n_be = header.number_of_sources.to_be_bytes();
+                #[rustfmt::skip]
+                let mut re = ArrayVec::from([
+                    TYPE_MULTICAST_LISTENER_QUERY, 0, checksum_be[0], checksum_be[1],
+                    code_be[0], code_be[1], 0, 0,
+
+                    a[0], a[1], a[2], a[3],
+                    a[4], a[5], a[6], a[7],
+                    a[8], a[9], a[10], a[11],
+                    a[12], a[13], a[14], a[15],
+
+                    header.flags_u8(), header.querier_query_interval_code, n_be[0], n_be[1],
+                    0, 0, 0, 0,
+                    0, 0, 0, 0,
+                    0, 0, 0, 0,
+                ]);
+                // SAFETY: Safe as u8 has no destruction behavior and as 28 is smaller then 40.
+                unsafe {
+                    re.set_len(MulticastListenerQueryV2Header::SERIALIZED_SIZE);
+                }
+                re
+            },
+            MulticastListenerReportV2 { number_of_records } => {
+                let n_be = number_of_records.to_be_bytes();
+                return_4u8(TYPE_MULTICAST_LISTENER_REPORT_V2, 0, [0, 0, n_be[0], n_be[1]])
+            },
         }
     }
 }
@@ -1022,6 +1690,15 @@ pub struct Icmpv6Slice<'a> {
 }
 
 impl<'a> Icmpv6Slice<'a> {
+
+    /// Position & size of the fields in the first 8 bytes of the ICMPv6 message
+    /// ("rest_of_header" contains the type specific values).
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "type", bit_offset: 0, bit_width: 8 },
+        HeaderField{ name: "code", bit_offset: 8, bit_width: 8 },
+        HeaderField{ name: "checksum", bit_offset: 16, bit_width: 16 },
+        HeaderField{ name: "rest_of_header", bit_offset: 32, bit_width: 32 },
+    ];
     /// Creates a slice containing an ICMPv6 packet.
     ///
     /// # Errors
@@ -1057,7 +1734,12 @@ impl<'a> Icmpv6Slice<'a> {
     /// [`Icmpv6Header`] when [`Icmpv6Slice::header`] gets called.
     #[inline]
     pub fn header_len(&self) -> usize {
-        8
+        match self.type_u8() {
+            TYPE_MULTICAST_LISTENER_QUERY | TYPE_MULTICAST_LISTENER_REPORT | TYPE_MULTICAST_LISTENER_REDUCTION => {
+                self.icmp_type().header_len()
+            },
+            _ => 8,
+        }
     }
 
     /// Decode the header values (excluding the checksum) into an [`Icmpv6Type`] enum.
@@ -1102,6 +1784,50 @@ impl<'a> Icmpv6Slice<'a> {
                     return EchoReply(IcmpEchoHeader::from_bytes(self.bytes5to8()));
                 }
             }
+            TYPE_MULTICAST_LISTENER_QUERY if 0 == self.code_u8() => {
+                if self.slice.len() >= MulticastListenerQueryV2Header::SERIALIZED_SIZE {
+                    // SAFETY:
+                    // Safe as the slice length is checked to be at least
+                    // MulticastListenerQueryV2Header::SERIALIZED_SIZE (28).
+                    let (flags, qqic, number_of_sources) = unsafe {
+                        (
+                            *self.slice.get_unchecked(24),
+                            *self.slice.get_unchecked(25),
+                            get_unchecked_be_u16(self.slice.as_ptr().add(26)),
+                        )
+                    };
+                    return MulticastListenerQueryV2(MulticastListenerQueryV2Header {
+                        maximum_response_code: self.mld_maximum_response_delay(),
+                        multicast_address: self.mld_multicast_address(),
+                        suppress_router_side_processing: 0 != flags & 0b1000,
+                        querier_robustness_variable: flags & 0b111,
+                        querier_query_interval_code: qqic,
+                        number_of_sources,
+                    });
+                } else if let Some(header) = self.mldv1_header() {
+                    return MulticastListenerQuery(header);
+                }
+            }
+            TYPE_MULTICAST_LISTENER_REPORT if 0 == self.code_u8() => {
+                if let Some(header) = self.mldv1_header() {
+                    return MulticastListenerReport(header);
+                }
+            }
+            TYPE_MULTICAST_LISTENER_REDUCTION if 0 == self.code_u8() => {
+                if let Some(header) = self.mldv1_header() {
+                    return MulticastListenerDone(header);
+                }
+            }
+            TYPE_MULTICAST_LISTENER_REPORT_V2 if 0 == self.code_u8() => {
+                return MulticastListenerReportV2 {
+                    // SAFETY:
+                    // Safe as the contructor checks that the slice has
+                    // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE (8).
+                    number_of_records: unsafe {
+                        get_unchecked_be_u16(self.slice.as_ptr().add(6))
+                    },
+                };
+            }
             _ => {}
         }
         Unknown {
@@ -1111,6 +1837,74 @@ impl<'a> Icmpv6Slice<'a> {
         }
     }
 
+    /// Decodes the MLDv1 values if the slice is long enough to contain them.
+    fn mldv1_header(&self) -> Option<MulticastListenerHeader> {
+        if self.slice.len() >= MulticastListenerHeader::SERIALIZED_SIZE {
+            Some(MulticastListenerHeader {
+                maximum_response_delay: self.mld_maximum_response_delay(),
+                multicast_address: self.mld_multicast_address(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Reads the "Maximum Response Delay" / "Maximum Response Code" field of an
+    /// MLD message (caller has to make sure it is one).
+    #[inline]
+    fn mld_maximum_response_delay(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE (8).
+        unsafe { get_unchecked_be_u16(self.slice.as_ptr().add(4)) }
+    }
+
+    /// Reads the "Multicast Address" of an MLD message.
+    ///
+    /// Caller must make sure the slice has at least the length of
+    /// `MulticastListenerHeader::SERIALIZED_SIZE` (24).
+    #[inline]
+    fn mld_multicast_address(&self) -> [u8;16] {
+        debug_assert!(self.slice.len() >= MulticastListenerHeader::SERIALIZED_SIZE);
+        // SAFETY:
+        // Safe as the callers verify the slice length is at least
+        // MulticastListenerHeader::SERIALIZED_SIZE (24).
+        unsafe { get_unchecked_16_byte_array(self.slice.as_ptr().add(8)) }
+    }
+
+    /// Returns an iterator over the multicast address records if the slice
+    /// contains an MLDv2 report ([`Icmpv6Type::MulticastListenerReportV2`]).
+    ///
+    /// Returns `None` if the slice contains a different message.
+    pub fn mldv2_records(&self) -> Option<MulticastAddressRecordIterator<'a>> {
+        match self.icmp_type() {
+            Icmpv6Type::MulticastListenerReportV2{ number_of_records } => {
+                Some(MulticastAddressRecordIterator::new(number_of_records, self.payload()))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the source addresses if the slice contains
+    /// an MLDv2 query ([`Icmpv6Type::MulticastListenerQueryV2`]).
+    ///
+    /// The iteration is limited by the "Number of Sources" field and
+    /// the length of the slice. Returns `None` if the slice contains a
+    /// different message.
+    pub fn mldv2_query_sources(&self) -> Option<SourceAddressIterator<'a>> {
+        match self.icmp_type() {
+            Icmpv6Type::MulticastListenerQueryV2(header) => {
+                let payload = self.payload();
+                let len = core::cmp::min(
+                    payload.len(),
+                    usize::from(header.number_of_sources)*16
+                );
+                Some(SourceAddressIterator::from_slice(&payload[..len]))
+            },
+            _ => None,
+        }
+    }
+
     /// Returns "type" value in the ICMPv6 header.
     #[inline]
     pub fn type_u8(&self) -> u8 {
@@ -1186,9 +1980,58 @@ impl<'a> Icmpv6Slice<'a> {
     /// Returns a slice to the bytes not covered by `.header()`.
     #[inline]
     pub fn payload(&self) -> &'a [u8] {
+        let header_len = self.header_len();
         // SAFETY:
         // Safe as the contructor checks that the slice has
-        // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE(8).
-        unsafe { from_raw_parts(self.slice.as_ptr().add(8), self.slice.len() - 8) }
+        // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE(8)
+        // and header_len only returns larger values if the slice
+        // has been checked to be long enough.
+        unsafe { from_raw_parts(self.slice.as_ptr().add(header_len), self.slice.len() - header_len) }
+    }
+
+    /// Returns the "length" attribute defined in [RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884)
+    /// (length of the "original datagram" in 64-bit words) for message types
+    /// that support ICMP extension structures.
+    ///
+    /// The attribute is located in the 5th byte/octet of the ICMPv6 packet and
+    /// `Some` is only returned for "Destination Unreachable" & "Time Exceeded"
+    /// messages. A value of zero indicates that no extension structure is present.
+    #[inline]
+    pub fn length_attribute(&self) -> Option<u8> {
+        match self.type_u8() {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE (8).
+            TYPE_DST_UNREACH | TYPE_TIME_EXCEEDED => {
+                Some(unsafe { *self.slice.get_unchecked(4) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the "original datagram" part of the payload.
+    ///
+    /// If a non zero RFC 4884 length attribute is present ([`Icmpv6Slice::length_attribute`])
+    /// the returned slice is limited to the indicated length, otherwise the
+    /// complete payload is returned.
+    #[inline]
+    pub fn original_datagram(&self) -> &'a [u8] {
+        let len = usize::from(self.length_attribute().unwrap_or(0))*8;
+        split_icmp_extensions(self.payload(), len).0
+    }
+
+    /// Returns the ICMP extension structure ([RFC 4884](https://datatracker.ietf.org/doc/html/rfc4884))
+    /// located after the "original datagram" if one is present.
+    ///
+    /// `Ok(None)` is returned if the message type does not support extensions,
+    /// the length attribute is zero or no data is present after the
+    /// "original datagram". An error is returned if data is present but does
+    /// not start with a valid extension header.
+    pub fn extensions(&self) -> Result<Option<IcmpExtensionsSlice<'a>>, ReadError> {
+        let len = usize::from(self.length_attribute().unwrap_or(0))*8;
+        match split_icmp_extensions(self.payload(), len).1 {
+            Some(slice) => Ok(Some(IcmpExtensionsSlice::from_slice(slice)?)),
+            None => Ok(None),
+        }
     }
 }
@@ -0,0 +1,984 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use core::slice::from_raw_parts;
+
+/// Module containing IGMP related types and constants
+pub mod igmp {
+
+    /// IGMP type value of a "Membership Query" message (IGMPv1, IGMPv2 & IGMPv3).
+    ///
+    /// The version of the query is determined by the length of the message
+    /// and the "Max Resp Code" (see [RFC 3376 section 7.1](https://datatracker.ietf.org/doc/html/rfc3376#section-7.1)).
+    pub const TYPE_MEMBERSHIP_QUERY: u8 = 0x11;
+
+    /// IGMP type value of a "Version 1 Membership Report" message (defined in [RFC 1112](https://datatracker.ietf.org/doc/html/rfc1112)).
+    pub const TYPE_MEMBERSHIP_REPORT_V1: u8 = 0x12;
+
+    /// IGMP type value of a "Version 2 Membership Report" message (defined in [RFC 2236](https://datatracker.ietf.org/doc/html/rfc2236)).
+    pub const TYPE_MEMBERSHIP_REPORT_V2: u8 = 0x16;
+
+    /// IGMP type value of a "Leave Group" message (defined in [RFC 2236](https://datatracker.ietf.org/doc/html/rfc2236)).
+    pub const TYPE_LEAVE_GROUP: u8 = 0x17;
+
+    /// IGMP type value of a "Version 3 Membership Report" message (defined in [RFC 3376](https://datatracker.ietf.org/doc/html/rfc3376)).
+    pub const TYPE_MEMBERSHIP_REPORT_V3: u8 = 0x22;
+
+    /// IGMPv3 group record type "MODE_IS_INCLUDE" (current state record).
+    pub const RECORD_MODE_IS_INCLUDE: u8 = 1;
+
+    /// IGMPv3 group record type "MODE_IS_EXCLUDE" (current state record).
+    pub const RECORD_MODE_IS_EXCLUDE: u8 = 2;
+
+    /// IGMPv3 group record type "CHANGE_TO_INCLUDE_MODE" (filter mode change record).
+    pub const RECORD_CHANGE_TO_INCLUDE_MODE: u8 = 3;
+
+    /// IGMPv3 group record type "CHANGE_TO_EXCLUDE_MODE" (filter mode change record).
+    pub const RECORD_CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+
+    /// IGMPv3 group record type "ALLOW_NEW_SOURCES" (source list change record).
+    pub const RECORD_ALLOW_NEW_SOURCES: u8 = 5;
+
+    /// IGMPv3 group record type "BLOCK_OLD_SOURCES" (source list change record).
+    pub const RECORD_BLOCK_OLD_SOURCES: u8 = 6;
+
+    /// Values of an IGMPv1 or IGMPv2 "Membership Query" message.
+    ///
+    /// IGMPv1 queries are identified by a "Max Response Time" of 0.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MembershipQueryHeader {
+        /// Maximum allowed time before sending a responding report in
+        /// units of 1/10 second (0 for IGMPv1 queries).
+        pub max_response_time: u8,
+        /// Group address being queried (zero for general queries).
+        pub group_address: [u8; 4],
+    }
+
+    impl MembershipQueryHeader {
+        /// Number of bytes/octets of an IGMPv1 or IGMPv2 query.
+        pub const SERIALIZED_SIZE: usize = 8;
+    }
+
+    /// Values of an IGMPv3 "Membership Query" message (excluding
+    /// the source addresses).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct MembershipQueryV3Header {
+        /// Encoded maximum response time (see [`MembershipQueryV3Header::max_response_time`]
+        /// for the decoded value).
+        pub max_response_code: u8,
+        /// Group address being queried (zero for general queries).
+        pub group_address: [u8; 4],
+        /// "Suppress Router-Side Processing" flag.
+        pub suppress_router_side_processing: bool,
+        /// "Querier's Robustness Variable" (3 bits, 0 if the value exceeds 7).
+        pub querier_robustness_variable: u8,
+        /// Encoded "Querier's Query Interval Code" (see
+        /// [`MembershipQueryV3Header::querier_query_interval`] for the decoded value).
+        pub querier_query_interval_code: u8,
+        /// Number of source addresses present after the header.
+        pub number_of_sources: u16,
+    }
+
+    impl MembershipQueryV3Header {
+        /// Number of bytes/octets of an IGMPv3 query (excluding the source addresses).
+        pub const SERIALIZED_SIZE: usize = 12;
+
+        /// Maximum value of the 3 bit "Querier's Robustness Variable" field.
+        pub const MAX_QUERIER_ROBUSTNESS_VARIABLE: u8 = 0b111;
+
+        /// Returns the byte containing the "Suppress Router-Side Processing"
+        /// flag & "Querier's Robustness Variable" (reserved bits set to zero).
+        #[inline]
+        pub fn flags_u8(&self) -> u8 {
+            let s = if self.suppress_router_side_processing {
+                0b1000
+            } else {
+                0
+            };
+            s | (self.querier_robustness_variable & Self::MAX_QUERIER_ROBUSTNESS_VARIABLE)
+        }
+
+        /// Decodes the "Max Resp Code" into the maximum response time
+        /// in units of 1/10 second (RFC 3376 section 4.1.1).
+        pub fn max_response_time(&self) -> u32 {
+            decode_exp_code(self.max_response_code)
+        }
+
+        /// Decodes the "Querier's Query Interval Code" into the query
+        /// interval in seconds (RFC 3376 section 4.1.7).
+        pub fn querier_query_interval(&self) -> u32 {
+            decode_exp_code(self.querier_query_interval_code)
+        }
+    }
+
+    /// Decodes the 8 bit floating point format used by IGMPv3 for the
+    /// "Max Resp Code" & "QQIC" fields.
+    fn decode_exp_code(code: u8) -> u32 {
+        if code < 0x80 {
+            u32::from(code)
+        } else {
+            let mant = u32::from(code & 0x0f);
+            let exp = u32::from((code >> 4) & 0b111);
+            (mant | 0x10) << (exp + 3)
+        }
+    }
+
+    /// Type of an IGMPv3 group record.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum GroupRecordType {
+        /// "MODE_IS_INCLUDE" current state record.
+        ModeIsInclude = 1,
+        /// "MODE_IS_EXCLUDE" current state record.
+        ModeIsExclude = 2,
+        /// "CHANGE_TO_INCLUDE_MODE" filter mode change record.
+        ChangeToIncludeMode = 3,
+        /// "CHANGE_TO_EXCLUDE_MODE" filter mode change record.
+        ChangeToExcludeMode = 4,
+        /// "ALLOW_NEW_SOURCES" source list change record.
+        AllowNewSources = 5,
+        /// "BLOCK_OLD_SOURCES" source list change record.
+        BlockOldSources = 6,
+    }
+
+    impl GroupRecordType {
+        /// Converts the u8 record type value to a [`GroupRecordType`].
+        ///
+        /// Returns [`None`] in case the value is not a known record type.
+        pub fn from_u8(value: u8) -> Option<GroupRecordType> {
+            use GroupRecordType::*;
+            match value {
+                RECORD_MODE_IS_INCLUDE => Some(ModeIsInclude),
+                RECORD_MODE_IS_EXCLUDE => Some(ModeIsExclude),
+                RECORD_CHANGE_TO_INCLUDE_MODE => Some(ChangeToIncludeMode),
+                RECORD_CHANGE_TO_EXCLUDE_MODE => Some(ChangeToExcludeMode),
+                RECORD_ALLOW_NEW_SOURCES => Some(AllowNewSources),
+                RECORD_BLOCK_OLD_SOURCES => Some(BlockOldSources),
+                _ => None,
+            }
+        }
+
+        /// Returns the u8 value of the record type.
+        #[inline]
+        pub fn type_u8(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    /// Statically sized part of a group record in an IGMPv3 report.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+
+    /// |  record_type  | aux_data_len  |       number_of_sources       |
+    /// +---------------------------------------------------------------+
+    /// |                       multicast_address                       |
+    /// +---------------------------------------------------------------+
+    /// |          source addresses (number_of_sources * 4 bytes)       |
+    /// +---------------------------------------------------------------+
+    /// |             auxiliary data (aux_data_len * 4 bytes)           |
+    /// +---------------------------------------------------------------+
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct GroupRecordHeader {
+        /// Raw record type (see [`GroupRecordType`] for the known values).
+        pub record_type: u8,
+        /// Length of the auxiliary data in units of 32 bit words.
+        pub aux_data_len: u8,
+        /// Number of source addresses present in the record.
+        pub number_of_sources: u16,
+        /// Multicast address the record refers to.
+        pub multicast_address: [u8; 4],
+    }
+
+    impl GroupRecordHeader {
+        /// Number of bytes/octets of the statically sized part of a record.
+        pub const SERIALIZED_SIZE: usize = 8;
+
+        /// Length of the complete record (including source addresses &
+        /// auxiliary data) in bytes/octets.
+        #[inline]
+        pub fn record_len(&self) -> usize {
+            GroupRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources) * 4
+                + usize::from(self.aux_data_len) * 4
+        }
+
+        /// Returns the serialized form of the header.
+        pub fn to_bytes(&self) -> [u8; 8] {
+            let n = self.number_of_sources.to_be_bytes();
+            let a = self.multicast_address;
+            [
+                self.record_type, self.aux_data_len, n[0], n[1],
+                a[0], a[1], a[2], a[3],
+            ]
+        }
+
+        /// Writes the header to the given writer.
+        #[cfg(feature = "std")]
+        pub fn write<T: std::io::Write + Sized>(&self, writer: &mut T) -> Result<(), crate::WriteError> {
+            writer.write_all(&self.to_bytes())?;
+            Ok(())
+        }
+    }
+
+    /// Slice containing a complete group record of an IGMPv3 report
+    /// (including source addresses & auxiliary data).
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct GroupRecordSlice<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> GroupRecordSlice<'a> {
+        /// Creates a record slice from the start of the given slice.
+        ///
+        /// The resulting slice only contains the record itself (the length
+        /// is determined via the "Number of Sources" & "Aux Data Len" fields).
+        pub fn from_slice(slice: &'a [u8]) -> Result<GroupRecordSlice<'a>, crate::ReadError> {
+            use crate::ReadError::*;
+            if slice.len() < GroupRecordHeader::SERIALIZED_SIZE {
+                return Err(UnexpectedEndOfSlice(GroupRecordHeader::SERIALIZED_SIZE));
+            }
+            let record_len = GroupRecordHeader::SERIALIZED_SIZE
+                + usize::from(u16::from_be_bytes([slice[2], slice[3]])) * 4
+                + usize::from(slice[1]) * 4;
+            if slice.len() < record_len {
+                return Err(UnexpectedEndOfSlice(record_len));
+            }
+            Ok(GroupRecordSlice {
+                slice: &slice[..record_len],
+            })
+        }
+
+        /// Returns the slice containing the record.
+        #[inline]
+        pub fn slice(&self) -> &'a [u8] {
+            self.slice
+        }
+
+        /// Raw "Record Type" value.
+        #[inline]
+        pub fn record_type_u8(&self) -> u8 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of GroupRecordHeader::SERIALIZED_SIZE (8).
+            unsafe { *self.slice.get_unchecked(0) }
+        }
+
+        /// Decoded "Record Type" ([`None`] if the record type is unknown).
+        #[inline]
+        pub fn record_type(&self) -> Option<GroupRecordType> {
+            GroupRecordType::from_u8(self.record_type_u8())
+        }
+
+        /// Length of the auxiliary data in units of 32 bit words.
+        #[inline]
+        pub fn aux_data_len(&self) -> u8 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of GroupRecordHeader::SERIALIZED_SIZE (8).
+            unsafe { *self.slice.get_unchecked(1) }
+        }
+
+        /// Number of source addresses present in the record.
+        #[inline]
+        pub fn number_of_sources(&self) -> u16 {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of GroupRecordHeader::SERIALIZED_SIZE (8).
+            unsafe { crate::get_unchecked_be_u16(self.slice.as_ptr().add(2)) }
+        }
+
+        /// Multicast address the record refers to.
+        #[inline]
+        pub fn multicast_address(&self) -> [u8; 4] {
+            // SAFETY:
+            // Safe as the contructor checks that the slice has
+            // at least the length of GroupRecordHeader::SERIALIZED_SIZE (8).
+            unsafe { crate::get_unchecked_4_byte_array(self.slice.as_ptr().add(4)) }
+        }
+
+        /// Iterator over the source addresses of the record.
+        #[inline]
+        pub fn sources(&self) -> SourceAddressIterator<'a> {
+            let end = GroupRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources()) * 4;
+            SourceAddressIterator::from_slice(
+                &self.slice[GroupRecordHeader::SERIALIZED_SIZE..end]
+            )
+        }
+
+        /// Auxiliary data of the record.
+        #[inline]
+        pub fn aux_data(&self) -> &'a [u8] {
+            let start = GroupRecordHeader::SERIALIZED_SIZE
+                + usize::from(self.number_of_sources()) * 4;
+            &self.slice[start..]
+        }
+
+        /// Decodes the statically sized part of the record into a [`GroupRecordHeader`].
+        pub fn to_header(&self) -> GroupRecordHeader {
+            GroupRecordHeader {
+                record_type: self.record_type_u8(),
+                aux_data_len: self.aux_data_len(),
+                number_of_sources: self.number_of_sources(),
+                multicast_address: self.multicast_address(),
+            }
+        }
+    }
+
+    /// Iterator over the group records of an IGMPv3 report.
+    ///
+    /// In case a record can not be read (e.g. the slice ends too early)
+    /// an error is returned and the iteration ends afterwards.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct GroupRecordIterator<'a> {
+        number_of_records: u16,
+        rest: &'a [u8],
+    }
+
+    impl<'a> GroupRecordIterator<'a> {
+        /// Creates an iterator over `number_of_records` records at the
+        /// start of the given slice (usually the payload of an IGMPv3 report).
+        pub fn new(number_of_records: u16, slice: &'a [u8]) -> GroupRecordIterator<'a> {
+            GroupRecordIterator {
+                number_of_records,
+                rest: slice,
+            }
+        }
+
+        /// Returns the part of the slice not yet processed.
+        #[inline]
+        pub fn rest(&self) -> &'a [u8] {
+            self.rest
+        }
+    }
+
+    impl<'a> Iterator for GroupRecordIterator<'a> {
+        type Item = Result<GroupRecordSlice<'a>, crate::ReadError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if 0 == self.number_of_records {
+                return None;
+            }
+            match GroupRecordSlice::from_slice(self.rest) {
+                Ok(record) => {
+                    self.number_of_records -= 1;
+                    self.rest = &self.rest[record.slice().len()..];
+                    Some(Ok(record))
+                },
+                Err(err) => {
+                    self.number_of_records = 0;
+                    Some(Err(err))
+                }
+            }
+        }
+    }
+
+    /// Iterator over a list of IPv4 source addresses (e.g. in an IGMPv3 query
+    /// or group record).
+    ///
+    /// Trailing bytes not making up a full address are ignored.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct SourceAddressIterator<'a> {
+        rest: &'a [u8],
+    }
+
+    impl<'a> SourceAddressIterator<'a> {
+        /// Creates an iterator over the 4 byte addresses in the given slice.
+        #[inline]
+        pub fn from_slice(slice: &'a [u8]) -> SourceAddressIterator<'a> {
+            SourceAddressIterator { rest: slice }
+        }
+    }
+
+    impl<'a> Iterator for SourceAddressIterator<'a> {
+        type Item = [u8; 4];
+
+        fn next(&mut self) -> Option<[u8; 4]> {
+            if self.rest.len() < 4 {
+                None
+            } else {
+                // SAFETY:
+                // Safe as the length is checked to be at least 4 above.
+                let result = unsafe { crate::get_unchecked_4_byte_array(self.rest.as_ptr()) };
+                self.rest = &self.rest[4..];
+                Some(result)
+            }
+        }
+    }
+
+} // mod igmp
+
+use igmp::*;
+
+/// Different kinds of IGMP messages (the static part of the message
+/// without the checksum).
+///
+/// # Decoding & reserved fields
+///
+/// Reports & "Leave Group" messages are only decoded into their
+/// typed variants if the reserved fields are set to zero. Otherwise
+/// they are decoded as [`IgmpType::Unknown`], so that no data gets
+/// lost when the header is serialized again.
+///
+/// Queries are decoded as [`IgmpType::MembershipQueryV3`] if the message
+/// has a length of at least 12 bytes/octets (as described in
+/// [RFC 3376 section 7.1](https://datatracker.ietf.org/doc/html/rfc3376#section-7.1)).
+/// As the length of a message is unknown when reading it via
+/// [`IgmpHeader::read`], queries will always be read as
+/// [`IgmpType::MembershipQuery`] by it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IgmpType {
+    /// In case of an unknown IGMP type the first 8 bytes are stored
+    /// in this enum value. The `Unknown` value can also be used to write
+    /// arbitrary IGMP messages.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |     type_u8   |max_resp_code  |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |                          bytes5to8                            |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// |                                                               |  |
+    /// ...                           ...                             ...  | part of payload
+    /// |                                                               |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    Unknown {
+        /// IGMP type (present in the first byte of the IGMP message).
+        type_u8: u8,
+        /// Second byte of the IGMP message ("Max Resp Code" or reserved
+        /// depending on the type).
+        max_response_code: u8,
+        /// Bytes located at the 5th, 6th, 7th and 8th position of the IGMP message.
+        bytes5to8: [u8; 4],
+    },
+
+    /// IGMPv1 or IGMPv2 "Membership Query" message.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x11     |max_resp_time  |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |                         group_address                         |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MembershipQuery(MembershipQueryHeader),
+
+    /// IGMPv3 "Membership Query" message. The source addresses are
+    /// part of the payload (see [`IgmpSlice::query_v3_sources`]).
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x11     |max_resp_code  |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  |
+    /// |                         group_address                         |  | part of header & type
+    /// +---------------------------------------------------------------+  |
+    /// | Resv  |S| QRV |     QQIC      |       number_of_sources       |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// |                 source addresses (4 bytes each)               |  | part of payload
+    /// ...                           ...                             ...  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MembershipQueryV3(MembershipQueryV3Header),
+
+    /// IGMPv1 "Membership Report" message.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x12     |       0       |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |                         group_address                         |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MembershipReportV1 {
+        /// Multicast group address being reported.
+        group_address: [u8; 4],
+    },
+
+    /// IGMPv2 "Membership Report" message.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x16     |       0       |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |                         group_address                         |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MembershipReportV2 {
+        /// Multicast group address being reported.
+        group_address: [u8; 4],
+    },
+
+    /// IGMPv2 "Leave Group" message.
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x17     |       0       |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |                         group_address                         |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    LeaveGroup {
+        /// Multicast group address being left.
+        group_address: [u8; 4],
+    },
+
+    /// IGMPv3 "Membership Report" message. The group records are part
+    /// of the payload (see [`IgmpSlice::group_records`]).
+    ///
+    /// ```text
+    /// 0               1               2               3               4
+    /// +---------------------------------------------------------------+  -
+    /// |      0x22     |       0       |  checksum (in IgmpHeader)     |  |
+    /// +---------------------------------------------------------------+  | part of header & type
+    /// |               0               |       number_of_records       |  ↓
+    /// +---------------------------------------------------------------+  -
+    /// |                         group records                         |  | part of payload
+    /// ...                           ...                             ...  ↓
+    /// +---------------------------------------------------------------+  -
+    /// ```
+    MembershipReportV3 {
+        /// Number of group records present in the payload.
+        number_of_records: u16,
+    },
+}
+
+impl IgmpType {
+    /// Returns the type value (first byte of the IGMP message) of this type.
+    #[inline]
+    pub fn type_u8(&self) -> u8 {
+        use IgmpType::*;
+        match self {
+            Unknown { type_u8, .. } => *type_u8,
+            MembershipQuery(_) | MembershipQueryV3(_) => TYPE_MEMBERSHIP_QUERY,
+            MembershipReportV1 { .. } => TYPE_MEMBERSHIP_REPORT_V1,
+            MembershipReportV2 { .. } => TYPE_MEMBERSHIP_REPORT_V2,
+            LeaveGroup { .. } => TYPE_LEAVE_GROUP,
+            MembershipReportV3 { .. } => TYPE_MEMBERSHIP_REPORT_V3,
+        }
+    }
+
+    /// Returns the length in bytes/octets of the header of
+    /// this IGMP message type.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        use IgmpType::*;
+        match self {
+            Unknown { .. }
+            | MembershipQuery(_)
+            | MembershipReportV1 { .. }
+            | MembershipReportV2 { .. }
+            | LeaveGroup { .. }
+            | MembershipReportV3 { .. } => 8,
+            MembershipQueryV3(_) => MembershipQueryV3Header::SERIALIZED_SIZE,
+        }
+    }
+
+    /// Calculate the IGMP checksum value (the checksum covers the whole
+    /// IGMP message, no pseudo header is used).
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        use IgmpType::*;
+        match self {
+            Unknown {
+                type_u8,
+                max_response_code,
+                bytes5to8,
+            } => checksum::Sum16BitWords::new()
+                .add_2bytes([*type_u8, *max_response_code])
+                .add_4bytes(*bytes5to8),
+            MembershipQuery(header) => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_MEMBERSHIP_QUERY, header.max_response_time])
+                .add_4bytes(header.group_address),
+            MembershipQueryV3(header) => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_MEMBERSHIP_QUERY, header.max_response_code])
+                .add_4bytes(header.group_address)
+                .add_2bytes([header.flags_u8(), header.querier_query_interval_code])
+                .add_2bytes(header.number_of_sources.to_be_bytes()),
+            MembershipReportV1 { group_address } => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_MEMBERSHIP_REPORT_V1, 0])
+                .add_4bytes(*group_address),
+            MembershipReportV2 { group_address } => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_MEMBERSHIP_REPORT_V2, 0])
+                .add_4bytes(*group_address),
+            LeaveGroup { group_address } => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_LEAVE_GROUP, 0])
+                .add_4bytes(*group_address),
+            MembershipReportV3 { number_of_records } => checksum::Sum16BitWords::new()
+                .add_2bytes([TYPE_MEMBERSHIP_REPORT_V3, 0])
+                .add_2bytes(number_of_records.to_be_bytes()),
+        }
+        .add_slice(payload)
+        .ones_complement()
+        .to_be()
+    }
+}
+
+/// A header of an IGMP message.
+///
+/// What is part of the header depends on the IGMP type (see
+/// [`IgmpType`]). Variable length parts (source addresses & group
+/// records) are part of the payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IgmpHeader {
+    /// Type & type specific values.
+    pub igmp_type: IgmpType,
+    /// Checksum in the IGMP header.
+    pub checksum: u16,
+}
+
+impl IgmpHeader {
+    /// Minimum number of bytes/octets an IgmpHeader takes up
+    /// in serialized form.
+    pub const MIN_SERIALIZED_SIZE: usize = 8;
+
+    /// Maximum number of bytes/octets an IgmpHeader takes up
+    /// in serialized form (size of an IGMPv3 query without
+    /// source addresses).
+    pub const MAX_SERIALIZED_SIZE: usize = 12;
+
+    /// Constructs an [`IgmpHeader`] using the given type
+    /// and the checksum set to 0.
+    pub fn new(igmp_type: IgmpType) -> IgmpHeader {
+        IgmpHeader {
+            igmp_type,
+            checksum: 0,
+        }
+    }
+
+    /// Creates a [`IgmpHeader`] with a checksum calculated based on the given payload.
+    pub fn with_checksum(igmp_type: IgmpType, payload: &[u8]) -> IgmpHeader {
+        let checksum = igmp_type.calc_checksum(payload);
+        IgmpHeader {
+            igmp_type,
+            checksum,
+        }
+    }
+
+    /// Reads an IGMP header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(IgmpHeader, &[u8]), ReadError> {
+        let s = IgmpSlice::from_slice(slice)?;
+        Ok((s.header(), s.payload()))
+    }
+
+    /// Reads an IGMP header from the given reader.
+    ///
+    /// Note that queries are always read as IGMPv1/IGMPv2 queries
+    /// ([`IgmpType::MembershipQuery`]) as the version of a query can only
+    /// be determined based on the length of the message.
+    #[cfg(feature = "std")]
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<IgmpHeader, ReadError> {
+        let mut bytes = [0u8; IgmpHeader::MIN_SERIALIZED_SIZE];
+        reader.read_exact(&mut bytes)?;
+        Ok(IgmpSlice { slice: &bytes }.header())
+    }
+
+    /// Write the IGMP header to the given writer.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()).map_err(WriteError::from)
+    }
+
+    /// Write the IGMP header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        let bytes = self.to_bytes();
+        //length check
+        if slice.len() < bytes.len() {
+            Err(WriteError::SliceTooSmall(bytes.len()))
+        } else {
+            slice[..bytes.len()].copy_from_slice(&bytes);
+            Ok(&mut slice[bytes.len()..])
+        }
+    }
+
+    /// Length in bytes/octets of this header type.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        self.igmp_type.header_len()
+    }
+
+    /// Calculates & updates the checksum in the header.
+    pub fn update_checksum(&mut self, payload: &[u8]) {
+        self.checksum = self.igmp_type.calc_checksum(payload);
+    }
+
+    /// Converts the header to the on the wire bytes.
+    #[rustfmt::skip]
+    pub fn to_bytes(&self) -> ArrayVec<u8, { IgmpHeader::MAX_SERIALIZED_SIZE }> {
+        let checksum_be = self.checksum.to_be_bytes();
+        let re_4u8 = |type_u8: u8,
+                      byte2: u8,
+                      bytes5to8: [u8; 4]|
+         -> ArrayVec<u8, { IgmpHeader::MAX_SERIALIZED_SIZE }> {
+
+            #[rustfmt::skip]
+            let mut re = ArrayVec::from([
+                type_u8, byte2, checksum_be[0], checksum_be[1],
+                bytes5to8[0], bytes5to8[1], bytes5to8[2], bytes5to8[3],
+                0, 0, 0, 0,
+            ]);
+            // SAFETY: Safe as u8 has no destruction behavior and as 8 is smaller then 12.
+            unsafe {
+                re.set_len(8);
+            }
+            re
+        };
+
+        use IgmpType::*;
+        match self.igmp_type {
+            Unknown {
+                type_u8,
+                max_response_code,
+                bytes5to8,
+            } => re_4u8(type_u8, max_response_code, bytes5to8),
+            MembershipQuery(ref header) => re_4u8(
+                TYPE_MEMBERSHIP_QUERY,
+                header.max_response_time,
+                header.group_address,
+            ),
+            MembershipQueryV3(ref header) => {
+                let g = header.group_address;
+                let n = header.number_of_sources.to_be_bytes();
+                ArrayVec::from([
+                    TYPE_MEMBERSHIP_QUERY, header.max_response_code, checksum_be[0], checksum_be[1],
+                    g[0], g[1], g[2], g[3],
+                    header.flags_u8(), header.querier_query_interval_code, n[0], n[1],
+                ])
+            }
+            MembershipReportV1 { group_address } => re_4u8(TYPE_MEMBERSHIP_REPORT_V1, 0, group_address),
+            MembershipReportV2 { group_address } => re_4u8(TYPE_MEMBERSHIP_REPORT_V2, 0, group_address),
+            LeaveGroup { group_address } => re_4u8(TYPE_LEAVE_GROUP, 0, group_address),
+            MembershipReportV3 { number_of_records } => {
+                let n = number_of_records.to_be_bytes();
+                re_4u8(TYPE_MEMBERSHIP_REPORT_V3, 0, [0, 0, n[0], n[1]])
+            }
+        }
+    }
+}
+
+/// A slice containing an IGMP message.
+///
+/// Struct allows the selective read of fields in the IGMP
+/// message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IgmpSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IgmpSlice<'a> {
+    /// Creates a slice containing an IGMP message.
+    ///
+    /// # Errors
+    ///
+    /// The function will return an `Err` `ReadError::UnexpectedEndOfSlice`
+    /// if the given slice is too small.
+    #[inline]
+    pub fn from_slice(slice: &'a [u8]) -> Result<IgmpSlice<'a>, ReadError> {
+        use ReadError::*;
+        if slice.len() < IgmpHeader::MIN_SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(IgmpHeader::MIN_SERIALIZED_SIZE));
+        }
+        Ok(IgmpSlice { slice })
+    }
+
+    /// Decode the header values into an [`IgmpHeader`] struct.
+    #[inline]
+    pub fn header(&self) -> IgmpHeader {
+        IgmpHeader {
+            igmp_type: self.igmp_type(),
+            checksum: self.checksum(),
+        }
+    }
+
+    /// Number of bytes/octets that will be converted into a
+    /// [`IgmpHeader`] when [`IgmpSlice::header`] gets called.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        if TYPE_MEMBERSHIP_QUERY == self.type_u8()
+            && self.slice.len() >= MembershipQueryV3Header::SERIALIZED_SIZE
+        {
+            MembershipQueryV3Header::SERIALIZED_SIZE
+        } else {
+            8
+        }
+    }
+
+    /// Decode the header values (excluding the checksum) into an [`IgmpType`] enum.
+    pub fn igmp_type(&self) -> IgmpType {
+        use IgmpType::*;
+
+        match self.type_u8() {
+            TYPE_MEMBERSHIP_QUERY => {
+                return if self.slice.len() >= MembershipQueryV3Header::SERIALIZED_SIZE {
+                    // SAFETY:
+                    // Safe as the slice length is checked to be at least
+                    // MembershipQueryV3Header::SERIALIZED_SIZE (12).
+                    let (flags, qqic, number_of_sources) = unsafe {
+                        (
+                            *self.slice.get_unchecked(8),
+                            *self.slice.get_unchecked(9),
+                            get_unchecked_be_u16(self.slice.as_ptr().add(10)),
+                        )
+                    };
+                    MembershipQueryV3(MembershipQueryV3Header {
+                        max_response_code: self.max_response_code(),
+                        group_address: self.bytes5to8(),
+                        suppress_router_side_processing: 0 != flags & 0b1000,
+                        querier_robustness_variable: flags & 0b111,
+                        querier_query_interval_code: qqic,
+                        number_of_sources,
+                    })
+                } else {
+                    MembershipQuery(MembershipQueryHeader {
+                        max_response_time: self.max_response_code(),
+                        group_address: self.bytes5to8(),
+                    })
+                };
+            }
+            TYPE_MEMBERSHIP_REPORT_V1 if 0 == self.max_response_code() => {
+                return MembershipReportV1 {
+                    group_address: self.bytes5to8(),
+                };
+            }
+            TYPE_MEMBERSHIP_REPORT_V2 if 0 == self.max_response_code() => {
+                return MembershipReportV2 {
+                    group_address: self.bytes5to8(),
+                };
+            }
+            TYPE_LEAVE_GROUP if 0 == self.max_response_code() => {
+                return LeaveGroup {
+                    group_address: self.bytes5to8(),
+                };
+            }
+            TYPE_MEMBERSHIP_REPORT_V3 => {
+                let bytes5to8 = self.bytes5to8();
+                if 0 == self.max_response_code() && 0 == bytes5to8[0] && 0 == bytes5to8[1] {
+                    return MembershipReportV3 {
+                        number_of_records: u16::from_be_bytes([bytes5to8[2], bytes5to8[3]]),
+                    };
+                }
+            }
+            _ => {}
+        }
+        Unknown {
+            type_u8: self.type_u8(),
+            max_response_code: self.max_response_code(),
+            bytes5to8: self.bytes5to8(),
+        }
+    }
+
+    /// Returns "type" value in the IGMP header.
+    #[inline]
+    pub fn type_u8(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of IgmpHeader::MIN_SERIALIZED_SIZE (8).
+        unsafe { *self.slice.get_unchecked(0) }
+    }
+
+    /// Returns the second byte in the IGMP header ("Max Resp Code" for
+    /// queries, reserved/unused for other message types).
+    #[inline]
+    pub fn max_response_code(&self) -> u8 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of IgmpHeader::MIN_SERIALIZED_SIZE (8).
+        unsafe { *self.slice.get_unchecked(1) }
+    }
+
+    /// Returns "checksum" value in the IGMP header.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of IgmpHeader::MIN_SERIALIZED_SIZE (8).
+        unsafe { get_unchecked_be_u16(self.slice.as_ptr().add(2)) }
+    }
+
+    /// Returns the bytes from position 4 till and including the 8th position
+    /// in the IGMP header (the group address for all message types except
+    /// IGMPv3 reports).
+    #[inline]
+    pub fn bytes5to8(&self) -> [u8; 4] {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of IgmpHeader::MIN_SERIALIZED_SIZE (8).
+        unsafe { get_unchecked_4_byte_array(self.slice.as_ptr().add(4)) }
+    }
+
+    /// Returns if the checksum in the slice is correct.
+    pub fn is_checksum_valid(&self) -> bool {
+        // NOTE: From RFC 1071
+        // To check a checksum, the 1's complement sum is computed over the
+        // same set of octets, including the checksum field.  If the result
+        // is all 1 bits (-0 in 1's complement arithmetic), the check
+        // succeeds.
+        checksum::Sum16BitWords::new()
+            .add_slice(self.slice)
+            .ones_complement()
+            == 0
+    }
+
+    /// Returns an iterator over the group records if the slice
+    /// contains an IGMPv3 report ([`IgmpType::MembershipReportV3`]).
+    ///
+    /// Returns `None` if the slice contains a different message.
+    pub fn group_records(&self) -> Option<GroupRecordIterator<'a>> {
+        match self.igmp_type() {
+            IgmpType::MembershipReportV3 { number_of_records } => {
+                Some(GroupRecordIterator::new(number_of_records, self.payload()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the source addresses if the slice contains
+    /// an IGMPv3 query ([`IgmpType::MembershipQueryV3`]).
+    ///
+    /// The iteration is limited by the "Number of Sources" field and
+    /// the length of the slice. Returns `None` if the slice contains a
+    /// different message.
+    pub fn query_v3_sources(&self) -> Option<SourceAddressIterator<'a>> {
+        match self.igmp_type() {
+            IgmpType::MembershipQueryV3(header) => {
+                let payload = self.payload();
+                let len = core::cmp::min(
+                    payload.len(),
+                    usize::from(header.number_of_sources) * 4
+                );
+                Some(SourceAddressIterator::from_slice(&payload[..len]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a slice to the bytes not covered by `.header()`.
+    ///
+    /// | `.header().igmp_type` or `.igmp_type()` | Payload Content                                  |
+    /// |-----------------------------------------|--------------------------------------------------|
+    /// | [`IgmpType::MembershipQueryV3`]         | Source addresses                                 |
+    /// | [`IgmpType::MembershipReportV3`]        | Group records                                    |
+    /// | All other types                         | Everything after the 8th byte/octet of the message |
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        let header_len = self.header_len();
+        // SAFETY:
+        // Safe as header_len() returns at most the length of the slice
+        // (8 is checked in the constructor & 12 only if the slice is long enough).
+        unsafe { from_raw_parts(self.slice.as_ptr().add(header_len), self.slice.len() - header_len) }
+    }
+
+    /// Returns the slice containing the IGMP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+}
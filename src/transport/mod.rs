@@ -1,20 +1,32 @@
+pub mod dccp_impl;
 pub mod icmp;
+pub mod icmp_extension_impl;
 pub mod icmpv4_impl;
 pub mod icmpv6_impl;
+pub mod igmp_impl;
+pub mod mptcp_impl;
+pub mod sctp_impl;
 pub mod udp;
+pub mod udp_lite;
 pub mod tcp;
 
 use super::*;
 
+#[cfg(feature = "std")]
 use std::io;
 
 ///The possible headers on the transport layer
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum TransportHeader {
     Udp(udp::UdpHeader),
     Tcp(tcp::TcpHeader),
     Icmpv4(Icmpv4Header),
     Icmpv6(Icmpv6Header),
+    Igmp(IgmpHeader),
+    Sctp(SctpHeader),
+    UdpLite(UdpLiteHeader),
+    Dccp(DccpHeader),
 }
 
 impl TransportHeader {
@@ -107,6 +119,94 @@ impl TransportHeader {
         }
     }
 
+    /// Returns Result::Some containing the IGMP header if self has the value Igmp.
+    /// Otherwise None is returned.
+    pub fn igmp(self) -> Option<IgmpHeader> {
+        use crate::TransportHeader::*;
+        if let Igmp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the IGMP header if self has the value Igmp.
+    /// Otherwise None is returned.
+    pub fn mut_igmp(&mut self) -> Option<&mut IgmpHeader> {
+        use crate::TransportHeader::*;
+        if let Igmp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the SCTP header if self has the value Sctp.
+    /// Otherwise None is returned.
+    pub fn sctp(self) -> Option<SctpHeader> {
+        use crate::TransportHeader::*;
+        if let Sctp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the SCTP header if self has the value Sctp.
+    /// Otherwise None is returned.
+    pub fn mut_sctp(&mut self) -> Option<&mut SctpHeader> {
+        use crate::TransportHeader::*;
+        if let Sctp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the UDP-Lite header if self has the value UdpLite.
+    /// Otherwise None is returned.
+    pub fn udp_lite(self) -> Option<UdpLiteHeader> {
+        use crate::TransportHeader::*;
+        if let UdpLite(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the UDP-Lite header if self has the value UdpLite.
+    /// Otherwise None is returned.
+    pub fn mut_udp_lite(&mut self) -> Option<&mut UdpLiteHeader> {
+        use crate::TransportHeader::*;
+        if let UdpLite(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the DCCP header if self has the value Dccp.
+    /// Otherwise None is returned.
+    pub fn dccp(self) -> Option<DccpHeader> {
+        use crate::TransportHeader::*;
+        if let Dccp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the DCCP header if self has the value Dccp.
+    /// Otherwise None is returned.
+    pub fn mut_dccp(&mut self) -> Option<&mut DccpHeader> {
+        use crate::TransportHeader::*;
+        if let Dccp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
     /// Returns the size of the transport header (in case of UDP fixed,
     /// in case of TCP cotanining the options).
     pub fn header_len(&self) -> usize {
@@ -116,6 +216,10 @@ impl TransportHeader {
             Tcp(value) => usize::from(value.header_len()),
             Icmpv4(value) => value.header_len(),
             Icmpv6(value) => value.header_len(),
+            Igmp(value) => value.header_len(),
+            Sctp(value) => value.header_len(),
+            UdpLite(value) => value.header_len(),
+            Dccp(value) => value.header_len(),
         }
     }
 
@@ -134,6 +238,18 @@ impl TransportHeader {
                 header.update_checksum(payload);
             },
             Icmpv6(_) => return Err(ValueError::Icmpv6InIpv4),
+            Igmp(header) => {
+                header.update_checksum(payload);
+            },
+            Sctp(header) => {
+                header.update_checksum(payload);
+            },
+            UdpLite(header) => {
+                header.checksum = header.calc_checksum_ipv4(ip_header, payload)?;
+            },
+            Dccp(header) => {
+                header.checksum = header.calc_checksum_ipv4(ip_header, payload)?;
+            },
         }
         Ok(())
     }
@@ -145,24 +261,53 @@ impl TransportHeader {
         match self {
             Icmpv4(header) => header.update_checksum(payload),
             Icmpv6(header) => header.update_checksum(ip_header.source, ip_header.destination, payload)?,
+            Igmp(header) => header.update_checksum(payload),
+            Sctp(header) => header.update_checksum(payload),
             Udp(header) => {
                 header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
             },
             Tcp(header) => {
                 header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
+            },
+            UdpLite(header) => {
+                header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
+            },
+            Dccp(header) => {
+                header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
             }
         }
         Ok(())
     }
 
     /// Write the transport header to the given writer.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         use crate::TransportHeader::*;
         match self {
             Icmpv4(value) => value.write(writer),
             Icmpv6(value) => value.write(writer),
+            Igmp(value) => value.write(writer),
+            Sctp(value) => value.write(writer),
             Udp(value) => value.write(writer),
-            Tcp(value) => value.write(writer).map_err(WriteError::from)
+            Tcp(value) => value.write(writer).map_err(WriteError::from),
+            UdpLite(value) => value.write(writer),
+            Dccp(value) => value.write(writer),
+        }
+    }
+
+    /// Write the transport header to the start of the given slice.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use crate::TransportHeader::*;
+        match self {
+            Icmpv4(value) => value.write_to_slice(slice),
+            Icmpv6(value) => value.write_to_slice(slice),
+            Igmp(value) => value.write_to_slice(slice),
+            Sctp(value) => value.write_to_slice(slice),
+            Udp(value) => value.write_to_slice(slice),
+            Tcp(value) => value.write_to_slice(slice),
+            UdpLite(value) => value.write_to_slice(slice),
+            Dccp(value) => value.write_to_slice(slice),
         }
     }
 }
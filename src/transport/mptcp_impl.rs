@@ -0,0 +1,480 @@
+use super::super::*;
+
+/// Decoded Multipath TCP option (TCP option kind 30) according to
+/// [RFC 8684](https://datatracker.ietf.org/doc/html/rfc8684).
+///
+/// Only the MP_CAPABLE, MP_JOIN, DSS & ADD_ADDR subtypes are decoded. Other
+/// subtypes and options with non zero reserved bits are returned as
+/// [`TcpOptionElement::Unknown`] by the [`TcpOptionsIterator`] so no
+/// information gets lost when the options are written again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MptcpOption {
+    /// "Multipath Capable" option (subtype 0).
+    MpCapable{
+        /// MPTCP version (4 bits).
+        version: u8,
+        /// Flags "A" to "H".
+        flags: u8,
+        /// Keys (and data) present in the option (defines the option length).
+        keys: MptcpCapableKeys,
+    },
+    /// "Join Connection" option (subtype 1) in a SYN segment.
+    MpJoinSyn{
+        /// True if the "B" (backup) flag is set.
+        backup: bool,
+        address_id: u8,
+        receiver_token: u32,
+        sender_random_number: u32,
+    },
+    /// "Join Connection" option (subtype 1) in a SYN/ACK segment.
+    MpJoinSynAck{
+        /// True if the "B" (backup) flag is set.
+        backup: bool,
+        address_id: u8,
+        truncated_hmac: u64,
+        sender_random_number: u32,
+    },
+    /// "Join Connection" option (subtype 1) in the third ACK.
+    MpJoinAck{
+        hmac: [u8;20],
+    },
+    /// "Data Sequence Signal" option (subtype 2).
+    Dss{
+        /// True if the "F" (DATA_FIN) flag is set.
+        data_fin: bool,
+        /// Data ACK (present if the "A" flag is set).
+        data_ack: Option<MptcpDssNumber>,
+        /// Data sequence mapping (present if the "M" flag is set).
+        mapping: Option<MptcpDssMapping>,
+    },
+    /// "Add Address" option (subtype 3).
+    AddAddr{
+        address_id: u8,
+        address: MptcpAddress,
+        port: Option<u16>,
+        /// Truncated HMAC. `None` if the "E" (echo) flag is set.
+        truncated_hmac: Option<u64>,
+    },
+}
+
+/// Keys & data contained in a MP_CAPABLE option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MptcpCapableKeys {
+    /// No keys present (SYN in version 1, option length 4).
+    None,
+    /// Only the key of the sender is present (option length 12).
+    Sender(u64),
+    /// Keys of the sender & receiver are present (option length 20).
+    SenderReceiver{
+        sender_key: u64,
+        receiver_key: u64,
+    },
+    /// Keys of the sender & receiver plus the data-level length and
+    /// an optional checksum (option length 22 or 24).
+    WithData{
+        sender_key: u64,
+        receiver_key: u64,
+        data_level_length: u16,
+        checksum: Option<u16>,
+    },
+}
+
+/// 4 or 8 byte number used in the DSS option (size is selected
+/// via the "a" & "m" flags).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MptcpDssNumber {
+    U32(u32),
+    U64(u64),
+}
+
+impl MptcpDssNumber {
+    /// Returns the number of bytes used to encode the number.
+    #[inline]
+    fn encoded_len(&self) -> usize {
+        match self {
+            MptcpDssNumber::U32(_) => 4,
+            MptcpDssNumber::U64(_) => 8,
+        }
+    }
+
+    /// Writes the number to the start of the given slice.
+    fn write(&self, target: &mut [u8]) {
+        match self {
+            MptcpDssNumber::U32(value) => target[..4].copy_from_slice(&value.to_be_bytes()),
+            MptcpDssNumber::U64(value) => target[..8].copy_from_slice(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Data sequence mapping contained in a DSS option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MptcpDssMapping {
+    pub data_sequence_number: MptcpDssNumber,
+    pub subflow_sequence_number: u32,
+    pub data_level_length: u16,
+    /// Checksum (only present if checksums were negotiated).
+    pub checksum: Option<u16>,
+}
+
+/// Address advertised in an ADD_ADDR option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MptcpAddress {
+    Ipv4([u8;4]),
+    Ipv6([u8;16]),
+}
+
+/// Module containing the constants for the multipath tcp option.
+pub mod mptcp {
+    /// MP_CAPABLE subtype value.
+    pub const SUBTYPE_MP_CAPABLE: u8 = 0;
+    /// MP_JOIN subtype value.
+    pub const SUBTYPE_MP_JOIN: u8 = 1;
+    /// DSS (data sequence signal) subtype value.
+    pub const SUBTYPE_DSS: u8 = 2;
+    /// ADD_ADDR subtype value.
+    pub const SUBTYPE_ADD_ADDR: u8 = 3;
+    /// REMOVE_ADDR subtype value.
+    pub const SUBTYPE_REMOVE_ADDR: u8 = 4;
+    /// MP_PRIO subtype value.
+    pub const SUBTYPE_MP_PRIO: u8 = 5;
+    /// MP_FAIL subtype value.
+    pub const SUBTYPE_MP_FAIL: u8 = 6;
+    /// MP_FASTCLOSE subtype value.
+    pub const SUBTYPE_MP_FASTCLOSE: u8 = 7;
+    /// MP_TCPRST subtype value.
+    pub const SUBTYPE_MP_TCPRST: u8 = 8;
+
+    /// "A" flag in the DSS option (data ACK present).
+    pub const DSS_FLAG_DATA_ACK: u8 = 0b0000_0001;
+    /// "a" flag in the DSS option (data ACK is 8 bytes).
+    pub const DSS_FLAG_DATA_ACK_8: u8 = 0b0000_0010;
+    /// "M" flag in the DSS option (mapping present).
+    pub const DSS_FLAG_MAPPING: u8 = 0b0000_0100;
+    /// "m" flag in the DSS option (data sequence number is 8 bytes).
+    pub const DSS_FLAG_DSN_8: u8 = 0b0000_1000;
+    /// "F" flag in the DSS option (DATA_FIN).
+    pub const DSS_FLAG_DATA_FIN: u8 = 0b0001_0000;
+}
+
+impl MptcpOption {
+
+    /// Returns the length of the option in bytes (including the kind & length bytes).
+    pub fn option_len(&self) -> usize {
+        use MptcpOption::*;
+        match self {
+            MpCapable{ keys, .. } => match keys {
+                MptcpCapableKeys::None => 4,
+                MptcpCapableKeys::Sender(_) => 12,
+                MptcpCapableKeys::SenderReceiver{ .. } => 20,
+                MptcpCapableKeys::WithData{ checksum: None, .. } => 22,
+                MptcpCapableKeys::WithData{ checksum: Some(_), .. } => 24,
+            },
+            MpJoinSyn{ .. } => 12,
+            MpJoinSynAck{ .. } => 16,
+            MpJoinAck{ .. } => 24,
+            Dss{ data_ack, mapping, .. } => {
+                4 + data_ack.map(|v| v.encoded_len()).unwrap_or(0) +
+                match mapping {
+                    None => 0,
+                    Some(m) => m.data_sequence_number.encoded_len() + 6 + if m.checksum.is_some() { 2 } else { 0 },
+                }
+            },
+            AddAddr{ address, port, truncated_hmac, .. } => {
+                4 +
+                match address {
+                    MptcpAddress::Ipv4(_) => 4,
+                    MptcpAddress::Ipv6(_) => 16,
+                } +
+                if port.is_some() { 2 } else { 0 } +
+                if truncated_hmac.is_some() { 8 } else { 0 }
+            },
+        }
+    }
+
+    /// Writes the option (including kind & length) to the start of the given
+    /// slice. The slice has to have at least the length `option_len()`.
+    pub(crate) fn write_option(&self, target: &mut [u8]) {
+        use MptcpOption::*;
+        use mptcp::*;
+
+        let len = self.option_len();
+        target[0] = tcp_option::KIND_MULTIPATH_TCP;
+        target[1] = len as u8;
+        match self {
+            MpCapable{ version, flags, keys } => {
+                target[2] = (SUBTYPE_MP_CAPABLE << 4) | (version & 0xf);
+                target[3] = *flags;
+                match keys {
+                    MptcpCapableKeys::None => {},
+                    MptcpCapableKeys::Sender(sender_key) => {
+                        target[4..12].copy_from_slice(&sender_key.to_be_bytes());
+                    },
+                    MptcpCapableKeys::SenderReceiver{ sender_key, receiver_key } => {
+                        target[4..12].copy_from_slice(&sender_key.to_be_bytes());
+                        target[12..20].copy_from_slice(&receiver_key.to_be_bytes());
+                    },
+                    MptcpCapableKeys::WithData{ sender_key, receiver_key, data_level_length, checksum } => {
+                        target[4..12].copy_from_slice(&sender_key.to_be_bytes());
+                        target[12..20].copy_from_slice(&receiver_key.to_be_bytes());
+                        target[20..22].copy_from_slice(&data_level_length.to_be_bytes());
+                        if let Some(checksum) = checksum {
+                            target[22..24].copy_from_slice(&checksum.to_be_bytes());
+                        }
+                    },
+                }
+            },
+            MpJoinSyn{ backup, address_id, receiver_token, sender_random_number } => {
+                target[2] = (SUBTYPE_MP_JOIN << 4) | (if *backup { 1 } else { 0 });
+                target[3] = *address_id;
+                target[4..8].copy_from_slice(&receiver_token.to_be_bytes());
+                target[8..12].copy_from_slice(&sender_random_number.to_be_bytes());
+            },
+            MpJoinSynAck{ backup, address_id, truncated_hmac, sender_random_number } => {
+                target[2] = (SUBTYPE_MP_JOIN << 4) | (if *backup { 1 } else { 0 });
+                target[3] = *address_id;
+                target[4..12].copy_from_slice(&truncated_hmac.to_be_bytes());
+                target[12..16].copy_from_slice(&sender_random_number.to_be_bytes());
+            },
+            MpJoinAck{ hmac } => {
+                target[2] = SUBTYPE_MP_JOIN << 4;
+                target[3] = 0;
+                target[4..24].copy_from_slice(hmac);
+            },
+            Dss{ data_fin, data_ack, mapping } => {
+                target[2] = SUBTYPE_DSS << 4;
+                let mut flags = if *data_fin { DSS_FLAG_DATA_FIN } else { 0 };
+                let mut offset = 4;
+                if let Some(data_ack) = data_ack {
+                    flags |= DSS_FLAG_DATA_ACK;
+                    if let MptcpDssNumber::U64(_) = data_ack {
+                        flags |= DSS_FLAG_DATA_ACK_8;
+                    }
+                    data_ack.write(&mut target[offset..]);
+                    offset += data_ack.encoded_len();
+                }
+                if let Some(mapping) = mapping {
+                    flags |= DSS_FLAG_MAPPING;
+                    if let MptcpDssNumber::U64(_) = mapping.data_sequence_number {
+                        flags |= DSS_FLAG_DSN_8;
+                    }
+                    mapping.data_sequence_number.write(&mut target[offset..]);
+                    offset += mapping.data_sequence_number.encoded_len();
+                    target[offset..offset + 4].copy_from_slice(&mapping.subflow_sequence_number.to_be_bytes());
+                    target[offset + 4..offset + 6].copy_from_slice(&mapping.data_level_length.to_be_bytes());
+                    if let Some(checksum) = mapping.checksum {
+                        target[offset + 6..offset + 8].copy_from_slice(&checksum.to_be_bytes());
+                    }
+                }
+                target[3] = flags;
+            },
+            AddAddr{ address_id, address, port, truncated_hmac } => {
+                target[2] = (SUBTYPE_ADD_ADDR << 4) | (if truncated_hmac.is_none() { 1 } else { 0 });
+                target[3] = *address_id;
+                let mut offset = match address {
+                    MptcpAddress::Ipv4(value) => {
+                        target[4..8].copy_from_slice(value);
+                        8
+                    },
+                    MptcpAddress::Ipv6(value) => {
+                        target[4..20].copy_from_slice(value);
+                        20
+                    },
+                };
+                if let Some(port) = port {
+                    target[offset..offset + 2].copy_from_slice(&port.to_be_bytes());
+                    offset += 2;
+                }
+                if let Some(hmac) = truncated_hmac {
+                    target[offset..offset + 8].copy_from_slice(&hmac.to_be_bytes());
+                }
+            },
+        }
+    }
+
+    /// Decodes a multipath tcp option (the slice has to contain the complete
+    /// option including the kind & length byte and nothing else).
+    ///
+    /// Returns `Ok(None)` if the subtype is not supported or reserved bits are
+    /// set (in this case the option should be treated as an unknown option).
+    pub(crate) fn from_option_slice(option: &[u8]) -> Result<Option<MptcpOption>, TcpOptionReadError> {
+        use MptcpOption::*;
+        use mptcp::*;
+
+        let len = option.len();
+        let bad_size = || TcpOptionReadError::UnexpectedSize{
+            option_id: tcp_option::KIND_MULTIPATH_TCP,
+            size: len as u8,
+        };
+        if len < 4 {
+            return if len < 3 {
+                Err(bad_size())
+            } else {
+                // no subtype with a length of 3 is decoded
+                Ok(None)
+            };
+        }
+
+        let u16_at = |offset: usize| u16::from_be_bytes([option[offset], option[offset + 1]]);
+        let u32_at = |offset: usize| u32::from_be_bytes([option[offset], option[offset + 1], option[offset + 2], option[offset + 3]]);
+        let u64_at = |offset: usize| {
+            let mut bytes = [0u8;8];
+            bytes.copy_from_slice(&option[offset..offset + 8]);
+            u64::from_be_bytes(bytes)
+        };
+
+        let subtype = option[2] >> 4;
+        let low_bits = option[2] & 0xf;
+        match subtype {
+            SUBTYPE_MP_CAPABLE => {
+                let keys = match len {
+                    4 => MptcpCapableKeys::None,
+                    12 => MptcpCapableKeys::Sender(u64_at(4)),
+                    20 => MptcpCapableKeys::SenderReceiver{
+                        sender_key: u64_at(4),
+                        receiver_key: u64_at(12),
+                    },
+                    22 | 24 => MptcpCapableKeys::WithData{
+                        sender_key: u64_at(4),
+                        receiver_key: u64_at(12),
+                        data_level_length: u16_at(20),
+                        checksum: if 24 == len { Some(u16_at(22)) } else { None },
+                    },
+                    _ => return Err(bad_size()),
+                };
+                Ok(Some(MpCapable{
+                    version: low_bits,
+                    flags: option[3],
+                    keys,
+                }))
+            },
+            SUBTYPE_MP_JOIN => {
+                match len {
+                    12 | 16 if 0 == low_bits & 0b1110 => {
+                        let backup = 0 != low_bits & 1;
+                        let address_id = option[3];
+                        Ok(Some(if 12 == len {
+                            MpJoinSyn{
+                                backup,
+                                address_id,
+                                receiver_token: u32_at(4),
+                                sender_random_number: u32_at(8),
+                            }
+                        } else {
+                            MpJoinSynAck{
+                                backup,
+                                address_id,
+                                truncated_hmac: u64_at(4),
+                                sender_random_number: u32_at(12),
+                            }
+                        }))
+                    },
+                    24 if 0 == low_bits && 0 == option[3] => {
+                        let mut hmac = [0u8;20];
+                        hmac.copy_from_slice(&option[4..24]);
+                        Ok(Some(MpJoinAck{ hmac }))
+                    },
+                    12 | 16 | 24 => Ok(None),
+                    _ => Err(bad_size()),
+                }
+            },
+            SUBTYPE_DSS => {
+                let flags = option[3];
+                let has_ack = 0 != flags & DSS_FLAG_DATA_ACK;
+                let has_mapping = 0 != flags & DSS_FLAG_MAPPING;
+                // reserved bits & size flags without the matching
+                // presence flag can not be represented
+                if 0 != low_bits ||
+                   0 != flags & 0b1110_0000 ||
+                   (!has_ack && 0 != flags & DSS_FLAG_DATA_ACK_8) ||
+                   (!has_mapping && 0 != flags & DSS_FLAG_DSN_8)
+                {
+                    return Ok(None);
+                }
+                let ack_len = if has_ack {
+                    if 0 != flags & DSS_FLAG_DATA_ACK_8 { 8 } else { 4 }
+                } else {
+                    0
+                };
+                let dsn_len = if 0 != flags & DSS_FLAG_DSN_8 { 8 } else { 4 };
+                let mapping_len = if has_mapping { dsn_len + 6 } else { 0 };
+                let with_checksum = if len == 4 + ack_len + mapping_len {
+                    false
+                } else if has_mapping && len == 4 + ack_len + mapping_len + 2 {
+                    true
+                } else {
+                    return Err(bad_size());
+                };
+
+                let number_at = |offset: usize, size: usize| {
+                    if 8 == size {
+                        MptcpDssNumber::U64(u64_at(offset))
+                    } else {
+                        MptcpDssNumber::U32(u32_at(offset))
+                    }
+                };
+                let data_ack = if has_ack {
+                    Some(number_at(4, ack_len))
+                } else {
+                    None
+                };
+                let mapping = if has_mapping {
+                    let offset = 4 + ack_len;
+                    Some(MptcpDssMapping{
+                        data_sequence_number: number_at(offset, dsn_len),
+                        subflow_sequence_number: u32_at(offset + dsn_len),
+                        data_level_length: u16_at(offset + dsn_len + 4),
+                        checksum: if with_checksum {
+                            Some(u16_at(offset + dsn_len + 6))
+                        } else {
+                            None
+                        },
+                    })
+                } else {
+                    None
+                };
+                Ok(Some(Dss{
+                    data_fin: 0 != flags & DSS_FLAG_DATA_FIN,
+                    data_ack,
+                    mapping,
+                }))
+            },
+            SUBTYPE_ADD_ADDR => {
+                if 0 != low_bits & 0b1110 {
+                    return Ok(None);
+                }
+                let echo = 0 != low_bits & 1;
+                let hmac_len = if echo { 0 } else { 8 };
+                // options too short to contain the truncated hmac are rejected
+                let (address, address_len) = match len.saturating_sub(hmac_len) {
+                    8 | 10 => {
+                        let mut value = [0u8;4];
+                        value.copy_from_slice(&option[4..8]);
+                        (MptcpAddress::Ipv4(value), 4)
+                    },
+                    20 | 22 => {
+                        let mut value = [0u8;16];
+                        value.copy_from_slice(&option[4..20]);
+                        (MptcpAddress::Ipv6(value), 16)
+                    },
+                    _ => return Err(bad_size()),
+                };
+                let port = if len - hmac_len == 4 + address_len + 2 {
+                    Some(u16_at(4 + address_len))
+                } else {
+                    None
+                };
+                Ok(Some(AddAddr{
+                    address_id: option[3],
+                    address,
+                    port,
+                    truncated_hmac: if echo {
+                        None
+                    } else {
+                        Some(u64_at(len - 8))
+                    },
+                }))
+            },
+            _ => Ok(None),
+        }
+    }
+}
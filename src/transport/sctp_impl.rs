@@ -0,0 +1,838 @@
+use super::super::*;
+
+use core::slice::from_raw_parts;
+
+/// Module containing SCTP related types and constants.
+pub mod sctp {
+    use crate::*;
+    use core::slice::from_raw_parts;
+
+    /// SCTP chunk type of a "Payload Data" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.1)).
+    pub const CHUNK_TYPE_DATA: u8 = 0;
+
+    /// SCTP chunk type of an "Initiation" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.2)).
+    pub const CHUNK_TYPE_INIT: u8 = 1;
+
+    /// SCTP chunk type of an "Initiation Acknowledgement" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.3)).
+    pub const CHUNK_TYPE_INIT_ACK: u8 = 2;
+
+    /// SCTP chunk type of a "Selective Acknowledgement" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.4)).
+    pub const CHUNK_TYPE_SACK: u8 = 3;
+
+    /// SCTP chunk type of a "Heartbeat Request" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.5)).
+    pub const CHUNK_TYPE_HEARTBEAT: u8 = 4;
+
+    /// SCTP chunk type of a "Heartbeat Acknowledgement" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.6)).
+    pub const CHUNK_TYPE_HEARTBEAT_ACK: u8 = 5;
+
+    /// SCTP chunk type of an "Abort Association" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.7)).
+    pub const CHUNK_TYPE_ABORT: u8 = 6;
+
+    /// SCTP chunk type of a "Shutdown Association" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.8)).
+    pub const CHUNK_TYPE_SHUTDOWN: u8 = 7;
+
+    /// SCTP chunk type of a "Shutdown Acknowledgement" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.9)).
+    pub const CHUNK_TYPE_SHUTDOWN_ACK: u8 = 8;
+
+    /// SCTP chunk type of an "Operation Error" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.10)).
+    pub const CHUNK_TYPE_ERROR: u8 = 9;
+
+    /// SCTP chunk type of a "State Cookie" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.11)).
+    pub const CHUNK_TYPE_COOKIE_ECHO: u8 = 10;
+
+    /// SCTP chunk type of a "Cookie Acknowledgement" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.12)).
+    pub const CHUNK_TYPE_COOKIE_ACK: u8 = 11;
+
+    /// SCTP chunk type of a "Shutdown Complete" chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.3.13)).
+    pub const CHUNK_TYPE_SHUTDOWN_COMPLETE: u8 = 14;
+
+    /// Flag in DATA chunks indicating that the "Immediate" bit is set (defined in [RFC 7053](https://datatracker.ietf.org/doc/html/rfc7053)).
+    pub const DATA_FLAG_IMMEDIATE: u8 = 0b1000;
+
+    /// Flag in DATA chunks indicating that the "Unordered" bit is set.
+    pub const DATA_FLAG_UNORDERED: u8 = 0b100;
+
+    /// Flag in DATA chunks indicating that the chunk is the first fragment of an user message.
+    pub const DATA_FLAG_BEGINNING: u8 = 0b10;
+
+    /// Flag in DATA chunks indicating that the chunk is the last fragment of an user message.
+    pub const DATA_FLAG_ENDING: u8 = 0b1;
+
+    /// Flag in ABORT chunks indicating that the sender did not have a TCB it destroyed.
+    pub const ABORT_FLAG_TCB_NOT_DESTROYED: u8 = 0b1;
+
+    /// Variable length parameter (or error cause) contained in SCTP chunks.
+    ///
+    /// Parameters (e.g. in INIT or HEARTBEAT chunks) and error causes (e.g. in
+    /// ABORT chunks) share the same type-length-value format:
+    ///
+    /// ```text
+    ///  0                   1                   2                   3
+    ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |   Parameter Type/Cause Code   |       Parameter Length        |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// \                                                               \
+    /// /                        Parameter Value                        /
+    /// \                                                               \
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Tlv<'a> {
+        /// Parameter type or error cause code.
+        pub tlv_type: u16,
+        /// Value of the parameter (without the padding).
+        pub value: &'a [u8],
+    }
+
+    /// Iterator over the parameters or error causes contained in a chunk.
+    ///
+    /// After an error was returned the iteration ends.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct TlvIterator<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> TlvIterator<'a> {
+        /// Creates an iterator over the parameters contained in the given slice.
+        pub fn from_slice(slice: &'a [u8]) -> TlvIterator<'a> {
+            TlvIterator { slice }
+        }
+
+        /// Returns the not yet iterated part of the slice.
+        #[inline]
+        pub fn rest(&self) -> &'a [u8] {
+            self.slice
+        }
+    }
+
+    impl<'a> Iterator for TlvIterator<'a> {
+        type Item = Result<Tlv<'a>, ReadError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.slice.is_empty() {
+                return None;
+            }
+            if self.slice.len() < 4 {
+                self.slice = &[];
+                return Some(Err(ReadError::UnexpectedEndOfSlice(4)));
+            }
+            // SAFETY:
+            // Safe as the slice length is checked to be at least 4.
+            let (tlv_type, length) = unsafe {
+                (
+                    get_unchecked_be_u16(self.slice.as_ptr()),
+                    get_unchecked_be_u16(self.slice.as_ptr().add(2)),
+                )
+            };
+            let len = usize::from(length);
+            if len < 4 {
+                self.slice = &[];
+                return Some(Err(ReadError::SctpParameterLengthTooSmall(length)));
+            }
+            if self.slice.len() < len {
+                self.slice = &[];
+                return Some(Err(ReadError::UnexpectedEndOfSlice(len)));
+            }
+            let result = Tlv {
+                tlv_type,
+                // SAFETY:
+                // Safe as the slice length is checked to be at least len.
+                value: unsafe { from_raw_parts(self.slice.as_ptr().add(4), len - 4) },
+            };
+            // skip the parameter and the padding (the padding of the
+            // last parameter is allowed to be missing)
+            let padded_len = core::cmp::min(self.slice.len(), (len + 3) & !3);
+            self.slice = &self.slice[padded_len..];
+            Some(Ok(result))
+        }
+    }
+
+    /// Iterator over the gap ack blocks of a SACK chunk. The start and end
+    /// offsets are returned as a tuple `(start, end)`.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct GapAckBlockIterator<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> GapAckBlockIterator<'a> {
+        /// Creates an iterator over the gap ack blocks contained in the given slice.
+        pub fn from_slice(slice: &'a [u8]) -> GapAckBlockIterator<'a> {
+            GapAckBlockIterator { slice }
+        }
+    }
+
+    impl<'a> Iterator for GapAckBlockIterator<'a> {
+        type Item = (u16, u16);
+
+        fn next(&mut self) -> Option<(u16, u16)> {
+            if self.slice.len() < 4 {
+                None
+            } else {
+                let (block, rest) = self.slice.split_at(4);
+                self.slice = rest;
+                Some((
+                    u16::from_be_bytes([block[0], block[1]]),
+                    u16::from_be_bytes([block[2], block[3]]),
+                ))
+            }
+        }
+    }
+
+    /// Iterator over the duplicate TSNs of a SACK chunk.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct DuplicateTsnIterator<'a> {
+        slice: &'a [u8],
+    }
+
+    impl<'a> DuplicateTsnIterator<'a> {
+        /// Creates an iterator over the duplicate TSNs contained in the given slice.
+        pub fn from_slice(slice: &'a [u8]) -> DuplicateTsnIterator<'a> {
+            DuplicateTsnIterator { slice }
+        }
+    }
+
+    impl<'a> Iterator for DuplicateTsnIterator<'a> {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            if self.slice.len() < 4 {
+                None
+            } else {
+                let (tsn, rest) = self.slice.split_at(4);
+                self.slice = rest;
+                Some(u32::from_be_bytes([tsn[0], tsn[1], tsn[2], tsn[3]]))
+            }
+        }
+    }
+
+    /// Decoded "Payload Data" (DATA) chunk.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct DataChunk<'a> {
+        /// Set if the receiver should send the SACK without delay (RFC 7053).
+        pub immediate: bool,
+        /// Set if the data is an unordered user message.
+        pub unordered: bool,
+        /// Set if the chunk contains the first fragment of an user message.
+        pub beginning: bool,
+        /// Set if the chunk contains the last fragment of an user message.
+        pub ending: bool,
+        /// Transmission sequence number.
+        pub tsn: u32,
+        /// Stream the data belongs to.
+        pub stream_identifier: u16,
+        /// Sequence number of the user message within the stream.
+        pub stream_sequence_number: u16,
+        /// Application specified protocol identifier.
+        pub payload_protocol_identifier: u32,
+        /// The user data (without the padding).
+        pub user_data: &'a [u8],
+    }
+
+    impl<'a> DataChunk<'a> {
+        /// Length of the fixed part of the chunk value (excluding the chunk header).
+        pub const FIXED_VALUE_LEN: usize = 12;
+
+        /// Returns the flags of the chunk.
+        pub fn flags(&self) -> u8 {
+            (if self.immediate { DATA_FLAG_IMMEDIATE } else { 0 })
+                | (if self.unordered { DATA_FLAG_UNORDERED } else { 0 })
+                | (if self.beginning { DATA_FLAG_BEGINNING } else { 0 })
+                | (if self.ending { DATA_FLAG_ENDING } else { 0 })
+        }
+    }
+
+    /// Decoded "Initiation" (INIT) or "Initiation Acknowledgement" (INIT ACK) chunk.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct InitChunk<'a> {
+        /// Verification tag the receiver of the chunk has to use.
+        pub initiate_tag: u32,
+        /// Advertised receiver window credit.
+        pub advertised_receiver_window_credit: u32,
+        /// Number of outbound streams the sender wants to create.
+        pub number_of_outbound_streams: u16,
+        /// Maximum number of inbound streams the sender allows.
+        pub number_of_inbound_streams: u16,
+        /// Initial transmission sequence number.
+        pub initial_tsn: u32,
+        /// Optional or variable length parameters (e.g. addresses or a state cookie).
+        pub parameters: TlvIterator<'a>,
+    }
+
+    impl<'a> InitChunk<'a> {
+        /// Length of the fixed part of the chunk value (excluding the chunk header).
+        pub const FIXED_VALUE_LEN: usize = 16;
+    }
+
+    /// Decoded "Selective Acknowledgement" (SACK) chunk.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct SackChunk<'a> {
+        /// Last TSN received in sequence.
+        pub cumulative_tsn_ack: u32,
+        /// Advertised receiver window credit.
+        pub advertised_receiver_window_credit: u32,
+        /// Gap ack blocks (start & end offsets relative to the cumulative TSN ack).
+        pub gap_ack_blocks: GapAckBlockIterator<'a>,
+        /// TSNs received more then once.
+        pub duplicate_tsns: DuplicateTsnIterator<'a>,
+    }
+
+    impl<'a> SackChunk<'a> {
+        /// Length of the fixed part of the chunk value (excluding the chunk header).
+        pub const FIXED_VALUE_LEN: usize = 12;
+    }
+
+    /// Decoded "Heartbeat Request" (HEARTBEAT) or "Heartbeat Acknowledgement"
+    /// (HEARTBEAT ACK) chunk.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct HeartbeatChunk<'a> {
+        /// Parameters of the chunk (normally the "Heartbeat Info" parameter).
+        pub parameters: TlvIterator<'a>,
+    }
+
+    /// Decoded "Abort Association" (ABORT) chunk.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct AbortChunk<'a> {
+        /// Set if the sender did not have a TCB it destroyed ("T" bit).
+        pub tcb_not_destroyed: bool,
+        /// Error causes indicating why the association was aborted.
+        pub error_causes: TlvIterator<'a>,
+    }
+
+    /// Decoded "Shutdown Association" (SHUTDOWN) chunk.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ShutdownChunk {
+        /// Last TSN received in sequence.
+        pub cumulative_tsn_ack: u32,
+    }
+
+    impl ShutdownChunk {
+        /// Length of the chunk value (excluding the chunk header).
+        pub const VALUE_LEN: usize = 4;
+    }
+}
+
+use sctp::*;
+
+/// SCTP common header (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.1)).
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |     Source Port Number        |     Destination Port Number   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                      Verification Tag                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           Checksum                            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// The chunks following the header are not part of the header and are
+/// treated as payload (e.g. [`SlicedPacket::payload`]). They can be
+/// iterated using [`SctpChunkIterator`].
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct SctpHeader {
+    /// Source port of the packet.
+    pub source_port: u16,
+    /// Destination port of the packet.
+    pub destination_port: u16,
+    /// Verification tag used by the receiver to validate the sender.
+    pub verification_tag: u32,
+    /// CRC32c checksum over the header and the chunks.
+    ///
+    /// The value is the checksum field as read in network byte order (note
+    /// that the CRC32c value itself is stored in reversed byte order on the
+    /// wire, see [RFC 9260 Appendix A](https://datatracker.ietf.org/doc/html/rfc9260#appendix-A)).
+    pub checksum: u32,
+}
+
+impl SctpHeader {
+    /// Returns a SCTP header with the given values and a zero checksum.
+    pub fn new(source_port: u16, destination_port: u16, verification_tag: u32) -> SctpHeader {
+        SctpHeader {
+            source_port,
+            destination_port,
+            verification_tag,
+            checksum: 0,
+        }
+    }
+
+    /// Returns a SCTP header with the given values and a checksum calculated
+    /// based on the given payload (the serialized chunks).
+    pub fn with_checksum(source_port: u16, destination_port: u16, verification_tag: u32, payload: &[u8]) -> SctpHeader {
+        let mut result = SctpHeader::new(source_port, destination_port, verification_tag);
+        result.update_checksum(payload);
+        result
+    }
+
+    /// Calculates the checksum of the header & the given payload (the
+    /// serialized chunks).
+    ///
+    /// The checksum field is treated as zero during the calculation and
+    /// the result is returned in the format of the `checksum` field.
+    pub fn calc_checksum(&self, payload: &[u8]) -> u32 {
+        let bytes = self.to_bytes();
+        checksum::Crc32c::new()
+            .add_slice(&bytes[..8])
+            .add_zeros(4)
+            .add_slice(payload)
+            .crc()
+            .swap_bytes()
+    }
+
+    /// Calculates & sets the checksum based on the given payload (the
+    /// serialized chunks).
+    #[inline]
+    pub fn update_checksum(&mut self, payload: &[u8]) {
+        self.checksum = self.calc_checksum(payload);
+    }
+
+    /// Returns true if the checksum in the header matches the checksum
+    /// calculated over the header and the given payload.
+    #[inline]
+    pub fn is_checksum_valid(&self, payload: &[u8]) -> bool {
+        self.checksum == self.calc_checksum(payload)
+    }
+
+    /// Reads a SCTP header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(SctpHeader, &[u8]), ReadError> {
+        Ok((
+            SctpHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[SctpHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a SctpHeader from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;12]) -> SctpHeader {
+        SctpHeader {
+            source_port: u16::from_be_bytes([bytes[0], bytes[1]]),
+            destination_port: u16::from_be_bytes([bytes[2], bytes[3]]),
+            verification_tag: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            checksum: u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+        }
+    }
+
+    /// Tries to read a SCTP header from the current position.
+    #[cfg(feature = "std")]
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<SctpHeader, io::Error> {
+        let bytes = {
+            let mut bytes : [u8;12] = [0;12];
+            reader.read_exact(&mut bytes)?;
+            bytes
+        };
+        Ok(SctpHeader::from_bytes(bytes))
+    }
+
+    /// Write the SCTP header without recalculating the checksum.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write the SCTP header to the start of the given slice without recalculating the checksum.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < SctpHeader::SERIALIZED_SIZE {
+            Err(SliceTooSmall(SctpHeader::SERIALIZED_SIZE))
+        } else {
+            slice[..SctpHeader::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes());
+            Ok(&mut slice[SctpHeader::SERIALIZED_SIZE..])
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant SctpHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        SctpHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;12] {
+        let source_port_be = self.source_port.to_be_bytes();
+        let destination_port_be = self.destination_port.to_be_bytes();
+        let verification_tag_be = self.verification_tag.to_be_bytes();
+        let checksum_be = self.checksum.to_be_bytes();
+        [
+            source_port_be[0],
+            source_port_be[1],
+            destination_port_be[0],
+            destination_port_be[1],
+            verification_tag_be[0],
+            verification_tag_be[1],
+            verification_tag_be[2],
+            verification_tag_be[3],
+            checksum_be[0],
+            checksum_be[1],
+            checksum_be[2],
+            checksum_be[3],
+        ]
+    }
+}
+
+impl SerializedSize for SctpHeader {
+    ///Size of the header itself
+    const SERIALIZED_SIZE: usize = 12;
+}
+
+///A slice containing a SCTP common header of a network package. Struct allows the selective read of fields in the header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SctpHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> SctpHeaderSlice<'a> {
+
+    /// Creates a slice containing a SCTP common header.
+    #[inline]
+    pub fn from_slice(slice: &'a[u8]) -> Result<SctpHeaderSlice<'a>, ReadError> {
+        //check length
+        use crate::ReadError::*;
+        if slice.len() < SctpHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(SctpHeader::SERIALIZED_SIZE));
+        }
+
+        //done
+        Ok(SctpHeaderSlice{
+            // SAFETY:
+            // Safe as slice length is checked to be at least
+            // SctpHeader::SERIALIZED_SIZE (12) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    SctpHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the SCTP header
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads the "source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Reads the "destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Reads the "verification tag" from the slice.
+    #[inline]
+    pub fn verification_tag(&self) -> u32 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u32(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Reads the "checksum" from the slice.
+    #[inline]
+    pub fn checksum(&self) -> u32 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u32(self.slice.as_ptr().add(8))
+        }
+    }
+
+    /// Returns true if the checksum in the header matches the checksum
+    /// calculated over the header and the given payload (the chunks
+    /// following the header).
+    pub fn is_checksum_valid(&self, payload: &[u8]) -> bool {
+        let crc = checksum::Crc32c::new()
+            .add_slice(&self.slice[..8])
+            .add_zeros(4)
+            .add_slice(payload)
+            .crc();
+        self.checksum() == crc.swap_bytes()
+    }
+
+    /// Decode all the fields and copy the results to a SctpHeader struct
+    #[inline]
+    pub fn to_header(&self) -> SctpHeader {
+        SctpHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            verification_tag: self.verification_tag(),
+            checksum: self.checksum(),
+        }
+    }
+}
+
+/// Iterator over the chunks following a SCTP common header.
+///
+/// After an error was returned the iteration ends.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SctpChunkIterator<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> SctpChunkIterator<'a> {
+    /// Creates an iterator over the chunks contained in the given slice
+    /// (the payload following the SCTP common header).
+    pub fn from_slice(slice: &'a [u8]) -> SctpChunkIterator<'a> {
+        SctpChunkIterator { slice }
+    }
+
+    /// Returns the not yet iterated part of the slice.
+    #[inline]
+    pub fn rest(&self) -> &'a [u8] {
+        self.slice
+    }
+}
+
+impl<'a> Iterator for SctpChunkIterator<'a> {
+    type Item = Result<SctpChunkSlice<'a>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.slice.is_empty() {
+            return None;
+        }
+        match SctpChunkSlice::from_slice(self.slice) {
+            Ok(chunk) => {
+                // skip the chunk and the padding (the padding of the
+                // last chunk is allowed to be missing)
+                let padded_len = core::cmp::min(self.slice.len(), (chunk.slice().len() + 3) & !3);
+                self.slice = &self.slice[padded_len..];
+                Some(Ok(chunk))
+            }
+            Err(err) => {
+                self.slice = &[];
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Slice containing a SCTP chunk (defined in [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260#section-3.2)).
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   Chunk Type  | Chunk  Flags  |        Chunk Length           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// \                                                               \
+/// /                          Chunk Value                          /
+/// \                                                               \
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SctpChunkSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> SctpChunkSlice<'a> {
+    /// Length of the chunk header (type, flags & length).
+    pub const HEADER_LEN: usize = 4;
+
+    /// Creates a slice containing the chunk at the start of the given
+    /// slice (the slice is cut to the chunk length, excluding the padding).
+    pub fn from_slice(slice: &'a [u8]) -> Result<SctpChunkSlice<'a>, ReadError> {
+        if slice.len() < SctpChunkSlice::HEADER_LEN {
+            return Err(ReadError::UnexpectedEndOfSlice(SctpChunkSlice::HEADER_LEN));
+        }
+        // SAFETY:
+        // Safe as the slice length is checked to be at least
+        // SctpChunkSlice::HEADER_LEN (4).
+        let length = unsafe { get_unchecked_be_u16(slice.as_ptr().add(2)) };
+        if usize::from(length) < SctpChunkSlice::HEADER_LEN {
+            return Err(ReadError::SctpChunkLengthTooSmall(length));
+        }
+        if slice.len() < usize::from(length) {
+            return Err(ReadError::UnexpectedEndOfSlice(usize::from(length)));
+        }
+        Ok(SctpChunkSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least length.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), usize::from(length)) },
+        })
+    }
+
+    /// Returns the "chunk type" field.
+    #[inline]
+    pub fn chunk_type(&self) -> u8 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of SctpChunkSlice::HEADER_LEN (4).
+        unsafe { *self.slice.get_unchecked(0) }
+    }
+
+    /// Returns the "chunk flags" field.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of SctpChunkSlice::HEADER_LEN (4).
+        unsafe { *self.slice.get_unchecked(1) }
+    }
+
+    /// Returns the "chunk length" field (includes the chunk header but not the padding).
+    #[inline]
+    pub fn length(&self) -> u16 {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of SctpChunkSlice::HEADER_LEN (4).
+        unsafe { get_unchecked_be_u16(self.slice.as_ptr().add(2)) }
+    }
+
+    /// Returns the chunk value (the bytes after the chunk header without the padding).
+    #[inline]
+    pub fn value(&self) -> &'a [u8] {
+        // SAFETY:
+        // Safe as the constructor checks that the slice has
+        // at least the length of SctpChunkSlice::HEADER_LEN (4).
+        unsafe {
+            from_raw_parts(
+                self.slice.as_ptr().add(SctpChunkSlice::HEADER_LEN),
+                self.slice.len() - SctpChunkSlice::HEADER_LEN,
+            )
+        }
+    }
+
+    /// Decodes the chunk based on the chunk type.
+    ///
+    /// Chunks with an unknown type or with a value that is too short for
+    /// the chunk type are returned as [`SctpChunk::Unknown`].
+    pub fn chunk(&self) -> SctpChunk<'a> {
+        use SctpChunk::*;
+
+        let value = self.value();
+        let be_u32 = |offset: usize| -> u32 {
+            u32::from_be_bytes([value[offset], value[offset + 1], value[offset + 2], value[offset + 3]])
+        };
+        let be_u16 = |offset: usize| -> u16 {
+            u16::from_be_bytes([value[offset], value[offset + 1]])
+        };
+
+        // number of bytes used by the gap ack blocks & duplicate TSNs of a SACK chunk
+        let sack_list_len = || -> usize {
+            usize::from(be_u16(8))*4 + usize::from(be_u16(10))*4
+        };
+
+        match self.chunk_type() {
+            CHUNK_TYPE_DATA if value.len() >= DataChunk::FIXED_VALUE_LEN => {
+                let flags = self.flags();
+                Data(DataChunk {
+                    immediate: 0 != flags & DATA_FLAG_IMMEDIATE,
+                    unordered: 0 != flags & DATA_FLAG_UNORDERED,
+                    beginning: 0 != flags & DATA_FLAG_BEGINNING,
+                    ending: 0 != flags & DATA_FLAG_ENDING,
+                    tsn: be_u32(0),
+                    stream_identifier: be_u16(4),
+                    stream_sequence_number: be_u16(6),
+                    payload_protocol_identifier: be_u32(8),
+                    user_data: &value[DataChunk::FIXED_VALUE_LEN..],
+                })
+            }
+            chunk_type @ (CHUNK_TYPE_INIT | CHUNK_TYPE_INIT_ACK) if value.len() >= InitChunk::FIXED_VALUE_LEN => {
+                let init = InitChunk {
+                    initiate_tag: be_u32(0),
+                    advertised_receiver_window_credit: be_u32(4),
+                    number_of_outbound_streams: be_u16(8),
+                    number_of_inbound_streams: be_u16(10),
+                    initial_tsn: be_u32(12),
+                    parameters: TlvIterator::from_slice(&value[InitChunk::FIXED_VALUE_LEN..]),
+                };
+                if CHUNK_TYPE_INIT == chunk_type {
+                    Init(init)
+                } else {
+                    InitAck(init)
+                }
+            }
+            CHUNK_TYPE_SACK if value.len() >= SackChunk::FIXED_VALUE_LEN &&
+                               value.len() == SackChunk::FIXED_VALUE_LEN + sack_list_len() => {
+                let gap_len = usize::from(be_u16(8))*4;
+                let (gaps, dups) = value[SackChunk::FIXED_VALUE_LEN..].split_at(gap_len);
+                Sack(SackChunk {
+                    cumulative_tsn_ack: be_u32(0),
+                    advertised_receiver_window_credit: be_u32(4),
+                    gap_ack_blocks: GapAckBlockIterator::from_slice(gaps),
+                    duplicate_tsns: DuplicateTsnIterator::from_slice(dups),
+                })
+            }
+            CHUNK_TYPE_HEARTBEAT => Heartbeat(HeartbeatChunk {
+                parameters: TlvIterator::from_slice(value),
+            }),
+            CHUNK_TYPE_HEARTBEAT_ACK => HeartbeatAck(HeartbeatChunk {
+                parameters: TlvIterator::from_slice(value),
+            }),
+            CHUNK_TYPE_ABORT => Abort(AbortChunk {
+                tcb_not_destroyed: 0 != self.flags() & ABORT_FLAG_TCB_NOT_DESTROYED,
+                error_causes: TlvIterator::from_slice(value),
+            }),
+            CHUNK_TYPE_SHUTDOWN if value.len() == ShutdownChunk::VALUE_LEN => Shutdown(ShutdownChunk {
+                cumulative_tsn_ack: be_u32(0),
+            }),
+            chunk_type => Unknown {
+                chunk_type,
+                flags: self.flags(),
+                value,
+            },
+        }
+    }
+
+    /// Returns the slice containing the chunk (without the padding).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+}
+
+/// Decoded SCTP chunk.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SctpChunk<'a> {
+    /// "Payload Data" (DATA) chunk.
+    Data(DataChunk<'a>),
+    /// "Initiation" (INIT) chunk.
+    Init(InitChunk<'a>),
+    /// "Initiation Acknowledgement" (INIT ACK) chunk.
+    InitAck(InitChunk<'a>),
+    /// "Selective Acknowledgement" (SACK) chunk.
+    Sack(SackChunk<'a>),
+    /// "Heartbeat Request" (HEARTBEAT) chunk.
+    Heartbeat(HeartbeatChunk<'a>),
+    /// "Heartbeat Acknowledgement" (HEARTBEAT ACK) chunk.
+    HeartbeatAck(HeartbeatChunk<'a>),
+    /// "Abort Association" (ABORT) chunk.
+    Abort(AbortChunk<'a>),
+    /// "Shutdown Association" (SHUTDOWN) chunk.
+    Shutdown(ShutdownChunk),
+    /// Chunk with an unknown type or a value that could not be decoded.
+    Unknown {
+        chunk_type: u8,
+        flags: u8,
+        value: &'a [u8],
+    },
+}
@@ -1,7 +1,7 @@
 use super::super::*;
 
-use std::fmt::{Debug, Formatter};
-use std::slice::from_raw_parts;
+use core::fmt::{Debug, Formatter};
+use core::slice::from_raw_parts;
 
 ///The minimum size of the tcp header in bytes
 pub const TCP_MINIMUM_HEADER_SIZE: usize = 5*4;
@@ -129,6 +129,7 @@ impl TcpHeader {
 
         //calculate the required size of the options
         use crate::TcpOptionElement::*;
+        use tcp_option::LEN_USER_TIMEOUT;
         let required_length = options.iter().fold(0, |acc, ref x| {
             acc + match x {
                 Noop => 1,
@@ -144,6 +145,12 @@ impl TcpHeader {
                     })
                 },
                 Timestamp(_, _) => 10,
+                End => 1,
+                UserTimeout{ .. } => usize::from(LEN_USER_TIMEOUT),
+                AuthenticationOption{ mac, .. } => 4 + mac.len(),
+                Mptcp(value) => value.option_len(),
+                FastOpenCookie(cookie) => 2 + cookie.len(),
+                Unknown{ data, .. } => 2 + data.len(),
             }
         });
 
@@ -238,7 +245,54 @@ impl TcpHeader {
                         insert[1] = 10;
                         insert[2..6].copy_from_slice(&a.to_be_bytes());
                         insert[6..10].copy_from_slice(&b.to_be_bytes());
-                    }
+                    },
+                    End => {
+                        self.options_buffer[i] = KIND_END;
+                        i += 1;
+                    },
+                    UserTimeout{ granularity_minutes, timeout } => {
+                        let insert = &mut self.options_buffer[i..i + 4];
+                        i += 4;
+
+                        insert[0] = KIND_USER_TIMEOUT;
+                        insert[1] = LEN_USER_TIMEOUT;
+                        let value = (timeout & 0x7fff) | if *granularity_minutes { 0x8000 } else { 0 };
+                        insert[2..4].copy_from_slice(&value.to_be_bytes());
+                    },
+                    AuthenticationOption{ key_id, rnext_key_id, mac } => {
+                        let len = 4 + mac.len();
+                        let insert = &mut self.options_buffer[i..i + len];
+                        i += len;
+
+                        insert[0] = KIND_AUTHENTICATION;
+                        insert[1] = len as u8;
+                        insert[2] = *key_id;
+                        insert[3] = *rnext_key_id;
+                        insert[4..].copy_from_slice(mac.as_slice());
+                    },
+                    Mptcp(value) => {
+                        let len = value.option_len();
+                        value.write_option(&mut self.options_buffer[i..i + len]);
+                        i += len;
+                    },
+                    FastOpenCookie(cookie) => {
+                        let len = 2 + cookie.len();
+                        let insert = &mut self.options_buffer[i..i + len];
+                        i += len;
+
+                        insert[0] = KIND_FAST_OPEN;
+                        insert[1] = len as u8;
+                        insert[2..].copy_from_slice(cookie.as_slice());
+                    },
+                    Unknown{ kind, data } => {
+                        let len = 2 + data.len();
+                        let insert = &mut self.options_buffer[i..i + len];
+                        i += len;
+
+                        insert[0] = *kind;
+                        insert[1] = len as u8;
+                        insert[2..].copy_from_slice(data.as_slice());
+                    },
                 }
             }
             //set the new data offset
@@ -300,6 +354,7 @@ impl TcpHeader {
     }
 
     /// Read a tcp header from the current position
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<TcpHeader, ReadError> {
         let raw = {
             let mut raw : [u8;20] = [0;20];
@@ -351,7 +406,29 @@ impl TcpHeader {
     }
 
     /// Write the tcp header to a stream (does NOT calculate the checksum).
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        writer.write_all(&self.to_bytes_without_options())?;
+        writer.write_all(self.options())?;
+        Ok(())
+    }
+
+    /// Write the tcp header to the start of the given slice (does NOT calculate the checksum).
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        //length check
+        let header_len = usize::from(self.header_len());
+        if slice.len() < header_len {
+            Err(WriteError::SliceTooSmall(header_len))
+        } else {
+            slice[..TCP_MINIMUM_HEADER_SIZE].copy_from_slice(&self.to_bytes_without_options());
+            slice[TCP_MINIMUM_HEADER_SIZE..header_len].copy_from_slice(self.options());
+            Ok(&mut slice[header_len..])
+        }
+    }
+
+    /// Returns the serialized fixed part of the header (without the options).
+    fn to_bytes_without_options(&self) -> [u8;TCP_MINIMUM_HEADER_SIZE] {
 
         //check that the data offset is within range
         debug_assert!(TCP_MINIMUM_DATA_OFFSET <= self._data_offset);
@@ -365,58 +442,49 @@ impl TcpHeader {
         let checksum_be = self.checksum.to_be_bytes();
         let urg_ptr_be = self.urgent_pointer.to_be_bytes();
 
-        writer.write_all(
-            &[
-                src_be[0], src_be[1], dst_be[0], dst_be[1],
-                seq_be[0], seq_be[1], seq_be[2], seq_be[3],
-                ack_be[0], ack_be[1], ack_be[2], ack_be[3],
-                {
-                    let value = (self._data_offset << 4) & 0xF0;
-                    if self.ns {
-                        value | 1
-                    } else {
-                        value
-                    }
-                },
-                {
-                    let mut value = 0;
-                    if self.fin {
-                        value |= 1;
-                    }
-                    if self.syn {
-                        value |= 2;
-                    }
-                    if self.rst {
-                        value |= 4;
-                    }
-                    if self.psh {
-                        value |= 8;
-                    }
-                    if self.ack {
-                        value |= 16;
-                    }
-                    if self.urg {
-                        value |= 32;
-                    }
-                    if self.ece {
-                        value |= 64;
-                    }
-                    if self.cwr {
-                        value |= 128;
-                    }
+        [
+            src_be[0], src_be[1], dst_be[0], dst_be[1],
+            seq_be[0], seq_be[1], seq_be[2], seq_be[3],
+            ack_be[0], ack_be[1], ack_be[2], ack_be[3],
+            {
+                let value = (self._data_offset << 4) & 0xF0;
+                if self.ns {
+                    value | 1
+                } else {
                     value
-                },
-                window_be[0], window_be[1],
-                checksum_be[0], checksum_be[1], urg_ptr_be[0], urg_ptr_be[1]
-            ]
-        )?;
-
-        //write options if the data_offset is large enough
-        if self._data_offset > TCP_MINIMUM_DATA_OFFSET {
-            let len = ((self._data_offset - TCP_MINIMUM_DATA_OFFSET) as usize)*4;
-            writer.write_all(&self.options_buffer[..len])?;
-        }
-        Ok(())
+                }
+            },
+            {
+                let mut value = 0;
+                if self.fin {
+                    value |= 1;
+                }
+                if self.syn {
+                    value |= 2;
+                }
+                if self.rst {
+                    value |= 4;
+                }
+                if self.psh {
+                    value |= 8;
+                }
+                if self.ack {
+                    value |= 16;
+                }
+                if self.urg {
+                    value |= 32;
+                }
+                if self.ece {
+                    value |= 64;
+                }
+                if self.cwr {
+                    value |= 128;
+                }
+                value
+            },
+            window_be[0], window_be[1],
+            checksum_be[0], checksum_be[1], urg_ptr_be[0], urg_ptr_be[1]
+        ]
     }
 
     /// Calculates the upd header checksum based on a ipv4 header and returns the result. This does NOT set the checksum.
@@ -429,7 +497,7 @@ impl TcpHeader {
         
         //check that the total length fits into the field
         let tcp_length = (self._data_offset as usize)*4 + payload.len();
-        if (std::u16::MAX as usize) < tcp_length {
+        if (core::u16::MAX as usize) < tcp_length {
             return Err(ValueError::TcpLengthTooLarge(tcp_length));
         }
 
@@ -456,7 +524,7 @@ impl TcpHeader {
 
         //check that the total length fits into the field
         let tcp_length = (self._data_offset as usize)*4 + payload.len();
-        if (std::u32::MAX as usize) < tcp_length {
+        if (core::u32::MAX as usize) < tcp_length {
             return Err(ValueError::TcpLengthTooLarge(tcp_length));
         }
 
@@ -551,7 +619,7 @@ impl Default for TcpHeader {
 //      So the only option left to me was to write an implementation myself and deal with the added complexity
 //      and potential added error source.
 impl Debug for TcpHeader {
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), core::fmt::Error> {
         fmt.debug_struct("TcpHeader")
             .field("source_port", &self.source_port)
             .field("destination_port", &self.destination_port)
@@ -575,7 +643,7 @@ impl Debug for TcpHeader {
     }
 }
 
-impl std::cmp::PartialEq for TcpHeader {
+impl core::cmp::PartialEq for TcpHeader {
     fn eq(&self, other: &TcpHeader) -> bool {
         self.source_port == other.source_port &&
         self.destination_port == other.destination_port &&
@@ -598,7 +666,7 @@ impl std::cmp::PartialEq for TcpHeader {
     }
 }
 
-impl std::cmp::Eq for TcpHeader {}
+impl core::cmp::Eq for TcpHeader {}
 
 ///A slice containing an tcp header of a network package.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -608,6 +676,21 @@ pub struct TcpHeaderSlice<'a> {
 
 impl<'a> TcpHeaderSlice<'a> {
 
+    /// Position & size of the fields in the serialized header (excluding the options).
+    /// The "flags" field contains the bits from NS to FIN.
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "source_port", bit_offset: 0, bit_width: 16 },
+        HeaderField{ name: "destination_port", bit_offset: 16, bit_width: 16 },
+        HeaderField{ name: "sequence_number", bit_offset: 32, bit_width: 32 },
+        HeaderField{ name: "acknowledgment_number", bit_offset: 64, bit_width: 32 },
+        HeaderField{ name: "data_offset", bit_offset: 96, bit_width: 4 },
+        HeaderField{ name: "reserved", bit_offset: 100, bit_width: 3 },
+        HeaderField{ name: "flags", bit_offset: 103, bit_width: 9 },
+        HeaderField{ name: "window_size", bit_offset: 112, bit_width: 16 },
+        HeaderField{ name: "checksum", bit_offset: 128, bit_width: 16 },
+        HeaderField{ name: "urgent_pointer", bit_offset: 144, bit_width: 16 },
+    ];
+
     ///Creates a slice containing an tcp header.
     pub fn from_slice(slice: &'a[u8]) -> Result<TcpHeaderSlice<'a>, ReadError> {
         //check length
@@ -925,7 +1008,7 @@ impl<'a> TcpHeaderSlice<'a> {
         
         //check that the total length fits into the field
         let tcp_length = self.slice.len() + payload.len();
-        if (std::u16::MAX as usize) < tcp_length {
+        if (core::u16::MAX as usize) < tcp_length {
             return Err(ValueError::TcpLengthTooLarge(tcp_length));
         }
 
@@ -952,7 +1035,7 @@ impl<'a> TcpHeaderSlice<'a> {
 
         //check that the total length fits into the field
         let tcp_length = (self.data_offset() as usize)*4 + payload.len();
-        if (std::u32::MAX as usize) < tcp_length {
+        if (core::u32::MAX as usize) < tcp_length {
             return Err(ValueError::TcpLengthTooLarge(tcp_length));
         }
 
@@ -1007,6 +1090,102 @@ pub enum TcpOptionElement {
     SelectiveAcknowledgement((u32,u32), [Option<(u32,u32)>;3]),
     ///Timestamp & echo (first number is the sender timestamp, the second the echo timestamp)
     Timestamp(u32, u32),
+    /// "End of Option List" option.
+    ///
+    /// When reading, all bytes following the end option are treated as
+    /// padding and skipped by the [`TcpOptionsIterator`]. The end option
+    /// itself is only returned if it is followed by non zero bytes (otherwise
+    /// it can not be distinguished from the padding of the options).
+    End,
+    /// "User Timeout" option ([RFC 5482](https://datatracker.ietf.org/doc/html/rfc5482)).
+    UserTimeout{
+        /// If true the timeout is in minutes, otherwise in seconds.
+        granularity_minutes: bool,
+        /// Timeout (15 bits, the highest bit is ignored when writing).
+        timeout: u16,
+    },
+    /// "TCP Authentication Option" (TCP-AO, [RFC 5925](https://datatracker.ietf.org/doc/html/rfc5925)).
+    AuthenticationOption{
+        key_id: u8,
+        rnext_key_id: u8,
+        /// Message authentication code.
+        mac: TcpOptionData,
+    },
+    /// "Multipath TCP" option ([RFC 8684](https://datatracker.ietf.org/doc/html/rfc8684)).
+    Mptcp(MptcpOption),
+    /// "TCP Fast Open" cookie option ([RFC 7413](https://datatracker.ietf.org/doc/html/rfc7413)).
+    ///
+    /// An empty cookie is used to request a cookie.
+    FastOpenCookie(TcpOptionData),
+    /// Option with a kind that is not decoded by etherparse (or an option that
+    /// could not be represented by one of the other variants).
+    ///
+    /// When written the kind is followed by a length byte & the data.
+    Unknown{
+        kind: u8,
+        data: TcpOptionData,
+    },
+}
+
+/// Data of a variable length tcp option (e.g. fast open cookie or an
+/// unknown option) without the kind & length bytes.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct TcpOptionData {
+    len: u8,
+    buffer: [u8;TcpOptionData::MAX_LEN],
+}
+
+impl TcpOptionData {
+    /// Maximum length of the data of an option (40 bytes of options minus the kind & length byte).
+    pub const MAX_LEN: usize = 38;
+
+    /// Creates the option data from the given slice or returns an error if
+    /// the slice is bigger then [`TcpOptionData::MAX_LEN`].
+    pub fn from_slice(data: &[u8]) -> Result<TcpOptionData, TcpOptionWriteError> {
+        if data.len() > TcpOptionData::MAX_LEN {
+            Err(TcpOptionWriteError::NotEnoughSpace(data.len() + 2))
+        } else {
+            let mut buffer = [0;TcpOptionData::MAX_LEN];
+            buffer[..data.len()].copy_from_slice(data);
+            Ok(TcpOptionData{
+                len: data.len() as u8,
+                buffer,
+            })
+        }
+    }
+
+    /// Returns the data as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..usize::from(self.len)]
+    }
+
+    /// Returns the length of the data in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        usize::from(self.len)
+    }
+
+    /// Returns true if the data is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+}
+
+impl Default for TcpOptionData {
+    fn default() -> TcpOptionData {
+        TcpOptionData{
+            len: 0,
+            buffer: [0;TcpOptionData::MAX_LEN],
+        }
+    }
+}
+
+impl fmt::Debug for TcpOptionData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TcpOptionData").field(&self.as_slice()).finish()
+    }
 }
 
 ///Errors that can occour while reading the options of a TCP header.
@@ -1021,9 +1200,13 @@ pub enum TcpOptionReadError {
     ///Returned if an unknown tcp header option is encountered.
     ///
     ///The first element is the identifier and the slice contains the rest of data left in the options.
+    ///
+    ///Note: This error is no longer returned by the [`TcpOptionsIterator`], unknown options
+    ///are returned as [`TcpOptionElement::Unknown`] instead.
     UnknownId(u8),
 }
 
+#[cfg(feature = "std")]
 impl Error for TcpOptionReadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
@@ -1059,6 +1242,7 @@ pub enum TcpOptionWriteError {
     NotEnoughSpace(usize)
 }
 
+#[cfg(feature = "std")]
 impl Error for TcpOptionWriteError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         None
@@ -1147,6 +1331,14 @@ pub mod tcp_option {
     pub const KIND_SELECTIVE_ACK: u8 = 5;
     /// `u8` identifying a "timestamp and echo of previous timestamp" tcp option.
     pub const KIND_TIMESTAMP: u8 = 8;
+    /// `u8` identifying a "user timeout" tcp option.
+    pub const KIND_USER_TIMEOUT: u8 = 28;
+    /// `u8` identifying a "tcp authentication option" (TCP-AO).
+    pub const KIND_AUTHENTICATION: u8 = 29;
+    /// `u8` identifying a "multipath tcp" option.
+    pub const KIND_MULTIPATH_TCP: u8 = 30;
+    /// `u8` identifying a "tcp fast open cookie" option.
+    pub const KIND_FAST_OPEN: u8 = 34;
     /// Length in octets/bytes of the "end" tcp option (includes kind value).
     pub const LEN_END: u8 = 1;
     /// Length in octets/bytes of the "no operation" tcp option (includes kind value).
@@ -1159,6 +1351,8 @@ pub mod tcp_option {
     pub const LEN_SELECTIVE_ACK_PERMITTED: u8 = 2;
     /// Length in octets/bytes of the "timestamp and echo of previous timestamp" tcp option (includes kind value).
     pub const LEN_TIMESTAMP: u8 = 10;
+    /// Length in octets/bytes of the "user timeout" tcp option (includes kind value).
+    pub const LEN_USER_TIMEOUT: u8 = 4;
 }
 
 impl<'a> TcpOptionsIterator<'a> {
@@ -1201,15 +1395,45 @@ impl<'a> Iterator for TcpOptionsIterator<'a> {
             }
         };
 
+        // returns the complete option (including kind & length) for options with a variable length
+        let variable_size = |slice: &'a [u8]| -> Result<&'a [u8], TcpOptionReadError> {
+            if slice.len() < 2 {
+                Err(UnexpectedEndOfSlice{
+                    option_id: slice[0],
+                    expected_len: 2,
+                    actual_len: slice.len()
+                })
+            } else if slice[1] < 2 {
+                Err(UnexpectedSize{
+                    option_id: slice[0],
+                    size: slice[1]
+                })
+            } else if slice.len() < usize::from(slice[1]) {
+                Err(UnexpectedEndOfSlice{
+                    option_id: slice[0],
+                    expected_len: slice[1],
+                    actual_len: slice.len()
+                })
+            } else {
+                Ok(&slice[..usize::from(slice[1])])
+            }
+        };
+
         if self.options.is_empty() {
             None
         } else {
             //first determine the result
             use tcp_option::*;
             let result = match self.options[0] {
-                //end
+                //end (the rest of the options is padding)
                 KIND_END => {
-                    None
+                    // an end option only followed by zeros can not be
+                    // distinguished from the padding of the options
+                    if self.options[1..].iter().all(|value| 0 == *value) {
+                        None
+                    } else {
+                        Some(Ok(End))
+                    }
                 },
                 KIND_NOOP => {
                     self.options = &self.options[1..];
@@ -1337,15 +1561,77 @@ impl<'a> Iterator for TcpOptionsIterator<'a> {
                     }
                 },
 
-                //unknown id
-                _ => {
-                    Some(Err(UnknownId(self.options[0])))
+                KIND_USER_TIMEOUT => {
+                    match expect_specific_size(LEN_USER_TIMEOUT, self.options) {
+                        Err(value) => Some(Err(value)),
+                        _ => {
+                            // SAFETY:
+                            // Safe as the slice size is checked beforehand to be at
+                            // least of size LEN_USER_TIMEOUT (4).
+                            let value = unsafe {
+                                get_unchecked_be_u16(self.options.as_ptr().add(2))
+                            };
+                            self.options = &self.options[4..];
+                            Some(Ok(UserTimeout{
+                                granularity_minutes: 0 != value & 0x8000,
+                                timeout: value & 0x7fff,
+                            }))
+                        }
+                    }
+                },
+                kind => {
+                    match variable_size(self.options) {
+                        Err(value) => Some(Err(value)),
+                        Ok(option) => {
+                            let data = &option[2..];
+                            let bad_size = || UnexpectedSize{
+                                option_id: kind,
+                                size: option[1]
+                            };
+                            // options of a TcpHeader are at most 40 bytes long, but
+                            // slices passed to `from_slice` can contain longer options
+                            let unknown = || TcpOptionData::from_slice(data)
+                                .map(|data| Unknown{ kind, data })
+                                .map_err(|_| bad_size());
+                            let element = match kind {
+                                KIND_AUTHENTICATION => if option.len() < 4 {
+                                    Err(bad_size())
+                                } else {
+                                    TcpOptionData::from_slice(&data[2..])
+                                        .map(|mac| AuthenticationOption{
+                                            key_id: data[0],
+                                            rnext_key_id: data[1],
+                                            mac,
+                                        })
+                                        .map_err(|_| bad_size())
+                                },
+                                KIND_MULTIPATH_TCP => MptcpOption::from_option_slice(option).and_then(
+                                    |value| match value {
+                                        Some(value) => Ok(Mptcp(value)),
+                                        None => unknown(),
+                                    }
+                                ),
+                                KIND_FAST_OPEN => if data.is_empty() || (4..=16).contains(&data.len()) {
+                                    TcpOptionData::from_slice(data)
+                                        .map(FastOpenCookie)
+                                        .map_err(|_| bad_size())
+                                } else {
+                                    Err(bad_size())
+                                },
+                                _ => unknown(),
+                            };
+                            if element.is_ok() {
+                                self.options = &self.options[option.len()..];
+                            }
+                            Some(element)
+                        }
+                    }
                 },
             };
 
             //in case the result was an error or the end move the slice to an end position
             match result {
-                None | Some(Err(_)) => {
+                None | Some(Err(_)) | Some(Ok(End)) => {
                     let len = self.options.len();
                     self.options = &self.options[len..len];
                 },
@@ -1359,7 +1645,7 @@ impl<'a> Iterator for TcpOptionsIterator<'a> {
 }
 
 impl<'a> Debug for TcpOptionsIterator<'a> {
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), std::fmt::Error> {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), core::fmt::Error> {
         let mut list = fmt.debug_list();
 
         // create a copy and iterate over all elements
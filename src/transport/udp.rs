@@ -1,6 +1,6 @@
 use super::super::*;
 
-use std::slice::from_raw_parts;
+use core::slice::from_raw_parts;
 
 ///Udp header according to rfc768.
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -20,7 +20,7 @@ impl UdpHeader {
     /// Returns an udp header for the given parameters
     pub fn without_ipv4_checksum(source_port: u16, destination_port: u16, payload_length: usize) -> Result<UdpHeader, ValueError> {
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = (std::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        const MAX_PAYLOAD_LENGTH: usize = (core::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
         if MAX_PAYLOAD_LENGTH < payload_length {
             return Err(ValueError::UdpPayloadLengthTooLarge(payload_length));
         }
@@ -37,7 +37,7 @@ impl UdpHeader {
     pub fn with_ipv4_checksum(source_port: u16, destination_port: u16, ip_header: &Ipv4Header, payload: &[u8]) -> Result<UdpHeader, ValueError> {
 
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = (std::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        const MAX_PAYLOAD_LENGTH: usize = (core::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
         if MAX_PAYLOAD_LENGTH < payload.len() {
             return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
         }
@@ -60,7 +60,7 @@ impl UdpHeader {
     /// Calculates the upd header checksum based on a ipv4 header.
     pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = (std::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        const MAX_PAYLOAD_LENGTH: usize = (core::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
         if MAX_PAYLOAD_LENGTH < payload.len() {
             return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
         }
@@ -86,7 +86,7 @@ impl UdpHeader {
     pub fn with_ipv6_checksum(source_port: u16, destination_port: u16, ip_header: &Ipv6Header, payload: &[u8]) -> Result<UdpHeader, ValueError> {
 
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = (std::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        const MAX_PAYLOAD_LENGTH: usize = (core::u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
         if MAX_PAYLOAD_LENGTH <= payload.len() {
             return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
         }
@@ -109,7 +109,7 @@ impl UdpHeader {
     /// Calculates the checksum of the current udp header given an ipv6 source & destination address plus the payload.
     pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
         //check that the total length fits into the field
-        const MAX_PAYLOAD_LENGTH: usize = (std::u32::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        const MAX_PAYLOAD_LENGTH: usize = (core::u32::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
         if MAX_PAYLOAD_LENGTH < payload.len() {
             return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
         }
@@ -191,6 +191,7 @@ impl UdpHeader {
     }
 
     /// Tries to read an udp header from the current position.
+    #[cfg(feature = "std")]
     pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<UdpHeader, io::Error> {
         let bytes = {
             let mut bytes : [u8;8] = [0;8];
@@ -201,11 +202,25 @@ impl UdpHeader {
     }
 
     /// Write the udp header without recalculating the checksum or length.
+    #[cfg(feature = "std")]
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         writer.write_all(&self.to_bytes())?;
         Ok(())
     }
 
+    /// Write the udp header to the start of the given slice without recalculating the checksum or length.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < UdpHeader::SERIALIZED_SIZE {
+            Err(SliceTooSmall(UdpHeader::SERIALIZED_SIZE))
+        } else {
+            slice[..UdpHeader::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes());
+            Ok(&mut slice[UdpHeader::SERIALIZED_SIZE..])
+        }
+    }
+
     /// Length of the serialized header in bytes.
     ///
     /// The function always returns the constant UdpHeader::SERIALIZED_SIZE
@@ -249,6 +264,14 @@ pub struct UdpHeaderSlice<'a> {
 
 impl<'a> UdpHeaderSlice<'a> {
 
+    /// Position & size of the fields in the serialized header.
+    pub const FIELDS: &'static [HeaderField] = &[
+        HeaderField{ name: "source_port", bit_offset: 0, bit_width: 16 },
+        HeaderField{ name: "destination_port", bit_offset: 16, bit_width: 16 },
+        HeaderField{ name: "length", bit_offset: 32, bit_width: 16 },
+        HeaderField{ name: "checksum", bit_offset: 48, bit_width: 16 },
+    ];
+
     /// Creates a slice containing an udp header.
     #[inline]
     pub fn from_slice(slice: &'a[u8]) -> Result<UdpHeaderSlice<'a>, ReadError> {
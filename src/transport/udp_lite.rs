@@ -0,0 +1,292 @@
+use super::super::*;
+
+use core::slice::from_raw_parts;
+
+/// UDP-Lite header according to [RFC 3828](https://datatracker.ietf.org/doc/html/rfc3828).
+///
+/// UDP-Lite uses the same header layout as UDP, but replaces the "length"
+/// field with a "checksum coverage" field specifying how many bytes (starting
+/// with the first byte of the UDP-Lite header) are covered by the checksum.
+/// The packet length itself is derived from the ip header.
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct UdpLiteHeader {
+    ///Source port of the packet (optional).
+    pub source_port: u16,
+    ///Destination port of the packet.
+    pub destination_port: u16,
+    ///Number of bytes (including the UDP-Lite header) covered by the checksum.
+    ///The value 0 indicates that the whole packet is covered by the checksum.
+    ///
+    ///Note that values between 1 & 7 (smaller then the header itself) and values
+    ///bigger then the packet length are not allowed.
+    pub checksum_coverage: u16,
+    ///The checksum of the packet. The checksum is calculated from a pseudo header, the UDP-Lite header and the covered part of the payload.
+    pub checksum: u16
+}
+
+impl UdpLiteHeader {
+
+    /// Returns an UDP-Lite header with the given ports & checksum coverage and a zero checksum.
+    pub fn new(source_port: u16, destination_port: u16, checksum_coverage: u16) -> UdpLiteHeader {
+        UdpLiteHeader{
+            source_port,
+            destination_port,
+            checksum_coverage,
+            checksum: 0
+        }
+    }
+
+    /// Calculates the UDP-Lite header checksum based on a ipv4 header.
+    pub fn calc_checksum_ipv4(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the UDP-Lite header checksum based on a ipv4 source & destination address plus the payload.
+    pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the pseudo header field
+        const MAX_PAYLOAD_LENGTH: usize = (u16::MAX as usize) - UdpLiteHeader::SERIALIZED_SIZE;
+        if MAX_PAYLOAD_LENGTH < payload.len() {
+            return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
+        }
+        let covered = self.covered_payload(payload)?;
+
+        Ok(self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_4bytes(source)
+            .add_4bytes(destination)
+            .add_2bytes([0, ip_number::UDP_LITE])
+            .add_2bytes(((UdpLiteHeader::SERIALIZED_SIZE + payload.len()) as u16).to_be_bytes()),
+            covered
+        ))
+    }
+
+    /// Calculates the checksum of the current UDP-Lite header given an ipv6 header and the payload.
+    pub fn calc_checksum_ipv6(&self, ip_header: &Ipv6Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the checksum of the current UDP-Lite header given an ipv6 source & destination address plus the payload.
+    pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the pseudo header field
+        const MAX_PAYLOAD_LENGTH: usize = (u32::MAX as usize) - UdpLiteHeader::SERIALIZED_SIZE;
+        if MAX_PAYLOAD_LENGTH < payload.len() {
+            return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
+        }
+        let covered = self.covered_payload(payload)?;
+
+        Ok(self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_16bytes(source)
+            .add_16bytes(destination)
+            .add_4bytes(((UdpLiteHeader::SERIALIZED_SIZE + payload.len()) as u32).to_be_bytes())
+            .add_4bytes([0, 0, 0, ip_number::UDP_LITE]),
+            covered
+        ))
+    }
+
+    /// Returns the part of the payload covered by the checksum or an error
+    /// if the checksum coverage value is not valid for the given payload.
+    fn covered_payload<'a>(&self, payload: &'a [u8]) -> Result<&'a [u8], ValueError> {
+        if 0 == self.checksum_coverage {
+            Ok(payload)
+        } else {
+            let coverage = usize::from(self.checksum_coverage);
+            if coverage < UdpLiteHeader::SERIALIZED_SIZE || coverage > UdpLiteHeader::SERIALIZED_SIZE + payload.len() {
+                Err(ValueError::UdpLiteChecksumCoverageBad(self.checksum_coverage))
+            } else {
+                Ok(&payload[..coverage - UdpLiteHeader::SERIALIZED_SIZE])
+            }
+        }
+    }
+
+    /// This method takes the sum of the pseudo ip header and calculates the rest of the checksum.
+    fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, covered_payload: &[u8]) -> u16 {
+        ip_pseudo_header_sum
+        .add_2bytes(self.source_port.to_be_bytes())
+        .add_2bytes(self.destination_port.to_be_bytes())
+        .add_2bytes(self.checksum_coverage.to_be_bytes())
+        .add_slice(covered_payload)
+        .to_ones_complement_with_no_zero()
+        .to_be()
+    }
+
+    /// Reads an UDP-Lite header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(UdpLiteHeader, &[u8]), ReadError> {
+        Ok((
+            UdpLiteHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[UdpLiteHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read an UdpLiteHeader from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;8]) -> UdpLiteHeader {
+        UdpLiteHeader{
+            source_port: u16::from_be_bytes([bytes[0], bytes[1]]),
+            destination_port: u16::from_be_bytes([bytes[2], bytes[3]]),
+            checksum_coverage: u16::from_be_bytes([bytes[4], bytes[5]]),
+            checksum: u16::from_be_bytes([bytes[6], bytes[7]]),
+        }
+    }
+
+    /// Tries to read an UDP-Lite header from the current position.
+    #[cfg(feature = "std")]
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<UdpLiteHeader, io::Error> {
+        let bytes = {
+            let mut bytes : [u8;8] = [0;8];
+            reader.read_exact(&mut bytes)?;
+            bytes
+        };
+        Ok(UdpLiteHeader::from_bytes(bytes))
+    }
+
+    /// Write the UDP-Lite header without recalculating the checksum.
+    #[cfg(feature = "std")]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write the UDP-Lite header to the start of the given slice without recalculating the checksum.
+    /// Returns the unused part of the slice.
+    pub fn write_to_slice<'a>(&self, slice: &'a mut [u8]) -> Result<&'a mut [u8], WriteError> {
+        use self::WriteError::*;
+        //length check
+        if slice.len() < UdpLiteHeader::SERIALIZED_SIZE {
+            Err(SliceTooSmall(UdpLiteHeader::SERIALIZED_SIZE))
+        } else {
+            slice[..UdpLiteHeader::SERIALIZED_SIZE].copy_from_slice(&self.to_bytes());
+            Ok(&mut slice[UdpLiteHeader::SERIALIZED_SIZE..])
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant UdpLiteHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        UdpLiteHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;8] {
+        let source_port_be = self.source_port.to_be_bytes();
+        let destination_port_be = self.destination_port.to_be_bytes();
+        let checksum_coverage_be = self.checksum_coverage.to_be_bytes();
+        let checksum = self.checksum.to_be_bytes();
+        [
+            source_port_be[0],
+            source_port_be[1],
+            destination_port_be[0],
+            destination_port_be[1],
+            checksum_coverage_be[0],
+            checksum_coverage_be[1],
+            checksum[0],
+            checksum[1],
+        ]
+    }
+}
+
+impl SerializedSize for UdpLiteHeader {
+    ///Size of the header itself
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+///A slice containing an UDP-Lite header of a network package. Struct allows the selective read of fields in the header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UdpLiteHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> UdpLiteHeaderSlice<'a> {
+
+    /// Creates a slice containing an UDP-Lite header.
+    #[inline]
+    pub fn from_slice(slice: &'a[u8]) -> Result<UdpLiteHeaderSlice<'a>, ReadError> {
+        //check length
+        use crate::ReadError::*;
+        if slice.len() < UdpLiteHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(UdpLiteHeader::SERIALIZED_SIZE));
+        }
+
+        //done
+        Ok(UdpLiteHeaderSlice{
+            // SAFETY:
+            // Safe as slice length is checked to be at least
+            // UdpLiteHeader::SERIALIZED_SIZE (8) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    UdpLiteHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the UDP-Lite header
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads the "source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SERIALIZED_SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Reads the "destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SERIALIZED_SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Reads the "checksum coverage" from the slice.
+    #[inline]
+    pub fn checksum_coverage(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SERIALIZED_SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Reads the "checksum" from the slice.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SERIALIZED_SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(6))
+        }
+    }
+
+    /// Decode all the fields and copy the results to a UdpLiteHeader struct
+    #[inline]
+    pub fn to_header(&self) -> UdpLiteHeader {
+        UdpLiteHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            checksum_coverage: self.checksum_coverage(),
+            checksum: self.checksum()
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use super::*;
+use etherparse::bpf::*;
+use etherparse::packet_filter::*;
+
+/// Ethernet, vlan (id 100, pcp 3), ipv4 10.0.1.2 > 192.168.1.2, tcp 1234 > 443 (syn & ack)
+fn tcp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .vlan(VlanHeader::Single(SingleVlanHeader{
+            priority_code_point: 3,
+            drop_eligible_indicator: false,
+            vlan_identifier: 100,
+            ether_type: 0,
+        }))
+        .ipv4([10,0,1,2], [192,168,1,2], 20)
+        .tcp(1234, 443, 1, 4000)
+        .syn()
+        .ack(2);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Ethernet, double vlan (ids 1 & 2), ipv6 fe80::1 > 2001:db8::2, udp 5000 > 53
+fn udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(1, 2)
+        .ipv6(
+            [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+            [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2],
+            20
+        )
+        .udp(5000, 53);
+    let mut packet = Vec::with_capacity(builder.size(2));
+    builder.write(&mut packet, &[5,6]).unwrap();
+    packet
+}
+
+/// Checks that the compiled expression returns the same result as
+/// `FilterExpression::applies_to_slice`.
+fn assert_equivalent(expression: &FilterExpression, packet: &[u8]) -> bool {
+    let program = compile(expression).unwrap();
+    let expected = expression.applies_to_slice(&SlicedPacket::from_ethernet(packet).unwrap());
+    let result = run(&program, packet).unwrap();
+    assert_eq!(
+        if expected { BPF_ACCEPT } else { 0 },
+        result,
+        "{:?}",
+        expression
+    );
+    expected
+}
+
+#[test]
+fn instruction() {
+    assert_eq!(
+        BpfInstruction{ code: BPF_RET | BPF_K, jt: 0, jf: 0, k: 5 },
+        BpfInstruction::statement(BPF_RET | BPF_K, 5)
+    );
+    assert_eq!(
+        BpfInstruction{ code: BPF_JMP | BPF_JEQ | BPF_K, jt: 1, jf: 2, k: 3 },
+        BpfInstruction::jump(BPF_JMP | BPF_JEQ | BPF_K, 3, 1, 2)
+    );
+    assert_eq!(8, std::mem::size_of::<BpfInstruction>());
+}
+
+#[test]
+fn run_instructions() {
+    use BpfInstruction as I;
+    let packet = [0x12, 0x34, 0x56, 0x78, 0x45];
+
+    // loads
+    assert_eq!(Ok(0x12345678), run(&[I::statement(BPF_LD | BPF_W | BPF_ABS, 0), I::statement(BPF_RET | BPF_A, 0)], &packet));
+    assert_eq!(Ok(0x3456), run(&[I::statement(BPF_LD | BPF_H | BPF_ABS, 1), I::statement(BPF_RET | BPF_A, 0)], &packet));
+    assert_eq!(Ok(0x45), run(&[I::statement(BPF_LD | BPF_B | BPF_ABS, 4), I::statement(BPF_RET | BPF_A, 0)], &packet));
+    assert_eq!(Ok(5), run(&[I::statement(BPF_LD | BPF_W | BPF_LEN, 0), I::statement(BPF_RET | BPF_A, 0)], &packet));
+    assert_eq!(
+        Ok(0x5678),
+        run(&[
+            I::statement(BPF_LDX | BPF_IMM, 2),
+            I::statement(BPF_LD | BPF_H | BPF_IND, 0),
+            I::statement(BPF_RET | BPF_A, 0)
+        ], &packet)
+    );
+    assert_eq!(
+        Ok(20),
+        run(&[
+            I::statement(BPF_LDX | BPF_B | BPF_MSH, 4),
+            I::statement(BPF_MISC | BPF_TXA, 0),
+            I::statement(BPF_RET | BPF_A, 0)
+        ], &packet)
+    );
+
+    // loads outside of the packet end the program
+    assert_eq!(Ok(0), run(&[I::statement(BPF_LD | BPF_W | BPF_ABS, 2), I::statement(BPF_RET | BPF_K, 1)], &packet));
+    assert_eq!(Ok(0), run(&[I::statement(BPF_LD | BPF_B | BPF_ABS, u32::MAX), I::statement(BPF_RET | BPF_K, 1)], &packet));
+    assert_eq!(
+        Ok(0),
+        run(&[
+            I::statement(BPF_LDX | BPF_IMM, u32::MAX),
+            I::statement(BPF_LD | BPF_B | BPF_IND, 1),
+            I::statement(BPF_RET | BPF_K, 1)
+        ], &packet)
+    );
+
+    // scratch memory
+    assert_eq!(
+        Ok(7),
+        run(&[
+            I::statement(BPF_LD | BPF_IMM, 7),
+            I::statement(BPF_ST, 15),
+            I::statement(BPF_LDX | BPF_MEM, 15),
+            I::statement(BPF_STX, 3),
+            I::statement(BPF_LD | BPF_MEM, 3),
+            I::statement(BPF_RET | BPF_A, 0)
+        ], &packet)
+    );
+
+    // alu operations
+    for (op, a, operand, expected) in [
+        (BPF_ADD, 5, 3, Some(8)),
+        (BPF_SUB, 5, 7, Some(5u32.wrapping_sub(7))),
+        (BPF_MUL, 5, 3, Some(15)),
+        (BPF_DIV, 7, 2, Some(3)),
+        (BPF_DIV, 7, 0, None),
+        (BPF_MOD, 7, 4, Some(3)),
+        (BPF_MOD, 7, 0, None),
+        (BPF_OR, 0b0101, 0b0011, Some(0b0111)),
+        (BPF_AND, 0b0101, 0b0011, Some(0b0001)),
+        (BPF_XOR, 0b0101, 0b0011, Some(0b0110)),
+        (BPF_LSH, 1, 4, Some(16)),
+        (BPF_LSH, 1, 32, Some(0)),
+        (BPF_RSH, 16, 4, Some(1)),
+        (BPF_NEG, 1, 0, Some(u32::MAX)),
+    ].iter() {
+        // constant operand
+        assert_eq!(
+            Ok(expected.unwrap_or(0)),
+            run(&[
+                I::statement(BPF_LD | BPF_IMM, *a),
+                I::statement(BPF_ALU | op | BPF_K, *operand),
+                I::statement(BPF_RET | BPF_A, 0)
+            ], &packet)
+        );
+        // index register operand
+        assert_eq!(
+            Ok(expected.unwrap_or(0)),
+            run(&[
+                I::statement(BPF_LDX | BPF_IMM, *operand),
+                I::statement(BPF_LD | BPF_IMM, *a),
+                I::statement(BPF_ALU | op | BPF_X, 0),
+                I::statement(BPF_RET | BPF_A, 0)
+            ], &packet)
+        );
+    }
+
+    // jumps
+    for (op, a, operand, expected) in [
+        (BPF_JEQ, 5, 5, true),
+        (BPF_JEQ, 5, 6, false),
+        (BPF_JGT, 6, 5, true),
+        (BPF_JGT, 5, 5, false),
+        (BPF_JGE, 5, 5, true),
+        (BPF_JGE, 4, 5, false),
+        (BPF_JSET, 0b0110, 0b0100, true),
+        (BPF_JSET, 0b0110, 0b1001, false),
+    ].iter() {
+        let result = run(&[
+            I::statement(BPF_LDX | BPF_IMM, *operand),
+            I::statement(BPF_LD | BPF_IMM, *a),
+            I::jump(BPF_JMP | op | BPF_X, 0, 1, 2),
+            I::statement(BPF_RET | BPF_K, 0),
+            I::statement(BPF_RET | BPF_K, 1),
+            I::statement(BPF_RET | BPF_K, 2),
+        ], &packet);
+        assert_eq!(Ok(if *expected { 1 } else { 2 }), result);
+    }
+    assert_eq!(
+        Ok(2),
+        run(&[
+            I::statement(BPF_JMP | BPF_JA, 1),
+            I::statement(BPF_RET | BPF_K, 1),
+            I::statement(BPF_RET | BPF_K, 2),
+        ], &packet)
+    );
+}
+
+#[test]
+fn run_errors() {
+    use BpfInstruction as I;
+    assert_eq!(Err(BpfError::ProgramCounterOutOfRange(0)), run(&[], &[]));
+    assert_eq!(
+        Err(BpfError::ProgramCounterOutOfRange(1)),
+        run(&[I::statement(BPF_LD | BPF_IMM, 0)], &[])
+    );
+    assert_eq!(
+        Err(BpfError::ProgramCounterOutOfRange(3)),
+        run(&[I::statement(BPF_JMP | BPF_JA, 2)], &[])
+    );
+    assert_eq!(
+        Err(BpfError::InvalidMemoryIndex{ index: 1, k: 16 }),
+        run(&[I::statement(BPF_LD | BPF_IMM, 0), I::statement(BPF_ST, 16)], &[])
+    );
+    assert_eq!(
+        Err(BpfError::InvalidInstruction{ index: 0, code: BPF_LD | BPF_MSH }),
+        run(&[I::statement(BPF_LD | BPF_MSH, 0)], &[])
+    );
+    assert_eq!(
+        Err(BpfError::InvalidInstruction{ index: 0, code: BPF_ALU | 0xb0 }),
+        run(&[I::statement(BPF_ALU | 0xb0, 0)], &[])
+    );
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        "BpfError: The program has 5000 instructions, which exceeds the maximum of 4096 instructions.",
+        &format!("{}", BpfError::ProgramTooLong(5000))
+    );
+    assert_eq!(
+        "BpfError: Invalid operation code 0x00b4 in the instruction at index 2.",
+        &format!("{}", BpfError::InvalidInstruction{ index: 2, code: 0xb4 })
+    );
+    assert_eq!(
+        "BpfError: The instruction at index 1 accesses the non existing scratch memory word 16.",
+        &format!("{}", BpfError::InvalidMemoryIndex{ index: 1, k: 16 })
+    );
+    assert_eq!(
+        "BpfError: The program counter 3 is outside of the program (missing return instruction or jump out of range).",
+        &format!("{}", BpfError::ProgramCounterOutOfRange(3))
+    );
+    use std::error::Error;
+    assert!(BpfError::ProgramTooLong(0).source().is_none());
+}
+
+#[test]
+fn compile_expressions() {
+    use etherparse::pcap_filter::parse;
+    let packets = [tcp_packet(), udp_packet()];
+    for (expression, expected) in [
+        ("", [true, true]),
+        ("vlan", [true, true]),
+        ("vlan 100", [true, false]),
+        ("vlan 1", [false, true]),
+        ("ether src 01:02:03:04:05:06", [true, true]),
+        ("ether dst 01:02:03:04:05:06", [false, false]),
+        ("ip", [true, false]),
+        ("ip6", [false, true]),
+        ("host 10.0.1.2", [true, false]),
+        ("src net 10.0.0.0/8", [true, false]),
+        ("dst net 10.0.0.0/8", [false, false]),
+        ("net 2001:db8::/32", [false, true]),
+        ("src host fe80::1", [false, true]),
+        ("port 53", [false, true]),
+        ("src portrange 1000-2000", [true, false]),
+        ("tcp", [true, false]),
+        ("udp", [false, true]),
+        ("icmp or icmp6", [false, false]),
+        ("tcp[tcpflags] & (tcp-syn|tcp-ack) == 0x12", [true, false]),
+        ("tcp[20:4] = 0x01020304", [true, false]),
+        ("tcp[24] >= 0", [false, false]),
+        ("udp[8:2] = 0x0506", [false, true]),
+        ("ether[12:2] = 0x8100", [true, false]),
+        ("ip[0] & 0xf = 5", [true, false]),
+        ("ip6[6] == 17", [false, true]),
+        ("not tcp and not udp", [false, false]),
+        ("(tcp and port 443) or (udp and port 53)", [true, true]),
+    ].iter() {
+        let filter = parse(expression).unwrap();
+        for (packet, expected) in packets.iter().zip(expected.iter()) {
+            assert_eq!(*expected, assert_equivalent(&filter, packet), "{}", expression);
+        }
+    }
+
+    // expressions without a pcap filter syntax
+    for (expression, expected) in [
+        (FilterExpression::VlanPcp(3), [true, false]),
+        (FilterExpression::VlanPcp(0), [false, true]),
+        (FilterExpression::Dscp(0), [true, true]),
+        (FilterExpression::PayloadLength{ min: 4, max: 4 }, [true, false]),
+        (FilterExpression::PayloadLength{ min: 2, max: 3 }, [false, true]),
+        (FilterExpression::TcpFlags{ mask: TCP_FLAG_SYN, value: TCP_FLAG_SYN }, [true, false]),
+        (
+            FilterExpression::Bytes{ layer: FilterLayer::Ipv4, offset: 12, size: 3, mask: u32::MAX, op: CompareOp::Equal, value: 0x0a0001 },
+            [true, false]
+        ),
+        (
+            FilterExpression::Bytes{ layer: FilterLayer::Ipv6, offset: 0, size: 0, mask: u32::MAX, op: CompareOp::Equal, value: 0 },
+            [false, true]
+        ),
+    ].iter() {
+        for (packet, expected) in packets.iter().zip(expected.iter()) {
+            assert_eq!(*expected, assert_equivalent(expression, packet));
+        }
+    }
+}
+
+#[test]
+fn compile_filter_layers() {
+    let filter = Filter{
+        link: ElementFilter::Any,
+        vlan: ElementFilter::Some(VlanFilter::Double{ outer_identifier: Some(1), inner_identifier: Some(2) }),
+        ip: ElementFilter::Any,
+        transport: ElementFilter::Some(TransportFilter::Udp{ source_port: Some(5000), destination_port: None }),
+    };
+    let program = compile_filter(&filter).unwrap();
+    assert_eq!(Ok(0), run(&program, &tcp_packet()));
+    assert_eq!(Ok(BPF_ACCEPT), run(&program, &udp_packet()));
+}
+
+#[test]
+fn long_jumps() {
+    // enough comparisons to require jumps further than 255 instructions
+    let ports: Vec<u16> = (1000..1400).collect();
+    let expression = FilterExpression::Or(vec![
+        FilterExpression::Port{ direction: Direction::Destination, ports: PortMatch::List(ports) },
+        FilterExpression::IpNumber(ip_number::UDP),
+    ]);
+    let program = compile(&expression).unwrap();
+    assert!(program.len() > 2*usize::from(u8::MAX));
+    assert_equivalent(&expression, &tcp_packet());
+    assert_equivalent(&expression, &udp_packet());
+    for port in [443, 999, 1000, 1200, 1399, 1400].iter() {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,1,2], [192,168,1,2], 20)
+            .tcp(1234, *port, 1, 4000);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_eq!(
+            (1000..1400).contains(port),
+            assert_equivalent(&expression, &packet)
+        );
+    }
+}
+
+#[test]
+fn program_too_long() {
+    let expression = FilterExpression::Or(
+        (0..5000u16).map(|port| FilterExpression::Port{
+            direction: Direction::Source,
+            ports: PortMatch::List(vec![port]),
+        }).collect()
+    );
+    assert_matches!(compile(&expression), Err(BpfError::ProgramTooLong(_)));
+}
+
+fn direction_any() -> impl Strategy<Value = Direction> {
+    prop_oneof![
+        Just(Direction::Source),
+        Just(Direction::Destination),
+        Just(Direction::SourceOrDestination),
+        Just(Direction::SourceAndDestination),
+    ]
+}
+
+fn filter_layer_any() -> impl Strategy<Value = FilterLayer> {
+    prop_oneof![
+        Just(FilterLayer::Ethernet2),
+        Just(FilterLayer::Ipv4),
+        Just(FilterLayer::Ipv6),
+        Just(FilterLayer::Tcp),
+        Just(FilterLayer::Udp),
+        Just(FilterLayer::Icmpv4),
+        Just(FilterLayer::Icmpv6),
+    ]
+}
+
+fn compare_op_any() -> impl Strategy<Value = CompareOp> {
+    prop_oneof![
+        Just(CompareOp::Equal),
+        Just(CompareOp::NotEqual),
+        Just(CompareOp::Less),
+        Just(CompareOp::LessOrEqual),
+        Just(CompareOp::Greater),
+        Just(CompareOp::GreaterOrEqual),
+    ]
+}
+
+fn expression_leaf_any() -> impl Strategy<Value = FilterExpression> {
+    use FilterExpression::*;
+    prop_oneof![
+        filter_any().prop_map(Layers),
+        (direction_any(), any::<[u8;4]>(), 0u8..34)
+            .prop_map(|(direction, address, prefix_len)| Ipv4Net{ direction, net: Ipv4Cidr{ address, prefix_len } }),
+        (direction_any(), any::<[u8;16]>(), prop_oneof![0u8..4, 120u8..130])
+            .prop_map(|(direction, address, prefix_len)| Ipv6Net{ direction, net: Ipv6Cidr{ address, prefix_len } }),
+        (direction_any(), any::<u16>(), any::<u16>())
+            .prop_map(|(direction, min, max)| Port{ direction, ports: PortMatch::Range{ min, max } }),
+        (direction_any(), proptest::collection::vec(any::<u16>(), 0..3))
+            .prop_map(|(direction, ports)| Port{ direction, ports: PortMatch::List(ports) }),
+        prop_oneof![Just(1u8), Just(6), Just(17), Just(58), Just(51), Just(44), Just(0)].prop_map(IpNumber),
+        (any::<u16>(), any::<u16>()).prop_map(|(mask, value)| TcpFlags{ mask, value: value & mask & 0x1ff }),
+        (proptest::option::of(0u8..4), proptest::option::of(0u8..2)).prop_map(|(icmp_type, code)| Icmpv4{ icmp_type, code }),
+        (proptest::option::of(0u8..4), proptest::option::of(0u8..2)).prop_map(|(icmp_type, code)| Icmpv6{ icmp_type, code }),
+        (0u8..4).prop_map(Dscp),
+        (0u8..8).prop_map(VlanPcp),
+        (0usize..40, 0usize..80).prop_map(|(min, max)| PayloadLength{ min, max }),
+        (filter_layer_any(), 0usize..80, 0u8..6, prop_oneof![Just(u32::MAX), Just(0), any::<u32>()], compare_op_any(), prop_oneof![Just(0u32), 0u32..0x100, any::<u32>()])
+            .prop_map(|(layer, offset, size, mask, op, value)| Bytes{ layer, offset, size, mask, op, value }),
+    ]
+}
+
+fn expression_any() -> impl Strategy<Value = FilterExpression> {
+    expression_leaf_any().prop_recursive(3, 16, 4, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..4).prop_map(FilterExpression::And),
+            proptest::collection::vec(inner.clone(), 0..4).prop_map(FilterExpression::Or),
+            inner.prop_map(|e| FilterExpression::Not(Box::new(e))),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn compiled_equals_applies_to_slice(
+        packet in ethernet_packet_any(),
+        expressions in proptest::collection::vec(expression_any(), 1..8)
+    ) {
+        if let Ok(sliced) = SlicedPacket::from_ethernet(&packet) {
+            for expression in expressions.iter() {
+                let program = compile(expression).unwrap();
+                let expected = if expression.applies_to_slice(&sliced) { BPF_ACCEPT } else { 0 };
+                prop_assert_eq!(Ok(expected), run(&program, &packet), "{:?}", expression);
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use super::*;
+
+mod tunnel_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn header_len_ip_number(
+            udp in udp_any(),
+            gre in gre_any()
+        ) {
+            assert_eq!(UdpHeader::SERIALIZED_SIZE, TunnelHeader::Udp(udp.clone()).header_len());
+            assert_eq!(gre.header_len(), TunnelHeader::Gre(gre.clone()).header_len());
+            assert_eq!(ip_number::UDP, TunnelHeader::Udp(udp.clone()).ip_number());
+            assert_eq!(ip_number::GRE, TunnelHeader::Gre(gre).ip_number());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(
+            udp in udp_any(),
+            gre in gre_any()
+        ) {
+            // udp
+            {
+                let mut buffer = [0u8;UdpHeader::SERIALIZED_SIZE + 1];
+                let rest = TunnelHeader::Udp(udp.clone()).write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&udp.to_bytes(), &buffer[..UdpHeader::SERIALIZED_SIZE]);
+            }
+            // gre
+            {
+                let mut buffer = [0u8;GreHeader::MAX_LEN + 1];
+                let rest = TunnelHeader::Gre(gre.clone()).write_to_slice(&mut buffer).unwrap();
+                assert_eq!(GreHeader::MAX_LEN + 1 - gre.header_len(), rest.len());
+                assert_eq!(&gre.to_bytes()[..], &buffer[..gre.header_len()]);
+            }
+            // slice too small
+            assert_eq!(
+                Some(UdpHeader::SERIALIZED_SIZE),
+                TunnelHeader::Udp(udp.clone()).write_to_slice(&mut [0u8;1])
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+            assert_eq!(
+                Some(gre.header_len()),
+                TunnelHeader::Gre(gre.clone()).write_to_slice(&mut [0u8;1])
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+        }
+    }
+}
+
+#[test]
+fn default() {
+    let encapsulation: Encapsulation = Default::default();
+    assert_eq!(0, encapsulation.header_len());
+
+    // without headers the payload range is returned unchanged
+    let mut buffer = [1,2,3,4];
+    assert_eq!(
+        1..3,
+        encapsulation.prepend(&mut buffer, 1..3).unwrap()
+    );
+    assert_eq!([1,2,3,4], buffer);
+}
+
+proptest! {
+    #[test]
+    fn header_len(
+        ethernet2 in ethernet_2_any(),
+        vlan in vlan_double_any(),
+        ipv4 in ipv4_any(),
+        gre in gre_any()
+    ) {
+        let encapsulation = Encapsulation {
+            ethernet2: Some(ethernet2.clone()),
+            vlan: Some(VlanHeader::Double(vlan.clone())),
+            ip: Some(IpHeader::Version4(ipv4.clone(), Default::default())),
+            tunnel: Some(TunnelHeader::Gre(gre.clone())),
+        };
+        assert_eq!(
+            ethernet2.header_len() + vlan.header_len() + ipv4.header_len() + gre.header_len(),
+            encapsulation.header_len()
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn prepend_matches_packet_builder(
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+        headroom in 0usize..8,
+        tailroom in 0usize..8
+    ) {
+        let expected = {
+            let mut result = Vec::new();
+            PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .single_vlan(0x123)
+                .ipv4([13,14,15,16], [17,18,19,20], 21)
+                .udp(22, 23)
+                .write(&mut result, &payload)
+                .unwrap();
+            result
+        };
+
+        let encapsulation = Encapsulation {
+            ethernet2: Some(Ethernet2Header {
+                source: [1,2,3,4,5,6],
+                destination: [7,8,9,10,11,12],
+                ether_type: 0,
+            }),
+            vlan: Some(VlanHeader::Single(SingleVlanHeader {
+                priority_code_point: 0,
+                drop_eligible_indicator: false,
+                vlan_identifier: 0x123,
+                ether_type: 0,
+            })),
+            ip: Some(IpHeader::Version4(
+                {
+                    let mut value: Ipv4Header = Default::default();
+                    value.source = [13,14,15,16];
+                    value.destination = [17,18,19,20];
+                    value.time_to_live = 21;
+                    value
+                },
+                Default::default()
+            )),
+            tunnel: Some(TunnelHeader::Udp(UdpHeader {
+                source_port: 22,
+                destination_port: 23,
+                length: 0,
+                checksum: 0,
+            })),
+        };
+        let header_len = encapsulation.header_len();
+        assert_eq!(expected.len() - payload.len(), header_len);
+
+        // enough headroom
+        {
+            let payload_start = header_len + headroom;
+            let payload_end = payload_start + payload.len();
+            let mut buffer = vec![0xffu8;payload_end + tailroom];
+            buffer[payload_start..payload_end].copy_from_slice(&payload);
+
+            let frame = encapsulation.prepend(&mut buffer, payload_start..payload_end).unwrap();
+            assert_eq!(headroom..payload_end, frame);
+            assert_eq!(&expected[..], &buffer[frame]);
+
+            // the data around the frame is untouched
+            assert!(buffer[..headroom].iter().all(|v| *v == 0xff));
+            assert!(buffer[payload_end..].iter().all(|v| *v == 0xff));
+        }
+
+        // not enough headroom
+        for payload_start in 0..header_len {
+            let payload_end = payload_start + payload.len();
+            let mut buffer = vec![0xffu8;payload_end];
+            assert_eq!(
+                Some(header_len),
+                encapsulation.prepend(&mut buffer, payload_start..payload_end)
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+            // nothing gets written
+            assert!(buffer.iter().all(|v| *v == 0xff));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn prepend_gre_ipv6(
+        ethernet2 in ethernet_2_any(),
+        ipv6 in ipv6_any(),
+        gre in gre_any(),
+        checksum in any::<bool>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..64)
+    ) {
+        let gre = GreHeader {
+            checksum: if checksum { Some(0) } else { None },
+            ..gre
+        };
+        let encapsulation = Encapsulation {
+            ethernet2: Some(ethernet2.clone()),
+            vlan: None,
+            ip: Some(IpHeader::Version6(ipv6.clone(), Default::default())),
+            tunnel: Some(TunnelHeader::Gre(gre.clone())),
+        };
+        let header_len = encapsulation.header_len();
+
+        let mut buffer = vec![0u8;header_len + payload.len()];
+        buffer[header_len..].copy_from_slice(&payload);
+        let frame = encapsulation.prepend(&mut buffer, header_len..header_len + payload.len()).unwrap();
+        assert_eq!(0..buffer.len(), frame);
+
+        // ethernet
+        let (actual_eth, rest) = Ethernet2Header::from_slice(&buffer).unwrap();
+        assert_eq!(
+            Ethernet2Header {
+                ether_type: ether_type::IPV6,
+                ..ethernet2
+            },
+            actual_eth
+        );
+
+        // ipv6
+        let (actual_ipv6, rest) = Ipv6Header::from_slice(rest).unwrap();
+        assert_eq!(
+            Ipv6Header {
+                payload_length: (gre.header_len() + payload.len()) as u16,
+                next_header: ip_number::GRE,
+                ..ipv6
+            },
+            actual_ipv6
+        );
+
+        // gre
+        let (actual_gre, rest) = GreHeader::from_slice(rest).unwrap();
+        assert_eq!(gre.key, actual_gre.key);
+        assert_eq!(gre.sequence_number, actual_gre.sequence_number);
+        assert_eq!(gre.protocol_type, actual_gre.protocol_type);
+        if checksum {
+            assert_eq!(Some(gre.calc_checksum(&payload)), actual_gre.checksum);
+        } else {
+            assert_eq!(None, actual_gre.checksum);
+        }
+
+        // payload
+        assert_eq!(&payload[..], rest);
+    }
+}
+
+proptest! {
+    #[test]
+    fn prepend_double_vlan_ip_in_ip(
+        ethernet2 in ethernet_2_any(),
+        vlan in vlan_double_any(),
+        ipv4 in ipv4_with(ip_number::IPV4),
+        payload in proptest::collection::vec(any::<u8>(), 0..64)
+    ) {
+        let encapsulation = Encapsulation {
+            ethernet2: Some(ethernet2),
+            vlan: Some(VlanHeader::Double(vlan.clone())),
+            ip: Some(IpHeader::Version4(ipv4.clone(), Default::default())),
+            tunnel: None,
+        };
+        let header_len = encapsulation.header_len();
+
+        let mut buffer = vec![0u8;header_len + payload.len()];
+        buffer[header_len..].copy_from_slice(&payload);
+        let buffer_len = buffer.len();
+        encapsulation.prepend(&mut buffer, header_len..buffer_len).unwrap();
+
+        let (actual_eth, rest) = Ethernet2Header::from_slice(&buffer).unwrap();
+        assert_eq!(ether_type::PROVIDER_BRIDGING, actual_eth.ether_type);
+
+        let (actual_vlan, rest) = DoubleVlanHeader::from_slice(rest).unwrap();
+        assert_eq!(ether_type::VLAN_TAGGED_FRAME, actual_vlan.outer.ether_type);
+        assert_eq!(ether_type::IPV4, actual_vlan.inner.ether_type);
+        assert_eq!(vlan.outer.vlan_identifier, actual_vlan.outer.vlan_identifier);
+        assert_eq!(vlan.inner.vlan_identifier, actual_vlan.inner.vlan_identifier);
+
+        // without a tunnel header the protocol is kept as set
+        let (actual_ipv4, rest) = Ipv4Header::from_slice(rest).unwrap();
+        assert_eq!(ip_number::IPV4, actual_ipv4.protocol);
+        assert_eq!(
+            (ipv4.header_len() + payload.len()) as u16,
+            actual_ipv4.total_len()
+        );
+        assert_eq!(actual_ipv4.calc_header_checksum().unwrap(), actual_ipv4.header_checksum);
+        assert_eq!(&payload[..], rest);
+    }
+}
+
+#[test]
+fn prepend_value_error() {
+    // udp payload too big
+    let payload_len = (u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE + 1;
+    let encapsulation = Encapsulation {
+        ethernet2: None,
+        vlan: None,
+        ip: None,
+        tunnel: Some(TunnelHeader::Udp(Default::default())),
+    };
+    let mut buffer = vec![0u8;UdpHeader::SERIALIZED_SIZE + payload_len];
+    assert_eq!(
+        Some(ValueError::UdpPayloadLengthTooLarge(payload_len)),
+        encapsulation.prepend(&mut buffer, UdpHeader::SERIALIZED_SIZE..UdpHeader::SERIALIZED_SIZE + payload_len)
+            .unwrap_err()
+            .value_error()
+    );
+}
+
+#[test]
+#[should_panic]
+fn prepend_payload_out_of_range() {
+    let encapsulation: Encapsulation = Default::default();
+    let mut buffer = [0u8;4];
+    let _ = encapsulation.prepend(&mut buffer, 2..5);
+}
@@ -8,6 +8,8 @@ proptest! {
         arg_u16 in any::<u16>(),
         arg_usize in any::<usize>(),
         arg2_usize in any::<usize>(),
+        arg_u32 in any::<u32>(),
+        arg2_u32 in any::<u32>(),
     ) { //arg_u16 in any::<u16>()
 
         use super::ReadError::*;
@@ -98,6 +100,90 @@ proptest! {
             &format!("ReadError: ICMPv6 packet length {} is bigger then can be represented in an u32.", arg_usize),
             &format!("{}", Icmpv6PacketTooBig(arg_usize))
         );
+
+        //IcmpExtensionUnexpectedVersion
+        assert_eq!(
+            &format!("ReadError: Unexpected ICMP extension structure version. Expected version 2 but the extension header contained the version number {}.", arg_u8),
+            &format!("{}", IcmpExtensionUnexpectedVersion(arg_u8))
+        );
+
+        //IcmpExtensionObjectLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: ICMP extension object length too small. The length value {} in the extension object is smaller then the object header itself.", arg_u16),
+            &format!("{}", IcmpExtensionObjectLengthTooSmall(arg_u16))
+        );
+
+        //SctpChunkLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: SCTP chunk length too small. The length value {} in the chunk is smaller then the chunk header itself.", arg_u16),
+            &format!("{}", SctpChunkLengthTooSmall(arg_u16))
+        );
+
+        //SctpParameterLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: SCTP parameter length too small. The length value {} in the parameter is smaller then the parameter header itself.", arg_u16),
+            &format!("{}", SctpParameterLengthTooSmall(arg_u16))
+        );
+
+        //DccpDataOffsetTooSmall
+        assert_eq!(
+            &format!("ReadError: DCCP data offset too small. The data offset value {} in the DCCP header is smaller then the fixed part of the header.", arg_u8),
+            &format!("{}", DccpDataOffsetTooSmall(arg_u8))
+        );
+
+        //DccpOptionLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: DCCP option length too small. The length value {} in the option is smaller then the minimum of 2.", arg_u8),
+            &format!("{}", DccpOptionLengthTooSmall(arg_u8))
+        );
+
+        //GreUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GRE version {} (only version 0 is supported).", arg_u8),
+            &format!("{}", GreUnsupportedVersion(arg_u8))
+        );
+
+        //PcapUnknownMagicNumber
+        assert_eq!(
+            &format!("ReadError: Unknown pcap magic number 0x{:08x}.", arg_u32),
+            &format!("{}", PcapUnknownMagicNumber(arg_u32))
+        );
+
+        //PcapCapturedLengthTooBig
+        assert_eq!(
+            &format!("ReadError: Captured length {} of the pcap packet is bigger then the allowed maximum of {} bytes.", arg_u32, arg2_u32),
+            &format!("{}", PcapCapturedLengthTooBig{ captured_len: arg_u32, max_len: arg2_u32 })
+        );
+
+        //PcapngByteOrderMagicUnknown
+        assert_eq!(
+            &format!("ReadError: Unknown pcapng byte order magic 0x{:08x} in the section header block.", arg_u32),
+            &format!("{}", PcapngByteOrderMagicUnknown(arg_u32))
+        );
+
+        //PcapngBlockLengthBad
+        assert_eq!(
+            &format!("ReadError: Bad pcapng block length. The block length {} is not a multiple of 4, too big or does not match the block content.", arg_u32),
+            &format!("{}", PcapngBlockLengthBad(arg_u32))
+        );
+
+        //PcapngOptionLengthTooBig
+        assert_eq!(
+            &format!("ReadError: The length {} of a pcapng option exceeds the block containing it.", arg_u16),
+            &format!("{}", PcapngOptionLengthTooBig(arg_u16))
+        );
+
+        //PcapngFirstBlockNotSectionHeader
+        assert_eq!(
+            &format!("ReadError: Expected a pcapng section header block at the start of the file but found a block with type 0x{:08x}.", arg_u32),
+            &format!("{}", PcapngFirstBlockNotSectionHeader(arg_u32))
+        );
+
+        //PcapngUnknownInterfaceId
+        assert_eq!(
+            &format!("ReadError: A pcapng packet references the undescribed interface with id {}.", arg_u32),
+            &format!("{}", PcapngUnknownInterfaceId(arg_u32))
+        );
     }
 }
 
@@ -126,6 +212,20 @@ fn read_error_source() {
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
         Icmpv6PacketTooBig(0),
+        IcmpExtensionUnexpectedVersion(0),
+        IcmpExtensionObjectLengthTooSmall(0),
+        SctpChunkLengthTooSmall(0),
+        SctpParameterLengthTooSmall(0),
+        DccpDataOffsetTooSmall(0),
+        DccpOptionLengthTooSmall(0),
+        GreUnsupportedVersion(0),
+        PcapUnknownMagicNumber(0),
+        PcapCapturedLengthTooBig{ captured_len: 0, max_len: 0 },
+        PcapngByteOrderMagicUnknown(0),
+        PcapngBlockLengthBad(0),
+        PcapngOptionLengthTooBig(0),
+        PcapngFirstBlockNotSectionHeader(0),
+        PcapngUnknownInterfaceId(0),
     ];
 
     for value in &none_values {
@@ -133,6 +233,93 @@ fn read_error_source() {
     }
 }
 
+#[test]
+fn read_error_field() {
+    use super::ReadError::*;
+
+    let tests = [
+        (UnexpectedEndOfSlice(0), None),
+        (UnexpectedLenOfSlice{ expected: 0, actual: 0 }, None),
+        (DoubleVlanOuterNonVlanEtherType(0), Some(ErrorField::VlanTagEtherType)),
+        (IpUnsupportedVersion(0), Some(ErrorField::IpVersion)),
+        (Ipv4UnexpectedVersion(0), Some(ErrorField::IpVersion)),
+        (Ipv4HeaderLengthBad(0), Some(ErrorField::Ipv4Ihl)),
+        (Ipv4TotalLengthTooSmall(0), Some(ErrorField::Ipv4TotalLength)),
+        (Ipv6UnexpectedVersion(0), Some(ErrorField::IpVersion)),
+        (Ipv6TooManyHeaderExtensions, None),
+        (Ipv6HopByHopHeaderNotAtStart, None),
+        (IpAuthenticationHeaderTooSmallPayloadLength(0), Some(ErrorField::IpAuthPayloadLength)),
+        (TcpDataOffsetTooSmall(0), Some(ErrorField::TcpDataOffset)),
+        (Icmpv6PacketTooBig(0), None),
+        (IcmpExtensionUnexpectedVersion(0), Some(ErrorField::IcmpExtensionVersion)),
+        (IcmpExtensionObjectLengthTooSmall(0), Some(ErrorField::IcmpExtensionObjectLength)),
+        (SctpChunkLengthTooSmall(0), Some(ErrorField::SctpChunkLength)),
+        (SctpParameterLengthTooSmall(0), Some(ErrorField::SctpParameterLength)),
+        (DccpDataOffsetTooSmall(0), Some(ErrorField::DccpDataOffset)),
+        (DccpOptionLengthTooSmall(0), Some(ErrorField::DccpOptionLength)),
+        (GreUnsupportedVersion(0), Some(ErrorField::GreVersion)),
+        (PcapUnknownMagicNumber(0), None),
+        (PcapngBlockLengthBad(0), None),
+    ];
+    for (err, expected) in tests.iter() {
+        assert_eq!(*expected, err.field());
+    }
+}
+
+#[test]
+fn read_error_context_display() {
+    let context = ReadErrorContext{
+        layer: Layer::TcpHeader,
+        offset: 34,
+        required_len: None,
+        available_len: 19,
+        field: None,
+    };
+    assert_eq!(
+        "TCP header at offset 34 (19 bytes available)",
+        &format!("{}", context)
+    );
+    assert_eq!(
+        "TCP header at offset 34 (19 bytes available, 20 bytes required)",
+        &format!("{}", ReadErrorContext{ required_len: Some(20), ..context })
+    );
+    assert_eq!(
+        "TCP header at offset 34 (19 bytes available, invalid field TcpHeader.data_offset)",
+        &format!("{}", ReadErrorContext{ field: Some(ErrorField::TcpDataOffset), ..context })
+    );
+}
+
+#[test]
+fn packet_read_error() {
+    use std::error::Error;
+
+    let err = PacketHeaders::from_ethernet_slice(&[0;4]).unwrap_err();
+    assert_matches!(err.error, ReadError::UnexpectedEndOfSlice(14));
+    assert_eq!(
+        ReadErrorContext{
+            layer: Layer::Ethernet2Header,
+            offset: 0,
+            required_len: Some(14),
+            available_len: 4,
+            field: None,
+        },
+        err.context
+    );
+    assert_eq!(
+        format!("{} in {}", err.error, err.context),
+        format!("{}", err)
+    );
+    assert!(format!("{:?}", err).starts_with("PacketReadError"));
+    assert_eq!(
+        format!("{}", err.error),
+        format!("{}", err.source().unwrap())
+    );
+
+    // conversion to the plain error
+    let plain: ReadError = err.into();
+    assert_matches!(plain, ReadError::UnexpectedEndOfSlice(14));
+}
+
 #[test]
 fn read_error_debug() {
     use super::ReadError::*;
@@ -152,6 +339,20 @@ fn read_error_debug() {
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
         Icmpv6PacketTooBig(0),
+        IcmpExtensionUnexpectedVersion(0),
+        IcmpExtensionObjectLengthTooSmall(0),
+        SctpChunkLengthTooSmall(0),
+        SctpParameterLengthTooSmall(0),
+        DccpDataOffsetTooSmall(0),
+        DccpOptionLengthTooSmall(0),
+        GreUnsupportedVersion(0),
+        PcapUnknownMagicNumber(0),
+        PcapCapturedLengthTooBig{ captured_len: 0, max_len: 0 },
+        PcapngByteOrderMagicUnknown(0),
+        PcapngBlockLengthBad(0),
+        PcapngOptionLengthTooBig(0),
+        PcapngFirstBlockNotSectionHeader(0),
+        PcapngUnknownInterfaceId(0),
     ];
 
     for value in &values {
@@ -236,7 +437,17 @@ fn value_error_source() {
         U8TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
+        U64TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         Icmpv6InIpv4,
+        UdpLiteChecksumCoverageBad(0),
+        DccpLengthTooLarge(0),
+        DccpChecksumCoverageBad(0),
+        DccpOptionsLengthBad(0),
+        PcapngUnknownInterfaceId(0),
+        PcapngOptionTooLarge(0),
+        HeaderFieldUnknown("a".to_string()),
+        HeaderFieldValueTooLarge{ name: "a", value: 0, bit_width: 0 },
+        HeaderFieldOutOfSlice{ name: "a", required_len: 0, actual_len: 0 },
     ];
 
     for value in &none_values {
@@ -262,7 +473,17 @@ fn value_error_debug() {
         U8TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
+        U64TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         Icmpv6InIpv4,
+        UdpLiteChecksumCoverageBad(0),
+        DccpLengthTooLarge(0),
+        DccpChecksumCoverageBad(0),
+        DccpOptionsLengthBad(0),
+        PcapngUnknownInterfaceId(0),
+        PcapngOptionTooLarge(0),
+        HeaderFieldUnknown("a".to_string()),
+        HeaderFieldValueTooLarge{ name: "a", value: 0, bit_width: 0 },
+        HeaderFieldOutOfSlice{ name: "a", required_len: 0, actual_len: 0 },
     ];
 
     for value in &values {
@@ -279,6 +500,8 @@ proptest! {
         max_u16 in any::<u16>(),
         value_u32 in any::<u32>(),
         max_u32 in any::<u32>(),
+        value_u64 in any::<u64>(),
+        max_u64 in any::<u64>(),
         arg_usize in any::<usize>(),
         field in error_field_any(),
         ip_protocol_number in ip_number_any(),
@@ -369,7 +592,7 @@ proptest! {
             &format!("{}", U8TooLarge{
                 value: value_u8,
                 max: max_u8,
-                field: field.clone()
+                field
             })
         );
 
@@ -379,7 +602,7 @@ proptest! {
             &format!("{}", U16TooLarge{
                 value: value_u16,
                 max: max_u16,
-                field: field.clone()
+                field
             })
         );
 
@@ -389,7 +612,7 @@ proptest! {
             &format!("{}", U32TooLarge{
                 value: value_u32,
                 max: max_u32,
-                field: field.clone()
+                field
             })
         );
 
@@ -398,6 +621,70 @@ proptest! {
             &format!("ICMPv6 packet can not be combined with IPv4 headers."),
             &format!("{}", Icmpv6InIpv4)
         );
+
+        //U64TooLarge
+        assert_eq!(
+            &format!("The value {} of the field '{}' is larger then the allowed maximum of {}.", value_u64, field, max_u64),
+            &format!("{}", U64TooLarge{
+                value: value_u64,
+                max: max_u64,
+                field
+            })
+        );
+
+        //UdpLiteChecksumCoverageBad
+        assert_eq!(
+            &format!("UDP-Lite checksum coverage bad. The checksum coverage ({} bytes) is either smaller then the UDP-Lite header or bigger then the UDP-Lite packet.", value_u16),
+            &format!("{}", UdpLiteChecksumCoverageBad(value_u16))
+        );
+
+        //DccpLengthTooLarge
+        assert_eq!(
+            &format!("DCCP length too large. The DCCP packet length ({} bytes) is larger then what is supported.", arg_usize),
+            &format!("{}", DccpLengthTooLarge(arg_usize))
+        );
+
+        //DccpChecksumCoverageBad
+        assert_eq!(
+            &format!("DCCP checksum coverage bad. The checksum coverage value {} covers more application data then present.", value_u8),
+            &format!("{}", DccpChecksumCoverageBad(value_u8))
+        );
+
+        //DccpOptionsLengthBad
+        assert_eq!(
+            &format!("Bad DCCP options length. The DCCP options length ({} bytes) is either not a multiple of 4 bytes or too big to be represented in the data offset field.", arg_usize),
+            &format!("{}", DccpOptionsLengthBad(arg_usize))
+        );
+
+        //PcapngUnknownInterfaceId
+        assert_eq!(
+            &format!("Unknown pcapng interface id {}. No interface with this id was added to the writer.", value_u32),
+            &format!("{}", PcapngUnknownInterfaceId(value_u32))
+        );
+
+        //PcapngOptionTooLarge
+        assert_eq!(
+            &format!("pcapng option too large. The option value ({} bytes) is too big to be represented in the option length field.", arg_usize),
+            &format!("{}", PcapngOptionTooLarge(arg_usize))
+        );
+
+        //HeaderFieldUnknown
+        assert_eq!(
+            "Unknown header field 'ttl'. No field with this name exists in the field table of the header.",
+            &format!("{}", HeaderFieldUnknown("ttl".to_string()))
+        );
+
+        //HeaderFieldValueTooLarge
+        assert_eq!(
+            &format!("The value {} is too large for the header field 'ttl' ({} bits).", value_u32, arg_usize),
+            &format!("{}", HeaderFieldValueTooLarge{ name: "ttl", value: value_u32.into(), bit_width: arg_usize })
+        );
+
+        //HeaderFieldOutOfSlice
+        assert_eq!(
+            &format!("The header field 'ttl' requires a slice with at least {} bytes but the given slice only has {} bytes.", arg_usize, value_u16),
+            &format!("{}", HeaderFieldOutOfSlice{ name: "ttl", required_len: arg_usize, actual_len: value_u16.into() })
+        );
     }
 }
 
@@ -413,4 +700,21 @@ fn error_field_display() {
     assert_eq!("Ipv6FragmentHeader.fragment_offset", &format!("{}", Ipv6FragmentOffset));
     assert_eq!("SingleVlanHeader.priority_code_point", &format!("{}", VlanTagPriorityCodePoint));
     assert_eq!("SingleVlanHeader.vlan_identifier", &format!("{}", VlanTagVlanId));
+    assert_eq!("DccpHeader.ccval", &format!("{}", DccpCcVal));
+    assert_eq!("DccpHeader.checksum_coverage", &format!("{}", DccpChecksumCoverage));
+    assert_eq!("DccpHeader.sequence_number", &format!("{}", DccpSequenceNumber));
+    assert_eq!("DccpHeader.packet_type.acknowledgement_number", &format!("{}", DccpAcknowledgementNumber));
+    assert_eq!("IpHeader.version", &format!("{}", IpVersion));
+    assert_eq!("SingleVlanHeader.ether_type", &format!("{}", VlanTagEtherType));
+    assert_eq!("Ipv4Header.ihl", &format!("{}", Ipv4Ihl));
+    assert_eq!("Ipv4Header.total_len", &format!("{}", Ipv4TotalLength));
+    assert_eq!("IpAuthenticationHeader.payload_len", &format!("{}", IpAuthPayloadLength));
+    assert_eq!("TcpHeader.data_offset", &format!("{}", TcpDataOffset));
+    assert_eq!("IcmpExtensionsSlice.version", &format!("{}", IcmpExtensionVersion));
+    assert_eq!("IcmpExtensionObjectSlice.length", &format!("{}", IcmpExtensionObjectLength));
+    assert_eq!("SctpChunk.length", &format!("{}", SctpChunkLength));
+    assert_eq!("SctpParameter.length", &format!("{}", SctpParameterLength));
+    assert_eq!("DccpHeader.data_offset", &format!("{}", DccpDataOffset));
+    assert_eq!("DccpOption.length", &format!("{}", DccpOptionLength));
+    assert_eq!("GreHeader.version", &format!("{}", GreVersion));
 }
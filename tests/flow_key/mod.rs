@@ -0,0 +1,230 @@
+use super::*;
+use std::net::{IpAddr, Ipv6Addr};
+
+/// Builds a packet & returns the flow keys determined via slicing & decoding.
+macro_rules! keys {
+    ($builder:expr, $payload:expr) => {{
+        let mut packet = Vec::new();
+        $builder.write(&mut packet, $payload).unwrap();
+        (
+            FlowKey::from_sliced(&SlicedPacket::from_ethernet(&packet).unwrap()),
+            FlowKey::from_headers(&PacketHeaders::from_ethernet_slice(&packet).unwrap()),
+        )
+    }};
+}
+
+const V4: FlowAddresses = FlowAddresses::Ipv4{
+    source: [192,168,1,1],
+    destination: [192,168,1,2],
+};
+
+const V6: FlowAddresses = FlowAddresses::Ipv6{
+    source: [1;16],
+    destination: [2;16],
+};
+
+fn ports(source: u16, destination: u16) -> FlowTransportId {
+    FlowTransportId::Ports{ source, destination }
+}
+
+#[test]
+fn from_packet() {
+    let eth = || PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12]);
+    let v4 = || eth().ipv4([192,168,1,1], [192,168,1,2], 20);
+    let v6 = || eth().ipv6([1;16], [2;16], 20);
+
+    // udp & tcp
+    {
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::UDP, transport: ports(21, 1234) });
+        assert_eq!((expected, expected), keys!(v4().udp(21, 1234), &[1,2,3]));
+    }
+    {
+        let expected = Some(FlowKey{ addresses: V6, protocol: ip_number::TCP, transport: ports(1, 2) });
+        assert_eq!((expected, expected), keys!(v6().tcp(1, 2, 3, 4), &[1,2,3]));
+    }
+    // udp-lite, sctp & dccp
+    {
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::UDP_LITE, transport: ports(3, 4) });
+        assert_eq!((expected, expected), keys!(v4().udp_lite(3, 4, 0), &[]));
+    }
+    {
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::SCTP, transport: ports(5, 6) });
+        assert_eq!((expected, expected), keys!(v4().sctp(5, 6, 7), &[]));
+    }
+    {
+        let expected = Some(FlowKey{ addresses: V6, protocol: ip_number::DCCP, transport: ports(7, 8) });
+        assert_eq!(
+            (expected, expected),
+            keys!(v6().dccp(7, 8, DccpSequenceNumber::Extended(1), DccpPacketType::Request{ service_code: 0 }), &[])
+        );
+    }
+    // icmp echo
+    {
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::ICMP, transport: FlowTransportId::IcmpId(9) });
+        assert_eq!((expected, expected), keys!(v4().icmpv4_echo_request(9, 10), &[]));
+    }
+    {
+        let expected = Some(FlowKey{ addresses: V6, protocol: ip_number::IPV6_ICMP, transport: FlowTransportId::IcmpId(11) });
+        assert_eq!((expected, expected), keys!(v6().icmpv6_echo_reply(11, 12), &[]));
+    }
+    // icmp without id
+    {
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::ICMP, transport: FlowTransportId::None });
+        assert_eq!(
+            (expected, expected),
+            keys!(v4().icmpv4(Icmpv4Type::TimeExceeded(icmpv4::TimeExceededCode::TtlExceededInTransit)), &[])
+        );
+    }
+}
+
+#[test]
+fn from_packet_without_transport() {
+    // unknown protocol
+    {
+        let mut packet = Vec::new();
+        Ipv4Header::new(0, 20, 250, [192,168,1,1], [192,168,1,2]).write(&mut packet).unwrap();
+        let expected = Some(FlowKey{ addresses: V4, protocol: 250, transport: FlowTransportId::None });
+        assert_eq!(expected, FlowKey::from_sliced(&SlicedPacket::from_ip(&packet).unwrap()));
+        assert_eq!(expected, FlowKey::from_headers(&PacketHeaders::from_ip_slice(&packet).unwrap()));
+    }
+
+    // fragmented ipv4 packet
+    {
+        let mut header = Ipv4Header::new(8, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+        header.more_fragments = true;
+        let mut packet = Vec::new();
+        header.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[0;8]);
+        let expected = Some(FlowKey{ addresses: V4, protocol: ip_number::UDP, transport: FlowTransportId::None });
+        assert_eq!(expected, FlowKey::from_sliced(&SlicedPacket::from_ip(&packet).unwrap()));
+    }
+
+    // no ip header
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [0;6],
+            destination: [0;6],
+            ether_type: 0x1234,
+        }.write(&mut packet).unwrap();
+        assert_eq!(None, FlowKey::from_sliced(&SlicedPacket::from_ethernet(&packet).unwrap()));
+        assert_eq!(None, FlowKey::from_headers(&PacketHeaders::from_ethernet_slice(&packet).unwrap()));
+    }
+}
+
+#[test]
+fn addresses() {
+    assert_eq!(IpAddr::from([192,168,1,1]), V4.source_addr());
+    assert_eq!(IpAddr::from([192,168,1,2]), V4.destination_addr());
+    assert_eq!(IpAddr::from([1u8;16]), V6.source_addr());
+    assert_eq!(IpAddr::from([2u8;16]), V6.destination_addr());
+}
+
+proptest! {
+    #[test]
+    fn canonical(
+        v4_source in any::<[u8;4]>(),
+        v4_destination in any::<[u8;4]>(),
+        v6_source in any::<[u8;16]>(),
+        v6_destination in any::<[u8;16]>(),
+        protocol in any::<u8>(),
+        source_port in any::<u16>(),
+        destination_port in any::<u16>(),
+        icmp_id in any::<u16>(),
+    ) {
+        for addresses in [
+            FlowAddresses::Ipv4{ source: v4_source, destination: v4_destination },
+            FlowAddresses::Ipv6{ source: v6_source, destination: v6_destination },
+        ] {
+            for transport in [
+                FlowTransportId::None,
+                ports(source_port, destination_port),
+                FlowTransportId::IcmpId(icmp_id),
+            ] {
+                let key = FlowKey{ addresses, protocol, transport };
+                let reversed = key.reversed();
+
+                // reversed twice is the original
+                assert_eq!(key, reversed.reversed());
+                assert_eq!(key.protocol, reversed.protocol);
+                assert_eq!(key.addresses.source_addr(), reversed.addresses.destination_addr());
+
+                // both directions have the same canonical form
+                assert_eq!(key.canonical(), reversed.canonical());
+                assert!(key.canonical().is_canonical());
+                assert!(key.is_canonical() || reversed.is_canonical());
+                if key.is_canonical() {
+                    assert_eq!(key, key.canonical());
+                }
+
+                // symmetric rss key
+                assert_eq!(
+                    key.toeplitz_hash(&rss::SYMMETRIC_KEY),
+                    reversed.toeplitz_hash(&rss::SYMMETRIC_KEY)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn ord_and_hash() {
+    use std::collections::{BTreeSet, HashSet};
+    let a = FlowKey{ addresses: V4, protocol: ip_number::UDP, transport: ports(1, 2) };
+    let b = FlowKey{ addresses: V4, protocol: ip_number::UDP, transport: ports(1, 3) };
+    let c = FlowKey{ addresses: V6, protocol: ip_number::UDP, transport: ports(1, 2) };
+    assert!(a < b);
+    assert!(b < c);
+
+    let hashed: HashSet<FlowKey> = [a, b, c, a].iter().copied().collect();
+    assert_eq!(3, hashed.len());
+    let ordered: BTreeSet<FlowKey> = [c, b, a].iter().copied().collect();
+    assert_eq!(vec![a, b, c], ordered.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn toeplitz_hash() {
+    // verification values from the Microsoft RSS specification
+    let v4 = |source: [u8;4], source_port, destination: [u8;4], destination_port, ip_only, with_ports| {
+        let key = FlowKey{
+            addresses: FlowAddresses::Ipv4{ source, destination },
+            protocol: ip_number::TCP,
+            transport: ports(source_port, destination_port),
+        };
+        assert_eq!(with_ports, key.toeplitz_hash(&rss::MICROSOFT_KEY));
+        let key = FlowKey{
+            transport: FlowTransportId::None,
+            ..key
+        };
+        assert_eq!(ip_only, key.toeplitz_hash(&rss::MICROSOFT_KEY));
+    };
+    v4([66,9,149,187], 2794, [161,142,100,80], 1766, 0x323e8fc2, 0x51ccc178);
+    v4([199,92,111,2], 14230, [65,69,140,83], 4739, 0xd718262a, 0xc626b0ea);
+    v4([24,19,198,95], 12898, [12,22,207,184], 38024, 0xd2d0a5de, 0x5c2b394a);
+
+    let v6 = |source: &str, source_port, destination: &str, destination_port, ip_only, with_ports| {
+        let key = FlowKey{
+            addresses: FlowAddresses::Ipv6{
+                source: source.parse::<Ipv6Addr>().unwrap().octets(),
+                destination: destination.parse::<Ipv6Addr>().unwrap().octets(),
+            },
+            protocol: ip_number::TCP,
+            transport: ports(source_port, destination_port),
+        };
+        assert_eq!(with_ports, key.toeplitz_hash(&rss::MICROSOFT_KEY));
+        let key = FlowKey{
+            transport: FlowTransportId::None,
+            ..key
+        };
+        assert_eq!(ip_only, key.toeplitz_hash(&rss::MICROSOFT_KEY));
+    };
+    v6("3ffe:2501:200:1fff::7", 2794, "3ffe:2501:200:3::1", 1766, 0x2cc18cd5, 0x40207d3d);
+    v6("3ffe:501:8::260:97ff:fe40:efab", 14230, "ff02::1", 4739, 0x0f0c461c, 0xdde51bbf);
+
+    // missing key bits are treated as zero
+    assert_eq!(0, rss::toeplitz_hash(&[], &[0xff;12]));
+    assert_eq!(
+        rss::toeplitz_hash(&[1,2,3,4,5,0,0,0], &[0xff;4]),
+        rss::toeplitz_hash(&[1,2,3,4,5], &[0xff;4])
+    );
+}
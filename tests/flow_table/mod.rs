@@ -0,0 +1,433 @@
+use super::*;
+use etherparse::flow_table::*;
+use std::time::Duration;
+
+const KEY: FlowKey = FlowKey{
+    addresses: FlowAddresses::Ipv4{
+        source: [192,168,1,1],
+        destination: [192,168,1,2],
+    },
+    protocol: ip_number::UDP,
+    transport: FlowTransportId::Ports{ source: 1, destination: 2 },
+};
+
+const TCP_KEY: FlowKey = FlowKey{
+    protocol: ip_number::TCP,
+    ..KEY
+};
+
+const V6_KEY: FlowKey = FlowKey{
+    addresses: FlowAddresses::Ipv6{
+        source: [1;16],
+        destination: [2;16],
+    },
+    ..KEY
+};
+
+fn secs(value: u64) -> Duration {
+    Duration::from_secs(value)
+}
+
+fn record(key: FlowKey, first_seen: u64, last_seen: u64, packets: u64, bytes: u64) -> FlowRecord {
+    FlowRecord{
+        key,
+        first_seen: secs(first_seen),
+        last_seen: secs(last_seen),
+        packets,
+        bytes,
+        tcp_flags: 0,
+        end_reason: None,
+    }
+}
+
+fn config(max_flows: usize) -> FlowTableConfig {
+    FlowTableConfig{
+        idle_timeout: secs(10),
+        active_timeout: secs(100),
+        max_flows,
+    }
+}
+
+#[test]
+fn aggregation() {
+    let mut table = FlowTable::new(config(10));
+    assert!(table.is_empty());
+    let mut expired = Vec::new();
+    table.add_packet(secs(1), KEY, 100, 0, &mut |r| expired.push(r));
+    table.add_packet(secs(2), KEY, 50, 0, &mut |r| expired.push(r));
+    table.add_packet(secs(3), KEY.reversed(), 20, 0, &mut |r| expired.push(r));
+    // syn & ack
+    table.add_packet(secs(3), TCP_KEY, 40, 0b0000_0010, &mut |r| expired.push(r));
+    table.add_packet(secs(4), TCP_KEY, 40, 0b0001_0000, &mut |r| expired.push(r));
+
+    assert!(expired.is_empty());
+    assert_eq!(3, table.len());
+    assert_eq!(Some(&record(KEY, 1, 2, 2, 150)), table.get(&KEY));
+    assert_eq!(Some(&record(KEY.reversed(), 3, 3, 1, 20)), table.get(&KEY.reversed()));
+    assert_eq!(
+        Some(&FlowRecord{ tcp_flags: 0b0001_0010, ..record(TCP_KEY, 3, 4, 2, 80) }),
+        table.get(&TCP_KEY)
+    );
+    assert_eq!(3, table.iter().count());
+    assert_eq!(&config(10), table.config());
+}
+
+#[test]
+fn end_of_flow() {
+    // fin & rst end the flow
+    for flags in [0b0000_0001, 0b0000_0100, 0b0001_0001] {
+        let mut table = FlowTable::new(config(10));
+        let mut expired = Vec::new();
+        table.add_packet(secs(1), TCP_KEY, 40, 0b0001_0000, &mut |r| expired.push(r));
+        table.add_packet(secs(2), TCP_KEY, 40, flags, &mut |r| expired.push(r));
+        assert!(table.is_empty());
+        assert_eq!(
+            vec![FlowRecord{
+                tcp_flags: 0b0001_0000 | flags,
+                end_reason: Some(FlowEndReason::EndOfFlowDetected),
+                ..record(TCP_KEY, 1, 2, 2, 80)
+            }],
+            expired
+        );
+    }
+    // flags are ignored for non tcp flows
+    {
+        let mut table = FlowTable::new(config(10));
+        let mut expired = Vec::new();
+        table.add_packet(secs(1), KEY, 40, 0b0000_0001, &mut |r| expired.push(r));
+        assert!(expired.is_empty());
+        assert_eq!(1, table.len());
+    }
+}
+
+#[test]
+fn timeouts() {
+    // idle timeout on a new packet
+    {
+        let mut table = FlowTable::new(config(10));
+        let mut expired = Vec::new();
+        table.add_packet(secs(1), KEY, 10, 0, &mut |r| expired.push(r));
+        table.add_packet(secs(11), KEY, 20, 0, &mut |r| expired.push(r));
+        assert!(expired.is_empty());
+        table.add_packet(secs(22), KEY, 30, 0, &mut |r| expired.push(r));
+        assert_eq!(
+            vec![FlowRecord{
+                end_reason: Some(FlowEndReason::IdleTimeout),
+                ..record(KEY, 1, 11, 2, 30)
+            }],
+            expired
+        );
+        assert_eq!(Some(&record(KEY, 22, 22, 1, 30)), table.get(&KEY));
+    }
+    // active timeout on a new packet
+    {
+        let mut table = FlowTable::new(config(10));
+        let mut expired = Vec::new();
+        for t in 0..=100 {
+            table.add_packet(secs(t), KEY, 1, 0, &mut |r| expired.push(r));
+        }
+        assert!(expired.is_empty());
+        table.add_packet(secs(101), KEY, 1, 0, &mut |r| expired.push(r));
+        assert_eq!(
+            vec![FlowRecord{
+                end_reason: Some(FlowEndReason::ActiveTimeout),
+                ..record(KEY, 0, 100, 101, 101)
+            }],
+            expired
+        );
+        assert_eq!(Some(&record(KEY, 101, 101, 1, 1)), table.get(&KEY));
+    }
+    // expire
+    {
+        let mut table = FlowTable::new(config(10));
+        let mut expired = Vec::new();
+        table.add_packet(secs(0), KEY, 1, 0, &mut |r| expired.push(r));
+        for t in 0..=95 {
+            table.add_packet(secs(t), V6_KEY, 1, 0, &mut |r| expired.push(r));
+        }
+        table.add_packet(secs(95), TCP_KEY, 1, 0, &mut |r| expired.push(r));
+
+        table.expire(secs(101), &mut |r| expired.push(r));
+        expired.sort_by_key(|r| r.key);
+        assert_eq!(
+            vec![
+                FlowRecord{
+                    end_reason: Some(FlowEndReason::IdleTimeout),
+                    ..record(KEY, 0, 0, 1, 1)
+                },
+                FlowRecord{
+                    end_reason: Some(FlowEndReason::ActiveTimeout),
+                    ..record(V6_KEY, 0, 95, 96, 96)
+                },
+            ],
+            expired
+        );
+        assert_eq!(1, table.len());
+        assert!(table.get(&TCP_KEY).is_some());
+
+        // time going backwards does not expire anything
+        expired.clear();
+        table.expire(secs(0), &mut |r| expired.push(r));
+        assert!(expired.is_empty());
+    }
+}
+
+#[test]
+fn max_flows() {
+    // least recently seen flow gets removed
+    {
+        let mut table = FlowTable::new(config(2));
+        let mut expired = Vec::new();
+        table.add_packet(secs(1), KEY, 1, 0, &mut |r| expired.push(r));
+        table.add_packet(secs(2), V6_KEY, 1, 0, &mut |r| expired.push(r));
+        table.add_packet(secs(3), KEY, 1, 0, &mut |r| expired.push(r));
+        // existing flows do not trigger an eviction
+        assert!(expired.is_empty());
+        table.add_packet(secs(4), TCP_KEY, 1, 0, &mut |r| expired.push(r));
+        assert_eq!(
+            vec![FlowRecord{
+                end_reason: Some(FlowEndReason::LackOfResources),
+                ..record(V6_KEY, 2, 2, 1, 1)
+            }],
+            expired
+        );
+        assert_eq!(2, table.len());
+        assert!(table.get(&KEY).is_some());
+        assert!(table.get(&TCP_KEY).is_some());
+    }
+    // no flows can be stored
+    {
+        let mut table = FlowTable::new(config(0));
+        let mut expired = Vec::new();
+        table.add_packet(secs(1), KEY, 1, 0, &mut |r| expired.push(r));
+        assert!(expired.is_empty());
+        assert!(table.is_empty());
+    }
+}
+
+#[test]
+fn flush() {
+    let mut table = FlowTable::default();
+    assert_eq!(&FlowTableConfig::default(), table.config());
+    let mut expired = Vec::new();
+    table.add_packet(secs(1), KEY, 1, 0, &mut |r| expired.push(r));
+    table.add_packet(secs(2), V6_KEY, 2, 0, &mut |r| expired.push(r));
+    table.flush(&mut |r| expired.push(r));
+    assert!(table.is_empty());
+    expired.sort_by_key(|r| r.key);
+    assert_eq!(
+        vec![
+            FlowRecord{ end_reason: Some(FlowEndReason::ForcedEnd), ..record(KEY, 1, 1, 1, 1) },
+            FlowRecord{ end_reason: Some(FlowEndReason::ForcedEnd), ..record(V6_KEY, 2, 2, 1, 2) },
+        ],
+        expired
+    );
+}
+
+#[test]
+fn process_sliced() {
+    let mut table = FlowTable::default();
+    let mut expired = Vec::new();
+
+    // ipv4 udp
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1, 2)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(table.process_sliced(secs(1), &sliced, &mut |r| expired.push(r)));
+        assert_eq!(Some(&record(KEY, 1, 1, 1, 20 + 8 + 4)), table.get(&KEY));
+    }
+    // ipv6 tcp with syn & fin
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([1;16], [2;16], 20)
+            .tcp(1, 2, 3, 4)
+            .syn()
+            .fin()
+            .write(&mut packet, &[1,2])
+            .unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(table.process_sliced(secs(2), &sliced, &mut |r| expired.push(r)));
+        let key = FlowKey{ protocol: ip_number::TCP, ..V6_KEY };
+        assert_eq!(
+            vec![FlowRecord{
+                tcp_flags: 0b0000_0011,
+                end_reason: Some(FlowEndReason::EndOfFlowDetected),
+                ..record(key, 2, 2, 1, 40 + 20 + 2)
+            }],
+            expired
+        );
+    }
+    // packet without ip header
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [0;6],
+            destination: [0;6],
+            ether_type: 0x1234,
+        }.write(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(!table.process_sliced(secs(3), &sliced, &mut |r| expired.push(r)));
+        assert_eq!(1, table.len());
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+#[test]
+fn ipfix() {
+    let mut exporter = FlowExporter::new(FlowExportFormat::Ipfix, 1234);
+    assert_eq!(FlowExportFormat::Ipfix, exporter.format());
+    assert_eq!(0, exporter.sequence_number());
+
+    let v4 = FlowRecord{
+        tcp_flags: 0x12,
+        end_reason: Some(FlowEndReason::IdleTimeout),
+        ..record(KEY, 1, 2, 3, 4)
+    };
+    let messages = exporter.encode(secs(1000), std::slice::from_ref(&v4));
+    assert_eq!(1, messages.len());
+    let m = &messages[0];
+
+    // header
+    assert_eq!(10, u16_at(m, 0));
+    assert_eq!(m.len(), usize::from(u16_at(m, 2)));
+    assert_eq!(1000, u32_at(m, 4));
+    assert_eq!(0, u32_at(m, 8));
+    assert_eq!(1234, u32_at(m, 12));
+
+    // template set
+    assert_eq!(2, u16_at(m, 16));
+    assert_eq!(4 + 4 + 11*4, u16_at(m, 18));
+    assert_eq!(flow_export::TEMPLATE_ID_IPV4, u16_at(m, 20));
+    assert_eq!(11, u16_at(m, 22));
+    assert_eq!(
+        vec![
+            (flow_export::IE_SOURCE_IPV4_ADDRESS, 4),
+            (flow_export::IE_DESTINATION_IPV4_ADDRESS, 4),
+            (flow_export::IE_PROTOCOL_IDENTIFIER, 1),
+            (flow_export::IE_SOURCE_TRANSPORT_PORT, 2),
+            (flow_export::IE_DESTINATION_TRANSPORT_PORT, 2),
+            (flow_export::IE_PACKET_DELTA_COUNT, 8),
+            (flow_export::IE_OCTET_DELTA_COUNT, 8),
+            (flow_export::IE_TCP_CONTROL_BITS, 1),
+            (flow_export::IE_FLOW_START_MILLISECONDS, 8),
+            (flow_export::IE_FLOW_END_MILLISECONDS, 8),
+            (flow_export::IE_FLOW_END_REASON, 1),
+        ],
+        (0..11).map(|i| (u16_at(m, 24 + i*4), u16_at(m, 26 + i*4))).collect::<Vec<_>>()
+    );
+
+    // data set
+    let data = &m[68..];
+    assert_eq!(flow_export::TEMPLATE_ID_IPV4, u16_at(data, 0));
+    assert_eq!(4 + 47, u16_at(data, 2));
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&[192,168,1,1, 192,168,1,2, ip_number::UDP, 0,1, 0,2]);
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&4u64.to_be_bytes());
+    expected.push(0x12);
+    expected.extend_from_slice(&1000u64.to_be_bytes());
+    expected.extend_from_slice(&2000u64.to_be_bytes());
+    expected.push(FlowEndReason::IdleTimeout as u8);
+    assert_eq!(&expected[..], &data[4..]);
+    assert_eq!(1, exporter.sequence_number());
+
+    // ipv4 & ipv6 mixed & split into multiple messages
+    exporter.max_records_per_message = 2;
+    let v6 = record(V6_KEY, 1, 2, 3, 4);
+    let messages = exporter.encode(secs(1000), &[v4.clone(), v6.clone(), v4]);
+    assert_eq!(2, messages.len());
+    {
+        let m = &messages[0];
+        assert_eq!(m.len(), usize::from(u16_at(m, 2)));
+        assert_eq!(1, u32_at(m, 8));
+        assert_eq!(16 + (4 + 2*(4 + 11*4)) + (4 + 47) + (4 + 71), m.len());
+        assert_eq!(flow_export::TEMPLATE_ID_IPV6, u16_at(m, 20 + 48));
+    }
+    {
+        let m = &messages[1];
+        assert_eq!(3, u32_at(m, 8));
+        assert_eq!(16 + (4 + 48) + (4 + 47), m.len());
+    }
+    assert_eq!(4, exporter.sequence_number());
+
+    // no records
+    assert!(exporter.encode(secs(1000), &[]).is_empty());
+}
+
+#[test]
+fn netflow_v9() {
+    let mut exporter = FlowExporter::new(FlowExportFormat::NetflowV9, 5);
+    exporter.system_init_time = secs(900);
+    assert_eq!(FlowExportFormat::NetflowV9, exporter.format());
+
+    let v4 = FlowRecord{
+        tcp_flags: 0x12,
+        end_reason: Some(FlowEndReason::IdleTimeout),
+        ..record(KEY, 901, 902, 3, 4)
+    };
+    let messages = exporter.encode(secs(1000), &[v4.clone(), v4.clone()]);
+    assert_eq!(1, messages.len());
+    let m = &messages[0];
+
+    // header
+    assert_eq!(9, u16_at(m, 0));
+    // one template & two records
+    assert_eq!(3, u16_at(m, 2));
+    assert_eq!(100_000, u32_at(m, 4));
+    assert_eq!(1000, u32_at(m, 8));
+    assert_eq!(0, u32_at(m, 12));
+    assert_eq!(5, u32_at(m, 16));
+
+    // template flowset
+    assert_eq!(0, u16_at(m, 20));
+    assert_eq!(4 + 4 + 10*4, u16_at(m, 22));
+    assert_eq!(flow_export::TEMPLATE_ID_IPV4, u16_at(m, 24));
+    assert_eq!(10, u16_at(m, 26));
+    assert_eq!(
+        (flow_export::IE_FLOW_START_SYS_UP_TIME, 4),
+        (u16_at(m, 28 + 8*4), u16_at(m, 30 + 8*4))
+    );
+    assert_eq!(
+        (flow_export::IE_FLOW_END_SYS_UP_TIME, 4),
+        (u16_at(m, 28 + 9*4), u16_at(m, 30 + 9*4))
+    );
+
+    // data flowset (padded to 4 bytes)
+    let data = &m[68..];
+    assert_eq!(flow_export::TEMPLATE_ID_IPV4, u16_at(data, 0));
+    assert_eq!(4 + 2*38, u16_at(data, 2));
+    assert_eq!(data.len(), usize::from(u16_at(data, 2)));
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&[192,168,1,1, 192,168,1,2, ip_number::UDP, 0,1, 0,2]);
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&4u64.to_be_bytes());
+    expected.push(0x12);
+    expected.extend_from_slice(&1000u32.to_be_bytes());
+    expected.extend_from_slice(&2000u32.to_be_bytes());
+    assert_eq!(&expected[..], &data[4..4 + 38]);
+    assert_eq!(&expected[..], &data[4 + 38..]);
+
+    // padding & sequence number per message
+    let messages = exporter.encode(secs(1000), &[v4, record(V6_KEY, 1, 2, 3, 4)]);
+    assert_eq!(1, messages.len());
+    let m = &messages[0];
+    assert_eq!(1, u32_at(m, 12));
+    assert_eq!(4, u16_at(m, 2));
+    // template flowset, ipv4 data (38 + 2 padding) & ipv6 data (62 + 2 padding)
+    assert_eq!(20 + (4 + 2*44) + (4 + 40) + (4 + 64), m.len());
+    assert_eq!(0, m.len() % 4);
+    assert_eq!(2, exporter.sequence_number());
+}
@@ -0,0 +1,510 @@
+use super::*;
+
+use etherparse::icmp_extension::MplsLabelStackEntry;
+use std::error::Error;
+
+/// Ethernet II, IPv4 & UDP frame with the given payload.
+fn udp_frame(payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut result, payload)
+        .unwrap();
+    result
+}
+
+fn vlan(vlan_identifier: u16) -> SingleVlanHeader {
+    SingleVlanHeader {
+        priority_code_point: 0,
+        drop_eligible_indicator: false,
+        vlan_identifier,
+        ether_type: 0,
+    }
+}
+
+fn mpls(label: u32) -> MplsLabelStackEntry {
+    MplsLabelStackEntry {
+        label,
+        traffic_class: 0,
+        bottom_of_stack: false,
+        ttl: 64,
+    }
+}
+
+mod frame_edit_error {
+    use super::*;
+
+    #[test]
+    fn display_source() {
+        use FrameEditError::*;
+
+        let err = Read(ReadError::UnexpectedEndOfSlice(14));
+        assert_eq!(
+            format!("FrameEditError: Failed to read the frame headers: {}", ReadError::UnexpectedEndOfSlice(14)),
+            format!("{}", err)
+        );
+        assert!(err.source().is_some());
+
+        let err = Write(WriteError::SliceTooSmall(4));
+        assert_eq!(
+            format!("FrameEditError: Failed to write the pushed header: {}", WriteError::SliceTooSmall(4)),
+            format!("{}", err)
+        );
+        assert!(err.source().is_some());
+
+        let err = UnexpectedEtherType(0x0800);
+        assert_eq!(
+            "FrameEditError: Unexpected ether type 0x0800 at the position of the operation.",
+            format!("{}", err)
+        );
+        assert!(err.source().is_none());
+
+        let err = UnsupportedIpNumber(17);
+        assert_eq!(
+            "FrameEditError: Unsupported ip number 17 after the tunnel ip header (only IPv4, IPv6 & GRE are supported).",
+            format!("{}", err)
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn from() {
+        assert_matches!(
+            FrameEditError::from(ReadError::UnexpectedEndOfSlice(1)),
+            FrameEditError::Read(ReadError::UnexpectedEndOfSlice(1))
+        );
+        assert_matches!(
+            FrameEditError::from(WriteError::SliceTooSmall(1)),
+            FrameEditError::Write(WriteError::SliceTooSmall(1))
+        );
+        assert_matches!(
+            FrameEditError::from(ValueError::Ipv4OptionsLengthBad(1)),
+            FrameEditError::Write(WriteError::ValueError(ValueError::Ipv4OptionsLengthBad(1)))
+        );
+    }
+}
+
+mod headroom_frame {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let mut buffer = [0u8;10];
+        let frame = HeadroomFrame::new(&mut buffer, 4..8);
+        assert_eq!(4..8, frame.range());
+        assert_eq!(4, frame.headroom());
+        assert_eq!(4, frame.frame().len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_out_of_range() {
+        let mut buffer = [0u8;10];
+        HeadroomFrame::new(&mut buffer, 4..11);
+    }
+
+    #[test]
+    fn insert_remove_bytes() {
+        let mut buffer = [0,0,0,1,2,3,4,5];
+        {
+            let mut frame = HeadroomFrame::new(&mut buffer, 3..8);
+            frame.insert_bytes(2, &[9,9]).unwrap();
+            assert_eq!(1..8, frame.range());
+            assert_eq!(&[1,2,9,9,3,4,5], frame.frame());
+
+            // not enough headroom
+            assert_matches!(
+                frame.insert_bytes(0, &[8,8]),
+                Err(WriteError::SliceTooSmall(2))
+            );
+            assert_eq!(&[1,2,9,9,3,4,5], frame.frame());
+
+            frame.remove_bytes(2..4);
+            assert_eq!(3..8, frame.range());
+            assert_eq!(&[1,2,3,4,5], frame.frame());
+        }
+        // the data behind the modified part was not moved
+        assert_eq!(&[3,4,5], &buffer[5..]);
+    }
+}
+
+mod vlan {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn push_pop(
+            payload in proptest::collection::vec(any::<u8>(), 0..32),
+            outer_id in 0u16..0x1000,
+            inner_id in 0u16..0x1000
+        ) {
+            let original = udp_frame(&payload);
+            let expected = {
+                let mut result = Vec::new();
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .double_vlan(outer_id, inner_id)
+                    .ipv4([192,168,1,1], [192,168,1,2], 20)
+                    .udp(21, 1234)
+                    .write(&mut result, &payload)
+                    .unwrap();
+                result
+            };
+
+            // vec
+            {
+                let mut frame = original.clone();
+                frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(inner_id)).unwrap();
+                frame.push_vlan(ether_type::PROVIDER_BRIDGING, &vlan(outer_id)).unwrap();
+                assert_eq!(expected, frame);
+
+                let (tpid, outer) = frame.pop_vlan().unwrap();
+                assert_eq!(ether_type::PROVIDER_BRIDGING, tpid);
+                assert_eq!(SingleVlanHeader{ ether_type: ether_type::VLAN_TAGGED_FRAME, ..vlan(outer_id) }, outer);
+
+                let (tpid, inner) = frame.pop_vlan().unwrap();
+                assert_eq!(ether_type::VLAN_TAGGED_FRAME, tpid);
+                assert_eq!(SingleVlanHeader{ ether_type: ether_type::IPV4, ..vlan(inner_id) }, inner);
+
+                assert_eq!(original, frame);
+            }
+
+            // headroom
+            {
+                let buffer_len = 8 + original.len();
+                let mut buffer = vec![0u8;buffer_len];
+                buffer[8..].copy_from_slice(&original);
+                let mut frame = HeadroomFrame::new(&mut buffer, 8..buffer_len);
+
+                frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(inner_id)).unwrap();
+                frame.push_vlan(ether_type::PROVIDER_BRIDGING, &vlan(outer_id)).unwrap();
+                assert_eq!(0..buffer_len, frame.range());
+                assert_eq!(&expected[..], frame.frame());
+
+                // no headroom left
+                assert_matches!(
+                    frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)),
+                    Err(FrameEditError::Write(WriteError::SliceTooSmall(4)))
+                );
+                assert_eq!(&expected[..], frame.frame());
+
+                frame.pop_vlan().unwrap();
+                frame.pop_vlan().unwrap();
+                assert_eq!(8..buffer_len, frame.range());
+                assert_eq!(&original[..], frame.frame());
+            }
+        }
+    }
+
+    #[test]
+    fn push_errors() {
+        let original = udp_frame(&[]);
+        let mut frame = original.clone();
+
+        // unknown tag protocol identifier
+        assert_matches!(
+            frame.push_vlan(ether_type::IPV4, &vlan(1)),
+            Err(FrameEditError::UnexpectedEtherType(ether_type::IPV4))
+        );
+        // vlan identifier out of range
+        assert_matches!(
+            frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(0x1000)),
+            Err(FrameEditError::Write(WriteError::ValueError(_)))
+        );
+        assert_eq!(original, frame);
+
+        // frame too short
+        let mut frame = original[..13].to_vec();
+        assert_matches!(
+            frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)),
+            Err(FrameEditError::Read(ReadError::UnexpectedEndOfSlice(14)))
+        );
+    }
+
+    #[test]
+    fn pop_errors() {
+        // untagged
+        let original = udp_frame(&[]);
+        let mut frame = original.clone();
+        assert_matches!(
+            frame.pop_vlan(),
+            Err(FrameEditError::UnexpectedEtherType(ether_type::IPV4))
+        );
+        assert_eq!(original, frame);
+
+        // tag cut off
+        frame.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)).unwrap();
+        let mut frame = frame[..17].to_vec();
+        assert_matches!(
+            frame.pop_vlan(),
+            Err(FrameEditError::Read(ReadError::UnexpectedEndOfSlice(18)))
+        );
+        assert_eq!(17, frame.len());
+    }
+}
+
+mod mpls {
+    use super::*;
+
+    #[test]
+    fn push_pop() {
+        let original = {
+            let mut result = udp_frame(&[1,2,3,4]);
+            result.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)).unwrap();
+            result
+        };
+
+        let mut frame = original.clone();
+        frame.push_mpls(mpls(0x12345)).unwrap();
+        frame.push_mpls(mpls(0x6789a)).unwrap();
+
+        // ether type of the vlan tag is patched & the labels are inserted after the tag
+        assert_eq!(
+            ether_type::VLAN_TAGGED_FRAME,
+            Ethernet2Header::from_slice(&frame).unwrap().0.ether_type
+        );
+        assert_eq!(
+            ether_type::MPLS_UNICAST,
+            SingleVlanHeader::from_slice(&frame[14..]).unwrap().0.ether_type
+        );
+        assert_eq!(
+            MplsLabelStackEntry{ bottom_of_stack: false, ..mpls(0x6789a) },
+            MplsLabelStackEntry::from_bytes([frame[18], frame[19], frame[20], frame[21]])
+        );
+        assert_eq!(
+            MplsLabelStackEntry{ bottom_of_stack: true, ..mpls(0x12345) },
+            MplsLabelStackEntry::from_bytes([frame[22], frame[23], frame[24], frame[25]])
+        );
+        assert_eq!(&original[18..], &frame[26..]);
+
+        // pop
+        assert_eq!(
+            MplsLabelStackEntry{ bottom_of_stack: false, ..mpls(0x6789a) },
+            frame.pop_mpls(ether_type::IPV4).unwrap()
+        );
+        assert_eq!(
+            ether_type::MPLS_UNICAST,
+            SingleVlanHeader::from_slice(&frame[14..]).unwrap().0.ether_type
+        );
+        assert_eq!(
+            MplsLabelStackEntry{ bottom_of_stack: true, ..mpls(0x12345) },
+            frame.pop_mpls(ether_type::IPV4).unwrap()
+        );
+        assert_eq!(original, frame);
+    }
+
+    #[test]
+    fn push_pop_headroom() {
+        let original = udp_frame(&[1,2,3,4]);
+        let mut buffer = vec![0u8;4 + original.len()];
+        buffer[4..].copy_from_slice(&original);
+        {
+            let mut frame = HeadroomFrame::new(&mut buffer, 4..4 + original.len());
+            frame.push_mpls(mpls(1)).unwrap();
+            assert_eq!(
+                ether_type::MPLS_UNICAST,
+                Ethernet2Header::from_slice(frame.frame()).unwrap().0.ether_type
+            );
+            assert_matches!(
+                frame.push_mpls(mpls(2)),
+                Err(FrameEditError::Write(WriteError::SliceTooSmall(4)))
+            );
+            frame.pop_mpls(ether_type::IPV4).unwrap();
+            assert_eq!(&original[..], frame.frame());
+        }
+    }
+
+    #[test]
+    fn pop_errors() {
+        // no label stack
+        let original = udp_frame(&[]);
+        let mut frame = original.clone();
+        assert_matches!(
+            frame.pop_mpls(ether_type::IPV4),
+            Err(FrameEditError::UnexpectedEtherType(ether_type::IPV4))
+        );
+        assert_eq!(original, frame);
+
+        // label cut off
+        frame.push_mpls(mpls(1)).unwrap();
+        let mut frame = frame[..17].to_vec();
+        assert_matches!(
+            frame.pop_mpls(ether_type::IPV4),
+            Err(FrameEditError::Read(ReadError::UnexpectedEndOfSlice(18)))
+        );
+
+        // vlan tag cut off
+        let mut frame = {
+            let mut result = udp_frame(&[]);
+            result.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)).unwrap();
+            result[..17].to_vec()
+        };
+        assert_matches!(
+            frame.pop_mpls(ether_type::IPV4),
+            Err(FrameEditError::Read(ReadError::UnexpectedEndOfSlice(18)))
+        );
+    }
+}
+
+mod tunnel {
+    use super::*;
+
+    fn outer_ipv4() -> IpHeader {
+        IpHeader::Version4(
+            Ipv4Header::new(0, 64, 0, [10,0,0,1], [10,0,0,2]),
+            Default::default()
+        )
+    }
+
+    fn outer_ipv6() -> IpHeader {
+        IpHeader::Version6(
+            Ipv6Header {
+                traffic_class: 0,
+                flow_label: 0,
+                payload_length: 0,
+                next_header: 0,
+                hop_limit: 64,
+                source: [1;16],
+                destination: [2;16],
+            },
+            Default::default()
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn ip_in_ip(
+            payload in proptest::collection::vec(any::<u8>(), 0..32)
+        ) {
+            let original = udp_frame(&payload);
+            let inner_len = original.len() - Ethernet2Header::SERIALIZED_SIZE;
+
+            let mut frame = original.clone();
+            frame.push_tunnel(outer_ipv6(), None).unwrap();
+
+            let (eth, rest) = Ethernet2Header::from_slice(&frame).unwrap();
+            assert_eq!(ether_type::IPV6, eth.ether_type);
+            let (ipv6, rest) = Ipv6Header::from_slice(rest).unwrap();
+            assert_eq!(ip_number::IPV4, ipv6.next_header);
+            assert_eq!(inner_len as u16, ipv6.payload_length);
+            assert_eq!(&original[Ethernet2Header::SERIALIZED_SIZE..], rest);
+
+            let (ip, tunnel) = frame.pop_tunnel().unwrap();
+            assert_eq!(None, tunnel);
+            match ip {
+                IpHeader::Version6(header, _) => assert_eq!(ipv6, header),
+                _ => unreachable!(),
+            }
+            assert_eq!(original, frame);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn gre(
+            payload in proptest::collection::vec(any::<u8>(), 0..32),
+            checksum in any::<bool>()
+        ) {
+            let original = {
+                let mut result = udp_frame(&payload);
+                result.push_vlan(ether_type::VLAN_TAGGED_FRAME, &vlan(1)).unwrap();
+                result
+            };
+            let inner_start = Ethernet2Header::SERIALIZED_SIZE + SingleVlanHeader::SERIALIZED_SIZE;
+            let gre = GreHeader {
+                protocol_type: 0,
+                checksum: if checksum { Some(0) } else { None },
+                key: Some(1234),
+                sequence_number: None,
+            };
+
+            let mut buffer = vec![0u8;64 + original.len()];
+            buffer[64..].copy_from_slice(&original);
+            let mut frame = HeadroomFrame::new(&mut buffer, 64..64 + original.len());
+            frame.push_tunnel(outer_ipv4(), Some(TunnelHeader::Gre(gre.clone()))).unwrap();
+
+            let sliced = SlicedPacket::from_ethernet(frame.frame()).unwrap();
+            match sliced.vlan.unwrap() {
+                VlanSlice::SingleVlan(vlan) => assert_eq!(ether_type::IPV4, vlan.ether_type()),
+                _ => unreachable!(),
+            }
+            match sliced.ip.unwrap() {
+                InternetSlice::Ipv4(ipv4, _) => {
+                    assert_eq!(ip_number::GRE, ipv4.protocol());
+                    assert_eq!(
+                        (Ipv4Header::SERIALIZED_SIZE + gre.header_len() + original.len() - inner_start) as u16,
+                        ipv4.total_len()
+                    );
+                },
+                _ => unreachable!(),
+            }
+            let (actual_gre, rest) = GreHeader::from_slice(sliced.payload).unwrap();
+            assert_eq!(ether_type::IPV4, actual_gre.protocol_type);
+            assert_eq!(gre.key, actual_gre.key);
+            if checksum {
+                assert_eq!(
+                    Some(GreHeader{ protocol_type: ether_type::IPV4, ..gre.clone() }.calc_checksum(rest)),
+                    actual_gre.checksum
+                );
+            }
+            assert_eq!(&original[inner_start..], rest);
+
+            let (_, tunnel) = frame.pop_tunnel().unwrap();
+            assert_eq!(Some(TunnelHeader::Gre(actual_gre)), tunnel);
+            assert_eq!(64..64 + original.len(), frame.range());
+            assert_eq!(&original[..], frame.frame());
+        }
+    }
+
+    #[test]
+    fn push_errors() {
+        // ip in ip with a non ip payload
+        let mut frame = udp_frame(&[]);
+        frame.push_mpls(mpls(1)).unwrap();
+        let original = frame.clone();
+        assert_matches!(
+            frame.push_tunnel(outer_ipv4(), None),
+            Err(FrameEditError::UnexpectedEtherType(ether_type::MPLS_UNICAST))
+        );
+        assert_eq!(original, frame);
+
+        // not enough headroom
+        let original = udp_frame(&[]);
+        let mut buffer = original.clone();
+        let mut frame = HeadroomFrame::new(&mut buffer, 0..original.len());
+        assert_matches!(
+            frame.push_tunnel(outer_ipv4(), Some(TunnelHeader::Gre(GreHeader::new(0)))),
+            Err(FrameEditError::Write(WriteError::SliceTooSmall(24)))
+        );
+        assert_eq!(&original[..], frame.frame());
+    }
+
+    #[test]
+    fn pop_errors() {
+        // non ip frame
+        let mut frame = udp_frame(&[]);
+        frame.push_mpls(mpls(1)).unwrap();
+        assert_matches!(
+            frame.pop_tunnel(),
+            Err(FrameEditError::UnexpectedEtherType(ether_type::MPLS_UNICAST))
+        );
+
+        // unsupported ip number
+        let original = udp_frame(&[]);
+        let mut frame = original.clone();
+        assert_matches!(
+            frame.pop_tunnel(),
+            Err(FrameEditError::UnsupportedIpNumber(ip_number::UDP))
+        );
+        assert_eq!(original, frame);
+
+        // gre header cut off
+        let mut frame = udp_frame(&[]);
+        frame.push_tunnel(outer_ipv4(), Some(TunnelHeader::Gre(GreHeader::new(0)))).unwrap();
+        let mut frame = frame[..Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE + 2].to_vec();
+        assert_matches!(
+            frame.pop_tunnel(),
+            Err(FrameEditError::Read(ReadError::UnexpectedEndOfSlice(38)))
+        );
+    }
+}
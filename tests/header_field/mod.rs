@@ -0,0 +1,183 @@
+use super::*;
+
+/// Checks that the fields are sorted, do not overlap & cover exactly `len` bytes.
+fn assert_fields_cover(fields: &[HeaderField], len: usize) {
+    let mut next_bit = 0;
+    for field in fields {
+        assert_eq!(next_bit, field.bit_offset, "{}", field.name);
+        assert!(field.bit_width > 0);
+        next_bit += field.bit_width;
+    }
+    assert_eq!(len*8, next_bit);
+
+    // names must be unique
+    for (index, field) in fields.iter().enumerate() {
+        assert!(fields[index + 1..].iter().all(|other| other.name != field.name));
+    }
+}
+
+#[test]
+fn byte_range() {
+    assert_eq!(
+        0..1,
+        HeaderField{ name: "a", bit_offset: 0, bit_width: 1 }.byte_range()
+    );
+    assert_eq!(
+        0..1,
+        HeaderField{ name: "a", bit_offset: 0, bit_width: 8 }.byte_range()
+    );
+    assert_eq!(
+        6..8,
+        HeaderField{ name: "a", bit_offset: 51, bit_width: 13 }.byte_range()
+    );
+    assert_eq!(
+        12..14,
+        HeaderField{ name: "a", bit_offset: 103, bit_width: 9 }.byte_range()
+    );
+}
+
+#[test]
+fn tables_cover_headers() {
+    assert_fields_cover(Ethernet2HeaderSlice::FIELDS, Ethernet2Header::SERIALIZED_SIZE);
+    assert_fields_cover(SingleVlanHeaderSlice::FIELDS, SingleVlanHeader::SERIALIZED_SIZE);
+    assert_fields_cover(Ipv4HeaderSlice::FIELDS, Ipv4Header::SERIALIZED_SIZE);
+    assert_fields_cover(Ipv6HeaderSlice::FIELDS, Ipv6Header::SERIALIZED_SIZE);
+    assert_fields_cover(TcpHeaderSlice::FIELDS, TCP_MINIMUM_HEADER_SIZE);
+    assert_fields_cover(UdpHeaderSlice::FIELDS, UdpHeader::SERIALIZED_SIZE);
+    assert_fields_cover(Icmpv4Slice::FIELDS, Icmpv4Header::MIN_SERIALIZED_SIZE);
+    assert_fields_cover(Icmpv6Slice::FIELDS, Icmpv6Header::MIN_SERIALIZED_SIZE);
+}
+
+#[test]
+fn tables_match_getters() {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(1234, 80, 0x01020304, 4000)
+        .syn()
+        .ack(0x05060708);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+    let field = |fields: &[HeaderField], name: &str| -> std::ops::Range<usize> {
+        fields.iter().find(|field| field.name == name).unwrap().byte_range()
+    };
+
+    let ip = match sliced.ip.unwrap() {
+        InternetSlice::Ipv4(ip, _) => ip,
+        _ => unreachable!(),
+    };
+    assert_eq!(&[20], &ip.slice()[field(Ipv4HeaderSlice::FIELDS, "ttl")]);
+    assert_eq!(&[ip_number::TCP], &ip.slice()[field(Ipv4HeaderSlice::FIELDS, "protocol")]);
+    assert_eq!(&[192,168,1,2], &ip.slice()[field(Ipv4HeaderSlice::FIELDS, "destination")]);
+
+    let tcp = match sliced.transport.unwrap() {
+        TransportSlice::Tcp(tcp) => tcp,
+        _ => unreachable!(),
+    };
+    assert_eq!(&[1,2,3,4], &tcp.slice()[field(TcpHeaderSlice::FIELDS, "sequence_number")]);
+    assert_eq!(&[5,6,7,8], &tcp.slice()[field(TcpHeaderSlice::FIELDS, "acknowledgment_number")]);
+    assert_eq!(&4000u16.to_be_bytes(), &tcp.slice()[field(TcpHeaderSlice::FIELDS, "window_size")]);
+}
+
+#[test]
+fn read_write() {
+    // byte aligned
+    {
+        let field = HeaderField{ name: "a", bit_offset: 8, bit_width: 16 };
+        let mut data = [0xff, 0, 0, 0xff];
+        field.write(&mut data, 0x1234).unwrap();
+        assert_eq!([0xff, 0x12, 0x34, 0xff], data);
+        assert_eq!(Some(0x1234), field.read(&data));
+    }
+    // unaligned (e.g. ipv4 fragment offset)
+    {
+        let field = HeaderField{ name: "a", bit_offset: 3, bit_width: 13 };
+        let mut data = [0xff, 0xff];
+        field.write(&mut data, 0).unwrap();
+        assert_eq!([0b1110_0000, 0], data);
+        field.write(&mut data, 0x1fff).unwrap();
+        assert_eq!([0xff, 0xff], data);
+        field.write(&mut data, 0x0123).unwrap();
+        assert_eq!([0b1110_0001, 0x23], data);
+        assert_eq!(Some(0x0123), field.read(&data));
+    }
+    // full width
+    {
+        let field = HeaderField{ name: "a", bit_offset: 0, bit_width: 128 };
+        let mut data = [0;16];
+        field.write(&mut data, u128::MAX).unwrap();
+        assert_eq!([0xff;16], data);
+        assert_eq!(Some(u128::MAX), field.read(&data));
+    }
+    // value too large
+    {
+        let field = HeaderField{ name: "a", bit_offset: 4, bit_width: 4 };
+        let mut data = [0];
+        assert_eq!(
+            Err(ValueError::HeaderFieldValueTooLarge{ name: "a", value: 16, bit_width: 4 }),
+            field.write(&mut data, 16)
+        );
+        assert_eq!([0], data);
+    }
+    // slice too short
+    {
+        let field = HeaderField{ name: "a", bit_offset: 12, bit_width: 8 };
+        let mut data = [0;2];
+        assert_eq!(None, field.read(&data));
+        assert_eq!(
+            Err(ValueError::HeaderFieldOutOfSlice{ name: "a", required_len: 3, actual_len: 2 }),
+            field.write(&mut data, 1)
+        );
+    }
+}
+
+#[test]
+fn get_set_field() {
+    let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(1234, 80, 1, 4000)
+        .syn();
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+
+    // ipv4
+    Ipv4HeaderSlice::set_field(&mut packet, "fragment_offset", 0x123).unwrap();
+    Ipv4HeaderSlice::set_field(&mut packet, "flags", 0b001).unwrap();
+    {
+        let ip = Ipv4HeaderSlice::from_slice(&packet).unwrap();
+        assert_eq!(0x123, ip.fragments_offset());
+        assert!(ip.more_fragments());
+        assert_eq!(false, ip.dont_fragment());
+        assert_eq!(Some(0x123), ip.get_field("fragment_offset"));
+        assert_eq!(Some(0b001), ip.get_field("flags"));
+        assert_eq!(Some(u128::from(ip_number::TCP)), ip.get_field("protocol"));
+        assert_eq!(None, ip.get_field("unknown"));
+        assert_eq!(Some(&Ipv4HeaderSlice::FIELDS[8]), Ipv4HeaderSlice::field("ttl"));
+    }
+
+    // tcp
+    let tcp_start = Ipv4Header::SERIALIZED_SIZE;
+    TcpHeaderSlice::set_field(&mut packet[tcp_start..], "flags", 0b0_0001_0010).unwrap();
+    {
+        let tcp = TcpHeaderSlice::from_slice(&packet[tcp_start..]).unwrap();
+        assert!(tcp.syn());
+        assert!(tcp.ack());
+        assert_eq!(Some(5), tcp.get_field("data_offset"));
+        assert_eq!(Some(1234), tcp.get_field("source_port"));
+        assert_eq!(Some(0b0_0001_0010), tcp.get_field("flags"));
+    }
+
+    // errors
+    assert_eq!(
+        Err(ValueError::HeaderFieldUnknown("unknown".to_string())),
+        UdpHeaderSlice::set_field(&mut packet, "unknown", 0)
+    );
+    assert_eq!(
+        Err(ValueError::HeaderFieldValueTooLarge{ name: "priority_code_point", value: 8, bit_width: 3 }),
+        SingleVlanHeaderSlice::set_field(&mut packet, "priority_code_point", 8)
+    );
+    assert_eq!(
+        Err(ValueError::HeaderFieldOutOfSlice{ name: "ether_type", required_len: 14, actual_len: 10 }),
+        Ethernet2HeaderSlice::set_field(&mut packet[..10], "ether_type", 0x800)
+    );
+}
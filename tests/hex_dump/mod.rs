@@ -0,0 +1,146 @@
+use super::*;
+use etherparse::hex_dump::*;
+
+fn udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+fn labels(dump: &AnnotatedHexDump) -> Vec<(std::ops::Range<usize>, String)> {
+    dump.annotations()
+        .iter()
+        .map(|annotation| (annotation.range.clone(), annotation.label.clone()))
+        .collect()
+}
+
+#[test]
+fn from_sliced() {
+    let packet = udp_packet();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let dump = AnnotatedHexDump::from_sliced(&packet, &sliced);
+    assert_eq!(dump, AnnotatedHexDump::from_ethernet(&packet));
+    assert_eq!(&packet[..], dump.data());
+    assert_eq!(None, dump.error_offset());
+    assert_eq!(None, dump.error_message());
+
+    let labels = labels(&dump);
+    assert!(labels.contains(&(0..6, "eth.destination".to_string())));
+    assert!(labels.contains(&(22..23, "ipv4.ttl".to_string())));
+    assert!(labels.contains(&(20..21, "ipv4.flags".to_string())));
+    assert!(labels.contains(&(20..22, "ipv4.fragment_offset".to_string())));
+    assert!(labels.contains(&(34..36, "udp.source_port".to_string())));
+    assert!(labels.contains(&(42..46, "payload".to_string())));
+
+    let output = dump.to_string();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(18, lines.len());
+    assert_eq!(
+        "0000  07 08 09 0a 0b 0c                                eth.destination",
+        lines[0]
+    );
+    assert_eq!(
+        "0014  40 00                                            ipv4.flags, ipv4.fragment_offset",
+        lines[7]
+    );
+    assert_eq!(
+        "002a  01 02 03 04                                      payload",
+        lines[17]
+    );
+}
+
+#[test]
+fn from_sliced_foreign_data() {
+    // headers pointing outside of the given data are ignored
+    let packet = udp_packet();
+    let other = packet.clone();
+    let sliced = SlicedPacket::from_ethernet(&other).unwrap();
+    let dump = AnnotatedHexDump::from_sliced(&packet, &sliced);
+    assert!(dump.annotations().is_empty());
+    assert_eq!(3, dump.to_string().lines().count());
+}
+
+#[test]
+fn from_ethernet_error() {
+    let packet = udp_packet();
+
+    // cut in the udp header
+    {
+        let data = &packet[..38];
+        let dump = AnnotatedHexDump::from_ethernet(data);
+        assert_eq!(Some(34), dump.error_offset());
+        assert_eq!(
+            Some(SlicedPacket::from_ethernet(data).unwrap_err().error.to_string().as_str()),
+            dump.error_message()
+        );
+        let labels = labels(&dump);
+        assert!(labels.contains(&(30..34, "ipv4.destination".to_string())));
+        assert!(labels.contains(&(34..38, "unparsed".to_string())));
+        let output = dump.to_string();
+        assert!(output.ends_with(&format!(
+            "0022  00 15 04 d2                                      unparsed\nerror at offset 34: {}\n",
+            SlicedPacket::from_ethernet(data).unwrap_err().error
+        )));
+    }
+
+    // cut in the ethernet header
+    {
+        let dump = AnnotatedHexDump::from_ethernet(&packet[..10]);
+        assert_eq!(Some(0), dump.error_offset());
+        assert_eq!(vec![(0..10, "unparsed".to_string())], labels(&dump));
+    }
+}
+
+#[test]
+fn from_partial() {
+    let packet = udp_packet();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let partial = SlicedPacket{
+        link: sliced.link.clone(),
+        vlan: None,
+        ip: None,
+        transport: None,
+        payload: &packet[Ethernet2Header::SERIALIZED_SIZE..],
+    };
+    let dump = AnnotatedHexDump::from_partial(&packet, &partial, &ReadError::IpUnsupportedVersion(5));
+    assert_eq!(Some(14), dump.error_offset());
+    assert_eq!(
+        vec![
+            (0..6, "eth.destination".to_string()),
+            (6..12, "eth.source".to_string()),
+            (12..14, "eth.ether_type".to_string()),
+            (14..packet.len(), "unparsed".to_string()),
+        ],
+        labels(&dump)
+    );
+}
+
+#[test]
+fn long_ranges() {
+    // ranges longer then one line are continued on the next lines without label
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6([0;16], [1;16], 20)
+        .tcp(1, 2, 3, 4)
+        .options(&[TcpOptionElement::MaximumSegmentSize(1400)]).unwrap();
+    let mut packet = Vec::with_capacity(builder.size(20));
+    builder.write(&mut packet, &[0xff;20]).unwrap();
+    let output = AnnotatedHexDump::from_ethernet(&packet).to_string();
+    let lines: Vec<&str> = output.lines().collect();
+    let source_line = lines.iter().position(|line| line.ends_with("ipv6.source")).unwrap();
+    assert_eq!(
+        "0016  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  ipv6.source",
+        lines[source_line]
+    );
+    assert!(lines.iter().any(|line| line.ends_with("tcp.options")));
+    assert!(lines.iter().any(|line| line.ends_with("tcp.data_offset, tcp.reserved, tcp.flags")));
+    assert_eq!(
+        &[
+            "004e  ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff  payload",
+            "005e  ff ff ff ff",
+        ],
+        &lines[lines.len() - 2..]
+    );
+}
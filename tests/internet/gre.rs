@@ -0,0 +1,149 @@
+use super::super::*;
+
+mod gre_header {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            GreHeader {
+                protocol_type: ether_type::IPV4,
+                checksum: None,
+                key: None,
+                sequence_number: None,
+            },
+            GreHeader::new(ether_type::IPV4)
+        );
+    }
+
+    #[test]
+    fn to_bytes() {
+        // no optional fields
+        assert_eq!(
+            &[0, 0, 0x08, 0x00],
+            &GreHeader::new(ether_type::IPV4).to_bytes()[..]
+        );
+        // all optional fields
+        assert_eq!(
+            &[
+                0b1011_0000, 0, 0x86, 0xdd,
+                0x12, 0x34, 0, 0,
+                1, 2, 3, 4,
+                5, 6, 7, 8,
+            ],
+            &GreHeader {
+                protocol_type: ether_type::IPV6,
+                checksum: Some(0x1234),
+                key: Some(0x01020304),
+                sequence_number: Some(0x05060708),
+            }.to_bytes()[..]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn header_len(input in gre_any()) {
+            assert_eq!(input.to_bytes().len(), input.header_len());
+            assert!(GreHeader::MIN_LEN <= input.header_len());
+            assert!(input.header_len() <= GreHeader::MAX_LEN);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            input in gre_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            let mut buffer = Vec::with_capacity(input.header_len() + dummy_data.len());
+            input.write(&mut buffer).unwrap();
+            buffer.extend(&dummy_data[..]);
+
+            // calls with a valid result
+            {
+                let (result, rest) = GreHeader::from_slice(&buffer).unwrap();
+                assert_eq!(input, result);
+                assert_eq!(&buffer[input.header_len()..], rest);
+            }
+            // call with not enough data in the slice
+            for len in 0..input.header_len() {
+                assert_eq!(
+                    if len < GreHeader::MIN_LEN {
+                        Some(GreHeader::MIN_LEN)
+                    } else {
+                        Some(input.header_len())
+                    },
+                    GreHeader::from_slice(&buffer[..len])
+                        .unwrap_err()
+                        .unexpected_end_of_slice_min_expected_size()
+                );
+            }
+            // unsupported version
+            for version in 1..=0b111u8 {
+                let mut buffer = buffer.clone();
+                buffer[1] |= version;
+                assert_matches!(
+                    GreHeader::from_slice(&buffer),
+                    Err(ReadError::GreUnsupportedVersion(v)) if v == version
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in gre_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            assert_eq!(&expected[..], &input.to_bytes()[..]);
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum(
+            input in gre_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64)
+        ) {
+            // the checksum field has to be present to be included in the checksum
+            let header = GreHeader {
+                checksum: Some(0),
+                ..input
+            };
+            let header = GreHeader {
+                checksum: Some(header.calc_checksum(&payload)),
+                ..header
+            };
+            // the checksum over the header (including the checksum
+            // field) and the payload must be zero
+            let mut buffer = header.to_bytes().to_vec();
+            buffer.extend(&payload);
+            assert_eq!(
+                0,
+                etherparse::checksum::Sum16BitWords::new()
+                    .add_slice(&buffer)
+                    .ones_complement()
+            );
+        }
+    }
+}
@@ -185,6 +185,39 @@ mod ip_header {
         }
     }
 
+    proptest!{
+        #[test]
+        fn write_to_slice(
+            v4 in ipv4_any(),
+            v4_exts in ipv4_extensions_any(),
+            v6 in ipv6_any(),
+            v6_exts in ipv6_extensions_any(),
+        ) {
+            for header in [combine_v4(&v4, &v4_exts), combine_v6(&v6, &v6_exts)] {
+                let expected = {
+                    let mut buffer = Vec::with_capacity(header.header_len());
+                    header.write(&mut buffer).unwrap();
+                    buffer
+                };
+
+                // normal write
+                let mut buffer = vec![0u8;header.header_len() + 1];
+                let rest = header.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..header.header_len()]);
+
+                // slice too small
+                for len in 0..header.header_len() {
+                    let mut buffer = vec![0u8;len];
+                    assert_eq!(
+                        Some(header.header_len()),
+                        header.write_to_slice(&mut buffer).unwrap_err().slice_too_small_size()
+                    );
+                }
+            }
+        }
+    }
+
     proptest!{
         #[test]
         fn write(
@@ -509,13 +542,16 @@ mod ip_number {
             (STREAM, Stream),
             (TCP, Tcp),
             (UDP, Udp),
+            (DCCP, Dccp),
             (IPV6, Ipv6),
             (IPV6_ROUTE, IPv6RouteHeader),
             (IPV6_FRAG, IPv6FragmentationHeader),
             (ENCAP_SEC, EncapsulatingSecurityPayload),
             (AUTH, AuthenticationHeader),
             (IPV6_DEST_OPTIONS, IPv6DestinationOptions),
+            (SCTP, Sctp),
             (MOBILITY, MobilityHeader),
+            (UDP_LITE, UdpLite),
             (HIP, Hip),
             (SHIM6, Shim6),
             (EXP0, ExperimentalAndTesting0),
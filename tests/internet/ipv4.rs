@@ -428,6 +428,34 @@ mod header {
             assert!(header.is_fragmenting_payload());
         }
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in ipv4_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 mod slice {
@@ -510,6 +538,15 @@ fn range_errors() {
                        .unwrap_err()
                        .value_error());
         }
+        //write_to_slice
+        {
+            let mut buffer = [0u8;60];
+            assert_eq!(Some(expected.clone()),
+                       input
+                       .write_to_slice(&mut buffer)
+                       .unwrap_err()
+                       .value_error());
+        }
     }
     //dscp
     {
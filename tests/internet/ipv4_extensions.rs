@@ -165,6 +165,58 @@ pub mod header {
         }
     }
 
+    #[test]
+    fn write_to_slice() {
+        // None
+        {
+            let mut buffer = [0u8;1];
+            let rest = Ipv4Extensions{
+                auth: None,
+            }.write_to_slice(&mut buffer, UDP).unwrap();
+            assert_eq!(1, rest.len());
+        }
+
+        // Some
+        let auth_header = IpAuthenticationHeader::new(
+            UDP,
+            0,
+            0,
+            &[1,2,3,4]
+        ).unwrap();
+        let exts = Ipv4Extensions{
+            auth: Some(auth_header.clone()),
+        };
+        {
+            let mut buffer = vec![0u8;auth_header.header_len() + 1];
+            let rest = exts.write_to_slice(&mut buffer, AUTH).unwrap();
+            assert_eq!(1, rest.len());
+            let (read_header, _) = IpAuthenticationHeader::from_slice(&buffer).unwrap();
+            assert_eq!(auth_header, read_header);
+        }
+
+        // Some bad start number
+        {
+            let mut buffer = vec![0u8;auth_header.header_len()];
+            assert_matches!(
+                exts.write_to_slice(&mut buffer, UDP),
+                Err(WriteError::ValueError(
+                    ValueError::Ipv4ExtensionNotReferenced(
+                        IpNumber::AuthenticationHeader
+                    )
+                ))
+            );
+        }
+
+        // Some: slice too small
+        for len in 0..auth_header.header_len() {
+            let mut buffer = vec![0u8;len];
+            assert_eq!(
+                Some(auth_header.header_len()),
+                exts.write_to_slice(&mut buffer, AUTH).unwrap_err().slice_too_small_size()
+            );
+        }
+    }
+
     #[test]
     fn header_len() {
         // None
@@ -101,6 +101,13 @@ fn write_errors() {
             value
         }), 
         Err(ValueError(U32TooLarge{value: 0x100000, max: 0xFFFFF, field: Ipv6FlowLabel})));
+    assert_matches!(
+        {
+            let mut value = base();
+            value.flow_label = 0x100000;
+            value.write_to_slice(&mut [0u8;Ipv6Header::SERIALIZED_SIZE])
+        },
+        Err(ValueError(U32TooLarge{value: 0x100000, max: 0xFFFFF, field: Ipv6FlowLabel})));
 
     //io error (not enough space)
     {
@@ -495,3 +502,31 @@ fn eq() {
     assert!(slice.eq(&slice.clone()));
     assert!(false == slice.ne(&slice.clone()));
 }
+
+proptest! {
+    #[test]
+    fn write_to_slice(input in ipv6_any()) {
+        let expected = {
+            let mut buffer = Vec::new();
+            input.write(&mut buffer).unwrap();
+            buffer
+        };
+        // normal write (the unused rest of the slice is returned)
+        {
+            let mut buffer = vec![0u8;expected.len() + 1];
+            let rest = input.write_to_slice(&mut buffer).unwrap();
+            assert_eq!(1, rest.len());
+            assert_eq!(&expected[..], &buffer[..expected.len()]);
+        }
+        // slice too small
+        for len in 0..expected.len() {
+            let mut buffer = vec![0u8;len];
+            assert_eq!(
+                Some(expected.len()),
+                input.write_to_slice(&mut buffer)
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+        }
+    }
+}
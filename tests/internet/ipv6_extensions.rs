@@ -487,10 +487,10 @@ pub mod header {
                     assert_eq!(next, expected_post_header);
                     assert_eq!(rest, &e.slice()[read_len..]);
                 
-                    // unexpected end of slice
+                    // unexpected end of slice (length relative to the start of the slice)
                     assert_matches!(
                         Ipv6Extensions::from_slice(ip_numbers[0], &e.slice()[..read_len - 1]).unwrap_err(),
-                        ReadError::UnexpectedEndOfSlice(_)
+                        ReadError::UnexpectedEndOfSlice(value) if value == read_len
                     );
                 }
             }
@@ -638,6 +638,11 @@ pub mod header {
                         e.data.write(&mut writer, e.ip_numbers[0]).unwrap_err().value_error().unwrap(),
                         Ipv6ExtensionHopByHopNotAtStart
                     );
+                    let mut buffer = vec![0u8;e.data.header_len()];
+                    assert_eq!(
+                        e.data.write_to_slice(&mut buffer, e.ip_numbers[0]).unwrap_err().value_error().unwrap(),
+                        Ipv6ExtensionHopByHopNotAtStart
+                    );
                 } else {
                     // normal write
                     {
@@ -656,6 +661,30 @@ pub mod header {
                         }
                     }
 
+                    // write to slice
+                    {
+                        let expected = {
+                            let mut writer = Vec::with_capacity(e.data.header_len());
+                            e.data.write(&mut writer, e.ip_numbers[0]).unwrap();
+                            writer
+                        };
+                        let mut buffer = vec![0u8;e.data.header_len() + 1];
+                        let rest = e.data.write_to_slice(&mut buffer, e.ip_numbers[0]).unwrap();
+                        assert_eq!(1, rest.len());
+                        assert_eq!(&expected[..], &buffer[..e.data.header_len()]);
+
+                        // slice too small
+                        for len in 0..e.data.header_len() {
+                            let mut buffer = vec![0u8;len];
+                            assert_eq!(
+                                Some(e.data.header_len()),
+                                e.data.write_to_slice(&mut buffer, e.ip_numbers[0])
+                                    .unwrap_err()
+                                    .slice_too_small_size()
+                            );
+                        }
+                    }
+
                     // write error
                     {
                         let mut writer = TestWriter::with_max_size(
@@ -687,6 +716,16 @@ pub mod header {
                             err.value_error().unwrap(),
                             Ipv6ExtensionNotReferenced(missing_ip_number)
                         );
+
+                        let mut buffer = vec![0u8;missing_ref.data.header_len()];
+                        let err = missing_ref.data.write_to_slice(
+                            &mut buffer,
+                            missing_ref.ip_numbers[0]
+                        ).unwrap_err();
+                        assert_eq!(
+                            err.value_error().unwrap(),
+                            Ipv6ExtensionNotReferenced(missing_ip_number)
+                        );
                     }
                 }
             }
@@ -1126,10 +1165,10 @@ pub mod slice {
                     assert_eq!(next, *ip_numbers.last().unwrap());
                     assert_eq!(rest, &e.slice()[e.slice().len()..]);
                 
-                    // unexpected end of slice
+                    // unexpected end of slice (length relative to the start of the slice)
                     assert_matches!(
                         Ipv6ExtensionsSlice::from_slice(ip_numbers[0], &e.slice()[..e.slice().len() - 1]).unwrap_err(),
-                        ReadError::UnexpectedEndOfSlice(_)
+                        ReadError::UnexpectedEndOfSlice(value) if value == e.slice().len()
                     );
                 }
             }
@@ -281,6 +281,34 @@ pub mod header {
             assert_eq!(input, input.clone());
         }
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in ipv6_fragment_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 pub mod slice {
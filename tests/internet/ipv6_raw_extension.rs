@@ -178,6 +178,86 @@ fn extension_from_slice_bad_length() {
     }
 }
 
+#[test]
+fn ipv6_option_constants() {
+    use ipv6_option::*;
+    assert_eq!(0, TYPE_PAD1);
+    assert_eq!(1, TYPE_PADN);
+    assert_eq!(5, TYPE_ROUTER_ALERT);
+    assert_eq!(2, LEN_ROUTER_ALERT);
+    assert_eq!(0, ROUTER_ALERT_MLD);
+    assert_eq!(1, ROUTER_ALERT_RSVP);
+    assert_eq!(2, ROUTER_ALERT_ACTIVE_NETWORKS);
+}
+
+#[test]
+fn new_router_alert() {
+    let header = Ipv6RawExtensionHeader::new_router_alert(ip_number::IPV6_ICMP, 0x1234);
+    assert_eq!(ip_number::IPV6_ICMP, header.next_header);
+    assert_eq!(&[5, 2, 0x12, 0x34, 1, 0], header.payload());
+    assert_eq!(8, header.header_len());
+    assert_eq!(Some(0x1234), header.router_alert());
+
+    // slice
+    let mut bytes = Vec::with_capacity(header.header_len());
+    header.write(&mut bytes).unwrap();
+    let slice = Ipv6RawExtensionHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(Some(0x1234), slice.router_alert());
+}
+
+#[test]
+fn add_router_alert() {
+    // appended after the existing options
+    {
+        let mut header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[1, 4, 0, 0, 0, 0]).unwrap();
+        header.add_router_alert(0x1234).unwrap();
+        assert_eq!(
+            &[1, 4, 0, 0, 0, 0, 5, 2, 0x12, 0x34, 1, 2, 0, 0],
+            header.payload()
+        );
+        assert_eq!(16, header.header_len());
+        assert_eq!(Some(0x1234), header.router_alert());
+    }
+    // payload too large
+    {
+        let payload = [0u8;Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN];
+        let mut header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &payload).unwrap();
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN + 8)),
+            header.add_router_alert(0x1234)
+        );
+        assert_eq!(&payload[..], header.payload());
+    }
+}
+
+#[test]
+fn router_alert() {
+    let tests: [(&[u8], Option<u16>); 8] = [
+        // no options (padding only)
+        (&[1, 4, 0, 0, 0, 0], None),
+        (&[0, 0, 0, 0, 0, 0], None),
+        // router alert after pad1 & padn
+        (&[0, 5, 2, 0, 1, 0], Some(1)),
+        (&[1, 0, 5, 2, 0, 2], Some(2)),
+        // router alert behind another option
+        (&[0x3e, 0, 5, 2, 0xab, 0xcd], Some(0xabcd)),
+        // router alert option with wrong length
+        (&[5, 3, 0, 0, 0, 0], None),
+        // truncated option
+        (&[1, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5, 2], None),
+        (&[1, 10, 5, 2, 0, 0], None),
+    ];
+    for (payload, expected) in tests.iter() {
+        let header = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, payload).unwrap();
+        assert_eq!(*expected, header.router_alert());
+
+        let mut bytes = Vec::with_capacity(header.header_len());
+        header.write(&mut bytes).unwrap();
+        let slice = Ipv6RawExtensionHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(*expected, slice.router_alert());
+    }
+}
+
 #[test]
 fn header_type_supported() {
     use crate::ip_number::*;
@@ -348,3 +428,31 @@ fn partial_equal() {
         assert_ne!(a, b);
     }
 }
+
+proptest! {
+    #[test]
+    fn write_to_slice(input in ipv6_raw_extension_any()) {
+        let expected = {
+            let mut buffer = Vec::new();
+            input.write(&mut buffer).unwrap();
+            buffer
+        };
+        // normal write (the unused rest of the slice is returned)
+        {
+            let mut buffer = vec![0u8;expected.len() + 1];
+            let rest = input.write_to_slice(&mut buffer).unwrap();
+            assert_eq!(1, rest.len());
+            assert_eq!(&expected[..], &buffer[..expected.len()]);
+        }
+        // slice too small
+        for len in 0..expected.len() {
+            let mut buffer = vec![0u8;len];
+            assert_eq!(
+                Some(expected.len()),
+                input.write_to_slice(&mut buffer)
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+        }
+    }
+}
@@ -5,4 +5,5 @@ pub mod ipv4_extensions;
 pub mod ipv6;
 pub mod ipv6_extensions;
 pub mod ipv6_raw_extension;
-pub mod ipv6_fragment;
\ No newline at end of file
+pub mod ipv6_fragment;
+pub mod gre;
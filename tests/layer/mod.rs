@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn name_and_display() {
+    use Layer::*;
+    let tests = [
+        (LinkHeader, "link header"),
+        (Ethernet2Header, "Ethernet 2 header"),
+        (VlanHeader, "VLAN header"),
+        (IpHeader, "IP header"),
+        (Ipv4Header, "IPv4 header"),
+        (Ipv4Extensions, "IPv4 extension headers"),
+        (Ipv6Header, "IPv6 header"),
+        (Ipv6Extensions, "IPv6 extension headers"),
+        (Icmpv4, "ICMPv4 packet"),
+        (Icmpv6, "ICMPv6 packet"),
+        (Igmp, "IGMP message"),
+        (SctpHeader, "SCTP header"),
+        (UdpHeader, "UDP header"),
+        (TcpHeader, "TCP header"),
+        (UdpLiteHeader, "UDP-Lite header"),
+        (DccpHeader, "DCCP header"),
+    ];
+    for (layer, name) in tests.iter() {
+        assert_eq!(*name, layer.name());
+        assert_eq!(*name, format!("{}", layer));
+    }
+}
+
+#[test]
+fn debug_clone_eq_ord() {
+    let layer = Layer::TcpHeader;
+    assert_eq!("TcpHeader", format!("{:?}", layer));
+    assert_eq!(layer, layer.clone());
+    assert!(Layer::Ethernet2Header < Layer::IpHeader);
+}
@@ -15,6 +15,8 @@ mod ether_type {
         assert_eq!(0x8100, VlanTaggedFrame as u16);
         assert_eq!(0x88A8, ProviderBridging as u16);
         assert_eq!(0x9100, VlanDoubleTaggedFrame as u16);
+        assert_eq!(0x8847, MplsUnicast as u16);
+        assert_eq!(0x8848, MplsMulticast as u16);
     }
 
     #[test]
@@ -27,6 +29,8 @@ mod ether_type {
         assert_eq!(EtherType::from_u16(0x8100), Some(VlanTaggedFrame));
         assert_eq!(EtherType::from_u16(0x88A8), Some(ProviderBridging));
         assert_eq!(EtherType::from_u16(0x9100), Some(VlanDoubleTaggedFrame));
+        assert_eq!(EtherType::from_u16(0x8847), Some(MplsUnicast));
+        assert_eq!(EtherType::from_u16(0x8848), Some(MplsMulticast));
         assert_eq!(EtherType::from_u16(0x1234), None);
     }
 
@@ -41,7 +45,9 @@ mod ether_type {
             (WakeOnLan, WAKE_ON_LAN),
             (VlanTaggedFrame, VLAN_TAGGED_FRAME),
             (ProviderBridging, PROVIDER_BRIDGING),
-            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME)
+            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME),
+            (MplsUnicast, MPLS_UNICAST),
+            (MplsMulticast, MPLS_MULTICAST)
         ];
 
         for (enum_value, constant) in pairs {
@@ -59,7 +65,9 @@ mod ether_type {
             (WakeOnLan, "WakeOnLan"),
             (VlanTaggedFrame, "VlanTaggedFrame"),
             (ProviderBridging, "ProviderBridging"),
-            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame")
+            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame"),
+            (MplsUnicast, "MplsUnicast"),
+            (MplsMulticast, "MplsMulticast")
         ];
 
         for (enum_value, str_value) in pairs {
@@ -81,6 +89,8 @@ mod ether_type {
             VlanTaggedFrame,
             ProviderBridging,
             VlanDoubleTaggedFrame,
+            MplsUnicast,
+            MplsMulticast,
         ];
 
         // clone
@@ -86,6 +86,35 @@ mod vlan_header {
         }
     }
 
+    proptest!{
+        #[test]
+        fn write_to_slice(
+            single in vlan_single_any(),
+            double in vlan_double_any(),
+        ) {
+            for header in [VlanHeader::Single(single.clone()), VlanHeader::Double(double.clone())] {
+                let expected = {
+                    let mut buffer = Vec::with_capacity(header.header_len());
+                    header.write(&mut buffer).unwrap();
+                    buffer
+                };
+                let mut buffer = vec![0u8;header.header_len() + 1];
+                let rest = header.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..header.header_len()]);
+
+                // slice too small
+                for len in 0..header.header_len() {
+                    let mut buffer = vec![0u8;len];
+                    assert_eq!(
+                        Some(header.header_len()),
+                        header.write_to_slice(&mut buffer).unwrap_err().slice_too_small_size()
+                    );
+                }
+            }
+        }
+    }
+
     proptest!{
         #[test]
         fn write(
@@ -354,6 +383,13 @@ mod single_vlan_header {
                         bad_input.to_bytes()
                             .unwrap_err()
                     );
+                    assert_eq!(
+                        expected,
+                        bad_input.write_to_slice(&mut [0u8;SingleVlanHeader::SERIALIZED_SIZE])
+                            .unwrap_err()
+                            .value_error()
+                            .unwrap()
+                    );
                 }
             }
 
@@ -436,6 +472,34 @@ mod single_vlan_header {
             );
         }
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in vlan_single_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 mod double_vlan_header {
@@ -676,6 +740,34 @@ mod double_vlan_header {
             );
         }
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in vlan_double_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 mod single_vlan_header_slice {
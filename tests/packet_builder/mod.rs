@@ -1560,3 +1560,541 @@ proptest! {
         }
     }
 }
+
+#[test]
+fn ipv6_icmpv6_mld() {
+    use etherparse::icmpv6::*;
+
+    let source = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+    let destination = [0xff,0x02,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+    let group = [0xff,0x12,0,0,0,0,0,0,0,0,0,0,0,0,0,0x16];
+    let query_v2 = MulticastListenerQueryV2Header{
+        maximum_response_code: 1000,
+        multicast_address: group,
+        suppress_router_side_processing: true,
+        querier_robustness_variable: 2,
+        querier_query_interval_code: 125,
+        number_of_sources: 0,
+    };
+
+    let tests = [
+        (
+            PacketBuilder::ipv6(source, destination, 1).icmpv6_mld_query(1000, group),
+            Icmpv6Type::MulticastListenerQuery(MulticastListenerHeader{
+                maximum_response_delay: 1000,
+                multicast_address: group,
+            })
+        ),
+        (
+            PacketBuilder::ipv6(source, destination, 1).icmpv6_mld_report(group),
+            Icmpv6Type::MulticastListenerReport(MulticastListenerHeader{
+                maximum_response_delay: 0,
+                multicast_address: group,
+            })
+        ),
+        (
+            PacketBuilder::ipv6(source, destination, 1).icmpv6_mld_done(group),
+            Icmpv6Type::MulticastListenerDone(MulticastListenerHeader{
+                maximum_response_delay: 0,
+                multicast_address: group,
+            })
+        ),
+        (
+            PacketBuilder::ipv6(source, destination, 1).icmpv6_mldv2_query(query_v2),
+            Icmpv6Type::MulticastListenerQueryV2(query_v2)
+        ),
+        (
+            PacketBuilder::ipv6(source, destination, 1).icmpv6_mldv2_report(0),
+            Icmpv6Type::MulticastListenerReportV2{ number_of_records: 0 }
+        ),
+    ];
+
+    for (builder, expected_type) in tests {
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(0));
+        builder.write(&mut buffer, &[]).unwrap();
+        assert_eq!(
+            Ipv6Header::SERIALIZED_SIZE + 8 + expected_type.header_len(),
+            buffer.len()
+        );
+
+        let actual = SlicedPacket::from_ip(&buffer).unwrap();
+
+        // hop-by-hop header with router alert
+        match actual.ip.unwrap() {
+            InternetSlice::Ipv6(header, ext) => {
+                assert_eq!(ip_number::IPV6_HOP_BY_HOP, header.next_header());
+                let (hop_by_hop, _) = Ipv6RawExtensionHeader::from_slice(ext.slice()).unwrap();
+                assert_eq!(ip_number::IPV6_ICMP, hop_by_hop.next_header);
+                assert_eq!(Some(ipv6_option::ROUTER_ALERT_MLD), hop_by_hop.router_alert());
+            },
+            _ => panic!("expected ipv6 header"),
+        }
+
+        // icmpv6 message
+        match actual.transport.unwrap() {
+            TransportSlice::Icmpv6(icmp) => {
+                assert_eq!(expected_type, icmp.icmp_type());
+                assert!(icmp.is_checksum_valid(source, destination));
+            },
+            _ => panic!("expected icmpv6"),
+        }
+    }
+}
+
+#[test]
+fn ipv6_icmpv6_mld_existing_hop_by_hop() {
+    let ipv6 = Ipv6Header{
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 0,
+        next_header: ip_number::IPV6_HOP_BY_HOP,
+        hop_limit: 1,
+        source: [0;16],
+        destination: [0;16],
+    };
+
+    // the router alert option gets added to an already present hop-by-hop header
+    {
+        let hop_by_hop = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[1,4,0,0,0,0]).unwrap();
+        let builder = PacketBuilder::ip(IpHeader::Version6(
+            ipv6.clone(),
+            Ipv6Extensions{
+                hop_by_hop_options: Some(hop_by_hop.clone()),
+                ..Default::default()
+            }
+        )).icmpv6_mld_report([0xff;16]);
+
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(0));
+        builder.write(&mut buffer, &[]).unwrap();
+        assert_eq!(buffer.capacity(), buffer.len());
+
+        let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            IpHeader::Version6(_, ext) => {
+                let actual_hop_by_hop = ext.hop_by_hop_options.unwrap();
+                assert_eq!(hop_by_hop.payload(), &actual_hop_by_hop.payload()[..hop_by_hop.payload().len()]);
+                assert_eq!(Some(ipv6_option::ROUTER_ALERT_MLD), actual_hop_by_hop.router_alert());
+            },
+            _ => panic!("expected ipv6 header"),
+        }
+    }
+
+    // an already present router alert option is kept
+    {
+        let hop_by_hop = Ipv6RawExtensionHeader::new_router_alert(ip_number::UDP, 0x1234);
+        let builder = PacketBuilder::ip(IpHeader::Version6(
+            ipv6.clone(),
+            Ipv6Extensions{
+                hop_by_hop_options: Some(hop_by_hop.clone()),
+                ..Default::default()
+            }
+        )).icmpv6_mld_report([0xff;16]);
+
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(0));
+        builder.write(&mut buffer, &[]).unwrap();
+
+        let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            IpHeader::Version6(_, ext) => {
+                assert_eq!(hop_by_hop.payload(), ext.hop_by_hop_options.unwrap().payload());
+            },
+            _ => panic!("expected ipv6 header"),
+        }
+    }
+
+    // error if the router alert option does not fit into the hop-by-hop header
+    {
+        let mut payload = [0u8;Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN];
+        payload[0] = ipv6_option::TYPE_PADN;
+        payload[1] = 0xff;
+        let hop_by_hop = Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &payload).unwrap();
+        let builder = PacketBuilder::ip(IpHeader::Version6(
+            ipv6.clone(),
+            Ipv6Extensions{
+                hop_by_hop_options: Some(hop_by_hop),
+                ..Default::default()
+            }
+        )).icmpv6_mld_report([0xff;16]);
+
+        let mut buffer = Vec::<u8>::new();
+        assert_eq!(
+            Some(ValueError::Ipv6ExtensionPayloadTooLarge(Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN + 8)),
+            builder.write(&mut buffer, &[]).unwrap_err().value_error()
+        );
+    }
+}
+
+#[test]
+fn ipv4_igmp() {
+    use etherparse::igmp::*;
+
+    let query_v3 = MembershipQueryV3Header{
+        max_response_code: 100,
+        group_address: [239,1,2,3],
+        suppress_router_side_processing: false,
+        querier_robustness_variable: 2,
+        querier_query_interval_code: 125,
+        number_of_sources: 0,
+    };
+    let tests = [
+        IgmpType::MembershipQuery(MembershipQueryHeader{
+            max_response_time: 100,
+            group_address: [0;4],
+        }),
+        IgmpType::MembershipQueryV3(query_v3),
+        IgmpType::MembershipReportV2{ group_address: [239,1,2,3] },
+        IgmpType::LeaveGroup{ group_address: [239,1,2,3] },
+        IgmpType::MembershipReportV3{ number_of_records: 0 },
+    ];
+
+    for igmp_type in tests {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [224,0,0,22], 1)
+            .igmp(igmp_type);
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(0));
+        builder.write(&mut buffer, &[]).unwrap();
+        assert_eq!(
+            Ipv4Header::SERIALIZED_SIZE + 4 + igmp_type.header_len(),
+            buffer.len()
+        );
+
+        let actual = SlicedPacket::from_ip(&buffer).unwrap();
+
+        // ipv4 header with router alert option
+        match actual.ip.unwrap() {
+            InternetSlice::Ipv4(header, _) => {
+                assert_eq!(ip_number::IGMP, header.protocol());
+                assert_eq!(&[0x94, 0x04, 0, 0], header.options());
+                assert_eq!(1, header.ttl());
+            },
+            _ => panic!("expected ipv4 header"),
+        }
+
+        // igmp message
+        match actual.transport.unwrap() {
+            TransportSlice::Igmp(igmp) => {
+                assert_eq!(igmp_type, igmp.igmp_type());
+                assert!(igmp.is_checksum_valid());
+            },
+            _ => panic!("expected igmp slice"),
+        }
+    }
+}
+
+#[test]
+fn ipv4_igmp_existing_options() {
+    // already present ipv4 options are not replaced
+    let mut ip_header = Ipv4Header::new(0, 1, ip_number::IGMP, [192,168,1,1], [224,0,0,2]);
+    ip_header.set_options(&[1,1,1,1]).unwrap();
+    let builder = PacketBuilder::ip(IpHeader::Version4(ip_header, Default::default()))
+        .igmp(IgmpType::LeaveGroup{ group_address: [239,1,2,3] });
+
+    let mut buffer = Vec::<u8>::with_capacity(builder.size(0));
+    builder.write(&mut buffer, &[]).unwrap();
+
+    let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+    match actual.ip.unwrap() {
+        IpHeader::Version4(header, _) => {
+            assert_eq!(&[1,1,1,1], header.options());
+        },
+        _ => panic!("expected ipv4 header"),
+    }
+    assert_eq!(
+        Some(TransportHeader::Igmp(IgmpHeader::with_checksum(
+            IgmpType::LeaveGroup{ group_address: [239,1,2,3] },
+            &[]
+        ))),
+        actual.transport
+    );
+}
+
+#[test]
+fn sctp() {
+    use etherparse::sctp::*;
+
+    let payload = [CHUNK_TYPE_SHUTDOWN, 0, 0, 8, 0, 0, 0, 1];
+
+    // ipv4
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .sctp(2905, 2906, 0x12345678);
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+        assert_eq!(
+            Ipv4Header::SERIALIZED_SIZE + SctpHeader::SERIALIZED_SIZE + payload.len(),
+            buffer.len()
+        );
+
+        let actual = SlicedPacket::from_ip(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            InternetSlice::Ipv4(header, _) => assert_eq!(ip_number::SCTP, header.protocol()),
+            _ => panic!("expected ipv4 header"),
+        }
+        match actual.transport.unwrap() {
+            TransportSlice::Sctp(sctp) => {
+                assert_eq!(2905, sctp.source_port());
+                assert_eq!(2906, sctp.destination_port());
+                assert_eq!(0x12345678, sctp.verification_tag());
+                assert!(sctp.is_checksum_valid(actual.payload));
+            },
+            _ => panic!("expected sctp slice"),
+        }
+        assert_eq!(&payload[..], actual.payload);
+    }
+
+    // ipv6
+    {
+        let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+            .sctp(2905, 2906, 0x12345678);
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+        assert_eq!(
+            Ipv6Header::SERIALIZED_SIZE + SctpHeader::SERIALIZED_SIZE + payload.len(),
+            buffer.len()
+        );
+
+        let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            IpHeader::Version6(header, _) => assert_eq!(ip_number::SCTP, header.next_header),
+            _ => panic!("expected ipv6 header"),
+        }
+        assert_eq!(
+            Some(TransportHeader::Sctp(SctpHeader::with_checksum(2905, 2906, 0x12345678, &payload))),
+            actual.transport
+        );
+        assert_eq!(&payload[..], actual.payload);
+    }
+}
+
+#[test]
+fn udp_lite() {
+    let payload = [1,2,3,4,5,6,7,8];
+
+    // ipv4
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp_lite(21, 1234, 12);
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+        assert_eq!(
+            Ipv4Header::SERIALIZED_SIZE + UdpLiteHeader::SERIALIZED_SIZE + payload.len(),
+            buffer.len()
+        );
+
+        let actual = SlicedPacket::from_ip(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            InternetSlice::Ipv4(header, _) => assert_eq!(ip_number::UDP_LITE, header.protocol()),
+            _ => panic!("expected ipv4 header"),
+        }
+        match actual.transport.unwrap() {
+            TransportSlice::UdpLite(udp_lite) => {
+                assert_eq!(21, udp_lite.source_port());
+                assert_eq!(1234, udp_lite.destination_port());
+                assert_eq!(12, udp_lite.checksum_coverage());
+                assert_eq!(
+                    udp_lite.checksum(),
+                    UdpLiteHeader::new(21, 1234, 12).calc_checksum_ipv4_raw(
+                        [192,168,1,1],
+                        [192,168,1,2],
+                        &payload
+                    ).unwrap()
+                );
+            },
+            _ => panic!("expected udp-lite slice"),
+        }
+        assert_eq!(&payload[..], actual.payload);
+    }
+
+    // ipv6
+    {
+        let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+            .udp_lite(21, 1234, 0);
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+
+        let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            IpHeader::Version6(header, _) => assert_eq!(ip_number::UDP_LITE, header.next_header),
+            _ => panic!("expected ipv6 header"),
+        }
+        let mut expected = UdpLiteHeader::new(21, 1234, 0);
+        expected.checksum = expected.calc_checksum_ipv6_raw([1;16], [2;16], &payload).unwrap();
+        assert_eq!(Some(TransportHeader::UdpLite(expected)), actual.transport);
+        assert_eq!(&payload[..], actual.payload);
+    }
+
+    // checksum coverage error
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp_lite(21, 1234, 17);
+        assert_matches!(
+            builder.write(&mut Vec::new(), &payload),
+            Err(WriteError::ValueError(ValueError::UdpLiteChecksumCoverageBad(17)))
+        );
+    }
+}
+
+#[test]
+fn dccp() {
+    let payload = [1,2,3,4,5,6,7,8];
+    let options = [dccp::OPTION_TIMESTAMP, 6, 1, 2, 3, 4, 0, 0];
+
+    // ipv4
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .dccp(5001, 5002, DccpSequenceNumber::Short(1), DccpPacketType::DataAck{ acknowledgement_number: 2 })
+            .ccval(3)
+            .checksum_coverage(2)
+            .options_raw(&options).unwrap();
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+        assert_eq!(
+            Ipv4Header::SERIALIZED_SIZE + 16 + options.len() + payload.len(),
+            buffer.len()
+        );
+
+        let actual = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            IpHeader::Version4(header, _) => assert_eq!(ip_number::DCCP, header.protocol),
+            _ => panic!("expected ipv4 header"),
+        }
+        let expected = {
+            let mut header = DccpHeader::new(5001, 5002, DccpSequenceNumber::Short(1), DccpPacketType::DataAck{ acknowledgement_number: 2 });
+            header.ccval = 3;
+            header.checksum_coverage = 2;
+            header.set_options_raw(&options).unwrap();
+            header.checksum = header.calc_checksum_ipv4_raw([192,168,1,1], [192,168,1,2], &payload).unwrap();
+            header
+        };
+        assert_eq!(Some(TransportHeader::Dccp(expected)), actual.transport);
+        assert_eq!(&payload[..], actual.payload);
+    }
+
+    // ipv6
+    {
+        let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+            .dccp(5001, 5002, DccpSequenceNumber::Extended(1234), DccpPacketType::Request{ service_code: 42 });
+        let mut buffer = Vec::<u8>::with_capacity(builder.size(payload.len()));
+        builder.write(&mut buffer, &payload).unwrap();
+
+        let actual = SlicedPacket::from_ip(&buffer).unwrap();
+        match actual.ip.unwrap() {
+            InternetSlice::Ipv6(header, _) => assert_eq!(ip_number::DCCP, header.next_header()),
+            _ => panic!("expected ipv6 header"),
+        }
+        match actual.transport.unwrap() {
+            TransportSlice::Dccp(dccp) => {
+                assert_eq!(
+                    dccp.checksum(),
+                    DccpHeader::new(5001, 5002, DccpSequenceNumber::Extended(1234), DccpPacketType::Request{ service_code: 42 })
+                        .calc_checksum_ipv6_raw([1;16], [2;16], &payload).unwrap()
+                );
+            },
+            _ => panic!("expected dccp slice"),
+        }
+        assert_eq!(&payload[..], actual.payload);
+    }
+
+    // options error
+    assert_eq!(
+        Err(ValueError::DccpOptionsLengthBad(3)),
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .dccp(5001, 5002, DccpSequenceNumber::Short(1), DccpPacketType::Data)
+            .options_raw(&[0,0,0])
+            .map(|_| ())
+    );
+
+    // checksum coverage error
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .dccp(5001, 5002, DccpSequenceNumber::Short(1), DccpPacketType::Data)
+            .checksum_coverage(4);
+        assert_matches!(
+            builder.write(&mut Vec::new(), &payload),
+            Err(WriteError::ValueError(ValueError::DccpChecksumCoverageBad(4)))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_to_slice(
+        ref payload in proptest::collection::vec(any::<u8>(), 0..64),
+        ref tcp in tcp_any(),
+    ) {
+        /// Checks that `write_to_slice` produces the same result as `write`
+        /// and reports the packet size when the slice is too small.
+        macro_rules! check {
+            ($builder:expr) => {{
+                let expected = {
+                    let mut buffer = Vec::new();
+                    $builder.write(&mut buffer, payload).unwrap();
+                    buffer
+                };
+                assert_eq!(expected.len(), $builder.size(payload.len()));
+
+                // normal write
+                let mut buffer = vec![0u8;expected.len() + 1];
+                assert_eq!(
+                    expected.len(),
+                    $builder.write_to_slice(&mut buffer, payload).unwrap()
+                );
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+
+                // slice too small
+                for len in 0..expected.len() {
+                    let mut buffer = vec![0u8;len];
+                    assert_eq!(
+                        Some(expected.len()),
+                        $builder.write_to_slice(&mut buffer, payload)
+                            .unwrap_err()
+                            .slice_too_small_size()
+                    );
+                }
+            }};
+        }
+
+        let src4 = [192,168,1,1];
+        let dst4 = [192,168,1,2];
+        let src6 = [1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16];
+        let dst6 = [21,22,23,24,25,26,27,28,29,30,31,32,33,34,35,36];
+
+        check!(PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12]).ipv4(src4, dst4, 20).udp(21, 1234));
+        check!(PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12]).single_vlan(0x123).ipv6(src6, dst6, 20).udp(21, 1234));
+        check!(PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12]).double_vlan(0x123, 0x234).ipv4(src4, dst4, 20).udp(21, 1234));
+        check!(
+            PacketBuilder::ipv4(src4, dst4, 20)
+                .tcp(tcp.source_port, tcp.destination_port, tcp.sequence_number, tcp.window_size)
+                .options_raw(tcp.options()).unwrap()
+        );
+        check!(PacketBuilder::ipv4(src4, dst4, 20).icmpv4_echo_request(1, 2));
+        check!(PacketBuilder::ipv6(src6, dst6, 20).icmpv6_echo_request(1, 2));
+        check!(PacketBuilder::ipv4(src4, dst4, 20).igmp(IgmpType::MembershipReportV2{ group_address: [239,1,2,3] }));
+        check!(PacketBuilder::ipv4(src4, dst4, 20).sctp(1, 2, 3));
+        check!(PacketBuilder::ipv6(src6, dst6, 20).udp_lite(1, 2, 0));
+        check!(PacketBuilder::ipv4(src4, dst4, 20).dccp(1, 2, DccpSequenceNumber::Short(1), DccpPacketType::Request{ service_code: 3 }));
+
+        // ip step with a custom next header number
+        {
+            let expected = {
+                let mut buffer = Vec::new();
+                PacketBuilder::ipv6(src6, dst6, 20).write(&mut buffer, ip_number::IPV4, payload).unwrap();
+                buffer
+            };
+            let mut buffer = vec![0u8;expected.len()];
+            assert_eq!(
+                expected.len(),
+                PacketBuilder::ipv6(src6, dst6, 20)
+                    .write_to_slice(&mut buffer, ip_number::IPV4, payload)
+                    .unwrap()
+            );
+            assert_eq!(expected, buffer);
+            assert_eq!(
+                Some(expected.len()),
+                PacketBuilder::ipv6(src6, dst6, 20)
+                    .write_to_slice(&mut buffer[..expected.len() - 1], ip_number::IPV4, payload)
+                    .unwrap_err()
+                    .slice_too_small_size()
+            );
+        }
+    }
+}
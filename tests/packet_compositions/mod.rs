@@ -59,8 +59,12 @@ impl ComponentTest {
         match &self.transport {
             Some(TransportHeader::Icmpv6(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Icmpv4(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Igmp(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Sctp(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Udp(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Tcp(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::UdpLite(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Dccp(header)) => header.write(&mut buffer).unwrap(),
             None => {}
         }
         use std::io::Write;
@@ -76,6 +80,8 @@ impl ComponentTest {
     /// * `SlicedPacket::from_ip`
     /// * `PacketHeaders::from_ethernet_slice`
     /// * `PacketHeaders::from_ip_slice`
+    ///
+    /// and their lax counterparts in `LaxSlicedPacket` & `LaxPacketHeaders`.
     fn run(&self) {
         //packet with ethernet2 & vlan headers
         {
@@ -92,6 +98,12 @@ impl ComponentTest {
                 SlicedPacket::from_ethernet(&buffer).unwrap()
             );
 
+            // lax versions
+            self.assert_lax_complete(
+                LaxPacketHeaders::from_ethernet(&buffer),
+                LaxSlicedPacket::from_ethernet(&buffer)
+            );
+
             // create unexpected end of slice errors for the different headers
             for len in self.invalid_ser_lengths() {
                 if let Some(len) = len {
@@ -103,7 +115,12 @@ impl ComponentTest {
                         SlicedPacket::from_ethernet(&buffer[..len]),
                         Err(_)
                     );
-                    
+                    Self::assert_lax_err(
+                        PacketHeaders::from_ethernet_slice(&buffer[..len]),
+                        LaxPacketHeaders::from_ethernet(&buffer[..len]),
+                        SlicedPacket::from_ethernet(&buffer[..len]),
+                        LaxSlicedPacket::from_ethernet(&buffer[..len])
+                    );
                 }
             }
         }
@@ -136,6 +153,12 @@ impl ComponentTest {
                 ).unwrap()
             );
 
+            // lax versions
+            ether_down.assert_lax_complete(
+                LaxPacketHeaders::from_ether_type(self.link.as_ref().unwrap().ether_type, &buffer),
+                LaxSlicedPacket::from_ether_type(self.link.as_ref().unwrap().ether_type, &buffer)
+            );
+
             // create unexpected end of slice errors for the different headers
             for len in ether_down.invalid_ser_lengths() {
                 if let Some(len) = len {
@@ -153,6 +176,13 @@ impl ComponentTest {
                         ),
                         Err(_)
                     );
+                    let ether_type = self.link.as_ref().unwrap().ether_type;
+                    Self::assert_lax_err(
+                        PacketHeaders::from_ether_type(ether_type, &buffer[..len]),
+                        LaxPacketHeaders::from_ether_type(ether_type, &buffer[..len]),
+                        SlicedPacket::from_ether_type(ether_type, &buffer[..len]),
+                        LaxSlicedPacket::from_ether_type(ether_type, &buffer[..len])
+                    );
                 }
             }
         }
@@ -181,6 +211,12 @@ impl ComponentTest {
                 SlicedPacket::from_ip(&buffer).unwrap()
             );
 
+            // lax versions
+            ip_down.assert_lax_complete(
+                LaxPacketHeaders::from_ip(&buffer),
+                LaxSlicedPacket::from_ip(&buffer)
+            );
+
             // create unexpected end of slice errors for the different headers
             for len in ip_down.invalid_ser_lengths() {
                 if let Some(len) = len {
@@ -192,6 +228,12 @@ impl ComponentTest {
                         SlicedPacket::from_ip(&buffer[..len]),
                         Err(_)
                     );
+                    Self::assert_lax_err(
+                        PacketHeaders::from_ip_slice(&buffer[..len]),
+                        LaxPacketHeaders::from_ip(&buffer[..len]),
+                        SlicedPacket::from_ip(&buffer[..len]),
+                        LaxSlicedPacket::from_ip(&buffer[..len])
+                    );
                 }
             }
         }
@@ -273,6 +315,40 @@ impl ComponentTest {
         builder.result
     }
 
+    /// Checks that the lax results of a complete packet contain no error
+    /// and the same headers as the strict versions.
+    fn assert_lax_complete(&self, headers: LaxPacketHeaders, sliced: LaxSlicedPacket) {
+        assert!(headers.stop_err.is_none());
+        self.assert_headers(headers.headers);
+        assert!(sliced.stop_err.is_none());
+        self.assert_sliced_packet(sliced.packet);
+    }
+
+    /// Checks that the lax results of a truncated packet contain the same
+    /// error as the strict versions & that both stop at the same header.
+    fn assert_lax_err(
+        headers: Result<PacketHeaders, PacketReadError>,
+        lax_headers: LaxPacketHeaders,
+        sliced: Result<SlicedPacket, PacketReadError>,
+        lax_sliced: LaxSlicedPacket
+    ) {
+        // strict versions return the same error & context as the lax versions
+        let (err, headers_context) = lax_headers.stop_err.unwrap();
+        let headers_err = headers.unwrap_err();
+        assert_eq!(format!("{:?}", headers_err.error), format!("{:?}", err));
+        assert_eq!(headers_context, headers_err.context);
+        let (err, sliced_context) = lax_sliced.stop_err.unwrap();
+        let sliced_err = sliced.unwrap_err();
+        assert_eq!(format!("{:?}", sliced_err.error), format!("{:?}", err));
+        assert_eq!(sliced_context, sliced_err.context);
+
+        // both lax versions stop at the same header
+        assert_eq!(headers_context, sliced_context);
+        assert_eq!(lax_headers.headers.payload, lax_sliced.packet.payload);
+        assert_eq!(lax_headers.headers.link, lax_sliced.packet.link.map(|ref x| x.to_header()));
+        assert_eq!(lax_headers.headers.vlan, lax_sliced.packet.vlan.map(|ref x| x.to_header()));
+    }
+
     fn assert_headers(&self, actual: PacketHeaders) {
         assert_eq!(self.link, actual.link);
         assert_eq!(self.vlan, actual.vlan);
@@ -325,8 +401,12 @@ impl ComponentTest {
             match result.transport.as_ref() {
                 Some(TransportSlice::Icmpv4(actual)) => Some(TransportHeader::Icmpv4(actual.header())),
                 Some(TransportSlice::Icmpv6(actual)) => Some(TransportHeader::Icmpv6(actual.header())),
+                Some(TransportSlice::Igmp(actual)) => Some(TransportHeader::Igmp(actual.header())),
+                Some(TransportSlice::Sctp(actual)) => Some(TransportHeader::Sctp(actual.to_header())),
                 Some(TransportSlice::Udp(actual)) => Some(TransportHeader::Udp(actual.to_header())),
                 Some(TransportSlice::Tcp(actual)) => Some(TransportHeader::Tcp(actual.to_header())),
+                Some(TransportSlice::UdpLite(actual)) => Some(TransportHeader::UdpLite(actual.to_header())),
+                Some(TransportSlice::Dccp(actual)) => Some(TransportHeader::Dccp(actual.to_header())),
                 Some(TransportSlice::Unknown(_)) => None,
                 None => None
             }
@@ -351,6 +431,10 @@ impl ComponentTest {
                 assert_eq!(&self.payload[..], icmpv6.payload());
                 assert_eq!(0, result.payload.len());
             }
+            Some(TransportSlice::Igmp(igmp)) => {
+                assert_eq!(&self.payload[..], igmp.payload());
+                assert_eq!(0, result.payload.len());
+            }
             // for other cases
             _ => assert_eq!(&self.payload[..], &result.payload[..])
         }
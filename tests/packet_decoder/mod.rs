@@ -136,4 +136,131 @@ mod packet_headers {
             );
         }
     }
-}
\ No newline at end of file
+}
+mod lax_packet_headers {
+    use super::*;
+
+    #[test]
+    fn debug() {
+        let lax = LaxPacketHeaders::from_ip(&[]);
+        assert_eq!(
+            format!("{:?}", lax),
+            format!(
+                "LaxPacketHeaders {{ headers: {:?}, stop_err: {:?} }}",
+                lax.headers,
+                lax.stop_err
+            )
+        );
+    }
+
+    #[test]
+    fn from_ethernet() {
+        let mut data = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .double_vlan(0x123, 0x234)
+            .ipv6([1;16], [2;16], 20)
+            .udp(1, 2)
+            .write(&mut data, &[])
+            .unwrap();
+
+        // complete packet
+        {
+            let lax = LaxPacketHeaders::from_ethernet(&data);
+            assert!(lax.stop_err.is_none());
+            assert_eq!(PacketHeaders::from_ethernet_slice(&data).unwrap(), lax.headers);
+        }
+
+        // truncated udp header
+        {
+            let udp_start = 14 + 8 + 40;
+            let lax = LaxPacketHeaders::from_ethernet(&data[..data.len() - 1]);
+            assert!(lax.headers.link.is_some());
+            assert!(lax.headers.vlan.is_some());
+            assert!(lax.headers.ip.is_some());
+            assert!(lax.headers.transport.is_none());
+            assert_eq!(&data[udp_start..data.len() - 1], lax.headers.payload);
+            let (err, context) = lax.stop_err.unwrap();
+            // lengths in the error are relative to the start of the packet
+            assert_matches!(err, ReadError::UnexpectedEndOfSlice(value) if value == udp_start + 8);
+            assert_eq!(
+                ReadErrorContext{
+                    layer: Layer::UdpHeader,
+                    offset: udp_start,
+                    required_len: Some(8),
+                    available_len: 7,
+                    field: None,
+                },
+                context
+            );
+
+            // same error & context as when slicing & in strict mode
+            let sliced = LaxSlicedPacket::from_ethernet(&data[..data.len() - 1]);
+            let (sliced_err, sliced_context) = sliced.stop_err.unwrap();
+            assert_matches!(sliced_err, ReadError::UnexpectedEndOfSlice(value) if value == udp_start + 8);
+            assert_eq!(context, sliced_context);
+            let strict = PacketHeaders::from_ethernet_slice(&data[..data.len() - 1]).unwrap_err();
+            assert_matches!(strict.error, ReadError::UnexpectedEndOfSlice(value) if value == udp_start + 8);
+            assert_eq!(context, strict.context);
+        }
+
+        // truncated inner vlan header
+        {
+            let lax = LaxPacketHeaders::from_ethernet(&data[..14 + 7]);
+            assert!(lax.headers.link.is_some());
+            assert!(lax.headers.vlan.is_none());
+            assert_eq!(&data[14..14 + 7], lax.headers.payload);
+            let (err, context) = lax.stop_err.unwrap();
+            assert_matches!(err, ReadError::UnexpectedEndOfSlice(22));
+            assert_eq!(
+                ReadErrorContext{
+                    layer: Layer::VlanHeader,
+                    offset: 14,
+                    required_len: Some(8),
+                    available_len: 7,
+                    field: None,
+                },
+                context
+            );
+        }
+    }
+
+    #[test]
+    fn from_ether_type() {
+        let mut data = Vec::new();
+        Ipv4Header::new(0, 20, ip_number::AUTH, [1,2,3,4], [5,6,7,8])
+            .write(&mut data)
+            .unwrap();
+
+        // ip header is kept if the extension headers are truncated
+        let lax = LaxPacketHeaders::from_ether_type(ether_type::IPV4, &data);
+        assert_eq!(
+            Some(IpHeader::Version4(Ipv4Header::from_slice(&data).unwrap().0, Default::default())),
+            lax.headers.ip
+        );
+        assert!(lax.headers.payload.is_empty());
+        assert_matches!(
+            lax.stop_err,
+            Some((ReadError::UnexpectedEndOfSlice(_), ReadErrorContext{ layer: Layer::Ipv4Extensions, .. }))
+        );
+    }
+
+    #[test]
+    fn from_ip() {
+        // empty slice
+        assert_matches!(
+            LaxPacketHeaders::from_ip(&[]).stop_err,
+            Some((ReadError::UnexpectedEndOfSlice(1), ReadErrorContext{ layer: Layer::IpHeader, .. }))
+        );
+
+        // unsupported version
+        {
+            let lax = LaxPacketHeaders::from_ip(&[0x50, 0, 0]);
+            assert!(lax.headers.ip.is_none());
+            assert_eq!(&[0x50, 0, 0], lax.headers.payload);
+            assert_matches!(
+                lax.stop_err,
+                Some((ReadError::IpUnsupportedVersion(5), ReadErrorContext{ layer: Layer::IpHeader, .. }))
+            );
+        }
+    }
+}
@@ -367,6 +367,14 @@ impl PacketFilterTest {
                     header.write(&mut transport_data).unwrap();
                     Some(TransportSlice::Icmpv6(Icmpv6Slice::from_slice(&transport_data[..]).unwrap()))
                 },
+                Some(TransportHeader::Igmp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Igmp(IgmpSlice::from_slice(&transport_data[..]).unwrap()))
+                },
+                Some(TransportHeader::Sctp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Sctp(SctpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
                 Some(TransportHeader::Udp(header)) => {
                     header.write(&mut transport_data).unwrap();
                     Some(TransportSlice::Udp(UdpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
@@ -375,12 +383,29 @@ impl PacketFilterTest {
                     header.write(&mut transport_data).unwrap();
                     Some(TransportSlice::Tcp(TcpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
                 },
+                Some(TransportHeader::UdpLite(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::UdpLite(UdpLiteHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
+                Some(TransportHeader::Dccp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Dccp(DccpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
                 None => None
             },
             payload: &payload[..]
         };
 
         assert_eq!(expected_result, self.filter.applies_to_slice(&slice));
+
+        let headers = PacketHeaders {
+            link: self.link.clone(),
+            vlan: self.vlan.clone(),
+            ip: self.ip.clone(),
+            transport: self.transport.clone(),
+            payload: &payload[..]
+        };
+        assert_eq!(expected_result, self.filter.applies_to_headers(&headers));
     }
 }
 ///Test that all known packet compositions are parsed correctly.
@@ -725,3 +750,394 @@ fn type_derives() {
         destination_port: None
     });
 }
+
+mod filter_expression {
+    use super::*;
+
+    fn ipv4_tcp_packet() -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(0x123)
+            .ipv4([10,0,1,2], [192,168,1,2], 20)
+            .tcp(1234, 443, 1, 4000)
+            .syn()
+            .ack(2);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        // set the pcp of the vlan header to 5
+        packet[14] |= 5 << 5;
+        // set the dscp of the ipv4 header to 46
+        packet[18 + 1] = 46 << 2;
+        packet
+    }
+
+    fn ipv6_icmp_packet() -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6(
+                [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+                [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2],
+                20
+            )
+            .icmpv6_echo_request(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        packet
+    }
+
+    #[test]
+    fn cidr() {
+        let net = Ipv4Cidr{ address: [10,0,0,0], prefix_len: 8 };
+        assert!(net.contains([10,255,1,2]));
+        assert_eq!(false, net.contains([11,0,0,0]));
+        assert!(Ipv4Cidr{ address: [1,2,3,4], prefix_len: 0 }.contains([5,6,7,8]));
+        assert!(Ipv4Cidr{ address: [1,2,3,4], prefix_len: 40 }.contains([1,2,3,4]));
+        assert_eq!(false, Ipv4Cidr{ address: [1,2,3,4], prefix_len: 32 }.contains([1,2,3,5]));
+        assert!(Ipv4Cidr{ address: [1,2,3,4], prefix_len: 31 }.contains([1,2,3,5]));
+
+        let net = Ipv6Cidr{ address: [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,0], prefix_len: 10 };
+        assert!(net.contains([0xfe,0xbf,1,0,0,0,0,0,0,0,0,0,0,0,0,1]));
+        assert_eq!(false, net.contains([0xfe,0xc0,0,0,0,0,0,0,0,0,0,0,0,0,0,1]));
+        assert!(Ipv6Cidr{ address: [1;16], prefix_len: 0 }.contains([2;16]));
+        assert!(Ipv6Cidr{ address: [1;16], prefix_len: 200 }.contains([1;16]));
+    }
+
+    #[test]
+    fn port_match() {
+        let range = PortMatch::Range{ min: 10, max: 20 };
+        assert!(range.contains(10));
+        assert!(range.contains(20));
+        assert_eq!(false, range.contains(9));
+        assert_eq!(false, range.contains(21));
+        let reversed = PortMatch::Range{ min: 20, max: 10 };
+        assert!(reversed.contains(10));
+        assert!(reversed.contains(20));
+        assert!(!reversed.contains(9));
+        assert!(!reversed.contains(21));
+        let list = PortMatch::List(vec![80, 443]);
+        assert!(list.contains(443));
+        assert_eq!(false, list.contains(8080));
+    }
+
+    #[test]
+    fn applies_to_slice() {
+        use FilterExpression::*;
+
+        let ipv4_data = ipv4_tcp_packet();
+        let ipv4 = SlicedPacket::from_ethernet(&ipv4_data).unwrap();
+        let ipv6_data = ipv6_icmp_packet();
+        let ipv6 = SlicedPacket::from_ethernet(&ipv6_data).unwrap();
+
+        let ipv4_headers = PacketHeaders::from_ethernet_slice(&ipv4_data).unwrap();
+        let ipv6_headers = PacketHeaders::from_ethernet_slice(&ipv6_data).unwrap();
+
+        // all evaluators have to return the same result
+        let check = |expected_v4: bool, expected_v6: bool, expression: FilterExpression| {
+            assert_eq!(expected_v4, expression.applies_to_slice(&ipv4), "{:?}", expression);
+            assert_eq!(expected_v6, expression.applies_to_slice(&ipv6), "{:?}", expression);
+            assert_eq!(expected_v4, expression.applies_to_headers(&ipv4_headers), "{:?}", expression);
+            assert_eq!(expected_v6, expression.applies_to_headers(&ipv6_headers), "{:?}", expression);
+            assert_eq!(expected_v4, expression.applies_to_ethernet_packet(&ipv4_data), "{:?}", expression);
+            assert_eq!(expected_v6, expression.applies_to_ethernet_packet(&ipv6_data), "{:?}", expression);
+        };
+
+        // layers
+        check(true, true, Filter::default().into());
+        check(false, true, Layers(Filter{ vlan: ElementFilter::No, ..Default::default() }));
+
+        // ipv4 networks
+        let v4net = |direction, address, prefix_len| Ipv4Net{
+            direction,
+            net: Ipv4Cidr{ address, prefix_len },
+        };
+        check(true, false, v4net(Direction::Source, [10,0,0,0], 8));
+        check(false, false, v4net(Direction::Destination, [10,0,0,0], 8));
+        check(true, false, v4net(Direction::SourceOrDestination, [192,168,0,0], 16));
+        check(false, false, v4net(Direction::SourceAndDestination, [192,168,0,0], 16));
+        check(true, false, v4net(Direction::SourceAndDestination, [0,0,0,0], 0));
+
+        // ipv6 networks
+        let v6net = |direction, address, prefix_len| Ipv6Net{
+            direction,
+            net: Ipv6Cidr{ address, prefix_len },
+        };
+        let link_local = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,0];
+        check(false, true, v6net(Direction::Source, link_local, 10));
+        check(false, false, v6net(Direction::Destination, link_local, 10));
+        check(false, true, v6net(Direction::SourceOrDestination, [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,0], 32));
+
+        // ports
+        let port = |direction, ports| Port{ direction, ports };
+        check(true, false, port(Direction::Destination, PortMatch::List(vec![80, 443])));
+        check(false, false, port(Direction::Source, PortMatch::List(vec![80, 443])));
+        check(true, false, port(Direction::Source, PortMatch::Range{ min: 1024, max: 65535 }));
+        check(true, false, port(Direction::SourceOrDestination, PortMatch::Range{ min: 443, max: 443 }));
+        check(false, false, port(Direction::SourceAndDestination, PortMatch::Range{ min: 443, max: 443 }));
+
+        // protocols
+        check(true, false, IpNumber(ip_number::TCP));
+        check(false, true, IpNumber(ip_number::IPV6_ICMP));
+        check(false, false, IpNumber(ip_number::UDP));
+
+        // tcp flags
+        check(true, false, TcpFlags{ mask: TCP_FLAG_SYN, value: TCP_FLAG_SYN });
+        check(true, false, TcpFlags{ mask: TCP_FLAG_SYN | TCP_FLAG_ACK, value: TCP_FLAG_SYN | TCP_FLAG_ACK });
+        check(false, false, TcpFlags{ mask: TCP_FLAG_SYN | TCP_FLAG_ACK, value: TCP_FLAG_SYN });
+        check(true, false, TcpFlags{ mask: TCP_FLAG_RST | TCP_FLAG_FIN | TCP_FLAG_NS, value: 0 });
+
+        // icmp
+        check(false, true, Icmpv6{ icmp_type: Some(icmpv6::TYPE_ECHO_REQUEST), code: Some(0) });
+        check(false, true, Icmpv6{ icmp_type: None, code: None });
+        check(false, false, Icmpv6{ icmp_type: Some(icmpv6::TYPE_ECHO_REPLY), code: None });
+        check(false, false, Icmpv4{ icmp_type: None, code: None });
+
+        // dscp, pcp & payload length
+        check(true, false, Dscp(46));
+        check(false, true, Dscp(0));
+        check(true, false, VlanPcp(5));
+        check(false, false, VlanPcp(0));
+        check(true, false, PayloadLength{ min: 4, max: 4 });
+        check(true, true, PayloadLength{ min: 0, max: 100 });
+        check(false, false, PayloadLength{ min: 5, max: 100 });
+
+        // composition
+        check(true, true, And(Vec::new()));
+        check(false, false, Or(Vec::new()));
+        check(false, true, Not(Box::new(IpNumber(ip_number::TCP))));
+        check(true, true, Or(vec![IpNumber(ip_number::TCP), IpNumber(ip_number::IPV6_ICMP)]));
+        check(
+            true,
+            false,
+            And(vec![
+                IpNumber(ip_number::TCP),
+                Port{ direction: Direction::Destination, ports: PortMatch::List(vec![443]) },
+                Not(Box::new(v4net(Direction::Destination, [10,0,0,0], 8))),
+            ])
+        );
+    }
+
+    #[test]
+    fn ip_number_after_extensions() {
+        use FilterExpression::*;
+
+        // ipv6 with a hop by hop extension header
+        let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+            .udp(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0) + 8);
+        builder.write(&mut packet, &[]).unwrap();
+        packet[6] = ip_number::IPV6_HOP_BY_HOP;
+        packet.splice(40..40, [ip_number::UDP, 0, 0, 0, 0, 0, 0, 0].iter().cloned());
+        packet[5] += 8;
+
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(IpNumber(ip_number::UDP).applies_to_slice(&sliced));
+        assert_eq!(false, IpNumber(ip_number::IPV6_HOP_BY_HOP).applies_to_slice(&sliced));
+
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert!(IpNumber(ip_number::UDP).applies_to_headers(&headers));
+        assert!(!IpNumber(ip_number::IPV6_HOP_BY_HOP).applies_to_headers(&headers));
+    }
+
+    #[test]
+    fn bytes() {
+        use FilterExpression::*;
+
+        let ipv4_data = ipv4_tcp_packet();
+        let ipv4 = SlicedPacket::from_ethernet(&ipv4_data).unwrap();
+        let ipv4_headers = PacketHeaders::from_ethernet_slice(&ipv4_data).unwrap();
+        let ipv6_data = ipv6_icmp_packet();
+        let ipv6 = SlicedPacket::from_ethernet(&ipv6_data).unwrap();
+        let ipv6_headers = PacketHeaders::from_ethernet_slice(&ipv6_data).unwrap();
+
+        let layers = [
+            FilterLayer::Ethernet2,
+            FilterLayer::Ipv4,
+            FilterLayer::Ipv6,
+            FilterLayer::Tcp,
+            FilterLayer::Udp,
+            FilterLayer::Icmpv4,
+            FilterLayer::Icmpv6,
+        ];
+        for layer in layers.iter() {
+            for size in [1u8, 2, 4].iter() {
+                for offset in 0..ipv4_data.len() + 1 {
+                    // skip the ipv4 checksum as it is recalculated when
+                    // serializing the headers (the dscp was modified)
+                    let checksum_offset = match layer {
+                        FilterLayer::Ethernet2 => Some(18 + 10),
+                        FilterLayer::Ipv4 => Some(10),
+                        _ => None,
+                    };
+                    if let Some(checksum_offset) = checksum_offset {
+                        if offset <= checksum_offset + 1 && checksum_offset < offset + usize::from(*size) {
+                            continue;
+                        }
+                    }
+                    for (op, value) in [
+                        (CompareOp::Equal, 0u32),
+                        (CompareOp::Equal, 5),
+                        (CompareOp::Equal, 0x45),
+                        (CompareOp::Equal, 0x1bb),
+                        // matches if the value is present
+                        (CompareOp::NotEqual, u32::MAX),
+                    ].iter() {
+                        let expression = Bytes{
+                            layer: *layer,
+                            offset,
+                            size: *size,
+                            mask: u32::MAX,
+                            op: *op,
+                            value: *value,
+                        };
+                        assert_eq!(
+                            expression.applies_to_slice(&ipv4),
+                            expression.applies_to_headers(&ipv4_headers),
+                            "{:?}", expression
+                        );
+                        assert_eq!(
+                            expression.applies_to_slice(&ipv6),
+                            expression.applies_to_headers(&ipv6_headers),
+                            "{:?}", expression
+                        );
+                    }
+                }
+            }
+        }
+
+        // destination port of the tcp header
+        let port = Bytes{
+            layer: FilterLayer::Tcp,
+            offset: 2,
+            size: 2,
+            mask: u32::MAX,
+            op: CompareOp::Equal,
+            value: 443,
+        };
+        assert!(port.applies_to_headers(&ipv4_headers));
+        assert!(!port.applies_to_headers(&ipv6_headers));
+
+        // first payload byte relative to the ethernet header
+        let payload = Bytes{
+            layer: FilterLayer::Ethernet2,
+            offset: ipv4_data.len() - 4,
+            size: 1,
+            mask: u32::MAX,
+            op: CompareOp::Equal,
+            value: 1,
+        };
+        assert!(payload.applies_to_headers(&ipv4_headers));
+    }
+
+    #[test]
+    fn applies_to_ethernet_packet_errors() {
+        use FilterExpression::*;
+
+        let packet = ipv4_tcp_packet();
+        let header_len = packet.len() - 4;
+        let dscp = Dscp(46);
+        let dst_port = Port{ direction: Direction::Destination, ports: PortMatch::List(vec![443]) };
+        let not_dst_port = Not(Box::new(dst_port.clone()));
+        let vlan_or_port = Or(vec![VlanPcp(5), dst_port.clone()]);
+
+        // truncated tcp header
+        let tcp_cut = &packet[..header_len - 1];
+        assert!(dscp.applies_to_ethernet_packet(tcp_cut));
+        assert!(!dst_port.applies_to_ethernet_packet(tcp_cut));
+        assert!(!not_dst_port.applies_to_ethernet_packet(tcp_cut));
+        assert!(!vlan_or_port.applies_to_ethernet_packet(tcp_cut));
+        assert!(VlanPcp(5).applies_to_ethernet_packet(tcp_cut));
+        assert!(!PayloadLength{ min: 0, max: 100 }.applies_to_ethernet_packet(tcp_cut));
+        assert!(!And(Vec::new()).applies_to_ethernet_packet(&packet[..10]));
+
+        // broken ip header (version 5)
+        let mut broken = packet.clone();
+        broken[18] = 0x55;
+        assert!(VlanPcp(5).applies_to_ethernet_packet(&broken));
+        assert!(!dscp.applies_to_ethernet_packet(&broken));
+        assert!(!Not(Box::new(dscp.clone())).applies_to_ethernet_packet(&broken));
+
+        // complete packet
+        assert!(dst_port.applies_to_ethernet_packet(&packet));
+        assert!(!not_dst_port.applies_to_ethernet_packet(&packet));
+        assert!(PayloadLength{ min: 4, max: 4 }.applies_to_ethernet_packet(&packet));
+    }
+}
+
+mod filter {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn applies_to_headers_and_ethernet_packet(
+            packet in ethernet_packet_any(),
+            filter in filter_any()
+        ) {
+            let expected = SlicedPacket::from_ethernet(&packet)
+                .map(|sliced| filter.applies_to_slice(&sliced));
+
+            // lazy evaluation
+            if let Ok(expected) = expected {
+                prop_assert_eq!(expected, filter.applies_to_ethernet_packet(&packet));
+            }
+
+            // decoded headers (unknown transport protocols are only
+            // present in sliced packets)
+            if let (Ok(expected), Ok(headers)) = (&expected, PacketHeaders::from_ethernet_slice(&packet)) {
+                let unknown_transport = headers.ip.is_some() &&
+                    headers.transport.is_none() &&
+                    SlicedPacket::from_ethernet(&packet).unwrap().transport.is_some();
+                if !(unknown_transport && filter.transport == ElementFilter::No) {
+                    prop_assert_eq!(*expected, filter.applies_to_headers(&headers));
+                }
+            }
+
+            // truncated packets only match if the headers required
+            // by the filter are complete
+            for len in 0..packet.len() {
+                if filter.applies_to_ethernet_packet(&packet[..len]) {
+                    prop_assert!(matches!(expected, Ok(true)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn applies_to_ethernet_packet_stops_early() {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(12)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(21, 1234, 1, 4000);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+
+        // broken ip header (version 5)
+        let mut broken = packet.clone();
+        broken[18] = 0x55;
+        assert_matches!(SlicedPacket::from_ethernet(&broken), Err(_));
+
+        let link_only = Filter{
+            link: ElementFilter::Some(LinkFilter::Ethernet2{ source: Some([1,2,3,4,5,6]), destination: None }),
+            vlan: ElementFilter::Some(VlanFilter::Single(Some(12))),
+            ..Default::default()
+        };
+        assert!(link_only.applies_to_ethernet_packet(&packet));
+        assert!(link_only.applies_to_ethernet_packet(&broken));
+        assert!(link_only.applies_to_ethernet_packet(&packet[..18]));
+
+        // non matching link layer
+        let other_link = Filter{
+            link: ElementFilter::Some(LinkFilter::Ethernet2{ source: Some([0;6]), destination: None }),
+            transport: ElementFilter::Some(TransportFilter::Tcp{ source_port: None, destination_port: None }),
+            ..Default::default()
+        };
+        assert_eq!(false, other_link.applies_to_ethernet_packet(&packet));
+
+        // transport layer required
+        let tcp = Filter{
+            transport: ElementFilter::Some(TransportFilter::Tcp{ source_port: Some(21), destination_port: None }),
+            ..Default::default()
+        };
+        assert!(tcp.applies_to_ethernet_packet(&packet));
+        assert_eq!(false, tcp.applies_to_ethernet_packet(&broken));
+        assert_eq!(false, tcp.applies_to_ethernet_packet(&packet[..packet.len() - 1]));
+        assert_eq!(false, tcp.applies_to_ethernet_packet(&packet[..10]));
+    }
+}
@@ -0,0 +1,306 @@
+use super::*;
+use etherparse::packet_format::*;
+
+/// Checks that the summary of the sliced & decoded packet matches the expected value.
+fn assert_summary(expected: &str, packet: &[u8]) {
+    let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+    let headers = PacketHeaders::from_ethernet_slice(packet).unwrap();
+    assert_eq!(expected, sliced.summary().to_string());
+    assert_eq!(expected, headers.summary().to_string());
+    assert_eq!(expected, PacketSummary::from_sliced(&sliced).to_string());
+    assert_eq!(expected, PacketSummary::from_headers(&headers).to_string());
+}
+
+#[test]
+fn summary_tcp() {
+    // syn
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,0,1], [10,0,0,2], 64)
+            .tcp(1234, 80, 1, 64240)
+            .syn();
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_summary(
+            "IP 10.0.0.1.1234 > 10.0.0.2.80: Flags [S], seq 1, win 64240, length 0",
+            &packet
+        );
+    }
+    // syn ack with options
+    {
+        use TcpOptionElement::*;
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,0,2], [10,0,0,1], 64)
+            .tcp(80, 1234, 100, 65160)
+            .syn()
+            .ack(2)
+            .options(&[
+                MaximumSegmentSize(1460),
+                SelectiveAcknowledgementPermitted,
+                Timestamp(1, 2),
+                Noop,
+                WindowScale(7),
+            ]).unwrap();
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_summary(
+            "IP 10.0.0.2.80 > 10.0.0.1.1234: Flags [S.], seq 100, ack 2, win 65160, options [mss 1460,sackOK,TS val 1 ecr 2,nop,wscale 7], length 0",
+            &packet
+        );
+    }
+    // data with push
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([0;16], [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1], 64)
+            .tcp(1234, 80, 10, 500)
+            .psh()
+            .ack(20);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        assert_summary(
+            "IP6 ::.1234 > ::1.80: Flags [P.], seq 10:14, ack 20, win 500, length 4",
+            &packet
+        );
+    }
+    // no flags
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,0,1], [10,0,0,2], 64)
+            .tcp(1234, 80, 1, 10);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_summary(
+            "IP 10.0.0.1.1234 > 10.0.0.2.80: Flags [none], win 10, length 0",
+            &packet
+        );
+    }
+}
+
+#[test]
+fn summary_udp() {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(8));
+    builder.write(&mut packet, &[1,2,3,4,5,6,7,8]).unwrap();
+    assert_summary(
+        "IP 192.168.1.1.21 > 192.168.1.2.1234: UDP, length 8",
+        &packet
+    );
+}
+
+#[test]
+fn summary_icmp() {
+    // icmpv4
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([10,0,0,1], [10,0,0,2], 64)
+            .icmpv4_echo_request(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(4));
+        builder.write(&mut packet, &[1,2,3,4]).unwrap();
+        assert_summary(
+            "IP 10.0.0.1 > 10.0.0.2: ICMP echo request, id 1, seq 2, length 12",
+            &packet
+        );
+    }
+    // icmpv6
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([0;16], [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1], 64)
+            .icmpv6_echo_request(3, 4);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_summary(
+            "IP6 :: > ::1: ICMP6, echo request, id 3, seq 4, length 8",
+            &packet
+        );
+    }
+}
+
+#[test]
+fn summary_igmp() {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([10,0,0,1], [224,0,0,1], 1)
+        .igmp(IgmpType::MembershipReportV2{ group_address: [239,1,2,3] });
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    assert_summary(
+        "IP 10.0.0.1 > 224.0.0.1: IGMP v2 report 239.1.2.3, length 8",
+        &packet
+    );
+}
+
+#[test]
+fn summary_unknown_ip_payload() {
+    // unknown protocol
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ip(IpHeader::Version4(
+                Ipv4Header::new(0, 64, 47, [10,0,0,1], [10,0,0,2]),
+                Default::default()
+            ));
+        let mut packet = Vec::with_capacity(builder.size(3));
+        builder.write(&mut packet, 47, &[1,2,3]).unwrap();
+        assert_summary(
+            "IP 10.0.0.1 > 10.0.0.2: GRE, length 3",
+            &packet
+        );
+    }
+    // fragment
+    {
+        let mut ip = Ipv4Header::new(0, 64, ip_number::UDP, [10,0,0,1], [10,0,0,2]);
+        ip.identification = 1234;
+        ip.fragments_offset = 2;
+        ip.more_fragments = true;
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ip(IpHeader::Version4(ip, Default::default()));
+        let mut packet = Vec::with_capacity(builder.size(8));
+        builder.write(&mut packet, ip_number::UDP, &[0;8]).unwrap();
+        assert_summary(
+            "IP 10.0.0.1 > 10.0.0.2: UDP, length 8 (frag 1234:8@16+)",
+            &packet
+        );
+    }
+}
+
+#[test]
+fn summary_link() {
+    // ethernet only
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [0xff;6],
+            ether_type: ether_type::ARP,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[0;28]);
+        assert_summary(
+            "01:02:03:04:05:06 > ff:ff:ff:ff:ff:ff, ethertype ARP (0x0806), length 28",
+            &packet
+        );
+    }
+    // with vlan
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [0xff;6],
+            ether_type: ether_type::VLAN_TAGGED_FRAME,
+        }.write(&mut packet).unwrap();
+        SingleVlanHeader{
+            priority_code_point: 3,
+            drop_eligible_indicator: true,
+            vlan_identifier: 10,
+            ether_type: 0x1234,
+        }.write(&mut packet).unwrap();
+        assert_summary(
+            "01:02:03:04:05:06 > ff:ff:ff:ff:ff:ff, ethertype 802.1Q (0x8100), vlan 10, p 3, DEI, ethertype Unknown (0x1234), length 0",
+            &packet
+        );
+    }
+}
+
+#[test]
+fn dissection_udp() {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(10)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    let ip = headers.ip.clone().unwrap();
+    let ip_checksum = match ip {
+        IpHeader::Version4(ip, _) => ip.header_checksum,
+        _ => unreachable!(),
+    };
+    let udp_checksum = headers.transport.clone().unwrap().udp().unwrap().checksum;
+
+    let expected = format!(
+"Ethernet II, Src: 01:02:03:04:05:06, Dst: 07:08:09:0a:0b:0c
+    Destination: 07:08:09:0a:0b:0c
+    Source: 01:02:03:04:05:06
+    Type: 802.1Q (0x8100)
+802.1Q Virtual LAN, PRI: 0, DEI: 0, ID: 10
+    Priority: 0
+    DEI: 0
+    ID: 10
+    Type: IPv4 (0x0800)
+Internet Protocol Version 4, Src: 192.168.1.1, Dst: 192.168.1.2
+    Header Length: 20 bytes (5)
+    Differentiated Services Code Point: 0
+    Explicit Congestion Notification: 0
+    Total Length: 32
+    Identification: 0x0000 (0)
+    Don't Fragment: Set
+    More Fragments: Not set
+    Fragment Offset: 0 (0 bytes)
+    Time to Live: 20
+    Protocol: UDP (17)
+    Header Checksum: 0x{:04x}
+    Source Address: 192.168.1.1
+    Destination Address: 192.168.1.2
+User Datagram Protocol, Src Port: 21, Dst Port: 1234
+    Source Port: 21
+    Destination Port: 1234
+    Length: 12
+    Checksum: 0x{:04x}
+Data (4 bytes)
+    Data: 01020304
+",
+        ip_checksum,
+        udp_checksum
+    );
+    assert_eq!(expected, sliced.dissection().to_string());
+    assert_eq!(expected, headers.dissection().to_string());
+}
+
+#[test]
+fn dissection_tcp() {
+    use TcpOptionElement::*;
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6([0;16], [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1], 64)
+        .tcp(1234, 80, 1, 64240)
+        .syn()
+        .ack(5)
+        .options(&[MaximumSegmentSize(1460), Noop, WindowScale(7)]).unwrap();
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+
+    let dissection = SlicedPacket::from_ethernet(&packet).unwrap().dissection().to_string();
+    assert_eq!(
+        dissection,
+        PacketHeaders::from_ethernet_slice(&packet).unwrap().dissection().to_string()
+    );
+    let lines: Vec<&str> = dissection.lines().collect();
+    assert!(lines.contains(&"Internet Protocol Version 6, Src: ::, Dst: ::1"));
+    assert!(lines.contains(&"    Next Header: TCP (6)"));
+    assert!(lines.contains(&"Transmission Control Protocol, Src Port: 1234, Dst Port: 80, Seq: 1, Ack: 5, Len: 0"));
+    assert!(lines.contains(&"    Header Length: 28 bytes (7)"));
+    assert!(lines.contains(&"    Flags: 0x012 (ACK, SYN)"));
+    assert!(lines.contains(&"    Options: (8 bytes)"));
+    assert!(lines.contains(&"        Maximum segment size: 1460 bytes"));
+    assert!(lines.contains(&"        No-Operation (NOP)"));
+    assert!(lines.contains(&"        Window scale: 7 (multiply by 128)"));
+    // no payload
+    assert_eq!(false, dissection.contains("Data"));
+}
+
+#[test]
+fn dissection_data_truncated() {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let payload = [0xab;DISSECTION_MAX_DATA_BYTES + 1];
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+
+    let dissection = SlicedPacket::from_ethernet(&packet).unwrap().dissection().to_string();
+    assert!(dissection.ends_with(&format!(
+        "Data ({} bytes)\n    Data: {}...\n",
+        payload.len(),
+        "ab".repeat(DISSECTION_MAX_DATA_BYTES)
+    )));
+}
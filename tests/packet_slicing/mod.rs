@@ -171,7 +171,7 @@ mod sliced_packet {
         //slice length error
         assert_matches!(
             SlicedPacket::from_ip(&[]),
-            Err(UnexpectedEndOfSlice(1))
+            Err(PacketReadError{ error: UnexpectedEndOfSlice(1), .. })
         );
 
         //bad protocol number
@@ -181,12 +181,118 @@ mod sliced_packet {
             {
                 assert_matches!(
                     SlicedPacket::from_ip(&[i]),
-                    Err(IpUnsupportedVersion(_))
+                    Err(PacketReadError{ error: IpUnsupportedVersion(_), .. })
                 );
             }
         }
     }
 
+    #[test]
+    fn from_link_type() {
+        use crate::ReadError::*;
+
+        let mut ip = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1, 2)
+            .write(&mut ip, &[1,2,3])
+            .unwrap();
+        let mut ipv6 = Vec::new();
+        PacketBuilder::ipv6([1;16], [2;16], 20)
+            .udp(1, 2)
+            .write(&mut ipv6, &[1,2,3])
+            .unwrap();
+        let with_prefix = |prefix: &[u8], packet: &[u8]| {
+            let mut result = prefix.to_vec();
+            result.extend_from_slice(packet);
+            result
+        };
+        let check_ip = |link_type: u16, data: &[u8], payload: &[u8]| {
+            let sliced = SlicedPacket::from_link_type(link_type, data).unwrap();
+            assert_eq!(None, sliced.link);
+            assert!(sliced.ip.is_some());
+            assert_eq!(payload, sliced.payload);
+        };
+
+        // ethernet
+        {
+            let mut packet = Vec::new();
+            PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(1, 2)
+                .write(&mut packet, &[1,2,3])
+                .unwrap();
+            assert_eq!(
+                SlicedPacket::from_ethernet(&packet).unwrap(),
+                SlicedPacket::from_link_type(link_type::ETHERNET, &packet).unwrap()
+            );
+        }
+
+        // raw ip
+        check_ip(link_type::RAW, &ip, &[1,2,3]);
+        check_ip(link_type::RAW, &ipv6, &[1,2,3]);
+        check_ip(link_type::IPV4, &ip, &[1,2,3]);
+        check_ip(link_type::IPV6, &ipv6, &[1,2,3]);
+        assert_matches!(
+            SlicedPacket::from_link_type(link_type::IPV4, &ipv6),
+            Err(PacketReadError{ error: Ipv4UnexpectedVersion(6), .. })
+        );
+
+        // loopback (both byte orders for null)
+        check_ip(link_type::NULL, &with_prefix(&[2,0,0,0], &ip), &[1,2,3]);
+        check_ip(link_type::NULL, &with_prefix(&[0,0,0,2], &ip), &[1,2,3]);
+        check_ip(link_type::NULL, &with_prefix(&[30,0,0,0], &ipv6), &[1,2,3]);
+        check_ip(link_type::LOOP, &with_prefix(&[0,0,0,2], &ip), &[1,2,3]);
+        check_ip(link_type::LOOP, &with_prefix(&[0,0,0,24], &ipv6), &[1,2,3]);
+        {
+            let sliced = SlicedPacket::from_link_type(link_type::LOOP, &[0,0,0,7,1,2]).unwrap();
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&[1,2], sliced.payload);
+        }
+        assert_matches!(
+            SlicedPacket::from_link_type(link_type::NULL, &[2,0,0]),
+            Err(PacketReadError{
+                error: UnexpectedEndOfSlice(4),
+                context: ReadErrorContext{ layer: Layer::LinkHeader, offset: 0, .. },
+            })
+        );
+        // errors contain the offset of the loopback header
+        assert_matches!(
+            SlicedPacket::from_link_type(link_type::NULL, &with_prefix(&[2,0,0,0], &ip[..10])),
+            Err(PacketReadError{
+                error: UnexpectedEndOfSlice(24),
+                context: ReadErrorContext{ layer: Layer::Ipv4Header, offset: 4, .. },
+            })
+        );
+
+        // linux cooked capture
+        {
+            let mut header = [0u8;16];
+            header[14..16].copy_from_slice(&ether_type::IPV6.to_be_bytes());
+            check_ip(link_type::LINUX_SLL, &with_prefix(&header, &ipv6), &[1,2,3]);
+
+            header[14..16].copy_from_slice(&0x1234u16.to_be_bytes());
+            let data = with_prefix(&header, &[5,6]);
+            let sliced = SlicedPacket::from_link_type(link_type::LINUX_SLL, &data).unwrap();
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&[5,6], sliced.payload);
+
+            assert_matches!(
+                SlicedPacket::from_link_type(link_type::LINUX_SLL, &header[..15]),
+                Err(PacketReadError{
+                    error: UnexpectedEndOfSlice(16),
+                    context: ReadErrorContext{ layer: Layer::LinkHeader, offset: 0, .. },
+                })
+            );
+        }
+
+        // unknown link type
+        {
+            let sliced = SlicedPacket::from_link_type(1234, &ip).unwrap();
+            assert_eq!(None, sliced.ip);
+            assert_eq!(&ip[..], sliced.payload);
+        }
+    }
+
     #[test]
     fn debug() {
         let header = SlicedPacket{
@@ -333,3 +439,151 @@ mod sliced_packet {
         }
     }
 }
+
+mod lax_sliced_packet {
+    use super::*;
+
+    #[test]
+    fn debug() {
+        let lax = LaxSlicedPacket::from_ip(&[]);
+        assert_eq!(
+            format!("{:?}", lax),
+            format!(
+                "LaxSlicedPacket {{ packet: {:?}, stop_err: {:?} }}",
+                lax.packet,
+                lax.stop_err
+            )
+        );
+    }
+
+    #[test]
+    fn from_ethernet() {
+        let mut data = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(0x123)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(1, 2, 3, 4)
+            .write(&mut data, &[])
+            .unwrap();
+
+        // complete packet
+        {
+            let lax = LaxSlicedPacket::from_ethernet(&data);
+            assert!(lax.stop_err.is_none());
+            assert_eq!(SlicedPacket::from_ethernet(&data).unwrap(), lax.packet);
+        }
+
+        // truncated tcp header
+        {
+            let tcp_start = 14 + 4 + 20;
+            let lax = LaxSlicedPacket::from_ethernet(&data[..data.len() - 1]);
+            assert!(lax.packet.link.is_some());
+            assert!(lax.packet.vlan.is_some());
+            assert!(lax.packet.ip.is_some());
+            assert!(lax.packet.transport.is_none());
+            assert_eq!(&data[tcp_start..data.len() - 1], lax.packet.payload);
+            let (err, context) = lax.stop_err.unwrap();
+            assert_matches!(err, ReadError::UnexpectedEndOfSlice(58));
+            assert_eq!(
+                ReadErrorContext{
+                    layer: Layer::TcpHeader,
+                    offset: tcp_start,
+                    required_len: Some(20),
+                    available_len: 19,
+                    field: None,
+                },
+                context
+            );
+        }
+
+        // truncated ethernet header
+        {
+            let lax = LaxSlicedPacket::from_ethernet(&data[..13]);
+            assert!(lax.packet.link.is_none());
+            assert_eq!(&data[..13], lax.packet.payload);
+            assert_matches!(
+                lax.stop_err,
+                Some((ReadError::UnexpectedEndOfSlice(14), ReadErrorContext{ layer: Layer::Ethernet2Header, .. }))
+            );
+        }
+    }
+
+    #[test]
+    fn from_ether_type() {
+        let mut data = Vec::new();
+        Ipv4Header::new(0, 20, ip_number::AUTH, [1,2,3,4], [5,6,7,8])
+            .write(&mut data)
+            .unwrap();
+
+        // ip header is kept if the extension headers are truncated
+        let lax = LaxSlicedPacket::from_ether_type(ether_type::IPV4, &data);
+        assert_matches!(
+            lax.packet.ip,
+            Some(InternetSlice::Ipv4(_, ref ext)) if ext.is_empty()
+        );
+        assert!(lax.packet.payload.is_empty());
+        assert_matches!(
+            lax.stop_err,
+            Some((ReadError::UnexpectedEndOfSlice(_), ReadErrorContext{ layer: Layer::Ipv4Extensions, .. }))
+        );
+    }
+
+    #[test]
+    fn from_ip() {
+        // empty slice
+        assert_matches!(
+            LaxSlicedPacket::from_ip(&[]).stop_err,
+            Some((ReadError::UnexpectedEndOfSlice(1), ReadErrorContext{ layer: Layer::IpHeader, .. }))
+        );
+
+        // unsupported version
+        {
+            let lax = LaxSlicedPacket::from_ip(&[0x50, 0, 0]);
+            assert!(lax.packet.ip.is_none());
+            assert_eq!(&[0x50, 0, 0], lax.packet.payload);
+            let (err, context) = lax.stop_err.unwrap();
+            assert_matches!(err, ReadError::IpUnsupportedVersion(5));
+            assert_eq!(
+                ReadErrorContext{
+                    layer: Layer::IpHeader,
+                    offset: 0,
+                    required_len: None,
+                    available_len: 3,
+                    field: Some(ErrorField::IpVersion),
+                },
+                context
+            );
+        }
+    }
+
+    #[test]
+    fn icmp_original_datagram() {
+        // packet that triggered the icmp error
+        let mut invoking = Vec::new();
+        PacketBuilder::ipv6([1;16], [2;16], 1)
+            .udp(1234, 53)
+            .write(&mut invoking, &[0;20])
+            .unwrap();
+
+        // icmpv6 error message containing the truncated packet
+        let mut data = Vec::new();
+        PacketBuilder::ipv6([3;16], [1;16], 20)
+            .icmpv6(Icmpv6Type::TimeExceeded(icmpv6::TimeExceededCode::HopLimitExceeded))
+            .write(&mut data, &invoking[..Ipv6Header::SERIALIZED_SIZE + 4])
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ip(&data).unwrap();
+        let icmp = match sliced.transport {
+            Some(TransportSlice::Icmpv6(icmp)) => icmp,
+            _ => panic!(),
+        };
+        let embedded = LaxSlicedPacket::from_ip(icmp.payload());
+        assert_matches!(embedded.packet.ip, Some(InternetSlice::Ipv6(_, _)));
+        assert!(embedded.packet.transport.is_none());
+        assert_eq!(&[0x04, 0xd2, 0, 53], embedded.packet.payload);
+        assert_matches!(
+            embedded.stop_err,
+            Some((ReadError::UnexpectedEndOfSlice(_), ReadErrorContext{ layer: Layer::UdpHeader, .. }))
+        );
+    }
+}
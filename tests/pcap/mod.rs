@@ -0,0 +1,298 @@
+use super::*;
+use etherparse::pcap::*;
+use std::time::Duration;
+
+fn byte_order() -> impl Strategy<Value = PcapByteOrder> {
+    prop_oneof![
+        Just(PcapByteOrder::LittleEndian),
+        Just(PcapByteOrder::BigEndian),
+    ]
+}
+
+fn precision() -> impl Strategy<Value = PcapTimestampPrecision> {
+    prop_oneof![
+        Just(PcapTimestampPrecision::Microseconds),
+        Just(PcapTimestampPrecision::Nanoseconds),
+    ]
+}
+
+prop_compose! {
+    fn pcap_header_any()(
+        byte_order in byte_order(),
+        precision in precision(),
+        version_major in any::<u16>(),
+        version_minor in any::<u16>(),
+        snaplen in any::<u32>(),
+        link_type in any::<u16>(),
+        fcs_len in proptest::option::of(0u8..8),
+    ) -> PcapHeader {
+        PcapHeader {
+            byte_order,
+            precision,
+            version_major,
+            version_minor,
+            snaplen,
+            link_type,
+            fcs_len,
+        }
+    }
+}
+
+#[test]
+fn header_known_bytes() {
+    let header = PcapHeader::new(link_type::ETHERNET);
+    let expected = [
+        0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 4, 0, 1, 0, 0, 0,
+    ];
+    assert_eq!(expected, header.to_bytes());
+
+    let header = PcapHeader {
+        byte_order: PcapByteOrder::BigEndian,
+        precision: PcapTimestampPrecision::Nanoseconds,
+        snaplen: 1500,
+        link_type: link_type::RAW,
+        fcs_len: Some(2),
+        ..header
+    };
+    let expected = [
+        0xa1, 0xb2, 0x3c, 0x4d, 0, 2, 0, 4,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0x05, 0xdc, 0x50, 0, 0, 101,
+    ];
+    assert_eq!(expected, header.to_bytes());
+    assert_eq!(header, PcapHeader::from_slice(&expected).unwrap().0);
+}
+
+proptest! {
+    #[test]
+    fn header_read_write(
+        header in pcap_header_any(),
+        rest in proptest::collection::vec(any::<u8>(), 0..8),
+    ) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        assert_eq!(PcapHeader::SERIALIZED_SIZE, buffer.len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..]);
+        buffer.extend_from_slice(&rest);
+
+        // from_slice
+        {
+            let (actual, actual_rest) = PcapHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, actual);
+            assert_eq!(&rest[..], actual_rest);
+        }
+        // read
+        {
+            let mut cursor = io::Cursor::new(&buffer);
+            assert_eq!(header, PcapHeader::read(&mut cursor).unwrap());
+            assert_eq!(PcapHeader::SERIALIZED_SIZE as u64, cursor.position());
+        }
+        // length error
+        for len in 0..PcapHeader::SERIALIZED_SIZE {
+            assert_matches!(
+                PcapHeader::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(PcapHeader::SERIALIZED_SIZE))
+            );
+            assert_matches!(
+                PcapHeader::read(&mut io::Cursor::new(&buffer[..len])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+#[test]
+fn header_unknown_magic_number() {
+    let mut buffer = PcapHeader::new(link_type::ETHERNET).to_bytes();
+    buffer[0..4].copy_from_slice(&[0x0a, 0x0d, 0x0d, 0x0a]);
+    assert_matches!(
+        PcapHeader::from_slice(&buffer),
+        Err(ReadError::PcapUnknownMagicNumber(0x0a0d_0d0a))
+    );
+    assert_matches!(
+        PcapSliceReader::new(&buffer),
+        Err(ReadError::PcapUnknownMagicNumber(0x0a0d_0d0a))
+    );
+    assert_matches!(
+        PcapReader::new(io::Cursor::new(&buffer[..])),
+        Err(ReadError::PcapUnknownMagicNumber(0x0a0d_0d0a))
+    );
+}
+
+proptest! {
+    #[test]
+    fn write_read(
+        header in pcap_header_any(),
+        packets in proptest::collection::vec(
+            (0u64..=u64::from(u32::MAX), 0u32..1_000_000_000, proptest::collection::vec(any::<u8>(), 0..100)),
+            0..5
+        ),
+    ) {
+        let header = PcapHeader {
+            snaplen: header.snaplen % 120,
+            ..header
+        };
+        let packets: Vec<(Duration, Vec<u8>)> = packets
+            .into_iter()
+            .map(|(seconds, nanos, data)| (Duration::new(seconds, nanos), data))
+            .collect();
+
+        let mut writer = PcapWriter::new(Vec::new(), header.clone()).unwrap();
+        assert_eq!(&header, writer.header());
+        for (timestamp, data) in packets.iter() {
+            writer.write_packet(*timestamp, data).unwrap();
+        }
+        writer.flush().unwrap();
+        let file = writer.into_inner();
+
+        let expected: Vec<PcapPacket> = packets
+            .iter()
+            .map(|(timestamp, data)| PcapPacket {
+                timestamp: match header.precision {
+                    PcapTimestampPrecision::Microseconds => Duration::new(
+                        timestamp.as_secs(),
+                        timestamp.subsec_micros() * 1000
+                    ),
+                    PcapTimestampPrecision::Nanoseconds => *timestamp,
+                },
+                original_len: data.len() as u32,
+                link_type: header.link_type,
+                data: if 0 != header.snaplen && data.len() > header.snaplen as usize {
+                    &data[..header.snaplen as usize]
+                } else {
+                    &data[..]
+                },
+            })
+            .collect();
+
+        // slice reader
+        {
+            let reader = PcapSliceReader::new(&file).unwrap();
+            assert_eq!(&header, reader.header());
+            let actual: Vec<PcapPacket> = reader.map(|p| p.unwrap()).collect();
+            assert_eq!(expected, actual);
+        }
+        // io reader
+        {
+            let mut reader = PcapReader::new(io::Cursor::new(&file)).unwrap();
+            assert_eq!(&header, reader.header());
+            for e in expected.iter() {
+                assert_eq!(Some(e), reader.next_packet().unwrap().as_ref());
+            }
+            assert_eq!(None, reader.next_packet().unwrap());
+            assert_eq!(file.len() as u64, reader.into_inner().position());
+        }
+        // truncated
+        for (packet, (_, data)) in expected.iter().zip(packets.iter()) {
+            assert_eq!(packet.data.len() < data.len(), packet.is_truncated());
+        }
+    }
+}
+
+#[test]
+fn write_packet_with_original_len() {
+    let header = PcapHeader {
+        snaplen: 4,
+        ..PcapHeader::new(link_type::RAW)
+    };
+    let mut writer = PcapWriter::new(Vec::new(), header).unwrap();
+    writer.write_packet_with_original_len(Duration::from_secs(1), 100, &[1,2,3]).unwrap();
+    // the original length is never smaller then the data
+    writer.write_packet_with_original_len(Duration::from_secs(2), 0, &[1,2,3]).unwrap();
+    // snaplen is still applied
+    writer.write_packet_with_original_len(Duration::from_secs(3), 10, &[1,2,3,4,5,6]).unwrap();
+    let file = writer.into_inner();
+
+    let actual: Vec<(u32, Vec<u8>)> = PcapSliceReader::new(&file)
+        .unwrap()
+        .map(|p| {
+            let p = p.unwrap();
+            (p.original_len, p.data.to_vec())
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (100, vec![1,2,3]),
+            (3, vec![1,2,3]),
+            (10, vec![1,2,3,4]),
+        ],
+        actual
+    );
+}
+
+#[test]
+fn read_errors() {
+    use ReadError::*;
+
+    let mut writer = PcapWriter::new(Vec::new(), PcapHeader::new(link_type::ETHERNET)).unwrap();
+    writer.write_packet(Duration::from_secs(1), &[1,2,3,4]).unwrap();
+    let file = writer.into_inner();
+    let file_len = file.len();
+    assert_eq!(24 + 16 + 4, file_len);
+
+    // end of file within a packet
+    for len in PcapHeader::SERIALIZED_SIZE + 1..file_len {
+        let data = &file[..len];
+        {
+            let mut reader = PcapSliceReader::new(data).unwrap();
+            if len < PcapHeader::SERIALIZED_SIZE + 16 {
+                assert_matches!(reader.next(), Some(Err(UnexpectedEndOfSlice(40))));
+            } else {
+                assert_matches!(reader.next(), Some(Err(UnexpectedEndOfSlice(44))));
+            }
+            assert_matches!(reader.next(), None);
+        }
+        {
+            let mut reader = PcapReader::new(io::Cursor::new(data)).unwrap();
+            assert_matches!(
+                reader.next_packet(),
+                Err(IoError(ref err)) if io::ErrorKind::UnexpectedEof == err.kind()
+            );
+        }
+    }
+
+    // captured length too big
+    {
+        let mut data = file.clone();
+        data[32..36].copy_from_slice(&(MAX_SNAPLEN + 1).to_le_bytes());
+        let mut reader = PcapSliceReader::new(&data).unwrap();
+        assert_matches!(
+            reader.next(),
+            Some(Err(PcapCapturedLengthTooBig{ captured_len, max_len: MAX_SNAPLEN })) if captured_len == MAX_SNAPLEN + 1
+        );
+        assert_matches!(reader.next(), None);
+
+        let mut reader = PcapReader::new(io::Cursor::new(&data)).unwrap();
+        assert_matches!(reader.next_packet(), Err(PcapCapturedLengthTooBig{ .. }));
+    }
+    // a bigger snaplen allows bigger packets
+    {
+        let mut data = file;
+        data[16..20].copy_from_slice(&(MAX_SNAPLEN + 1).to_le_bytes());
+        data[32..36].copy_from_slice(&(MAX_SNAPLEN + 1).to_le_bytes());
+        let mut reader = PcapSliceReader::new(&data).unwrap();
+        assert_matches!(reader.next(), Some(Err(UnexpectedEndOfSlice(_))));
+    }
+}
+
+#[test]
+fn packet_slice() {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(1, 2)
+        .write(&mut packet, &[1,2,3])
+        .unwrap();
+    let pcap_packet = PcapPacket {
+        timestamp: Duration::from_secs(1),
+        original_len: packet.len() as u32,
+        link_type: link_type::RAW,
+        data: &packet,
+    };
+    assert!(!pcap_packet.is_truncated());
+    assert_eq!(
+        SlicedPacket::from_ip(&packet).unwrap(),
+        pcap_packet.slice().unwrap()
+    );
+}
@@ -0,0 +1,252 @@
+use super::*;
+use etherparse::packet_filter::*;
+use etherparse::pcap_filter::*;
+
+/// Ethernet, vlan (id 100), ipv4 10.0.1.2 > 192.168.1.2, tcp 1234 > 443 (syn & ack)
+fn tcp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(100)
+        .ipv4([10,0,1,2], [192,168,1,2], 20)
+        .tcp(1234, 443, 1, 4000)
+        .syn()
+        .ack(2);
+    let mut packet = Vec::with_capacity(builder.size(4));
+    builder.write(&mut packet, &[1,2,3,4]).unwrap();
+    packet
+}
+
+/// Ethernet, ipv4 192.168.1.1 > 8.8.8.8, udp 5000 > 53
+fn udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [8,8,8,8], 20)
+        .udp(5000, 53);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    packet
+}
+
+/// Ethernet, ipv6 fe80::1 > 2001:db8::2, icmpv6 echo request
+fn icmpv6_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv6(
+            [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1],
+            [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2],
+            20
+        )
+        .icmpv6_echo_request(1, 2);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    packet
+}
+
+/// Checks which of the test packets (tcp, udp, icmpv6) match the expression.
+fn assert_matches(expression: &str, expected: [bool;3]) {
+    let filter = parse(expression).unwrap();
+    let packets = [tcp_packet(), udp_packet(), icmpv6_packet()];
+    for (packet, expected) in packets.iter().zip(expected.iter()) {
+        let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+        assert_eq!(*expected, filter.applies_to_slice(&sliced), "{} {:?}", expression, filter);
+    }
+}
+
+#[test]
+fn primitives() {
+    assert_matches("", [true, true, true]);
+
+    // protocols
+    assert_matches("ip", [true, true, false]);
+    assert_matches("ip6", [false, false, true]);
+    assert_matches("tcp", [true, false, false]);
+    assert_matches("udp", [false, true, false]);
+    assert_matches("icmp6", [false, false, true]);
+    assert_matches("icmp", [false, false, false]);
+    assert_matches("proto 17", [false, true, false]);
+    assert_matches("proto \\udp", [false, true, false]);
+    assert_matches("ip proto tcp", [true, false, false]);
+    assert_matches("ip6 proto 58", [false, false, true]);
+
+    // hosts
+    assert_matches("host 192.168.1.2", [true, false, false]);
+    assert_matches("src host 192.168.1.1", [false, true, false]);
+    assert_matches("dst 8.8.8.8", [false, true, false]);
+    assert_matches("src or dst host 8.8.8.8", [false, true, false]);
+    assert_matches("src and dst host 8.8.8.8", [false, false, false]);
+    assert_matches("host fe80::1", [false, false, true]);
+    assert_matches("ip6 dst host 2001:db8::2", [false, false, true]);
+    assert_matches("tcp host 192.168.1.2", [true, false, false]);
+    assert_matches("ether host 01:02:03:04:05:06", [true, true, true]);
+    assert_matches("ether dst 07-08-09-0a-0b-0c", [true, true, true]);
+    assert_matches("ether src 07-08-09-0a-0b-0c", [false, false, false]);
+    assert_matches("ether src host 01:02:03:04:05:06", [true, true, true]);
+    assert_matches("ether src and dst host 01:02:03:04:05:06", [false, false, false]);
+
+    // networks
+    assert_matches("net 10.0.0.0/8", [true, false, false]);
+    assert_matches("net 192.168", [true, true, false]);
+    assert_matches("src net 192.168.0.0 mask 255.255.0.0", [false, true, false]);
+    assert_matches("dst net 2001:db8::/32", [false, false, true]);
+    assert_matches("ip net 0.0.0.0/0", [true, true, false]);
+
+    // ports
+    assert_matches("port 443", [true, false, false]);
+    assert_matches("dst port 53", [false, true, false]);
+    assert_matches("src port 53", [false, false, false]);
+    assert_matches("tcp port 53", [false, false, false]);
+    assert_matches("udp port 53", [false, true, false]);
+    assert_matches("portrange 1000-5000", [true, true, false]);
+    assert_matches("src portrange 5000-1000", [true, true, false]);
+    assert_matches("portrange 500-400", [true, false, false]);
+    assert_matches("tcp dst portrange 400-500", [true, false, false]);
+
+    // vlan
+    assert_matches("vlan", [true, false, false]);
+    assert_matches("vlan 100", [true, false, false]);
+    assert_matches("vlan 101", [false, false, false]);
+}
+
+#[test]
+fn byte_comparisons() {
+    assert_matches("tcp[13] & 2 != 0", [true, false, false]);
+    assert_matches("tcp[tcpflags] & (tcp-syn) != 0", [true, false, false]);
+    assert_matches("tcp[tcpflags] & tcp-syn != 0", [true, false, false]);
+    assert_matches("tcp[tcpflags] & tcp-fin != 0", [false, false, false]);
+    assert_matches("tcp[tcpflags]&(tcp-syn|tcp-ack)==0x12", [true, false, false]);
+    assert_matches("tcp[2:2] = 443", [true, false, false]);
+    assert_matches("udp[0:2] == 5000", [false, true, false]);
+    assert_matches("ip[0] & 0xf = 5", [true, true, false]);
+    assert_matches("ip[2:2] > 40", [true, false, false]);
+    assert_matches("ip[2:2] <= 28", [false, true, false]);
+    assert_matches("ip[9] = 6", [true, false, false]);
+    assert_matches("ip6[6] == 58", [false, false, true]);
+    assert_matches("icmp6[icmp6type] == icmp6-echo", [false, false, true]);
+    assert_matches("icmp[icmptype] == icmp-echo", [false, false, false]);
+    assert_matches("ether[12:2] = 0x8100", [true, false, false]);
+    assert_matches("ether[12:4] = 0x81000064", [true, false, false]);
+    // access to the payload
+    assert_matches("tcp[20:4] = 0x01020304", [true, false, false]);
+    // out of range
+    assert_matches("tcp[24] >= 0", [false, false, false]);
+}
+
+#[test]
+fn composition() {
+    assert_matches("tcp or udp", [true, true, false]);
+    assert_matches("tcp || udp", [true, true, false]);
+    assert_matches("not tcp", [false, true, true]);
+    assert_matches("! tcp", [false, true, true]);
+    assert_matches("ip and not tcp", [false, true, false]);
+    assert_matches("ip && !tcp", [false, true, false]);
+    assert_matches("tcp port 443 and not net 10.0.0.0/8", [false, false, false]);
+    assert_matches("tcp port 443 and not net 192.168.0.0/16", [false, false, false]);
+    assert_matches("tcp port 443 and not dst net 10.0.0.0/8", [true, false, false]);
+    assert_matches("not (tcp or udp)", [false, false, true]);
+    assert_matches("not not tcp", [true, false, false]);
+
+    // "and" & "or" have the same precedence and are evaluated from left to right
+    assert_matches("icmp6 or tcp and port 53", [false, false, false]);
+    assert_matches("icmp6 or (tcp and port 53)", [false, false, true]);
+    assert_matches("tcp and port 53 or icmp6", [false, false, true]);
+}
+
+#[test]
+fn expression_tree() {
+    use FilterExpression::*;
+    assert_eq!(
+        And(vec![
+            IpNumber(ip_number::TCP),
+            Port{ direction: Direction::Destination, ports: PortMatch::Range{ min: 443, max: 443 } },
+            Not(Box::new(Ipv4Net{
+                direction: Direction::SourceOrDestination,
+                net: Ipv4Cidr{ address: [10,0,0,0], prefix_len: 8 },
+            })),
+        ]),
+        parse("tcp and dst port 443 and not net 10.0.0.0/8").unwrap()
+    );
+    // reversed port ranges are swapped
+    assert_eq!(
+        Port{ direction: Direction::SourceOrDestination, ports: PortMatch::Range{ min: 5, max: 10 } },
+        parse("portrange 10-5").unwrap()
+    );
+    assert_eq!(
+        Bytes{
+            layer: FilterLayer::Tcp,
+            offset: 12,
+            size: 2,
+            mask: 0xf0,
+            op: CompareOp::Greater,
+            value: 0x50,
+        },
+        parse("tcp[12:2] & 0xf0 > 0x50").unwrap()
+    );
+}
+
+#[test]
+fn errors() {
+    use PcapFilterError::*;
+
+    let check = |expression: &str, expected: PcapFilterError| {
+        assert_eq!(Err(expected), parse(expression), "{}", expression);
+    };
+
+    check("tcp and", UnexpectedEnd{ expected: "a filter primitive" });
+    check("(tcp", UnexpectedEnd{ expected: "')'" });
+    check("host", UnexpectedEnd{ expected: "an address" });
+    check("tcp udp", UnexpectedToken{ position: 4, token: "udp".to_string(), expected: "'and', 'or' or the end of the expression" });
+    check("tcp and )", UnexpectedToken{ position: 8, token: ")".to_string(), expected: "a filter primitive" });
+    check("foo", UnexpectedToken{ position: 0, token: "foo".to_string(), expected: "a filter primitive" });
+    check("ether", UnexpectedEnd{ expected: "'host', 'src' or 'dst'" });
+    check("ether net 10.0.0.0/8", UnexpectedToken{ position: 6, token: "net".to_string(), expected: "'host' after 'ether'" });
+    check("icmp port 1", UnexpectedToken{ position: 0, token: "icmp".to_string(), expected: "'tcp', 'udp' or 'sctp' in front of a port" });
+    check("tcp[13 = 2", UnexpectedToken{ position: 7, token: "=".to_string(), expected: "']'" });
+    check("tcp[13] 2", UnexpectedToken{ position: 8, token: "2".to_string(), expected: "a comparison operator" });
+    check("port 80 + 1", UnexpectedCharacter{ position: 8, character: '+' });
+    check("arp", Unsupported{ position: 0, keyword: "arp".to_string() });
+    check("tcp and less 100", Unsupported{ position: 8, keyword: "less".to_string() });
+    check("ether proto 0x800", Unsupported{ position: 6, keyword: "proto".to_string() });
+    check("ether broadcast", Unsupported{ position: 6, keyword: "broadcast".to_string() });
+    check("sctp[0] = 1", Unsupported{ position: 0, keyword: "sctp[]".to_string() });
+    check("host example.com", InvalidValue{ position: 5, value: "example.com".to_string(), reason: "not an ip address (host names are not supported)" });
+    check("port http", InvalidValue{ position: 5, value: "http".to_string(), reason: "not a port number (service names are not supported)" });
+    check("port 65536", InvalidValue{ position: 5, value: "65536".to_string(), reason: "not a port number (service names are not supported)" });
+    check("portrange 10", InvalidValue{ position: 10, value: "10".to_string(), reason: "port ranges have the format <start>-<end>" });
+    check("net 10.0.0.0/33", InvalidValue{ position: 4, value: "10.0.0.0/33".to_string(), reason: "ipv4 prefix lengths can be at most 32" });
+    check("net fe80::/129", InvalidValue{ position: 4, value: "fe80::/129".to_string(), reason: "ipv6 prefix lengths can be at most 128" });
+    check("net 1.2.3.4.5", InvalidValue{ position: 4, value: "1.2.3.4.5".to_string(), reason: "not an ipv4 address" });
+    check("net 10.0.0.0 mask 255.0.255.0", InvalidValue{ position: 18, value: "255.0.255.0".to_string(), reason: "non contiguous masks are not supported" });
+    check("ip6 host 1.2.3.4", InvalidValue{ position: 9, value: "1.2.3.4".to_string(), reason: "address family does not match the protocol" });
+    check("ip host ::1", InvalidValue{ position: 8, value: "::1".to_string(), reason: "address family does not match the protocol" });
+    check("ether host 1:2:3", InvalidValue{ position: 11, value: "1:2:3".to_string(), reason: "not an ethernet address" });
+    check("vlan 4096", InvalidValue{ position: 5, value: "4096".to_string(), reason: "vlan identifiers have to be smaller then 4096" });
+    check("proto 256", InvalidValue{ position: 6, value: "256".to_string(), reason: "protocol numbers have to be smaller then 256" });
+    check("tcp[13:3] = 1", InvalidValue{ position: 4, value: "13:3".to_string(), reason: "the size has to be 1, 2 or 4" });
+    check("tcp[13] & (tcp-syn tcp-ack) != 0", UnexpectedToken{ position: 19, token: "tcp-ack".to_string(), expected: "'|' or ')'" });
+    check("tcp[13] = 0xzz", InvalidValue{ position: 10, value: "0xzz".to_string(), reason: "not a number" });
+}
+
+#[test]
+fn error_display() {
+    use PcapFilterError::*;
+    use std::error::Error;
+
+    assert_eq!(
+        "PcapFilterError: Unexpected end of the filter expression (expected an address).",
+        &format!("{}", UnexpectedEnd{ expected: "an address" })
+    );
+    assert_eq!(
+        "PcapFilterError: Unexpected 'udp' at position 4 (expected a value).",
+        &format!("{}", UnexpectedToken{ position: 4, token: "udp".to_string(), expected: "a value" })
+    );
+    assert_eq!(
+        "PcapFilterError: Unexpected character '+' at position 8.",
+        &format!("{}", UnexpectedCharacter{ position: 8, character: '+' })
+    );
+    assert_eq!(
+        "PcapFilterError: The keyword 'arp' (at position 0) is not supported.",
+        &format!("{}", Unsupported{ position: 0, keyword: "arp".to_string() })
+    );
+    assert_eq!(
+        "PcapFilterError: Invalid value 'x' at position 5 (not a number).",
+        &format!("{}", InvalidValue{ position: 5, value: "x".to_string(), reason: "not a number" })
+    );
+    assert!(UnexpectedEnd{ expected: "" }.source().is_none());
+}
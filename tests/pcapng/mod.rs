@@ -0,0 +1,596 @@
+use super::*;
+use etherparse::pcap::PcapByteOrder;
+use etherparse::pcapng::*;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Serializes a block with the given body (padded to 4 bytes).
+fn block(byte_order: PcapByteOrder, block_type: u32, body: &[u8]) -> Vec<u8> {
+    let u32_to = |value: u32| match byte_order {
+        PcapByteOrder::LittleEndian => value.to_le_bytes(),
+        PcapByteOrder::BigEndian => value.to_be_bytes(),
+    };
+    let padded_len = (body.len() + 3) & !3;
+    let total_len = (12 + padded_len) as u32;
+    let mut result = Vec::new();
+    result.extend_from_slice(&u32_to(block_type));
+    result.extend_from_slice(&u32_to(total_len));
+    result.extend_from_slice(body);
+    result.resize(8 + padded_len, 0);
+    result.extend_from_slice(&u32_to(total_len));
+    result
+}
+
+/// Serializes an option (or name record) in little endian.
+fn option_le(code: u16, value: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&code.to_le_bytes());
+    result.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    result.extend_from_slice(value);
+    result.resize((result.len() + 3) & !3, 0);
+    result
+}
+
+fn section_header_le(options: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+    body.extend_from_slice(&[1,0, 0,0]);
+    body.extend_from_slice(&123u64.to_le_bytes());
+    body.extend_from_slice(options);
+    block(PcapByteOrder::LittleEndian, block_type::SECTION_HEADER, &body)
+}
+
+fn interface_le(link_type: u16, snaplen: u32, options: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&link_type.to_le_bytes());
+    body.extend_from_slice(&[0,0]);
+    body.extend_from_slice(&snaplen.to_le_bytes());
+    body.extend_from_slice(options);
+    block(PcapByteOrder::LittleEndian, block_type::INTERFACE_DESCRIPTION, &body)
+}
+
+fn enhanced_packet_le(interface_id: u32, timestamp: u64, captured_len: u32, original_len: u32, data: &[u8], options: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&interface_id.to_le_bytes());
+    body.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+    body.extend_from_slice(&(timestamp as u32).to_le_bytes());
+    body.extend_from_slice(&captured_len.to_le_bytes());
+    body.extend_from_slice(&original_len.to_le_bytes());
+    body.extend_from_slice(data);
+    body.resize((body.len() + 3) & !3, 0);
+    body.extend_from_slice(options);
+    block(PcapByteOrder::LittleEndian, block_type::ENHANCED_PACKET, &body)
+}
+
+fn udp_packet() -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(1, 2)
+        .write(&mut packet, &[1,2,3])
+        .unwrap();
+    packet
+}
+
+#[test]
+fn timestamp_resolution() {
+    use PcapngTimestampResolution::*;
+    assert_eq!(Decimal(6), PcapngTimestampResolution::default());
+    for value in 0..=255u8 {
+        assert_eq!(value, PcapngTimestampResolution::from_option_value(value).to_option_value());
+    }
+    assert_eq!(Decimal(9), PcapngTimestampResolution::from_option_value(9));
+    assert_eq!(Binary(10), PcapngTimestampResolution::from_option_value(0x8a));
+
+    assert_eq!(Duration::new(1, 500_000), Decimal(6).to_duration(1_000_500));
+    assert_eq!(Duration::new(1, 5), Decimal(9).to_duration(1_000_000_005));
+    assert_eq!(Duration::from_secs(12), Decimal(0).to_duration(12));
+    assert_eq!(Duration::new(1, 1), Decimal(12).to_duration(1_000_000_001_999));
+    assert_eq!(Duration::from_secs(0), Decimal(100).to_duration(u64::MAX));
+    assert_eq!(Duration::new(3, 500_000_000), Binary(1).to_duration(7));
+    assert_eq!(Duration::new(1, 250_000_000), Binary(10).to_duration(1024 + 256));
+    assert_eq!(Duration::from_secs(0), Binary(127).to_duration(u64::MAX));
+}
+
+proptest! {
+    #[test]
+    fn write_read(
+        big_endian in any::<bool>(),
+        packets in proptest::collection::vec(
+            (
+                0u32..2,
+                0u64..=u64::from(u32::MAX),
+                0u32..1_000_000_000,
+                proptest::collection::vec(any::<u8>(), 0..40),
+                proptest::collection::vec("[a-z ]{0,10}", 0..3),
+            ),
+            0..5
+        ),
+    ) {
+        let byte_order = if big_endian { PcapByteOrder::BigEndian } else { PcapByteOrder::LittleEndian };
+
+        let mut writer = PcapngWriter::new(Vec::new(), byte_order).unwrap();
+        assert_eq!(0, writer.add_interface(link_type::ETHERNET, 0).unwrap());
+        assert_eq!(1, writer.add_interface(link_type::RAW, 20).unwrap());
+        assert_eq!(2, writer.interfaces().len());
+        for (interface_id, seconds, nanos, data, comments) in packets.iter() {
+            let comments: Vec<&str> = comments.iter().map(|c| c.as_str()).collect();
+            writer.write_packet_with_comments(*interface_id, Duration::new(*seconds, *nanos), data, &comments).unwrap();
+        }
+        writer.flush().unwrap();
+        let file = writer.into_inner();
+        assert_eq!(0, file.len() % 4);
+
+        let check = |packet: &PcapngPacket, expected: &(u32, u64, u32, Vec<u8>, Vec<String>)| {
+            let (interface_id, seconds, nanos, data, comments) = expected;
+            assert_eq!(*interface_id, packet.interface_id);
+            assert_eq!(Some(Duration::new(*seconds, *nanos)), packet.timestamp);
+            assert_eq!(data.len() as u32, packet.original_len);
+            if 0 == *interface_id {
+                assert_eq!(link_type::ETHERNET, packet.link_type);
+                assert_eq!(&data[..], packet.data);
+            } else {
+                assert_eq!(link_type::RAW, packet.link_type);
+                assert_eq!(&data[..data.len().min(20)], packet.data);
+            }
+            assert_eq!(data.len() > 20 && 1 == *interface_id, packet.is_truncated());
+            assert_eq!(comments, &packet.options.comments().map(|c| c.to_string()).collect::<Vec<_>>());
+        };
+
+        // slice reader
+        {
+            let mut reader = PcapngSliceReader::new(&file).unwrap();
+            for expected in packets.iter() {
+                check(&reader.next_packet().unwrap().unwrap(), expected);
+            }
+            assert!(reader.next_packet().is_none());
+            assert_eq!(
+                &[link_type::ETHERNET, link_type::RAW],
+                &reader.interfaces().iter().map(|i| i.link_type).collect::<Vec<_>>()[..]
+            );
+
+            // blocks
+            let blocks: Vec<PcapngBlock> = PcapngSliceReader::new(&file).unwrap().map(|b| b.unwrap()).collect();
+            assert_eq!(3 + packets.len(), blocks.len());
+            assert_matches!(
+                &blocks[0],
+                PcapngBlock::SectionHeader(PcapngSectionHeader{ byte_order: b, version_major: 1, version_minor: 0, section_length: None, .. }) if *b == byte_order
+            );
+            assert_matches!(&blocks[1], PcapngBlock::InterfaceDescription(PcapngInterfaceDescription{ link_type: link_type::ETHERNET, snaplen: 0, .. }));
+            assert_matches!(&blocks[2], PcapngBlock::InterfaceDescription(PcapngInterfaceDescription{ link_type: link_type::RAW, snaplen: 20, .. }));
+        }
+        // io reader
+        {
+            let mut reader = PcapngReader::new(io::Cursor::new(&file)).unwrap();
+            for expected in packets.iter() {
+                check(&reader.next_packet().unwrap().unwrap(), expected);
+            }
+            assert_eq!(None, reader.next_packet().unwrap());
+            assert_eq!(2, reader.interfaces().len());
+            assert_eq!(file.len() as u64, reader.into_inner().position());
+
+            let mut reader = PcapngReader::new(io::Cursor::new(&file)).unwrap();
+            let mut count = 0;
+            while reader.next_block().unwrap().is_some() {
+                count += 1;
+            }
+            assert_eq!(3 + packets.len(), count);
+        }
+    }
+}
+
+#[test]
+fn read_all_block_types() {
+    let packet = udp_packet();
+    let mut file = Vec::new();
+
+    // section header with options
+    file.extend(section_header_le(&[
+        option_le(option_code::SHB_USERAPPL, b"etherparse"),
+        option_le(option_code::COMMENT, b"section"),
+        option_le(option_code::END_OF_OPT, &[]),
+    ].concat()));
+
+    // interfaces with binary timestamp resolution & timestamp offset
+    file.extend(interface_le(link_type::RAW, 10, &[
+        option_le(option_code::IF_NAME, b"eth0"),
+        option_le(option_code::IF_TSRESOL, &[0x80 | 10]),
+        option_le(option_code::IF_TSOFFSET, &100u64.to_le_bytes()),
+        option_le(option_code::END_OF_OPT, &[]),
+    ].concat()));
+    file.extend(interface_le(link_type::ETHERNET, 0, &[]));
+
+    // simple packet (truncated to the snaplen of the first interface)
+    {
+        let mut body = Vec::new();
+        body.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        body.extend_from_slice(&packet);
+        file.extend(block(PcapByteOrder::LittleEndian, block_type::SIMPLE_PACKET, &body));
+    }
+
+    // name resolution
+    {
+        let mut ipv6 = vec![1u8;16];
+        ipv6.extend_from_slice(b"six\0");
+        let body = [
+            option_le(name_record_type::IPV4, b"\xc0\xa8\x01\x01one\0uno\0"),
+            option_le(name_record_type::IPV6, &ipv6),
+            option_le(1234, &[1,2]),
+            option_le(name_record_type::END, &[]),
+            option_le(option_code::COMMENT, b"names"),
+            option_le(option_code::END_OF_OPT, &[]),
+        ].concat();
+        file.extend(block(PcapByteOrder::LittleEndian, block_type::NAME_RESOLUTION, &body));
+    }
+
+    // interface statistics (unknown)
+    file.extend(block(PcapByteOrder::LittleEndian, block_type::INTERFACE_STATISTICS, &[0,0,0,0, 1,2,3,4, 5,6,7,8]));
+
+    // custom blocks
+    file.extend(block(PcapByteOrder::LittleEndian, block_type::CUSTOM, &[0,0,0,32, 9,8]));
+    file.extend(block(PcapByteOrder::LittleEndian, block_type::CUSTOM_NO_COPY, &[1,0,0,0]));
+
+    // enhanced packet (timestamp: 1.5 seconds in 2^-10 resolution)
+    file.extend(enhanced_packet_le(
+        0, 1024 + 512, packet.len() as u32, 100, &packet,
+        &[option_le(option_code::COMMENT, b"hello"), option_le(option_code::END_OF_OPT, &[])].concat()
+    ));
+
+    // check blocks
+    {
+        use PcapngBlock::*;
+        let blocks: Vec<PcapngBlock> = PcapngSliceReader::new(&file).unwrap().map(|b| b.unwrap()).collect();
+        assert_eq!(9, blocks.len());
+
+        match &blocks[0] {
+            SectionHeader(header) => {
+                assert_eq!(PcapByteOrder::LittleEndian, header.byte_order);
+                assert_eq!((1, 0), (header.version_major, header.version_minor));
+                assert_eq!(Some(123), header.section_length);
+                assert_eq!(Some(&b"etherparse"[..]), header.options.get(option_code::SHB_USERAPPL));
+                assert_eq!(vec!["section"], header.options.comments().collect::<Vec<_>>());
+                assert_eq!(PcapByteOrder::LittleEndian, header.options.byte_order());
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+        match &blocks[1] {
+            InterfaceDescription(description) => {
+                assert_eq!(
+                    PcapngInterface {
+                        link_type: link_type::RAW,
+                        snaplen: 10,
+                        timestamp_resolution: PcapngTimestampResolution::Binary(10),
+                        timestamp_offset: 100,
+                    },
+                    description.interface()
+                );
+                assert_eq!(Some(&b"eth0"[..]), description.options.get(option_code::IF_NAME));
+                assert_eq!(Some(100), description.options.get_u64(option_code::IF_TSOFFSET));
+                assert_eq!(None, description.options.get_u64(option_code::IF_NAME));
+                assert_eq!(3, description.options.iter().count());
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+        match &blocks[2] {
+            InterfaceDescription(description) => {
+                assert_eq!(PcapngTimestampResolution::Decimal(6), description.interface().timestamp_resolution);
+                assert_eq!(0, description.options.iter().count());
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+        assert_eq!(
+            SimplePacket(PcapngSimplePacket{ original_len: packet.len() as u32, data: &packet }),
+            blocks[3]
+        );
+        match &blocks[4] {
+            NameResolution(names) => {
+                let records: Vec<PcapngNameRecord> = names.records().map(|r| r.unwrap()).collect();
+                assert_eq!(3, records.len());
+                assert_eq!(Some(IpAddr::from([192,168,1,1])), records[0].address());
+                assert_eq!(vec!["one", "uno"], records[0].names().collect::<Vec<_>>());
+                assert_eq!(Some(IpAddr::from([1u8;16])), records[1].address());
+                assert_eq!(vec!["six"], records[1].names().collect::<Vec<_>>());
+                assert_eq!(PcapngNameRecord{ record_type: 1234, value: &[1,2] }, records[2]);
+                assert_eq!(None, records[2].address());
+                assert_eq!(0, records[2].names().count());
+                assert_eq!(vec!["names"], names.options.comments().collect::<Vec<_>>());
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+        assert_eq!(
+            Unknown{ block_type: block_type::INTERFACE_STATISTICS, body: &[0,0,0,0, 1,2,3,4, 5,6,7,8] },
+            blocks[5]
+        );
+        assert_eq!(
+            Custom(PcapngCustomBlock{ copyable: true, private_enterprise_number: 32 << 24, data: &[9,8,0,0] }),
+            blocks[6]
+        );
+        assert_eq!(
+            Custom(PcapngCustomBlock{ copyable: false, private_enterprise_number: 1, data: &[] }),
+            blocks[7]
+        );
+        match &blocks[8] {
+            EnhancedPacket(p) => {
+                assert_eq!(0, p.interface_id);
+                assert_eq!(1024 + 512, p.timestamp);
+                assert_eq!(100, p.original_len);
+                assert_eq!(&packet[..], p.data);
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+    }
+
+    // check packets
+    let check_first = |first: PcapngPacket| {
+        assert_eq!(
+            PcapngPacket {
+                interface_id: 0,
+                timestamp: None,
+                original_len: packet.len() as u32,
+                link_type: link_type::RAW,
+                data: &packet[..10],
+                options: PcapngOptions::from_slice(PcapByteOrder::LittleEndian, &[]),
+            },
+            first
+        );
+        assert!(first.is_truncated());
+    };
+    let check_second = |second: PcapngPacket| {
+        assert_eq!(Some(Duration::from_millis(101_500)), second.timestamp);
+        assert_eq!(link_type::RAW, second.link_type);
+        assert_eq!(vec!["hello"], second.options.comments().collect::<Vec<_>>());
+        assert!(second.is_truncated());
+        assert_eq!(&[1,2,3], second.slice().unwrap().payload);
+    };
+    {
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        check_first(reader.next_packet().unwrap().unwrap());
+        check_second(reader.next_packet().unwrap().unwrap());
+        assert!(reader.next_packet().is_none());
+    }
+    {
+        let mut reader = PcapngReader::new(io::Cursor::new(&file)).unwrap();
+        check_first(reader.next_packet().unwrap().unwrap());
+        check_second(reader.next_packet().unwrap().unwrap());
+        assert_eq!(None, reader.next_packet().unwrap());
+    }
+}
+
+#[test]
+fn timestamp_offset_saturated() {
+    for (offset, timestamp, expected) in [
+        // timestamp in seconds + offset overflows the duration
+        (1i64, u64::MAX, Duration::MAX),
+        // negative offset bigger than the timestamp
+        (-2i64, 1u64, Duration::from_secs(0)),
+    ].iter() {
+        let mut file = Vec::new();
+        file.extend(section_header_le(&[]));
+        file.extend(interface_le(link_type::RAW, 0, &[
+            option_le(option_code::IF_TSRESOL, &[0]),
+            option_le(option_code::IF_TSOFFSET, &offset.to_le_bytes()),
+            option_le(option_code::END_OF_OPT, &[]),
+        ].concat()));
+        file.extend(enhanced_packet_le(0, *timestamp, 0, 0, &[], &[]));
+
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        assert_eq!(Some(*expected), reader.next_packet().unwrap().unwrap().timestamp);
+        assert!(reader.next_packet().is_none());
+    }
+}
+
+#[test]
+fn multiple_sections() {
+    let packet = udp_packet();
+    let mut file = section_header_le(&[]);
+    file.extend(interface_le(link_type::ETHERNET, 0, &[]));
+
+    // big endian section with a raw ip interface
+    {
+        let mut body = Vec::new();
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_be_bytes());
+        body.extend_from_slice(&[0,1, 0,0]);
+        body.extend_from_slice(&u64::MAX.to_be_bytes());
+        file.extend(block(PcapByteOrder::BigEndian, block_type::SECTION_HEADER, &body));
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&link_type::RAW.to_be_bytes());
+        body.extend_from_slice(&[0,0, 0,0,0,0]);
+        file.extend(block(PcapByteOrder::BigEndian, block_type::INTERFACE_DESCRIPTION, &body));
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&0u32.to_be_bytes());
+        body.extend_from_slice(&0u32.to_be_bytes());
+        body.extend_from_slice(&2_000_000u32.to_be_bytes());
+        body.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+        body.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+        body.extend_from_slice(&packet);
+        file.extend(block(PcapByteOrder::BigEndian, block_type::ENHANCED_PACKET, &body));
+    }
+
+    let check = |packet: &PcapngPacket| {
+        assert_eq!(link_type::RAW, packet.link_type);
+        assert_eq!(Some(Duration::from_secs(2)), packet.timestamp);
+        assert!(packet.slice().unwrap().ip.is_some());
+    };
+    {
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        check(&reader.next_packet().unwrap().unwrap());
+        assert_eq!(1, reader.interfaces().len());
+        assert!(reader.next_packet().is_none());
+    }
+    {
+        let mut reader = PcapngReader::new(io::Cursor::new(&file)).unwrap();
+        check(&reader.next_packet().unwrap().unwrap());
+        assert_eq!(1, reader.interfaces().len());
+        assert_eq!(None, reader.next_packet().unwrap());
+    }
+}
+
+#[test]
+fn read_errors() {
+    use ReadError::*;
+    let packet = udp_packet();
+
+    // first block is not a section header
+    {
+        let file = interface_le(link_type::ETHERNET, 0, &[]);
+        assert_matches!(PcapngSliceReader::new(&file), Err(PcapngFirstBlockNotSectionHeader(0x0100_0000)));
+        assert_matches!(PcapngReader::new(io::Cursor::new(&file)), Err(PcapngFirstBlockNotSectionHeader(0x0100_0000)));
+        assert_matches!(PcapngSliceReader::new(&file[..11]), Err(UnexpectedEndOfSlice(12)));
+        assert_matches!(PcapngReader::new(io::Cursor::new(&file[..0])), Err(IoError(_)));
+    }
+
+    // unknown byte order magic
+    {
+        let mut file = section_header_le(&[]);
+        file[8..12].copy_from_slice(&[1,2,3,4]);
+        assert_matches!(PcapngSliceReader::new(&file), Err(PcapngByteOrderMagicUnknown(0x0102_0304)));
+        assert_matches!(PcapngReader::new(io::Cursor::new(&file)), Err(PcapngByteOrderMagicUnknown(0x0102_0304)));
+        assert_matches!(
+            PcapngBlock::from_slice(PcapByteOrder::LittleEndian, &file),
+            Err(PcapngByteOrderMagicUnknown(0x0102_0304))
+        );
+    }
+
+    let header = section_header_le(&[]);
+    let with_header = |data: &[u8]| {
+        let mut file = header.clone();
+        file.extend_from_slice(data);
+        file
+    };
+    let first_error = |file: &[u8]| -> ReadError {
+        PcapngSliceReader::new(file).unwrap().find_map(|b| b.err()).unwrap()
+    };
+    let first_io_error = |file: &[u8]| -> ReadError {
+        let mut reader = PcapngReader::new(io::Cursor::new(file)).unwrap();
+        loop {
+            match reader.next_block() {
+                Ok(Some(_)) => {},
+                Ok(None) => panic!("expected an error"),
+                Err(err) => return err,
+            }
+        }
+    };
+
+    // bad block lengths
+    {
+        let mut interface = interface_le(link_type::ETHERNET, 0, &[]);
+        // not a multiple of 4
+        interface[4] = 21;
+        let file = with_header(&interface);
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(21));
+        assert_matches!(first_io_error(&file), PcapngBlockLengthBad(21));
+        // too small
+        interface[4] = 8;
+        let file = with_header(&interface);
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(8));
+        assert_matches!(first_io_error(&file), PcapngBlockLengthBad(8));
+        // too big
+        interface[4..8].copy_from_slice(&(MAX_BLOCK_LEN + 4).to_le_bytes());
+        let file = with_header(&interface);
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(_));
+        assert_matches!(first_io_error(&file), PcapngBlockLengthBad(_));
+        // trailing length differs
+        let mut interface = interface_le(link_type::ETHERNET, 0, &[]);
+        interface[16] = 24;
+        let file = with_header(&interface);
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(24));
+        assert_matches!(first_io_error(&file), PcapngBlockLengthBad(24));
+        // body too small for the block type
+        let file = with_header(&block(PcapByteOrder::LittleEndian, block_type::INTERFACE_DESCRIPTION, &[1,0,0,0]));
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(16));
+        // captured length bigger then the block
+        let file = with_header(&enhanced_packet_le(0, 0, 100, 100, &packet, &[]));
+        assert_matches!(first_error(&file), PcapngBlockLengthBad(_));
+    }
+
+    // end of file within a block
+    {
+        let file = with_header(&interface_le(link_type::ETHERNET, 0, &[]));
+        for len in header.len() + 1..file.len() {
+            let expected = if len < header.len() + 12 { header.len() + 12 } else { file.len() };
+            assert_matches!(first_error(&file[..len]), UnexpectedEndOfSlice(v) if v == expected);
+            assert_matches!(
+                first_io_error(&file[..len]),
+                IoError(ref err) if io::ErrorKind::UnexpectedEof == err.kind()
+            );
+        }
+    }
+
+    // unknown interface
+    {
+        let file = with_header(&enhanced_packet_le(1, 0, packet.len() as u32, packet.len() as u32, &packet, &[]));
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        assert_matches!(reader.next_packet(), Some(Err(PcapngUnknownInterfaceId(1))));
+        let mut reader = PcapngReader::new(io::Cursor::new(&file)).unwrap();
+        assert_matches!(reader.next_packet(), Err(PcapngUnknownInterfaceId(1)));
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&(packet.len() as u32).to_le_bytes());
+        body.extend_from_slice(&packet);
+        let file = with_header(&block(PcapByteOrder::LittleEndian, block_type::SIMPLE_PACKET, &body));
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        assert_matches!(reader.next_packet(), Some(Err(PcapngUnknownInterfaceId(0))));
+    }
+
+    // option too long
+    {
+        let mut options = option_le(option_code::COMMENT, b"abc");
+        options[2] = 5;
+        let file = with_header(&interface_le(link_type::ETHERNET, 0, &options));
+        let mut reader = PcapngSliceReader::new(&file).unwrap();
+        reader.next();
+        match reader.next().unwrap().unwrap() {
+            PcapngBlock::InterfaceDescription(description) => {
+                let mut iter = description.options.iter();
+                assert_matches!(iter.next(), Some(Err(PcapngOptionLengthTooBig(5))));
+                assert_matches!(iter.next(), None);
+                assert_eq!(0, description.options.comments().count());
+            },
+            other => panic!("unexpected block {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn write_errors() {
+    let mut writer = PcapngWriter::new(Vec::new(), PcapByteOrder::LittleEndian).unwrap();
+    assert_matches!(
+        writer.write_packet(0, Duration::from_secs(0), &[]),
+        Err(WriteError::ValueError(ValueError::PcapngUnknownInterfaceId(0)))
+    );
+    writer.add_interface(link_type::ETHERNET, 0).unwrap();
+    let comment = "a".repeat(u16::MAX as usize + 1);
+    assert_matches!(
+        writer.write_packet_with_comments(0, Duration::from_secs(0), &[], &[&comment]),
+        Err(WriteError::ValueError(ValueError::PcapngOptionTooLarge(len))) if len == comment.len()
+    );
+    // nothing is written on errors
+    let len = writer.into_inner().len();
+    let mut expected = PcapngWriter::new(Vec::new(), PcapByteOrder::LittleEndian).unwrap();
+    expected.add_interface(link_type::ETHERNET, 0).unwrap();
+    assert_eq!(expected.into_inner().len(), len);
+}
+
+#[test]
+fn known_bytes() {
+    let mut writer = PcapngWriter::new(Vec::new(), PcapByteOrder::LittleEndian).unwrap();
+    writer.add_interface(link_type::ETHERNET, 1500).unwrap();
+    writer.write_packet_with_comments(0, Duration::new(1, 2), &[0xaa, 0xbb], &["hi"]).unwrap();
+    let file = writer.into_inner();
+    let expected = [
+        section_header_le(&[]),
+        interface_le(link_type::ETHERNET, 1500, &[
+            option_le(option_code::IF_TSRESOL, &[9]),
+            option_le(option_code::END_OF_OPT, &[]),
+        ].concat()),
+        enhanced_packet_le(0, 1_000_000_002, 2, 2, &[0xaa, 0xbb], &[
+            option_le(option_code::COMMENT, b"hi"),
+            option_le(option_code::END_OF_OPT, &[]),
+        ].concat()),
+    ].concat();
+    // the writer uses an unknown section length
+    let mut expected = expected;
+    expected[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(expected, file);
+}
@@ -11,7 +11,24 @@ pub fn error_field_any() -> impl Strategy<Value = ErrorField> {
         Just(Ipv4FragmentsOffset),
         Just(Ipv6FlowLabel),
         Just(VlanTagPriorityCodePoint),
-        Just(VlanTagVlanId)
+        Just(VlanTagVlanId),
+        Just(DccpCcVal),
+        Just(DccpChecksumCoverage),
+        Just(DccpSequenceNumber),
+        Just(DccpAcknowledgementNumber),
+        Just(IpVersion),
+        Just(VlanTagEtherType),
+        Just(Ipv4Ihl),
+        Just(Ipv4TotalLength),
+        Just(IpAuthPayloadLength),
+        Just(TcpDataOffset),
+        Just(IcmpExtensionVersion),
+        Just(IcmpExtensionObjectLength),
+        Just(SctpChunkLength),
+        Just(SctpParameterLength),
+        Just(DccpDataOffset),
+        Just(DccpOptionLength),
+        Just(GreVersion)
     ]
 }
 
@@ -211,7 +228,11 @@ prop_compose! {
 
 static IPV4_KNOWN_PROTOCOLS: &'static [u8] = &[
     ip_number::ICMP,
+    ip_number::IGMP,
     ip_number::UDP,
+    ip_number::UDP_LITE,
+    ip_number::DCCP,
+    ip_number::SCTP,
     ip_number::TCP,
     ip_number::AUTH,
     ip_number::IPV6_ICMP,
@@ -310,7 +331,11 @@ prop_compose! {
 
 static IPV6_KNOWN_NEXT_HEADERS: &'static [u8] = &[
     ip_number::ICMP,
+    ip_number::IGMP,
     ip_number::UDP,
+    ip_number::UDP_LITE,
+    ip_number::DCCP,
+    ip_number::SCTP,
     ip_number::TCP,
     ip_number::IPV6_HOP_BY_HOP,
     ip_number::IPV6_ICMP,
@@ -494,6 +519,22 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    pub(crate) fn gre_any()(
+        protocol_type in any::<u16>(),
+        checksum in proptest::option::of(any::<u16>()),
+        key in proptest::option::of(any::<u32>()),
+        sequence_number in proptest::option::of(any::<u32>()),
+    ) -> GreHeader {
+        GreHeader {
+            protocol_type,
+            checksum,
+            key,
+            sequence_number,
+        }
+    }
+}
+
 prop_compose! {
     pub(crate) fn ip_authentication_with(
         next_header: u8
@@ -772,3 +813,287 @@ prop_compose! {
         Icmpv6Header::from_slice(&bytes).unwrap().0
     }
 }
+
+prop_compose! {
+    pub fn igmp_type_any()
+        (
+            type_u8 in prop_oneof![
+                Just(igmp::TYPE_MEMBERSHIP_QUERY),
+                Just(igmp::TYPE_MEMBERSHIP_REPORT_V1),
+                Just(igmp::TYPE_MEMBERSHIP_REPORT_V2),
+                Just(igmp::TYPE_LEAVE_GROUP),
+                Just(igmp::TYPE_MEMBERSHIP_REPORT_V3),
+                any::<u8>()
+            ],
+            reserved_zero in any::<bool>(),
+            len in 8usize..=12,
+            mut bytes in any::<[u8;12]>(),
+        ) -> IgmpType
+    {
+        bytes[0] = type_u8;
+        if reserved_zero {
+            bytes[1] = 0;
+            bytes[4] = 0;
+            bytes[5] = 0;
+        }
+        IgmpSlice::from_slice(&bytes[..len]).unwrap().igmp_type()
+    }
+}
+
+prop_compose! {
+    pub fn igmp_header_any()
+        (
+            igmp_type in igmp_type_any(),
+            checksum in any::<u16>(),
+        ) -> IgmpHeader
+    {
+        IgmpHeader{
+            igmp_type,
+            checksum,
+        }
+    }
+}
+
+prop_compose! {
+    pub fn udp_lite_header_any()
+        (
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            checksum_coverage in any::<u16>(),
+            checksum in any::<u16>(),
+        ) -> UdpLiteHeader
+    {
+        UdpLiteHeader{
+            source_port,
+            destination_port,
+            checksum_coverage,
+            checksum,
+        }
+    }
+}
+
+prop_compose! {
+    pub fn dccp_packet_type_any(max_number: u64)
+        (
+            type_value in 0u8..16,
+            acknowledgement_number in 0..=max_number,
+            service_code in any::<u32>(),
+            reset_code in any::<u8>(),
+            data in any::<[u8;3]>(),
+        ) -> DccpPacketType
+    {
+        use DccpPacketType::*;
+        match type_value {
+            dccp::TYPE_REQUEST => Request{ service_code },
+            dccp::TYPE_RESPONSE => Response{ acknowledgement_number, service_code },
+            dccp::TYPE_DATA => Data,
+            dccp::TYPE_ACK => Ack{ acknowledgement_number },
+            dccp::TYPE_DATA_ACK => DataAck{ acknowledgement_number },
+            dccp::TYPE_CLOSE_REQ => CloseReq{ acknowledgement_number },
+            dccp::TYPE_CLOSE => Close{ acknowledgement_number },
+            dccp::TYPE_RESET => Reset{ acknowledgement_number, reset_code, data },
+            dccp::TYPE_SYNC => Sync{ acknowledgement_number },
+            dccp::TYPE_SYNC_ACK => SyncAck{ acknowledgement_number },
+            value => Reserved(value),
+        }
+    }
+}
+
+prop_compose! {
+    pub fn dccp_header_any()
+        (
+            extended in any::<bool>()
+        )
+        (
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            ccval in 0u8..=0xf,
+            checksum_coverage in 0u8..=0xf,
+            checksum in any::<u16>(),
+            sequence_number in if extended {
+                (0..=dccp::MAX_EXTENDED_SEQUENCE_NUMBER).prop_map(DccpSequenceNumber::Extended).boxed()
+            } else {
+                (0..=dccp::MAX_SHORT_SEQUENCE_NUMBER as u32).prop_map(DccpSequenceNumber::Short).boxed()
+            },
+            packet_type in dccp_packet_type_any(if extended {
+                dccp::MAX_EXTENDED_SEQUENCE_NUMBER
+            } else {
+                dccp::MAX_SHORT_SEQUENCE_NUMBER
+            }),
+            options_words in 0usize..8,
+            options in any::<[u8;28]>(),
+        ) -> DccpHeader
+    {
+        let mut result = DccpHeader::new(source_port, destination_port, sequence_number, packet_type);
+        result.ccval = ccval;
+        result.checksum_coverage = checksum_coverage;
+        result.checksum = checksum;
+        result.set_options_raw(&options[..options_words*4]).unwrap();
+        result
+    }
+}
+
+prop_compose! {
+    pub fn sctp_header_any()
+        (
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            verification_tag in any::<u32>(),
+            checksum in any::<u32>(),
+        ) -> SctpHeader
+    {
+        SctpHeader{
+            source_port,
+            destination_port,
+            verification_tag,
+            checksum,
+        }
+    }
+}
+
+pub fn element_filter_any<T: std::fmt::Debug + Clone>(value: impl Strategy<Value = T>) -> impl Strategy<Value = etherparse::packet_filter::ElementFilter<T>> {
+    prop_oneof![
+        2 => Just(etherparse::packet_filter::ElementFilter::Any),
+        1 => Just(etherparse::packet_filter::ElementFilter::No),
+        2 => value.prop_map(etherparse::packet_filter::ElementFilter::Some),
+    ]
+}
+
+pub fn filter_any() -> impl Strategy<Value = etherparse::packet_filter::Filter> {
+    let small = || proptest::option::of(0u16..3);
+    (
+        element_filter_any(
+            (proptest::option::of(any::<[u8;6]>()), proptest::option::of(any::<[u8;6]>()))
+                .prop_map(|(source, destination)| etherparse::packet_filter::LinkFilter::Ethernet2{ source, destination })
+        ),
+        element_filter_any(prop_oneof![
+            small().prop_map(etherparse::packet_filter::VlanFilter::Single),
+            (small(), small()).prop_map(|(outer_identifier, inner_identifier)| etherparse::packet_filter::VlanFilter::Double{ outer_identifier, inner_identifier }),
+        ]),
+        element_filter_any(prop_oneof![
+            (proptest::option::of(any::<[u8;4]>()), proptest::option::of(any::<[u8;4]>()))
+                .prop_map(|(source, destination)| etherparse::packet_filter::IpFilter::Ipv4{ source, destination }),
+            (proptest::option::of(any::<[u8;16]>()), proptest::option::of(any::<[u8;16]>()))
+                .prop_map(|(source, destination)| etherparse::packet_filter::IpFilter::Ipv6{ source, destination }),
+        ]),
+        element_filter_any(prop_oneof![
+            (proptest::option::of(any::<u16>()), proptest::option::of(any::<u16>()))
+                .prop_map(|(source_port, destination_port)| etherparse::packet_filter::TransportFilter::Udp{ source_port, destination_port }),
+            (proptest::option::of(any::<u16>()), proptest::option::of(any::<u16>()))
+                .prop_map(|(source_port, destination_port)| etherparse::packet_filter::TransportFilter::Tcp{ source_port, destination_port }),
+        ]),
+    ).prop_map(|(link, vlan, ip, transport)| etherparse::packet_filter::Filter{ link, vlan, ip, transport })
+}
+
+/// Serializes a packet with matching ether types & ip numbers.
+fn serialize_ethernet_packet(
+    mut ethernet: Ethernet2Header,
+    vlan: Option<VlanHeader>,
+    ip: Option<IpHeader>,
+    transport: Option<TransportHeader>,
+    payload: &[u8]
+) -> Vec<u8> {
+    let transport_len = transport.as_ref().map(|t| t.header_len()).unwrap_or(0);
+    let ip_number = match &transport {
+        Some(TransportHeader::Udp(_)) => ip_number::UDP,
+        Some(TransportHeader::Tcp(_)) => ip_number::TCP,
+        Some(TransportHeader::Icmpv4(_)) => ip_number::ICMP,
+        Some(TransportHeader::Icmpv6(_)) => ip_number::IPV6_ICMP,
+        _ => 253,
+    };
+    let ip = ip.map(|mut ip| {
+        ip.set_next_headers(ip_number);
+        ip.set_payload_len(transport_len + payload.len()).unwrap();
+        ip
+    });
+    let ether_type = match &ip {
+        Some(IpHeader::Version4(_, _)) => ether_type::IPV4,
+        Some(IpHeader::Version6(_, _)) => ether_type::IPV6,
+        None => 0x1234,
+    };
+    let vlan = vlan.map(|vlan| match vlan {
+        VlanHeader::Single(mut single) => {
+            single.ether_type = ether_type;
+            VlanHeader::Single(single)
+        },
+        VlanHeader::Double(mut double) => {
+            double.outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
+            double.inner.ether_type = ether_type;
+            VlanHeader::Double(double)
+        },
+    });
+    ethernet.ether_type = match &vlan {
+        Some(VlanHeader::Single(_)) => ether_type::VLAN_TAGGED_FRAME,
+        Some(VlanHeader::Double(_)) => ether_type::PROVIDER_BRIDGING,
+        None => ether_type,
+    };
+
+    let mut packet = Vec::new();
+    ethernet.write(&mut packet).unwrap();
+    match &vlan {
+        Some(VlanHeader::Single(header)) => header.write(&mut packet).unwrap(),
+        Some(VlanHeader::Double(header)) => header.write(&mut packet).unwrap(),
+        None => {},
+    }
+    match &ip {
+        Some(IpHeader::Version4(header, extensions)) => {
+            header.write_raw(&mut packet).unwrap();
+            extensions.write(&mut packet, header.protocol).unwrap();
+        },
+        Some(IpHeader::Version6(header, extensions)) => {
+            header.write(&mut packet).unwrap();
+            extensions.write(&mut packet, header.next_header).unwrap();
+        },
+        None => {},
+    }
+    if ip.is_some() {
+        if let Some(transport) = &transport {
+            transport.write(&mut packet).unwrap();
+        }
+    }
+    packet.extend_from_slice(payload);
+    packet
+}
+
+prop_compose! {
+    /// Ethernet II packet with random vlan, ip & transport headers (the ether
+    /// types & ip numbers are set to match the following headers).
+    pub(crate) fn ethernet_packet_any()
+    (
+        ethernet in ethernet_2_any(),
+        vlan_kind in 0u8..3,
+        outer_vlan in vlan_single_any(),
+        inner_vlan in vlan_single_any(),
+        ip_kind in 0u8..3,
+        ipv4 in ipv4_any(),
+        ipv4_extensions in ipv4_extensions_any(),
+        ipv6 in ipv6_any(),
+        ipv6_extensions in ipv6_extensions_any(),
+        transport_kind in 0u8..5,
+        udp in udp_any(),
+        tcp in tcp_any(),
+        icmpv4 in icmpv4_header_any(),
+        icmpv6 in icmpv6_header_any(),
+        payload in proptest::collection::vec(any::<u8>(), 0..40)
+    ) -> Vec<u8>
+    {
+        let vlan = match vlan_kind {
+            0 => None,
+            1 => Some(VlanHeader::Single(inner_vlan)),
+            _ => Some(VlanHeader::Double(DoubleVlanHeader{ outer: outer_vlan, inner: inner_vlan })),
+        };
+        let ip = match ip_kind {
+            0 => None,
+            1 => Some(IpHeader::Version4(ipv4, ipv4_extensions)),
+            _ => Some(IpHeader::Version6(ipv6, ipv6_extensions)),
+        };
+        let transport = match transport_kind {
+            0 => None,
+            1 => Some(TransportHeader::Udp(udp)),
+            2 => Some(TransportHeader::Tcp(tcp)),
+            3 => Some(TransportHeader::Icmpv4(icmpv4)),
+            _ => Some(TransportHeader::Icmpv6(icmpv6)),
+        };
+        serialize_ethernet_packet(ethernet, vlan, ip, transport, &payload)
+    }
+}
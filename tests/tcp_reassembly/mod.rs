@@ -0,0 +1,514 @@
+use super::*;
+use etherparse::tcp_reassembly::*;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+const SERVER: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2));
+
+/// Owned version of the stream events (for comparison).
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Event {
+    Data(TcpStreamDirection, Vec<u8>),
+    Gap(TcpStreamDirection, u64),
+    Fin(TcpStreamDirection),
+    Reset(TcpStreamDirection),
+    TimedOut(TcpStreamDirection),
+}
+
+use TcpStreamDirection::*;
+
+/// Flags of a test segment.
+#[derive(Clone, Copy)]
+struct Flags {
+    syn: bool,
+    ack: bool,
+    fin: bool,
+    rst: bool,
+}
+
+const NONE: Flags = Flags{ syn: false, ack: false, fin: false, rst: false };
+const SYN: Flags = Flags{ syn: true, ack: false, fin: false, rst: false };
+const SYN_ACK: Flags = Flags{ syn: true, ack: true, fin: false, rst: false };
+const FIN: Flags = Flags{ syn: false, ack: true, fin: true, rst: false };
+const RST: Flags = Flags{ syn: false, ack: false, fin: false, rst: true };
+
+struct Tester {
+    reassembler: TcpReassembler,
+    events: Vec<Event>,
+    /// Timestamp used for the sent segments.
+    now: Duration,
+}
+
+impl Tester {
+    fn new(config: TcpReassemblyConfig) -> Tester {
+        Tester{
+            reassembler: TcpReassembler::new(config),
+            events: Vec::new(),
+            now: Duration::from_secs(0),
+        }
+    }
+
+    fn collect(events: &mut Vec<Event>) -> impl FnMut(&TcpConnectionKey, TcpStreamDirection, TcpStreamEvent<'_>) + '_ {
+        move |key, direction, event| {
+            assert_eq!(key.client.address, CLIENT);
+            assert_eq!(key.server.address, SERVER);
+            events.push(match event {
+                TcpStreamEvent::Data(data) => Event::Data(direction, data.to_vec()),
+                TcpStreamEvent::Gap(len) => Event::Gap(direction, len),
+                TcpStreamEvent::Fin => Event::Fin(direction),
+                TcpStreamEvent::Reset => Event::Reset(direction),
+                TcpStreamEvent::TimedOut => Event::TimedOut(direction),
+            });
+        }
+    }
+
+    /// Sends a segment in the given direction.
+    fn send(&mut self, direction: TcpStreamDirection, seq: u32, flags: Flags, payload: &[u8]) {
+        let (source, destination, source_port, destination_port) = match direction {
+            ClientToServer => (CLIENT, SERVER, 1234, 80),
+            ServerToClient => (SERVER, CLIENT, 80, 1234),
+        };
+        let mut header = TcpHeader::new(source_port, destination_port, seq, 1024);
+        header.syn = flags.syn;
+        header.ack = flags.ack;
+        header.fin = flags.fin;
+        header.rst = flags.rst;
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        let slice = TcpHeaderSlice::from_slice(&buffer).unwrap();
+        self.reassembler.process_segment(
+            self.now,
+            source,
+            destination,
+            &slice,
+            payload,
+            &mut Tester::collect(&mut self.events)
+        );
+    }
+
+    fn flush(&mut self) {
+        self.reassembler.flush(&mut Tester::collect(&mut self.events));
+    }
+
+    fn expire(&mut self, now: Duration) {
+        self.reassembler.expire(now, &mut Tester::collect(&mut self.events));
+    }
+
+    /// Returns the events since the last call.
+    fn take(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+#[test]
+fn config_default() {
+    let config: TcpReassemblyConfig = Default::default();
+    assert_eq!(1024*1024, config.max_buffered_bytes);
+    assert_eq!(1024, config.max_buffered_segments);
+    assert_eq!(64*1024, config.max_connections);
+    assert!(config.allow_midstream);
+    assert_eq!(Duration::from_secs(2*60*60), config.idle_timeout);
+    assert_eq!(&config, TcpReassembler::default().config());
+}
+
+#[test]
+fn in_order() {
+    let mut t = Tester::new(Default::default());
+
+    // handshake
+    t.send(ClientToServer, 100, SYN, &[]);
+    t.send(ServerToClient, 500, SYN_ACK, &[]);
+    assert_eq!(1, t.reassembler.connection_count());
+    assert_eq!(Vec::<Event>::new(), t.take());
+
+    // data
+    t.send(ClientToServer, 101, NONE, &[1,2,3]);
+    t.send(ServerToClient, 501, NONE, &[4,5]);
+    t.send(ClientToServer, 104, NONE, &[6]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![1,2,3]),
+            Event::Data(ServerToClient, vec![4,5]),
+            Event::Data(ClientToServer, vec![6]),
+        ],
+        t.take()
+    );
+
+    // close (data in the fin segment is delivered before the fin)
+    t.send(ClientToServer, 105, FIN, &[7]);
+    t.send(ServerToClient, 503, FIN, &[]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![7]),
+            Event::Fin(ClientToServer),
+            Event::Fin(ServerToClient),
+        ],
+        t.take()
+    );
+    assert_eq!(0, t.reassembler.connection_count());
+}
+
+#[test]
+fn syn_ack_first() {
+    // the receiver of the syn ack is treated as the client
+    let mut t = Tester::new(Default::default());
+    t.send(ServerToClient, 500, SYN_ACK, &[]);
+    t.send(ClientToServer, 101, NONE, &[1]);
+    t.send(ServerToClient, 501, NONE, &[2]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![1]),
+            Event::Data(ServerToClient, vec![2]),
+        ],
+        t.take()
+    );
+}
+
+#[test]
+fn out_of_order() {
+    let mut t = Tester::new(Default::default());
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 7, NONE, &[7,8]);
+    t.send(ClientToServer, 4, NONE, &[4,5,6]);
+    assert_eq!(Vec::<Event>::new(), t.take());
+    t.send(ClientToServer, 1, NONE, &[1,2,3]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![1,2,3]),
+            Event::Data(ClientToServer, vec![4,5,6]),
+            Event::Data(ClientToServer, vec![7,8]),
+        ],
+        t.take()
+    );
+
+    // fin before the missing data
+    t.send(ClientToServer, 11, FIN, &[11]);
+    t.send(ClientToServer, 9, NONE, &[9,10]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![9,10]),
+            Event::Data(ClientToServer, vec![11]),
+            Event::Fin(ClientToServer),
+        ],
+        t.take()
+    );
+
+    // no more data after the fin
+    t.send(ClientToServer, 12, NONE, &[12]);
+    assert_eq!(Vec::<Event>::new(), t.take());
+}
+
+#[test]
+fn retransmissions_and_overlaps() {
+    let mut t = Tester::new(Default::default());
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 1, NONE, &[1,2,3,4]);
+
+    // full retransmission & retransmitted syn
+    t.send(ClientToServer, 1, NONE, &[1,2,3,4]);
+    t.send(ClientToServer, 0, SYN, &[]);
+    // partial overlap with already delivered data
+    t.send(ClientToServer, 3, NONE, &[3,4,5,6]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![1,2,3,4]),
+            Event::Data(ClientToServer, vec![5,6]),
+        ],
+        t.take()
+    );
+
+    // overlapping out of order segments (the segment starting first wins)
+    t.send(ClientToServer, 9, NONE, &[9,10,11]);
+    t.send(ClientToServer, 8, NONE, &[8,0xff,0xff,0xff,12]);
+    t.send(ClientToServer, 9, NONE, &[9]);
+    t.send(ClientToServer, 7, NONE, &[7]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![7]),
+            Event::Data(ClientToServer, vec![8,0xff,0xff,0xff,12]),
+        ],
+        t.take()
+    );
+}
+
+#[test]
+fn sequence_number_wraparound() {
+    let mut t = Tester::new(Default::default());
+    t.send(ClientToServer, u32::MAX - 2, SYN, &[]);
+    t.send(ClientToServer, 1, NONE, &[4,5]);
+    t.send(ClientToServer, u32::MAX - 1, NONE, &[1,2,3]);
+    t.send(ClientToServer, u32::MAX, NONE, &[2,3,4]);
+    assert_eq!(
+        vec![
+            Event::Data(ClientToServer, vec![1,2,3]),
+            Event::Data(ClientToServer, vec![4,5]),
+        ],
+        t.take()
+    );
+}
+
+#[test]
+fn reset() {
+    let mut t = Tester::new(Default::default());
+
+    // rst of an unknown connection is ignored
+    t.send(ClientToServer, 0, RST, &[]);
+    assert_eq!(0, t.reassembler.connection_count());
+
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 5, NONE, &[5]);
+    t.send(ServerToClient, 10, RST, &[]);
+    assert_eq!(vec![Event::Reset(ServerToClient)], t.take());
+    assert_eq!(0, t.reassembler.connection_count());
+}
+
+#[test]
+fn buffer_limits() {
+    // byte limit
+    {
+        let mut t = Tester::new(TcpReassemblyConfig{
+            max_buffered_bytes: 4,
+            ..Default::default()
+        });
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.send(ClientToServer, 3, NONE, &[3,4]);
+        t.send(ClientToServer, 6, NONE, &[6,7]);
+        assert_eq!(Vec::<Event>::new(), t.take());
+        t.send(ClientToServer, 9, NONE, &[9]);
+        assert_eq!(
+            vec![
+                Event::Gap(ClientToServer, 2),
+                Event::Data(ClientToServer, vec![3,4]),
+            ],
+            t.take()
+        );
+        t.send(ClientToServer, 5, NONE, &[5]);
+        assert_eq!(
+            vec![
+                Event::Data(ClientToServer, vec![5]),
+                Event::Data(ClientToServer, vec![6,7]),
+            ],
+            t.take()
+        );
+    }
+
+    // segment limit
+    {
+        let mut t = Tester::new(TcpReassemblyConfig{
+            max_buffered_segments: 1,
+            ..Default::default()
+        });
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.send(ClientToServer, 5, NONE, &[5]);
+        t.send(ClientToServer, 3, NONE, &[3]);
+        assert_eq!(
+            vec![
+                Event::Gap(ClientToServer, 2),
+                Event::Data(ClientToServer, vec![3]),
+            ],
+            t.take()
+        );
+    }
+}
+
+#[test]
+fn flush() {
+    let mut t = Tester::new(Default::default());
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 3, NONE, &[3]);
+    t.send(ClientToServer, 6, FIN, &[6]);
+    t.flush();
+    assert_eq!(
+        vec![
+            Event::Gap(ClientToServer, 2),
+            Event::Data(ClientToServer, vec![3]),
+            Event::Gap(ClientToServer, 2),
+            Event::Data(ClientToServer, vec![6]),
+            Event::Fin(ClientToServer),
+        ],
+        t.take()
+    );
+    assert_eq!(0, t.reassembler.connection_count());
+}
+
+#[test]
+fn expire() {
+    let config = TcpReassemblyConfig{
+        idle_timeout: Duration::from_secs(10),
+        ..Default::default()
+    };
+
+    // half open connection with buffered data
+    {
+        let mut t = Tester::new(config.clone());
+        t.now = Duration::from_secs(1);
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.now = Duration::from_secs(5);
+        t.send(ClientToServer, 3, NONE, &[3]);
+
+        // the idle time is measured from the last segment
+        t.expire(Duration::from_secs(15));
+        assert_eq!(1, t.reassembler.connection_count());
+        assert_eq!(Vec::<Event>::new(), t.take());
+
+        t.expire(Duration::from_millis(15_001));
+        assert_eq!(0, t.reassembler.connection_count());
+        assert_eq!(
+            vec![
+                Event::Gap(ClientToServer, 2),
+                Event::Data(ClientToServer, vec![3]),
+                Event::TimedOut(ClientToServer),
+                Event::TimedOut(ServerToClient),
+            ],
+            t.take()
+        );
+    }
+
+    // connection only closed in one direction
+    {
+        let mut t = Tester::new(config.clone());
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.send(ServerToClient, 500, SYN_ACK, &[]);
+        t.send(ClientToServer, 1, FIN, &[]);
+        assert_eq!(vec![Event::Fin(ClientToServer)], t.take());
+
+        t.expire(Duration::from_secs(11));
+        assert_eq!(0, t.reassembler.connection_count());
+        assert_eq!(vec![Event::TimedOut(ServerToClient)], t.take());
+    }
+
+    // new connections are tracked again after expired connections freed the table
+    {
+        let mut t = Tester::new(TcpReassemblyConfig{
+            max_connections: 1,
+            ..config
+        });
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.expire(Duration::from_secs(11));
+        t.take();
+        t.now = Duration::from_secs(11);
+        t.send(ClientToServer, 100, SYN, &[]);
+        t.send(ClientToServer, 101, NONE, &[1]);
+        assert_eq!(1, t.reassembler.connection_count());
+        assert_eq!(vec![Event::Data(ClientToServer, vec![1])], t.take());
+    }
+}
+
+#[test]
+fn remove() {
+    let mut t = Tester::new(TcpReassemblyConfig{
+        max_connections: 1,
+        ..Default::default()
+    });
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 3, NONE, &[3]);
+    let key = TcpConnectionKey{
+        client: TcpEndpoint{ address: CLIENT, port: 1234 },
+        server: TcpEndpoint{ address: SERVER, port: 80 },
+    };
+
+    // the buffered data is discarded
+    assert!(t.reassembler.remove(&key));
+    assert_eq!(0, t.reassembler.connection_count());
+    assert!(!t.reassembler.remove(&key));
+    t.flush();
+    assert_eq!(Vec::<Event>::new(), t.take());
+
+    // space for new connections
+    t.send(ClientToServer, 10, SYN, &[]);
+    t.send(ClientToServer, 11, NONE, &[1]);
+    assert_eq!(vec![Event::Data(ClientToServer, vec![1])], t.take());
+}
+
+#[test]
+fn midstream() {
+    // allowed
+    {
+        let mut t = Tester::new(Default::default());
+        t.send(ClientToServer, 1000, NONE, &[1]);
+        t.send(ServerToClient, 2000, NONE, &[2]);
+        assert_eq!(
+            vec![
+                Event::Data(ClientToServer, vec![1]),
+                Event::Data(ServerToClient, vec![2]),
+            ],
+            t.take()
+        );
+    }
+    // not allowed
+    {
+        let mut t = Tester::new(TcpReassemblyConfig{
+            allow_midstream: false,
+            ..Default::default()
+        });
+        t.send(ClientToServer, 1000, NONE, &[1]);
+        assert_eq!(0, t.reassembler.connection_count());
+
+        // the other direction also requires a syn
+        t.send(ClientToServer, 0, SYN, &[]);
+        t.send(ServerToClient, 2000, NONE, &[2]);
+        t.send(ClientToServer, 1, NONE, &[1]);
+        assert_eq!(vec![Event::Data(ClientToServer, vec![1])], t.take());
+    }
+}
+
+#[test]
+fn max_connections() {
+    let mut t = Tester::new(TcpReassemblyConfig{
+        max_connections: 0,
+        ..Default::default()
+    });
+    t.send(ClientToServer, 0, SYN, &[]);
+    t.send(ClientToServer, 1, NONE, &[1]);
+    assert_eq!(0, t.reassembler.connection_count());
+    assert_eq!(Vec::<Event>::new(), t.take());
+}
+
+#[test]
+fn process_sliced() {
+    let mut reassembler = TcpReassembler::default();
+    let mut received = Vec::new();
+
+    // tcp packet
+    {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(1234, 80, 0, 1024);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[1,2,3]).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(reassembler.process_sliced(Duration::from_secs(0), &sliced, &mut |key, direction, event| {
+            assert_eq!(
+                TcpEndpoint{ address: IpAddr::V4(Ipv4Addr::new(192,168,1,1)), port: 1234 },
+                key.client
+            );
+            assert_eq!(ClientToServer, direction);
+            if let TcpStreamEvent::Data(data) = event {
+                received.extend_from_slice(data);
+            }
+        }));
+        assert_eq!(vec![1,2,3], received);
+    }
+
+    // ipv6 tcp packet
+    {
+        let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+            .tcp(1234, 80, 0, 1024);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[4]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(reassembler.process_sliced(Duration::from_secs(0), &sliced, &mut |_, _, _| {}));
+        assert_eq!(2, reassembler.connection_count());
+    }
+
+    // udp packet
+    {
+        let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 80);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[1]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(!reassembler.process_sliced(Duration::from_secs(0), &sliced, &mut |_, _, _| {}));
+    }
+}
@@ -0,0 +1,386 @@
+use super::*;
+use etherparse::tcp_reassembly::*;
+use etherparse::tcp_tracking::*;
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+const SERVER: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+use TcpStreamDirection::*;
+use TcpState::*;
+
+const KEY: TcpConnectionKey = TcpConnectionKey{
+    client: TcpEndpoint{ address: CLIENT, port: 1234 },
+    server: TcpEndpoint{ address: SERVER, port: 80 },
+};
+
+/// Flags of a test segment.
+#[derive(Clone, Copy, Default)]
+struct Flags {
+    syn: bool,
+    ack: bool,
+    fin: bool,
+    rst: bool,
+    psh: bool,
+    urg: bool,
+}
+
+const SYN: Flags = Flags{ syn: true, ack: false, fin: false, rst: false, psh: false, urg: false };
+const SYN_ACK: Flags = Flags{ syn: true, ack: true, fin: false, rst: false, psh: false, urg: false };
+const ACK: Flags = Flags{ syn: false, ack: true, fin: false, rst: false, psh: false, urg: false };
+const FIN: Flags = Flags{ syn: false, ack: true, fin: true, rst: false, psh: false, urg: false };
+const RST: Flags = Flags{ syn: false, ack: false, fin: false, rst: true, psh: false, urg: false };
+
+struct Tester {
+    tracker: TcpConnectionTracker,
+    events: Vec<(TcpConnectionKey, TcpTrackerEvent)>,
+}
+
+impl Tester {
+    fn new(config: TcpTrackerConfig) -> Tester {
+        Tester{
+            tracker: TcpConnectionTracker::new(config),
+            events: Vec::new(),
+        }
+    }
+
+    /// Sends a segment in the given direction at the given time (in milliseconds).
+    fn send(&mut self, millis: u64, direction: TcpStreamDirection, flags: Flags, seq: u32, ack: u32, payload_len: usize) {
+        let (source, destination, source_port, destination_port) = match direction {
+            ClientToServer => (CLIENT, SERVER, 1234, 80),
+            ServerToClient => (SERVER, CLIENT, 80, 1234),
+        };
+        let mut header = TcpHeader::new(source_port, destination_port, seq, 1024);
+        header.acknowledgment_number = ack;
+        header.syn = flags.syn;
+        header.ack = flags.ack;
+        header.fin = flags.fin;
+        header.rst = flags.rst;
+        header.psh = flags.psh;
+        header.urg = flags.urg;
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        let slice = TcpHeaderSlice::from_slice(&buffer).unwrap();
+        let events = &mut self.events;
+        self.tracker.process_segment(
+            Duration::from_millis(millis),
+            source,
+            destination,
+            &slice,
+            payload_len,
+            &mut |key, event| events.push((*key, event))
+        );
+    }
+
+    /// Returns the events since the last call.
+    fn take(&mut self) -> Vec<TcpTrackerEvent> {
+        std::mem::take(&mut self.events)
+            .into_iter()
+            .map(|(key, event)| {
+                assert_eq!(KEY, key);
+                event
+            })
+            .collect()
+    }
+
+    fn states(&self) -> (TcpState, TcpState) {
+        let c = self.tracker.connection(&KEY).unwrap();
+        (c.client_state, c.server_state)
+    }
+
+    /// Performs a three way handshake (client seq 100, server seq 500).
+    fn handshake(&mut self) {
+        self.send(0, ClientToServer, SYN, 100, 0, 0);
+        self.send(10, ServerToClient, SYN_ACK, 500, 101, 0);
+        self.send(15, ClientToServer, ACK, 101, 501, 0);
+    }
+}
+
+#[test]
+fn config_default() {
+    let config: TcpTrackerConfig = Default::default();
+    assert_eq!(Duration::from_secs(5*24*60*60), config.established_timeout);
+    assert_eq!(Duration::from_secs(120), config.transitory_timeout);
+    assert_eq!(Duration::from_secs(120), config.time_wait_timeout);
+    assert_eq!(64*1024, config.max_connections);
+    assert!(config.allow_midstream);
+    assert_eq!(&config, TcpConnectionTracker::default().config());
+}
+
+#[test]
+fn handshake() {
+    let mut t = Tester::new(Default::default());
+
+    t.send(0, ClientToServer, SYN, 100, 0, 0);
+    assert_eq!(vec![TcpTrackerEvent::New], t.take());
+    assert_eq!((SynSent, Listen), t.states());
+
+    t.send(10, ServerToClient, SYN_ACK, 500, 101, 0);
+    assert_eq!(Vec::<TcpTrackerEvent>::new(), t.take());
+    assert_eq!((SynSent, SynReceived), t.states());
+
+    t.send(15, ClientToServer, ACK, 101, 501, 0);
+    let rtt = TcpHandshakeRtt{
+        syn_to_syn_ack: Duration::from_millis(10),
+        syn_ack_to_ack: Duration::from_millis(5),
+    };
+    assert_eq!(vec![TcpTrackerEvent::HandshakeCompleted(Some(rtt))], t.take());
+    assert_eq!(Duration::from_millis(15), rtt.total());
+    assert_eq!((Established, Established), t.states());
+
+    let c = t.tracker.connection(&KEY).unwrap();
+    assert_eq!(Some(rtt), c.handshake_rtt);
+    assert_eq!(Duration::from_millis(0), c.first_seen);
+    assert_eq!(Duration::from_millis(15), c.last_seen);
+    assert!(c.is_established());
+    assert!(!c.is_closed());
+    assert_eq!(1, t.tracker.connections().count());
+}
+
+#[test]
+fn handshake_without_syn() {
+    let mut t = Tester::new(Default::default());
+    t.send(10, ServerToClient, SYN_ACK, 500, 101, 0);
+    t.send(15, ClientToServer, ACK, 101, 501, 0);
+    assert_eq!(
+        vec![
+            TcpTrackerEvent::New,
+            TcpTrackerEvent::HandshakeCompleted(None),
+        ],
+        t.take()
+    );
+}
+
+#[test]
+fn close() {
+    // client closes first
+    {
+        let mut t = Tester::new(Default::default());
+        t.handshake();
+        t.take();
+
+        t.send(20, ClientToServer, FIN, 101, 501, 10);
+        assert_eq!(vec![TcpTrackerEvent::HalfClosed(ClientToServer)], t.take());
+        assert_eq!((FinWait1, CloseWait), t.states());
+
+        // ack not covering the fin
+        t.send(21, ServerToClient, ACK, 501, 111, 0);
+        assert_eq!((FinWait1, CloseWait), t.states());
+
+        t.send(22, ServerToClient, ACK, 501, 112, 0);
+        assert_eq!((FinWait2, CloseWait), t.states());
+
+        t.send(30, ServerToClient, FIN, 501, 112, 0);
+        assert_eq!(vec![TcpTrackerEvent::HalfClosed(ServerToClient)], t.take());
+        assert_eq!((TimeWait, LastAck), t.states());
+
+        t.send(31, ClientToServer, ACK, 112, 502, 0);
+        assert_eq!(vec![TcpTrackerEvent::Closed], t.take());
+        assert_eq!((TimeWait, Closed), t.states());
+        assert!(t.tracker.connection(&KEY).unwrap().is_closed());
+    }
+
+    // simultaneous close
+    {
+        let mut t = Tester::new(Default::default());
+        t.handshake();
+        t.take();
+
+        t.send(20, ClientToServer, FIN, 101, 501, 0);
+        t.send(20, ServerToClient, FIN, 501, 101, 0);
+        assert_eq!(
+            vec![
+                TcpTrackerEvent::HalfClosed(ClientToServer),
+                TcpTrackerEvent::HalfClosed(ServerToClient),
+            ],
+            t.take()
+        );
+        assert_eq!((Closing, LastAck), t.states());
+
+        t.send(21, ClientToServer, ACK, 102, 502, 0);
+        assert_eq!((Closing, Closed), t.states());
+        t.send(21, ServerToClient, ACK, 502, 102, 0);
+        assert_eq!((TimeWait, Closed), t.states());
+        assert_eq!(vec![TcpTrackerEvent::Closed], t.take());
+    }
+}
+
+#[test]
+fn reset() {
+    let mut t = Tester::new(Default::default());
+
+    // rst of an unknown connection
+    t.send(0, ServerToClient, RST, 0, 0, 0);
+    assert_eq!(0, t.tracker.connection_count());
+
+    t.handshake();
+    t.take();
+    t.send(20, ServerToClient, RST, 501, 0, 0);
+    assert_eq!(vec![TcpTrackerEvent::Reset(ServerToClient)], t.take());
+    assert_eq!((Closed, Closed), t.states());
+
+    // a second rst is not reported
+    t.send(21, ClientToServer, RST, 101, 0, 0);
+    assert_eq!(Vec::<TcpTrackerEvent>::new(), t.take());
+
+    // new syn reopens the connection
+    t.send(30, ClientToServer, SYN, 1000, 0, 0);
+    assert_eq!(vec![TcpTrackerEvent::New], t.take());
+    assert_eq!((SynSent, Listen), t.states());
+}
+
+#[test]
+fn anomalies() {
+    use TcpAnomaly::*;
+
+    // flag combinations
+    for (flags, anomaly) in [
+        (Flags{ syn: true, fin: true, ..Default::default() }, SynFin),
+        (Flags{ syn: true, rst: true, ..Default::default() }, SynRst),
+        (Flags{ fin: true, psh: true, urg: true, ..Default::default() }, Xmas),
+        (Default::default(), NullFlags),
+    ] {
+        let mut t = Tester::new(Default::default());
+        t.send(0, ClientToServer, flags, 0, 0, 0);
+        assert_eq!(vec![TcpTrackerEvent::Anomaly(ClientToServer, anomaly)], t.take());
+        // no connection gets created
+        assert_eq!(0, t.tracker.connection_count());
+
+        // existing connections are not modified
+        t.handshake();
+        t.take();
+        t.send(20, ServerToClient, flags, 0, 0, 0);
+        assert_eq!(vec![TcpTrackerEvent::Anomaly(ServerToClient, anomaly)], t.take());
+        assert_eq!((Established, Established), t.states());
+    }
+
+    // data before handshake
+    {
+        let mut t = Tester::new(Default::default());
+        t.send(0, ClientToServer, SYN, 100, 0, 10);
+        t.send(1, ClientToServer, ACK, 101, 0, 10);
+        assert_eq!(
+            vec![
+                TcpTrackerEvent::New,
+                TcpTrackerEvent::Anomaly(ClientToServer, DataBeforeHandshake),
+            ],
+            t.take()
+        );
+    }
+}
+
+#[test]
+fn midstream() {
+    // allowed
+    {
+        let mut t = Tester::new(Default::default());
+        t.send(0, ClientToServer, FIN, 100, 500, 0);
+        assert_eq!(
+            vec![
+                TcpTrackerEvent::New,
+                TcpTrackerEvent::HalfClosed(ClientToServer),
+            ],
+            t.take()
+        );
+        assert_eq!((FinWait1, CloseWait), t.states());
+    }
+    // not allowed
+    {
+        let mut t = Tester::new(TcpTrackerConfig{
+            allow_midstream: false,
+            ..Default::default()
+        });
+        t.send(0, ClientToServer, ACK, 100, 500, 0);
+        assert_eq!(0, t.tracker.connection_count());
+        assert_eq!(Vec::<TcpTrackerEvent>::new(), t.take());
+    }
+}
+
+#[test]
+fn max_connections() {
+    let mut t = Tester::new(TcpTrackerConfig{
+        max_connections: 0,
+        ..Default::default()
+    });
+    t.send(0, ClientToServer, SYN, 100, 0, 0);
+    assert_eq!(0, t.tracker.connection_count());
+    assert_eq!(Vec::<TcpTrackerEvent>::new(), t.take());
+}
+
+#[test]
+fn expire() {
+    let config = TcpTrackerConfig{
+        established_timeout: Duration::from_secs(100),
+        transitory_timeout: Duration::from_secs(10),
+        time_wait_timeout: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let mut events = Vec::new();
+
+    // handshake (transitory timeout)
+    {
+        let mut t = Tester::new(config.clone());
+        t.send(0, ClientToServer, SYN, 100, 0, 0);
+        t.tracker.expire(Duration::from_secs(10), &mut |key, event| events.push((*key, event)));
+        assert_eq!(1, t.tracker.connection_count());
+        t.tracker.expire(Duration::from_millis(10_001), &mut |key, event| events.push((*key, event)));
+        assert_eq!(0, t.tracker.connection_count());
+        assert_eq!(vec![(KEY, TcpTrackerEvent::TimedOut)], events);
+        events.clear();
+    }
+
+    // established
+    {
+        let mut t = Tester::new(config.clone());
+        t.handshake();
+        t.tracker.expire(Duration::from_secs(100), &mut |key, event| events.push((*key, event)));
+        assert_eq!(1, t.tracker.connection_count());
+        t.tracker.expire(Duration::from_secs(101), &mut |key, event| events.push((*key, event)));
+        assert_eq!(0, t.tracker.connection_count());
+        assert_eq!(vec![(KEY, TcpTrackerEvent::TimedOut)], events);
+        events.clear();
+    }
+
+    // closed connections are removed silently
+    {
+        let mut t = Tester::new(config);
+        t.handshake();
+        t.send(20, ServerToClient, RST, 501, 0, 0);
+        t.tracker.expire(Duration::from_millis(1_020), &mut |key, event| events.push((*key, event)));
+        assert_eq!(1, t.tracker.connection_count());
+        t.tracker.expire(Duration::from_millis(1_021), &mut |key, event| events.push((*key, event)));
+        assert_eq!(0, t.tracker.connection_count());
+        assert!(events.is_empty());
+    }
+}
+
+#[test]
+fn process_sliced() {
+    let mut tracker = TcpConnectionTracker::default();
+    let mut events = Vec::new();
+
+    // tcp packet
+    {
+        let builder = PacketBuilder::ipv4([10,0,0,1], [10,0,0,2], 20)
+            .tcp(1234, 80, 0, 1024)
+            .syn();
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(tracker.process_sliced(Duration::from_secs(1), &sliced, &mut |key, event| {
+            events.push((*key, event))
+        }));
+        assert_eq!(vec![(KEY, TcpTrackerEvent::New)], events);
+    }
+
+    // udp packet
+    {
+        let builder = PacketBuilder::ipv4([10,0,0,1], [10,0,0,2], 20)
+            .udp(1234, 80);
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(!tracker.process_sliced(Duration::from_secs(1), &sliced, &mut |_, _| {}));
+    }
+}
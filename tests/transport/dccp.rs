@@ -0,0 +1,577 @@
+use super::super::*;
+use proptest::prelude::*;
+use std::io::Cursor;
+
+use etherparse::dccp::*;
+
+#[test]
+fn constants() {
+    // packet types according to
+    // https://datatracker.ietf.org/doc/html/rfc4340#section-5.1
+    assert_eq!(0, TYPE_REQUEST);
+    assert_eq!(1, TYPE_RESPONSE);
+    assert_eq!(2, TYPE_DATA);
+    assert_eq!(3, TYPE_ACK);
+    assert_eq!(4, TYPE_DATA_ACK);
+    assert_eq!(5, TYPE_CLOSE_REQ);
+    assert_eq!(6, TYPE_CLOSE);
+    assert_eq!(7, TYPE_RESET);
+    assert_eq!(8, TYPE_SYNC);
+    assert_eq!(9, TYPE_SYNC_ACK);
+
+    // options according to
+    // https://datatracker.ietf.org/doc/html/rfc4340#section-5.8
+    assert_eq!(0, OPTION_PADDING);
+    assert_eq!(1, OPTION_MANDATORY);
+    assert_eq!(2, OPTION_SLOW_RECEIVER);
+    assert_eq!(32, OPTION_CHANGE_L);
+    assert_eq!(33, OPTION_CONFIRM_L);
+    assert_eq!(34, OPTION_CHANGE_R);
+    assert_eq!(35, OPTION_CONFIRM_R);
+    assert_eq!(36, OPTION_INIT_COOKIE);
+    assert_eq!(37, OPTION_NDP_COUNT);
+    assert_eq!(38, OPTION_ACK_VECTOR_NONCE_0);
+    assert_eq!(39, OPTION_ACK_VECTOR_NONCE_1);
+    assert_eq!(40, OPTION_DATA_DROPPED);
+    assert_eq!(41, OPTION_TIMESTAMP);
+    assert_eq!(42, OPTION_TIMESTAMP_ECHO);
+    assert_eq!(43, OPTION_ELAPSED_TIME);
+    assert_eq!(44, OPTION_DATA_CHECKSUM);
+
+    // reset codes according to
+    // https://datatracker.ietf.org/doc/html/rfc4340#section-5.6
+    assert_eq!(0, RESET_CODE_UNSPECIFIED);
+    assert_eq!(1, RESET_CODE_CLOSED);
+    assert_eq!(2, RESET_CODE_ABORTED);
+    assert_eq!(3, RESET_CODE_NO_CONNECTION);
+    assert_eq!(4, RESET_CODE_PACKET_ERROR);
+    assert_eq!(5, RESET_CODE_OPTION_ERROR);
+    assert_eq!(6, RESET_CODE_MANDATORY_ERROR);
+    assert_eq!(7, RESET_CODE_CONNECTION_REFUSED);
+    assert_eq!(8, RESET_CODE_BAD_SERVICE_CODE);
+    assert_eq!(9, RESET_CODE_TOO_BUSY);
+    assert_eq!(10, RESET_CODE_BAD_INIT_COOKIE);
+    assert_eq!(11, RESET_CODE_AGGRESSION_PENALTY);
+
+    assert_eq!(0xff_ffff, MAX_SHORT_SEQUENCE_NUMBER);
+    assert_eq!(0xffff_ffff_ffff, MAX_EXTENDED_SEQUENCE_NUMBER);
+}
+
+/// DCCP-Request with an extended sequence number of 1234 & service code 42
+/// (ipv4 192.168.1.1 -> 192.168.1.2, checksum calculated with an
+/// independent implementation).
+const REQUEST_PACKET: [u8;20] = [
+    0x13, 0x89, 0x13, 0x8a, 5, 0, 0x4a, 0x67,
+    1, 0, 0, 0, 0, 0, 0x04, 0xd2,
+    0, 0, 0, 42,
+];
+
+mod dccp_sequence_number {
+    use super::*;
+
+    #[test]
+    fn value() {
+        assert_eq!(123, DccpSequenceNumber::Short(123).value());
+        assert_eq!(0xffff_ffff_ffff, DccpSequenceNumber::Extended(0xffff_ffff_ffff).value());
+    }
+
+    #[test]
+    fn is_extended() {
+        assert!(!DccpSequenceNumber::Short(0).is_extended());
+        assert!(DccpSequenceNumber::Extended(0).is_extended());
+    }
+}
+
+mod dccp_packet_type {
+    use super::*;
+
+    #[test]
+    fn type_value_and_acknowledgement_number() {
+        use DccpPacketType::*;
+        let tests = [
+            (TYPE_REQUEST, None, Request{ service_code: 1 }),
+            (TYPE_RESPONSE, Some(2), Response{ acknowledgement_number: 2, service_code: 1 }),
+            (TYPE_DATA, None, Data),
+            (TYPE_ACK, Some(3), Ack{ acknowledgement_number: 3 }),
+            (TYPE_DATA_ACK, Some(4), DataAck{ acknowledgement_number: 4 }),
+            (TYPE_CLOSE_REQ, Some(5), CloseReq{ acknowledgement_number: 5 }),
+            (TYPE_CLOSE, Some(6), Close{ acknowledgement_number: 6 }),
+            (TYPE_RESET, Some(7), Reset{ acknowledgement_number: 7, reset_code: 1, data: [1,2,3] }),
+            (TYPE_SYNC, Some(8), Sync{ acknowledgement_number: 8 }),
+            (TYPE_SYNC_ACK, Some(9), SyncAck{ acknowledgement_number: 9 }),
+            (12, None, Reserved(12)),
+        ];
+        for (type_value, ack, packet_type) in tests {
+            assert_eq!(type_value, packet_type.type_value());
+            assert_eq!(ack, packet_type.acknowledgement_number());
+        }
+    }
+}
+
+mod dccp_options_iterator {
+    use super::*;
+
+    #[test]
+    fn next() {
+        // valid options
+        {
+            let data = [
+                OPTION_PADDING,
+                OPTION_SLOW_RECEIVER,
+                OPTION_TIMESTAMP, 6, 1, 2, 3, 4,
+                OPTION_CHANGE_L, 2,
+            ];
+            let mut iter = DccpOptionsIterator::from_slice(&data);
+            assert_eq!(DccpOption{ option_type: OPTION_PADDING, data: &[] }, iter.next().unwrap().unwrap());
+            assert_eq!(DccpOption{ option_type: OPTION_SLOW_RECEIVER, data: &[] }, iter.next().unwrap().unwrap());
+            assert_eq!(DccpOption{ option_type: OPTION_TIMESTAMP, data: &[1,2,3,4] }, iter.next().unwrap().unwrap());
+            assert_eq!(&data[8..], iter.rest());
+            assert_eq!(DccpOption{ option_type: OPTION_CHANGE_L, data: &[] }, iter.next().unwrap().unwrap());
+            assert!(iter.next().is_none());
+        }
+
+        // missing length
+        {
+            let mut iter = DccpOptionsIterator::from_slice(&[OPTION_TIMESTAMP]);
+            assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(2))));
+            assert!(iter.next().is_none());
+        }
+
+        // length too small
+        {
+            let mut iter = DccpOptionsIterator::from_slice(&[OPTION_TIMESTAMP, 1, 0, 0]);
+            assert_matches!(iter.next(), Some(Err(ReadError::DccpOptionLengthTooSmall(1))));
+            assert!(iter.next().is_none());
+        }
+
+        // length bigger then the slice
+        {
+            let mut iter = DccpOptionsIterator::from_slice(&[OPTION_TIMESTAMP, 6, 0, 0]);
+            assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(6))));
+            assert!(iter.next().is_none());
+        }
+    }
+}
+
+mod dccp_header {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let header = DccpHeader::new(
+            1,
+            2,
+            DccpSequenceNumber::Short(3),
+            DccpPacketType::Data
+        );
+        assert_eq!(1, header.source_port);
+        assert_eq!(2, header.destination_port);
+        assert_eq!(0, header.ccval);
+        assert_eq!(0, header.checksum_coverage);
+        assert_eq!(0, header.checksum);
+        assert_eq!(DccpSequenceNumber::Short(3), header.sequence_number);
+        assert_eq!(DccpPacketType::Data, header.packet_type);
+        assert_eq!(0, header.options().len());
+        assert_eq!(12, header.header_len());
+    }
+
+    #[test]
+    fn header_len() {
+        use DccpPacketType::*;
+        let tests = [
+            (12, 16, Data),
+            (16, 24, Ack{ acknowledgement_number: 0 }),
+            (16, 20, Request{ service_code: 0 }),
+            (20, 28, Response{ acknowledgement_number: 0, service_code: 0 }),
+            (20, 28, Reset{ acknowledgement_number: 0, reset_code: 0, data: [0;3] }),
+            (12, 16, Reserved(15)),
+        ];
+        for (short_len, extended_len, packet_type) in tests {
+            let mut header = DccpHeader::new(0, 0, DccpSequenceNumber::Short(0), packet_type);
+            assert_eq!(short_len, header.fixed_header_len());
+            assert_eq!(short_len, header.header_len());
+            header.sequence_number = DccpSequenceNumber::Extended(0);
+            assert_eq!(extended_len, header.fixed_header_len());
+            header.set_options_raw(&[1,2,3,4]).unwrap();
+            assert_eq!(extended_len + 4, header.header_len());
+        }
+    }
+
+    #[test]
+    fn set_options_raw() {
+        let mut header = DccpHeader::new(0, 0, DccpSequenceNumber::Extended(0), DccpPacketType::Data);
+
+        // ok
+        header.set_options_raw(&[OPTION_TIMESTAMP, 6, 1, 2, 3, 4, 0, 0]).unwrap();
+        assert_eq!(&[OPTION_TIMESTAMP, 6, 1, 2, 3, 4, 0, 0], header.options());
+        assert_eq!(
+            DccpOptionsIterator::from_slice(&[OPTION_TIMESTAMP, 6, 1, 2, 3, 4, 0, 0]),
+            header.options_iterator()
+        );
+
+        // maximum length
+        let max = [0u8;DccpHeader::MAX_LEN - 16];
+        header.set_options_raw(&max).unwrap();
+        assert_eq!(DccpHeader::MAX_LEN, header.header_len());
+
+        // not a multiple of 4
+        assert_eq!(
+            Err(ValueError::DccpOptionsLengthBad(3)),
+            header.set_options_raw(&[0,0,0])
+        );
+
+        // too big
+        let too_big = [0u8;DccpHeader::MAX_LEN - 12];
+        assert_eq!(
+            Err(ValueError::DccpOptionsLengthBad(too_big.len())),
+            header.set_options_raw(&too_big)
+        );
+        // previous value is kept
+        assert_eq!(DccpHeader::MAX_LEN, header.header_len());
+    }
+
+    #[test]
+    fn check_ranges() {
+        let base = DccpHeader::new(0, 0, DccpSequenceNumber::Short(0), DccpPacketType::Ack{ acknowledgement_number: 0 });
+        assert_eq!(Ok(()), base.check_ranges());
+
+        // ccval
+        {
+            let mut header = base.clone();
+            header.ccval = 0x10;
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: 0x10, max: 0xf, field: ErrorField::DccpCcVal }),
+                header.check_ranges()
+            );
+        }
+        // checksum coverage
+        {
+            let mut header = base.clone();
+            header.checksum_coverage = 0x10;
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: 0x10, max: 0xf, field: ErrorField::DccpChecksumCoverage }),
+                header.check_ranges()
+            );
+        }
+        // short sequence number
+        {
+            let mut header = base.clone();
+            header.sequence_number = DccpSequenceNumber::Short(0x100_0000);
+            assert_eq!(
+                Err(ValueError::U32TooLarge{ value: 0x100_0000, max: 0xff_ffff, field: ErrorField::DccpSequenceNumber }),
+                header.check_ranges()
+            );
+        }
+        // extended sequence number
+        {
+            let mut header = base.clone();
+            header.sequence_number = DccpSequenceNumber::Extended(0x1_0000_0000_0000);
+            assert_eq!(
+                Err(ValueError::U64TooLarge{ value: 0x1_0000_0000_0000, max: 0xffff_ffff_ffff, field: ErrorField::DccpSequenceNumber }),
+                header.check_ranges()
+            );
+        }
+        // acknowledgement number (uses the size of the sequence number)
+        {
+            let mut header = base.clone();
+            header.packet_type = DccpPacketType::Ack{ acknowledgement_number: 0x100_0000 };
+            assert_eq!(
+                Err(ValueError::U64TooLarge{ value: 0x100_0000, max: 0xff_ffff, field: ErrorField::DccpAcknowledgementNumber }),
+                header.check_ranges()
+            );
+            header.sequence_number = DccpSequenceNumber::Extended(0);
+            assert_eq!(Ok(()), header.check_ranges());
+        }
+        // options too long after changing the packet type
+        {
+            let mut header = DccpHeader::new(0, 0, DccpSequenceNumber::Extended(0), DccpPacketType::Data);
+            header.set_options_raw(&[0u8;DccpHeader::MAX_LEN - 16]).unwrap();
+            header.packet_type = DccpPacketType::Ack{ acknowledgement_number: 0 };
+            assert_eq!(
+                Err(ValueError::DccpOptionsLengthBad(DccpHeader::MAX_LEN - 16)),
+                header.check_ranges()
+            );
+            assert_matches!(
+                header.write(&mut Vec::new()),
+                Err(WriteError::ValueError(ValueError::DccpOptionsLengthBad(_)))
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum_ipv4() {
+        let header = DccpHeader::new(
+            5001,
+            5002,
+            DccpSequenceNumber::Extended(1234),
+            DccpPacketType::Request{ service_code: 42 }
+        );
+        assert_eq!(
+            Ok(0x4a67),
+            header.calc_checksum_ipv4_raw([192,168,1,1], [192,168,1,2], &[])
+        );
+        assert_eq!(
+            Ok(0x4a67),
+            header.calc_checksum_ipv4(
+                &Ipv4Header::new(20, 5, ip_number::DCCP, [192,168,1,1], [192,168,1,2]),
+                &[]
+            )
+        );
+
+        // range error
+        {
+            let mut header = header.clone();
+            header.ccval = 0x10;
+            assert_matches!(
+                header.calc_checksum_ipv4_raw([0;4], [0;4], &[]),
+                Err(ValueError::U8TooLarge{ .. })
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum_ipv4_payload_too_big() {
+        let header = DccpHeader::new(0, 0, DccpSequenceNumber::Short(0), DccpPacketType::Data);
+        let len = (u16::MAX as usize) - 12 + 1;
+        let payload = vec![0u8;len];
+        assert_eq!(
+            Err(ValueError::DccpLengthTooLarge(u16::MAX as usize + 1)),
+            header.calc_checksum_ipv4_raw([0;4], [0;4], &payload)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv6(
+            source in any::<[u8;16]>(),
+            destination in any::<[u8;16]>(),
+            header in dccp_header_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let result = header.calc_checksum_ipv6_raw(source, destination, &payload);
+            let covered_len = if 0 == header.checksum_coverage {
+                payload.len()
+            } else {
+                usize::from(header.checksum_coverage - 1)*4
+            };
+            if covered_len > payload.len() {
+                assert_eq!(
+                    Err(ValueError::DccpChecksumCoverageBad(header.checksum_coverage)),
+                    result
+                );
+            } else {
+                let bytes = {
+                    let mut header = header.clone();
+                    header.checksum = 0;
+                    let mut buffer = Vec::new();
+                    header.write(&mut buffer).unwrap();
+                    buffer
+                };
+                let expected = ::etherparse::checksum::Sum16BitWords::new()
+                    .add_16bytes(source)
+                    .add_16bytes(destination)
+                    .add_4bytes(((bytes.len() + payload.len()) as u32).to_be_bytes())
+                    .add_4bytes([0, 0, 0, ip_number::DCCP])
+                    .add_slice(&bytes)
+                    .add_slice(&payload[..covered_len])
+                    .ones_complement()
+                    .to_be();
+                assert_eq!(Ok(expected), result);
+                let ip_header = Ipv6Header{
+                    source,
+                    destination,
+                    ..Default::default()
+                };
+                assert_eq!(Ok(expected), header.calc_checksum_ipv6(&ip_header, &payload));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn read_write_from_slice(
+            header in dccp_header_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(header.header_len(), buffer.len());
+            buffer.extend_from_slice(&dummy_data);
+
+            // from_slice
+            {
+                let (actual, rest) = DccpHeader::from_slice(&buffer).unwrap();
+                assert_eq!(actual, header);
+                assert_eq!(rest, &dummy_data[..]);
+            }
+
+            // read
+            {
+                let mut cursor = Cursor::new(&buffer);
+                assert_eq!(header, DccpHeader::read(&mut cursor).unwrap());
+                assert_eq!(header.header_len() as u64, cursor.position());
+            }
+
+            // length errors
+            for len in 0..header.header_len() {
+                assert_matches!(
+                    DccpHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(_))
+                );
+                assert_matches!(
+                    DccpHeader::read(&mut Cursor::new(&buffer[..len])),
+                    Err(ReadError::IoError(_))
+                );
+            }
+
+            // write error
+            {
+                let mut a = [0u8;11];
+                assert_matches!(
+                    header.write(&mut Cursor::new(&mut a[..])),
+                    Err(WriteError::IoError(_))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn debug() {
+        let header = DccpHeader::new(1, 2, DccpSequenceNumber::Short(3), DccpPacketType::Data);
+        assert_eq!(
+            "DccpHeader { source_port: 1, destination_port: 2, ccval: 0, checksum_coverage: 0, checksum: 0, sequence_number: Short(3), packet_type: Data, options: [] }",
+            format!("{:?}", header)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in dccp_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
+}
+
+mod dccp_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(header in dccp_header_any()) {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+
+            let slice = DccpHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(slice.slice(), &buffer[..]);
+            assert_eq!(slice.source_port(), header.source_port);
+            assert_eq!(slice.destination_port(), header.destination_port);
+            assert_eq!(usize::from(slice.data_offset())*4, header.header_len());
+            assert_eq!(slice.ccval(), header.ccval);
+            assert_eq!(slice.checksum_coverage(), header.checksum_coverage);
+            assert_eq!(slice.checksum(), header.checksum);
+            assert_eq!(slice.packet_type_value(), header.packet_type.type_value());
+            assert_eq!(slice.extended_sequence_numbers(), header.sequence_number.is_extended());
+            assert_eq!(slice.sequence_number(), header.sequence_number);
+            assert_eq!(slice.packet_type(), header.packet_type);
+            assert_eq!(slice.options(), header.options());
+            assert_eq!(slice.options_iterator(), header.options_iterator());
+            assert_eq!(slice.to_header(), header);
+        }
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        // generic header too short
+        assert_matches!(
+            DccpHeaderSlice::from_slice(&REQUEST_PACKET[..11]),
+            Err(ReadError::UnexpectedEndOfSlice(12))
+        );
+        // extended generic header too short
+        assert_matches!(
+            DccpHeaderSlice::from_slice(&REQUEST_PACKET[..15]),
+            Err(ReadError::UnexpectedEndOfSlice(16))
+        );
+        // data offset too small for the packet type
+        {
+            let mut data = REQUEST_PACKET;
+            data[4] = 4;
+            assert_matches!(
+                DccpHeaderSlice::from_slice(&data),
+                Err(ReadError::DccpDataOffsetTooSmall(4))
+            );
+        }
+        // data offset bigger then the slice
+        {
+            let mut data = REQUEST_PACKET;
+            data[4] = 6;
+            assert_matches!(
+                DccpHeaderSlice::from_slice(&data),
+                Err(ReadError::UnexpectedEndOfSlice(24))
+            );
+        }
+    }
+}
+
+#[test]
+fn packet_slicing() {
+    let ip = Ipv4Header::new(
+        REQUEST_PACKET.len() as u16,
+        5,
+        ip_number::DCCP,
+        [192,168,1,1],
+        [192,168,1,2]
+    );
+    let mut buffer = Vec::new();
+    ip.write(&mut buffer).unwrap();
+    buffer.extend_from_slice(&REQUEST_PACKET);
+
+    let expected = {
+        let mut header = DccpHeader::new(
+            5001,
+            5002,
+            DccpSequenceNumber::Extended(1234),
+            DccpPacketType::Request{ service_code: 42 }
+        );
+        header.checksum = 0x4a67;
+        header
+    };
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip(&buffer).unwrap();
+        match sliced.transport {
+            Some(TransportSlice::Dccp(header)) => {
+                assert_eq!(header.to_header(), expected);
+            },
+            _ => panic!("expected DCCP slice"),
+        }
+        assert_eq!(0, sliced.payload.len());
+    }
+
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        assert_eq!(decoded.transport, Some(TransportHeader::Dccp(expected)));
+    }
+}
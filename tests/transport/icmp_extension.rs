@@ -0,0 +1,556 @@
+use super::super::*;
+use proptest::prelude::*;
+
+use etherparse::icmp_extension::*;
+
+/// Serializes an extension object with the given class-num, c-type & payload.
+fn object_bytes(class_num: u8, c_type: u8, payload: &[u8]) -> Vec<u8> {
+    let len = (4 + payload.len()) as u16;
+    let mut result = Vec::with_capacity(usize::from(len));
+    result.extend_from_slice(&len.to_be_bytes());
+    result.push(class_num);
+    result.push(c_type);
+    result.extend_from_slice(payload);
+    result
+}
+
+/// Serializes an extension structure (including a valid checksum) with the given objects.
+fn extensions_bytes(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut result = vec![VERSION << 4, 0, 0, 0];
+    for o in objects {
+        result.extend_from_slice(o);
+    }
+    let checksum = ::etherparse::checksum::Sum16BitWords::new()
+        .add_slice(&result)
+        .ones_complement()
+        .to_be()
+        .to_be_bytes();
+    result[2] = checksum[0];
+    result[3] = checksum[1];
+    result
+}
+
+#[test]
+fn constants() {
+    assert_eq!(2, VERSION);
+    assert_eq!(128, MIN_ORIGINAL_DATAGRAM_LEN);
+    assert_eq!(1, CLASS_NUM_MPLS_LABEL_STACK);
+    assert_eq!(2, CLASS_NUM_INTERFACE_INFORMATION);
+    assert_eq!(1, C_TYPE_MPLS_INCOMING_LABEL_STACK);
+    assert_eq!(1, AFI_IPV4);
+    assert_eq!(2, AFI_IPV6);
+    assert_eq!(64, MAX_INTERFACE_NAME_SUB_OBJECT_LEN);
+}
+
+mod mpls_label_stack_entry {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn to_bytes_from_bytes(
+            label in 0..=MplsLabelStackEntry::MAX_LABEL,
+            traffic_class in 0..=MplsLabelStackEntry::MAX_TRAFFIC_CLASS,
+            bottom_of_stack in any::<bool>(),
+            ttl in any::<u8>(),
+        ) {
+            let entry = MplsLabelStackEntry{ label, traffic_class, bottom_of_stack, ttl };
+            let bytes = entry.to_bytes();
+            let expected = (label << 12) | (u32::from(traffic_class) << 9) | (u32::from(bottom_of_stack) << 8) | u32::from(ttl);
+            assert_eq!(expected.to_be_bytes(), bytes);
+            assert_eq!(entry, MplsLabelStackEntry::from_bytes(bytes));
+        }
+    }
+
+    #[test]
+    fn to_bytes_ignores_out_of_range_bits() {
+        let entry = MplsLabelStackEntry{
+            label: 0xfff0_0000,
+            traffic_class: 0b1111_1000,
+            bottom_of_stack: false,
+            ttl: 0,
+        };
+        assert_eq!([0, 0, 0, 0], entry.to_bytes());
+    }
+
+    #[test]
+    fn iterator() {
+        let first = MplsLabelStackEntry{ label: 16, traffic_class: 1, bottom_of_stack: false, ttl: 1 };
+        let second = MplsLabelStackEntry{ label: 0x12345, traffic_class: 0, bottom_of_stack: true, ttl: 255 };
+        let mut bytes = first.to_bytes().to_vec();
+        bytes.extend_from_slice(&second.to_bytes());
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let iter = MplsLabelStackIterator::from_slice(&bytes);
+        assert_eq!(iter.clone(), iter);
+        assert_eq!(vec![first, second], iter.collect::<Vec<_>>());
+        assert_eq!(None, MplsLabelStackIterator::from_slice(&[]).next());
+    }
+}
+
+#[test]
+fn interface_role() {
+    use InterfaceRole::*;
+    for (c_type_bits, expected) in [
+        (0u8, IncomingIpInterface),
+        (1, SubIpComponentOfIncomingIpInterface),
+        (2, OutgoingIpInterface),
+        (3, IpNextHop),
+    ] {
+        // lower bits should be ignored
+        assert_eq!(expected, InterfaceRole::from_c_type((c_type_bits << 6) | 0b11_1111));
+        assert_eq!(expected, InterfaceRole::from_c_type(c_type_bits << 6));
+        assert_eq!(c_type_bits, expected.value());
+    }
+}
+
+mod interface_information {
+    use super::*;
+
+    const IF_INDEX: u8 = InterfaceInformation::C_TYPE_IF_INDEX_BIT;
+    const IP_ADDRESS: u8 = InterfaceInformation::C_TYPE_IP_ADDRESS_BIT;
+    const NAME: u8 = InterfaceInformation::C_TYPE_NAME_BIT;
+    const MTU: u8 = InterfaceInformation::C_TYPE_MTU_BIT;
+
+    #[test]
+    fn constants() {
+        assert_eq!(0b1000, IF_INDEX);
+        assert_eq!(0b100, IP_ADDRESS);
+        assert_eq!(0b10, NAME);
+        assert_eq!(0b1, MTU);
+    }
+
+    #[test]
+    fn from_slice_all_fields() {
+        let payload = [
+            0, 0, 0, 5, // if index
+            0, 1, 0, 0, 192, 168, 1, 1, // ipv4 address
+            8, b'e', b't', b'h', b'0', 0, 0, 0, // name
+            0, 0, 0x05, 0xdc, // mtu
+        ];
+        let c_type = (2 << 6) | IF_INDEX | IP_ADDRESS | NAME | MTU;
+        let actual = InterfaceInformation::from_slice(c_type, &payload).unwrap();
+        assert_eq!(
+            InterfaceInformation{
+                role: InterfaceRole::OutgoingIpInterface,
+                if_index: Some(5),
+                ip_address: Some(InterfaceIpAddress::Ipv4([192, 168, 1, 1])),
+                name: Some(b"eth0"),
+                mtu: Some(1500),
+            },
+            actual
+        );
+        assert_eq!(c_type, actual.c_type());
+        assert_eq!(actual.clone(), actual);
+        assert!(!format!("{:?}", actual).is_empty());
+    }
+
+    #[test]
+    fn from_slice_single_fields() {
+        // nothing present
+        {
+            let actual = InterfaceInformation::from_slice(3 << 6, &[]).unwrap();
+            assert_eq!(
+                InterfaceInformation{
+                    role: InterfaceRole::IpNextHop,
+                    if_index: None,
+                    ip_address: None,
+                    name: None,
+                    mtu: None,
+                },
+                actual
+            );
+            assert_eq!(3 << 6, actual.c_type());
+        }
+        // if index
+        {
+            let actual = InterfaceInformation::from_slice(IF_INDEX, &[1, 2, 3, 4]).unwrap();
+            assert_eq!(Some(0x0102_0304), actual.if_index);
+            assert_eq!(IF_INDEX, actual.c_type());
+        }
+        // ipv6 address
+        {
+            let mut payload = vec![0, 2, 0, 0];
+            payload.extend_from_slice(&[1; 16]);
+            let actual = InterfaceInformation::from_slice(IP_ADDRESS, &payload).unwrap();
+            assert_eq!(Some(InterfaceIpAddress::Ipv6([1; 16])), actual.ip_address);
+            assert_eq!(IP_ADDRESS, actual.c_type());
+        }
+        // name without padding
+        {
+            let actual = InterfaceInformation::from_slice(NAME, &[4, b'l', b'o', b'0']).unwrap();
+            assert_eq!(Some(&b"lo0"[..]), actual.name);
+            assert_eq!(NAME, actual.c_type());
+        }
+        // mtu
+        {
+            let actual = InterfaceInformation::from_slice(MTU, &[0, 0, 0x23, 0x28]).unwrap();
+            assert_eq!(Some(9000), actual.mtu);
+            assert_eq!(MTU, actual.c_type());
+        }
+    }
+
+    #[test]
+    fn from_slice_bad() {
+        let tests: [(u8, &[u8]); 12] = [
+            // too short if index
+            (IF_INDEX, &[1, 2, 3]),
+            // too short ip address header
+            (IP_ADDRESS, &[0, 1, 0]),
+            // too short ipv4 address
+            (IP_ADDRESS, &[0, 1, 0, 0, 1, 2, 3]),
+            // too short ipv6 address
+            (IP_ADDRESS, &[0, 2, 0, 0, 1, 2, 3, 4]),
+            // unknown address family
+            (IP_ADDRESS, &[0, 3, 0, 0, 1, 2, 3, 4]),
+            // missing name
+            (NAME, &[]),
+            // name length zero
+            (NAME, &[0, 0, 0, 0]),
+            // name length not a multiple of 4
+            (NAME, &[3, b'a', b'b', 0]),
+            // name length bigger then the payload
+            (NAME, &[8, b'a', b'b', b'c']),
+            // too short mtu
+            (MTU, &[1, 2, 3]),
+            // trailing data
+            (MTU, &[1, 2, 3, 4, 5]),
+            (0, &[1]),
+        ];
+        for (c_type, payload) in tests {
+            assert_eq!(None, InterfaceInformation::from_slice(c_type, payload));
+        }
+
+        // name longer then allowed
+        let mut payload = vec![68];
+        payload.resize(68, b'a');
+        assert_eq!(None, InterfaceInformation::from_slice(NAME, &payload));
+    }
+}
+
+mod icmp_extensions_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let bytes = extensions_bytes(&[
+            object_bytes(CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK, &[0, 1, 1, 1]),
+        ]);
+
+        // ok
+        {
+            let actual = IcmpExtensionsSlice::from_slice(&bytes).unwrap();
+            assert_eq!(&bytes[..], actual.slice());
+            assert_eq!(2, actual.version());
+            assert_eq!(u16::from_be_bytes([bytes[2], bytes[3]]), actual.checksum());
+            assert!(actual.is_checksum_valid());
+            assert_eq!(actual.clone(), actual);
+        }
+
+        // bad checksum
+        {
+            let mut bytes = bytes.clone();
+            bytes[3] = !bytes[3];
+            assert!(!IcmpExtensionsSlice::from_slice(&bytes).unwrap().is_checksum_valid());
+        }
+
+        // version error
+        for version in 0..16u8 {
+            if version != VERSION {
+                let mut bytes = bytes.clone();
+                bytes[0] = version << 4;
+                assert_matches!(
+                    IcmpExtensionsSlice::from_slice(&bytes),
+                    Err(ReadError::IcmpExtensionUnexpectedVersion(v)) if v == version
+                );
+            }
+        }
+
+        // too short
+        for len in 0..4 {
+            assert_matches!(
+                IcmpExtensionsSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(4))
+            );
+        }
+    }
+
+    #[test]
+    fn objects() {
+        let first = object_bytes(CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK, &[0, 1, 1, 1]);
+        let second = object_bytes(CLASS_NUM_INTERFACE_INFORMATION, 0, &[]);
+        let bytes = extensions_bytes(&[first.clone(), second.clone()]);
+
+        // valid objects
+        {
+            let extensions = IcmpExtensionsSlice::from_slice(&bytes).unwrap();
+            let mut iter = extensions.objects();
+            assert_eq!(&bytes[4..], iter.rest());
+            assert_eq!(&first[..], iter.next().unwrap().unwrap().slice());
+            assert_eq!(&second[..], iter.rest());
+            assert_eq!(&second[..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert!(iter.rest().is_empty());
+        }
+
+        // no objects
+        {
+            let bytes = extensions_bytes(&[]);
+            let extensions = IcmpExtensionsSlice::from_slice(&bytes).unwrap();
+            assert!(extensions.objects().next().is_none());
+        }
+
+        // error (iteration ends after the error)
+        {
+            let mut bytes = bytes.clone();
+            bytes.extend_from_slice(&[0, 8, 1]);
+            let extensions = IcmpExtensionsSlice::from_slice(&bytes).unwrap();
+            let mut iter = extensions.objects();
+            assert!(iter.next().unwrap().is_ok());
+            assert!(iter.next().unwrap().is_ok());
+            assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(4))));
+            assert!(iter.next().is_none());
+        }
+    }
+}
+
+mod icmp_extension_object_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let bytes = object_bytes(123, 234, &[1, 2, 3, 4]);
+        let mut with_rest = bytes.clone();
+        with_rest.extend_from_slice(&[5, 6]);
+
+        // ok
+        {
+            let actual = IcmpExtensionObjectSlice::from_slice(&with_rest).unwrap();
+            assert_eq!(&bytes[..], actual.slice());
+            assert_eq!(8, actual.length());
+            assert_eq!(123, actual.class_num());
+            assert_eq!(234, actual.c_type());
+            assert_eq!(&[1, 2, 3, 4], actual.payload());
+            assert_eq!(
+                IcmpExtensionObject::Unknown{
+                    class_num: 123,
+                    c_type: 234,
+                    payload: &[1, 2, 3, 4],
+                },
+                actual.object()
+            );
+            assert_eq!(actual.clone(), actual);
+        }
+
+        // too short for header
+        for len in 0..4 {
+            assert_matches!(
+                IcmpExtensionObjectSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(4))
+            );
+        }
+
+        // too short for payload
+        for len in 4..bytes.len() {
+            assert_matches!(
+                IcmpExtensionObjectSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+
+        // length too small
+        for length in 0..4u16 {
+            let mut bytes = bytes.clone();
+            bytes[0] = 0;
+            bytes[1] = length as u8;
+            assert_matches!(
+                IcmpExtensionObjectSlice::from_slice(&bytes),
+                Err(ReadError::IcmpExtensionObjectLengthTooSmall(l)) if l == length
+            );
+        }
+    }
+
+    #[test]
+    fn object() {
+        // mpls label stack
+        {
+            let entry = MplsLabelStackEntry{ label: 16, traffic_class: 0, bottom_of_stack: true, ttl: 1 };
+            let bytes = object_bytes(CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK, &entry.to_bytes());
+            let actual = IcmpExtensionObjectSlice::from_slice(&bytes).unwrap();
+            match actual.object() {
+                IcmpExtensionObject::MplsLabelStack(iter) => {
+                    assert_eq!(vec![entry], iter.collect::<Vec<_>>());
+                },
+                value => panic!("unexpected object {:?}", value),
+            }
+        }
+        // mpls label stack with bad length
+        {
+            let bytes = object_bytes(CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK, &[1, 2, 3]);
+            let actual = IcmpExtensionObjectSlice::from_slice(&bytes).unwrap();
+            assert_matches!(actual.object(), IcmpExtensionObject::Unknown{ class_num: 1, c_type: 1, .. });
+        }
+        // mpls with unknown c-type
+        {
+            let bytes = object_bytes(CLASS_NUM_MPLS_LABEL_STACK, 2, &[1, 2, 3, 4]);
+            let actual = IcmpExtensionObjectSlice::from_slice(&bytes).unwrap();
+            assert_matches!(actual.object(), IcmpExtensionObject::Unknown{ class_num: 1, c_type: 2, .. });
+        }
+        // interface information
+        {
+            let c_type = InterfaceInformation::C_TYPE_MTU_BIT;
+            let bytes = object_bytes(CLASS_NUM_INTERFACE_INFORMATION, c_type, &[0, 0, 5, 0xdc]);
+            let actual = IcmpExtensionObjectSlice::from_slice(&bytes).unwrap();
+            assert_eq!(
+                IcmpExtensionObject::InterfaceInformation(InterfaceInformation{
+                    role: InterfaceRole::IncomingIpInterface,
+                    if_index: None,
+                    ip_address: None,
+                    name: None,
+                    mtu: Some(1500),
+                }),
+                actual.object()
+            );
+        }
+        // interface information with bad payload
+        {
+            let bytes = object_bytes(CLASS_NUM_INTERFACE_INFORMATION, 0, &[0, 0, 5, 0xdc]);
+            let actual = IcmpExtensionObjectSlice::from_slice(&bytes).unwrap();
+            assert_eq!(
+                IcmpExtensionObject::Unknown{
+                    class_num: CLASS_NUM_INTERFACE_INFORMATION,
+                    c_type: 0,
+                    payload: &[0, 0, 5, 0xdc],
+                },
+                actual.object()
+            );
+        }
+    }
+}
+
+mod icmpv4_slice {
+    use super::*;
+
+    /// Returns an ICMPv4 packet with the given type, length attribute, original datagram & extensions.
+    fn packet(type_u8: u8, length: u8, original: &[u8], extensions: &[u8]) -> Vec<u8> {
+        let mut result = vec![type_u8, 0, 0, 0, 0, length, 0, 0];
+        result.extend_from_slice(original);
+        result.extend_from_slice(extensions);
+        result
+    }
+
+    #[test]
+    fn extensions() {
+        use etherparse::icmpv4::*;
+
+        let original = [0xabu8; 128];
+        let extensions = extensions_bytes(&[
+            object_bytes(CLASS_NUM_MPLS_LABEL_STACK, C_TYPE_MPLS_INCOMING_LABEL_STACK, &[0, 1, 1, 1]),
+        ]);
+
+        for type_u8 in [TYPE_DEST_UNREACH, TYPE_TIME_EXCEEDED, TYPE_PARAMETER_PROBLEM] {
+            // with extensions
+            {
+                let bytes = packet(type_u8, 32, &original, &extensions);
+                let slice = Icmpv4Slice::from_slice(&bytes).unwrap();
+                assert_eq!(Some(32), slice.length_attribute());
+                assert_eq!(&original[..], slice.original_datagram());
+                let actual = slice.extensions().unwrap().unwrap();
+                assert_eq!(&extensions[..], actual.slice());
+            }
+            // length attribute zero (no extensions)
+            {
+                let bytes = packet(type_u8, 0, &original, &extensions);
+                let slice = Icmpv4Slice::from_slice(&bytes).unwrap();
+                assert_eq!(Some(0), slice.length_attribute());
+                assert_eq!(slice.payload(), slice.original_datagram());
+                assert!(slice.extensions().unwrap().is_none());
+            }
+            // truncated original datagram
+            {
+                let bytes = packet(type_u8, 32, &original[..64], &[]);
+                let slice = Icmpv4Slice::from_slice(&bytes).unwrap();
+                assert_eq!(&original[..64], slice.original_datagram());
+                assert!(slice.extensions().unwrap().is_none());
+            }
+            // bad extension header
+            {
+                let bytes = packet(type_u8, 32, &original, &[0x10, 0, 0, 0]);
+                let slice = Icmpv4Slice::from_slice(&bytes).unwrap();
+                assert_matches!(
+                    slice.extensions(),
+                    Err(ReadError::IcmpExtensionUnexpectedVersion(1))
+                );
+            }
+        }
+
+        // types without extension support
+        {
+            let bytes = packet(TYPE_ECHO_REPLY, 32, &original, &extensions);
+            let slice = Icmpv4Slice::from_slice(&bytes).unwrap();
+            assert_eq!(None, slice.length_attribute());
+            assert_eq!(slice.payload(), slice.original_datagram());
+            assert!(slice.extensions().unwrap().is_none());
+        }
+    }
+}
+
+mod icmpv6_slice {
+    use super::*;
+
+    /// Returns an ICMPv6 packet with the given type, length attribute, original datagram & extensions.
+    fn packet(type_u8: u8, length: u8, original: &[u8], extensions: &[u8]) -> Vec<u8> {
+        let mut result = vec![type_u8, 0, 0, 0, length, 0, 0, 0];
+        result.extend_from_slice(original);
+        result.extend_from_slice(extensions);
+        result
+    }
+
+    #[test]
+    fn extensions() {
+        use etherparse::icmpv6::*;
+
+        let original = [0xabu8; 128];
+        let extensions = extensions_bytes(&[
+            object_bytes(CLASS_NUM_INTERFACE_INFORMATION, 0, &[]),
+        ]);
+
+        for type_u8 in [TYPE_DST_UNREACH, TYPE_TIME_EXCEEDED] {
+            // with extensions
+            {
+                let bytes = packet(type_u8, 16, &original, &extensions);
+                let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+                assert_eq!(Some(16), slice.length_attribute());
+                assert_eq!(&original[..], slice.original_datagram());
+                let actual = slice.extensions().unwrap().unwrap();
+                assert_eq!(&extensions[..], actual.slice());
+                assert!(actual.is_checksum_valid());
+            }
+            // length attribute zero (no extensions)
+            {
+                let bytes = packet(type_u8, 0, &original, &extensions);
+                let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+                assert_eq!(Some(0), slice.length_attribute());
+                assert_eq!(slice.payload(), slice.original_datagram());
+                assert!(slice.extensions().unwrap().is_none());
+            }
+            // too short extension header
+            {
+                let bytes = packet(type_u8, 16, &original, &[0x20, 0]);
+                let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+                assert_matches!(
+                    slice.extensions(),
+                    Err(ReadError::UnexpectedEndOfSlice(4))
+                );
+            }
+        }
+
+        // types without extension support
+        {
+            let bytes = packet(TYPE_PARAMETER_PROBLEM, 16, &original, &extensions);
+            let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+            assert_eq!(None, slice.length_attribute());
+            assert_eq!(slice.payload(), slice.original_datagram());
+            assert!(slice.extensions().unwrap().is_none());
+        }
+    }
+}
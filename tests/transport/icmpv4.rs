@@ -1108,6 +1108,34 @@ mod icmpv4_header {
             format!("Icmpv4Header {{ icmp_type: {:?}, checksum: {:?} }}", header.icmp_type, header.checksum)
         );
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in icmpv4_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 mod icmpv4_slice {
@@ -1753,7 +1781,7 @@ mod icmpv4_regression {
         use TransportSlice::*;
         let icmp4 = match echo.transport.unwrap() {
             Icmpv4(icmp4) => icmp4,
-            Icmpv6(_) | Udp(_) | Tcp(_) | Unknown(_) => panic!("Misparsed header!"),
+            Icmpv6(_) | Igmp(_) | Sctp(_) | Udp(_) | Tcp(_) | UdpLite(_) | Dccp(_) | Unknown(_) => panic!("Misparsed header!"),
         };
         assert!(matches!(icmp4.icmp_type(), Icmpv4Type::EchoRequest(_)));
     }
@@ -25,6 +25,7 @@ fn constants() {
     assert_eq!(138, TYPE_ROUTER_RENUMBERING);
     assert_eq!(141, TYPE_INVERSE_NEIGHBOR_DISCOVERY_SOLICITATION);
     assert_eq!(142, TYPE_INVERSE_NEIGHBOR_DISCOVERY_ADVERTISEMENT);
+    assert_eq!(143, TYPE_MULTICAST_LISTENER_REPORT_V2);
     assert_eq!(160, TYPE_EXT_ECHO_REQUEST);
     assert_eq!(161, TYPE_EXT_ECHO_REPLY);
 
@@ -59,6 +60,15 @@ fn constants() {
     assert_eq!(8, CODE_PARAM_PROBLEM_TOO_MANY_EXT_HEADERS);
     assert_eq!(9, CODE_PARAM_PROBLEM_TOO_MANY_OPTIONS_EXT_HEADER);
     assert_eq!(10, CODE_PARAM_PROBLEM_OPTION_TOO_BIG);
+
+    // mldv2 multicast address record types according to
+    // https://datatracker.ietf.org/doc/html/rfc3810#section-5.2.12
+    assert_eq!(1, MLDV2_RECORD_MODE_IS_INCLUDE);
+    assert_eq!(2, MLDV2_RECORD_MODE_IS_EXCLUDE);
+    assert_eq!(3, MLDV2_RECORD_CHANGE_TO_INCLUDE_MODE);
+    assert_eq!(4, MLDV2_RECORD_CHANGE_TO_EXCLUDE_MODE);
+    assert_eq!(5, MLDV2_RECORD_ALLOW_NEW_SOURCES);
+    assert_eq!(6, MLDV2_RECORD_BLOCK_OLD_SOURCES);
 }
 
 mod dest_unreachable_code {
@@ -379,7 +389,7 @@ mod icmpv6_type {
             code_u8 in any::<u8>(),
             bytes5to8 in any::<[u8;4]>(),
             // max length is u32::MAX - header_len (7)
-            bad_len in (std::u32::MAX - 7) as usize..=std::usize::MAX,
+            bad_len in (std::u32::MAX - 7) as usize..=(isize::MAX as usize),
             payload in proptest::collection::vec(any::<u8>(), 0..64)
         ) {
             use Icmpv6Type::*;
@@ -484,7 +494,7 @@ mod icmpv6_type {
             ip_header in ipv6_any(),
             icmpv6_type in icmpv6_type_any(),
             // max length is u32::MAX - header_len (7)
-            bad_len in (std::u32::MAX - 7) as usize..=std::usize::MAX,
+            bad_len in (std::u32::MAX - 7) as usize..=(isize::MAX as usize),
             payload in proptest::collection::vec(any::<u8>(), 0..1024)
         ) {
             // size error case
@@ -638,7 +648,7 @@ mod icmpv6_header {
             ip_header in ipv6_any(),
             icmp_type in icmpv6_type_any(),
             // max length is u32::MAX - header_len (7)
-            bad_len in (std::u32::MAX - 7) as usize..=std::usize::MAX,
+            bad_len in (std::u32::MAX - 7) as usize..=(isize::MAX as usize),
             payload in proptest::collection::vec(any::<u8>(), 0..1024)
         ) {
 
@@ -806,7 +816,7 @@ mod icmpv6_header {
             icmp_type in icmpv6_type_any(),
             start_checksum in any::<u16>(),
             // max length is u32::MAX - header_len (7)
-            bad_len in (std::u32::MAX - 7) as usize..=std::usize::MAX,
+            bad_len in (std::u32::MAX - 7) as usize..=(isize::MAX as usize),
             payload in proptest::collection::vec(any::<u8>(), 0..1024)
         ) {
 
@@ -989,6 +999,34 @@ mod icmpv6_header {
             assert_eq!(header, header.clone());
         }
     }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in icmpv6_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 }
 
 mod icmpv6_slice {
@@ -1016,7 +1054,7 @@ mod icmpv6_slice {
         #[cfg(not(any(target_pointer_width = "16", target_pointer_width = "32")))]
         #[test]
         fn from_slice_too_big_error(
-            bad_len in ((std::u32::MAX as usize) + 1)..=std::usize::MAX,
+            bad_len in ((std::u32::MAX as usize) + 1)..=(isize::MAX as usize),
         ) {
             // too large packet error case
             {
@@ -1416,6 +1454,487 @@ mod icmpv6_slice {
     }
 }
 
+mod mld {
+    use super::*;
+
+    const SOURCE: [u8;16] = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    const DESTINATION: [u8;16] = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x16];
+
+    #[test]
+    fn query_v2_header_flags_u8() {
+        let mut header = MulticastListenerQueryV2Header{
+            maximum_response_code: 0,
+            multicast_address: [0;16],
+            suppress_router_side_processing: false,
+            querier_robustness_variable: 0,
+            querier_query_interval_code: 0,
+            number_of_sources: 0,
+        };
+        assert_eq!(0, header.flags_u8());
+        header.querier_robustness_variable = 2;
+        assert_eq!(2, header.flags_u8());
+        header.suppress_router_side_processing = true;
+        assert_eq!(0b1010, header.flags_u8());
+        // bits outside of the 3 bit qrv are ignored
+        header.querier_robustness_variable = 0xff;
+        assert_eq!(0b1111, header.flags_u8());
+    }
+
+    #[test]
+    fn query_v2_header_maximum_response_delay() {
+        let mut header = MulticastListenerQueryV2Header{
+            maximum_response_code: 0,
+            multicast_address: [0;16],
+            suppress_router_side_processing: false,
+            querier_robustness_variable: 0,
+            querier_query_interval_code: 0,
+            number_of_sources: 0,
+        };
+        for (code, expected) in [
+            (0u16, 0u32),
+            (10_000, 10_000),
+            (0x7fff, 0x7fff),
+            (0x8000, 0x1000 << 3),
+            (0x8001, 0x1001 << 3),
+            (0xffff, 0x1fff << 10),
+        ] {
+            header.maximum_response_code = code;
+            assert_eq!(expected, header.maximum_response_delay());
+        }
+    }
+
+    #[test]
+    fn query_v2_header_querier_query_interval() {
+        let mut header = MulticastListenerQueryV2Header{
+            maximum_response_code: 0,
+            multicast_address: [0;16],
+            suppress_router_side_processing: false,
+            querier_robustness_variable: 0,
+            querier_query_interval_code: 0,
+            number_of_sources: 0,
+        };
+        for (code, expected) in [
+            (0u8, 0u32),
+            (125, 125),
+            (0x7f, 0x7f),
+            (0x80, 0x10 << 3),
+            (0x81, 0x11 << 3),
+            (0xff, 0x1f << 10),
+        ] {
+            header.querier_query_interval_code = code;
+            assert_eq!(expected, header.querier_query_interval());
+        }
+    }
+
+    #[test]
+    fn record_type() {
+        use MulticastAddressRecordType::*;
+        assert_eq!(None, MulticastAddressRecordType::from_u8(0));
+        for (value, expected) in [
+            (MLDV2_RECORD_MODE_IS_INCLUDE, ModeIsInclude),
+            (MLDV2_RECORD_MODE_IS_EXCLUDE, ModeIsExclude),
+            (MLDV2_RECORD_CHANGE_TO_INCLUDE_MODE, ChangeToIncludeMode),
+            (MLDV2_RECORD_CHANGE_TO_EXCLUDE_MODE, ChangeToExcludeMode),
+            (MLDV2_RECORD_ALLOW_NEW_SOURCES, AllowNewSources),
+            (MLDV2_RECORD_BLOCK_OLD_SOURCES, BlockOldSources),
+        ] {
+            assert_eq!(Some(expected), MulticastAddressRecordType::from_u8(value));
+            assert_eq!(value, expected.type_u8());
+        }
+        for value in 7..=u8::MAX {
+            assert_eq!(None, MulticastAddressRecordType::from_u8(value));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn record_header_to_bytes_write(
+            record_type in any::<u8>(),
+            aux_data_len in any::<u8>(),
+            number_of_sources in any::<u16>(),
+            multicast_address in any::<[u8;16]>(),
+        ) {
+            let header = MulticastAddressRecordHeader{
+                record_type,
+                aux_data_len,
+                number_of_sources,
+                multicast_address,
+            };
+            let n = number_of_sources.to_be_bytes();
+            let mut expected = vec![record_type, aux_data_len, n[0], n[1]];
+            expected.extend_from_slice(&multicast_address);
+            assert_eq!(&expected[..], &header.to_bytes()[..]);
+            assert_eq!(
+                20 + usize::from(number_of_sources)*16 + usize::from(aux_data_len)*4,
+                header.record_len()
+            );
+
+            // write
+            let mut buffer = Vec::with_capacity(20);
+            header.write(&mut buffer).unwrap();
+            assert_eq!(expected, buffer);
+
+            // write error
+            let mut too_small = [0u8;19];
+            let mut cursor = std::io::Cursor::new(&mut too_small[..]);
+            assert!(header.write(&mut cursor).unwrap_err().io_error().is_some());
+        }
+    }
+
+    /// Serializes a record with the given sources & aux data.
+    fn record_bytes(record_type: u8, address: [u8;16], sources: &[[u8;16]], aux_data: &[u8]) -> Vec<u8> {
+        let header = MulticastAddressRecordHeader{
+            record_type,
+            aux_data_len: (aux_data.len() / 4) as u8,
+            number_of_sources: sources.len() as u16,
+            multicast_address: address,
+        };
+        let mut result = header.to_bytes().to_vec();
+        for s in sources {
+            result.extend_from_slice(s);
+        }
+        result.extend_from_slice(aux_data);
+        result
+    }
+
+    #[test]
+    fn record_slice_from_slice() {
+        let sources = [[1u8;16], [2u8;16]];
+        let aux = [9u8, 8, 7, 6];
+        let bytes = record_bytes(MLDV2_RECORD_ALLOW_NEW_SOURCES, DESTINATION, &sources, &aux);
+        let mut with_rest = bytes.clone();
+        with_rest.extend_from_slice(&[0xaa, 0xbb]);
+
+        // ok
+        {
+            let record = MulticastAddressRecordSlice::from_slice(&with_rest).unwrap();
+            assert_eq!(&bytes[..], record.slice());
+            assert_eq!(MLDV2_RECORD_ALLOW_NEW_SOURCES, record.record_type_u8());
+            assert_eq!(Some(MulticastAddressRecordType::AllowNewSources), record.record_type());
+            assert_eq!(1, record.aux_data_len());
+            assert_eq!(2, record.number_of_sources());
+            assert_eq!(DESTINATION, record.multicast_address());
+            assert_eq!(sources.to_vec(), record.sources().collect::<Vec<_>>());
+            assert_eq!(&aux[..], record.aux_data());
+            assert_eq!(
+                MulticastAddressRecordHeader{
+                    record_type: MLDV2_RECORD_ALLOW_NEW_SOURCES,
+                    aux_data_len: 1,
+                    number_of_sources: 2,
+                    multicast_address: DESTINATION,
+                },
+                record.to_header()
+            );
+            assert_eq!(record.clone(), record);
+        }
+
+        // unknown record type
+        {
+            let mut bytes = bytes.clone();
+            bytes[0] = 123;
+            let record = MulticastAddressRecordSlice::from_slice(&bytes).unwrap();
+            assert_eq!(None, record.record_type());
+            assert_eq!(123, record.record_type_u8());
+        }
+
+        // too short for the static part
+        for len in 0..20 {
+            assert_matches!(
+                MulticastAddressRecordSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(20))
+            );
+        }
+
+        // too short for sources & aux data
+        for len in 20..bytes.len() {
+            assert_matches!(
+                MulticastAddressRecordSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(l)) if l == bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn record_iterator() {
+        let first = record_bytes(MLDV2_RECORD_MODE_IS_INCLUDE, [1;16], &[[2;16]], &[]);
+        let second = record_bytes(MLDV2_RECORD_MODE_IS_EXCLUDE, [3;16], &[], &[4,4,4,4,5,5,5,5]);
+        let mut bytes = first.clone();
+        bytes.extend_from_slice(&second);
+        bytes.extend_from_slice(&[0xee]);
+
+        // all records
+        {
+            let mut iter = MulticastAddressRecordIterator::new(2, &bytes);
+            assert_eq!(&first[..], iter.next().unwrap().unwrap().slice());
+            assert_eq!(&second[..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert_eq!(&[0xee], iter.rest());
+        }
+
+        // fewer records then present
+        {
+            let mut iter = MulticastAddressRecordIterator::new(1, &bytes);
+            assert_eq!(&first[..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert_eq!(&bytes[first.len()..], iter.rest());
+        }
+
+        // no records
+        assert!(MulticastAddressRecordIterator::new(0, &bytes).next().is_none());
+
+        // more records then present (error & end of iteration afterwards)
+        {
+            let mut iter = MulticastAddressRecordIterator::new(3, &bytes);
+            assert!(iter.next().unwrap().is_ok());
+            assert!(iter.next().unwrap().is_ok());
+            assert_matches!(
+                iter.next(),
+                Some(Err(ReadError::UnexpectedEndOfSlice(20)))
+            );
+            assert!(iter.next().is_none());
+        }
+    }
+
+    #[test]
+    fn source_address_iterator() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[1u8;16]);
+        bytes.extend_from_slice(&[2u8;16]);
+        bytes.extend_from_slice(&[3u8;15]);
+        assert_eq!(
+            vec![[1u8;16], [2u8;16]],
+            SourceAddressIterator::from_slice(&bytes).collect::<Vec<_>>()
+        );
+        assert_eq!(None, SourceAddressIterator::from_slice(&[]).next());
+    }
+
+    #[test]
+    fn icmpv6_type_methods() {
+        use Icmpv6Type::*;
+        let v1 = MulticastListenerHeader{
+            maximum_response_delay: 1000,
+            multicast_address: DESTINATION,
+        };
+        let v2 = MulticastListenerQueryV2Header{
+            maximum_response_code: 1000,
+            multicast_address: DESTINATION,
+            suppress_router_side_processing: true,
+            querier_robustness_variable: 2,
+            querier_query_interval_code: 125,
+            number_of_sources: 3,
+        };
+        // note: queries with a payload are decoded as MLDv2 queries
+        // by Icmpv6Slice, so no payload is added for MLDv1 queries
+        for (value, type_u8, header_len, fixed_payload_size, payload) in [
+            (MulticastListenerQuery(v1), 130, 24, Some(0), &[][..]),
+            (MulticastListenerReport(v1), 131, 24, Some(0), &[1,2,3,4][..]),
+            (MulticastListenerDone(v1), 132, 24, Some(0), &[1,2,3,4][..]),
+            (MulticastListenerQueryV2(v2), 130, 28, None, &[1,2,3,4][..]),
+            (MulticastListenerReportV2{ number_of_records: 5 }, 143, 8, None, &[1,2,3,4][..]),
+        ] {
+            assert_eq!(type_u8, value.type_u8());
+            assert_eq!(0, value.code_u8());
+            assert_eq!(header_len, value.header_len());
+            assert_eq!(fixed_payload_size, value.fixed_payload_size());
+
+            // checksum equals the one calculated over the raw bytes
+            let header = Icmpv6Header::with_checksum(value, SOURCE, DESTINATION, payload).unwrap();
+            let mut bytes = header.to_bytes().to_vec();
+            assert_eq!(header_len, bytes.len());
+            bytes.extend_from_slice(payload);
+            let raw = Icmpv6Slice::from_slice(&bytes).unwrap();
+            assert!(raw.is_checksum_valid(SOURCE, DESTINATION));
+            assert_eq!(value, raw.icmp_type());
+            assert_eq!(header, raw.header());
+            assert_eq!(header_len, raw.header_len());
+            assert_eq!(payload, raw.payload());
+        }
+    }
+
+    #[test]
+    fn to_bytes() {
+        let header = Icmpv6Header{
+            icmp_type: Icmpv6Type::MulticastListenerReport(MulticastListenerHeader{
+                maximum_response_delay: 0x1234,
+                multicast_address: DESTINATION,
+            }),
+            checksum: 0xabcd,
+        };
+        let mut expected = vec![131, 0, 0xab, 0xcd, 0x12, 0x34, 0, 0];
+        expected.extend_from_slice(&DESTINATION);
+        assert_eq!(&expected[..], &header.to_bytes()[..]);
+
+        let header = Icmpv6Header{
+            icmp_type: Icmpv6Type::MulticastListenerQueryV2(MulticastListenerQueryV2Header{
+                maximum_response_code: 0x1234,
+                multicast_address: DESTINATION,
+                suppress_router_side_processing: true,
+                querier_robustness_variable: 2,
+                querier_query_interval_code: 125,
+                number_of_sources: 0x0102,
+            }),
+            checksum: 0xabcd,
+        };
+        let mut expected = vec![130, 0, 0xab, 0xcd, 0x12, 0x34, 0, 0];
+        expected.extend_from_slice(&DESTINATION);
+        expected.extend_from_slice(&[0b1010, 125, 1, 2]);
+        assert_eq!(&expected[..], &header.to_bytes()[..]);
+
+        let header = Icmpv6Header{
+            icmp_type: Icmpv6Type::MulticastListenerReportV2{ number_of_records: 0x0102 },
+            checksum: 0xabcd,
+        };
+        assert_eq!(&[143, 0, 0xab, 0xcd, 0, 0, 1, 2], &header.to_bytes()[..]);
+    }
+
+    #[test]
+    fn read() {
+        let v1 = Icmpv6Header{
+            icmp_type: Icmpv6Type::MulticastListenerDone(MulticastListenerHeader{
+                maximum_response_delay: 0,
+                multicast_address: DESTINATION,
+            }),
+            checksum: 0x1234,
+        };
+        let bytes = v1.to_bytes();
+        let mut cursor = std::io::Cursor::new(&bytes[..]);
+        assert_eq!(v1, Icmpv6Header::read(&mut cursor).unwrap());
+        assert_eq!(24, cursor.position());
+
+        // too short for the mld data (decoded as unknown, same as from_slice)
+        let mut cursor = std::io::Cursor::new(&bytes[..23]);
+        assert_eq!(
+            Icmpv6Header::from_slice(&bytes[..23]).unwrap().0,
+            Icmpv6Header::read(&mut cursor).unwrap()
+        );
+
+        // too short for the icmpv6 header
+        let mut cursor = std::io::Cursor::new(&bytes[..7]);
+        assert!(Icmpv6Header::read(&mut cursor).unwrap_err().io_error().is_some());
+
+        // v2 reports contain no additional static data
+        let v2 = Icmpv6Header{
+            icmp_type: Icmpv6Type::MulticastListenerReportV2{ number_of_records: 3 },
+            checksum: 0x1234,
+        };
+        let bytes = v2.to_bytes();
+        let mut cursor = std::io::Cursor::new(&bytes[..]);
+        assert_eq!(v2, Icmpv6Header::read(&mut cursor).unwrap());
+        assert_eq!(8, cursor.position());
+    }
+
+    #[test]
+    fn slice_too_short_fallback() {
+        // messages too short for the mld data are decoded as unknown
+        for type_u8 in [130u8, 131, 132] {
+            let mut bytes = [0u8;24];
+            bytes[0] = type_u8;
+            for len in 8..24 {
+                let slice = Icmpv6Slice::from_slice(&bytes[..len]).unwrap();
+                assert_eq!(
+                    Icmpv6Type::Unknown{
+                        type_u8,
+                        code_u8: 0,
+                        bytes5to8: [0;4],
+                    },
+                    slice.icmp_type()
+                );
+                assert_eq!(8, slice.header_len());
+
+                // same result when reading
+                let mut cursor = std::io::Cursor::new(&bytes[..len]);
+                assert_eq!(slice.header(), Icmpv6Header::read(&mut cursor).unwrap());
+                assert_eq!(
+                    Icmpv6Header::from_slice(&bytes[..len]).unwrap().0,
+                    slice.header()
+                );
+            }
+        }
+
+        // a non zero code is not decoded as mld
+        let mut bytes = [0u8;28];
+        bytes[0] = 130;
+        bytes[1] = 1;
+        let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+        assert_eq!(
+            Icmpv6Type::Unknown{
+                type_u8: 130,
+                code_u8: 1,
+                bytes5to8: [0;4],
+            },
+            slice.icmp_type()
+        );
+        assert_eq!(8, slice.header_len());
+        assert!(slice.mldv2_query_sources().is_none());
+    }
+
+    #[test]
+    fn slice_mldv2_records() {
+        let first = record_bytes(MLDV2_RECORD_CHANGE_TO_INCLUDE_MODE, [1;16], &[[2;16]], &[]);
+        let second = record_bytes(MLDV2_RECORD_BLOCK_OLD_SOURCES, [3;16], &[[4;16], [5;16]], &[]);
+        let mut payload = first.clone();
+        payload.extend_from_slice(&second);
+
+        let header = Icmpv6Header::with_checksum(
+            Icmpv6Type::MulticastListenerReportV2{ number_of_records: 2 },
+            SOURCE,
+            DESTINATION,
+            &payload
+        ).unwrap();
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&payload);
+
+        let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+        assert!(slice.is_checksum_valid(SOURCE, DESTINATION));
+        assert!(slice.mldv2_query_sources().is_none());
+        let records: Vec<_> = slice.mldv2_records().unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(2, records.len());
+        assert_eq!(&first[..], records[0].slice());
+        assert_eq!(vec![[4u8;16], [5u8;16]], records[1].sources().collect::<Vec<_>>());
+
+        // other types return none
+        let echo = Icmpv6Header::new(Icmpv6Type::EchoRequest(IcmpEchoHeader{ id: 1, seq: 2 })).to_bytes();
+        assert!(Icmpv6Slice::from_slice(&echo).unwrap().mldv2_records().is_none());
+    }
+
+    #[test]
+    fn slice_mldv2_query_sources() {
+        let query = MulticastListenerQueryV2Header{
+            maximum_response_code: 1000,
+            multicast_address: [0;16],
+            suppress_router_side_processing: false,
+            querier_robustness_variable: 2,
+            querier_query_interval_code: 125,
+            number_of_sources: 2,
+        };
+        let header = Icmpv6Header::new(Icmpv6Type::MulticastListenerQueryV2(query));
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&[1u8;16]);
+        bytes.extend_from_slice(&[2u8;16]);
+        bytes.extend_from_slice(&[3u8;16]);
+
+        // limited by the number of sources
+        {
+            let slice = Icmpv6Slice::from_slice(&bytes).unwrap();
+            assert_eq!(Icmpv6Type::MulticastListenerQueryV2(query), slice.icmp_type());
+            assert!(slice.mldv2_records().is_none());
+            assert_eq!(
+                vec![[1u8;16], [2u8;16]],
+                slice.mldv2_query_sources().unwrap().collect::<Vec<_>>()
+            );
+        }
+
+        // limited by the slice length
+        {
+            let slice = Icmpv6Slice::from_slice(&bytes[..28 + 16 + 8]).unwrap();
+            assert_eq!(
+                vec![[1u8;16]],
+                slice.mldv2_query_sources().unwrap().collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
 mod regression {
     use super::*;
 
@@ -1513,7 +2032,7 @@ mod regression {
         use TransportSlice::*;
         let icmp6 = match echo.transport.unwrap() {
             Icmpv6(icmp6) => icmp6,
-            Icmpv4(_) | Udp(_) | Tcp(_) | Unknown(_) => panic!("Misparsed header!"),
+            Icmpv4(_) | Igmp(_) | Sctp(_) | Udp(_) | Tcp(_) | UdpLite(_) | Dccp(_) | Unknown(_) => panic!("Misparsed header!"),
         };
         assert!(matches!(
             icmp6.header().icmp_type,
@@ -0,0 +1,711 @@
+use super::super::*;
+use proptest::prelude::*;
+
+use etherparse::igmp::*;
+
+#[test]
+fn constants() {
+    // type values according to
+    // https://www.iana.org/assignments/igmp-type-numbers/igmp-type-numbers.xhtml
+    assert_eq!(0x11, TYPE_MEMBERSHIP_QUERY);
+    assert_eq!(0x12, TYPE_MEMBERSHIP_REPORT_V1);
+    assert_eq!(0x16, TYPE_MEMBERSHIP_REPORT_V2);
+    assert_eq!(0x17, TYPE_LEAVE_GROUP);
+    assert_eq!(0x22, TYPE_MEMBERSHIP_REPORT_V3);
+
+    // group record types according to
+    // https://datatracker.ietf.org/doc/html/rfc3376#section-4.2.12
+    assert_eq!(1, RECORD_MODE_IS_INCLUDE);
+    assert_eq!(2, RECORD_MODE_IS_EXCLUDE);
+    assert_eq!(3, RECORD_CHANGE_TO_INCLUDE_MODE);
+    assert_eq!(4, RECORD_CHANGE_TO_EXCLUDE_MODE);
+    assert_eq!(5, RECORD_ALLOW_NEW_SOURCES);
+    assert_eq!(6, RECORD_BLOCK_OLD_SOURCES);
+}
+
+mod membership_query_v3_header {
+    use super::*;
+
+    fn query() -> MembershipQueryV3Header {
+        MembershipQueryV3Header{
+            max_response_code: 0,
+            group_address: [0;4],
+            suppress_router_side_processing: false,
+            querier_robustness_variable: 0,
+            querier_query_interval_code: 0,
+            number_of_sources: 0,
+        }
+    }
+
+    #[test]
+    fn flags_u8() {
+        let mut header = query();
+        assert_eq!(0, header.flags_u8());
+        header.querier_robustness_variable = 2;
+        assert_eq!(2, header.flags_u8());
+        header.suppress_router_side_processing = true;
+        assert_eq!(0b1010, header.flags_u8());
+        // bits outside of the 3 bit qrv are ignored
+        header.querier_robustness_variable = 0xff;
+        assert_eq!(0b1111, header.flags_u8());
+    }
+
+    #[test]
+    fn max_response_time() {
+        let mut header = query();
+        for (code, expected) in [
+            (0u8, 0u32),
+            (100, 100),
+            (0x7f, 0x7f),
+            (0x80, 0x10 << 3),
+            (0x81, 0x11 << 3),
+            (0xff, 0x1f << 10),
+        ] {
+            header.max_response_code = code;
+            assert_eq!(expected, header.max_response_time());
+        }
+    }
+
+    #[test]
+    fn querier_query_interval() {
+        let mut header = query();
+        for (code, expected) in [
+            (0u8, 0u32),
+            (125, 125),
+            (0x80, 0x10 << 3),
+            (0xff, 0x1f << 10),
+        ] {
+            header.querier_query_interval_code = code;
+            assert_eq!(expected, header.querier_query_interval());
+        }
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let header = query();
+        assert_eq!(header.clone(), header);
+        assert_eq!(
+            format!("{:?}", header),
+            "MembershipQueryV3Header { max_response_code: 0, group_address: [0, 0, 0, 0], suppress_router_side_processing: false, querier_robustness_variable: 0, querier_query_interval_code: 0, number_of_sources: 0 }"
+        );
+    }
+}
+
+#[test]
+fn group_record_type() {
+    use GroupRecordType::*;
+    assert_eq!(None, GroupRecordType::from_u8(0));
+    for (value, expected) in [
+        (RECORD_MODE_IS_INCLUDE, ModeIsInclude),
+        (RECORD_MODE_IS_EXCLUDE, ModeIsExclude),
+        (RECORD_CHANGE_TO_INCLUDE_MODE, ChangeToIncludeMode),
+        (RECORD_CHANGE_TO_EXCLUDE_MODE, ChangeToExcludeMode),
+        (RECORD_ALLOW_NEW_SOURCES, AllowNewSources),
+        (RECORD_BLOCK_OLD_SOURCES, BlockOldSources),
+    ] {
+        assert_eq!(Some(expected), GroupRecordType::from_u8(value));
+        assert_eq!(value, expected.type_u8());
+    }
+    for value in 7..=u8::MAX {
+        assert_eq!(None, GroupRecordType::from_u8(value));
+    }
+}
+
+/// Serializes a group record with the given sources & aux data.
+fn record_bytes(record_type: u8, address: [u8;4], sources: &[[u8;4]], aux_data: &[u8]) -> Vec<u8> {
+    let header = GroupRecordHeader{
+        record_type,
+        aux_data_len: (aux_data.len() / 4) as u8,
+        number_of_sources: sources.len() as u16,
+        multicast_address: address,
+    };
+    let mut result = header.to_bytes().to_vec();
+    for s in sources {
+        result.extend_from_slice(s);
+    }
+    result.extend_from_slice(aux_data);
+    result
+}
+
+mod group_record {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn header_to_bytes_write(
+            record_type in any::<u8>(),
+            aux_data_len in any::<u8>(),
+            number_of_sources in any::<u16>(),
+            multicast_address in any::<[u8;4]>(),
+        ) {
+            let header = GroupRecordHeader{
+                record_type,
+                aux_data_len,
+                number_of_sources,
+                multicast_address,
+            };
+            let n = number_of_sources.to_be_bytes();
+            let expected = [
+                record_type, aux_data_len, n[0], n[1],
+                multicast_address[0], multicast_address[1], multicast_address[2], multicast_address[3],
+            ];
+            assert_eq!(expected, header.to_bytes());
+            assert_eq!(
+                8 + usize::from(number_of_sources)*4 + usize::from(aux_data_len)*4,
+                header.record_len()
+            );
+
+            // write
+            let mut buffer = Vec::with_capacity(8);
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&expected[..], &buffer[..]);
+
+            // write error
+            let mut too_small = [0u8;7];
+            let mut cursor = std::io::Cursor::new(&mut too_small[..]);
+            assert!(header.write(&mut cursor).unwrap_err().io_error().is_some());
+        }
+    }
+
+    #[test]
+    fn slice_from_slice() {
+        let sources = [[10,0,0,1], [10,0,0,2]];
+        let aux = [9u8, 8, 7, 6];
+        let bytes = record_bytes(RECORD_ALLOW_NEW_SOURCES, [239,1,2,3], &sources, &aux);
+        let mut with_rest = bytes.clone();
+        with_rest.extend_from_slice(&[0xaa, 0xbb]);
+
+        // ok
+        {
+            let record = GroupRecordSlice::from_slice(&with_rest).unwrap();
+            assert_eq!(&bytes[..], record.slice());
+            assert_eq!(RECORD_ALLOW_NEW_SOURCES, record.record_type_u8());
+            assert_eq!(Some(GroupRecordType::AllowNewSources), record.record_type());
+            assert_eq!(1, record.aux_data_len());
+            assert_eq!(2, record.number_of_sources());
+            assert_eq!([239,1,2,3], record.multicast_address());
+            assert_eq!(sources.to_vec(), record.sources().collect::<Vec<_>>());
+            assert_eq!(&aux[..], record.aux_data());
+            assert_eq!(
+                GroupRecordHeader{
+                    record_type: RECORD_ALLOW_NEW_SOURCES,
+                    aux_data_len: 1,
+                    number_of_sources: 2,
+                    multicast_address: [239,1,2,3],
+                },
+                record.to_header()
+            );
+            assert_eq!(record.clone(), record);
+        }
+
+        // unknown record type
+        {
+            let mut bytes = bytes.clone();
+            bytes[0] = 123;
+            let record = GroupRecordSlice::from_slice(&bytes).unwrap();
+            assert_eq!(None, record.record_type());
+            assert_eq!(123, record.record_type_u8());
+        }
+
+        // too short for the static part
+        for len in 0..8 {
+            assert_matches!(
+                GroupRecordSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+
+        // too short for sources & aux data
+        for len in 8..bytes.len() {
+            assert_matches!(
+                GroupRecordSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(l)) if l == bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn iterator() {
+        let first = record_bytes(RECORD_MODE_IS_INCLUDE, [239,0,0,1], &[[1,2,3,4]], &[]);
+        let second = record_bytes(RECORD_MODE_IS_EXCLUDE, [239,0,0,2], &[], &[4,4,4,4,5,5,5,5]);
+        let mut bytes = first.clone();
+        bytes.extend_from_slice(&second);
+        bytes.extend_from_slice(&[0xee]);
+
+        // all records
+        {
+            let mut iter = GroupRecordIterator::new(2, &bytes);
+            assert_eq!(&first[..], iter.next().unwrap().unwrap().slice());
+            assert_eq!(&second[..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert_eq!(&[0xee], iter.rest());
+        }
+
+        // fewer records then present
+        {
+            let mut iter = GroupRecordIterator::new(1, &bytes);
+            assert_eq!(&first[..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert_eq!(&bytes[first.len()..], iter.rest());
+        }
+
+        // no records
+        assert!(GroupRecordIterator::new(0, &bytes).next().is_none());
+
+        // more records then present (error & end of iteration afterwards)
+        {
+            let mut iter = GroupRecordIterator::new(3, &bytes);
+            assert!(iter.next().unwrap().is_ok());
+            assert!(iter.next().unwrap().is_ok());
+            assert_matches!(
+                iter.next(),
+                Some(Err(ReadError::UnexpectedEndOfSlice(8)))
+            );
+            assert!(iter.next().is_none());
+        }
+    }
+}
+
+#[test]
+fn source_address_iterator() {
+    let bytes = [1,1,1,1, 2,2,2,2, 3,3,3];
+    assert_eq!(
+        vec![[1u8;4], [2u8;4]],
+        SourceAddressIterator::from_slice(&bytes).collect::<Vec<_>>()
+    );
+    assert_eq!(None, SourceAddressIterator::from_slice(&[]).next());
+}
+
+mod igmp_type {
+    use super::*;
+
+    fn query_v3() -> MembershipQueryV3Header {
+        MembershipQueryV3Header{
+            max_response_code: 100,
+            group_address: [239,1,2,3],
+            suppress_router_side_processing: true,
+            querier_robustness_variable: 2,
+            querier_query_interval_code: 125,
+            number_of_sources: 1,
+        }
+    }
+
+    #[test]
+    fn type_u8_header_len() {
+        use IgmpType::*;
+        let tests = [
+            (Unknown{ type_u8: 0x30, max_response_code: 1, bytes5to8: [2,3,4,5] }, 0x30, 8),
+            (MembershipQuery(MembershipQueryHeader{ max_response_time: 100, group_address: [0;4] }), 0x11, 8),
+            (MembershipQueryV3(query_v3()), 0x11, 12),
+            (MembershipReportV1{ group_address: [239,1,2,3] }, 0x12, 8),
+            (MembershipReportV2{ group_address: [239,1,2,3] }, 0x16, 8),
+            (LeaveGroup{ group_address: [239,1,2,3] }, 0x17, 8),
+            (MembershipReportV3{ number_of_records: 3 }, 0x22, 8),
+        ];
+        for (value, type_u8, header_len) in tests {
+            assert_eq!(type_u8, value.type_u8());
+            assert_eq!(header_len, value.header_len());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum(
+            igmp_type in igmp_type_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            // the checksum is the ones complement of the sum over the
+            // header (with a zero checksum) & payload
+            let mut bytes = IgmpHeader::new(igmp_type).to_bytes().to_vec();
+            bytes.extend_from_slice(&payload);
+            let expected = ::etherparse::checksum::Sum16BitWords::new()
+                .add_slice(&bytes)
+                .ones_complement()
+                .to_be();
+            assert_eq!(expected, igmp_type.calc_checksum(&payload));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn clone_eq_debug(igmp_type in igmp_type_any()) {
+            assert_eq!(igmp_type.clone(), igmp_type);
+            assert!(!format!("{:?}", igmp_type).is_empty());
+        }
+    }
+}
+
+mod igmp_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(8, IgmpHeader::MIN_SERIALIZED_SIZE);
+        assert_eq!(12, IgmpHeader::MAX_SERIALIZED_SIZE);
+    }
+
+    proptest! {
+        #[test]
+        fn new(igmp_type in igmp_type_any()) {
+            assert_eq!(
+                IgmpHeader{ igmp_type, checksum: 0 },
+                IgmpHeader::new(igmp_type)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn with_checksum_update_checksum(
+            igmp_type in igmp_type_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let header = IgmpHeader::with_checksum(igmp_type, &payload);
+            assert_eq!(igmp_type.calc_checksum(&payload), header.checksum);
+
+            let mut updated = IgmpHeader::new(igmp_type);
+            updated.update_checksum(&payload);
+            assert_eq!(header, updated);
+
+            // the result has to be a valid checksum
+            let mut bytes = header.to_bytes().to_vec();
+            bytes.extend_from_slice(&payload);
+            assert!(IgmpSlice::from_slice(&bytes).unwrap().is_checksum_valid());
+        }
+    }
+
+    #[test]
+    fn to_bytes() {
+        use IgmpType::*;
+        let tests = [
+            (
+                Unknown{ type_u8: 0x30, max_response_code: 1, bytes5to8: [2,3,4,5] },
+                &[0x30, 1, 0xab, 0xcd, 2, 3, 4, 5][..]
+            ),
+            (
+                MembershipQuery(MembershipQueryHeader{ max_response_time: 100, group_address: [239,1,2,3] }),
+                &[0x11, 100, 0xab, 0xcd, 239, 1, 2, 3][..]
+            ),
+            (
+                MembershipQueryV3(MembershipQueryV3Header{
+                    max_response_code: 100,
+                    group_address: [239,1,2,3],
+                    suppress_router_side_processing: true,
+                    querier_robustness_variable: 2,
+                    querier_query_interval_code: 125,
+                    number_of_sources: 0x0102,
+                }),
+                &[0x11, 100, 0xab, 0xcd, 239, 1, 2, 3, 0b1010, 125, 1, 2][..]
+            ),
+            (
+                MembershipReportV1{ group_address: [239,1,2,3] },
+                &[0x12, 0, 0xab, 0xcd, 239, 1, 2, 3][..]
+            ),
+            (
+                MembershipReportV2{ group_address: [239,1,2,3] },
+                &[0x16, 0, 0xab, 0xcd, 239, 1, 2, 3][..]
+            ),
+            (
+                LeaveGroup{ group_address: [239,1,2,3] },
+                &[0x17, 0, 0xab, 0xcd, 239, 1, 2, 3][..]
+            ),
+            (
+                MembershipReportV3{ number_of_records: 0x0102 },
+                &[0x22, 0, 0xab, 0xcd, 0, 0, 1, 2][..]
+            ),
+        ];
+        for (igmp_type, expected) in tests {
+            let header = IgmpHeader{ igmp_type, checksum: 0xabcd };
+            assert_eq!(expected, &header.to_bytes()[..]);
+            assert_eq!(expected.len(), header.header_len());
+
+            // write
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(expected, &buffer[..]);
+
+            // write error
+            let mut too_small = [0u8;7];
+            let mut cursor = std::io::Cursor::new(&mut too_small[..]);
+            assert_matches!(header.write(&mut cursor), Err(WriteError::IoError(_)));
+
+            // from_slice & back (payload shorter then 4 bytes so that
+            // the v1/v2 query does not get decoded as a v3 query)
+            let mut with_payload = expected.to_vec();
+            with_payload.extend_from_slice(&[1,2,3]);
+            let (decoded, rest) = IgmpHeader::from_slice(&with_payload).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&[1,2,3], rest);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in igmp_header_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..16),
+        ) {
+            let mut bytes = header.to_bytes().to_vec();
+            bytes.extend_from_slice(&payload);
+
+            // a query with 4 or more bytes of payload is decoded as
+            // an IGMPv3 query, so only check the other types
+            if let IgmpType::MembershipQuery(_) = header.igmp_type {
+                if payload.len() < 4 {
+                    let (actual, rest) = IgmpHeader::from_slice(&bytes).unwrap();
+                    assert_eq!(header, actual);
+                    assert_eq!(&payload[..], rest);
+                }
+            } else {
+                let (actual, rest) = IgmpHeader::from_slice(&bytes).unwrap();
+                assert_eq!(header, actual);
+                assert_eq!(&payload[..], rest);
+            }
+
+            // too short
+            for len in 0..8 {
+                assert_matches!(
+                    IgmpHeader::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(8))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn read(header in igmp_header_any()) {
+            let bytes = header.to_bytes();
+            let mut cursor = std::io::Cursor::new(&bytes[..]);
+            let actual = IgmpHeader::read(&mut cursor).unwrap();
+            assert_eq!(8, cursor.position());
+            match header.igmp_type {
+                IgmpType::MembershipQueryV3(q) => {
+                    // queries are always read as v1/v2 queries
+                    assert_eq!(
+                        IgmpType::MembershipQuery(MembershipQueryHeader{
+                            max_response_time: q.max_response_code,
+                            group_address: q.group_address,
+                        }),
+                        actual.igmp_type
+                    );
+                },
+                _ => assert_eq!(header, actual),
+            }
+
+            // io error
+            let mut cursor = std::io::Cursor::new(&bytes[..7]);
+            assert!(IgmpHeader::read(&mut cursor).unwrap_err().io_error().is_some());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in igmp_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
+}
+
+mod igmp_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let bytes = [0x16, 0, 0, 0, 239, 1, 2, 3];
+        assert_eq!(&bytes[..], IgmpSlice::from_slice(&bytes).unwrap().slice());
+        for len in 0..8 {
+            assert_matches!(
+                IgmpSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+    }
+
+    #[test]
+    fn accessors() {
+        let bytes = [0x30, 1, 0xab, 0xcd, 2, 3, 4, 5, 6];
+        let slice = IgmpSlice::from_slice(&bytes).unwrap();
+        assert_eq!(0x30, slice.type_u8());
+        assert_eq!(1, slice.max_response_code());
+        assert_eq!(0xabcd, slice.checksum());
+        assert_eq!([2,3,4,5], slice.bytes5to8());
+        assert_eq!(8, slice.header_len());
+        assert_eq!(&[6], slice.payload());
+        assert_eq!(
+            IgmpHeader{
+                igmp_type: IgmpType::Unknown{
+                    type_u8: 0x30,
+                    max_response_code: 1,
+                    bytes5to8: [2,3,4,5],
+                },
+                checksum: 0xabcd,
+            },
+            slice.header()
+        );
+    }
+
+    #[test]
+    fn igmp_type_query_versions() {
+        let mut bytes = [0x11, 100, 0, 0, 239, 1, 2, 3, 0b1010_1010, 125, 0, 1, 10, 0, 0, 1];
+
+        // v1/v2 query (8 bytes)
+        {
+            let slice = IgmpSlice::from_slice(&bytes[..8]).unwrap();
+            assert_eq!(
+                IgmpType::MembershipQuery(MembershipQueryHeader{
+                    max_response_time: 100,
+                    group_address: [239,1,2,3],
+                }),
+                slice.igmp_type()
+            );
+            assert_eq!(8, slice.header_len());
+            assert!(slice.query_v3_sources().is_none());
+        }
+
+        // invalid lengths between v2 & v3 (decoded as v1/v2 query)
+        for len in 9..12 {
+            let slice = IgmpSlice::from_slice(&bytes[..len]).unwrap();
+            assert_matches!(slice.igmp_type(), IgmpType::MembershipQuery(_));
+            assert_eq!(&bytes[8..len], slice.payload());
+        }
+
+        // v3 query (reserved flag bits are ignored)
+        {
+            let slice = IgmpSlice::from_slice(&bytes).unwrap();
+            assert_eq!(
+                IgmpType::MembershipQueryV3(MembershipQueryV3Header{
+                    max_response_code: 100,
+                    group_address: [239,1,2,3],
+                    suppress_router_side_processing: true,
+                    querier_robustness_variable: 2,
+                    querier_query_interval_code: 125,
+                    number_of_sources: 1,
+                }),
+                slice.igmp_type()
+            );
+            assert_eq!(12, slice.header_len());
+            assert_eq!(&[10,0,0,1], slice.payload());
+            assert_eq!(
+                vec![[10,0,0,1]],
+                slice.query_v3_sources().unwrap().collect::<Vec<_>>()
+            );
+            assert!(slice.group_records().is_none());
+        }
+
+        // v3 query with less sources present then indicated
+        {
+            bytes[11] = 2;
+            let slice = IgmpSlice::from_slice(&bytes).unwrap();
+            assert_eq!(
+                vec![[10,0,0,1]],
+                slice.query_v3_sources().unwrap().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn igmp_type_reserved_fallback() {
+        use IgmpType::*;
+        for type_u8 in [TYPE_MEMBERSHIP_REPORT_V1, TYPE_MEMBERSHIP_REPORT_V2, TYPE_LEAVE_GROUP] {
+            // reserved zero
+            {
+                let bytes = [type_u8, 0, 0, 0, 239, 1, 2, 3];
+                let expected = match type_u8 {
+                    TYPE_MEMBERSHIP_REPORT_V1 => MembershipReportV1{ group_address: [239,1,2,3] },
+                    TYPE_MEMBERSHIP_REPORT_V2 => MembershipReportV2{ group_address: [239,1,2,3] },
+                    _ => LeaveGroup{ group_address: [239,1,2,3] },
+                };
+                assert_eq!(expected, IgmpSlice::from_slice(&bytes).unwrap().igmp_type());
+            }
+            // reserved non zero
+            {
+                let bytes = [type_u8, 1, 0, 0, 239, 1, 2, 3];
+                assert_eq!(
+                    Unknown{ type_u8, max_response_code: 1, bytes5to8: [239,1,2,3] },
+                    IgmpSlice::from_slice(&bytes).unwrap().igmp_type()
+                );
+            }
+        }
+
+        // v3 report
+        assert_eq!(
+            MembershipReportV3{ number_of_records: 0x0102 },
+            IgmpSlice::from_slice(&[0x22, 0, 0, 0, 0, 0, 1, 2]).unwrap().igmp_type()
+        );
+        for bytes in [
+            [0x22, 1, 0, 0, 0, 0, 1, 2],
+            [0x22, 0, 0, 0, 1, 0, 1, 2],
+            [0x22, 0, 0, 0, 0, 1, 1, 2],
+        ] {
+            assert_matches!(
+                IgmpSlice::from_slice(&bytes).unwrap().igmp_type(),
+                Unknown{ type_u8: 0x22, .. }
+            );
+        }
+    }
+
+    #[test]
+    fn group_records() {
+        let first = record_bytes(RECORD_CHANGE_TO_EXCLUDE_MODE, [239,0,0,1], &[], &[]);
+        let second = record_bytes(RECORD_BLOCK_OLD_SOURCES, [239,0,0,2], &[[10,0,0,1], [10,0,0,2]], &[]);
+        let mut payload = first.clone();
+        payload.extend_from_slice(&second);
+
+        let header = IgmpHeader::with_checksum(
+            IgmpType::MembershipReportV3{ number_of_records: 2 },
+            &payload
+        );
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&payload);
+
+        let slice = IgmpSlice::from_slice(&bytes).unwrap();
+        assert!(slice.is_checksum_valid());
+        assert!(slice.query_v3_sources().is_none());
+        let records: Vec<_> = slice.group_records().unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(2, records.len());
+        assert_eq!(&first[..], records[0].slice());
+        assert_eq!(vec![[10,0,0,1], [10,0,0,2]], records[1].sources().collect::<Vec<_>>());
+
+        // invalid checksum
+        bytes[2] = !bytes[2];
+        assert!(!IgmpSlice::from_slice(&bytes).unwrap().is_checksum_valid());
+
+        // other types return none
+        let bytes = [0x16, 0, 0, 0, 239, 1, 2, 3];
+        assert!(IgmpSlice::from_slice(&bytes).unwrap().group_records().is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn clone_eq_debug(slice in proptest::collection::vec(any::<u8>(), 8..16)) {
+            let value = IgmpSlice::from_slice(&slice).unwrap();
+            assert_eq!(value.clone(), value);
+            assert_eq!(
+                format!("{:?}", value),
+                format!("IgmpSlice {{ slice: {:?} }}", &slice[..])
+            );
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod dccp;
 pub mod icmp;
+pub mod icmp_extension;
 pub mod icmpv4;
 pub mod icmpv6;
+pub mod igmp;
+pub mod sctp;
 pub mod udp;
+pub mod udp_lite;
 pub mod tcp;
 
 mod transport_header {
@@ -16,6 +21,10 @@ mod transport_header {
             udp in udp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             use TransportHeader::*;
             assert_eq!(
@@ -34,6 +43,22 @@ mod transport_header {
                 format!("Icmpv6({:?})", icmpv6),
                 format!("{:?}", Icmpv6(icmpv6.clone())),
             );
+            assert_eq!(
+                format!("Igmp({:?})", igmp),
+                format!("{:?}", Igmp(igmp.clone())),
+            );
+            assert_eq!(
+                format!("Sctp({:?})", sctp),
+                format!("{:?}", Sctp(sctp.clone())),
+            );
+            assert_eq!(
+                format!("UdpLite({:?})", udp_lite),
+                format!("{:?}", UdpLite(udp_lite.clone())),
+            );
+            assert_eq!(
+                format!("Dccp({:?})", dccp),
+                format!("{:?}", Dccp(dccp.clone())),
+            );
         }
     }
 
@@ -44,6 +69,10 @@ mod transport_header {
             udp in udp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             use TransportHeader::*;
             let values = [
@@ -51,6 +80,10 @@ mod transport_header {
                 Tcp(tcp),
                 Icmpv4(icmpv4),
                 Icmpv6(icmpv6),
+                Igmp(igmp),
+                Sctp(sctp),
+                UdpLite(udp_lite),
+                Dccp(dccp),
             ];
             for value in values {
                 assert_eq!(value.clone(), value);
@@ -110,6 +143,62 @@ mod transport_header {
             assert_eq!(None, TransportHeader::Udp(Default::default()).mut_icmpv6());
         }
     }
+    proptest! {
+        #[test]
+        fn igmp(igmp in igmp_header_any()) {
+            assert_eq!(Some(igmp.clone()), TransportHeader::Igmp(igmp).igmp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).igmp());
+        }
+    }
+    proptest! {
+        #[test]
+        fn mut_igmp(igmp in igmp_header_any()) {
+            assert_eq!(Some(&mut igmp.clone()), TransportHeader::Igmp(igmp).mut_igmp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).mut_igmp());
+        }
+    }
+    proptest! {
+        #[test]
+        fn sctp(sctp in sctp_header_any()) {
+            assert_eq!(Some(sctp.clone()), TransportHeader::Sctp(sctp).sctp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).sctp());
+        }
+    }
+    proptest! {
+        #[test]
+        fn mut_sctp(sctp in sctp_header_any()) {
+            assert_eq!(Some(&mut sctp.clone()), TransportHeader::Sctp(sctp).mut_sctp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).mut_sctp());
+        }
+    }
+    proptest! {
+        #[test]
+        fn udp_lite(udp_lite in udp_lite_header_any()) {
+            assert_eq!(Some(udp_lite.clone()), TransportHeader::UdpLite(udp_lite).udp_lite());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).udp_lite());
+        }
+    }
+    proptest! {
+        #[test]
+        fn mut_udp_lite(udp_lite in udp_lite_header_any()) {
+            assert_eq!(Some(&mut udp_lite.clone()), TransportHeader::UdpLite(udp_lite).mut_udp_lite());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).mut_udp_lite());
+        }
+    }
+    proptest! {
+        #[test]
+        fn dccp(dccp in dccp_header_any()) {
+            assert_eq!(Some(dccp.clone()), TransportHeader::Dccp(dccp).dccp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).dccp());
+        }
+    }
+    proptest! {
+        #[test]
+        fn mut_dccp(dccp in dccp_header_any()) {
+            assert_eq!(Some(&mut dccp.clone()), TransportHeader::Dccp(dccp).mut_dccp());
+            assert_eq!(None, TransportHeader::Udp(Default::default()).mut_dccp());
+        }
+    }
     proptest! {
         #[test]
         fn header_size(
@@ -117,6 +206,10 @@ mod transport_header {
             tcp in tcp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             assert_eq!(
                 TransportHeader::Udp(udp).header_len(), 
@@ -134,6 +227,22 @@ mod transport_header {
                 TransportHeader::Icmpv6(icmpv6.clone()).header_len(), 
                 icmpv6.header_len()
             );
+            assert_eq!(
+                TransportHeader::Igmp(igmp.clone()).header_len(), 
+                igmp.header_len()
+            );
+            assert_eq!(
+                TransportHeader::Sctp(sctp).header_len(), 
+                SctpHeader::SERIALIZED_SIZE
+            );
+            assert_eq!(
+                TransportHeader::UdpLite(udp_lite).header_len(), 
+                UdpLiteHeader::SERIALIZED_SIZE
+            );
+            assert_eq!(
+                TransportHeader::Dccp(dccp.clone()).header_len(), 
+                dccp.header_len()
+            );
         }
     }
     proptest! {
@@ -144,6 +253,10 @@ mod transport_header {
             tcp in tcp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             use TransportHeader::*;
 
@@ -218,6 +331,66 @@ mod transport_header {
                 Icmpv6(icmpv6).update_checksum_ipv4(&ipv4, &[]),
                 Err(ValueError::Icmpv6InIpv4)
             );
+
+            // igmp
+            {
+                let mut transport = Igmp(igmp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(
+                    transport.igmp().unwrap().checksum, 
+                    igmp.igmp_type.calc_checksum(&payload)
+                );
+            }
+
+            // sctp
+            {
+                let mut transport = Sctp(sctp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(
+                    transport.sctp().unwrap().checksum, 
+                    sctp.calc_checksum(&payload)
+                );
+            }
+
+            // udp lite
+            {
+                // ok case
+                {
+                    let mut udp_lite = udp_lite.clone();
+                    udp_lite.checksum_coverage = 0;
+                    let mut transport = UdpLite(udp_lite.clone());
+                    let payload = [1,2,3,4];
+                    transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                    assert_eq!(
+                        transport.udp_lite().unwrap().checksum, 
+                        udp_lite.calc_checksum_ipv4(&ipv4, &payload).unwrap()
+                    );
+                }
+                // error case (coverage bigger then the packet)
+                {
+                    let mut udp_lite = udp_lite.clone();
+                    udp_lite.checksum_coverage = 13;
+                    assert_eq!(
+                        Err(ValueError::UdpLiteChecksumCoverageBad(13)),
+                        UdpLite(udp_lite).update_checksum_ipv4(&ipv4, &[1,2,3,4])
+                    );
+                }
+            }
+
+            // dccp
+            {
+                let mut dccp = dccp.clone();
+                dccp.checksum_coverage = 0;
+                let mut transport = Dccp(dccp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(
+                    transport.dccp().unwrap().checksum, 
+                    dccp.calc_checksum_ipv4(&ipv4, &payload).unwrap()
+                );
+            }
         }
     }
 
@@ -230,6 +403,10 @@ mod transport_header {
             tcp in tcp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             use TransportHeader::*;
 
@@ -336,6 +513,110 @@ mod transport_header {
                     );
                 }
             }
+
+            // igmp
+            {
+                let mut transport = Igmp(igmp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(
+                    transport.igmp().unwrap().checksum, 
+                    igmp.igmp_type.calc_checksum(&payload)
+                );
+            }
+
+            // sctp
+            {
+                let mut transport = Sctp(sctp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(
+                    transport.sctp().unwrap().checksum, 
+                    sctp.calc_checksum(&payload)
+                );
+            }
+
+            // udp lite
+            {
+                // ok case
+                {
+                    let mut udp_lite = udp_lite.clone();
+                    udp_lite.checksum_coverage = 0;
+                    let mut transport = UdpLite(udp_lite.clone());
+                    let payload = [1,2,3,4];
+                    transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                    assert_eq!(
+                        transport.udp_lite().unwrap().checksum, 
+                        udp_lite.calc_checksum_ipv6(&ipv6, &payload).unwrap()
+                    );
+                }
+                // error case (coverage bigger then the packet)
+                {
+                    let mut udp_lite = udp_lite.clone();
+                    udp_lite.checksum_coverage = 13;
+                    assert_eq!(
+                        Err(ValueError::UdpLiteChecksumCoverageBad(13)),
+                        UdpLite(udp_lite).update_checksum_ipv6(&ipv6, &[1,2,3,4])
+                    );
+                }
+            }
+
+            // dccp
+            {
+                let mut dccp = dccp.clone();
+                dccp.checksum_coverage = 0;
+                let mut transport = Dccp(dccp.clone());
+                let payload = [1,2,3,4];
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(
+                    transport.dccp().unwrap().checksum, 
+                    dccp.calc_checksum_ipv6(&ipv6, &payload).unwrap()
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(
+            udp in udp_any(),
+            tcp in tcp_any(),
+            icmpv4 in icmpv4_header_any(),
+            icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
+        ) {
+            for header in [
+                TransportHeader::Udp(udp),
+                TransportHeader::Tcp(tcp),
+                TransportHeader::Icmpv4(icmpv4),
+                TransportHeader::Icmpv6(icmpv6),
+                TransportHeader::Igmp(igmp),
+                TransportHeader::Sctp(sctp),
+                TransportHeader::UdpLite(udp_lite),
+                TransportHeader::Dccp(dccp),
+            ] {
+                let expected = {
+                    let mut buffer = Vec::new();
+                    header.write(&mut buffer).unwrap();
+                    buffer
+                };
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = header.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+
+                // slice too small
+                for len in 0..expected.len() {
+                    let mut buffer = vec![0u8;len];
+                    assert_eq!(
+                        Some(expected.len()),
+                        header.write_to_slice(&mut buffer).unwrap_err().slice_too_small_size()
+                    );
+                }
+            }
         }
     }
 
@@ -346,6 +627,10 @@ mod transport_header {
             tcp in tcp_any(),
             icmpv4 in icmpv4_header_any(),
             icmpv6 in icmpv6_header_any(),
+            igmp in igmp_header_any(),
+            sctp in sctp_header_any(),
+            udp_lite in udp_lite_header_any(),
+            dccp in dccp_header_any(),
         ) {
             // udp
             {
@@ -447,6 +732,104 @@ mod transport_header {
                     );
                 }
             }
+
+            // igmp
+            {
+                // normal write
+                {
+                    let result_input = {
+                        let mut buffer = Vec::new();
+                        igmp.write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    let result_transport = {
+                        let mut buffer = Vec::new();
+                        TransportHeader::Igmp(igmp.clone()).write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    assert_eq!(result_input, result_transport);
+                }
+
+                // error during write
+                {
+                    let mut a: [u8;0] = [];
+                    assert_matches!(
+                        TransportHeader::Igmp(igmp.clone()).write(&mut Cursor::new(&mut a[..])),
+                        Err(WriteError::IoError(_))
+                    );
+                }
+            }
+
+            // sctp
+            {
+                // normal write
+                {
+                    let result_transport = {
+                        let mut buffer = Vec::new();
+                        TransportHeader::Sctp(sctp.clone()).write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    assert_eq!(&sctp.to_bytes()[..], &result_transport[..]);
+                }
+
+                // error during write
+                {
+                    let mut a: [u8;0] = [];
+                    assert_matches!(
+                        TransportHeader::Sctp(sctp.clone()).write(&mut Cursor::new(&mut a[..])),
+                        Err(WriteError::IoError(_))
+                    );
+                }
+            }
+
+            // udp lite
+            {
+                // normal write
+                {
+                    let result_transport = {
+                        let mut buffer = Vec::new();
+                        TransportHeader::UdpLite(udp_lite.clone()).write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    assert_eq!(&udp_lite.to_bytes()[..], &result_transport[..]);
+                }
+
+                // error during write
+                {
+                    let mut a: [u8;0] = [];
+                    assert_matches!(
+                        TransportHeader::UdpLite(udp_lite.clone()).write(&mut Cursor::new(&mut a[..])),
+                        Err(WriteError::IoError(_))
+                    );
+                }
+            }
+
+            // dccp
+            {
+                // normal write
+                {
+                    let result_input = {
+                        let mut buffer = Vec::new();
+                        dccp.write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    let result_transport = {
+                        let mut buffer = Vec::new();
+                        TransportHeader::Dccp(dccp.clone()).write(&mut buffer).unwrap();
+                        buffer
+                    };
+                    assert_eq!(result_input, result_transport);
+                }
+
+                // error during write
+                {
+                    let mut a: [u8;0] = [];
+                    assert_matches!(
+                        TransportHeader::Dccp(dccp.clone()).write(&mut Cursor::new(&mut a[..])),
+                        Err(WriteError::IoError(_))
+                    );
+                }
+            }
         }
     }
 }
\ No newline at end of file
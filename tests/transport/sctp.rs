@@ -0,0 +1,619 @@
+use super::super::*;
+use proptest::prelude::*;
+use std::io::Cursor;
+
+use etherparse::sctp::*;
+
+#[test]
+fn constants() {
+    // chunk types according to
+    // https://datatracker.ietf.org/doc/html/rfc9260#section-3.2
+    assert_eq!(0, CHUNK_TYPE_DATA);
+    assert_eq!(1, CHUNK_TYPE_INIT);
+    assert_eq!(2, CHUNK_TYPE_INIT_ACK);
+    assert_eq!(3, CHUNK_TYPE_SACK);
+    assert_eq!(4, CHUNK_TYPE_HEARTBEAT);
+    assert_eq!(5, CHUNK_TYPE_HEARTBEAT_ACK);
+    assert_eq!(6, CHUNK_TYPE_ABORT);
+    assert_eq!(7, CHUNK_TYPE_SHUTDOWN);
+    assert_eq!(8, CHUNK_TYPE_SHUTDOWN_ACK);
+    assert_eq!(9, CHUNK_TYPE_ERROR);
+    assert_eq!(10, CHUNK_TYPE_COOKIE_ECHO);
+    assert_eq!(11, CHUNK_TYPE_COOKIE_ACK);
+    assert_eq!(14, CHUNK_TYPE_SHUTDOWN_COMPLETE);
+}
+
+/// SCTP packet containing an INIT chunk (checksum calculated with an
+/// independent CRC32c implementation).
+const INIT_PACKET: [u8;32] = [
+    // common header
+    0x13, 0x88, 0x13, 0x89, 0, 0, 0, 0, 0xba, 0x5e, 0x9d, 0x09,
+    // init chunk
+    1, 0, 0, 20,
+    0x12, 0x34, 0x56, 0x78, // initiate tag
+    0, 1, 0, 0, // a_rwnd
+    0, 10, // outbound streams
+    0, 11, // inbound streams
+    0xaa, 0xbb, 0xcc, 0xdd, // initial tsn
+];
+
+mod sctp_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn new(
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            verification_tag in any::<u32>(),
+        ) {
+            assert_eq!(
+                SctpHeader{
+                    source_port,
+                    destination_port,
+                    verification_tag,
+                    checksum: 0,
+                },
+                SctpHeader::new(source_port, destination_port, verification_tag)
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum() {
+        let header = SctpHeader::new(5000, 5001, 0);
+        assert_eq!(
+            u32::from_be_bytes([0xba, 0x5e, 0x9d, 0x09]),
+            header.calc_checksum(&INIT_PACKET[12..])
+        );
+
+        // existing checksum values are ignored
+        let header = SctpHeader{
+            checksum: 1234,
+            ..header
+        };
+        assert_eq!(
+            u32::from_be_bytes([0xba, 0x5e, 0x9d, 0x09]),
+            header.calc_checksum(&INIT_PACKET[12..])
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn with_checksum_update_checksum(
+            header in sctp_header_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64)
+        ) {
+            let expected = header.calc_checksum(&payload);
+            let actual = SctpHeader::with_checksum(
+                header.source_port,
+                header.destination_port,
+                header.verification_tag,
+                &payload
+            );
+            assert_eq!(expected, actual.checksum);
+            assert!(actual.is_checksum_valid(&payload));
+
+            let mut updated = header.clone();
+            updated.update_checksum(&payload);
+            assert_eq!(actual, updated);
+        }
+    }
+
+    #[test]
+    fn is_checksum_valid() {
+        let (header, payload) = SctpHeader::from_slice(&INIT_PACKET).unwrap();
+        assert!(header.is_checksum_valid(payload));
+        assert!(!header.is_checksum_valid(&payload[1..]));
+    }
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in sctp_header_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            let mut buffer = Vec::with_capacity(12 + dummy_data.len());
+            header.write(&mut buffer).unwrap();
+            buffer.extend_from_slice(&dummy_data);
+
+            // normal
+            {
+                let (actual, rest) = SctpHeader::from_slice(&buffer).unwrap();
+                assert_eq!(header, actual);
+                assert_eq!(&dummy_data[..], rest);
+            }
+
+            // length error
+            for len in 0..SctpHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    SctpHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(SctpHeader::SERIALIZED_SIZE))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_bytes_to_bytes(header in sctp_header_any()) {
+            let bytes = header.to_bytes();
+            assert_eq!(header.source_port.to_be_bytes(), [bytes[0], bytes[1]]);
+            assert_eq!(header.destination_port.to_be_bytes(), [bytes[2], bytes[3]]);
+            assert_eq!(header.verification_tag.to_be_bytes(), [bytes[4], bytes[5], bytes[6], bytes[7]]);
+            assert_eq!(header.checksum.to_be_bytes(), [bytes[8], bytes[9], bytes[10], bytes[11]]);
+            assert_eq!(header, SctpHeader::from_bytes(bytes));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn read_write(header in sctp_header_any()) {
+            // ok
+            {
+                let mut buffer = Vec::new();
+                header.write(&mut buffer).unwrap();
+                assert_eq!(&header.to_bytes()[..], &buffer[..]);
+                let mut cursor = Cursor::new(&buffer);
+                assert_eq!(header, SctpHeader::read(&mut cursor).unwrap());
+            }
+            // read error
+            {
+                let bytes = header.to_bytes();
+                let mut cursor = Cursor::new(&bytes[..11]);
+                assert!(SctpHeader::read(&mut cursor).is_err());
+            }
+            // write error
+            {
+                let mut a: [u8;11] = [0;11];
+                assert_matches!(
+                    header.write(&mut Cursor::new(&mut a[..])),
+                    Err(WriteError::IoError(_))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn header_len(header in sctp_header_any()) {
+            assert_eq!(12, header.header_len());
+            assert_eq!(12, SctpHeader::SERIALIZED_SIZE);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn clone_eq_debug_default(header in sctp_header_any()) {
+            assert_eq!(header, header.clone());
+            assert_eq!(
+                format!(
+                    "SctpHeader {{ source_port: {}, destination_port: {}, verification_tag: {}, checksum: {} }}",
+                    header.source_port,
+                    header.destination_port,
+                    header.verification_tag,
+                    header.checksum,
+                ),
+                format!("{:?}", header)
+            );
+            assert_eq!(SctpHeader::new(0, 0, 0), SctpHeader::default());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in sctp_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
+}
+
+mod sctp_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in sctp_header_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            let mut buffer = Vec::with_capacity(12 + dummy_data.len());
+            buffer.extend_from_slice(&header.to_bytes());
+            buffer.extend_from_slice(&dummy_data);
+
+            // normal
+            {
+                let slice = SctpHeaderSlice::from_slice(&buffer).unwrap();
+                assert_eq!(&buffer[..12], slice.slice());
+                assert_eq!(header.source_port, slice.source_port());
+                assert_eq!(header.destination_port, slice.destination_port());
+                assert_eq!(header.verification_tag, slice.verification_tag());
+                assert_eq!(header.checksum, slice.checksum());
+                assert_eq!(header, slice.to_header());
+                assert_eq!(slice.clone(), slice);
+                assert!(format!("{:?}", slice).starts_with("SctpHeaderSlice"));
+            }
+
+            // length error
+            for len in 0..SctpHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    SctpHeaderSlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(SctpHeader::SERIALIZED_SIZE))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_checksum_valid() {
+        let slice = SctpHeaderSlice::from_slice(&INIT_PACKET).unwrap();
+        assert!(slice.is_checksum_valid(&INIT_PACKET[12..]));
+        assert!(!slice.is_checksum_valid(&INIT_PACKET[13..]));
+
+        let mut modified = INIT_PACKET;
+        modified[4] = 1;
+        let slice = SctpHeaderSlice::from_slice(&modified).unwrap();
+        assert!(!slice.is_checksum_valid(&modified[12..]));
+    }
+}
+
+mod sctp_chunk_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        // ok (padding is not part of the chunk)
+        {
+            let data = [1, 2, 0, 5, 9, 0, 0, 0];
+            let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+            assert_eq!(1, chunk.chunk_type());
+            assert_eq!(2, chunk.flags());
+            assert_eq!(5, chunk.length());
+            assert_eq!(&[9], chunk.value());
+            assert_eq!(&data[..5], chunk.slice());
+        }
+        // header too short
+        for len in 0..SctpChunkSlice::HEADER_LEN {
+            assert_matches!(
+                SctpChunkSlice::from_slice(&[0, 0, 0, 4][..len]),
+                Err(ReadError::UnexpectedEndOfSlice(SctpChunkSlice::HEADER_LEN))
+            );
+        }
+        // length too small
+        for length in 0..4u8 {
+            assert_matches!(
+                SctpChunkSlice::from_slice(&[0, 0, 0, length]),
+                Err(ReadError::SctpChunkLengthTooSmall(l)) if l == u16::from(length)
+            );
+        }
+        // length bigger then slice
+        assert_matches!(
+            SctpChunkSlice::from_slice(&[0, 0, 0, 9, 1, 2, 3, 4]),
+            Err(ReadError::UnexpectedEndOfSlice(9))
+        );
+    }
+
+    #[test]
+    fn chunk_data() {
+        let data = [
+            CHUNK_TYPE_DATA, DATA_FLAG_BEGINNING | DATA_FLAG_ENDING | DATA_FLAG_IMMEDIATE, 0, 19,
+            0, 0, 0, 5, // tsn
+            0, 6, // stream identifier
+            0, 7, // stream sequence number
+            0, 0, 0, 8, // payload protocol identifier
+            1, 2, 3, // user data
+            0, // padding
+        ];
+        let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+        let expected = DataChunk{
+            immediate: true,
+            unordered: false,
+            beginning: true,
+            ending: true,
+            tsn: 5,
+            stream_identifier: 6,
+            stream_sequence_number: 7,
+            payload_protocol_identifier: 8,
+            user_data: &[1, 2, 3],
+        };
+        assert_eq!(SctpChunk::Data(expected.clone()), chunk.chunk());
+        assert_eq!(data[1], expected.flags());
+
+        // unordered flag
+        let mut unordered = data;
+        unordered[1] = DATA_FLAG_UNORDERED;
+        let chunk = SctpChunkSlice::from_slice(&unordered).unwrap();
+        match chunk.chunk() {
+            SctpChunk::Data(data) => {
+                assert!(data.unordered);
+                assert!(!data.beginning);
+                assert_eq!(DATA_FLAG_UNORDERED, data.flags());
+            },
+            _ => panic!("expected data chunk"),
+        }
+
+        // too short value
+        let short = [CHUNK_TYPE_DATA, 0, 0, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            SctpChunk::Unknown{ chunk_type: CHUNK_TYPE_DATA, flags: 0, value: &short[4..] },
+            SctpChunkSlice::from_slice(&short).unwrap().chunk()
+        );
+    }
+
+    #[test]
+    fn chunk_init_init_ack() {
+        let mut data = [
+            CHUNK_TYPE_INIT, 0, 0, 28,
+            0x12, 0x34, 0x56, 0x78, // initiate tag
+            0, 1, 0, 0, // a_rwnd
+            0, 10, // outbound streams
+            0, 11, // inbound streams
+            0xaa, 0xbb, 0xcc, 0xdd, // initial tsn
+            // ipv4 address parameter
+            0, 5, 0, 8, 192, 168, 1, 1,
+        ];
+        for chunk_type in [CHUNK_TYPE_INIT, CHUNK_TYPE_INIT_ACK] {
+            data[0] = chunk_type;
+            let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+            let init = match chunk.chunk() {
+                SctpChunk::Init(value) if CHUNK_TYPE_INIT == chunk_type => value,
+                SctpChunk::InitAck(value) if CHUNK_TYPE_INIT_ACK == chunk_type => value,
+                value => panic!("unexpected chunk {:?}", value),
+            };
+            assert_eq!(0x12345678, init.initiate_tag);
+            assert_eq!(0x10000, init.advertised_receiver_window_credit);
+            assert_eq!(10, init.number_of_outbound_streams);
+            assert_eq!(11, init.number_of_inbound_streams);
+            assert_eq!(0xaabbccdd, init.initial_tsn);
+            let parameters: Vec<_> = init.parameters.collect();
+            assert_eq!(1, parameters.len());
+            assert_eq!(
+                &Tlv{ tlv_type: 5, value: &[192, 168, 1, 1] },
+                parameters[0].as_ref().unwrap()
+            );
+        }
+
+        // too short value
+        let short = [CHUNK_TYPE_INIT, 0, 0, 8, 0, 0, 0, 0];
+        assert_eq!(
+            SctpChunk::Unknown{ chunk_type: CHUNK_TYPE_INIT, flags: 0, value: &short[4..] },
+            SctpChunkSlice::from_slice(&short).unwrap().chunk()
+        );
+    }
+
+    #[test]
+    fn chunk_sack() {
+        let data = [
+            CHUNK_TYPE_SACK, 0, 0, 28,
+            0, 0, 0, 100, // cumulative tsn ack
+            0, 0, 1, 0, // a_rwnd
+            0, 2, // number of gap ack blocks
+            0, 1, // number of duplicate tsns
+            0, 2, 0, 3, // gap block 1
+            0, 5, 0, 6, // gap block 2
+            0, 0, 0, 99, // duplicate tsn
+        ];
+        let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+        match chunk.chunk() {
+            SctpChunk::Sack(sack) => {
+                assert_eq!(100, sack.cumulative_tsn_ack);
+                assert_eq!(256, sack.advertised_receiver_window_credit);
+                assert_eq!(vec![(2, 3), (5, 6)], sack.gap_ack_blocks.collect::<Vec<_>>());
+                assert_eq!(vec![99], sack.duplicate_tsns.collect::<Vec<_>>());
+            },
+            value => panic!("unexpected chunk {:?}", value),
+        }
+
+        // number of blocks not matching the length
+        let mut bad = data;
+        bad[15] = 2;
+        assert_eq!(
+            SctpChunk::Unknown{ chunk_type: CHUNK_TYPE_SACK, flags: 0, value: &bad[4..] },
+            SctpChunkSlice::from_slice(&bad).unwrap().chunk()
+        );
+    }
+
+    #[test]
+    fn chunk_heartbeat() {
+        let data = [
+            CHUNK_TYPE_HEARTBEAT, 0, 0, 12,
+            0, 1, 0, 8, 1, 2, 3, 4, // heartbeat info
+        ];
+        let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+        assert_eq!(
+            SctpChunk::Heartbeat(HeartbeatChunk{
+                parameters: TlvIterator::from_slice(&data[4..])
+            }),
+            chunk.chunk()
+        );
+
+        let mut ack = data;
+        ack[0] = CHUNK_TYPE_HEARTBEAT_ACK;
+        let chunk = SctpChunkSlice::from_slice(&ack).unwrap();
+        assert_eq!(
+            SctpChunk::HeartbeatAck(HeartbeatChunk{
+                parameters: TlvIterator::from_slice(&ack[4..])
+            }),
+            chunk.chunk()
+        );
+    }
+
+    #[test]
+    fn chunk_abort() {
+        let data = [
+            CHUNK_TYPE_ABORT, ABORT_FLAG_TCB_NOT_DESTROYED, 0, 10,
+            0, 12, 0, 6, b'h', b'i', // user initiated abort
+        ];
+        let chunk = SctpChunkSlice::from_slice(&data).unwrap();
+        match chunk.chunk() {
+            SctpChunk::Abort(abort) => {
+                assert!(abort.tcb_not_destroyed);
+                let causes: Vec<_> = abort.error_causes.collect();
+                assert_eq!(1, causes.len());
+                assert_eq!(
+                    &Tlv{ tlv_type: 12, value: b"hi" },
+                    causes[0].as_ref().unwrap()
+                );
+            },
+            value => panic!("unexpected chunk {:?}", value),
+        }
+    }
+
+    #[test]
+    fn chunk_shutdown() {
+        let data = [CHUNK_TYPE_SHUTDOWN, 0, 0, 8, 0, 0, 1, 0];
+        assert_eq!(
+            SctpChunk::Shutdown(ShutdownChunk{ cumulative_tsn_ack: 256 }),
+            SctpChunkSlice::from_slice(&data).unwrap().chunk()
+        );
+
+        // bad length
+        let bad = [CHUNK_TYPE_SHUTDOWN, 0, 0, 7, 0, 0, 1];
+        assert_eq!(
+            SctpChunk::Unknown{ chunk_type: CHUNK_TYPE_SHUTDOWN, flags: 0, value: &bad[4..] },
+            SctpChunkSlice::from_slice(&bad).unwrap().chunk()
+        );
+    }
+
+    #[test]
+    fn chunk_unknown() {
+        let data = [CHUNK_TYPE_COOKIE_ACK, 3, 0, 4];
+        assert_eq!(
+            SctpChunk::Unknown{ chunk_type: CHUNK_TYPE_COOKIE_ACK, flags: 3, value: &[] },
+            SctpChunkSlice::from_slice(&data).unwrap().chunk()
+        );
+    }
+}
+
+mod sctp_chunk_iterator {
+    use super::*;
+
+    #[test]
+    fn next() {
+        // multiple chunks with padding (last padding missing)
+        {
+            let data = [
+                CHUNK_TYPE_COOKIE_ACK, 0, 0, 4,
+                CHUNK_TYPE_SHUTDOWN_ACK, 1, 0, 5, 9, 0, 0, 0,
+                CHUNK_TYPE_SHUTDOWN_COMPLETE, 0, 0, 5, 8,
+            ];
+            let mut iter = SctpChunkIterator::from_slice(&data);
+            assert_eq!(&data[..], iter.rest());
+            assert_eq!(&data[..4], iter.next().unwrap().unwrap().slice());
+            assert_eq!(&data[4..], iter.rest());
+            assert_eq!(&data[4..9], iter.next().unwrap().unwrap().slice());
+            assert_eq!(&data[12..], iter.rest());
+            assert_eq!(&data[12..], iter.next().unwrap().unwrap().slice());
+            assert!(iter.next().is_none());
+            assert!(iter.rest().is_empty());
+        }
+
+        // error ends the iteration
+        {
+            let data = [
+                CHUNK_TYPE_COOKIE_ACK, 0, 0, 4,
+                CHUNK_TYPE_SHUTDOWN_ACK, 0, 0, 2,
+                CHUNK_TYPE_COOKIE_ACK, 0, 0, 4,
+            ];
+            let mut iter = SctpChunkIterator::from_slice(&data);
+            assert!(iter.next().unwrap().is_ok());
+            assert_matches!(
+                iter.next(),
+                Some(Err(ReadError::SctpChunkLengthTooSmall(2)))
+            );
+            assert!(iter.next().is_none());
+        }
+
+        // too short chunk
+        {
+            let mut iter = SctpChunkIterator::from_slice(&[0, 0, 0, 8, 1]);
+            assert_matches!(
+                iter.next(),
+                Some(Err(ReadError::UnexpectedEndOfSlice(8)))
+            );
+            assert!(iter.next().is_none());
+        }
+    }
+
+    #[test]
+    fn from_sliced_packet() {
+        let mut buffer = Vec::new();
+        Ipv4Header::new(INIT_PACKET.len() as u16, 64, ip_number::SCTP, [192,168,1,1], [192,168,1,2])
+            .write(&mut buffer)
+            .unwrap();
+        buffer.extend_from_slice(&INIT_PACKET);
+        let packet = SlicedPacket::from_ip(&buffer).unwrap();
+
+        let header = match packet.transport.unwrap() {
+            TransportSlice::Sctp(header) => header,
+            _ => panic!("expected sctp header"),
+        };
+        assert_eq!(5000, header.source_port());
+        assert_eq!(5001, header.destination_port());
+        assert!(header.is_checksum_valid(packet.payload));
+
+        let chunks: Vec<_> = SctpChunkIterator::from_slice(packet.payload)
+            .map(|c| c.unwrap().chunk_type())
+            .collect();
+        assert_eq!(vec![CHUNK_TYPE_INIT], chunks);
+    }
+}
+
+mod tlv_iterator {
+    use super::*;
+
+    #[test]
+    fn next() {
+        // padding between parameters
+        {
+            let data = [
+                0, 1, 0, 5, 9, 0, 0, 0,
+                0, 2, 0, 4,
+            ];
+            let mut iter = TlvIterator::from_slice(&data);
+            assert_eq!(Some(Tlv{ tlv_type: 1, value: &[9] }), iter.next().map(|v| v.unwrap()));
+            assert_eq!(&data[8..], iter.rest());
+            assert_eq!(Some(Tlv{ tlv_type: 2, value: &[] }), iter.next().map(|v| v.unwrap()));
+            assert!(iter.next().is_none());
+        }
+        // header too short
+        {
+            let mut iter = TlvIterator::from_slice(&[0, 1, 0]);
+            assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(4))));
+            assert!(iter.next().is_none());
+        }
+        // length too small
+        {
+            let mut iter = TlvIterator::from_slice(&[0, 1, 0, 3]);
+            assert_matches!(iter.next(), Some(Err(ReadError::SctpParameterLengthTooSmall(3))));
+            assert!(iter.next().is_none());
+        }
+        // length bigger then the slice
+        {
+            let mut iter = TlvIterator::from_slice(&[0, 1, 0, 6, 1]);
+            assert_matches!(iter.next(), Some(Err(ReadError::UnexpectedEndOfSlice(6))));
+            assert!(iter.next().is_none());
+        }
+    }
+}
@@ -676,6 +676,33 @@ mod header {
                     ]));
     }
 
+    proptest! {
+        #[test]
+        fn write_to_slice(input in tcp_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
 } // mod header
 
 proptest! {
@@ -1207,6 +1234,283 @@ fn options_iterator_method() {
     );
 }
 
+#[test]
+fn options_round_trip() {
+    use crate::TcpOptionElement::*;
+
+    fn round_trip(options: &[TcpOptionElement]) {
+        let mut header: TcpHeader = Default::default();
+        header.set_options(options).unwrap();
+
+        // decode
+        let decoded: Vec<TcpOptionElement> = header
+            .options_iterator()
+            .map(|x| x.unwrap())
+            .collect();
+        assert_eq!(options, &decoded[..]);
+
+        // re-encode & compare the resulting bytes
+        let mut other: TcpHeader = Default::default();
+        other.set_options(&decoded).unwrap();
+        assert_eq!(header.options(), other.options());
+    }
+
+    let data = |slice: &[u8]| TcpOptionData::from_slice(slice).unwrap();
+
+    round_trip(&[
+        UserTimeout{ granularity_minutes: true, timeout: 0x7fff },
+        UserTimeout{ granularity_minutes: false, timeout: 1234 },
+        FastOpenCookie(data(&[])),
+        FastOpenCookie(data(&[1,2,3,4,5,6,7,8])),
+    ]);
+    round_trip(&[
+        AuthenticationOption{ key_id: 1, rnext_key_id: 2, mac: data(&[3;12]) },
+        Unknown{ kind: 254, data: data(&[0xf9, 0x89, 1, 2]) },
+        Unknown{ kind: 200, data: data(&[]) },
+        Noop,
+    ]);
+    round_trip(&[
+        Mptcp(MptcpOption::MpCapable{
+            version: 1,
+            flags: 0x81,
+            keys: MptcpCapableKeys::WithData{
+                sender_key: 0x0102030405060708,
+                receiver_key: 0x1112131415161718,
+                data_level_length: 1234,
+                checksum: Some(0xabcd),
+            },
+        }),
+        Mptcp(MptcpOption::MpJoinSyn{
+            backup: true,
+            address_id: 3,
+            receiver_token: 0x01020304,
+            sender_random_number: 0x05060708,
+        }),
+    ]);
+    round_trip(&[
+        Mptcp(MptcpOption::Dss{
+            data_fin: true,
+            data_ack: Some(MptcpDssNumber::U64(0x0102030405060708)),
+            mapping: Some(MptcpDssMapping{
+                data_sequence_number: MptcpDssNumber::U32(0x01020304),
+                subflow_sequence_number: 5,
+                data_level_length: 6,
+                checksum: Some(7),
+            }),
+        }),
+        Mptcp(MptcpOption::AddAddr{
+            address_id: 1,
+            address: MptcpAddress::Ipv4([192,168,1,1]),
+            port: Some(443),
+            truncated_hmac: None,
+        }),
+    ]);
+    round_trip(&[
+        Mptcp(MptcpOption::AddAddr{
+            address_id: 2,
+            address: MptcpAddress::Ipv6([1;16]),
+            port: None,
+            truncated_hmac: Some(0x0102030405060708),
+        }),
+        Mptcp(MptcpOption::MpCapable{
+            version: 0,
+            flags: 0x01,
+            keys: MptcpCapableKeys::Sender(1),
+        }),
+    ]);
+    round_trip(&[
+        Mptcp(MptcpOption::MpJoinAck{ hmac: [5;20] }),
+        Mptcp(MptcpOption::MpJoinSynAck{
+            backup: false,
+            address_id: 1,
+            truncated_hmac: 2,
+            sender_random_number: 3,
+        }),
+    ]);
+
+    // not enough space
+    {
+        let mut header: TcpHeader = Default::default();
+        assert_eq!(
+            Err(TcpOptionWriteError::NotEnoughSpace(42)),
+            header.set_options(&[
+                AuthenticationOption{ key_id: 1, rnext_key_id: 2, mac: data(&[0;38]) }
+            ])
+        );
+    }
+}
+
+#[test]
+fn options_iterator_new_kinds() {
+    use crate::TcpOptionElement::*;
+    use tcp_option::*;
+
+    // end option followed by non padding bytes (everything after it gets skipped)
+    {
+        let mut it = TcpOptionsIterator::from_slice(&[KIND_NOOP, KIND_END, KIND_NOOP, 0]);
+        assert_eq!(Some(Ok(Noop)), it.next());
+        assert_eq!(Some(Ok(End)), it.next());
+        assert_eq!(None, it.next());
+        assert_eq!(0, it.rest().len());
+    }
+
+    // end option followed only by padding
+    {
+        let mut header: TcpHeader = Default::default();
+        header.set_options(&[Noop, End]).unwrap();
+        assert_eq!(
+            vec![Noop],
+            header.options_iterator().map(|x| x.unwrap()).collect::<Vec<_>>()
+        );
+    }
+
+    // user timeout (granularity bit & 15 bit timeout)
+    assert_eq!(
+        Some(Ok(UserTimeout{ granularity_minutes: true, timeout: 0x1234 })),
+        TcpOptionsIterator::from_slice(&[KIND_USER_TIMEOUT, 4, 0x92, 0x34]).next()
+    );
+    assert_eq!(
+        Some(Err(TcpOptionReadError::UnexpectedSize{ option_id: KIND_USER_TIMEOUT, size: 5 })),
+        TcpOptionsIterator::from_slice(&[KIND_USER_TIMEOUT, 5, 0, 0, 0]).next()
+    );
+
+    // fast open cookie sizes
+    for len in 2..=40u8 {
+        let mut data = [0u8;40];
+        data[0] = KIND_FAST_OPEN;
+        data[1] = len;
+        let actual = TcpOptionsIterator::from_slice(&data).next().unwrap();
+        if 2 == len || (6..=18).contains(&len) {
+            assert_eq!(
+                Ok(FastOpenCookie(TcpOptionData::from_slice(&data[2..usize::from(len)]).unwrap())),
+                actual
+            );
+        } else {
+            assert_eq!(
+                Err(TcpOptionReadError::UnexpectedSize{ option_id: KIND_FAST_OPEN, size: len }),
+                actual
+            );
+        }
+    }
+
+    // authentication option
+    assert_eq!(
+        Some(Ok(AuthenticationOption{
+            key_id: 1,
+            rnext_key_id: 2,
+            mac: TcpOptionData::from_slice(&[3]).unwrap(),
+        })),
+        TcpOptionsIterator::from_slice(&[KIND_AUTHENTICATION, 5, 1, 2, 3]).next()
+    );
+    assert_eq!(
+        Some(Err(TcpOptionReadError::UnexpectedSize{ option_id: KIND_AUTHENTICATION, size: 3 })),
+        TcpOptionsIterator::from_slice(&[KIND_AUTHENTICATION, 3, 1, 2]).next()
+    );
+}
+
+#[test]
+fn options_iterator_mptcp() {
+    use crate::TcpOptionElement::*;
+    use tcp_option::*;
+
+    // mp capable without keys
+    assert_eq!(
+        Some(Ok(Mptcp(MptcpOption::MpCapable{ version: 1, flags: 0x81, keys: MptcpCapableKeys::None }))),
+        TcpOptionsIterator::from_slice(&[KIND_MULTIPATH_TCP, 4, 0x01, 0x81]).next()
+    );
+
+    // dss with a 4 byte data ack
+    assert_eq!(
+        Some(Ok(Mptcp(MptcpOption::Dss{
+            data_fin: false,
+            data_ack: Some(MptcpDssNumber::U32(0x01020304)),
+            mapping: None,
+        }))),
+        TcpOptionsIterator::from_slice(&[
+            KIND_MULTIPATH_TCP, 8, 0x20, mptcp::DSS_FLAG_DATA_ACK,
+            1, 2, 3, 4
+        ]).next()
+    );
+
+    // add addr without echo bit that is too short for the truncated hmac
+    // (regression test for an underflow, the option fills the whole slice)
+    assert_eq!(
+        Some(Err(TcpOptionReadError::UnexpectedSize{ option_id: KIND_MULTIPATH_TCP, size: 4 })),
+        TcpOptionsIterator::from_slice(&[0x1e, 0x04, 0x30, 0x00]).next()
+    );
+
+    // options that can not be represented are returned as unknown
+    for data in [
+        // unsupported subtype (MP_PRIO)
+        [KIND_MULTIPATH_TCP, 4, 0x51, 0x00],
+        // dss with reserved bits set
+        [KIND_MULTIPATH_TCP, 4, 0x21, 0x00],
+        // dss with "a" flag but no data ack
+        [KIND_MULTIPATH_TCP, 4, 0x20, mptcp::DSS_FLAG_DATA_ACK_8],
+    ] {
+        let mut it = TcpOptionsIterator::from_slice(&data);
+        assert_eq!(
+            Some(Ok(Unknown{
+                kind: KIND_MULTIPATH_TCP,
+                data: TcpOptionData::from_slice(&data[2..]).unwrap(),
+            })),
+            it.next()
+        );
+        assert_eq!(None, it.next());
+
+        // unknown options are written unchanged
+        let mut header: TcpHeader = Default::default();
+        header.set_options(&[
+            TcpOptionsIterator::from_slice(&data).next().unwrap().unwrap()
+        ]).unwrap();
+        assert_eq!(&data[..], header.options());
+    }
+
+    // bad sizes
+    for data in [
+        [KIND_MULTIPATH_TCP, 2, 0, 0, 0, 0, 0, 0],
+        // mp capable
+        [KIND_MULTIPATH_TCP, 5, 0x00, 0, 0, 0, 0, 0],
+        // mp join
+        [KIND_MULTIPATH_TCP, 8, 0x10, 0, 0, 0, 0, 0],
+        // dss (no flags set)
+        [KIND_MULTIPATH_TCP, 6, 0x20, 0, 0, 0, 0, 0],
+        // add addr (echo)
+        [KIND_MULTIPATH_TCP, 7, 0x31, 0, 0, 0, 0, 0],
+        // add addr too short for the truncated hmac (regression test for an underflow)
+        [KIND_MULTIPATH_TCP, 4, 0x30, 0, 0, 0, 0, 0],
+        [KIND_MULTIPATH_TCP, 7, 0x30, 0, 0, 0, 0, 0],
+    ] {
+        assert_eq!(
+            Some(Err(TcpOptionReadError::UnexpectedSize{ option_id: KIND_MULTIPATH_TCP, size: data[1] })),
+            TcpOptionsIterator::from_slice(&data).next()
+        );
+    }
+}
+
+#[test]
+fn tcp_option_data() {
+    // from_slice
+    for len in 0..=TcpOptionData::MAX_LEN {
+        let data = [1u8;TcpOptionData::MAX_LEN];
+        let actual = TcpOptionData::from_slice(&data[..len]).unwrap();
+        assert_eq!(&data[..len], actual.as_slice());
+        assert_eq!(len, actual.len());
+        assert_eq!(0 == len, actual.is_empty());
+    }
+    assert_eq!(
+        Err(TcpOptionWriteError::NotEnoughSpace(41)),
+        TcpOptionData::from_slice(&[0;39])
+    );
+
+    // default & debug
+    assert_eq!(0, TcpOptionData::default().len());
+    assert_eq!(
+        "TcpOptionData([1, 2])",
+        format!("{:?}", TcpOptionData::from_slice(&[1,2]).unwrap())
+    );
+}
+
 #[test]
 fn options_iterator() {
     use crate::TcpOptionElement::*;
@@ -1432,6 +1736,19 @@ fn options_iterator_unexpected_length() {
 
     expect_unexpected_size(KIND_TIMESTAMP, 9);
     expect_unexpected_size(KIND_TIMESTAMP, 11);
+
+    // options with more data than fits into TcpOptionData
+    // (only possible when the iterator is created via from_slice)
+    for (id, first) in [(200, 0), (KIND_AUTHENTICATION, 0), (KIND_MULTIPATH_TCP, 0xf0)].iter() {
+        let mut data = [0u8;62];
+        data[0] = *id;
+        data[1] = 60;
+        data[2] = *first;
+        let mut it = TcpOptionsIterator::from_slice(&data);
+        assert_eq!(Some(Err(TcpOptionReadError::UnexpectedSize {option_id: *id, size: 60 })), it.next());
+        assert_eq!(0, it.rest().len());
+        assert_eq!(None, it.next());
+    }
 }
 
 #[test]
@@ -1444,11 +1761,63 @@ fn options_iterator_unexpected_id() {
                 0, 0, 0, 0, 0, //30
                 0, 0, 0, 0];
     let mut it = TcpOptionsIterator::from_slice(&data);
-    assert_eq!(Some(Err(TcpOptionReadError::UnknownId(255))), it.next());
+    // unknown ids are returned as unknown options
+    assert_eq!(
+        Some(Ok(TcpOptionElement::Unknown{
+            kind: 255,
+            data: TcpOptionData::from_slice(&[]).unwrap(),
+        })),
+        it.next()
+    );
     //expect the iterator slice to be moved to the end
+    assert_eq!(None, it.next());
     assert_eq!(0, it.rest().len());
+}
+
+#[test]
+fn options_iterator_unknown_id() {
+    let data = [255, 4, 1, 2, 0,
+                0, 0, 0, 0, 0, //10
+                0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, //20
+                0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, //30
+                0, 0, 0, 0];
+    let mut it = TcpOptionsIterator::from_slice(&data);
+    assert_eq!(
+        Some(Ok(TcpOptionElement::Unknown{
+            kind: 255,
+            data: TcpOptionData::from_slice(&[1,2]).unwrap(),
+        })),
+        it.next()
+    );
+    assert_eq!(&data[4..], it.rest());
+    //expect the padding to be skipped
     assert_eq!(None, it.next());
     assert_eq!(0, it.rest().len());
+
+    // length smaller then 2
+    for len in 0..2 {
+        let data = [255, len, 0, 0];
+        let mut it = TcpOptionsIterator::from_slice(&data);
+        assert_eq!(
+            Some(Err(TcpOptionReadError::UnexpectedSize{ option_id: 255, size: len })),
+            it.next()
+        );
+        assert_eq!(0, it.rest().len());
+    }
+
+    // length field missing
+    assert_eq!(
+        Some(Err(TcpOptionReadError::UnexpectedEndOfSlice{ option_id: 255, expected_len: 2, actual_len: 1 })),
+        TcpOptionsIterator::from_slice(&[255]).next()
+    );
+
+    // data missing
+    assert_eq!(
+        Some(Err(TcpOptionReadError::UnexpectedEndOfSlice{ option_id: 255, expected_len: 5, actual_len: 4 })),
+        TcpOptionsIterator::from_slice(&[255, 5, 0, 0]).next()
+    );
 }
 
 #[test]
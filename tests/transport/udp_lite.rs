@@ -0,0 +1,310 @@
+use super::super::*;
+use proptest::prelude::*;
+use std::io::Cursor;
+
+/// UDP-Lite packet (ipv4 192.168.1.1 -> 192.168.1.2) with a checksum
+/// coverage of 12 bytes (checksum calculated with an independent implementation).
+const PACKET: [u8;16] = [
+    // header
+    0, 21, 0x04, 0xd2, 0, 12, 0x73, 0x1a,
+    // payload
+    1, 2, 3, 4, 5, 6, 7, 8,
+];
+
+mod udp_lite_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn new(
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            checksum_coverage in any::<u16>(),
+        ) {
+            assert_eq!(
+                UdpLiteHeader{
+                    source_port,
+                    destination_port,
+                    checksum_coverage,
+                    checksum: 0,
+                },
+                UdpLiteHeader::new(source_port, destination_port, checksum_coverage)
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum_ipv4() {
+        let source = [192,168,1,1];
+        let destination = [192,168,1,2];
+
+        // partial coverage
+        {
+            let header = UdpLiteHeader::new(21, 1234, 12);
+            assert_eq!(
+                Ok(0x731a),
+                header.calc_checksum_ipv4_raw(source, destination, &PACKET[8..])
+            );
+            // data outside of the coverage does not change the checksum
+            assert_eq!(
+                Ok(0x731a),
+                header.calc_checksum_ipv4_raw(source, destination, &[1,2,3,4,0,0,0,0])
+            );
+        }
+
+        // coverage 0 (complete packet)
+        {
+            let header = UdpLiteHeader::new(21, 1234, 0);
+            assert_eq!(
+                Ok(0x6718),
+                header.calc_checksum_ipv4_raw(source, destination, &PACKET[8..])
+            );
+        }
+
+        // coverage equal to the packet length is equivalent to 0
+        {
+            let header = UdpLiteHeader::new(21, 1234, 16);
+            let expected = ::etherparse::checksum::Sum16BitWords::new()
+                .add_4bytes(source)
+                .add_4bytes(destination)
+                .add_2bytes([0, ip_number::UDP_LITE])
+                .add_2bytes(16u16.to_be_bytes())
+                .add_slice(&[0, 21, 0x04, 0xd2, 0, 16])
+                .add_slice(&PACKET[8..])
+                .to_ones_complement_with_no_zero()
+                .to_be();
+            assert_eq!(
+                Ok(expected),
+                header.calc_checksum_ipv4(
+                    &Ipv4Header::new(8, 5, ip_number::UDP_LITE, source, destination),
+                    &PACKET[8..]
+                )
+            );
+        }
+
+        // bad coverage values
+        for coverage in [1, 7, 17] {
+            let header = UdpLiteHeader::new(21, 1234, coverage);
+            assert_eq!(
+                Err(ValueError::UdpLiteChecksumCoverageBad(coverage)),
+                header.calc_checksum_ipv4_raw(source, destination, &PACKET[8..])
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum_ipv4_payload_too_big() {
+        let len = (u16::MAX as usize) - UdpLiteHeader::SERIALIZED_SIZE + 1;
+        let payload = vec![0u8;len];
+        assert_eq!(
+            Err(ValueError::UdpPayloadLengthTooLarge(len)),
+            UdpLiteHeader::new(0, 0, 8).calc_checksum_ipv4_raw([0;4], [0;4], &payload)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv6(
+            source in any::<[u8;16]>(),
+            destination in any::<[u8;16]>(),
+            header in udp_lite_header_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let coverage = usize::from(header.checksum_coverage);
+            let result = header.calc_checksum_ipv6_raw(source, destination, &payload);
+            if 0 != coverage && (coverage < 8 || coverage > 8 + payload.len()) {
+                assert_eq!(
+                    Err(ValueError::UdpLiteChecksumCoverageBad(header.checksum_coverage)),
+                    result
+                );
+            } else {
+                let covered = if 0 == coverage {
+                    &payload[..]
+                } else {
+                    &payload[..coverage - 8]
+                };
+                let expected = ::etherparse::checksum::Sum16BitWords::new()
+                    .add_16bytes(source)
+                    .add_16bytes(destination)
+                    .add_4bytes(((8 + payload.len()) as u32).to_be_bytes())
+                    .add_4bytes([0, 0, 0, ip_number::UDP_LITE])
+                    .add_2bytes(header.source_port.to_be_bytes())
+                    .add_2bytes(header.destination_port.to_be_bytes())
+                    .add_2bytes(header.checksum_coverage.to_be_bytes())
+                    .add_slice(covered)
+                    .to_ones_complement_with_no_zero()
+                    .to_be();
+                assert_eq!(Ok(expected), result);
+                let ip_header = Ipv6Header{
+                    source,
+                    destination,
+                    ..Default::default()
+                };
+                assert_eq!(Ok(expected), header.calc_checksum_ipv6(&ip_header, &payload));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in udp_lite_header_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut buffer = Vec::with_capacity(8 + dummy_data.len());
+            buffer.extend_from_slice(&header.to_bytes());
+            buffer.extend_from_slice(&dummy_data);
+
+            // normal
+            {
+                let (actual, rest) = UdpLiteHeader::from_slice(&buffer).unwrap();
+                assert_eq!(actual, header);
+                assert_eq!(rest, &dummy_data[..]);
+            }
+
+            // too small
+            for len in 0..8 {
+                assert_matches!(
+                    UdpLiteHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(8))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn read_write(header in udp_lite_header_any()) {
+            // write
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&buffer[..], &header.to_bytes()[..]);
+            assert_eq!(header, UdpLiteHeader::from_bytes(header.to_bytes()));
+
+            // read
+            assert_eq!(header, UdpLiteHeader::read(&mut Cursor::new(&buffer)).unwrap());
+
+            // read error
+            assert!(UdpLiteHeader::read(&mut Cursor::new(&buffer[..7])).is_err());
+
+            // write error
+            let mut a: [u8;0] = [];
+            assert_matches!(
+                header.write(&mut Cursor::new(&mut a[..])),
+                Err(WriteError::IoError(_))
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn header_len(header in udp_lite_header_any()) {
+            assert_eq!(8, header.header_len());
+            assert_eq!(8, UdpLiteHeader::SERIALIZED_SIZE);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_to_slice(input in udp_lite_header_any()) {
+            let expected = {
+                let mut buffer = Vec::new();
+                input.write(&mut buffer).unwrap();
+                buffer
+            };
+            // normal write (the unused rest of the slice is returned)
+            {
+                let mut buffer = vec![0u8;expected.len() + 1];
+                let rest = input.write_to_slice(&mut buffer).unwrap();
+                assert_eq!(1, rest.len());
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+            }
+            // slice too small
+            for len in 0..expected.len() {
+                let mut buffer = vec![0u8;len];
+                assert_eq!(
+                    Some(expected.len()),
+                    input.write_to_slice(&mut buffer)
+                        .unwrap_err()
+                        .slice_too_small_size()
+                );
+            }
+        }
+    }
+}
+
+mod udp_lite_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in udp_lite_header_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut buffer = Vec::with_capacity(8 + dummy_data.len());
+            buffer.extend_from_slice(&header.to_bytes());
+            buffer.extend_from_slice(&dummy_data);
+
+            let slice = UdpLiteHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(slice.slice(), &buffer[..8]);
+            assert_eq!(slice.source_port(), header.source_port);
+            assert_eq!(slice.destination_port(), header.destination_port);
+            assert_eq!(slice.checksum_coverage(), header.checksum_coverage);
+            assert_eq!(slice.checksum(), header.checksum);
+            assert_eq!(slice.to_header(), header);
+
+            for len in 0..8 {
+                assert_matches!(
+                    UdpLiteHeaderSlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(8))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn packet_slicing() {
+    let ip = Ipv4Header::new(
+        PACKET.len() as u16,
+        5,
+        ip_number::UDP_LITE,
+        [192,168,1,1],
+        [192,168,1,2]
+    );
+    let mut buffer = Vec::new();
+    ip.write(&mut buffer).unwrap();
+    buffer.extend_from_slice(&PACKET);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip(&buffer).unwrap();
+        match sliced.transport {
+            Some(TransportSlice::UdpLite(header)) => {
+                assert_eq!(header.slice(), &PACKET[..8]);
+            },
+            _ => panic!("expected UDP-Lite slice"),
+        }
+        assert_eq!(sliced.payload, &PACKET[8..]);
+    }
+
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice(&buffer).unwrap();
+        assert_eq!(
+            decoded.transport,
+            Some(TransportHeader::UdpLite(UdpLiteHeader::from_bytes([
+                PACKET[0], PACKET[1], PACKET[2], PACKET[3],
+                PACKET[4], PACKET[5], PACKET[6], PACKET[7],
+            ])))
+        );
+        assert_eq!(decoded.payload, &PACKET[8..]);
+    }
+
+    // too short
+    assert_matches!(
+        SlicedPacket::from_ip(&buffer[..20 + 7]),
+        Err(PacketReadError{ error: ReadError::UnexpectedEndOfSlice(_), .. })
+    );
+}
@@ -8,15 +8,29 @@ extern crate proptest;
 
 use std::io;
 
+mod bpf;
 mod checksum;
 mod errors;
+mod flow_key;
+mod flow_table;
+mod header_field;
+mod hex_dump;
 mod link;
 mod internet;
+mod layer;
 mod transport;
 mod packet_builder;
+mod encapsulation;
+mod frame_editing;
 mod packet_decoder;
 mod packet_filter;
+mod packet_format;
 mod packet_slicing;
+mod pcap;
+mod pcap_filter;
+mod pcapng;
+mod tcp_reassembly;
+mod tcp_tracking;
 mod proptest_generators;
 pub use crate::proptest_generators::*;
 use proptest::prelude::*;